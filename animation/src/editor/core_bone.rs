@@ -0,0 +1,165 @@
+use super::keyframe_core::*;
+use super::stream_animation_core::*;
+use crate::storage::*;
+use crate::undo::*;
+use crate::traits::*;
+
+use flo_canvas_animation::description::{Point2D};
+
+use futures::prelude::*;
+
+use std::time::{Duration};
+use std::collections::{HashMap};
+
+///
+/// Retrieves a bone from a keyframe
+///
+fn bone_with_id(frame: &KeyFrameCore, bone_id: ElementId) -> Option<BoneElement> {
+    match frame.elements.get(&bone_id).map(|wrapper| &wrapper.element) {
+        Some(Vector::Bone(bone))    => Some(bone.clone()),
+        _                           => None
+    }
+}
+
+///
+/// Creates the edit that restores the rotation of a bone at a particular time (the time of the edit is relative to the start of the animation)
+///
+fn reverse_rotation(bone: &BoneElement, relative_when: Duration, when: Duration) -> AnimationEdit {
+    match bone.rotation_at(relative_when) {
+        Some(degrees)   => AnimationEdit::Bone(bone.id(), BoneEdit::SetRotation(when, degrees)),
+        None            => AnimationEdit::Bone(bone.id(), BoneEdit::RemoveRotation(when))
+    }
+}
+
+///
+/// Works out the updated bones for a bone edit, along with the edits that will reverse it
+///
+fn apply_bone_edit(frame: &KeyFrameCore, bone_id: ElementId, bone_edit: &BoneEdit) -> Option<(Vec<BoneElement>, ReversedEdits)> {
+    use self::BoneEdit::*;
+
+    let bone            = bone_with_id(frame, bone_id)?;
+    let relative_time   = |when: Duration| when.checked_sub(frame.start).unwrap_or(Duration::from_millis(0));
+
+    match bone_edit {
+        SetParent(parent_id)            => {
+            let reversed = ReversedEdits::with_edit(AnimationEdit::Bone(bone_id, SetParent(bone.parent())));
+            Some((vec![bone.with_parent(*parent_id)], reversed))
+        }
+
+        SetRestPosition(start, end)     => {
+            let reversed = ReversedEdits::with_edit(AnimationEdit::Bone(bone_id, SetRestPosition(bone.start(), bone.end())));
+            Some((vec![bone.with_position(*start, *end)], reversed))
+        }
+
+        Bind(element_id, weight)        => {
+            let old_weight  = bone.bindings().iter().filter(|(bound_id, _)| bound_id == element_id).map(|(_, weight)| *weight).nth(0);
+            let reversed    = match old_weight {
+                Some(old_weight)    => AnimationEdit::Bone(bone_id, Bind(*element_id, old_weight)),
+                None                => AnimationEdit::Bone(bone_id, Unbind(*element_id))
+            };
+
+            Some((vec![bone.with_binding(*element_id, *weight)], ReversedEdits::with_edit(reversed)))
+        }
+
+        Unbind(element_id)              => {
+            let old_weight  = bone.bindings().iter().filter(|(bound_id, _)| bound_id == element_id).map(|(_, weight)| *weight).nth(0);
+            let reversed    = ReversedEdits::with_edits(old_weight.map(|old_weight| AnimationEdit::Bone(bone_id, Bind(*element_id, old_weight))));
+
+            Some((vec![bone.without_binding(*element_id)], reversed))
+        }
+
+        SetRotation(when, degrees)      => {
+            let relative_when   = relative_time(*when);
+            let reversed        = ReversedEdits::with_edit(reverse_rotation(&bone, relative_when, *when));
+
+            Some((vec![bone.with_rotation(relative_when, *degrees)], reversed))
+        }
+
+        RemoveRotation(when)            => {
+            let relative_when   = relative_time(*when);
+            let reversed        = ReversedEdits::with_edit(reverse_rotation(&bone, relative_when, *when));
+
+            Some((vec![bone.without_rotation(relative_when)], reversed))
+        }
+
+        SolveIk { when, target, chain_length, solver } => {
+            let relative_when   = relative_time(*when);
+
+            // Find the chain of bones that will be rotated to reach the target (which ends at this bone)
+            let (skeleton, indexes) = frame.skeleton();
+            let bone_idx            = *indexes.get(&bone_id)?;
+            let chain               = skeleton.chain_to_root(bone_idx);
+            let chain               = chain[chain.len().saturating_sub((*chain_length).max(1))..].to_vec();
+
+            // Solve for the new joint positions and work out the rotations needed to reach them
+            let joints              = skeleton.joint_positions(&chain, relative_when);
+            let new_joints          = solver.solve(&joints, Point2D(target.0, target.1));
+            let rotations           = skeleton.rotations_for_joints(&chain, &new_joints, relative_when);
+
+            // Update each bone in the chain
+            let bone_ids            = indexes.into_iter().map(|(bone_id, bone_idx)| (bone_idx, bone_id)).collect::<HashMap<_, _>>();
+            let mut new_bones       = vec![];
+            let mut reversed        = ReversedEdits::new();
+
+            for (chain_idx, rotation) in rotations {
+                let chain_bone = bone_with_id(frame, bone_ids[&chain_idx])?;
+
+                reversed.0.push(reverse_rotation(&chain_bone, relative_when, *when));
+                new_bones.push(chain_bone.with_rotation(relative_when, rotation.0));
+            }
+
+            Some((new_bones, reversed))
+        }
+    }
+}
+
+impl StreamAnimationCore {
+    ///
+    /// Performs a bone edit on this animation
+    ///
+    pub fn bone_edit<'a>(&'a mut self, bone_id: ElementId, bone_edit: &'a BoneEdit) -> impl 'a+Future<Output=ReversedEdits> {
+        async move {
+            // Convert the bone ID to an assigned element
+            let assigned_bone_id = match bone_id.id() {
+                Some(id)        => id,
+                None            => { return ReversedEdits::empty(); }
+            };
+
+            // Bones are edited in the keyframe that contains them
+            let frame = match self.edit_keyframe_for_element(assigned_bone_id).await {
+                Some(frame)     => frame,
+                None            => { return ReversedEdits::empty(); }
+            };
+
+            let bone_edit       = bone_edit.clone();
+            let maybe_updates   = frame.future_sync(move |frame| {
+                async move {
+                    let (new_bones, reversed)   = apply_bone_edit(frame, ElementId::Assigned(assigned_bone_id), &bone_edit)?;
+                    let mut updates             = vec![];
+
+                    // Replace the bones in the frame
+                    for bone in new_bones {
+                        let bone_id         = bone.id();
+                        let mut wrapper     = frame.elements.get(&bone_id)?.clone();
+                        wrapper.element     = Vector::Bone(bone);
+
+                        updates.push(StorageCommand::WriteElement(bone_id.id()?, wrapper.serialize_to_string()));
+                        frame.elements.insert(bone_id, wrapper);
+                    }
+
+                    frame.invalidate();
+
+                    Some((updates, reversed))
+                }.boxed()
+            }).await.unwrap();
+
+            // Send the updates to storage
+            if let Some((updates, reversed)) = maybe_updates {
+                self.request(updates).await;
+                reversed
+            } else {
+                ReversedEdits::empty()
+            }
+        }
+    }
+}
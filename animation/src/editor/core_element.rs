@@ -127,43 +127,20 @@ impl StreamAnimationCore {
                 }
 
                 Delete                              => {
-                    // Create the undo operation for each of the deleted elements
-                    let mut reversed        = ReversedEdits::new();
-                    let wrappers            = self.wrappers_for_elements(element_ids.iter().cloned()).await;
-                    let recreate_order      = element_ids.iter().map(|id| ElementId::Assigned(*id)).collect();
-                    let recreate_order      = ReversedEdits::recreate_order(recreate_order, &move |id: ElementId| id.id().and_then(|id| wrappers.get(&id).cloned()));
-                    let mut element_frames  = vec![];
-
-                    for element_id in recreate_order {
-                        let element_id = element_id.id().unwrap();
-
-                        if let Some(frame) = self.edit_keyframe_for_element(element_id).await {
-                            // Request the element from the frame
-                            let frame_reverse = frame.future_sync(move |frame| {
-                                async move {
-                                    let wrapper = frame.elements.get(&ElementId::Assigned(element_id))?;
-                                    Some(ReversedEdits::with_recreated_wrapper(frame.layer_id, wrapper, &|id| frame.elements.get(&id).cloned()))
-                                }.boxed()
-                            }).await.unwrap();
-
-                            frame_reverse.map(|frame_reverse| reversed.extend(frame_reverse));
-
-                            // Remember the frames for later
-                            element_frames.push((element_id, frame));
-                        }
-                    }
+                    self.delete_elements(&element_ids).await
+                }
 
-                    // If the element is attached to another element, remove it from the attachment list
-                    self.remove_from_attachments(&element_ids).await;
+                CopyTo { layer, when, new_ids }     => {
+                    self.copy_elements(&element_ids, *layer, *when, new_ids).await
+                }
 
-                    // Delete from storage
-                    self.request(element_ids.iter().cloned().map(|id| StorageCommand::DeleteElement(id))).await;
+                MoveTo { layer, when, new_ids }     => {
+                    // Elements are moved by copying them to the new location and deleting the originals
+                    let mut reversed = self.copy_elements(&element_ids, *layer, *when, new_ids).await;
 
-                    // Remove the element from the edit frames, so it's not cached
-                    element_frames.into_iter()
-                        .for_each(|(element_id, frame)| {
-                            frame.desync(move |frame| { frame.elements.remove(&ElementId::Assigned(element_id)); });
-                        });
+                    if !reversed.is_empty() {
+                        reversed.add_to_start(self.delete_elements(&element_ids).await);
+                    }
 
                     reversed
                 }
@@ -275,6 +252,53 @@ impl StreamAnimationCore {
         }
     }
 
+    ///
+    /// Deletes a set of elements, returning the edits needed to recreate them
+    ///
+    pub fn delete_elements<'a>(&'a mut self, element_ids: &'a Vec<i64>) -> impl 'a+Send+Future<Output=ReversedEdits> {
+        async move {
+            // Create the undo operation for each of the deleted elements
+            let mut reversed        = ReversedEdits::new();
+            let wrappers            = self.wrappers_for_elements(element_ids.iter().cloned()).await;
+            let recreate_order      = element_ids.iter().map(|id| ElementId::Assigned(*id)).collect();
+            let recreate_order      = ReversedEdits::recreate_order(recreate_order, &move |id: ElementId| id.id().and_then(|id| wrappers.get(&id).cloned()));
+            let mut element_frames  = vec![];
+
+            for element_id in recreate_order {
+                let element_id = element_id.id().unwrap();
+
+                if let Some(frame) = self.edit_keyframe_for_element(element_id).await {
+                    // Request the element from the frame
+                    let frame_reverse = frame.future_sync(move |frame| {
+                        async move {
+                            let wrapper = frame.elements.get(&ElementId::Assigned(element_id))?;
+                            Some(ReversedEdits::with_recreated_wrapper(frame.layer_id, wrapper, &|id| frame.elements.get(&id).cloned()))
+                        }.boxed()
+                    }).await.unwrap();
+
+                    frame_reverse.map(|frame_reverse| reversed.extend(frame_reverse));
+
+                    // Remember the frames for later
+                    element_frames.push((element_id, frame));
+                }
+            }
+
+            // If the element is attached to another element, remove it from the attachment list
+            self.remove_from_attachments(&element_ids).await;
+
+            // Delete from storage
+            self.request(element_ids.iter().cloned().map(|id| StorageCommand::DeleteElement(id))).await;
+
+            // Remove the element from the edit frames, so it's not cached
+            element_frames.into_iter()
+                .for_each(|(element_id, frame)| {
+                    frame.desync(move |frame| { frame.elements.remove(&ElementId::Assigned(element_id)); });
+                });
+
+            reversed
+        }
+    }
+
     ///
     /// When deleting or detaching an element, we might find that it has attachments or is attached to other elements.
     /// This will remove the element from the attachment lists of those related elements.
@@ -695,9 +719,9 @@ impl StreamAnimationCore {
 
                     group.parent    = parent;
 
-                    // Normal groups take their properties from their internal elements. Other groups use
-                    // the properties of their first element.
-                    if group_type != GroupType::Normal {
+                    // Normal and masked groups take their properties from their internal elements. Groups that
+                    // generate a single path use the properties of their first element.
+                    if group_type.is_single_path() {
                         for attachment_id in first_element.attachments.iter() {
                             // The group should have the same attachments as its first element
                             updates.extend(frame.add_attachment(ElementId::Assigned(group_id), &vec![*attachment_id]));
@@ -0,0 +1,189 @@
+use super::stream_animation_core::*;
+use crate::undo::*;
+use crate::traits::*;
+use crate::storage::*;
+
+use futures::prelude::*;
+
+use std::iter;
+use std::ops::{Range};
+use std::time::{Duration};
+use std::collections::{HashSet};
+
+///
+/// Returns the time that an element appearing at `when` in a keyframe starting at `from` will have once the keyframe starts at `to`
+///
+#[inline]
+fn retime(when: Duration, from: Duration, to: Duration) -> Duration {
+    to + when.saturating_sub(from)
+}
+
+impl StreamAnimationCore {
+    ///
+    /// True if there's a keyframe that starts exactly at the specified time on a layer
+    ///
+    pub fn key_frame_exists<'a>(&'a mut self, layer_id: u64, when: Duration) -> impl 'a+Future<Output=bool> {
+        async move {
+            let keyframes = self.storage_connection.read_keyframes_for_layer(layer_id, when..(when + Duration::from_micros(1))).await;
+
+            keyframes
+                .map(|keyframes| keyframes.iter().any(|keyframe| keyframe.start == when))
+                .unwrap_or(false)
+        }
+    }
+
+    ///
+    /// Removes the keyframe at the specified time (if there is one) so that another keyframe can be moved there, returning the edits needed to restore it
+    ///
+    fn clear_key_frame_time<'a>(&'a mut self, layer_id: u64, when: Duration) -> impl 'a+Future<Output=ReversedEdits> {
+        async move {
+            if self.key_frame_exists(layer_id, when).await {
+                self.remove_key_frame(layer_id, when).await
+            } else {
+                ReversedEdits::empty()
+            }
+        }
+    }
+
+    ///
+    /// Moves the keyframe starting at `from` so that it starts at `to` instead
+    ///
+    pub fn move_key_frame<'a>(&'a mut self, layer_id: u64, from: Duration, to: Duration) -> impl 'a+Future<Output=ReversedEdits> {
+        async move {
+            if from == to {
+                return ReversedEdits::empty();
+            }
+
+            // The keyframe must start at exactly the 'from' time
+            let keyframe = match self.storage_connection.read_keyframe(layer_id, from).await {
+                Some(keyframe) if keyframe.start_time == from   => keyframe,
+                _                                               => { return ReversedEdits::empty(); }
+            };
+
+            // Undoing moves the keyframe back, then restores anything that was replaced at the target time
+            let mut reverse = ReversedEdits::with_edit(AnimationEdit::Layer(layer_id, LayerEdit::MoveKeyFrame(to, from)));
+            reverse.extend(self.clear_key_frame_time(layer_id, to).await);
+
+            // The element wrappers store when they appear, so these need to move along with the keyframe
+            let retimed_elements = keyframe.elements.iter()
+                .filter_map(|(element_id, wrapper)| {
+                    // Elements that failed to load are left alone so they're not overwritten with an error element
+                    if let Vector::Error = &wrapper.element { return None; }
+
+                    let element_id          = element_id.id()?;
+                    let mut wrapper         = wrapper.clone();
+                    wrapper.start_time      = retime(wrapper.start_time, from, to);
+
+                    Some(StorageCommand::WriteElement(element_id, wrapper.serialize_to_string()))
+                })
+                .collect::<Vec<_>>();
+
+            self.cached_keyframe = None;
+            self.cached_layers.remove(&layer_id);
+
+            // Rewrite the elements, then move the keyframe and its attachments
+            self.request(retimed_elements.into_iter().chain(iter::once(StorageCommand::MoveKeyFrame(layer_id, from, to)))).await;
+
+            reverse
+        }
+    }
+
+    ///
+    /// Generates the edits that will create a copy of the elements in the keyframe starting at `from`, using the IDs of the original elements
+    ///
+    pub fn key_frame_copy_edits<'a>(&'a mut self, layer_id: u64, from: Duration) -> impl 'a+Future<Output=Vec<AnimationEdit>> {
+        async move {
+            if !self.key_frame_exists(layer_id, from).await {
+                return vec![];
+            }
+
+            // The edits that would recreate the source keyframe are used as the instructions for creating the copy
+            ReversedEdits::with_recreated_keyframe(layer_id, from, &mut HashSet::new(), &mut self.storage_connection).await.0
+        }
+    }
+
+    ///
+    /// Copies the keyframe starting at `from` to a new keyframe starting at `to`, giving the copied elements the IDs in `new_ids`
+    ///
+    pub fn duplicate_key_frame<'a>(&'a mut self, layer_id: u64, from: Duration, to: Duration, new_ids: &'a Vec<ElementId>) -> impl 'a+Future<Output=ReversedEdits> {
+        async move {
+            use self::AnimationEdit::*;
+            use self::LayerEdit::*;
+
+            if from == to || !self.key_frame_exists(layer_id, from).await {
+                return ReversedEdits::empty();
+            }
+
+            let copy_edits  = self.key_frame_copy_edits(layer_id, from).await;
+
+            // Undoing removes the copy, then restores anything that was replaced at the target time
+            let mut reverse = ReversedEdits::with_edit(Layer(layer_id, RemoveKeyFrame(to)));
+            reverse.extend(self.clear_key_frame_time(layer_id, to).await);
+
+            // Create the copy
+            self.add_key_frame(layer_id, to).await;
+            self.perform_copy_edits(layer_id, copy_edits, new_ids, move |when| retime(when, from, to)).await;
+
+            reverse
+        }
+    }
+
+    ///
+    /// Moves all of the keyframes that start within a range of times by an offset in microseconds
+    ///
+    /// The shift is rejected (nothing is moved) if any keyframe would be moved to before the start of the animation, or onto
+    /// a keyframe that is outside of the range (which would otherwise be replaced).
+    ///
+    pub fn shift_key_frames<'a>(&'a mut self, layer_id: u64, range: Range<Duration>, offset: i64) -> impl 'a+Future<Output=ReversedEdits> {
+        async move {
+            if offset == 0 {
+                return ReversedEdits::empty();
+            }
+
+            // Find the keyframes that start in the range
+            let keyframes       = self.storage_connection.read_keyframes_for_layer(layer_id, range.clone()).await.unwrap_or_else(|| vec![]);
+            let mut keyframes   = keyframes.into_iter()
+                .map(|keyframe| keyframe.start)
+                .filter(|start| range.contains(start))
+                .collect::<Vec<_>>();
+            keyframes.sort();
+
+            if keyframes.is_empty() {
+                return ReversedEdits::empty();
+            }
+
+            // Work out where each keyframe will end up, rejecting the shift if any keyframe would end up before the start of the animation
+            let targets = keyframes.iter()
+                .map(|from| (from.as_micros() as i64).checked_add(offset).filter(|to| *to >= 0).map(|to| Duration::from_micros(to as u64)))
+                .collect::<Option<HashSet<_>>>();
+            let targets = if let Some(targets) = targets { targets } else { return ReversedEdits::empty(); };
+
+            // Reject the shift if it would move a keyframe on top of a keyframe that isn't moving
+            let first_target    = *targets.iter().min().unwrap();
+            let last_target     = *targets.iter().max().unwrap();
+            let collisions      = self.storage_connection.read_keyframes_for_layer(layer_id, first_target..(last_target + Duration::from_micros(1))).await.unwrap_or_else(|| vec![]);
+            let collides        = collisions.iter()
+                .any(|keyframe| targets.contains(&keyframe.start) && !range.contains(&keyframe.start));
+
+            if collides {
+                return ReversedEdits::empty();
+            }
+
+            // Move the keyframes furthest along in the direction of the shift first, so no keyframe lands on one that has yet to move
+            if offset > 0 {
+                keyframes.reverse();
+            }
+
+            // Each keyframe is moved individually (none of these will replace an existing keyframe)
+            let mut reverse = ReversedEdits::new();
+
+            for from in keyframes {
+                let to = Duration::from_micros(((from.as_micros() as i64) + offset) as u64);
+
+                reverse.add_to_start(self.move_key_frame(layer_id, from, to).await);
+            }
+
+            reverse
+        }
+    }
+}
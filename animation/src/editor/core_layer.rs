@@ -33,9 +33,18 @@ impl StreamAnimationCore {
                 CreateElementUnattachedToFrame(when, element_id, vector)    => { self.create_element(layer_id, *when, *element_id, vector.clone(), true).await }
                 AddKeyFrame(when)                                           => { self.add_key_frame(layer_id, *when).await }
                 RemoveKeyFrame(when)                                        => { self.remove_key_frame(layer_id, *when).await }
+                MoveKeyFrame(from, to)                                      => { self.move_key_frame(layer_id, *from, *to).await }
+                DuplicateKeyFrame(from, to, new_ids)                        => { self.duplicate_key_frame(layer_id, *from, *to, new_ids).await }
+                ShiftKeyFrames(range, offset)                               => { self.shift_key_frames(layer_id, range.clone(), *offset).await }
                 SetName(new_name)                                           => { self.set_layer_name(layer_id, new_name).await }
                 SetOrdering(ordering)                                       => { self.set_layer_ordering(layer_id, *ordering).await }
                 SetAlpha(alpha)                                             => { self.set_layer_alpha(layer_id, *alpha).await }
+                SetHidden(hidden)                                           => { self.edit_layer_properties(layer_id, |properties| { let old = properties.hidden; properties.hidden = *hidden; SetHidden(old) }).await }
+                SetLocked(locked)                                           => { self.edit_layer_properties(layer_id, |properties| { let old = properties.locked; properties.locked = *locked; SetLocked(old) }).await }
+                SetBlendMode(blend_mode)                                    => { self.edit_layer_properties(layer_id, |properties| { let old = properties.blend_mode; properties.blend_mode = *blend_mode; SetBlendMode(old) }).await }
+                SetIsFolder(is_folder)                                      => { self.edit_layer_properties(layer_id, |properties| { let old = properties.is_folder; properties.is_folder = *is_folder; SetIsFolder(old) }).await }
+                SetCollapsed(collapsed)                                     => { self.edit_layer_properties(layer_id, |properties| { let old = properties.collapsed; properties.collapsed = *collapsed; SetCollapsed(old) }).await }
+                SetParentFolder(folder_id)                                  => { self.set_layer_parent_folder(layer_id, *folder_id).await }
                 Cut { path, when, inside_group }   => { 
                     let cut = self.layer_cut(layer_id, *when, Arc::clone(path)).await;
                     self.apply_layer_cut(layer_id, *when, cut, *inside_group).await
@@ -181,6 +190,12 @@ impl StreamAnimationCore {
                 _                                               => { return ReversedEdits::empty(); }
             };
 
+            // Folders are moved along with the layers they contain
+            let folder_contents = Self::folder_contents(layer_id, &layers);
+            if !folder_contents.is_empty() {
+                return self.move_folder_behind(layer_id, order_behind, folder_contents, layers).await;
+            }
+
             // Do nothing if the layer is being ordered back to where it already is
             if layer_index == order_behind_index || order_behind_index == layer_index + 1 {
                 return ReversedEdits::empty();
@@ -208,6 +223,86 @@ impl StreamAnimationCore {
                 layers.insert(order_behind_index, (layer_id, layer_props));
             }
 
+            self.write_layer_ordering(layers).await;
+
+            reversed_edits
+        } 
+    }
+
+    ///
+    /// Returns the IDs of the layers that are inside a folder layer (including the contents of any folders inside it), in the order they appear in the supplied list of layers
+    ///
+    fn folder_contents(folder_id: u64, layers: &Vec<(u64, LayerProperties)>) -> Vec<u64> {
+        let mut folders     = vec![folder_id];
+        let mut contents    = vec![];
+
+        while let Some(folder_id) = folders.pop() {
+            for (layer_id, properties) in layers.iter() {
+                if properties.parent_folder == Some(folder_id) && *layer_id != folder_id && !contents.contains(layer_id) {
+                    contents.push(*layer_id);
+                    folders.push(*layer_id);
+                }
+            }
+        }
+
+        layers.iter()
+            .map(|(layer_id, _)| *layer_id)
+            .filter(|layer_id| contents.contains(layer_id))
+            .collect()
+    }
+
+    ///
+    /// Moves a folder and its contents so that they're ordered behind another layer
+    ///
+    /// The contents of the folder are placed immediately behind the folder layer itself, so they stay together
+    ///
+    fn move_folder_behind<'a>(&'a mut self, folder_id: u64, order_behind: u64, folder_contents: Vec<u64>, layers: Vec<(u64, LayerProperties)>) -> impl 'a+Future<Output=ReversedEdits> {
+        async move {
+            // A folder can't be ordered behind one of the layers inside it
+            if folder_contents.contains(&order_behind) {
+                return ReversedEdits::empty();
+            }
+
+            // The reverse is to move the folder back behind the layer it's currently in front of, or to the front if there's no such layer
+            let folder_index    = layers.iter().position(|(id, _)| *id == folder_id).unwrap();
+            let first_in_folder = folder_contents[0];
+            let reversed_edits  = if let Some((originally_behind_id, _)) = layers.get(folder_index+1) {
+                ReversedEdits::with_edit(AnimationEdit::Layer(folder_id, LayerEdit::SetOrdering(*originally_behind_id)))
+            } else if let Some((frontmost_id, _)) = layers.iter().rev().filter(|(id, _)| *id != folder_id && !folder_contents.contains(id)).nth(0) {
+                ReversedEdits::with_edits(vec![
+                    AnimationEdit::Layer(folder_id, LayerEdit::SetOrdering(*frontmost_id)),
+                    AnimationEdit::Layer(*frontmost_id, LayerEdit::SetOrdering(first_in_folder))
+                ])
+            } else {
+                return ReversedEdits::empty();
+            };
+
+            // Take the folder and its contents out of the list of layers
+            let (mut moved, mut layers): (Vec<_>, Vec<_>) = layers.into_iter()
+                .partition(|(id, _)| *id == folder_id || folder_contents.contains(id));
+
+            // The folder goes in front of its contents
+            let folder_position = moved.iter().position(|(id, _)| *id == folder_id).unwrap();
+            let folder          = moved.remove(folder_position);
+            moved.push(folder);
+
+            // Insert behind the 'order-behind' layer
+            let order_behind_index = layers.iter().position(|(id, _)| *id == order_behind).unwrap_or(layers.len());
+            layers.splice(order_behind_index..order_behind_index, moved);
+
+            self.write_layer_ordering(layers).await;
+
+            reversed_edits
+        }
+    }
+
+    ///
+    /// Updates the ordering of a list of layers to match their position in the list and writes them to storage
+    ///
+    fn write_layer_ordering<'a>(&'a mut self, layers: Vec<(u64, LayerProperties)>) -> impl 'a+Future<Output=()> {
+        async move {
+            let mut layers = layers;
+
             // Update the layer ordering
             for layer_num in 0..layers.len() {
                 layers[layer_num].1.ordering = layer_num as i64;
@@ -223,9 +318,7 @@ impl StreamAnimationCore {
                     })
                     .map(|(layer_id, serialized)| StorageCommand::WriteLayerProperties(layer_id, serialized)))
                 .await;
-
-            reversed_edits
-        } 
+        }
     }
 
     ///
@@ -328,4 +421,55 @@ impl StreamAnimationCore {
             ReversedEdits::with_edit(AnimationEdit::Layer(layer_id, LayerEdit::SetAlpha(old_alpha)))
         } 
     }
+
+    ///
+    /// Updates the properties of a layer
+    ///
+    /// The update function returns the edit that will undo its changes
+    ///
+    pub fn edit_layer_properties<'a>(&'a mut self, layer_id: u64, update_properties: impl 'a+Send+FnOnce(&mut LayerProperties) -> LayerEdit) -> impl 'a+Future<Output=ReversedEdits> {
+        async move {
+            // Read the current properties for this layer
+            let mut properties = match self.request_one(StorageCommand::ReadLayerProperties(layer_id)).await {
+                Some(StorageResponse::LayerProperties(_, properties)) => {
+                    LayerProperties::deserialize(&mut properties.chars())
+                        .unwrap_or_else(|| LayerProperties::default())
+                }
+
+                _ => LayerProperties::default()
+            };
+
+            // Update the properties
+            let reverse = update_properties(&mut properties);
+
+            // Save back to the storage
+            let mut serialized = String::new();
+            properties.serialize(&mut serialized);
+            self.request_one(StorageCommand::WriteLayerProperties(layer_id, serialized)).await;
+
+            ReversedEdits::with_edit(AnimationEdit::Layer(layer_id, reverse))
+        }
+    }
+
+    ///
+    /// Moves a layer into a folder (or out of any folder if the folder ID is `None`)
+    ///
+    pub fn set_layer_parent_folder<'a>(&'a mut self, layer_id: u64, folder_id: Option<u64>) -> impl 'a+Future<Output=ReversedEdits> {
+        async move {
+            if let Some(folder_id) = folder_id {
+                // A layer can't be put inside itself, or inside a folder that it contains
+                let layers = self.storage_connection.read_all_layer_properties().await;
+                if folder_id == layer_id || Self::folder_contents(layer_id, &layers).contains(&folder_id) {
+                    return ReversedEdits::empty();
+                }
+            }
+
+            self.edit_layer_properties(layer_id, move |properties| { 
+                let old_folder              = properties.parent_folder;
+                properties.parent_folder    = folder_id;
+
+                LayerEdit::SetParentFolder(old_folder)
+            }).await
+        }
+    }
 }
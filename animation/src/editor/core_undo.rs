@@ -23,6 +23,8 @@ impl StreamAnimationCore {
                 CompletedUndo(_edits)                           => { /* original_edit is changed to this if PerformUndo is successful */ }
                 FailedUndo(_reason)                             => { /* original_edit is changed to this if PerformUndo is unsuccessful */ }
                 FinishAction                                    => { /* Sent straight to the retired stream for organization */ },
+                Checkpoint(_name)                               => { /* Sent straight to the retired stream for the undo history */ },
+                JumpTo(_checkpoint)                             => { /* Sent straight to the retired stream for the undo history */ },
                 PerformUndo { original_actions, undo_actions }  => {
                     // The original edit is updated according to whether or not the undo succeeds or fails
                     match self.perform_undo(Arc::clone(original_actions), Arc::clone(undo_actions)).await {
@@ -0,0 +1,235 @@
+use super::core_element::*;
+use super::element_wrapper::*;
+use super::stream_animation_core::*;
+use crate::undo::*;
+use crate::traits::*;
+
+use futures::prelude::*;
+
+use std::iter;
+use std::time::{Duration};
+use std::collections::{HashMap, HashSet};
+
+///
+/// Generates the edits that will create a copy of an element and the elements it depends on at a particular time
+///
+/// The edits use the IDs of the original elements: `perform_copy_edits()` will assign new IDs when they're applied
+///
+fn copy_element_edits(element_id: ElementId, layer_id: u64, when: Duration, wrappers: &HashMap<i64, ElementWrapper>, copied: &mut HashSet<ElementId>, edits: &mut Vec<AnimationEdit>) {
+    use self::AnimationEdit::*;
+    use self::LayerEdit::*;
+
+    // Elements that are shared (eg, brush definitions) are only copied once
+    if copied.contains(&element_id) { return; }
+
+    let wrapper = match element_id.id().and_then(|id| wrappers.get(&id)) {
+        Some(wrapper)   => wrapper,
+        None            => { return; }
+    };
+    copied.insert(element_id);
+
+    // Attachments need to exist before the elements that use them
+    for attachment_id in wrapper.attachments.iter() {
+        copy_element_edits(*attachment_id, layer_id, when, wrappers, copied, edits);
+    }
+
+    match &wrapper.element {
+        Vector::Group(group)    => {
+            // Copy the elements in the group, then group them together again
+            let sub_element_ids = group.elements().map(|elem| elem.id()).collect::<Vec<_>>();
+            for sub_element_id in sub_element_ids.iter() {
+                copy_element_edits(*sub_element_id, layer_id, when, wrappers, copied, edits);
+            }
+
+            let sub_element_ids = sub_element_ids.into_iter().filter(|sub_element_id| copied.contains(sub_element_id)).collect();
+            edits.push(Element(sub_element_ids, ElementEdit::Group(element_id, group.group_type())));
+        }
+
+        Vector::Transformed(_)  |
+        Vector::Motion(_)       |
+        Vector::Error           => { }
+
+        vector                  => {
+            if wrapper.unattached && wrapper.parent.is_none() {
+                edits.push(Layer(layer_id, CreateElementUnattachedToFrame(when, element_id, vector.clone())));
+            } else {
+                edits.push(Layer(layer_id, CreateElement(when, element_id, vector.clone())));
+            }
+        }
+    }
+
+    // Attach the copied attachments to the copy
+    let attachments = wrapper.attachments.iter().filter(|attachment_id| copied.contains(attachment_id)).cloned().collect::<Vec<_>>();
+    if !attachments.is_empty() {
+        edits.push(Element(attachments, ElementEdit::AttachTo(element_id)));
+    }
+}
+
+///
+/// Returns the IDs of the elements that will be copied by a set of copy edits (as generated by `ReversedEdits::with_recreated_keyframe()`
+/// or `copy_element_edits()`)
+///
+/// The IDs are sorted so that the new IDs for the copies can be matched up with them no matter what order the edits were generated in
+///
+fn copied_element_ids(edits: &Vec<AnimationEdit>) -> Vec<ElementId> {
+    use self::AnimationEdit::*;
+    use self::LayerEdit::*;
+    use self::ElementEdit::*;
+
+    let mut copied_ids = edits.iter()
+        .flat_map(|edit| match edit {
+            Layer(_, CreateElement(_, element_id, _))                   |
+            Layer(_, CreateElementUnattachedToFrame(_, element_id, _))  => vec![*element_id],
+            Element(element_ids, Group(group_id, _))                    => element_ids.iter().cloned().chain(iter::once(*group_id)).collect(),
+            Element(element_ids, AttachTo(attach_to))                   => element_ids.iter().cloned().chain(iter::once(*attach_to)).collect(),
+            _                                                           => vec![]
+        })
+        .collect::<Vec<_>>();
+
+    copied_ids.sort();
+    copied_ids.dedup();
+
+    copied_ids
+}
+
+impl StreamAnimationCore {
+    ///
+    /// Assigns the IDs for the copies that will be created by a set of copy edits
+    ///
+    /// These are stored in the edit log so that the copies get the same IDs when the edit is redone or replayed.
+    ///
+    pub fn assign_copy_ids<'a>(&'a mut self, copy_edits: &'a Vec<AnimationEdit>) -> impl 'a+Future<Output=Vec<ElementId>> {
+        async move {
+            let mut new_ids = vec![];
+
+            for _ in copied_element_ids(copy_edits) {
+                new_ids.push(self.assign_element_id(ElementId::Unassigned).await);
+            }
+
+            new_ids
+        }
+    }
+
+    ///
+    /// Performs a set of edits that create elements (as generated by `ReversedEdits::with_recreated_keyframe()` or `copy_element_edits()`),
+    /// giving every element a new ID and moving it to a new layer and time
+    ///
+    /// The new IDs are taken from `new_ids` (as generated by `assign_copy_ids()`), and any that are missing are assigned here.
+    /// `AddKeyFrame` edits are ignored: the keyframe that the elements are added to must already exist. The return value is a map of
+    /// the original element IDs to the IDs of the copies.
+    ///
+    pub fn perform_copy_edits<'a>(&'a mut self, layer_id: u64, edits: Vec<AnimationEdit>, new_ids: &'a Vec<ElementId>, retime: impl 'a+Send+Fn(Duration) -> Duration) -> impl 'a+Future<Output=HashMap<ElementId, ElementId>> {
+        async move {
+            use self::AnimationEdit::*;
+            use self::LayerEdit::*;
+            use self::ElementEdit::*;
+
+            // Every element in the copy gets a new ID
+            let mut copy_ids = HashMap::new();
+            for (idx, element_id) in copied_element_ids(&edits).into_iter().enumerate() {
+                let new_id = match new_ids.get(idx) {
+                    Some(new_id)    => *new_id,
+                    None            => self.assign_element_id(ElementId::Unassigned).await
+                };
+
+                copy_ids.insert(element_id, new_id);
+            }
+
+            let map_id = |element_id: &ElementId| *copy_ids.get(element_id).unwrap_or(element_id);
+
+            // Create the copy
+            self.cached_keyframe = None;
+            self.cached_layers.remove(&layer_id);
+
+            for edit in edits.iter() {
+                match edit {
+                    Layer(_, CreateElement(when, element_id, vector))                   => { self.create_element(layer_id, retime(*when), map_id(element_id), vector.clone(), false).await; }
+                    Layer(_, CreateElementUnattachedToFrame(when, element_id, vector))  => { self.create_element(layer_id, retime(*when), map_id(element_id), vector.clone(), true).await; }
+
+                    Element(element_ids, Group(group_id, group_type))                   => {
+                        let element_ids = element_ids.iter().map(map_id).flat_map(|id| id.id()).collect::<Vec<_>>();
+                        self.group_elements(element_ids, map_id(group_id), *group_type).await;
+                    }
+
+                    Element(element_ids, AttachTo(attach_to))                           => {
+                        if let Some(attach_to) = map_id(attach_to).id() {
+                            let attachment_ids = element_ids.iter().map(map_id).collect::<Vec<_>>();
+                            self.update_elements(vec![attach_to], move |_wrapper| ElementUpdate::AddAttachments(attachment_ids.clone())).await;
+                        }
+                    }
+
+                    _                                                                   => { }
+                }
+            }
+
+            copy_ids
+        }
+    }
+
+    ///
+    /// Generates the edits that will create a copy of a set of elements (along with any elements they contain or have attached) in the
+    /// keyframe at the specified time on a layer, using the IDs of the original elements
+    ///
+    pub fn element_copy_edits<'a>(&'a mut self, element_ids: &'a Vec<i64>, layer_id: u64, when: Duration) -> impl 'a+Send+Future<Output=Vec<AnimationEdit>> {
+        async move {
+            // There must be a keyframe to copy the elements to
+            if self.edit_keyframe(layer_id, when).await.is_none() {
+                return vec![];
+            }
+
+            // Fetch the wrappers for the elements and everything they depend on
+            let mut wrappers    = HashMap::new();
+            let mut requested   = HashSet::new();
+            let mut pending     = element_ids.clone();
+
+            while !pending.is_empty() {
+                requested.extend(pending.iter().cloned());
+
+                let found   = self.wrappers_for_elements(pending.into_iter()).await;
+                pending     = found.values()
+                    .flat_map(|wrapper| wrapper.element.sub_element_ids().into_iter().chain(wrapper.attachments.iter().cloned()))
+                    .flat_map(|id| id.id())
+                    .filter(|id| !requested.contains(id))
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .collect();
+
+                wrappers.extend(found);
+            }
+
+            // Generate the edits to create the copies
+            let mut copied      = HashSet::new();
+            let mut copy_edits  = vec![];
+
+            for element_id in element_ids.iter() {
+                copy_element_edits(ElementId::Assigned(*element_id), layer_id, when, &wrappers, &mut copied, &mut copy_edits);
+            }
+
+            copy_edits
+        }
+    }
+
+    ///
+    /// Copies a set of elements (along with any elements they contain or have attached) to the keyframe at the specified time on a layer
+    ///
+    /// The copies are given the IDs in `new_ids`. The reversed edits will delete the copies.
+    ///
+    pub fn copy_elements<'a>(&'a mut self, element_ids: &'a Vec<i64>, layer_id: u64, when: Duration, new_ids: &'a Vec<ElementId>) -> impl 'a+Send+Future<Output=ReversedEdits> {
+        async move {
+            let copy_edits = self.element_copy_edits(element_ids, layer_id, when).await;
+
+            if copy_edits.is_empty() {
+                return ReversedEdits::empty();
+            }
+
+            // Perform the edits with the new IDs
+            let copy_ids        = self.perform_copy_edits(layer_id, copy_edits, new_ids, |when| when).await;
+
+            // Undo by deleting all of the copies
+            let mut copy_ids    = copy_ids.into_iter().map(|(_, new_id)| new_id).collect::<Vec<_>>();
+            copy_ids.sort();
+
+            ReversedEdits::with_edit(AnimationEdit::Element(copy_ids, ElementEdit::Delete))
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use super::stream_animation_core::*;
 use super::element_wrapper::*;
+use super::keyframe_tween::*;
 use super::pending_storage_change::*;
 use crate::storage::*;
 use crate::traits::*;
@@ -39,6 +40,9 @@ pub (super) struct KeyFrameCore {
     /// The brush that's active on the last_element, or none if this has not been calculated yet
     pub (super) active_brush: Option<Arc<dyn Brush>>,
 
+    /// The paths rendered by the elements in the following keyframe that elements in this keyframe are tweened into
+    pub (super) tween_targets: HashMap<ElementId, Arc<Vec<AnimationPath>>>,
+
     /// The animation layer for this frame (or None if this hasn't been populated yet or has been invalidated)
     pub (super) animation_layer: Mutex<Option<Arc<Desync<AnimationLayer>>>>
 }
@@ -53,6 +57,7 @@ impl Clone for KeyFrameCore {
             start:              self.start,
             end:                self.end,
             active_brush:       self.active_brush.clone(),
+            tween_targets:      self.tween_targets.clone(),
             animation_layer:    Mutex::new(self.animation_layer.lock().unwrap().clone())
         }
     }
@@ -141,6 +146,9 @@ impl KeyFrameCore {
                 None
             };
 
+            // Shape tweens need to know about the elements in the following keyframe
+            let tween_targets = read_tween_targets(&mut core.storage_connection, layer_id, &resolved, end_time).await;
+
             // Create the keyframe
             Some(KeyFrameCore {
                 layer_id:           layer_id,
@@ -150,6 +158,7 @@ impl KeyFrameCore {
                 start:              start_time,
                 end:                end_time,
                 active_brush:       None,
+                tween_targets:      tween_targets,
                 animation_layer:    Mutex::new(None)
             })
         }
@@ -300,6 +309,10 @@ impl KeyFrameCore {
             // Move on to the next element in the list
             next_element = wrapper.order_before;
         }
+
+        // Elements bound to bones are deformed by regions generated from the skeleton, and tweened elements morph into their targets
+        core.add_skeleton_regions(&mut gc);
+        core.add_tween_regions(&mut gc);
     }
 
     ///
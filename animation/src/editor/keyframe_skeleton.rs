@@ -0,0 +1,114 @@
+use super::keyframe_core::*;
+use crate::traits::*;
+
+use flo_canvas_animation::*;
+use flo_canvas_animation::description::{Point2D, BezierPath, BezierPoint, RotateDegrees, BoneDescription, SkeletonDescription, EffectDescription, RegionDescription};
+
+use std::sync::*;
+use std::time::{Duration};
+use std::collections::{HashMap};
+
+/// The distance around the bounds of an element that's included in the regions that animate it (so that strokes and anti-aliasing are included too)
+const ELEMENT_REGION_MARGIN: f32 = 8.0;
+
+///
+/// Creates a straight line as a bezier point
+///
+fn line_to(from: Point2D, to: Point2D) -> BezierPoint {
+    let offset = to - from;
+    BezierPoint(from + offset * (1.0/3.0), from + offset * (2.0/3.0), to)
+}
+
+impl KeyFrameCore {
+    ///
+    /// Creates the outline of a region that covers an element in this keyframe, or None if the element has no paths
+    ///
+    pub (super) fn element_region_outline(&self, element_id: ElementId) -> Option<BezierPath> {
+        // Find the bounds of the element
+        let wrapper     = self.elements.get(&element_id)?;
+        let properties  = self.apply_properties_for_element(&wrapper.element, Arc::new(VectorProperties::default()), Duration::from_millis(0));
+        let paths       = wrapper.element.to_path(&properties, PathConversion::Fastest)?;
+        let bounds      = paths.iter()
+            .map(|path| Rect::from(path))
+            .fold(Rect::empty(), |bounds, path_bounds| bounds.union(path_bounds));
+
+        if bounds.is_zero_size() { return None; }
+        let bounds      = bounds.inset(-ELEMENT_REGION_MARGIN*2.0, -ELEMENT_REGION_MARGIN*2.0);
+
+        // Outline the bounds
+        let top_left    = Point2D(bounds.x1 as _, bounds.y1 as _);
+        let top_right   = Point2D(bounds.x2 as _, bounds.y1 as _);
+        let bot_right   = Point2D(bounds.x2 as _, bounds.y2 as _);
+        let bot_left    = Point2D(bounds.x1 as _, bounds.y2 as _);
+
+        Some(BezierPath(top_left, vec![line_to(top_left, top_right), line_to(top_right, bot_right), line_to(bot_right, bot_left), line_to(bot_left, top_left)]))
+    }
+
+    ///
+    /// Builds a skeleton description from the bones in this keyframe, along with a map from the ID of each bone to its index in the skeleton
+    ///
+    /// Bone rotations are stored relative to the start of the keyframe, so the skeleton uses the same times as the animation layer.
+    ///
+    pub fn skeleton(&self) -> (SkeletonDescription, HashMap<ElementId, usize>) {
+        // Order the bones by ID so the same keyframe always generates the same skeleton
+        let mut bones = self.elements.values()
+            .filter_map(|wrapper| if let Vector::Bone(bone) = &wrapper.element { Some(bone) } else { None })
+            .collect::<Vec<_>>();
+        bones.sort_by_key(|bone| bone.id());
+
+        let indexes = bones.iter().enumerate()
+            .map(|(idx, bone)| (bone.id(), idx))
+            .collect::<HashMap<_, _>>();
+
+        let bones   = bones.into_iter()
+            .map(|bone| {
+                let (x1, y1) = bone.start();
+                let (x2, y2) = bone.end();
+
+                BoneDescription {
+                    parent:     bone.parent().and_then(|parent_id| indexes.get(&parent_id).cloned()),
+                    start:      Point2D(x1, y1),
+                    end:        Point2D(x2, y2),
+                    rotations:  bone.rotations().iter().map(|(when, degrees)| (*when, RotateDegrees(*degrees))).collect()
+                }
+            })
+            .collect();
+
+        (SkeletonDescription(bones), indexes)
+    }
+
+    ///
+    /// Adds the animation regions that deform the elements that are bound to the bones in this keyframe
+    ///
+    /// Each bound element gets a region covering its bounding box, with an effect that moves it along with the bones it's bound to.
+    ///
+    pub fn add_skeleton_regions(&self, gc: &mut AnimationLayerContext<'_>) {
+        let (skeleton, indexes) = self.skeleton();
+        if indexes.len() == 0 { return; }
+
+        // Gather the bones that each element is bound to
+        let mut bindings = HashMap::<ElementId, Vec<(usize, f64)>>::new();
+
+        for wrapper in self.elements.values() {
+            if let Vector::Bone(bone) = &wrapper.element {
+                let bone_idx = indexes[&bone.id()];
+
+                for (element_id, weight) in bone.bindings().iter() {
+                    bindings.entry(*element_id).or_insert_with(|| vec![]).push((bone_idx, *weight));
+                }
+            }
+        }
+
+        let mut bindings = bindings.into_iter().collect::<Vec<_>>();
+        bindings.sort_by_key(|(element_id, _)| *element_id);
+
+        for (element_id, element_bindings) in bindings {
+            // Create a region around the element that deforms it using the skeleton
+            let outline     = if let Some(outline) = self.element_region_outline(element_id) { outline } else { continue; };
+            let description = RegionDescription(vec![outline], EffectDescription::Skeleton(skeleton.clone(), element_bindings));
+            let region: Arc<dyn AnimationRegion> = (&description).into();
+
+            gc.add_region(region);
+        }
+    }
+}
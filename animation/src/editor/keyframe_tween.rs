@@ -0,0 +1,107 @@
+use super::keyframe_core::*;
+use super::element_wrapper::*;
+use crate::storage::*;
+use crate::traits::*;
+
+use flo_canvas::{Draw};
+use flo_canvas_animation::*;
+use flo_canvas_animation::description::{Point2D, EffectDescription, RegionDescription, ShapeTweenDescription, TweenPathDescription};
+
+use futures::prelude::*;
+
+use std::sync::*;
+use std::time::{Duration};
+use std::collections::{HashMap};
+
+///
+/// Reads the shapes of the elements that the tweens in a keyframe morph into
+///
+/// Tweens target elements in the keyframe that starts where the tweened keyframe ends. The result maps the ID of each target
+/// element to the paths that it renders.
+///
+pub (super) fn read_tween_targets<'a>(storage: &'a mut StorageConnection, layer_id: u64, elements: &'a HashMap<ElementId, ElementWrapper>, end_time: Duration) -> impl 'a+Future<Output=HashMap<ElementId, Arc<Vec<AnimationPath>>>> {
+    async move {
+        // Find the elements that are being tweened into
+        let targets = elements.values()
+            .filter_map(|wrapper| if let Vector::Tween(tween) = &wrapper.element { Some(tween.target()) } else { None })
+            .collect::<Vec<_>>();
+
+        if targets.len() == 0 {
+            return HashMap::new();
+        }
+
+        // Read the following keyframe (if there is no following keyframe, we'll read this keyframe again, which won't start at the end time)
+        let next_keyframe = match storage.read_keyframe(layer_id, end_time).await {
+            Some(next_keyframe) => next_keyframe,
+            None                => { return HashMap::new(); }
+        };
+
+        if next_keyframe.start_time != end_time {
+            return HashMap::new();
+        }
+
+        // Render each target to a set of paths
+        targets.into_iter()
+            .filter_map(|target_id| {
+                let wrapper         = next_keyframe.elements.get(&target_id)?;
+                let mut properties  = Arc::new(VectorProperties::default());
+
+                for attachment_id in wrapper.attachments.iter() {
+                    if let Some(attachment) = next_keyframe.elements.get(attachment_id) {
+                        properties = attachment.element.update_properties(properties, Duration::from_millis(0));
+                    }
+                }
+
+                let mut drawing     = Vec::<Draw>::new();
+                properties.render_static(&mut drawing, wrapper.element.clone(), Duration::from_millis(0));
+
+                let mut to_paths    = LayerDrawingToPaths::new();
+                let paths           = to_paths.draw(drawing).collect::<Vec<_>>();
+
+                Some((target_id, Arc::new(paths)))
+            })
+            .collect()
+    }
+}
+
+impl KeyFrameCore {
+    ///
+    /// Adds the animation regions that morph elements with a tween attached into the shape of their target elements
+    ///
+    /// The tween lasts for the whole length of the keyframe, so the element reaches its final shape just as the following
+    /// keyframe starts.
+    ///
+    pub fn add_tween_regions(&self, gc: &mut AnimationLayerContext<'_>) {
+        if self.tween_targets.len() == 0 { return; }
+
+        // Find the elements that have tweens attached to them
+        let mut tweened = self.elements.iter()
+            .filter(|(_, wrapper)| !wrapper.unattached)
+            .flat_map(|(element_id, wrapper)| {
+                wrapper.attachments.iter()
+                    .filter_map(|attachment_id| self.elements.get(attachment_id))
+                    .filter_map(|attachment| if let Vector::Tween(tween) = &attachment.element { Some(tween.clone()) } else { None })
+                    .map(move |tween| (*element_id, tween))
+            })
+            .collect::<Vec<_>>();
+        tweened.sort_by_key(|(element_id, _)| *element_id);
+
+        let duration = self.end - self.start;
+
+        for (element_id, tween) in tweened {
+            // The target might not exist in the following keyframe
+            let target      = if let Some(target) = self.tween_targets.get(&tween.target()) { target } else { continue; };
+
+            // Create a region around the element that morphs it into the target
+            let outline     = if let Some(outline) = self.element_region_outline(element_id) { outline } else { continue; };
+            let targets     = target.iter().map(|path| TweenPathDescription::from(path)).collect();
+            let hints       = tween.hints().iter().map(|(from, to)| (Point2D(from.0, from.1), Point2D(to.0, to.1))).collect();
+            let tween       = ShapeTweenDescription { targets: targets, hints: hints };
+
+            let description = RegionDescription(vec![outline], EffectDescription::ShapeTween(duration, tween));
+            let region: Arc<dyn AnimationRegion> = (&description).into();
+
+            gc.add_region(region);
+        }
+    }
+}
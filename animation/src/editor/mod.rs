@@ -8,16 +8,21 @@ mod core_undo;
 mod core_path;
 mod core_paint;
 mod core_layer;
+mod core_keyframe;
 mod core_motion;
+mod core_bone;
 mod core_element;
 mod keyframe_core;
 mod keyframe_raycast;
+mod keyframe_skeleton;
+mod keyframe_tween;
 mod pending_storage_change;
 mod paint_fill;
 mod layer_cut;
 pub (crate) mod element_wrapper;
 mod element_collide;
 mod element_transform;
+mod element_copy;
 mod element_convert_to_path;
 mod stream_layer;
 mod stream_frame;
@@ -114,6 +114,12 @@ impl ReversedEdits {
             if let Some(layer_properties) = storage_connection.read_layer_properties(layer_id).await {
                 recreate_layer.push(AnimationEdit::Layer(layer_id, LayerEdit::SetName(layer_properties.name)));
                 recreate_layer.push(AnimationEdit::Layer(layer_id, LayerEdit::SetAlpha(layer_properties.alpha)));
+                recreate_layer.push(AnimationEdit::Layer(layer_id, LayerEdit::SetHidden(layer_properties.hidden)));
+                recreate_layer.push(AnimationEdit::Layer(layer_id, LayerEdit::SetLocked(layer_properties.locked)));
+                recreate_layer.push(AnimationEdit::Layer(layer_id, LayerEdit::SetBlendMode(layer_properties.blend_mode)));
+                recreate_layer.push(AnimationEdit::Layer(layer_id, LayerEdit::SetIsFolder(layer_properties.is_folder)));
+                recreate_layer.push(AnimationEdit::Layer(layer_id, LayerEdit::SetCollapsed(layer_properties.collapsed)));
+                recreate_layer.push(AnimationEdit::Layer(layer_id, LayerEdit::SetParentFolder(layer_properties.parent_folder)));
             }
 
             // Order it relative to other layers
@@ -14,6 +14,7 @@ use futures::task::{Poll};
 use futures::stream;
 use futures::stream::{BoxStream};
 
+use std::mem;
 use std::sync::*;
 use std::ops::{Range};
 use std::time::{Duration};
@@ -33,6 +34,37 @@ pub struct StreamAnimation {
     idle_sync_requests: Desync<Vec<Desync<Option<Vec<StorageResponse>>>>>,
 }
 
+///
+/// Splits a set of edits so that every edit that copies existing elements starts a new set
+///
+/// The IDs for a copy can't be assigned until the elements it copies exist, so the edits before it need to be performed first
+///
+fn split_before_copies(edits: &Vec<AnimationEdit>) -> Vec<Vec<AnimationEdit>> {
+    let mut split_edits = vec![];
+    let mut current     = vec![];
+
+    for edit in edits.iter() {
+        let copies_elements = match edit {
+            AnimationEdit::Layer(_, LayerEdit::DuplicateKeyFrame(_, _, new_ids))    => new_ids.is_empty(),
+            AnimationEdit::Element(_, ElementEdit::CopyTo { new_ids, .. })          => new_ids.is_empty(),
+            AnimationEdit::Element(_, ElementEdit::MoveTo { new_ids, .. })          => new_ids.is_empty(),
+            _                                                                       => false
+        };
+
+        if copies_elements && !current.is_empty() {
+            split_edits.push(mem::take(&mut current));
+        }
+
+        current.push(edit.clone());
+    }
+
+    if !current.is_empty() {
+        split_edits.push(current);
+    }
+
+    split_edits
+}
+
 impl StreamAnimation {
     ///
     /// Creates a new stream animation. The result is the animation implementation and the
@@ -63,20 +95,23 @@ impl StreamAnimation {
         // Anything published to the editor is piped into the core
         pipe_in(Arc::clone(&core), edit_publisher.subscribe(), |core, edits: Arc<Vec<AnimationEdit>>| {
             async move {
-                // Edits require some pre-processing: assign the IDs, perform undo actions and write to the log (note that undo edits are performed before serialization)
-                let mut edits   = core.assign_ids_to_edits(&*edits).await;
-                core.process_undo_edits(&mut edits).await;
-                core.serialize_edits_to_log(&edits).await;
-
-                // Perform the edits to retire them
-                let retired     = core.perform_edits(edits).await;
-
-                // Clean up the edit publishers, in case any aren't being listened to any more
-                core.retired_edit_senders.retain(|sender| sender.count_subscribers() > 0);
-
-                // Send the edits as retired
-                for retired_sender in core.retired_edit_senders.iter_mut() {
-                    retired_sender.publish(retired.clone()).await;
+                // Copies are given IDs according to what they copy, so the edits before a copy are performed before its IDs are assigned
+                for edits in split_before_copies(&*edits) {
+                    // Edits require some pre-processing: assign the IDs, perform undo actions and write to the log (note that undo edits are performed before serialization)
+                    let mut edits   = core.assign_ids_to_edits(&edits).await;
+                    core.process_undo_edits(&mut edits).await;
+                    core.serialize_edits_to_log(&edits).await;
+
+                    // Perform the edits to retire them
+                    let retired     = core.perform_edits(edits).await;
+
+                    // Clean up the edit publishers, in case any aren't being listened to any more
+                    core.retired_edit_senders.retain(|sender| sender.count_subscribers() > 0);
+
+                    // Send the edits as retired
+                    for retired_sender in core.retired_edit_senders.iter_mut() {
+                        retired_sender.publish(retired.clone()).await;
+                    }
                 }
             }.boxed()
         });
@@ -390,6 +425,25 @@ impl EditableAnimation for StreamAnimation {
         receiver.boxed()
     }
 
+    ///
+    /// Reads the undo history that has been stored for this animation
+    ///
+    fn read_undo_history(&self) -> UndoHistory {
+        self.wait_for_edits();
+
+        self.core.future_desync(|core| core.storage_connection.read_undo_history().boxed())
+            .sync().unwrap_or_else(|_| UndoHistory::empty())
+    }
+
+    ///
+    /// Stores changes to the undo history alongside this animation (nodes replace any existing nodes with the same ID)
+    ///
+    fn write_undo_history(&self, changes: UndoHistory) {
+        self.core.future_desync(move |core| async move {
+            core.storage_connection.write_undo_history(&changes).await;
+        }.boxed()).detach();
+    }
+
     ///
     /// Flushes any caches this might have (forces reload from data storage)
     ///
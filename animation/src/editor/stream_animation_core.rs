@@ -192,6 +192,23 @@ impl StreamAnimationCore {
                 Element(elements, Group(group_id, group_type)) =>
                     Element(elements.clone(), Group(self.assign_element_id(*group_id).await, *group_type)),
 
+                Layer(layer_id, DuplicateKeyFrame(from, to, new_ids)) if new_ids.is_empty() => {
+                    let copy_edits = self.key_frame_copy_edits(*layer_id, *from).await;
+                    Layer(*layer_id, DuplicateKeyFrame(*from, *to, self.assign_copy_ids(&copy_edits).await))
+                }
+
+                Element(elements, CopyTo { layer, when, new_ids }) if new_ids.is_empty() => {
+                    let element_ids = elements.iter().flat_map(|id| id.id()).collect();
+                    let copy_edits  = self.element_copy_edits(&element_ids, *layer, *when).await;
+                    Element(elements.clone(), CopyTo { layer: *layer, when: *when, new_ids: self.assign_copy_ids(&copy_edits).await })
+                }
+
+                Element(elements, MoveTo { layer, when, new_ids }) if new_ids.is_empty() => {
+                    let element_ids = elements.iter().flat_map(|id| id.id()).collect();
+                    let copy_edits  = self.element_copy_edits(&element_ids, *layer, *when).await;
+                    Element(elements.clone(), MoveTo { layer: *layer, when: *when, new_ids: self.assign_copy_ids(&copy_edits).await })
+                }
+
                 other => other.clone()
             }
         }
@@ -290,6 +307,7 @@ impl StreamAnimationCore {
                     Layer(layer_id, layer_edit)             => { reversed_edits.add_to_start(self.layer_edit(*layer_id, layer_edit).await); }
                     Element(element_ids, element_edit)      => { reversed_edits.add_to_start(self.element_edit(element_ids, element_edit).await); }
                     Motion(motion_id, motion_edit)          => { reversed_edits.add_to_start(self.motion_edit(*motion_id, motion_edit).await); }
+                    Bone(bone_id, bone_edit)                => { reversed_edits.add_to_start(self.bone_edit(*bone_id, bone_edit).await); }
                     Undo(_undo_edit)                        => { /* Undo edits, particularly perform_undo need to be done in a separate pass, in a separate function */ },
                     SetSize(width, height)                  => { reversed_edits.add_to_start(self.set_size(*width, *height).await) }
                     SetFrameLength(length)                  => { reversed_edits.add_to_start(self.set_frame_length(*length).await) }
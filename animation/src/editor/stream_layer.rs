@@ -79,6 +79,48 @@ impl Layer for StreamLayer {
         self.properties.alpha
     }
 
+    ///
+    /// True if this layer is hidden
+    ///
+    fn is_hidden(&self) -> bool {
+        self.properties.hidden
+    }
+
+    ///
+    /// True if this layer is locked against editing
+    ///
+    fn is_locked(&self) -> bool {
+        self.properties.locked
+    }
+
+    ///
+    /// How this layer is combined with the layers behind it
+    ///
+    fn blend_mode(&self) -> LayerBlendMode {
+        self.properties.blend_mode
+    }
+
+    ///
+    /// True if this layer is a folder that can contain other layers
+    ///
+    fn is_folder(&self) -> bool {
+        self.properties.is_folder
+    }
+
+    ///
+    /// True if this layer is a folder that is collapsed in the timeline
+    ///
+    fn is_collapsed(&self) -> bool {
+        self.properties.collapsed
+    }
+
+    ///
+    /// The ID of the folder layer containing this layer, if there is one
+    ///
+    fn parent_folder(&self) -> Option<u64> {
+        self.properties.parent_folder
+    }
+
     ///
     /// Retrieves a frame from this layer with the specified parameters
     ///
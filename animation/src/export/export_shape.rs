@@ -0,0 +1,318 @@
+use flo_canvas::*;
+
+///
+/// A colour used to paint a shape in an exported document
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExportPaint {
+    /// The red, green and blue components of this colour (0-1)
+    pub rgb: (f32, f32, f32),
+
+    /// The opacity of this colour (0-1)
+    pub opacity: f32
+}
+
+///
+/// A subpath in an exported shape (the coordinates are in document space, with the y axis pointing down)
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportSubpath {
+    /// Where this subpath starts
+    pub start: (f32, f32),
+
+    /// The curves in this subpath, as ((cp1, cp2), end_point)
+    pub curves: Vec<(((f32, f32), (f32, f32)), (f32, f32))>,
+
+    /// True if this subpath is closed
+    pub closed: bool
+}
+
+///
+/// A filled or stroked shape generated while exporting a frame of an animation
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportShape {
+    /// The subpaths making up this shape
+    pub subpaths: Vec<ExportSubpath>,
+
+    /// The paint used to fill this shape, if it's filled
+    pub fill: Option<ExportPaint>,
+
+    /// The paint and width used to stroke this shape, if it's stroked
+    pub stroke: Option<(ExportPaint, f32)>,
+
+    /// True if this shape is filled using the even-odd rule rather than the non-zero rule
+    pub even_odd: bool
+}
+
+///
+/// The drawing state while collecting shapes
+///
+#[derive(Clone)]
+struct CollectState {
+    /// The transform from canvas coordinates to document coordinates
+    transform:      Transform2D,
+
+    /// The fill colour
+    fill_color:     Color,
+
+    /// The stroke colour
+    stroke_color:   Color,
+
+    /// The width of the stroke
+    line_width:     f32,
+
+    /// The winding rule to use for fills
+    winding_rule:   WindingRule
+}
+
+///
+/// Converts a stream of drawing instructions into a list of export shapes
+///
+pub struct ExportShapeCollector {
+    /// The transform used when the transform is reset
+    initial_transform:  Transform2D,
+
+    /// The opacity to apply to everything that's drawn
+    opacity:            f32,
+
+    /// The current drawing state
+    state:              CollectState,
+
+    /// The stack of pushed states
+    state_stack:        Vec<CollectState>,
+
+    /// The subpaths in the path that's being defined
+    current_path:       Vec<ExportSubpath>,
+
+    /// The shapes that have been collected so far
+    shapes:             Vec<ExportShape>
+}
+
+impl ExportPaint {
+    ///
+    /// Creates the paint for a colour, with an additional opacity
+    ///
+    pub fn from_color(color: Color, opacity: f32) -> ExportPaint {
+        let (r, g, b, a) = color.to_rgba_components();
+
+        ExportPaint {
+            rgb:        (r, g, b),
+            opacity:    a * opacity
+        }
+    }
+}
+
+impl ExportShapeCollector {
+    ///
+    /// Creates a new shape collector for an animation canvas of a particular height
+    ///
+    /// Animation canvases have the y axis pointing upwards, whereas exported documents have it pointing downwards,
+    /// so the collector flips the canvas as shapes are generated.
+    ///
+    pub fn new(canvas_height: f64) -> ExportShapeCollector {
+        let initial_transform = Transform2D::translate(0.0, canvas_height as f32) * Transform2D::scale(1.0, -1.0);
+
+        ExportShapeCollector {
+            initial_transform:  initial_transform,
+            opacity:            1.0,
+            state:              CollectState {
+                transform:      initial_transform,
+                fill_color:     Color::Rgba(0.0, 0.0, 0.0, 1.0),
+                stroke_color:   Color::Rgba(0.0, 0.0, 0.0, 1.0),
+                line_width:     1.0,
+                winding_rule:   WindingRule::NonZero
+            },
+            state_stack:        vec![],
+            current_path:       vec![],
+            shapes:             vec![]
+        }
+    }
+
+    ///
+    /// Sets the opacity applied to the shapes that are drawn after this call (eg, for the alpha value of a layer)
+    ///
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+    }
+
+    ///
+    /// Returns the shapes that have been collected
+    ///
+    pub fn shapes(self) -> Vec<ExportShape> {
+        self.shapes
+    }
+
+    ///
+    /// Transforms a point from canvas coordinates to document coordinates
+    ///
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        self.state.transform.transform_point(x, y)
+    }
+
+    ///
+    /// Returns the subpath that's currently being defined
+    ///
+    fn current_subpath(&mut self) -> &mut ExportSubpath {
+        if self.current_path.len() == 0 {
+            self.current_path.push(ExportSubpath { start: self.point(0.0, 0.0), curves: vec![], closed: false });
+        }
+
+        self.current_path.last_mut().unwrap()
+    }
+
+    ///
+    /// Returns the width that a line of a particular width should have after the current transform has been applied
+    ///
+    fn transformed_width(&self, width: f32) -> f32 {
+        let (x1, y1) = self.point(0.0, 0.0);
+        let (x2, y2) = self.point(width, 0.0);
+
+        ((x2-x1)*(x2-x1) + (y2-y1)*(y2-y1)).sqrt()
+    }
+
+    ///
+    /// Processes a path operation
+    ///
+    fn path(&mut self, path_op: PathOp) {
+        match path_op {
+            PathOp::NewPath                                         => { self.current_path = vec![]; }
+            PathOp::Move(x, y)                                      => {
+                let start = self.point(x, y);
+                self.current_path.push(ExportSubpath { start: start, curves: vec![], closed: false });
+            }
+
+            PathOp::Line(x, y)                                      => {
+                let end     = self.point(x, y);
+                let subpath = self.current_subpath();
+                let start   = subpath.curves.last().map(|(_, end)| *end).unwrap_or(subpath.start);
+
+                // Lines are represented as curves with their control points on the line
+                let cp1     = (start.0 + (end.0-start.0)/3.0, start.1 + (end.1-start.1)/3.0);
+                let cp2     = (start.0 + (end.0-start.0)*2.0/3.0, start.1 + (end.1-start.1)*2.0/3.0);
+                subpath.curves.push(((cp1, cp2), end));
+            }
+
+            PathOp::BezierCurve(((cp1x, cp1y), (cp2x, cp2y)), (x, y))  => {
+                let cp1     = self.point(cp1x, cp1y);
+                let cp2     = self.point(cp2x, cp2y);
+                let end     = self.point(x, y);
+
+                self.current_subpath().curves.push(((cp1, cp2), end));
+            }
+
+            PathOp::ClosePath                                       => {
+                let subpath     = self.current_subpath();
+                subpath.closed  = true;
+
+                // Any further drawing starts from the same point
+                let start       = subpath.start;
+                self.current_path.push(ExportSubpath { start: start, curves: vec![], closed: false });
+            }
+        }
+    }
+
+    ///
+    /// Returns the subpaths that make up the current path
+    ///
+    fn current_subpaths(&self) -> Vec<ExportSubpath> {
+        self.current_path.iter()
+            .filter(|subpath| subpath.curves.len() > 0)
+            .cloned()
+            .collect()
+    }
+
+    ///
+    /// Processes a drawing instruction
+    ///
+    pub fn draw(&mut self, draw: Draw) {
+        match draw {
+            Draw::Path(path_op)                 => { self.path(path_op); }
+
+            Draw::Fill                          => {
+                let subpaths = self.current_subpaths();
+                if subpaths.len() > 0 {
+                    self.shapes.push(ExportShape {
+                        subpaths:   subpaths,
+                        fill:       Some(ExportPaint::from_color(self.state.fill_color, self.opacity)),
+                        stroke:     None,
+                        even_odd:   self.state.winding_rule == WindingRule::EvenOdd
+                    });
+                }
+            }
+
+            Draw::Stroke                        => {
+                let subpaths = self.current_subpaths();
+                if subpaths.len() > 0 {
+                    self.shapes.push(ExportShape {
+                        subpaths:   subpaths,
+                        fill:       None,
+                        stroke:     Some((ExportPaint::from_color(self.state.stroke_color, self.opacity), self.transformed_width(self.state.line_width))),
+                        even_odd:   false
+                    });
+                }
+            }
+
+            Draw::FillColor(color)              => { self.state.fill_color = color; }
+            Draw::StrokeColor(color)            => { self.state.stroke_color = color; }
+            Draw::LineWidth(width)              => { self.state.line_width = width; }
+            Draw::LineWidthPixels(width)        => { self.state.line_width = width; }
+            Draw::WindingRule(winding_rule)     => { self.state.winding_rule = winding_rule; }
+
+            Draw::IdentityTransform             => { self.state.transform = self.initial_transform; }
+            Draw::MultiplyTransform(transform)  => { self.state.transform = self.state.transform * transform; }
+
+            Draw::PushState                     => { self.state_stack.push(self.state.clone()); }
+            Draw::PopState                      => { if let Some(state) = self.state_stack.pop() { self.state = state; } }
+
+            Draw::ClearCanvas(_)                => { self.shapes = vec![]; self.state_stack = vec![]; self.state.transform = self.initial_transform; }
+
+            // Other instructions (textures, sprites, clipping, etc) aren't supported by the exporter
+            _                                   => { }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn flip_filled_rectangle() {
+        let mut collector = ExportShapeCollector::new(100.0);
+
+        collector.draw(Draw::FillColor(Color::Rgba(1.0, 0.0, 0.0, 0.5)));
+        collector.draw(Draw::Path(PathOp::NewPath));
+        collector.draw(Draw::Path(PathOp::Move(10.0, 10.0)));
+        collector.draw(Draw::Path(PathOp::Line(20.0, 10.0)));
+        collector.draw(Draw::Path(PathOp::Line(20.0, 30.0)));
+        collector.draw(Draw::Path(PathOp::ClosePath));
+        collector.draw(Draw::Fill);
+
+        let shapes = collector.shapes();
+
+        assert!(shapes.len() == 1);
+        assert!(shapes[0].fill == Some(ExportPaint { rgb: (1.0, 0.0, 0.0), opacity: 0.5 }));
+        assert!(shapes[0].subpaths.len() == 1);
+        assert!(shapes[0].subpaths[0].closed);
+        assert!(shapes[0].subpaths[0].start == (10.0, 90.0));
+        assert!(shapes[0].subpaths[0].curves[1].1 == (20.0, 70.0));
+    }
+
+    #[test]
+    fn stroke_width_is_transformed() {
+        let mut collector = ExportShapeCollector::new(100.0);
+
+        collector.draw(Draw::LineWidth(2.0));
+        collector.draw(Draw::MultiplyTransform(Transform2D::scale(3.0, 3.0)));
+        collector.draw(Draw::Path(PathOp::NewPath));
+        collector.draw(Draw::Path(PathOp::Move(0.0, 0.0)));
+        collector.draw(Draw::Path(PathOp::Line(10.0, 0.0)));
+        collector.draw(Draw::Stroke);
+
+        let shapes = collector.shapes();
+
+        assert!(shapes.len() == 1);
+        assert!((shapes[0].stroke.unwrap().1 - 6.0).abs() < 0.01);
+    }
+}
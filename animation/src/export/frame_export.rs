@@ -0,0 +1,125 @@
+use super::export_shape::*;
+use crate::traits::*;
+
+use futures::prelude::*;
+
+use std::time::Duration;
+
+///
+/// The shapes that make up a frame of an animation, ready to be written out in an export format
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportFrame {
+    /// The number of the first frame where these shapes appear
+    pub start_frame: usize,
+
+    /// The number of frames that these shapes are displayed for
+    pub num_frames: usize,
+
+    /// The shapes in this frame, in the order they should be drawn
+    pub shapes: Vec<ExportShape>
+}
+
+///
+/// Returns the number of frames in an animation
+///
+pub fn animation_frame_count(animation: &dyn Animation) -> usize {
+    let frame_length    = animation.frame_length().as_nanos().max(1);
+    let duration        = animation.duration().as_nanos();
+    let num_frames      = (duration + frame_length - 1) / frame_length;
+
+    (num_frames as usize).max(1)
+}
+
+///
+/// True if a layer is not hidden, and is not inside a hidden folder
+///
+pub fn is_layer_visible(animation: &dyn Animation, layer: &dyn Layer) -> bool {
+    let mut folder_id   = layer.parent_folder();
+    let mut depth       = 0;
+
+    if layer.is_hidden() {
+        return false;
+    }
+
+    // The depth check guards against folders that contain themselves
+    while let (Some(parent_id), true) = (folder_id, depth < 64) {
+        match animation.get_layer_with_id(parent_id) {
+            Some(folder)    => {
+                if folder.is_hidden() { return false; }
+                folder_id = folder.parent_folder();
+            }
+
+            None            => { break; }
+        }
+
+        depth += 1;
+    }
+
+    true
+}
+
+///
+/// Renders the shapes for all of the layers of an animation at a particular time
+///
+/// The layers are rendered using the same animation layers as the editor, so any animation effects that are attached
+/// to the elements in a keyframe are applied.
+///
+pub async fn export_shapes_at_time(animation: &dyn Animation, when: Duration) -> Vec<ExportShape> {
+    let (_, height)     = animation.size();
+    let mut collector   = ExportShapeCollector::new(height);
+
+    for layer_id in animation.get_layer_ids() {
+        let layer = match animation.get_layer_with_id(layer_id) {
+            Some(layer) => layer,
+            None        => { continue; }
+        };
+
+        // Hidden layers (and layers inside hidden folders) are not exported
+        if !is_layer_visible(animation, &*layer) {
+            continue;
+        }
+
+        // Fetch the animation layer for the keyframe at this time
+        let frame                   = layer.get_frame_at_time(when);
+        let (time, animation_layer) = frame.to_animation_layer();
+
+        // Render the layer at the appropriate time
+        let drawing = animation_layer.future_sync(move |animation_layer| {
+            async move {
+                animation_layer.render_at_time(time).await
+            }.boxed()
+        }).await.unwrap_or_else(|_| vec![]);
+
+        // Convert to shapes
+        collector.set_opacity(layer.alpha() as f32);
+        drawing.into_iter().for_each(|draw| collector.draw(draw));
+    }
+
+    collector.shapes()
+}
+
+///
+/// Renders every frame of an animation into shapes
+///
+/// Consecutive frames that render identically (for instance, the frames between two keyframes where nothing is
+/// animated) are combined into a single `ExportFrame`.
+///
+pub async fn export_frames(animation: &dyn Animation) -> Vec<ExportFrame> {
+    let frame_length                        = animation.frame_length();
+    let num_frames                          = animation_frame_count(animation);
+    let mut frames: Vec<ExportFrame>        = vec![];
+
+    for frame_num in 0..num_frames {
+        let when    = frame_length * (frame_num as u32);
+        let shapes  = export_shapes_at_time(animation, when).await;
+
+        // Extend the previous frame if it's identical to this one
+        match frames.last_mut() {
+            Some(last_frame) if last_frame.shapes == shapes => { last_frame.num_frames += 1; }
+            _                                               => { frames.push(ExportFrame { start_frame: frame_num, num_frames: 1, shapes: shapes }); }
+        }
+    }
+
+    frames
+}
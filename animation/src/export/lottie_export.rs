@@ -0,0 +1,164 @@
+use super::export_shape::*;
+use super::frame_export::*;
+use crate::traits::*;
+
+use serde_json::{json, Value};
+
+///
+/// A static (non-animated) Lottie property value
+///
+fn static_value(value: Value) -> Value {
+    json!({ "a": 0, "k": value })
+}
+
+///
+/// Converts a subpath to a Lottie shape path ('sh')
+///
+fn lottie_subpath(subpath: &ExportSubpath) -> Value {
+    // Lottie paths are a list of vertices with in and out tangents relative to each vertex
+    let mut vertices        = vec![subpath.start];
+    let mut in_tangents     = vec![(0.0, 0.0)];
+    let mut out_tangents    = vec![];
+
+    for ((cp1, cp2), end) in subpath.curves.iter() {
+        let last = *vertices.last().unwrap();
+
+        out_tangents.push((cp1.0 - last.0, cp1.1 - last.1));
+        vertices.push(*end);
+        in_tangents.push((cp2.0 - end.0, cp2.1 - end.1));
+    }
+    out_tangents.push((0.0, 0.0));
+
+    // Closed paths that end where they start don't need to repeat the first vertex
+    if subpath.closed && vertices.len() > 1 && vertices.last() == vertices.first() {
+        in_tangents[0] = *in_tangents.last().unwrap();
+
+        vertices.pop();
+        in_tangents.pop();
+        out_tangents.pop();
+    }
+
+    let points = |points: Vec<(f32, f32)>| points.into_iter().map(|(x, y)| json!([x, y])).collect::<Vec<_>>();
+
+    json!({
+        "ty":   "sh",
+        "ks":   static_value(json!({
+            "c": subpath.closed,
+            "v": points(vertices),
+            "i": points(in_tangents),
+            "o": points(out_tangents)
+        }))
+    })
+}
+
+///
+/// Converts a paint colour to a Lottie colour value
+///
+fn lottie_color(paint: &ExportPaint) -> Value {
+    let (r, g, b) = paint.rgb;
+    static_value(json!([r, g, b, 1.0]))
+}
+
+///
+/// Converts an export shape to a Lottie group ('gr')
+///
+fn lottie_shape(shape: &ExportShape) -> Value {
+    let mut items = shape.subpaths.iter().map(|subpath| lottie_subpath(subpath)).collect::<Vec<_>>();
+
+    if let Some(fill) = &shape.fill {
+        items.push(json!({
+            "ty":   "fl",
+            "c":    lottie_color(fill),
+            "o":    static_value(json!(fill.opacity * 100.0)),
+            "r":    if shape.even_odd { 2 } else { 1 }
+        }));
+    }
+
+    if let Some((stroke, width)) = &shape.stroke {
+        items.push(json!({
+            "ty":   "st",
+            "c":    lottie_color(stroke),
+            "o":    static_value(json!(stroke.opacity * 100.0)),
+            "w":    static_value(json!(width)),
+            "lc":   2,
+            "lj":   2
+        }));
+    }
+
+    // Groups must end with a transform
+    items.push(json!({
+        "ty":   "tr",
+        "p":    static_value(json!([0, 0])),
+        "a":    static_value(json!([0, 0])),
+        "s":    static_value(json!([100, 100])),
+        "r":    static_value(json!(0)),
+        "o":    static_value(json!(100))
+    }));
+
+    json!({
+        "ty":   "gr",
+        "it":   items
+    })
+}
+
+///
+/// Converts an animation to a Lottie JSON value
+///
+/// Each distinct frame of the animation becomes a shape layer that is only visible while that frame is displayed
+///
+pub async fn animation_to_lottie_json(animation: &dyn Animation) -> Value {
+    let (width, height) = animation.size();
+    let frames          = export_frames(animation).await;
+    let num_frames      = animation_frame_count(animation);
+    let frame_rate      = 1.0 / animation.frame_length().as_secs_f64();
+
+    let layers = frames.iter()
+        .enumerate()
+        .filter(|(_, frame)| frame.shapes.len() > 0)
+        .map(|(index, frame)| {
+            // Lottie draws the first shape in the list on top, so the shapes are reversed
+            let shapes = frame.shapes.iter().rev().map(|shape| lottie_shape(shape)).collect::<Vec<_>>();
+
+            json!({
+                "ddd":      0,
+                "ind":      index + 1,
+                "ty":       4,
+                "nm":       format!("Frame {}", frame.start_frame),
+                "sr":       1,
+                "ks":       {
+                    "o":    static_value(json!(100)),
+                    "r":    static_value(json!(0)),
+                    "p":    static_value(json!([0, 0, 0])),
+                    "a":    static_value(json!([0, 0, 0])),
+                    "s":    static_value(json!([100, 100, 100]))
+                },
+                "ao":       0,
+                "shapes":   shapes,
+                "ip":       frame.start_frame,
+                "op":       frame.start_frame + frame.num_frames,
+                "st":       0,
+                "bm":       0
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "v":        "5.7.0",
+        "fr":       frame_rate,
+        "ip":       0,
+        "op":       num_frames,
+        "w":        width.round() as i64,
+        "h":        height.round() as i64,
+        "nm":       "FlowBetween animation",
+        "ddd":      0,
+        "assets":   [],
+        "layers":   layers
+    })
+}
+
+///
+/// Converts an animation to a Lottie JSON string, suitable for playing back on the web
+///
+pub async fn animation_to_lottie(animation: &dyn Animation) -> String {
+    animation_to_lottie_json(animation).await.to_string()
+}
@@ -0,0 +1,14 @@
+//!
+//! Exporters that write out whole animations in formats suitable for playback elsewhere (SVG frame sequences,
+//! SMIL-animated SVG and Lottie JSON)
+//!
+
+mod export_shape;
+mod frame_export;
+mod svg_export;
+mod lottie_export;
+
+pub use self::export_shape::*;
+pub use self::frame_export::*;
+pub use self::svg_export::*;
+pub use self::lottie_export::*;
@@ -0,0 +1,191 @@
+use super::export_shape::*;
+use super::frame_export::*;
+use crate::traits::*;
+
+use std::fs;
+use std::io;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+///
+/// Formats a number for an SVG document
+///
+fn num(val: f32) -> String {
+    let val = (val * 1000.0).round() / 1000.0;
+
+    if val == 0.0 {
+        "0".to_string()
+    } else {
+        format!("{}", val)
+    }
+}
+
+///
+/// Formats a paint colour as an SVG colour
+///
+fn svg_color(paint: &ExportPaint) -> String {
+    let (r, g, b)   = paint.rgb;
+    let component   = |val: f32| (val.max(0.0).min(1.0) * 255.0).round() as u8;
+
+    format!("#{:02x}{:02x}{:02x}", component(r), component(g), component(b))
+}
+
+///
+/// Generates the SVG path data for a list of subpaths
+///
+fn svg_path_data(subpaths: &Vec<ExportSubpath>) -> String {
+    let mut data = String::new();
+
+    for subpath in subpaths.iter() {
+        write!(data, "M{} {}", num(subpath.start.0), num(subpath.start.1)).ok();
+
+        for ((cp1, cp2), end) in subpath.curves.iter() {
+            write!(data, " C{} {} {} {} {} {}", num(cp1.0), num(cp1.1), num(cp2.0), num(cp2.1), num(end.0), num(end.1)).ok();
+        }
+
+        if subpath.closed {
+            data.push_str(" Z");
+        }
+
+        data.push(' ');
+    }
+
+    data.trim_end().to_string()
+}
+
+///
+/// Writes out the SVG elements for a list of shapes
+///
+fn svg_shapes(shapes: &Vec<ExportShape>, indent: &str) -> String {
+    let mut svg = String::new();
+
+    for shape in shapes.iter() {
+        let mut attributes = String::new();
+
+        match &shape.fill {
+            Some(fill)  => {
+                write!(attributes, " fill=\"{}\"", svg_color(fill)).ok();
+                if fill.opacity < 1.0   { write!(attributes, " fill-opacity=\"{}\"", num(fill.opacity)).ok(); }
+                if shape.even_odd       { attributes.push_str(" fill-rule=\"evenodd\""); }
+            }
+            None        => { attributes.push_str(" fill=\"none\""); }
+        }
+
+        if let Some((stroke, width)) = &shape.stroke {
+            write!(attributes, " stroke=\"{}\" stroke-width=\"{}\"", svg_color(stroke), num(*width)).ok();
+            if stroke.opacity < 1.0     { write!(attributes, " stroke-opacity=\"{}\"", num(stroke.opacity)).ok(); }
+        }
+
+        writeln!(svg, "{}<path d=\"{}\"{} />", indent, svg_path_data(&shape.subpaths), attributes).ok();
+    }
+
+    svg
+}
+
+///
+/// Returns the opening tag for an SVG document for an animation
+///
+fn svg_header(animation: &dyn Animation) -> String {
+    let (width, height) = animation.size();
+    let (width, height) = (num(width as f32), num(height as f32));
+
+    format!("<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n", width, height, width, height)
+}
+
+///
+/// Generates a static SVG document containing a set of shapes from an animation
+///
+pub fn shapes_to_svg(animation: &dyn Animation, shapes: &Vec<ExportShape>) -> String {
+    let mut svg = svg_header(animation);
+
+    svg.push_str(&svg_shapes(shapes, "    "));
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+///
+/// Generates a SVG document for every frame in an animation
+///
+pub async fn animation_to_svg_frames(animation: &dyn Animation) -> Vec<String> {
+    let frames      = export_frames(animation).await;
+    let mut result  = vec![];
+
+    for frame in frames.iter() {
+        let svg = shapes_to_svg(animation, &frame.shapes);
+
+        for _ in 0..frame.num_frames {
+            result.push(svg.clone());
+        }
+    }
+
+    result
+}
+
+///
+/// Writes out an animation as a numbered sequence of SVG files
+///
+/// The files are written to the specified directory, and are named `<base_name>00000.svg`, `<base_name>00001.svg` and so on.
+/// The return value is the list of files that were written.
+///
+pub async fn export_svg_sequence(animation: &dyn Animation, directory: &Path, base_name: &str) -> io::Result<Vec<PathBuf>> {
+    let frames      = animation_to_svg_frames(animation).await;
+    let mut paths   = vec![];
+
+    fs::create_dir_all(directory)?;
+
+    for (frame_num, svg) in frames.into_iter().enumerate() {
+        let path = directory.join(format!("{}{:05}.svg", base_name, frame_num));
+        fs::write(&path, svg)?;
+
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+///
+/// Generates a single SVG document that uses SMIL animation to play back an animation
+///
+/// Each distinct frame is written as a group that is only displayed while that frame is showing. The animation
+/// loops indefinitely.
+///
+pub async fn animation_to_smil_svg(animation: &dyn Animation) -> String {
+    let frames          = export_frames(animation).await;
+    let num_frames      = animation_frame_count(animation);
+    let frame_length    = animation.frame_length().as_secs_f64();
+    let total_duration  = frame_length * (num_frames as f64);
+
+    let mut svg         = svg_header(animation);
+
+    for frame in frames.iter() {
+        if frame.shapes.len() == 0 { continue; }
+
+        if frame.num_frames >= num_frames {
+            // Frame is displayed for the whole animation
+            svg.push_str("    <g>\n");
+        } else {
+            // Frame is only displayed for part of the animation (this uses 'discrete' animation with key times as fractions of the total time)
+            let start   = (frame.start_frame as f64) / (num_frames as f64);
+            let end     = ((frame.start_frame + frame.num_frames) as f64) / (num_frames as f64);
+
+            let (values, key_times) = if frame.start_frame == 0 {
+                ("inline;none".to_string(), format!("0;{}", num(end as f32)))
+            } else if frame.start_frame + frame.num_frames >= num_frames {
+                ("none;inline".to_string(), format!("0;{}", num(start as f32)))
+            } else {
+                ("none;inline;none".to_string(), format!("0;{};{}", num(start as f32), num(end as f32)))
+            };
+
+            svg.push_str("    <g display=\"none\">\n");
+            writeln!(svg, "        <animate attributeName=\"display\" values=\"{}\" keyTimes=\"{}\" dur=\"{}s\" calcMode=\"discrete\" repeatCount=\"indefinite\" />", values, key_times, num(total_duration as f32)).ok();
+        }
+
+        svg.push_str(&svg_shapes(&frame.shapes, "        "));
+        svg.push_str("    </g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+
+    svg
+}
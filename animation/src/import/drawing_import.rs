@@ -0,0 +1,346 @@
+use super::stroke_outline::*;
+use crate::traits::*;
+
+use flo_canvas::*;
+
+use std::sync::*;
+use std::time::Duration;
+
+///
+/// The drawing state while importing a set of drawing instructions
+///
+#[derive(Clone)]
+struct ImportDrawingState {
+    /// The transform to apply to points in the drawing
+    transform: Transform2D,
+
+    /// The colour to use for fills
+    fill_color: Color,
+
+    /// The colour to use for strokes
+    stroke_color: Color,
+
+    /// The width of a stroke (before it's transformed)
+    line_width: f32
+}
+
+///
+/// Converts a series of drawing instructions into the animation edits required to add the same drawing to a layer
+///
+struct DrawingImporter<AssignId: FnMut() -> ElementId> {
+    /// The layer that's being edited
+    layer_id: u64,
+
+    /// The time where the drawing should be added
+    when: Duration,
+
+    /// Function used to assign IDs to the new elements
+    assign_element_id: AssignId,
+
+    /// The transform that is used when the transform is reset
+    initial_transform: Transform2D,
+
+    /// The current drawing state
+    state: ImportDrawingState,
+
+    /// States that have been pushed, along with the elements that were created within them
+    state_stack: Vec<(ImportDrawingState, Vec<ElementId>)>,
+
+    /// The elements that have been generated in the current state
+    current_elements: Vec<ElementId>,
+
+    /// The path that is currently being defined
+    current_path: Vec<PathComponent>,
+
+    /// The brush properties set for the most recently generated path
+    current_properties: Option<BrushProperties>,
+
+    /// True if the brush has been selected
+    brush_selected: bool,
+
+    /// The edits that have been generated
+    edits: Vec<AnimationEdit>
+}
+
+impl<AssignId: FnMut() -> ElementId> DrawingImporter<AssignId> {
+    ///
+    /// Creates a new drawing importer
+    ///
+    fn new(layer_id: u64, when: Duration, initial_transform: Transform2D, assign_element_id: AssignId) -> DrawingImporter<AssignId> {
+        DrawingImporter {
+            layer_id:           layer_id,
+            when:               when,
+            assign_element_id:  assign_element_id,
+            initial_transform:  initial_transform,
+            state:              ImportDrawingState {
+                transform:      initial_transform,
+                fill_color:     Color::Rgba(0.0, 0.0, 0.0, 1.0),
+                stroke_color:   Color::Rgba(0.0, 0.0, 0.0, 1.0),
+                line_width:     1.0
+            },
+            state_stack:        vec![],
+            current_elements:   vec![],
+            current_path:       vec![],
+            current_properties: None,
+            brush_selected:     false,
+            edits:              vec![]
+        }
+    }
+
+    ///
+    /// Returns the point at a particular position in the drawing after it's been transformed
+    ///
+    fn point(&self, x: f32, y: f32) -> PathPoint {
+        let (x, y) = self.state.transform.transform_point(x, y);
+        PathPoint::new(x, y)
+    }
+
+    ///
+    /// Returns the amount that the current transform scales lengths by
+    ///
+    fn transform_scale(&self) -> f64 {
+        let (x0, y0)    = self.state.transform.transform_point(0.0, 0.0);
+        let (x1, y1)    = self.state.transform.transform_point(1.0, 0.0);
+        let (x2, y2)    = self.state.transform.transform_point(0.0, 1.0);
+
+        // The square root of the area of a transformed unit square
+        let area        = ((x1-x0) as f64)*((y2-y0) as f64) - ((y1-y0) as f64)*((x2-x0) as f64);
+        area.abs().sqrt()
+    }
+
+    ///
+    /// Adds a path edit to the list of edits
+    ///
+    fn push_path_edit(&mut self, edit: PathEdit) {
+        self.edits.push(AnimationEdit::Layer(self.layer_id, LayerEdit::Path(self.when, edit)));
+    }
+
+    ///
+    /// Adds the edits that create a new path element with the specified colour and returns its ID
+    ///
+    fn create_path(&mut self, path: Vec<PathComponent>, color: Color) {
+        // Nothing to do for empty paths or invisible colours
+        let (r, g, b, alpha) = color.to_rgba_components();
+        if path.len() == 0 || alpha <= 0.0 {
+            return;
+        }
+
+        // Paths are drawn with the ink brush, which fills them
+        if !self.brush_selected {
+            let brush_id = (self.assign_element_id)();
+            self.push_path_edit(PathEdit::SelectBrush(brush_id, BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw));
+            self.brush_selected = true;
+        }
+
+        // Update the brush properties if the colour has changed
+        let properties = BrushProperties {
+            color:      Color::Rgba(r, g, b, 1.0),
+            opacity:    alpha,
+            ..BrushProperties::new()
+        };
+
+        if self.current_properties != Some(properties) {
+            let properties_id = (self.assign_element_id)();
+            self.push_path_edit(PathEdit::BrushProperties(properties_id, properties));
+            self.current_properties = Some(properties);
+        }
+
+        // Create the path
+        let path_id = (self.assign_element_id)();
+        self.push_path_edit(PathEdit::CreatePath(path_id, Arc::new(path)));
+        self.current_elements.push(path_id);
+    }
+
+    ///
+    /// Fills the current path
+    ///
+    fn fill(&mut self) {
+        let path        = self.current_path.clone();
+        let fill_color  = self.state.fill_color;
+
+        self.create_path(path, fill_color);
+    }
+
+    ///
+    /// Strokes the current path
+    ///
+    fn stroke(&mut self) {
+        let width           = (self.state.line_width as f64) * self.transform_scale();
+        let stroke_color    = self.state.stroke_color;
+
+        if width > 0.0 {
+            let outline = stroke_outline(&self.current_path, width);
+            self.create_path(outline, stroke_color);
+        }
+    }
+
+    ///
+    /// Saves the current state on the stack
+    ///
+    fn push_state(&mut self) {
+        let elements = std::mem::take(&mut self.current_elements);
+        self.state_stack.push((self.state.clone(), elements));
+    }
+
+    ///
+    /// Restores the state from the stack, grouping any elements that were created since the state was pushed
+    ///
+    fn pop_state(&mut self) {
+        if let Some((state, parent_elements)) = self.state_stack.pop() {
+            let elements            = std::mem::replace(&mut self.current_elements, parent_elements);
+            self.state              = state;
+
+            match elements.len() {
+                0 => { }
+                1 => { self.current_elements.extend(elements); }
+                _ => {
+                    // Elements created in the same state are grouped together
+                    let group_id = (self.assign_element_id)();
+                    self.edits.push(AnimationEdit::Element(elements, ElementEdit::Group(group_id, GroupType::Normal)));
+                    self.current_elements.push(group_id);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Processes a single drawing instruction
+    ///
+    fn draw(&mut self, draw: Draw) {
+        use self::Draw::*;
+
+        match draw {
+            Path(PathOp::NewPath)                       => { self.current_path = vec![]; }
+            Path(PathOp::Move(x, y))                    => { let point = self.point(x, y); self.current_path.push(PathComponent::Move(point)); }
+            Path(PathOp::Line(x, y))                    => { let point = self.point(x, y); self.current_path.push(PathComponent::Line(point)); }
+            Path(PathOp::BezierCurve(((c1x, c1y), (c2x, c2y)), (x, y))) => {
+                let cp1     = self.point(c1x, c1y);
+                let cp2     = self.point(c2x, c2y);
+                let point   = self.point(x, y);
+
+                self.current_path.push(PathComponent::Bezier(point, cp1, cp2));
+            }
+            Path(PathOp::ClosePath)                     => { self.current_path.push(PathComponent::Close); }
+
+            Fill                                        => { self.fill(); }
+            Stroke                                      => { self.stroke(); }
+
+            FillColor(color)                            => { self.state.fill_color = color; }
+            StrokeColor(color)                          => { self.state.stroke_color = color; }
+            LineWidth(width)                            => { self.state.line_width = width; }
+
+            IdentityTransform                           => { self.state.transform = self.initial_transform; }
+            MultiplyTransform(transform)                => { self.state.transform = self.state.transform * transform; }
+
+            PushState                                   => { self.push_state(); }
+            PopState                                    => { self.pop_state(); }
+
+            // Other instructions have no equivalent in an animation layer
+            _                                           => { }
+        }
+    }
+
+    ///
+    /// Finishes the import, returning the edits that were generated
+    ///
+    fn finish(mut self) -> Vec<AnimationEdit> {
+        // Pop any states that were left on the stack
+        while self.state_stack.len() > 0 {
+            self.pop_state();
+        }
+
+        self.edits
+    }
+}
+
+///
+/// Converts a series of drawing instructions into a set of edits that will add the same drawing to the specified layer and keyframe
+///
+/// Filled paths are converted to path elements, and stroked paths are converted to path elements representing the outline of
+/// the stroke. Elements that are drawn between a `PushState` and a `PopState` are grouped together. The transform is applied
+/// to the drawing instructions before they're converted (use this to map the coordinate scheme of the drawing to the coordinates
+/// used in the animation). New element IDs are generated by calling `assign_element_id()`: this is usually done by calling the
+/// function of the same name on an `EditableAnimation`.
+///
+pub fn drawing_to_edits<DrawIter, AssignId>(drawing: DrawIter, layer_id: u64, when: Duration, transform: Transform2D, assign_element_id: AssignId) -> Vec<AnimationEdit>
+where
+DrawIter: IntoIterator<Item=Draw>,
+AssignId: FnMut() -> ElementId {
+    let mut importer = DrawingImporter::new(layer_id, when, transform, assign_element_id);
+
+    for draw in drawing {
+        importer.draw(draw);
+    }
+
+    importer.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fill_path() {
+        let mut next_id = 0;
+        let edits       = drawing_to_edits(vec![
+            Draw::Path(PathOp::NewPath),
+            Draw::Path(PathOp::Move(10.0, 10.0)),
+            Draw::Path(PathOp::Line(20.0, 10.0)),
+            Draw::Path(PathOp::Line(20.0, 20.0)),
+            Draw::Path(PathOp::ClosePath),
+            Draw::FillColor(Color::Rgba(1.0, 0.0, 0.0, 0.5)),
+            Draw::Fill
+        ], 1, Duration::from_millis(0), Transform2D::identity(), || { next_id += 1; ElementId::Assigned(next_id) });
+
+        assert!(edits.len() == 3);
+        assert!(edits[0] == AnimationEdit::Layer(1, LayerEdit::Path(Duration::from_millis(0), PathEdit::SelectBrush(ElementId::Assigned(1), BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw))));
+        assert!(edits[1] == AnimationEdit::Layer(1, LayerEdit::Path(Duration::from_millis(0), PathEdit::BrushProperties(ElementId::Assigned(2), BrushProperties { color: Color::Rgba(1.0, 0.0, 0.0, 1.0), opacity: 0.5, ..BrushProperties::new() }))));
+        assert!(edits[2] == AnimationEdit::Layer(1, LayerEdit::Path(Duration::from_millis(0), PathEdit::CreatePath(ElementId::Assigned(3), Arc::new(vec![
+            PathComponent::Move(PathPoint::new(10.0, 10.0)),
+            PathComponent::Line(PathPoint::new(20.0, 10.0)),
+            PathComponent::Line(PathPoint::new(20.0, 20.0)),
+            PathComponent::Close
+        ])))));
+    }
+
+    #[test]
+    fn group_in_state() {
+        let mut next_id = 0;
+        let edits       = drawing_to_edits(vec![
+            Draw::PushState,
+            Draw::Path(PathOp::NewPath),
+            Draw::Path(PathOp::Move(10.0, 10.0)),
+            Draw::Path(PathOp::Line(20.0, 10.0)),
+            Draw::Path(PathOp::Line(20.0, 20.0)),
+            Draw::Path(PathOp::ClosePath),
+            Draw::Fill,
+            Draw::LineWidth(2.0),
+            Draw::Stroke,
+            Draw::PopState
+        ], 1, Duration::from_millis(0), Transform2D::identity(), || { next_id += 1; ElementId::Assigned(next_id) });
+
+        // Fill and stroke paths should be grouped together
+        assert!(edits.last() == Some(&AnimationEdit::Element(vec![ElementId::Assigned(3), ElementId::Assigned(4)], ElementEdit::Group(ElementId::Assigned(5), GroupType::Normal))));
+    }
+
+    #[test]
+    fn transform_path() {
+        let mut next_id = 0;
+        let edits       = drawing_to_edits(vec![
+            Draw::MultiplyTransform(Transform2D::translate(5.0, 6.0)),
+            Draw::Path(PathOp::NewPath),
+            Draw::Path(PathOp::Move(10.0, 10.0)),
+            Draw::Path(PathOp::Line(20.0, 10.0)),
+            Draw::Path(PathOp::Line(20.0, 20.0)),
+            Draw::Fill,
+        ], 1, Duration::from_millis(0), Transform2D::identity(), || { next_id += 1; ElementId::Assigned(next_id) });
+
+        match &edits[2] {
+            AnimationEdit::Layer(1, LayerEdit::Path(_, PathEdit::CreatePath(_, path))) => {
+                assert!(path[0] == PathComponent::Move(PathPoint::new(15.0, 16.0)));
+            }
+
+            _ => { assert!(false, "Expected a path"); }
+        }
+    }
+}
@@ -0,0 +1,8 @@
+///
+/// Errors that can occur while importing a file into an animation
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportError {
+    /// The file could not be parsed
+    CouldNotParse(String)
+}
@@ -0,0 +1,12 @@
+//!
+//! Importers that convert artwork from other formats into the edits needed to add it to an animation
+//!
+
+mod import_error;
+mod stroke_outline;
+mod drawing_import;
+mod svg_import;
+
+pub use self::import_error::*;
+pub use self::drawing_import::*;
+pub use self::svg_import::*;
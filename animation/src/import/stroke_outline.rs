@@ -0,0 +1,220 @@
+use crate::traits::*;
+
+use flo_curves::*;
+use flo_curves::bezier;
+
+///
+/// A subpath that's being stroked
+///
+struct StrokeSubpath {
+    /// The curves making up this subpath
+    curves: Vec<bezier::Curve<Coord2>>,
+
+    /// True if this subpath was closed
+    closed: bool
+}
+
+///
+/// Converts a path point to a Coord2
+///
+#[inline] fn to_coord(point: &PathPoint) -> Coord2 {
+    Coord2(point.x() as f64, point.y() as f64)
+}
+
+///
+/// Converts a Coord2 to a path point
+///
+#[inline] fn to_path_point(coord: Coord2) -> PathPoint {
+    PathPoint::new(coord.x() as f32, coord.y() as f32)
+}
+
+///
+/// Generates a curve representing a straight line
+///
+fn line_curve(start: Coord2, end: Coord2) -> bezier::Curve<Coord2> {
+    let cp1 = start + (end-start) * (1.0/3.0);
+    let cp2 = start + (end-start) * (2.0/3.0);
+
+    bezier::Curve::from_points(start, (cp1, cp2), end)
+}
+
+///
+/// Splits a path up into subpaths made up of bezier curves
+///
+fn stroke_subpaths(path: &[PathComponent]) -> Vec<StrokeSubpath> {
+    let mut subpaths        = vec![];
+    let mut current         = vec![];
+    let mut start_point     = Coord2(0.0, 0.0);
+    let mut last_point      = Coord2(0.0, 0.0);
+
+    for component in path.iter() {
+        match component {
+            PathComponent::Move(point)              => {
+                if current.len() > 0 {
+                    subpaths.push(StrokeSubpath { curves: current, closed: false });
+                    current = vec![];
+                }
+
+                start_point = to_coord(point);
+                last_point  = start_point;
+            }
+
+            PathComponent::Line(point)              => {
+                let end_point = to_coord(point);
+                if end_point != last_point {
+                    current.push(line_curve(last_point, end_point));
+                }
+                last_point = end_point;
+            }
+
+            PathComponent::Bezier(point, cp1, cp2)  => {
+                let end_point = to_coord(point);
+                let (cp1, cp2) = (to_coord(cp1), to_coord(cp2));
+
+                // Degenerate curves have no direction so can't be offset
+                if end_point != last_point || cp1 != last_point || cp2 != last_point {
+                    current.push(bezier::Curve::from_points(last_point, (cp1, cp2), end_point));
+                }
+                last_point = end_point;
+            }
+
+            PathComponent::Close                    => {
+                if last_point != start_point {
+                    current.push(line_curve(last_point, start_point));
+                }
+
+                if current.len() > 0 {
+                    subpaths.push(StrokeSubpath { curves: current, closed: true });
+                    current = vec![];
+                }
+
+                last_point = start_point;
+            }
+        }
+    }
+
+    if current.len() > 0 {
+        subpaths.push(StrokeSubpath { curves: current, closed: false });
+    }
+
+    subpaths
+}
+
+///
+/// Offsets a list of curves by a particular distance
+///
+fn offset_curves(curves: &Vec<bezier::Curve<Coord2>>, distance: f64) -> Vec<bezier::Curve<Coord2>> {
+    curves.iter()
+        .flat_map(|curve| bezier::offset_lms_sampling(curve, |_| distance, |_| 0.0, 20, 1.0).unwrap_or_else(|| vec![]))
+        .collect()
+}
+
+///
+/// Appends a set of curves to a path, joining them with straight lines where they don't meet
+///
+fn append_curves(path: &mut Vec<PathComponent>, curves: &Vec<bezier::Curve<Coord2>>) {
+    let mut last_point = None;
+
+    for curve in curves.iter() {
+        let start_point = curve.start_point();
+
+        match last_point {
+            None                                        => { path.push(PathComponent::Move(to_path_point(start_point))); }
+            Some(last_point) if last_point != start_point   => { path.push(PathComponent::Line(to_path_point(start_point))); }
+            _                                           => { }
+        }
+
+        let (cp1, cp2)  = curve.control_points();
+        let end_point   = curve.end_point();
+        path.push(PathComponent::Bezier(to_path_point(end_point), to_path_point(cp1), to_path_point(cp2)));
+
+        last_point      = Some(end_point);
+    }
+}
+
+///
+/// Generates a path that represents the outline of a stroke of a particular width along a path
+///
+/// Caps are squared off at the ends of the path, and corners are bevelled.
+///
+pub (super) fn stroke_outline(path: &[PathComponent], width: f64) -> Vec<PathComponent> {
+    let half_width  = width / 2.0;
+    let mut outline = vec![];
+
+    for subpath in stroke_subpaths(path) {
+        // Generate the two sides of the stroke
+        let upper = offset_curves(&subpath.curves, half_width);
+        let lower = offset_curves(&subpath.curves, -half_width);
+
+        if upper.len() == 0 || lower.len() == 0 {
+            continue;
+        }
+
+        // The lower side is traversed in reverse
+        let lower = lower.into_iter()
+            .rev()
+            .map(|curve| curve.reverse::<bezier::Curve<_>>())
+            .collect::<Vec<_>>();
+
+        if subpath.closed {
+            // Closed paths are outlined by two loops going in opposite directions
+            append_curves(&mut outline, &upper);
+            outline.push(PathComponent::Close);
+            append_curves(&mut outline, &lower);
+            outline.push(PathComponent::Close);
+        } else {
+            // Open paths go along one side and back along the other
+            let mut side = vec![];
+            append_curves(&mut side, &upper);
+
+            let lower_start = lower[0].start_point();
+            side.push(PathComponent::Line(to_path_point(lower_start)));
+
+            let mut lower_side = vec![];
+            append_curves(&mut lower_side, &lower);
+            side.extend(lower_side.into_iter().skip(1));
+            side.push(PathComponent::Close);
+
+            outline.extend(side);
+        }
+    }
+
+    outline
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stroke_straight_line() {
+        let line    = vec![PathComponent::Move(PathPoint::new(0.0, 0.0)), PathComponent::Line(PathPoint::new(100.0, 0.0))];
+        let outline = stroke_outline(&line, 10.0);
+        let bounds  = Rect::from(Path::from_elements(outline.clone()));
+
+        assert!(outline.len() > 0);
+        assert!((bounds.x1 - 0.0).abs() < 0.1);
+        assert!((bounds.x2 - 100.0).abs() < 0.1);
+        assert!((bounds.y1 - -5.0).abs() < 0.1);
+        assert!((bounds.y2 - 5.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn stroke_closed_square() {
+        let square  = vec![
+            PathComponent::Move(PathPoint::new(0.0, 0.0)),
+            PathComponent::Line(PathPoint::new(100.0, 0.0)),
+            PathComponent::Line(PathPoint::new(100.0, 100.0)),
+            PathComponent::Line(PathPoint::new(0.0, 100.0)),
+            PathComponent::Close
+        ];
+        let outline = stroke_outline(&square, 10.0);
+
+        // Two loops, for the inside and outside of the stroke
+        assert!(outline.iter().filter(|component| component == &&PathComponent::Close).count() == 2);
+
+        let bounds  = Rect::from(Path::from_elements(outline));
+        assert!((bounds.x1 - -5.0).abs() < 0.1);
+        assert!((bounds.x2 - 105.0).abs() < 0.1);
+    }
+}
@@ -0,0 +1,114 @@
+use super::import_error::*;
+use super::drawing_import::*;
+use crate::traits::*;
+
+use flo_canvas::*;
+use flo_svg::*;
+
+use std::time::Duration;
+
+///
+/// Converts an SVG document into a set of edits that will add its contents to the specified layer and keyframe
+///
+/// Paths and shapes are converted to path elements using their fill colour, and strokes are converted into path
+/// elements representing their outline. SVG groups become group elements. SVG documents use a coordinate scheme where
+/// the y axis points down, so the document is flipped so that the top of its view box is at `canvas_height` in the
+/// animation (usually the height of the animation, which will place the top-left corner of the SVG document in the
+/// top-left corner of the animation).
+///
+/// New element IDs are generated by calling `assign_element_id()`. The resulting edits can be sent to an animation using
+/// `EditableAnimation::perform_edits()`:
+///
+/// ```ignore
+/// let edits = svg_to_edits(&svg, layer_id, when, animation.size().1, || animation.assign_element_id())?;
+/// animation.perform_edits(edits);
+/// ```
+///
+pub fn svg_to_edits<AssignId: FnMut() -> ElementId>(svg: &str, layer_id: u64, when: Duration, canvas_height: f64, assign_element_id: AssignId) -> Result<Vec<AnimationEdit>, ImportError> {
+    // Use flo_svg to convert the document into a set of drawing instructions
+    let mut drawing = vec![];
+    let document    = parse_svg(svg, &mut drawing).map_err(|err| ImportError::CouldNotParse(format!("{:?}", err)))?;
+
+    // The top-left of the view box is moved to the top-left of the canvas, and the y axis is flipped
+    let (min_x, min_y)  = document.viewbox().map(|(min, _max)| min).unwrap_or((0.0, 0.0));
+    let flip_y          = Transform2D::translate(0.0, canvas_height as f32) * Transform2D::scale(1.0, -1.0);
+    let transform       = flip_y * Transform2D::translate(-min_x, -min_y);
+
+    // Convert the drawing instructions to edits
+    Ok(drawing_to_edits(drawing, layer_id, when, transform, assign_element_id))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn import_rectangle() {
+        let svg = "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 100\"><rect x=\"10\" y=\"20\" width=\"30\" height=\"40\" fill=\"#ff0000\" /></svg>";
+
+        let mut next_id = 0;
+        let edits       = svg_to_edits(svg, 1, Duration::from_millis(0), 100.0, || { next_id += 1; ElementId::Assigned(next_id) }).unwrap();
+
+        // Should create a single path
+        let paths       = edits.iter()
+            .filter_map(|edit| match edit {
+                AnimationEdit::Layer(1, LayerEdit::Path(_, PathEdit::CreatePath(_, path)))  => Some(path.clone()),
+                _                                                                           => None
+            })
+            .collect::<Vec<_>>();
+        assert!(paths.len() == 1);
+
+        // Path should be flipped so the top of the rectangle is at 80 and the bottom is at 40
+        let bounds      = Rect::from(Path::from_elements_arc(paths[0].clone()));
+        assert!((bounds.x1 - 10.0).abs() < 0.1);
+        assert!((bounds.x2 - 40.0).abs() < 0.1);
+        assert!((bounds.y1 - 40.0).abs() < 0.1);
+        assert!((bounds.y2 - 80.0).abs() < 0.1);
+
+        // Brush should be red
+        assert!(edits.iter().any(|edit| match edit {
+            AnimationEdit::Layer(1, LayerEdit::Path(_, PathEdit::BrushProperties(_, properties)))   => properties.color == Color::Rgba(1.0, 0.0, 0.0, 1.0),
+            _                                                                                       => false
+        }));
+    }
+
+    #[test]
+    fn import_group() {
+        let svg = "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 100\"><g><circle cx=\"20\" cy=\"20\" r=\"10\" /><circle cx=\"60\" cy=\"20\" r=\"10\" /></g></svg>";
+
+        let mut next_id = 0;
+        let edits       = svg_to_edits(svg, 1, Duration::from_millis(0), 100.0, || { next_id += 1; ElementId::Assigned(next_id) }).unwrap();
+
+        // The two circles should be grouped
+        assert!(edits.iter().any(|edit| match edit {
+            AnimationEdit::Element(elements, ElementEdit::Group(_, GroupType::Normal))  => elements.len() == 2,
+            _                                                                           => false
+        }));
+    }
+
+    #[test]
+    fn import_into_animation() {
+        use crate::storage::*;
+        use crate::editor::*;
+        use futures::prelude::*;
+
+        let in_memory_store = InMemoryStorage::new();
+        let animation       = create_animation_editor(move |commands| in_memory_store.get_responses(commands).boxed());
+
+        animation.perform_edits(vec![
+            AnimationEdit::AddNewLayer(1),
+            AnimationEdit::Layer(1, LayerEdit::AddKeyFrame(Duration::from_millis(0)))
+        ]);
+
+        let svg     = "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 100\"><path d=\"M 10 10 L 90 10 L 90 90 Z\" fill=\"blue\" stroke=\"black\" stroke-width=\"4\" /></svg>";
+        let edits   = svg_to_edits(svg, 1, Duration::from_millis(0), 1080.0, || animation.assign_element_id()).unwrap();
+        animation.perform_edits(edits);
+
+        // Should generate path elements in the frame
+        let layer       = animation.get_layer_with_id(1).unwrap();
+        let frame       = layer.get_frame_at_time(Duration::from_millis(0));
+        let elements    = frame.vector_elements().unwrap().collect::<Vec<_>>();
+
+        assert!(elements.len() > 0);
+    }
+}
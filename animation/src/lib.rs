@@ -32,6 +32,8 @@ pub mod serializer;
 pub mod storage;
 pub mod editor;
 pub mod undo;
+pub mod import;
+pub mod export;
 
 pub use self::traits::*;
 pub use self::onion_skin::*;
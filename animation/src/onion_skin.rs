@@ -47,7 +47,13 @@ pub fn onion_skin_for_layer(layer: Arc<dyn Layer>, when: Duration) -> CacheProce
                     if element_path.len() > 0 && (element_path.len() != 1 || element_path[0].len() > 0) {
                         let element_path = element_path.into_iter().flat_map(|path| path.to_subpaths()).collect();
 
-                        match (*properties).brush.drawing_style() {
+                        // Masked groups render their elements with their own properties, so always add to the onion skin
+                        let drawing_style = match &element {
+                            Vector::Group(group) if group.group_type().is_mask()    => BrushDrawingStyle::Draw,
+                            _                                                       => (*properties).brush.drawing_style()
+                        };
+
+                        match drawing_style {
                             BrushDrawingStyle::Draw     => { waiting_to_add.push(element_path); }
                             BrushDrawingStyle::Erase    => {
                                 if waiting_to_add.len() > 0 {
@@ -43,13 +43,15 @@ impl RaycastEdge {
             Vector::Motion(_motion)             => { Box::new(iter::empty()) }
             Vector::Transformation(_transform)  => { Box::new(iter::empty()) }
             Vector::AnimationRegion(_region)    => { Box::new(iter::empty()) }
+            Vector::Bone(_bone)                 => { Box::new(iter::empty()) }
+            Vector::Tween(_tween)               => { Box::new(iter::empty()) }
             Vector::Error                       => { Box::new(iter::empty()) }
 
             Vector::Transformed(transform)      => { Self::from_transformed(transform, properties) }
             Vector::BrushStroke(brush_stroke)   => { Self::from_brush_stroke(brush_stroke, properties) }
             Vector::Shape(shape)                => { Self::from_shape(shape, properties) }
             Vector::Path(path)                  => { Box::new(Self::from_path_element(path, properties)) }
-            Vector::Group(group_element)        => { Box::new(Self::from_group(group_element, properties)) }
+            Vector::Group(group_element)        => { Self::from_group(group_element, properties) }
         }
     }
 
@@ -70,15 +72,48 @@ impl RaycastEdge {
     ///
     /// Retrieves the edges corresponding to a group element
     ///
-    pub fn from_group<'a>(group: &'a GroupElement, properties: Arc<VectorProperties>) -> impl 'a+Iterator<Item=Self> {
+    pub fn from_group<'a>(group: &'a GroupElement, properties: Arc<VectorProperties>) -> Box<dyn 'a+Iterator<Item=Self>> {
         let element_id = group.id();
 
-        group.elements()
-            .flat_map(move |element| Self::from_vector(element, properties.clone()))
-            .map(move |mut element| {
-                element.element_id = element_id;
-                element
-            })
+        match group.group_type() {
+            GroupType::Normal       |
+            GroupType::Added        => {
+                // The edges are the edges of the elements within the group
+                Box::new(group.elements()
+                    .flat_map(move |element| Self::from_vector(element, properties.clone()))
+                    .map(move |mut element| {
+                        element.element_id = element_id;
+                        element
+                    }))
+            }
+
+            GroupType::Subtracted   => {
+                // The edges are from the path generated by the group, which uses the properties of the group
+                let edge_kind = match properties.brush.drawing_style() {
+                    BrushDrawingStyle::Erase    => RaycastEdgeKind::EraseContents,
+                    BrushDrawingStyle::Draw     => RaycastEdgeKind::Solid
+                };
+
+                Self::from_group_path(group, properties, edge_kind)
+            }
+
+            GroupType::Masked       |
+            GroupType::InvertedMask => {
+                // Only the visible part of a masked group generates edges
+                Self::from_group_path(group, properties, RaycastEdgeKind::Solid)
+            }
+        }
+    }
+
+    ///
+    /// Retrieves the edges corresponding to the combined path generated by a group element
+    ///
+    fn from_group_path<'a>(group: &'a GroupElement, properties: Arc<VectorProperties>, edge_kind: RaycastEdgeKind) -> Box<dyn 'a+Iterator<Item=Self>> {
+        let element_id  = group.id();
+        let paths       = group.to_path(&*properties, PathConversion::Fastest).unwrap_or_else(|| vec![]);
+
+        Box::new(paths.into_iter()
+            .flat_map(move |path| Self::from_path(element_id, &path, edge_kind).collect::<Vec<_>>()))
     }
 
     ///
@@ -0,0 +1,258 @@
+use super::target::*;
+
+///
+/// Marks a character that isn't in the ASCII range: it's followed by its UTF-8 encoding
+///
+const UTF8_CHAR: u8 = 0x80;
+
+///
+/// The shortest run of 6-bit characters that is worth packing (shorter runs are the same size or smaller as ASCII)
+///
+const MIN_PACKED_RUN: usize = 8;
+
+///
+/// The longest run of 6-bit characters that can be packed after a single marker byte
+///
+const MAX_PACKED_RUN: usize = 0x7f;
+
+///
+/// Returns the 6-bit value for a character if it's in the encoding character set
+///
+fn encoding_value(c: char) -> Option<u8> {
+    if c >= 'A' && c <= 'Z' {
+        Some((c as u8) - ('A' as u8))
+    } else if c >= 'a' && c <= 'z' {
+        Some((c as u8) - ('a' as u8) + 26)
+    } else if c >= '0' && c <= '9' {
+        Some((c as u8) - ('0' as u8) + 52)
+    } else if c == '+' {
+        Some(62)
+    } else if c == '/' {
+        Some(63)
+    } else {
+        None
+    }
+}
+
+///
+/// Writes a run of 6-bit values as a marker byte followed by the packed bits
+///
+fn write_packed_run(target: &mut Vec<u8>, run: &[u8]) {
+    target.push(0x80 | (run.len() as u8));
+
+    let mut bits        = 0u32;
+    let mut num_bits    = 0;
+
+    for value in run {
+        bits        |= (*value as u32) << num_bits;
+        num_bits    += 6;
+
+        while num_bits >= 8 {
+            target.push((bits & 0xff) as u8);
+            bits        >>= 8;
+            num_bits    -= 8;
+        }
+    }
+
+    if num_bits > 0 {
+        target.push((bits & 0xff) as u8);
+    }
+}
+
+///
+/// Writes a run of 6-bit values, packing them if it's worth it
+///
+fn write_run(target: &mut Vec<u8>, run: &[u8]) {
+    for chunk in run.chunks(MAX_PACKED_RUN) {
+        if chunk.len() >= MIN_PACKED_RUN {
+            write_packed_run(target, chunk);
+        } else {
+            target.extend(chunk.iter().map(|value| ENCODING_CHAR_SET[*value as usize] as u8));
+        }
+    }
+}
+
+///
+/// Converts serialized animation data (as generated by an `AnimationDataTarget`) to a compact binary form
+///
+/// Serialized data mostly consists of characters from a 64 character set, which each only represent 6 bits of data. The binary form
+/// stores runs of these characters 4 to every 3 bytes, so any raw data (such as the delta-encoded floats generated by
+/// `write_next_f64()`) takes no more space than it would as bytes. Other characters are stored as-is.
+///
+/// Floats written by `write_next_f64()` are already squished by `flo_float_encoder`, so this stores its output directly (along
+/// with the padding bits that round each value up to a whole number of characters). Working on the serialized form rather
+/// than the values means that storage doesn't need to know the type of the data to convert it.
+///
+pub fn encode_compact_bytes(serialized: &str) -> Vec<u8> {
+    let mut result  = Vec::with_capacity(serialized.len());
+    let mut run     = vec![];
+
+    for c in serialized.chars() {
+        if let Some(value) = encoding_value(c) {
+            // Add to the current run of 6-bit characters
+            run.push(value);
+        } else {
+            // Finish the current run and write this character on its own
+            write_run(&mut result, &run);
+            run.clear();
+
+            if (c as u32) < 0x80 {
+                result.push(c as u8);
+            } else {
+                let mut utf8 = [0u8; 4];
+
+                result.push(UTF8_CHAR);
+                result.extend(c.encode_utf8(&mut utf8).as_bytes());
+            }
+        }
+    }
+
+    write_run(&mut result, &run);
+
+    result
+}
+
+///
+/// Converts data generated by `encode_compact_bytes` back to its serialized form
+///
+/// Truncated data is decoded as far as possible (which will generally produce a serialized value that fails to deserialize)
+///
+pub fn decode_compact_bytes(bytes: &[u8]) -> String {
+    let mut result  = String::with_capacity(bytes.len() + bytes.len()/3);
+    let mut pos     = 0;
+
+    while pos < bytes.len() {
+        let marker = bytes[pos];
+        pos += 1;
+
+        if marker < 0x80 {
+            // ASCII character
+            result.push(marker as char);
+        } else if marker == UTF8_CHAR {
+            // UTF-8 character
+            let len = match bytes.get(pos) {
+                Some(lead) if *lead < 0xe0  => 2,
+                Some(lead) if *lead < 0xf0  => 3,
+                Some(_)                     => 4,
+                None                        => { break; }
+            };
+            let end = (pos+len).min(bytes.len());
+
+            result.push_str(&String::from_utf8_lossy(&bytes[pos..end]));
+            pos = end;
+        } else {
+            // Run of packed 6-bit characters
+            let run_length      = (marker & 0x7f) as usize;
+            let mut bits        = 0u32;
+            let mut num_bits    = 0;
+
+            for _ in 0..run_length {
+                if num_bits < 6 {
+                    let next_byte = if let Some(next_byte) = bytes.get(pos) { *next_byte } else { return result; };
+                    pos += 1;
+
+                    bits        |= (next_byte as u32) << num_bits;
+                    num_bits    += 8;
+                }
+
+                result.push(ENCODING_CHAR_SET[(bits & 0x3f) as usize]);
+                bits        >>= 6;
+                num_bits    -= 6;
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::traits::*;
+
+    use std::sync::*;
+    use std::time::{Duration};
+
+    #[test]
+    fn round_trip_short_string() {
+        let encoded = encode_compact_bytes("Lt");
+        assert!(encoded == vec!['L' as u8, 't' as u8]);
+        assert!(decode_compact_bytes(&encoded) == "Lt".to_string());
+    }
+
+    #[test]
+    fn round_trip_long_run() {
+        let serialized  = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let encoded     = encode_compact_bytes(serialized);
+
+        assert!(encoded.len() == 1 + 48);
+        assert!(decode_compact_bytes(&encoded) == serialized.to_string());
+    }
+
+    #[test]
+    fn round_trip_very_long_run() {
+        let serialized  = (0..1000).map(|idx| ENCODING_CHAR_SET[(idx*7)%64]).collect::<String>();
+        let encoded     = encode_compact_bytes(&serialized);
+
+        assert!(encoded.len() < serialized.len()*4/5);
+        assert!(decode_compact_bytes(&encoded) == serialized);
+    }
+
+    #[test]
+    fn round_trip_other_characters() {
+        let serialized  = "ABCDEFGHIJ-KLMNOPQRSTU\n; - Title with ünïcode 🎞 - \nABCDEFGHIJ";
+        let encoded     = encode_compact_bytes(serialized);
+
+        assert!(decode_compact_bytes(&encoded) == serialized.to_string());
+    }
+
+    #[test]
+    fn round_trip_edit() {
+        let edit            = AnimationEdit::Layer(2, LayerEdit::Paint(Duration::from_millis(442), PaintEdit::BrushStroke(ElementId::Assigned(126), Arc::new(vec![
+            RawPoint::from((10.0, 10.0)),
+            RawPoint::from((20.0, 5.0)),
+            RawPoint::from((25.0, 7.5)),
+            RawPoint::from((30.0, 12.0))
+        ]))));
+        let mut serialized  = String::new();
+        edit.serialize(&mut serialized);
+
+        let encoded         = encode_compact_bytes(&serialized);
+        let decoded         = decode_compact_bytes(&encoded);
+
+        assert!(encoded.len() < serialized.len());
+        assert!(decoded == serialized);
+        assert!(AnimationEdit::deserialize(&mut decoded.chars()).is_some());
+    }
+
+    #[test]
+    fn squished_floats_are_stored_as_bytes() {
+        let mut serialized  = String::new();
+        let mut squished    = vec![];
+        let mut last        = 0.0;
+
+        for idx in 0..200 {
+            let next = 500.0 + (idx as f64)*1.5;
+
+            serialized.write_next_f64(last, next);
+            flo_float_encoder::squish_float(&mut squished, last, next).unwrap();
+            last = next;
+        }
+
+        // Only the padding bits and the run markers are added to what flo_float_encoder generates
+        let encoded         = encode_compact_bytes(&serialized);
+
+        assert!(encoded.len() < squished.len()*6/5);
+        assert!(decode_compact_bytes(&encoded) == serialized);
+    }
+
+    #[test]
+    fn truncated_data_does_not_panic() {
+        let serialized  = "ABCDEFGHIJKLMNOPQRSTUVWXYZ-\u{00e9}";
+        let encoded     = encode_compact_bytes(serialized);
+
+        for len in 0..encoded.len() {
+            decode_compact_bytes(&encoded[0..len]);
+        }
+    }
+}
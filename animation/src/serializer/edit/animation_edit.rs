@@ -19,6 +19,7 @@ impl AnimationEdit {
             Layer(_, _)             |
             Element(_, _)           |
             Motion(_, _)            |
+            Bone(_, _)              |
             SetSize(_, _)           |
             SetFrameLength(_)       |
             SetLength(_)            |
@@ -30,6 +31,8 @@ impl AnimationEdit {
             Undo(PrepareToUndo(_))  |
             Undo(CompletedUndo(_))  |
             Undo(FailedUndo(_))     |
+            Undo(Checkpoint(_))     |
+            Undo(JumpTo(_))         |
             Undo(PerformUndo { original_actions: _, undo_actions: _ }) => false,
         }
     }
@@ -45,6 +48,7 @@ impl AnimationEdit {
             Layer(layer_id, edit)       => { data.write_chr('L'); data.write_small_u64(*layer_id); edit.serialize(data); },
             Element(elements, edit)     => { data.write_chr('E'); data.write_usize(elements.len()); elements.iter().for_each(|elem| elem.serialize(data)); edit.serialize(data); },
             Motion(element, edit)       => { data.write_chr('M'); element.serialize(data); edit.serialize(data); },
+            Bone(element, edit)         => { data.write_chr('B'); element.serialize(data); edit.serialize(data); },
             SetSize(width, height)      => { data.write_chr('S'); data.write_f64(*width); data.write_f64(*height); },
             SetFrameLength(len)         => { data.write_chr('f'); data.write_small_u64(len.as_nanos() as _); }
             SetLength(len)              => { data.write_chr('l'); data.write_duration(*len); },
@@ -57,6 +61,8 @@ impl AnimationEdit {
             Undo(PrepareToUndo(_))      => { },
             Undo(CompletedUndo(_))      => { },
             Undo(FailedUndo(_))         => { },
+            Undo(Checkpoint(_))         => { },
+            Undo(JumpTo(_))             => { },
             Undo(PerformUndo { original_actions: _, undo_actions: _ }) => { },
         }
     }
@@ -68,6 +74,7 @@ impl AnimationEdit {
         match data.next_chr() {
             'L' => { let layer_id = data.next_small_u64(); LayerEdit::deserialize(data).map(move |edit| AnimationEdit::Layer(layer_id, edit)) }
             'M' => { ElementId::deserialize(data).and_then(|elem| MotionEdit::deserialize(data).map(move |edit| AnimationEdit::Motion(elem, edit))) }
+            'B' => { ElementId::deserialize(data).and_then(|elem| BoneEdit::deserialize(data).map(move |edit| AnimationEdit::Bone(elem, edit))) }
             'S' => { Some(AnimationEdit::SetSize(data.next_f64(), data.next_f64())) }
             'f' => { Some(AnimationEdit::SetFrameLength(Duration::from_nanos(data.next_small_u64() as _))) }
             'l' => { Some(AnimationEdit::SetLength(data.next_duration())) }
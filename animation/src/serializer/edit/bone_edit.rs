@@ -0,0 +1,122 @@
+use super::super::source::*;
+use super::super::target::*;
+use super::super::super::traits::*;
+
+use flo_canvas_animation::description::{IkSolver};
+
+impl BoneEdit {
+    ///
+    /// Generates a serialized version of this edit on the specified data target
+    ///
+    pub fn serialize<Tgt: AnimationDataTarget>(&self, data: &mut Tgt) {
+        use self::BoneEdit::*;
+
+        match self {
+            SetParent(None)                 => { data.write_chr('P'); data.write_chr('X'); }
+            SetParent(Some(parent))         => { data.write_chr('P'); data.write_chr('P'); parent.serialize(data); }
+            SetRestPosition(start, end)     => { data.write_chr('R'); data.write_f64(start.0); data.write_f64(start.1); data.write_f64(end.0); data.write_f64(end.1); }
+            Bind(element_id, weight)        => { data.write_chr('+'); element_id.serialize(data); data.write_f64(*weight); }
+            Unbind(element_id)              => { data.write_chr('-'); element_id.serialize(data); }
+            SetRotation(when, degrees)      => { data.write_chr('A'); data.write_duration(*when); data.write_f64(*degrees); }
+            RemoveRotation(when)            => { data.write_chr('a'); data.write_duration(*when); }
+
+            SolveIk { when, target, chain_length, solver } => {
+                data.write_chr('I');
+                data.write_duration(*when);
+                data.write_f64(target.0);
+                data.write_f64(target.1);
+                data.write_usize(*chain_length);
+
+                match solver {
+                    IkSolver::TwoBone                               => { data.write_chr('2'); }
+                    IkSolver::Fabrik { max_iterations, tolerance }  => { data.write_chr('F'); data.write_usize(*max_iterations); data.write_f64(*tolerance); }
+                }
+            }
+        }
+    }
+
+    ///
+    /// Deserializes a bone edit from a data source
+    ///
+    pub fn deserialize<Src: AnimationDataSource>(data: &mut Src) -> Option<BoneEdit> {
+        match data.next_chr() {
+            'P'     => match data.next_chr() {
+                'X' => Some(BoneEdit::SetParent(None)),
+                'P' => ElementId::deserialize(data).map(|parent| BoneEdit::SetParent(Some(parent))),
+
+                _   => None
+            },
+            'R'     => Some(BoneEdit::SetRestPosition((data.next_f64(), data.next_f64()), (data.next_f64(), data.next_f64()))),
+            '+'     => ElementId::deserialize(data).map(|element_id| BoneEdit::Bind(element_id, data.next_f64())),
+            '-'     => ElementId::deserialize(data).map(|element_id| BoneEdit::Unbind(element_id)),
+            'A'     => Some(BoneEdit::SetRotation(data.next_duration(), data.next_f64())),
+            'a'     => Some(BoneEdit::RemoveRotation(data.next_duration())),
+
+            'I'     => {
+                let when            = data.next_duration();
+                let target          = (data.next_f64(), data.next_f64());
+                let chain_length    = data.next_usize();
+                let solver          = match data.next_chr() {
+                    '2' => IkSolver::TwoBone,
+                    'F' => IkSolver::Fabrik { max_iterations: data.next_usize(), tolerance: data.next_f64() },
+                    _   => { return None; }
+                };
+
+                Some(BoneEdit::SolveIk { when, target, chain_length, solver })
+            }
+
+            _       => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::{Duration};
+
+    fn round_trip(edit: BoneEdit) {
+        let mut encoded = String::new();
+        edit.serialize(&mut encoded);
+
+        assert!(BoneEdit::deserialize(&mut encoded.chars()) == Some(edit));
+    }
+
+    #[test]
+    fn set_parent() {
+        round_trip(BoneEdit::SetParent(Some(ElementId::Assigned(42))));
+        round_trip(BoneEdit::SetParent(None));
+    }
+
+    #[test]
+    fn set_rest_position() {
+        round_trip(BoneEdit::SetRestPosition((1.0, 2.0), (3.0, 4.0)));
+    }
+
+    #[test]
+    fn bind_and_unbind() {
+        round_trip(BoneEdit::Bind(ElementId::Assigned(10), 0.75));
+        round_trip(BoneEdit::Unbind(ElementId::Assigned(10)));
+    }
+
+    #[test]
+    fn rotations() {
+        round_trip(BoneEdit::SetRotation(Duration::from_millis(1500), 90.0));
+        round_trip(BoneEdit::RemoveRotation(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn solve_ik() {
+        round_trip(BoneEdit::SolveIk { when: Duration::from_millis(250), target: (100.0, 200.0), chain_length: 2, solver: IkSolver::TwoBone });
+        round_trip(BoneEdit::SolveIk { when: Duration::from_millis(250), target: (100.0, 200.0), chain_length: 5, solver: IkSolver::Fabrik { max_iterations: 20, tolerance: 0.5 } });
+    }
+
+    #[test]
+    fn animation_edit() {
+        let mut encoded = String::new();
+        let edit        = AnimationEdit::Bone(ElementId::Assigned(3), BoneEdit::Bind(ElementId::Assigned(4), 1.0));
+        edit.serialize(&mut encoded);
+
+        assert!(AnimationEdit::deserialize(&mut encoded.chars()) == Some(edit));
+    }
+}
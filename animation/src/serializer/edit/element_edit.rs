@@ -29,6 +29,8 @@ impl ElementEdit {
             SetAnimationBaseType(desc)              => { data.write_chr('1'); data.write_str(&json::to_string(desc).unwrap()); }
             AddAnimationEffect(desc)                => { data.write_chr('2'); data.write_str(&json::to_string(desc).unwrap()); }
             ReplaceAnimationEffect(address, desc)   => { data.write_chr('3'); data.write_str(&json::to_string(address).unwrap()); data.write_str(&json::to_string(desc).unwrap()); }
+            CopyTo { layer, when, new_ids }         => { data.write_chr('y'); data.write_u64(*layer); data.write_duration(*when); data.write_usize(new_ids.len()); new_ids.iter().for_each(|id| id.serialize(data)); }
+            MoveTo { layer, when, new_ids }         => { data.write_chr('m'); data.write_u64(*layer); data.write_duration(*when); data.write_usize(new_ids.len()); new_ids.iter().for_each(|id| id.serialize(data)); }
 
             SetControlPoints(points, when)          => { 
                 data.write_chr('c');
@@ -128,6 +130,24 @@ impl ElementEdit {
                 Some(ElementEdit::ReplaceAnimationEffect(json::from_str(&data.next_string()).ok()?, json::from_str(&data.next_string()).ok()?))
             }
 
+            'y' => {
+                let layer   = data.next_u64();
+                let when    = data.next_duration();
+                let num_ids = data.next_usize();
+                let new_ids = (0..num_ids).map(|_| ElementId::deserialize(data)).collect::<Option<Vec<_>>>()?;
+
+                Some(ElementEdit::CopyTo { layer, when, new_ids })
+            }
+
+            'm' => {
+                let layer   = data.next_u64();
+                let when    = data.next_duration();
+                let num_ids = data.next_usize();
+                let new_ids = (0..num_ids).map(|_| ElementId::deserialize(data)).collect::<Option<Vec<_>>>()?;
+
+                Some(ElementEdit::MoveTo { layer, when, new_ids })
+            }
+
             'C' => {
                 // Obsolete version from older versions of FlowBetween
                 let num_points      = data.next_usize();
@@ -241,6 +261,22 @@ mod test {
         assert!(ElementEdit::deserialize(&mut encoded.chars()) == Some(ElementEdit::Delete));
     }
 
+    #[test]
+    fn copy_to() {
+        let mut encoded = String::new();
+        ElementEdit::CopyTo { layer: 2, when: Duration::from_millis(1234), new_ids: vec![ElementId::Assigned(42)] }.serialize(&mut encoded);
+
+        assert!(ElementEdit::deserialize(&mut encoded.chars()) == Some(ElementEdit::CopyTo { layer: 2, when: Duration::from_millis(1234), new_ids: vec![ElementId::Assigned(42)] }));
+    }
+
+    #[test]
+    fn move_to() {
+        let mut encoded = String::new();
+        ElementEdit::MoveTo { layer: 2, when: Duration::from_millis(1234), new_ids: vec![ElementId::Assigned(42)] }.serialize(&mut encoded);
+
+        assert!(ElementEdit::deserialize(&mut encoded.chars()) == Some(ElementEdit::MoveTo { layer: 2, when: Duration::from_millis(1234), new_ids: vec![ElementId::Assigned(42)] }));
+    }
+
     #[test]
     fn collide_with_existing() {
         let mut encoded = String::new();
@@ -289,6 +325,30 @@ mod test {
         assert!(ElementEdit::deserialize(&mut encoded.chars()) == Some(ElementEdit::Group(ElementId::Unassigned, GroupType::Added)));
     }
 
+    #[test]
+    fn group_subtracted() {
+        let mut encoded = String::new();
+        ElementEdit::Group(ElementId::Assigned(42), GroupType::Subtracted).serialize(&mut encoded);
+
+        assert!(ElementEdit::deserialize(&mut encoded.chars()) == Some(ElementEdit::Group(ElementId::Assigned(42), GroupType::Subtracted)));
+    }
+
+    #[test]
+    fn group_masked() {
+        let mut encoded = String::new();
+        ElementEdit::Group(ElementId::Assigned(42), GroupType::Masked).serialize(&mut encoded);
+
+        assert!(ElementEdit::deserialize(&mut encoded.chars()) == Some(ElementEdit::Group(ElementId::Assigned(42), GroupType::Masked)));
+    }
+
+    #[test]
+    fn group_inverted_mask() {
+        let mut encoded = String::new();
+        ElementEdit::Group(ElementId::Assigned(42), GroupType::InvertedMask).serialize(&mut encoded);
+
+        assert!(ElementEdit::deserialize(&mut encoded.chars()) == Some(ElementEdit::Group(ElementId::Assigned(42), GroupType::InvertedMask)));
+    }
+
     #[test]
     fn ungroup() {
         let mut encoded = String::new();
@@ -307,6 +367,17 @@ mod test {
         assert!(ElementEdit::deserialize(&mut encoded.chars()) == Some(set_animation));
     }
 
+    #[test]
+    fn set_eased_animation_description() {
+        let mut encoded     = String::new();
+        let circle          = Circle::new(Coord2(100.0, 100.0), 50.0).to_path::<SimpleBezierPath>();
+        let effect          = EffectDescription::EasedTimeCurve(Easing::BounceOut, Duration::from_millis(2500), EffectDescription::Sequence(vec![]).boxed());
+        let set_animation   = ElementEdit::SetAnimationDescription(RegionDescription(vec![circle.into()], effect));
+        set_animation.serialize(&mut encoded);
+
+        assert!(ElementEdit::deserialize(&mut encoded.chars()) == Some(set_animation));
+    }
+
     #[test]
     fn set_animation_base_type() {
         let mut encoded     = String::new();
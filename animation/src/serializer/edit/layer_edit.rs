@@ -20,9 +20,19 @@ impl LayerEdit {
             Path(when, edit)                                    => { data.write_chr('p'); data.write_duration(*when); edit.serialize(data); },
             AddKeyFrame(when)                                   => { data.write_chr('+'); data.write_duration(*when); },
             RemoveKeyFrame(when)                                => { data.write_chr('-'); data.write_duration(*when); },
+            MoveKeyFrame(from, to)                              => { data.write_chr('M'); data.write_duration(*from); data.write_duration(*to); },
+            DuplicateKeyFrame(from, to, new_ids)                => { data.write_chr('D'); data.write_duration(*from); data.write_duration(*to); data.write_usize(new_ids.len()); new_ids.iter().for_each(|id| id.serialize(data)); },
+            ShiftKeyFrames(range, offset)                       => { data.write_chr('S'); data.write_duration(range.start); data.write_duration(range.end); data.write_i64(*offset); },
             SetName(name)                                       => { data.write_chr('N'); data.write_str(name); },
             SetOrdering(ordering)                               => { data.write_chr('O'); data.write_u64(*ordering); }
             SetAlpha(alpha)                                     => { data.write_chr('a'); data.write_f64(*alpha); }
+            SetHidden(hidden)                                   => { data.write_chr('h'); data.write_chr(if *hidden { '+' } else { '-' }); }
+            SetLocked(locked)                                   => { data.write_chr('l'); data.write_chr(if *locked { '+' } else { '-' }); }
+            SetBlendMode(blend_mode)                            => { data.write_chr('b'); blend_mode.serialize(data); }
+            SetIsFolder(is_folder)                              => { data.write_chr('F'); data.write_chr(if *is_folder { '+' } else { '-' }); }
+            SetParentFolder(None)                               => { data.write_chr('f'); data.write_chr('-'); }
+            SetParentFolder(Some(folder_id))                    => { data.write_chr('f'); data.write_chr('+'); data.write_u64(*folder_id); }
+            SetCollapsed(collapsed)                             => { data.write_chr('C'); data.write_chr(if *collapsed { '+' } else { '-' }); }
             CreateAnimation(when, id, description)              => { data.write_chr('A'); data.write_duration(*when); id.serialize(data); data.write_str(&json::to_string(description).unwrap()); }
             CreateElement(when, id, vector)                     => { data.write_chr('V'); data.write_duration(*when); id.serialize(data); vector.serialize(data); },
             CreateElementUnattachedToFrame(when, id, vector)    => { data.write_chr('v'); data.write_duration(*when); id.serialize(data); vector.serialize(data); },
@@ -60,10 +70,38 @@ impl LayerEdit {
             }
             '+' => { Some(LayerEdit::AddKeyFrame(data.next_duration())) }
             '-' => { Some(LayerEdit::RemoveKeyFrame(data.next_duration())) }
+            'M' => { Some(LayerEdit::MoveKeyFrame(data.next_duration(), data.next_duration())) }
+            'D' => { 
+                let from        = data.next_duration();
+                let to          = data.next_duration();
+                let num_ids     = data.next_usize();
+                let new_ids     = (0..num_ids).map(|_| ElementId::deserialize(data)).collect::<Option<Vec<_>>>()?;
+
+                Some(LayerEdit::DuplicateKeyFrame(from, to, new_ids))
+            }
+            'S' => { 
+                let start   = data.next_duration();
+                let end     = data.next_duration();
+                let offset  = data.next_i64();
+
+                Some(LayerEdit::ShiftKeyFrames(start..end, offset))
+            }
             'N' => { Some(LayerEdit::SetName(data.next_string())) }
             'O' => { Some(LayerEdit::SetOrdering(data.next_u64())) }
             'A' => { Some(LayerEdit::CreateAnimation(data.next_duration(), ElementId::deserialize(data)?, json::from_str(&data.next_string()).ok()?)) }
             'a' => { Some(LayerEdit::SetAlpha(data.next_f64())) }
+            'h' => { Some(LayerEdit::SetHidden(data.next_chr() == '+')) }
+            'l' => { Some(LayerEdit::SetLocked(data.next_chr() == '+')) }
+            'b' => { Some(LayerEdit::SetBlendMode(LayerBlendMode::deserialize(data)?)) }
+            'F' => { Some(LayerEdit::SetIsFolder(data.next_chr() == '+')) }
+            'f' => { 
+                match data.next_chr() {
+                    '-' => Some(LayerEdit::SetParentFolder(None)),
+                    '+' => Some(LayerEdit::SetParentFolder(Some(data.next_u64()))),
+                    _   => None
+                }
+            }
+            'C' => { Some(LayerEdit::SetCollapsed(data.next_chr() == '+')) }
 
             'V' => { 
                 let when    = data.next_duration();
@@ -152,6 +190,33 @@ mod test {
         assert!(LayerEdit::deserialize(&mut encoded.chars()) == Some(edit));
     }
 
+    #[test]
+    fn move_key_frame() {
+        let mut encoded = String::new();
+        let edit        = LayerEdit::MoveKeyFrame(Duration::from_millis(1234), Duration::from_millis(5678));
+        edit.serialize(&mut encoded);
+
+        assert!(LayerEdit::deserialize(&mut encoded.chars()) == Some(edit));
+    }
+
+    #[test]
+    fn duplicate_key_frame() {
+        let mut encoded = String::new();
+        let edit        = LayerEdit::DuplicateKeyFrame(Duration::from_millis(1234), Duration::from_millis(5678), vec![ElementId::Assigned(42), ElementId::Assigned(43)]);
+        edit.serialize(&mut encoded);
+
+        assert!(LayerEdit::deserialize(&mut encoded.chars()) == Some(edit));
+    }
+
+    #[test]
+    fn shift_key_frames() {
+        let mut encoded = String::new();
+        let edit        = LayerEdit::ShiftKeyFrames(Duration::from_millis(1234)..Duration::from_micros(i64::max_value() as u64), -5678);
+        edit.serialize(&mut encoded);
+
+        assert!(LayerEdit::deserialize(&mut encoded.chars()) == Some(edit));
+    }
+
     #[test]
     fn set_name() {
         let mut encoded = String::new();
@@ -201,4 +266,67 @@ mod test {
 
         assert!(LayerEdit::deserialize(&mut encoded.chars()) == Some(edit));
     }
+
+    #[test]
+    fn set_hidden() {
+        let mut encoded = String::new();
+        let edit        = LayerEdit::SetHidden(true);
+        edit.serialize(&mut encoded);
+
+        assert!(LayerEdit::deserialize(&mut encoded.chars()) == Some(edit));
+    }
+
+    #[test]
+    fn set_locked() {
+        let mut encoded = String::new();
+        let edit        = LayerEdit::SetLocked(true);
+        edit.serialize(&mut encoded);
+
+        assert!(LayerEdit::deserialize(&mut encoded.chars()) == Some(edit));
+    }
+
+    #[test]
+    fn set_blend_mode() {
+        let mut encoded = String::new();
+        let edit        = LayerEdit::SetBlendMode(LayerBlendMode::Overlay);
+        edit.serialize(&mut encoded);
+
+        assert!(LayerEdit::deserialize(&mut encoded.chars()) == Some(edit));
+    }
+
+    #[test]
+    fn set_is_folder() {
+        let mut encoded = String::new();
+        let edit        = LayerEdit::SetIsFolder(true);
+        edit.serialize(&mut encoded);
+
+        assert!(LayerEdit::deserialize(&mut encoded.chars()) == Some(edit));
+    }
+
+    #[test]
+    fn set_parent_folder() {
+        let mut encoded = String::new();
+        let edit        = LayerEdit::SetParentFolder(Some(42));
+        edit.serialize(&mut encoded);
+
+        assert!(LayerEdit::deserialize(&mut encoded.chars()) == Some(edit));
+    }
+
+    #[test]
+    fn remove_parent_folder() {
+        let mut encoded = String::new();
+        let edit        = LayerEdit::SetParentFolder(None);
+        edit.serialize(&mut encoded);
+
+        assert!(LayerEdit::deserialize(&mut encoded.chars()) == Some(edit));
+    }
+
+    #[test]
+    fn set_collapsed() {
+        let mut encoded = String::new();
+        let edit        = LayerEdit::SetCollapsed(true);
+        edit.serialize(&mut encoded);
+
+        assert!(LayerEdit::deserialize(&mut encoded.chars()) == Some(edit));
+    }
 }
@@ -2,18 +2,22 @@ mod path_edit;
 mod layer_edit;
 mod paint_edit;
 mod motion_edit;
+mod bone_edit;
 mod element_edit;
 mod element_align;
 mod animation_edit;
 mod element_ordering;
 mod element_transform;
+mod undo_history;
 
 pub use self::path_edit::*;
 pub use self::layer_edit::*;
 pub use self::paint_edit::*;
 pub use self::motion_edit::*;
+pub use self::bone_edit::*;
 pub use self::element_edit::*;
 pub use self::element_align::*;
 pub use self::animation_edit::*;
 pub use self::element_ordering::*;
-pub use self::element_transform::*;
\ No newline at end of file
+pub use self::element_transform::*;
+pub use self::undo_history::*;
\ No newline at end of file
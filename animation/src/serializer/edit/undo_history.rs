@@ -0,0 +1,171 @@
+use super::super::source::*;
+use super::super::target::*;
+use crate::traits::*;
+
+use std::sync::*;
+use std::str::{Chars};
+
+///
+/// Writes a list of edits to a data target (edits that are not written to the edit log are left out)
+///
+fn serialize_edit_list<Tgt: AnimationDataTarget>(edits: &Vec<AnimationEdit>, data: &mut Tgt) {
+    let edits = edits.iter().filter(|edit| edit.is_serialized()).collect::<Vec<_>>();
+
+    data.write_usize(edits.len());
+    edits.into_iter().for_each(|edit| edit.serialize(data));
+}
+
+///
+/// Reads a list of edits written by `serialize_edit_list`
+///
+fn deserialize_edit_list(data: &mut Chars) -> Option<Vec<AnimationEdit>> {
+    let num_edits = data.next_usize();
+
+    (0..num_edits).into_iter()
+        .map(|_| AnimationEdit::deserialize(data))
+        .collect()
+}
+
+impl UndoHistoryNode {
+    ///
+    /// Generates a serialized version of this history node on the specified data target
+    ///
+    /// The node ID is not serialized: it's expected to be stored alongside the node
+    ///
+    pub fn serialize<Tgt: AnimationDataTarget>(&self, data: &mut Tgt) {
+        data.write_chr('H');
+
+        match self.parent {
+            Some(parent)    => { data.write_chr('p'); data.write_u64(parent); }
+            None            => { data.write_chr('-'); }
+        }
+
+        match &self.checkpoint {
+            Some(name)      => { data.write_chr('c'); data.write_str(name); }
+            None            => { data.write_chr('-'); }
+        }
+
+        data.write_usize(self.edit_log_range.start);
+        data.write_usize(self.edit_log_range.end);
+
+        serialize_edit_list(&*self.reverse_edits, data);
+
+        match &self.undone_edits {
+            Some(edits)     => { data.write_chr('u'); serialize_edit_list(&*edits, data); }
+            None            => { data.write_chr('-'); }
+        }
+    }
+
+    ///
+    /// Deserializes a history node with the specified ID
+    ///
+    pub fn deserialize(id: u64, data: &mut Chars) -> Option<UndoHistoryNode> {
+        match data.next_chr() {
+            'H' => {
+                let parent = match data.next_chr() {
+                    'p' => Some(data.next_u64()),
+                    '-' => None,
+                    _   => { return None; }
+                };
+
+                let checkpoint = match data.next_chr() {
+                    'c' => Some(data.next_string()),
+                    '-' => None,
+                    _   => { return None; }
+                };
+
+                let start           = data.next_usize();
+                let end             = data.next_usize();
+                let reverse_edits   = Arc::new(deserialize_edit_list(data)?);
+
+                let undone_edits    = match data.next_chr() {
+                    'u' => Some(Arc::new(deserialize_edit_list(data)?)),
+                    '-' => None,
+                    _   => { return None; }
+                };
+
+                Some(UndoHistoryNode {
+                    id:             id,
+                    parent:         parent,
+                    checkpoint:     checkpoint,
+                    edit_log_range: start..end,
+                    reverse_edits:  reverse_edits,
+                    undone_edits:   undone_edits,
+                })
+            }
+
+            _   => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn first_node() {
+        let mut encoded = String::new();
+        let node        = UndoHistoryNode {
+            id:             0,
+            parent:         None,
+            checkpoint:     None,
+            edit_log_range: 0..2,
+            reverse_edits:  Arc::new(vec![AnimationEdit::RemoveLayer(0)]),
+            undone_edits:   None,
+        };
+        node.serialize(&mut encoded);
+
+        assert!(UndoHistoryNode::deserialize(0, &mut encoded.chars()) == Some(node));
+    }
+
+    #[test]
+    fn checkpoint_node() {
+        let mut encoded = String::new();
+        let node        = UndoHistoryNode {
+            id:             3,
+            parent:         Some(2),
+            checkpoint:     Some("Before lip sync".to_string()),
+            edit_log_range: 1024..1100,
+            reverse_edits:  Arc::new(vec![]),
+            undone_edits:   None,
+        };
+        node.serialize(&mut encoded);
+
+        assert!(UndoHistoryNode::deserialize(3, &mut encoded.chars()) == Some(node));
+    }
+
+    #[test]
+    fn undone_node() {
+        let mut encoded = String::new();
+        let node        = UndoHistoryNode {
+            id:             4,
+            parent:         Some(3),
+            checkpoint:     None,
+            edit_log_range: 1100..1102,
+            reverse_edits:  Arc::new(vec![AnimationEdit::Element(vec![ElementId::Assigned(42)], ElementEdit::Delete)]),
+            undone_edits:   Some(Arc::new(vec![AnimationEdit::Element(vec![ElementId::Assigned(42)], ElementEdit::Delete), AnimationEdit::Undo(UndoEdit::FinishAction)])),
+        };
+        node.serialize(&mut encoded);
+
+        assert!(UndoHistoryNode::deserialize(4, &mut encoded.chars()) == Some(node));
+    }
+
+    #[test]
+    fn edits_not_in_edit_log_are_left_out() {
+        let mut encoded = String::new();
+        let node        = UndoHistoryNode {
+            id:             1,
+            parent:         Some(0),
+            checkpoint:     None,
+            edit_log_range: 2..3,
+            reverse_edits:  Arc::new(vec![AnimationEdit::RemoveLayer(2), AnimationEdit::Undo(UndoEdit::PrepareToUndo("Test".to_string()))]),
+            undone_edits:   Some(Arc::new(vec![AnimationEdit::AddNewLayer(2), AnimationEdit::Undo(UndoEdit::PrepareToUndo("Test".to_string()))])),
+        };
+        node.serialize(&mut encoded);
+
+        let decoded     = UndoHistoryNode::deserialize(1, &mut encoded.chars()).unwrap();
+        assert!(decoded.reverse_edits == Arc::new(vec![AnimationEdit::RemoveLayer(2)]));
+        assert!(decoded.undone_edits == Some(Arc::new(vec![AnimationEdit::AddNewLayer(2)])));
+    }
+}
@@ -0,0 +1,50 @@
+use super::source::*;
+use super::target::*;
+use super::super::traits::*;
+
+impl LayerBlendMode {
+    ///
+    /// Generates a serialized version of this blend mode on the specified data target
+    ///
+    pub fn serialize<Tgt: AnimationDataTarget>(&self, data: &mut Tgt) {
+        use self::LayerBlendMode::*;
+
+        match self {
+            Normal      => data.write_chr('N'),
+            Multiply    => data.write_chr('M'),
+            Screen      => data.write_chr('S'),
+            Overlay     => data.write_chr('O'),
+            Add         => data.write_chr('A')
+        }
+    }
+
+    ///
+    /// Deserializes a blend mode from a data source
+    ///
+    pub fn deserialize<Src: AnimationDataSource>(data: &mut Src) -> Option<LayerBlendMode> {
+        match data.next_chr() {
+            'N'     => Some(LayerBlendMode::Normal),
+            'M'     => Some(LayerBlendMode::Multiply),
+            'S'     => Some(LayerBlendMode::Screen),
+            'O'     => Some(LayerBlendMode::Overlay),
+            'A'     => Some(LayerBlendMode::Add),
+
+            _       => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn all_blend_modes() {
+        for blend_mode in [LayerBlendMode::Normal, LayerBlendMode::Multiply, LayerBlendMode::Screen, LayerBlendMode::Overlay, LayerBlendMode::Add] {
+            let mut encoded = String::new();
+            blend_mode.serialize(&mut encoded);
+
+            assert!(LayerBlendMode::deserialize(&mut encoded.chars()) == Some(blend_mode));
+        }
+    }
+}
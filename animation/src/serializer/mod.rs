@@ -5,11 +5,13 @@
 //! and layer data describes the elements/entities that make up an animation.
 //! 
 //! The custom ASCII format is used for compactness and speed over more verbose formats like JSON, as animations
-//! can contain a lot of data.
+//! can contain a lot of data. Storage layers that can store binary data can use `encode_compact_bytes()` to reduce
+//! the size further.
 //!
 
 mod source;
 mod target;
+mod compact;
 
 mod edit;
 mod color;
@@ -19,12 +21,14 @@ mod cache_type;
 mod element_id;
 mod fill_option;
 mod drawing_style;
+mod layer_blend_mode;
 mod path_component;
 mod brush_definition;
 mod brush_properties;
 
 pub use self::source::*;
 pub use self::target::*;
+pub use self::compact::*;
 
 pub use self::edit::*;
 pub use self::color::*;
@@ -34,6 +38,7 @@ pub use self::cache_type::*;
 pub use self::element_id::*;
 pub use self::fill_option::*;
 pub use self::drawing_style::*;
+pub use self::layer_blend_mode::*;
 pub use self::path_component::*;
 pub use self::brush_definition::*;
 pub use self::brush_properties::*;
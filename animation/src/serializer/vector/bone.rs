@@ -0,0 +1,90 @@
+use super::super::source::*;
+use super::super::target::*;
+use super::super::super::traits::*;
+
+impl BoneElement {
+    ///
+    /// Generates a serialized version of this bone element on the specified data target
+    ///
+    pub fn serialize<Tgt: AnimationDataTarget>(&self, data: &mut Tgt) {
+        match self.parent() {
+            None            => { data.write_chr('X'); }
+            Some(parent)    => { data.write_chr('P'); parent.serialize(data); }
+        }
+
+        let (x1, y1) = self.start();
+        let (x2, y2) = self.end();
+        data.write_f64(x1); data.write_f64(y1);
+        data.write_f64(x2); data.write_f64(y2);
+
+        let bindings = self.bindings();
+        data.write_usize(bindings.len());
+        bindings.iter().for_each(|(element_id, weight)| { element_id.serialize(data); data.write_f64(*weight); });
+
+        let rotations = self.rotations();
+        data.write_usize(rotations.len());
+        rotations.iter().for_each(|(when, degrees)| { data.write_duration(*when); data.write_f64(*degrees); });
+    }
+
+    ///
+    /// Deserializes a bone element from a data source
+    ///
+    pub fn deserialize<Src: AnimationDataSource>(element_id: ElementId, data: &mut Src) -> Option<BoneElement> {
+        let parent = match data.next_chr() {
+            'X' => None,
+            'P' => Some(ElementId::deserialize(data)?),
+            _   => { return None; }
+        };
+
+        let start           = (data.next_f64(), data.next_f64());
+        let end             = (data.next_f64(), data.next_f64());
+
+        let num_bindings    = data.next_usize();
+        let bindings        = (0..num_bindings).into_iter()
+            .map(|_| ElementId::deserialize(data).map(|element_id| (element_id, data.next_f64())))
+            .collect::<Option<Vec<_>>>()?;
+
+        let num_rotations   = data.next_usize();
+        let rotations       = (0..num_rotations).into_iter()
+            .map(|_| (data.next_duration(), data.next_f64()))
+            .collect::<Vec<_>>();
+
+        Some(BoneElement::with_bindings_and_rotations(element_id, parent, start, end, bindings, rotations))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::serializer::*;
+
+    use std::time::{Duration};
+
+    #[test]
+    fn bone_element() {
+        let bone = BoneElement::new(ElementId::Assigned(2), Some(ElementId::Assigned(1)), (10.0, 20.0), (30.0, 40.0))
+            .with_binding(ElementId::Assigned(3), 0.5)
+            .with_binding(ElementId::Assigned(4), 1.0)
+            .with_rotation(Duration::from_millis(500), 45.0)
+            .with_rotation(Duration::from_millis(1000), -30.0);
+
+        let mut encoded = String::new();
+        bone.serialize(&mut encoded);
+
+        let decoded     = BoneElement::deserialize(ElementId::Assigned(2), &mut encoded.chars());
+        assert!(decoded == Some(bone));
+    }
+
+    #[test]
+    fn root_bone_element() {
+        let bone = BoneElement::new(ElementId::Assigned(1), None, (0.0, 0.0), (100.0, 0.0));
+
+        let mut encoded = String::new();
+        Vector::Bone(bone.clone()).serialize(&mut encoded);
+
+        let decoded     = Vector::deserialize(ElementId::Assigned(1), &mut encoded.chars());
+        let decoded     = decoded.and_then(|resolver| resolver.resolve(&mut |_| None));
+
+        assert!(decoded == Some(Vector::Bone(bone)));
+    }
+}
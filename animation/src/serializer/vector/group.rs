@@ -13,8 +13,11 @@ impl GroupType {
     pub fn serialize<Tgt: AnimationDataTarget>(&self, data: &mut Tgt) {
         use self::GroupType::*;
         match self {
-            Normal      => { data.write_chr('N'); }
-            Added       => { data.write_chr('+'); }
+            Normal          => { data.write_chr('N'); }
+            Added           => { data.write_chr('+'); }
+            Subtracted      => { data.write_chr('-'); }
+            Masked          => { data.write_chr('M'); }
+            InvertedMask    => { data.write_chr('I'); }
         }
     }
 
@@ -25,6 +28,9 @@ impl GroupType {
         match data.next_chr() {
             'N'     => Some(GroupType::Normal),
             '+'     => Some(GroupType::Added),
+            '-'     => Some(GroupType::Subtracted),
+            'M'     => Some(GroupType::Masked),
+            'I'     => Some(GroupType::InvertedMask),
             _       => None
         }
     }
@@ -168,4 +174,38 @@ mod test {
         assert!(elements[2].id() == ElementId::Assigned(3));
         assert!(elements[3].id() == ElementId::Unassigned);
     }
+
+    #[test]
+    fn group_types() {
+        for group_type in vec![GroupType::Normal, GroupType::Added, GroupType::Subtracted, GroupType::Masked, GroupType::InvertedMask] {
+            let mut encoded = String::new();
+            group_type.serialize(&mut encoded);
+
+            assert!(GroupType::deserialize(&mut encoded.chars()) == Some(group_type));
+        }
+    }
+
+    #[test]
+    fn masked_group() {
+        let element1    = Vector::BrushDefinition(BrushDefinitionElement::new(ElementId::Assigned(1), BrushDefinition::Simple, BrushDrawingStyle::Draw));
+        let element2    = Vector::BrushDefinition(BrushDefinitionElement::new(ElementId::Assigned(2), BrushDefinition::Simple, BrushDrawingStyle::Draw));
+        let group       = GroupElement::new(ElementId::Assigned(3), GroupType::Masked, Arc::new(vec![element1.clone(), element2.clone()]));
+
+        let mut encoded = String::new();
+        group.serialize(&mut encoded);
+
+        let decoded     = GroupElement::deserialize(ElementId::Assigned(3), &mut encoded.chars());
+        let decoded     = decoded.unwrap();
+        let decoded     = decoded.resolve(&mut |element_id| {
+            match element_id {
+                ElementId::Assigned(1)  => Some(element1.clone()),
+                ElementId::Assigned(2)  => Some(element2.clone()),
+                _                       => None
+            }
+        });
+        let decoded     = decoded.unwrap();
+
+        assert!(decoded.group_type() == GroupType::Masked);
+        assert!(decoded.num_elements() == 2);
+    }
 }
@@ -1,5 +1,7 @@
 mod path;
+mod bone;
 mod group;
+mod tween;
 mod shape;
 mod vector;
 mod motion;
@@ -13,7 +15,9 @@ mod brush_properties;
 mod animation_region;
 
 pub use self::path::*;
+pub use self::bone::*;
 pub use self::group::*;
+pub use self::tween::*;
 pub use self::shape::*;
 pub use self::vector::*;
 pub use self::motion::*;
@@ -0,0 +1,63 @@
+use super::super::source::*;
+use super::super::target::*;
+use super::super::super::traits::*;
+
+impl TweenElement {
+    ///
+    /// Generates a serialized version of this tween element on the specified data target
+    ///
+    pub fn serialize<Tgt: AnimationDataTarget>(&self, data: &mut Tgt) {
+        self.target().serialize(data);
+
+        let hints = self.hints();
+        data.write_usize(hints.len());
+        hints.iter().for_each(|((x1, y1), (x2, y2))| {
+            data.write_f64(*x1); data.write_f64(*y1);
+            data.write_f64(*x2); data.write_f64(*y2);
+        });
+    }
+
+    ///
+    /// Deserializes a tween element from a data source
+    ///
+    pub fn deserialize<Src: AnimationDataSource>(element_id: ElementId, data: &mut Src) -> Option<TweenElement> {
+        let target      = ElementId::deserialize(data)?;
+
+        let num_hints   = data.next_usize();
+        let hints       = (0..num_hints).into_iter()
+            .map(|_| ((data.next_f64(), data.next_f64()), (data.next_f64(), data.next_f64())))
+            .collect();
+
+        Some(TweenElement::new(element_id, target, hints))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::serializer::*;
+
+    #[test]
+    fn tween_element() {
+        let tween = TweenElement::new(ElementId::Assigned(2), ElementId::Assigned(10), vec![((1.0, 2.0), (3.0, 4.0)), ((5.0, 6.0), (7.0, 8.0))]);
+
+        let mut encoded = String::new();
+        tween.serialize(&mut encoded);
+
+        let decoded     = TweenElement::deserialize(ElementId::Assigned(2), &mut encoded.chars());
+        assert!(decoded == Some(tween));
+    }
+
+    #[test]
+    fn tween_element_as_vector() {
+        let tween       = TweenElement::new(ElementId::Assigned(2), ElementId::Assigned(10), vec![]);
+
+        let mut encoded = String::new();
+        Vector::Tween(tween.clone()).serialize(&mut encoded);
+
+        let decoded     = Vector::deserialize(ElementId::Assigned(2), &mut encoded.chars());
+        let decoded     = decoded.and_then(|resolver| resolver.resolve(&mut |_| None));
+
+        assert!(decoded == Some(Vector::Tween(tween)));
+    }
+}
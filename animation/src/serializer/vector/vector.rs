@@ -32,6 +32,8 @@ impl Vector {
             Path(_path)                         => { false }
             Shape(_shape)                       => { false }
             AnimationRegion(_region)            => { false }
+            Bone(_bone)                         => { false }
+            Tween(_tween)                       => { false }
             Transformation((_id, _transform))   => { false }
         }
     }
@@ -54,6 +56,8 @@ impl Vector {
             Motion(motion)                  => { data.write_chr('m'); motion.serialize(data); }
             Group(group)                    => { data.write_chr('g'); group.serialize(data); }
             AnimationRegion(region)         => { data.write_chr('A'); region.serialize(data); }
+            Bone(bone)                      => { data.write_chr('b'); bone.serialize(data); }
+            Tween(tween)                    => { data.write_chr('w'); tween.serialize(data); }
             Error                           => { data.write_chr('?'); }
 
             Transformation((id, transform)) => { 
@@ -109,6 +113,14 @@ impl Vector {
                     Some(Vector::AnimationRegion(animation_element))
                 }))
             }
+            'b' => {
+                BoneElement::deserialize(element_id, data)
+                    .map(|bone| box_fn(move |_| Some(Vector::Bone(bone))))
+            }
+            'w' => {
+                TweenElement::deserialize(element_id, data)
+                    .map(|tween| box_fn(move |_| Some(Vector::Tween(tween))))
+            }
             'm' => { 
                 MotionElement::deserialize(element_id, data)
                     .map(|motion| box_fn(move |_| Some(Vector::Motion(motion))))
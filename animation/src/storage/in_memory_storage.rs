@@ -9,7 +9,7 @@ use futures::future;
 use std::i64;
 use std::sync::*;
 use std::time::{Duration};
-use std::collections::{HashMap};
+use std::collections::{HashMap, BTreeMap};
 
 ///
 /// Represents a key frame
@@ -75,7 +75,13 @@ struct InMemoryStorageCore {
     element_attachments: HashMap<i64, Vec<ElementAttachment>>,
 
     /// The layers
-    layers: HashMap<u64, InMemoryLayerStorage>
+    layers: HashMap<u64, InMemoryLayerStorage>,
+
+    /// The serialized nodes of the undo history
+    undo_history: BTreeMap<u64, String>,
+
+    /// The current position in the undo history
+    undo_history_position: Option<u64>
 }
 
 ///
@@ -97,7 +103,9 @@ impl InMemoryStorage {
             edit_log:               vec![],
             elements:               HashMap::new(),
             layers:                 HashMap::new(),
-            element_attachments:    HashMap::new()
+            element_attachments:    HashMap::new(),
+            undo_history:           BTreeMap::new(),
+            undo_history_position:  None
         };
 
         // And the storage
@@ -320,6 +328,47 @@ impl InMemoryStorageCore {
                     }
                 }
 
+                MoveKeyFrame(layer_id, from, to)                    => {
+                    if let Some(layer) = self.layers.get_mut(&layer_id) {
+                        // The source keyframe must exist and the target must be empty
+                        let from_index  = layer.keyframes.binary_search_by(|frame| frame.when.cmp(&from));
+                        let to_index    = layer.keyframes.binary_search_by(|frame| frame.when.cmp(&to));
+
+                        match (from_index, to_index) {
+                            (Err(_), _)                     => { response.push(StorageResponse::NotFound); }
+                            (Ok(_), Ok(_)) if from != to    => { response.push(StorageResponse::NotReplacingExisting); }
+
+                            (Ok(from_index), _)             => {
+                                // Move the keyframe and the times of the elements attached to it
+                                let mut keyframe    = layer.keyframes.remove(from_index);
+                                keyframe.when       = to;
+
+                                for (element_id, when) in keyframe.attached_elements.iter_mut() {
+                                    *when = to + when.saturating_sub(from);
+
+                                    if let Some(attachments) = self.element_attachments.get_mut(element_id) {
+                                        attachments.iter_mut()
+                                            .filter(|attachment| attachment.layer_id == layer_id && attachment.keyframe_time == from)
+                                            .for_each(|attachment| attachment.keyframe_time = to);
+                                    }
+                                }
+
+                                // Re-insert the keyframe at its new location
+                                let to_index = layer.keyframes.binary_search_by(|frame| frame.when.cmp(&to)).unwrap_or_else(|index| index);
+                                layer.keyframes.insert(to_index, keyframe);
+
+                                // Anything cached for the old or new keyframe times is out of date
+                                layer.cache.retain(|cache_item| cache_item.when != from && cache_item.when != to);
+
+                                response.push(StorageResponse::Updated);
+                            }
+                        }
+                    } else {
+                        // Layer not found
+                        response.push(StorageResponse::NotFound);
+                    }
+                }
+
                 ReadKeyFrames(layer_id, period)                     => {
                     if let Some(layer) = self.layers.get(&layer_id) {
                         // Search for the initial keyframe
@@ -488,6 +537,22 @@ impl InMemoryStorageCore {
                         response.push(StorageResponse::NotFound);
                     }
                 }
+
+                WriteUndoHistoryNode(node_id, node)                 => {
+                    self.undo_history.insert(node_id, node);
+                    response.push(StorageResponse::Updated);
+                }
+
+                WriteUndoHistoryPosition(position)                  => {
+                    self.undo_history_position = position;
+                    response.push(StorageResponse::Updated);
+                }
+
+                ReadUndoHistory                                     => {
+                    response.extend(self.undo_history.iter()
+                        .map(|(node_id, node)| StorageResponse::UndoHistoryNode(*node_id, node.clone())));
+                    response.push(StorageResponse::UndoHistoryPosition(self.undo_history_position));
+                }
             }
         }
 
@@ -1,4 +1,5 @@
 use super::super::serializer::*;
+use super::super::traits::*;
 
 use std::i64;
 
@@ -13,16 +14,40 @@ pub struct LayerProperties {
     pub alpha: f64,
 
     /// The ordering of this layer, relative to other layers
-    pub ordering: i64
+    pub ordering: i64,
+
+    /// True if this layer is hidden
+    pub hidden: bool,
+
+    /// True if this layer is locked against editing
+    pub locked: bool,
+
+    /// How this layer is combined with the layers behind it
+    pub blend_mode: LayerBlendMode,
+
+    /// True if this layer is a folder that can contain other layers
+    pub is_folder: bool,
+
+    /// The folder layer that contains this layer
+    pub parent_folder: Option<u64>,
+
+    /// True if this layer is a folder that is collapsed in the timeline
+    pub collapsed: bool,
 }
 
 
 impl Default for LayerProperties {
     fn default() -> LayerProperties {
         LayerProperties {
-            name:       "".to_string(),
-            alpha:      1.0,
-            ordering:   i64::max_value()
+            name:           "".to_string(),
+            alpha:          1.0,
+            ordering:       i64::max_value(),
+            hidden:         false,
+            locked:         false,
+            blend_mode:     LayerBlendMode::Normal,
+            is_folder:      false,
+            parent_folder:  None,
+            collapsed:      false,
         }
     }
 }
@@ -32,12 +57,23 @@ impl LayerProperties {
     /// Serializes these file properties to a target
     ///
     pub fn serialize<Tgt: AnimationDataTarget>(&self, data: &mut Tgt) {
-        // Version 1 of the properties
-        data.write_small_u64(1);
+        // Version 2 of the properties
+        data.write_small_u64(2);
 
         data.write_str(&self.name);
         data.write_f64(self.alpha);
         data.write_i64(self.ordering);
+
+        data.write_chr(if self.hidden { '+' } else { '-' });
+        data.write_chr(if self.locked { '+' } else { '-' });
+        self.blend_mode.serialize(data);
+        data.write_chr(if self.is_folder { '+' } else { '-' });
+        data.write_chr(if self.collapsed { '+' } else { '-' });
+
+        match self.parent_folder {
+            None            => { data.write_chr('-'); }
+            Some(folder_id) => { data.write_chr('+'); data.write_u64(folder_id); }
+        }
     }
 
     ///
@@ -63,7 +99,80 @@ impl LayerProperties {
                 Some(result)
             }
 
+            2 => {
+                result.name             = data.next_string();
+                result.alpha            = data.next_f64();
+                result.ordering         = data.next_i64();
+
+                result.hidden           = data.next_chr() == '+';
+                result.locked           = data.next_chr() == '+';
+                result.blend_mode       = LayerBlendMode::deserialize(data)?;
+                result.is_folder        = data.next_chr() == '+';
+                result.collapsed        = data.next_chr() == '+';
+
+                result.parent_folder    = match data.next_chr() {
+                    '-' => None,
+                    '+' => Some(data.next_u64()),
+                    _   => { return None; }
+                };
+
+                Some(result)
+            }
+
             _ => None
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn read_version_1() {
+        let mut encoded = String::new();
+        encoded.write_small_u64(1);
+        encoded.write_str("Layer");
+        encoded.write_f64(0.5);
+        encoded.write_i64(3);
+
+        let properties = LayerProperties::deserialize(&mut encoded.chars()).unwrap();
+
+        assert!(properties.name == "Layer");
+        assert!(properties.alpha == 0.5);
+        assert!(properties.ordering == 3);
+        assert!(!properties.hidden);
+        assert!(!properties.locked);
+        assert!(properties.blend_mode == LayerBlendMode::Normal);
+        assert!(properties.parent_folder.is_none());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut properties          = LayerProperties::default();
+        properties.name             = "Folder".to_string();
+        properties.alpha            = 0.25;
+        properties.ordering         = 7;
+        properties.hidden           = true;
+        properties.locked           = true;
+        properties.blend_mode       = LayerBlendMode::Multiply;
+        properties.is_folder        = true;
+        properties.parent_folder    = Some(42);
+        properties.collapsed        = true;
+
+        let mut encoded = String::new();
+        properties.serialize(&mut encoded);
+
+        let decoded = LayerProperties::deserialize(&mut encoded.chars()).unwrap();
+
+        assert!(decoded.name == "Folder");
+        assert!(decoded.alpha == 0.25);
+        assert!(decoded.ordering == 7);
+        assert!(decoded.hidden);
+        assert!(decoded.locked);
+        assert!(decoded.blend_mode == LayerBlendMode::Multiply);
+        assert!(decoded.is_folder);
+        assert!(decoded.parent_folder == Some(42));
+        assert!(decoded.collapsed);
+    }
+}
@@ -57,6 +57,9 @@ pub enum StorageCommand {
     /// Removes a key frame from a layer
     DeleteKeyFrame(u64, Duration),
 
+    /// Moves the key frame at the first time to the second time, along with the elements attached to it (parameters are layer id, from, to)
+    MoveKeyFrame(u64, Duration, Duration),
+
     /// Reads the keyframes that appear in a particular time range for a layer
     ReadKeyFrames(u64, Range<Duration>),
 
@@ -79,5 +82,14 @@ pub enum StorageCommand {
     DeleteLayerCache(u64, Duration, String),
 
     /// Reads from the layer cache (parameters are layer id, cache time and key)
-    ReadLayerCache(u64, Duration, String)
+    ReadLayerCache(u64, Duration, String),
+
+    /// Writes (or replaces) a serialized node in the undo history
+    WriteUndoHistoryNode(u64, String),
+
+    /// Sets the node in the undo history that matches the current state of the edit log (None if no actions from the history are applied)
+    WriteUndoHistoryPosition(Option<u64>),
+
+    /// Reads the undo history (returns an `UndoHistoryNode` for each node followed by the `UndoHistoryPosition`)
+    ReadUndoHistory
 }
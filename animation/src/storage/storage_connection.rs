@@ -12,6 +12,7 @@ use flo_stream::*;
 use futures::prelude::*;
 use futures::stream::{BoxStream};
 
+use std::iter;
 use std::ops::{Range};
 use std::time::{Duration};
 use std::collections::{HashMap};
@@ -128,6 +129,44 @@ impl StorageConnection {
         }
     }
 
+    ///
+    /// Writes changes to the undo history to storage
+    ///
+    pub fn write_undo_history<'a>(&'a mut self, history: &'a UndoHistory) -> impl 'a + Future<Output=()> {
+        async move {
+            let write_nodes = history.nodes.iter()
+                .map(|node| {
+                    let mut serialized = String::new();
+                    node.serialize(&mut serialized);
+
+                    StorageCommand::WriteUndoHistoryNode(node.id, serialized)
+                });
+            let write_position = StorageCommand::WriteUndoHistoryPosition(history.position);
+
+            self.request(write_nodes.chain(iter::once(write_position))).await;
+        }
+    }
+
+    ///
+    /// Reads the undo history from storage (nodes that can't be deserialized are left out)
+    ///
+    pub fn read_undo_history<'a>(&'a mut self) -> impl 'a + Future<Output=UndoHistory> {
+        async move {
+            let responses   = self.request(vec![StorageCommand::ReadUndoHistory]).await.unwrap_or_else(|| vec![]);
+            let mut history = UndoHistory::empty();
+
+            for response in responses {
+                match response {
+                    StorageResponse::UndoHistoryNode(id, node)      => { history.nodes.extend(UndoHistoryNode::deserialize(id, &mut node.chars())); }
+                    StorageResponse::UndoHistoryPosition(position)  => { history.position = position; }
+                    _                                               => { }
+                }
+            }
+
+            history
+        }
+    }
+
     ///
     /// Reads the properties for a single layer from this connection
     ///
@@ -46,6 +46,12 @@ pub enum StorageResponse {
     /// Returns the contents of the requested layer cache
     LayerCache(String),
 
+    /// A serialized node from the undo history
+    UndoHistoryNode(u64, String),
+
+    /// The node in the undo history that matches the current state of the edit log
+    UndoHistoryPosition(Option<u64>),
+
     /// The storage subsystem encountered an error
     Error(StorageError, String)
 }
@@ -0,0 +1,132 @@
+use super::*;
+
+use flo_canvas_animation::description::{IkSolver};
+
+use std::sync::*;
+use std::time::Duration;
+
+///
+/// Creates an animation with a square path and an arm made of two bones on layer 24, in a keyframe starting at 300ms
+///
+fn create_arm() -> impl EditableAnimation {
+    use self::LayerEdit::*;
+
+    let anim = create_animation();
+
+    anim.perform_edits(vec![
+        AnimationEdit::AddNewLayer(24),
+        AnimationEdit::Layer(24, LayerEdit::AddKeyFrame(Duration::from_millis(300))),
+        AnimationEdit::Layer(24, Path(Duration::from_millis(300),
+            PathEdit::SelectBrush(ElementId::Unassigned, BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw))),
+        AnimationEdit::Layer(24, Path(Duration::from_millis(300),
+            PathEdit::BrushProperties(ElementId::Unassigned, BrushProperties::new()))),
+        AnimationEdit::Layer(24, Path(Duration::from_millis(300),
+            PathEdit::CreatePath(ElementId::Assigned(100), Arc::new(vec![
+                PathComponent::Move(PathPoint::new(190.0, -10.0)),
+                PathComponent::Line(PathPoint::new(210.0, -10.0)),
+                PathComponent::Line(PathPoint::new(210.0, 10.0)),
+                PathComponent::Line(PathPoint::new(190.0, 10.0)),
+                PathComponent::Close
+            ])))),
+        AnimationEdit::Layer(24, CreateElement(Duration::from_millis(300), ElementId::Assigned(101),
+            Vector::Bone(BoneElement::new(ElementId::Assigned(101), None, (0.0, 0.0), (100.0, 0.0))))),
+        AnimationEdit::Layer(24, CreateElement(Duration::from_millis(300), ElementId::Assigned(102),
+            Vector::Bone(BoneElement::new(ElementId::Assigned(102), Some(ElementId::Assigned(101)), (100.0, 0.0), (200.0, 0.0))))),
+    ]);
+
+    anim
+}
+
+///
+/// Retrieves a bone from the keyframe at 300ms
+///
+fn bone<Anim: EditableAnimation>(anim: &Anim, bone_id: i64) -> BoneElement {
+    let layer   = anim.get_layer_with_id(24).unwrap();
+    let frame   = layer.get_frame_at_time(Duration::from_millis(300));
+
+    match frame.element_with_id(ElementId::Assigned(bone_id)) {
+        Some(Vector::Bone(bone))    => bone,
+        other                       => panic!("Not a bone: {:?}", other)
+    }
+}
+
+#[test]
+fn create_bones() {
+    let anim = create_arm();
+
+    let bone101 = bone(&anim, 101);
+    let bone102 = bone(&anim, 102);
+
+    assert!(bone101.parent() == None);
+    assert!(bone102.parent() == Some(ElementId::Assigned(101)));
+    assert!(bone102.start() == (100.0, 0.0));
+    assert!(bone102.end() == (200.0, 0.0));
+}
+
+#[test]
+fn bind_element_to_bone() {
+    let anim = create_arm();
+
+    anim.perform_edits(vec![
+        AnimationEdit::Bone(ElementId::Assigned(102), BoneEdit::Bind(ElementId::Assigned(100), 0.5)),
+        AnimationEdit::Bone(ElementId::Assigned(102), BoneEdit::Bind(ElementId::Assigned(100), 1.0)),
+    ]);
+
+    assert!(*bone(&anim, 102).bindings() == vec![(ElementId::Assigned(100), 1.0)]);
+
+    anim.perform_edits(vec![
+        AnimationEdit::Bone(ElementId::Assigned(102), BoneEdit::Unbind(ElementId::Assigned(100))),
+    ]);
+
+    assert!(bone(&anim, 102).bindings().len() == 0);
+}
+
+#[test]
+fn rotation_is_relative_to_keyframe() {
+    let anim = create_arm();
+
+    anim.perform_edits(vec![
+        AnimationEdit::Bone(ElementId::Assigned(101), BoneEdit::SetRotation(Duration::from_millis(1300), 90.0)),
+        AnimationEdit::Bone(ElementId::Assigned(101), BoneEdit::SetRotation(Duration::from_millis(800), 45.0)),
+        AnimationEdit::Bone(ElementId::Assigned(101), BoneEdit::RemoveRotation(Duration::from_millis(800))),
+    ]);
+
+    assert!(*bone(&anim, 101).rotations() == vec![(Duration::from_millis(1000), 90.0)]);
+}
+
+#[test]
+fn solve_ik_for_arm() {
+    let anim = create_arm();
+
+    anim.perform_edits(vec![
+        AnimationEdit::Bone(ElementId::Assigned(102), BoneEdit::SolveIk { when: Duration::from_millis(1300), target: (100.0, 100.0), chain_length: 2, solver: IkSolver::TwoBone }),
+    ]);
+
+    let bone101 = bone(&anim, 101);
+    let bone102 = bone(&anim, 102);
+
+    // Both bones should have a rotation at 1000ms relative to the keyframe
+    let root_rotation   = bone101.rotation_at(Duration::from_millis(1000)).unwrap();
+    let end_rotation    = bone102.rotation_at(Duration::from_millis(1000)).unwrap();
+
+    // Work out where the end of the arm is with these rotations
+    let root_angle      = root_rotation.to_radians();
+    let end_angle       = (root_rotation + end_rotation).to_radians();
+    let elbow           = (100.0 * root_angle.cos(), 100.0 * root_angle.sin());
+    let hand            = (elbow.0 + 100.0 * end_angle.cos(), elbow.1 + 100.0 * end_angle.sin());
+
+    assert!((hand.0 - 100.0).abs() < 0.1, "{:?}", hand);
+    assert!((hand.1 - 100.0).abs() < 0.1, "{:?}", hand);
+}
+
+#[test]
+fn solve_ik_for_one_bone_only_rotates_that_bone() {
+    let anim = create_arm();
+
+    anim.perform_edits(vec![
+        AnimationEdit::Bone(ElementId::Assigned(102), BoneEdit::SolveIk { when: Duration::from_millis(300), target: (100.0, 100.0), chain_length: 1, solver: IkSolver::default() }),
+    ]);
+
+    assert!(bone(&anim, 101).rotations().len() == 0);
+    assert!((bone(&anim, 102).rotation_at(Duration::from_millis(0)).unwrap() - 90.0).abs() < 0.1);
+}
@@ -0,0 +1,165 @@
+use super::*;
+
+use std::sync::*;
+use std::time::{Duration};
+
+///
+/// Creates an animation with a transformed path (ID 100) on layer 24 at 300ms, and an empty keyframe on layer 25 at 0ms
+///
+fn create_transformed_path() -> impl EditableAnimation {
+    use self::LayerEdit::*;
+
+    let anim = create_animation();
+
+    anim.perform_edits(vec![
+        AnimationEdit::AddNewLayer(24),
+        AnimationEdit::AddNewLayer(25),
+        AnimationEdit::Layer(24, LayerEdit::AddKeyFrame(Duration::from_millis(300))),
+        AnimationEdit::Layer(25, LayerEdit::AddKeyFrame(Duration::from_millis(0))),
+        AnimationEdit::Layer(24, Path(Duration::from_millis(300),
+            PathEdit::SelectBrush(ElementId::Unassigned, BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw))),
+        AnimationEdit::Layer(24, Path(Duration::from_millis(300),
+            PathEdit::BrushProperties(ElementId::Unassigned, BrushProperties::new()))),
+        AnimationEdit::Layer(24, Path(Duration::from_millis(300),
+            PathEdit::CreatePath(ElementId::Assigned(100), Arc::new(vec![
+                PathComponent::Move(PathPoint::new(10.0, 20.0)),
+                PathComponent::Line(PathPoint::new(20.0, 30.0)),
+                PathComponent::Bezier(PathPoint::new(40.0, 40.0), PathPoint::new(30.0, 30.0), PathPoint::new(20.0, 20.0)),
+                PathComponent::Close
+            ])))),
+        AnimationEdit::Element(vec![ElementId::Assigned(100)], ElementEdit::Transform(vec![ElementTransform::SetAnchor(10.0, 20.0), ElementTransform::MoveTo(0.0, 0.0)]))
+    ]);
+
+    anim
+}
+
+///
+/// Returns the IDs of the path elements in a frame
+///
+fn path_ids<Anim: EditableAnimation>(anim: &Anim, layer_id: u64, when: Duration) -> Vec<ElementId> {
+    let layer = anim.get_layer_with_id(layer_id).unwrap();
+    let frame = layer.get_frame_at_time(when);
+
+    frame.vector_elements()
+        .map(|elements| elements.filter(|elem| if let Vector::Path(_) = elem { true } else { false }).map(|elem| elem.id()).collect())
+        .unwrap_or_else(|| vec![])
+}
+
+#[test]
+fn copy_path_to_other_layer() {
+    let anim = create_transformed_path();
+
+    anim.perform_edits(vec![
+        AnimationEdit::Element(vec![ElementId::Assigned(100)], ElementEdit::CopyTo { layer: 25, when: Duration::from_millis(0), new_ids: vec![] })
+    ]);
+    anim.flush_caches();
+
+    // Original is unchanged
+    assert!(path_ids(&anim, 24, Duration::from_millis(300)) == vec![ElementId::Assigned(100)]);
+
+    let layer               = anim.get_layer_with_id(24).unwrap();
+    let frame               = layer.get_frame_at_time(Duration::from_millis(300));
+    let original_attached   = frame.attached_elements(ElementId::Assigned(100)).into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+    assert!(original_attached.len() == 3);
+
+    // Copy has a new ID
+    let copied_ids          = path_ids(&anim, 25, Duration::from_millis(0));
+    assert!(copied_ids.len() == 1);
+    assert!(copied_ids[0] != ElementId::Assigned(100));
+    assert!(copied_ids[0].is_assigned());
+
+    // Copy has its own copies of the brush and the transformation
+    let layer               = anim.get_layer_with_id(25).unwrap();
+    let frame               = layer.get_frame_at_time(Duration::from_millis(0));
+    let copy_attached       = frame.attached_elements(copied_ids[0]);
+    assert!(copy_attached.len() == 3);
+    assert!(copy_attached.iter().all(|(id, _)| !original_attached.contains(id)));
+
+    let transformations     = copy_attached.iter()
+        .filter(|(id, _)| if let Some(Vector::Transformation(_)) = frame.element_with_id(*id) { true } else { false })
+        .count();
+    assert!(transformations == 1);
+}
+
+#[test]
+fn copy_path_within_keyframe() {
+    let anim = create_transformed_path();
+
+    anim.perform_edits(vec![
+        AnimationEdit::Element(vec![ElementId::Assigned(100)], ElementEdit::CopyTo { layer: 24, when: Duration::from_millis(300), new_ids: vec![] })
+    ]);
+    anim.flush_caches();
+
+    let ids = path_ids(&anim, 24, Duration::from_millis(300));
+    assert!(ids.len() == 2);
+    assert!(ids.contains(&ElementId::Assigned(100)));
+}
+
+#[test]
+fn copy_group_copies_children() {
+    let anim = create_transformed_path();
+
+    anim.perform_edits(vec![
+        AnimationEdit::Layer(24, LayerEdit::Path(Duration::from_millis(300),
+            PathEdit::CreatePath(ElementId::Assigned(101), Arc::new(vec![
+                PathComponent::Move(PathPoint::new(30.0, 20.0)),
+                PathComponent::Line(PathPoint::new(40.0, 30.0)),
+                PathComponent::Close
+            ])))),
+        AnimationEdit::Element(vec![ElementId::Assigned(100), ElementId::Assigned(101)], ElementEdit::Group(ElementId::Assigned(200), GroupType::Normal)),
+        AnimationEdit::Element(vec![ElementId::Assigned(200)], ElementEdit::CopyTo { layer: 25, when: Duration::from_millis(0), new_ids: vec![] })
+    ]);
+    anim.flush_caches();
+
+    let layer       = anim.get_layer_with_id(25).unwrap();
+    let frame       = layer.get_frame_at_time(Duration::from_millis(0));
+    let elements    = frame.vector_elements().unwrap().collect::<Vec<_>>();
+    let groups      = elements.iter().filter(|elem| if let Vector::Group(_) = elem { true } else { false }).collect::<Vec<_>>();
+
+    assert!(groups.len() == 1);
+    assert!(groups[0].id() != ElementId::Assigned(200));
+
+    let group_ids   = match groups[0] {
+        Vector::Group(group)    => group.elements().map(|elem| elem.id()).collect::<Vec<_>>(),
+        _                       => vec![]
+    };
+    assert!(group_ids.len() == 2);
+    assert!(!group_ids.contains(&ElementId::Assigned(100)));
+    assert!(!group_ids.contains(&ElementId::Assigned(101)));
+
+    // Original group is still present
+    let layer       = anim.get_layer_with_id(24).unwrap();
+    let frame       = layer.get_frame_at_time(Duration::from_millis(300));
+    assert!(frame.element_with_id(ElementId::Assigned(200)).is_some());
+}
+
+#[test]
+fn move_path_to_other_layer() {
+    let anim = create_transformed_path();
+
+    anim.perform_edits(vec![
+        AnimationEdit::Element(vec![ElementId::Assigned(100)], ElementEdit::MoveTo { layer: 25, when: Duration::from_millis(0), new_ids: vec![] })
+    ]);
+    anim.flush_caches();
+
+    assert!(path_ids(&anim, 24, Duration::from_millis(300)).len() == 0);
+
+    let moved_ids = path_ids(&anim, 25, Duration::from_millis(0));
+    assert!(moved_ids.len() == 1);
+    assert!(moved_ids[0] != ElementId::Assigned(100));
+}
+
+#[test]
+fn copy_to_missing_keyframe_does_nothing() {
+    let anim = create_transformed_path();
+    anim.perform_edits(vec![AnimationEdit::AddNewLayer(26)]);
+
+    anim.perform_edits(vec![
+        AnimationEdit::Element(vec![ElementId::Assigned(100)], ElementEdit::MoveTo { layer: 26, when: Duration::from_millis(0), new_ids: vec![] })
+    ]);
+    anim.flush_caches();
+
+    // Nothing to move to, so the original should be left alone
+    assert!(path_ids(&anim, 24, Duration::from_millis(300)) == vec![ElementId::Assigned(100)]);
+    assert!(path_ids(&anim, 26, Duration::from_millis(0)).len() == 0);
+}
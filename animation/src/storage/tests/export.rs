@@ -0,0 +1,121 @@
+use super::*;
+use crate::export::*;
+
+use futures::executor;
+
+use std::sync::*;
+use std::time::Duration;
+
+///
+/// Creates a square path component list
+///
+fn square(x: f32, y: f32, size: f32) -> Arc<Vec<PathComponent>> {
+    Arc::new(vec![
+        PathComponent::Move(PathPoint::new(x, y)),
+        PathComponent::Line(PathPoint::new(x+size, y)),
+        PathComponent::Line(PathPoint::new(x+size, y+size)),
+        PathComponent::Line(PathPoint::new(x, y+size)),
+        PathComponent::Line(PathPoint::new(x, y))
+    ])
+}
+
+///
+/// Creates a 4 frame animation with a square in the first two frames and a second square in the last two frames
+///
+fn two_keyframe_animation() -> impl EditableAnimation {
+    let anim = create_animation();
+
+    anim.perform_edits(vec![
+        AnimationEdit::SetSize(200.0, 100.0),
+        AnimationEdit::SetFrameLength(Duration::from_millis(100)),
+        AnimationEdit::SetLength(Duration::from_millis(400)),
+        AnimationEdit::AddNewLayer(1),
+        AnimationEdit::Layer(1, LayerEdit::AddKeyFrame(Duration::from_millis(0))),
+        AnimationEdit::Layer(1, LayerEdit::AddKeyFrame(Duration::from_millis(200))),
+        AnimationEdit::Layer(1, LayerEdit::Path(Duration::from_millis(0), PathEdit::SelectBrush(
+                ElementId::Assigned(1),
+                BrushDefinition::Ink(InkDefinition::default()),
+                BrushDrawingStyle::Draw
+            ))),
+        AnimationEdit::Layer(1, LayerEdit::Path(Duration::from_millis(0), PathEdit::BrushProperties(ElementId::Assigned(2), BrushProperties::new()))),
+        AnimationEdit::Layer(1, LayerEdit::Path(Duration::from_millis(0), PathEdit::CreatePath(ElementId::Assigned(3), square(10.0, 10.0, 20.0)))),
+        AnimationEdit::Layer(1, LayerEdit::Path(Duration::from_millis(200), PathEdit::SelectBrush(
+                ElementId::Assigned(4),
+                BrushDefinition::Ink(InkDefinition::default()),
+                BrushDrawingStyle::Draw
+            ))),
+        AnimationEdit::Layer(1, LayerEdit::Path(Duration::from_millis(200), PathEdit::BrushProperties(ElementId::Assigned(5), BrushProperties::new()))),
+        AnimationEdit::Layer(1, LayerEdit::Path(Duration::from_millis(200), PathEdit::CreatePath(ElementId::Assigned(6), square(100.0, 10.0, 20.0)))),
+    ]);
+
+    anim
+}
+
+#[test]
+fn export_frames_combines_keyframes() {
+    let anim    = two_keyframe_animation();
+    let frames  = executor::block_on(export_frames(&anim));
+
+    // The two keyframes should each be displayed for two frames
+    assert!(animation_frame_count(&anim) == 4);
+    assert!(frames.len() == 2);
+    assert!(frames[0].start_frame == 0 && frames[0].num_frames == 2);
+    assert!(frames[1].start_frame == 2 && frames[1].num_frames == 2);
+
+    // Shapes are flipped so the y axis points down
+    assert!(frames[0].shapes.len() > 0);
+    for shape in frames[0].shapes.iter() {
+        for subpath in shape.subpaths.iter() {
+            assert!(subpath.start.0 < 50.0);
+            assert!(subpath.start.1 > 60.0);
+        }
+    }
+
+    for shape in frames[1].shapes.iter() {
+        for subpath in shape.subpaths.iter() {
+            assert!(subpath.start.0 > 50.0);
+        }
+    }
+}
+
+#[test]
+fn export_svg_frame_sequence() {
+    let anim    = two_keyframe_animation();
+    let frames  = executor::block_on(animation_to_svg_frames(&anim));
+
+    assert!(frames.len() == 4);
+    assert!(frames[0] == frames[1]);
+    assert!(frames[1] != frames[2]);
+    assert!(frames[0].starts_with("<svg"));
+    assert!(frames[0].contains("viewBox=\"0 0 200 100\""));
+    assert!(frames[0].contains("<path d=\"M"));
+}
+
+#[test]
+fn export_smil_svg() {
+    let anim    = two_keyframe_animation();
+    let svg     = executor::block_on(animation_to_smil_svg(&anim));
+
+    // One animated group per keyframe
+    assert!(svg.matches("<animate ").count() == 2);
+    assert!(svg.contains("values=\"inline;none\" keyTimes=\"0;0.5\""));
+    assert!(svg.contains("values=\"none;inline\" keyTimes=\"0;0.5\""));
+    assert!(svg.contains("dur=\"0.4s\""));
+}
+
+#[test]
+fn export_lottie() {
+    let anim    = two_keyframe_animation();
+    let lottie  = executor::block_on(animation_to_lottie_json(&anim));
+
+    assert!(lottie["w"] == 200);
+    assert!(lottie["h"] == 100);
+    assert!(lottie["op"] == 4);
+    assert!((lottie["fr"].as_f64().unwrap() - 10.0).abs() < 0.01);
+
+    let layers = lottie["layers"].as_array().unwrap();
+    assert!(layers.len() == 2);
+    assert!(layers[0]["ip"] == 0 && layers[0]["op"] == 2);
+    assert!(layers[1]["ip"] == 2 && layers[1]["op"] == 4);
+    assert!(layers[0]["shapes"][0]["ty"] == "gr");
+}
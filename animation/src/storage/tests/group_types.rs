@@ -0,0 +1,232 @@
+use super::*;
+use crate::raycast::edge::*;
+
+use flo_curves::*;
+
+use futures::prelude::*;
+use futures::executor;
+
+use std::sync::*;
+use std::time::Duration;
+
+///
+/// Creates a square path component list
+///
+fn square(x: f32, y: f32, size: f32) -> Arc<Vec<PathComponent>> {
+    Arc::new(vec![
+        PathComponent::Move(PathPoint::new(x, y)),
+        PathComponent::Line(PathPoint::new(x+size, y)),
+        PathComponent::Line(PathPoint::new(x+size, y+size)),
+        PathComponent::Line(PathPoint::new(x, y+size)),
+        PathComponent::Line(PathPoint::new(x, y))
+    ])
+}
+
+///
+/// Creates an animation with two overlapping squares (IDs 3 and 4) in layer 2, grouped using the specified group type (ID 5)
+///
+fn overlapping_squares(group_type: GroupType) -> impl EditableAnimation {
+    let anim = create_animation();
+
+    anim.perform_edits(vec![
+        AnimationEdit::AddNewLayer(2),
+        AnimationEdit::Layer(2, LayerEdit::AddKeyFrame(Duration::from_millis(0))),
+        AnimationEdit::Layer(2, LayerEdit::Path(Duration::from_millis(0), PathEdit::SelectBrush(
+                ElementId::Assigned(1),
+                BrushDefinition::Ink(InkDefinition::default()),
+                BrushDrawingStyle::Draw
+            ))),
+        AnimationEdit::Layer(2, LayerEdit::Path(Duration::from_millis(0), PathEdit::BrushProperties(ElementId::Assigned(2), BrushProperties::new()))),
+        AnimationEdit::Layer(2, LayerEdit::Path(Duration::from_millis(0), PathEdit::CreatePath(ElementId::Assigned(3), square(100.0, 100.0, 100.0)))),
+        AnimationEdit::Layer(2, LayerEdit::Path(Duration::from_millis(0), PathEdit::CreatePath(ElementId::Assigned(4), square(150.0, 100.0, 100.0)))),
+        AnimationEdit::Element(vec![ElementId::Assigned(3), ElementId::Assigned(4)], ElementEdit::Group(ElementId::Assigned(5), group_type))
+    ]);
+
+    anim
+}
+
+///
+/// Reads the bounds of the path generated by the group with ID 5 in layer 2
+///
+fn group_bounds<Anim: Animation>(anim: &Anim, group_type: GroupType) -> Rect {
+    anim.flush_caches();
+
+    let layer       = anim.get_layer_with_id(2).unwrap();
+    let frame       = layer.get_frame_at_time(Duration::from_millis(0));
+    let elements    = frame.vector_elements().unwrap().collect::<Vec<_>>();
+
+    // The two squares should have been replaced by the group
+    assert!(elements.len() == 1);
+    assert!(elements[0].id() == ElementId::Assigned(5));
+
+    // Group type should be preserved when it's reloaded
+    let group       = match &elements[0] {
+        Vector::Group(group)    => group.clone(),
+        _                       => { assert!(false, "Not a group"); unimplemented!() }
+    };
+    assert!(group.group_type() == group_type);
+    assert!(group.elements().map(|elem| elem.id()).collect::<Vec<_>>() == vec![ElementId::Assigned(3), ElementId::Assigned(4)]);
+
+    // Fetch the path for the group
+    let properties  = frame.apply_properties_for_element(&elements[0], Arc::new(VectorProperties::default()));
+    let path        = elements[0].to_path(&*properties, PathConversion::Fastest).unwrap();
+
+    path.iter()
+        .map(|path| Rect::from(path))
+        .fold(Rect::empty(), |a, b| if a.is_zero_size() { b } else { a.union(b) })
+}
+
+#[test]
+fn subtracted_group_path() {
+    let anim    = overlapping_squares(GroupType::Subtracted);
+    let bounds  = group_bounds(&anim, GroupType::Subtracted);
+
+    // Second square is cut out of the first
+    assert!((bounds.x1 - 100.0).abs() < 0.1);
+    assert!((bounds.x2 - 150.0).abs() < 0.1);
+    assert!((bounds.y1 - 100.0).abs() < 0.1);
+    assert!((bounds.y2 - 200.0).abs() < 0.1);
+}
+
+#[test]
+fn masked_group_path() {
+    let anim    = overlapping_squares(GroupType::Masked);
+    let bounds  = group_bounds(&anim, GroupType::Masked);
+
+    // Only the overlapping part of the first square is visible
+    assert!((bounds.x1 - 150.0).abs() < 0.1);
+    assert!((bounds.x2 - 200.0).abs() < 0.1);
+    assert!((bounds.y1 - 100.0).abs() < 0.1);
+    assert!((bounds.y2 - 200.0).abs() < 0.1);
+}
+
+#[test]
+fn inverted_mask_group_path() {
+    let anim    = overlapping_squares(GroupType::InvertedMask);
+    let bounds  = group_bounds(&anim, GroupType::InvertedMask);
+
+    // First square is cut out of the second square
+    assert!((bounds.x1 - 200.0).abs() < 0.1);
+    assert!((bounds.x2 - 250.0).abs() < 0.1);
+    assert!((bounds.y1 - 100.0).abs() < 0.1);
+    assert!((bounds.y2 - 200.0).abs() < 0.1);
+}
+
+#[test]
+fn raycast_edges_masked_group() {
+    let anim        = overlapping_squares(GroupType::Masked);
+    let layer       = anim.get_layer_with_id(2).unwrap();
+    let frame       = layer.get_frame_at_time(Duration::from_millis(0));
+    let elements    = frame.vector_elements().unwrap().collect::<Vec<_>>();
+    let properties  = frame.apply_properties_for_element(&elements[0], Arc::new(VectorProperties::default()));
+
+    // Only the visible region of the mask should generate edges
+    let edges       = RaycastEdge::from_vector(&elements[0], properties).collect::<Vec<_>>();
+
+    assert!(edges.len() > 0);
+    for edge in edges.iter() {
+        let start_point = edge.curve.start_point();
+
+        assert!(edge.element_id == ElementId::Assigned(5));
+        assert!(edge.kind == RaycastEdgeKind::Solid);
+        assert!(start_point.x() > 149.9 && start_point.x() < 200.1);
+    }
+}
+
+///
+/// Groups two elements with a particular group type and checks that the group is removed again when the reverse edits are performed
+///
+fn undo_group_round_trip(group_type: GroupType) {
+    executor::block_on(async {
+        let anim = create_animation();
+
+        anim.perform_edits(vec![
+            AnimationEdit::AddNewLayer(2),
+            AnimationEdit::Layer(2, LayerEdit::AddKeyFrame(Duration::from_millis(0))),
+            AnimationEdit::Layer(2, LayerEdit::Path(Duration::from_millis(0), PathEdit::SelectBrush(
+                    ElementId::Assigned(1),
+                    BrushDefinition::Ink(InkDefinition::default()),
+                    BrushDrawingStyle::Draw
+                ))),
+            AnimationEdit::Layer(2, LayerEdit::Path(Duration::from_millis(0), PathEdit::BrushProperties(ElementId::Assigned(2), BrushProperties::new()))),
+            AnimationEdit::Layer(2, LayerEdit::Path(Duration::from_millis(0), PathEdit::CreatePath(ElementId::Assigned(3), square(100.0, 100.0, 100.0)))),
+            AnimationEdit::Layer(2, LayerEdit::Path(Duration::from_millis(0), PathEdit::CreatePath(ElementId::Assigned(4), square(150.0, 100.0, 100.0)))),
+        ]);
+
+        // Group the two squares, and read the reverse edits
+        let mut retired_edits   = anim.retired_edits();
+        let group_edit          = Arc::new(vec![AnimationEdit::Element(vec![ElementId::Assigned(3), ElementId::Assigned(4)], ElementEdit::Group(ElementId::Assigned(5), group_type))]);
+
+        anim.edit().publish(Arc::clone(&group_edit)).await;
+        anim.edit().when_empty().await;
+
+        let retired             = retired_edits.next().await.unwrap();
+        let committed           = retired.committed_edits();
+        let reverse             = retired.reverse_edits();
+
+        assert!(committed == group_edit);
+        assert!(!reverse.is_empty());
+
+        // Elements should be grouped
+        anim.flush_caches();
+        let layer               = anim.get_layer_with_id(2).unwrap();
+        let frame               = layer.get_frame_at_time(Duration::from_millis(0));
+        let elements            = frame.vector_elements().unwrap().collect::<Vec<_>>();
+        assert!(elements.iter().map(|elem| elem.id()).collect::<Vec<_>>() == vec![ElementId::Assigned(5)]);
+
+        // Undo the group operation
+        anim.edit().publish(Arc::new(vec![AnimationEdit::Undo(UndoEdit::PerformUndo { original_actions: committed, undo_actions: reverse })])).await;
+        anim.edit().when_empty().await;
+
+        // Should be back to the original elements
+        anim.flush_caches();
+        let frame               = layer.get_frame_at_time(Duration::from_millis(0));
+        let elements            = frame.vector_elements().unwrap().collect::<Vec<_>>();
+        assert!(elements.iter().map(|elem| elem.id()).collect::<Vec<_>>() == vec![ElementId::Assigned(3), ElementId::Assigned(4)]);
+
+        // The undo edits are retired along with a 'completed undo' edit
+        retired_edits.next().await.unwrap();
+        retired_edits.next().await.unwrap();
+
+        // Ungrouping also generates the correct group type when undone
+        anim.edit().publish(group_edit).await;
+        anim.edit().when_empty().await;
+        retired_edits.next().await.unwrap();
+
+        anim.edit().publish(Arc::new(vec![AnimationEdit::Element(vec![ElementId::Assigned(5)], ElementEdit::Ungroup)])).await;
+        anim.edit().when_empty().await;
+
+        let retired             = retired_edits.next().await.unwrap();
+        let reverse             = retired.reverse_edits();
+
+        assert!(reverse.iter().any(|edit| edit == &AnimationEdit::Element(vec![ElementId::Assigned(3), ElementId::Assigned(4)], ElementEdit::Group(ElementId::Assigned(5), group_type))));
+
+        anim.edit().publish(Arc::new(vec![AnimationEdit::Undo(UndoEdit::PerformUndo { original_actions: retired.committed_edits(), undo_actions: reverse })])).await;
+        anim.edit().when_empty().await;
+
+        anim.flush_caches();
+        let frame               = layer.get_frame_at_time(Duration::from_millis(0));
+        let elements            = frame.vector_elements().unwrap().collect::<Vec<_>>();
+        assert!(elements.iter().map(|elem| elem.id()).collect::<Vec<_>>() == vec![ElementId::Assigned(5)]);
+
+        match &elements[0] {
+            Vector::Group(group)    => assert!(group.group_type() == group_type),
+            _                       => assert!(false, "Not a group")
+        }
+    });
+}
+
+#[test]
+fn undo_subtracted_group() {
+    undo_group_round_trip(GroupType::Subtracted);
+}
+
+#[test]
+fn undo_masked_group() {
+    undo_group_round_trip(GroupType::Masked);
+}
+
+#[test]
+fn undo_inverted_mask_group() {
+    undo_group_round_trip(GroupType::InvertedMask);
+}
@@ -14,8 +14,13 @@ mod shape;
 mod caching;
 mod collide_paths;
 mod grouping;
+mod copy_elements;
+mod group_types;
+mod export;
 mod transformation;
 mod fill_paths;
+mod bones;
+mod tweening;
 
 ///
 /// Creates an in-memory animaton for the tests
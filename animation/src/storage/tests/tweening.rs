@@ -0,0 +1,102 @@
+use super::*;
+
+use flo_canvas::*;
+
+use std::sync::*;
+use std::time::Duration;
+
+///
+/// Creates the edits to draw a square in a keyframe on layer 24
+///
+fn square(when: Duration, element_id: i64, size: f64) -> Vec<AnimationEdit> {
+    use self::LayerEdit::*;
+
+    vec![
+        AnimationEdit::Layer(24, Path(when,
+            PathEdit::SelectBrush(ElementId::Unassigned, BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw))),
+        AnimationEdit::Layer(24, Path(when,
+            PathEdit::BrushProperties(ElementId::Unassigned, BrushProperties::new()))),
+        AnimationEdit::Layer(24, Path(when,
+            PathEdit::CreatePath(ElementId::Assigned(element_id), Arc::new(vec![
+                PathComponent::Move(PathPoint::new(0.0, 0.0)),
+                PathComponent::Line(PathPoint::new(size as _, 0.0)),
+                PathComponent::Line(PathPoint::new(size as _, size as _)),
+                PathComponent::Line(PathPoint::new(0.0, size as _)),
+                PathComponent::Line(PathPoint::new(0.0, 0.0)),
+                PathComponent::Close
+            ])))),
+    ]
+}
+
+///
+/// Finds the largest x coordinate drawn when rendering layer 24 at a particular time
+///
+fn max_x_at_time<Anim: EditableAnimation>(anim: &Anim, when: Duration) -> f32 {
+    let layer           = anim.get_layer_with_id(24).unwrap();
+    let frame           = layer.get_frame_at_time(when);
+    let mut drawing     = Vec::<Draw>::new();
+
+    frame.render_to(&mut drawing);
+
+    drawing.into_iter()
+        .flat_map(|draw| match draw {
+            Draw::Path(PathOp::Move(x, _))                              |
+            Draw::Path(PathOp::Line(x, _))                              => vec![x],
+            Draw::Path(PathOp::BezierCurve(((x1, _), (x2, _)), (x, _))) => vec![x1, x2, x],
+            _                                                           => vec![]
+        })
+        .fold(f32::MIN, |max_x, x| max_x.max(x))
+}
+
+#[test]
+fn tween_square_into_larger_square() {
+    use self::LayerEdit::*;
+
+    let anim = create_animation();
+
+    anim.perform_edits(vec![
+        AnimationEdit::AddNewLayer(24),
+        AnimationEdit::Layer(24, AddKeyFrame(Duration::from_millis(0))),
+        AnimationEdit::Layer(24, AddKeyFrame(Duration::from_millis(1000))),
+    ]);
+    anim.perform_edits(square(Duration::from_millis(0), 100, 10.0));
+    anim.perform_edits(square(Duration::from_millis(1000), 200, 20.0));
+
+    // Without a tween, the square stays the same size until the next keyframe
+    assert!((max_x_at_time(&anim, Duration::from_millis(500)) - 10.0).abs() < 0.01);
+
+    // Tween the small square into the large one
+    anim.perform_edits(vec![
+        AnimationEdit::Layer(24, CreateElementUnattachedToFrame(Duration::from_millis(0), ElementId::Assigned(300),
+            Vector::Tween(TweenElement::new(ElementId::Assigned(300), ElementId::Assigned(200), vec![])))),
+        AnimationEdit::Element(vec![ElementId::Assigned(100)], ElementEdit::AddAttachment(ElementId::Assigned(300))),
+    ]);
+
+    assert!((max_x_at_time(&anim, Duration::from_millis(0)) - 10.0).abs() < 0.01);
+    assert!((max_x_at_time(&anim, Duration::from_millis(500)) - 15.0).abs() < 0.01);
+    assert!((max_x_at_time(&anim, Duration::from_millis(1000)) - 20.0).abs() < 0.01);
+}
+
+#[test]
+fn tween_without_following_keyframe_does_nothing() {
+    use self::LayerEdit::*;
+
+    let anim = create_animation();
+
+    anim.perform_edits(vec![
+        AnimationEdit::AddNewLayer(24),
+        AnimationEdit::Layer(24, AddKeyFrame(Duration::from_millis(0))),
+    ]);
+    anim.perform_edits(square(Duration::from_millis(0), 100, 10.0));
+    anim.perform_edits(vec![
+        AnimationEdit::Layer(24, CreateElementUnattachedToFrame(Duration::from_millis(0), ElementId::Assigned(300),
+            Vector::Tween(TweenElement::new(ElementId::Assigned(300), ElementId::Assigned(200), vec![])))),
+        AnimationEdit::Element(vec![ElementId::Assigned(100)], ElementEdit::AddAttachment(ElementId::Assigned(300))),
+    ]);
+
+    let layer   = anim.get_layer_with_id(24).unwrap();
+    let frame   = layer.get_frame_at_time(Duration::from_millis(0));
+
+    assert!(match frame.element_with_id(ElementId::Assigned(300)) { Some(Vector::Tween(_)) => true, _ => false });
+    assert!((max_x_at_time(&anim, Duration::from_millis(500)) - 10.0).abs() < 0.01);
+}
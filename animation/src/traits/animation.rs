@@ -80,6 +80,19 @@ pub trait EditableAnimation : Animation+Send+Sync {
     ///
     fn retired_edits(&self) -> BoxStream<'static, RetiredEdit>;
 
+    ///
+    /// Reads the undo history that has been stored for this animation
+    ///
+    fn read_undo_history(&self) -> UndoHistory {
+        UndoHistory::empty()
+    }
+
+    ///
+    /// Stores changes to the undo history alongside this animation (nodes replace any existing nodes with the same ID)
+    ///
+    fn write_undo_history(&self, _changes: UndoHistory) {
+    }
+
     ///
     /// Flushes any caches this might have (forces reload from data storage)
     ///
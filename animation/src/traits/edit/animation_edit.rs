@@ -2,6 +2,7 @@ use super::element_id::*;
 use super::undo_edit::*;
 use super::layer_edit::*;
 use super::motion_edit::*;
+use super::bone_edit::*;
 use super::element_edit::*;
 
 use smallvec::*;
@@ -23,6 +24,9 @@ pub enum AnimationEdit {
     /// Motions have element IDs so can be treated as elements but are not attached to a layer
     Motion(ElementId, MotionEdit),
 
+    /// Edit to a bone (which is an element that deforms the elements bound to it)
+    Bone(ElementId, BoneEdit),
+
     /// Performs actions relating to undoing other edits
     Undo(UndoEdit),
 
@@ -54,6 +58,7 @@ impl AnimationEdit {
             Layer(_, edit)                      => edit.used_element_ids(),
             Element(element_ids, element_edit)  => element_ids.iter().cloned().chain(element_edit.used_element_ids()).collect(),
             Motion(element_id, motion_edit)     => iter::once(*element_id).chain(motion_edit.used_element_ids()).collect(),
+            Bone(element_id, bone_edit)         => iter::once(*element_id).chain(bone_edit.used_element_ids()).collect(),
 
             Undo(_)                             |
             SetSize(_, _)                       |
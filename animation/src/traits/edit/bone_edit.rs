@@ -0,0 +1,58 @@
+use super::element_id::*;
+
+use flo_canvas_animation::description::{IkSolver};
+
+use smallvec::*;
+use std::time::{Duration};
+
+///
+/// Represents an edit to a bone in a skeleton
+///
+/// Bones are created by adding a `Vector::Bone` element to a layer. Times in these edits are relative to the start of the
+/// animation, and the bone's rotations are relative to its parent bone.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub enum BoneEdit {
+    /// Sets the parent of this bone (the parent must be a bone in the same keyframe)
+    SetParent(Option<ElementId>),
+
+    /// Moves the start and end point of the bone in its rest pose
+    SetRestPosition((f64, f64), (f64, f64)),
+
+    /// Binds an element to this bone with the specified weight (replacing any existing binding for the same element)
+    Bind(ElementId, f64),
+
+    /// Removes the binding between an element and this bone
+    Unbind(ElementId),
+
+    /// Sets the rotation of the bone relative to its parent at a particular time, in degrees
+    SetRotation(Duration, f64),
+
+    /// Removes the rotation that's set at a particular time
+    RemoveRotation(Duration),
+
+    /// Sets the rotations of this bone and its parents so that the end of this bone reaches towards the target point at the
+    /// specified time. The chain length is the number of bones to rotate, including this one.
+    SolveIk { when: Duration, target: (f64, f64), chain_length: usize, solver: IkSolver }
+}
+
+impl BoneEdit {
+    ///
+    /// Retrieves the element IDs used by this edit
+    ///
+    #[inline]
+    pub fn used_element_ids(&self) -> SmallVec<[ElementId; 4]> {
+        use BoneEdit::*;
+
+        match self {
+            SetParent(Some(parent_id))          => smallvec![*parent_id],
+            SetParent(None)                     => smallvec![],
+            SetRestPosition(_, _)               => smallvec![],
+            Bind(element_id, _)                 => smallvec![*element_id],
+            Unbind(element_id)                  => smallvec![*element_id],
+            SetRotation(_, _)                   => smallvec![],
+            RemoveRotation(_)                   => smallvec![],
+            SolveIk { .. }                      => smallvec![],
+        }
+    }
+}
@@ -98,6 +98,19 @@ pub enum ElementEdit {
     /// If this element is an animation element, replaces the subeffect at the specified address with a new description
     /// (Follow the same rules as `EffectDescription::replace_sub_effect()` when the effect is nested: ie, will preserve the nested contents of the effect)
     ReplaceAnimationEffect(Vec<usize>, EffectDescription),
+
+    /// Copies the elements to the keyframe at the specified time on a layer
+    ///
+    /// This is a deep copy: groups, brush definitions and properties and other attachments are copied along with
+    /// the elements. The copied elements are given the IDs in `new_ids`, in order of the IDs of the elements they're
+    /// copied from. These are assigned when the edit is written to the edit log if the list is empty.
+    CopyTo { layer: u64, when: Duration, new_ids: Vec<ElementId> },
+
+    /// Moves the elements to the keyframe at the specified time on a layer
+    ///
+    /// This copies the elements in the same way as `CopyTo` and then deletes the originals, so the moved elements
+    /// will have the IDs in `new_ids`.
+    MoveTo { layer: u64, when: Duration, new_ids: Vec<ElementId> },
 }
 
 impl ElementEdit {
@@ -127,6 +140,8 @@ impl ElementEdit {
             SetAnimationBaseType(_)         => smallvec![],
             AddAnimationEffect(_)           => smallvec![],
             ReplaceAnimationEffect(_, _)    => smallvec![],
+            CopyTo { new_ids, .. }          => new_ids.iter().cloned().collect(),
+            MoveTo { new_ids, .. }          => new_ids.iter().cloned().collect(),
         }
     }
 }
@@ -1,6 +1,7 @@
 use super::frame_edit::*;
 use super::element_id::*;
 use crate::traits::vector::*;
+use crate::traits::layer_blend_mode::*;
 
 use crate::traits::path::*;
 
@@ -8,6 +9,7 @@ use flo_canvas_animation::description::*;
 
 use smallvec::*;
 use std::sync::*;
+use std::ops::{Range};
 use std::time::Duration;
 
 ///
@@ -59,6 +61,24 @@ pub enum LayerEdit {
     /// Removes a keyframe previously added at a particular duration
     RemoveKeyFrame(Duration),
 
+    /// Moves the keyframe that starts at the first time so that it starts at the second time, along with the elements attached to it
+    ///
+    /// Any keyframe that already exists at the target time is replaced.
+    MoveKeyFrame(Duration, Duration),
+
+    /// Copies the keyframe that starts at the first time to a new keyframe at the second time
+    ///
+    /// The copied elements are given the new element IDs in order of the IDs of the elements they're copied from. These are
+    /// assigned when the edit is written to the edit log if the list is empty. Any keyframe that already exists at the target
+    /// time is replaced.
+    DuplicateKeyFrame(Duration, Duration, Vec<ElementId>),
+
+    /// Moves every keyframe that starts within a time range by an offset in microseconds
+    ///
+    /// A positive offset inserts time into the layer and a negative offset removes it. The edit has no effect if any keyframe
+    /// would be moved to before the start of the animation, or on top of a keyframe that's outside of the range.
+    ShiftKeyFrames(Range<Duration>, i64),
+
     /// Changes the name of this layer
     SetName(String),
 
@@ -67,6 +87,24 @@ pub enum LayerEdit {
 
     /// Sets the layer alpha blend (0.0-1.0)
     SetAlpha(f64),
+
+    /// Hides (true) or shows (false) this layer
+    SetHidden(bool),
+
+    /// Locks (true) or unlocks (false) this layer. Tools will not edit a locked layer
+    SetLocked(bool),
+
+    /// Sets how this layer is combined with the layers behind it
+    SetBlendMode(LayerBlendMode),
+
+    /// Sets whether or not this layer is a folder that can contain other layers
+    SetIsFolder(bool),
+
+    /// Moves this layer into the folder layer with the specified ID, or out of any folder if this is `None`
+    SetParentFolder(Option<u64>),
+
+    /// Collapses (true) or expands (false) a folder layer
+    SetCollapsed(bool),
 }
 
 impl LayerEdit {
@@ -83,13 +121,22 @@ impl LayerEdit {
             CreateElement(_, element_id, _)         => smallvec![*element_id],
             CreateAnimation(_, element_id, _)       => smallvec![*element_id],
             Cut { path: _, when: _, inside_group }  => smallvec![*inside_group],
+            DuplicateKeyFrame(_, _, new_ids)        => new_ids.iter().cloned().collect(),
 
             CreateElementUnattachedToFrame(_, _, _) |
             AddKeyFrame(_)                          |
             RemoveKeyFrame(_)                       |
+            MoveKeyFrame(_, _)                      |
+            ShiftKeyFrames(_, _)                    |
             SetName(_)                              |
             SetOrdering(_)                          |
-            SetAlpha(_)                             => smallvec![]
+            SetAlpha(_)                             |
+            SetHidden(_)                            |
+            SetLocked(_)                            |
+            SetBlendMode(_)                         |
+            SetIsFolder(_)                          |
+            SetParentFolder(_)                      |
+            SetCollapsed(_)                         => smallvec![]
         }
     }
 
@@ -6,7 +6,9 @@ mod element_edit;
 mod element_align;
 mod element_transform;
 mod motion_edit;
+mod bone_edit;
 mod undo_edit;
+mod undo_history;
 mod shape;
 mod retired_edit;
 
@@ -18,6 +20,8 @@ pub use self::element_edit::*;
 pub use self::element_align::*;
 pub use self::element_transform::*;
 pub use self::motion_edit::*;
+pub use self::bone_edit::*;
 pub use self::undo_edit::*;
+pub use self::undo_history::*;
 pub use self::shape::*;
 pub use self::retired_edit::*;
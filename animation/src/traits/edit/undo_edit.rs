@@ -31,6 +31,9 @@ pub enum UndoFailureReason {
 
     /// The animation failed to report the undo success/failure properly
     BadEditingSequence,
+
+    /// The requested node could not be found in the undo history
+    NoSuchHistoryNode,
 }
 
 impl From<StorageError> for UndoFailureReason {
@@ -68,5 +71,12 @@ pub enum UndoEdit {
     FinishAction,
 
     /// Performs a set of undo actions, removing the original actions from the log (this is never serialized to the log)
-    PerformUndo { original_actions: Arc<Vec<AnimationEdit>>, undo_actions: Arc<Vec<AnimationEdit>> }
+    PerformUndo { original_actions: Arc<Vec<AnimationEdit>>, undo_actions: Arc<Vec<AnimationEdit>> },
+
+    /// Names the current point in the undo history so that it can be returned to later (this should be sent on its own)
+    Checkpoint(String),
+
+    /// Undoes and redoes actions until the animation matches the most recent checkpoint with the specified name. This should
+    /// be sent on its own, and is performed once it has been retired.
+    JumpTo(String),
 }
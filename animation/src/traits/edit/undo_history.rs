@@ -0,0 +1,57 @@
+use super::animation_edit::*;
+
+use std::sync::*;
+use std::ops::{Range};
+
+///
+/// A single action in the undo history
+///
+/// The undo history is a tree: undoing some actions and then making a new edit starts a new branch, leaving the
+/// actions that were undone in the history so that they can be returned to later on.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct UndoHistoryNode {
+    /// The ID of this node
+    pub id: u64,
+
+    /// The node that this action was performed after (None if this action was performed at the start of the history)
+    pub parent: Option<u64>,
+
+    /// The name of the checkpoint at this node, if the user has named it
+    pub checkpoint: Option<String>,
+
+    /// Where the edits for this action are in the edit log while the action is applied to the animation
+    pub edit_log_range: Range<usize>,
+
+    /// The edits that will reverse this action (stored so that actions loaded from storage can be undone without having
+    /// to replay the edit log to regenerate them)
+    pub reverse_edits: Arc<Vec<AnimationEdit>>,
+
+    /// The edits for this action when it has been undone (undoing an action removes its edits from the edit log, so
+    /// they're kept here so that it can be redone later on). None while the action is applied.
+    pub undone_edits: Option<Arc<Vec<AnimationEdit>>>,
+}
+
+///
+/// The undo history for an animation, or a set of changes to it
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct UndoHistory {
+    /// The nodes in the history
+    pub nodes: Vec<UndoHistoryNode>,
+
+    /// The node that matches the current state of the animation (None if no actions from the history are applied)
+    pub position: Option<u64>,
+}
+
+impl UndoHistory {
+    ///
+    /// Creates an empty undo history
+    ///
+    pub fn empty() -> UndoHistory {
+        UndoHistory {
+            nodes:      vec![],
+            position:   None
+        }
+    }
+}
@@ -9,14 +9,40 @@ pub enum GroupType {
     /// Elements are added together (the path properties of the first element are used for all elements)
     Added,
 
-    /*
-    /// Elements after the first element are subtracted from the first element
+    /// Elements after the first element are subtracted from the first element (the path properties of the first element are used for the result)
     Subtracted,
 
-    /// The first element is intersected with future elements
+    /// The first element is intersected with future elements (the first element is rendered as normal, clipped to the other elements)
     Masked,
 
-    /// The first element is subtracted from future elements
+    /// The first element is subtracted from future elements (the future elements are rendered as normal, with the first element cut out of them)
     InvertedMask
-    */
+}
+
+impl GroupType {
+    ///
+    /// True if this group type generates a single path that's rendered using the properties of the first element in the group
+    ///
+    pub fn is_single_path(&self) -> bool {
+        match self {
+            GroupType::Normal       => false,
+            GroupType::Added        => true,
+            GroupType::Subtracted   => true,
+            GroupType::Masked       => false,
+            GroupType::InvertedMask => false
+        }
+    }
+
+    ///
+    /// True if this group type masks some of its elements using the others
+    ///
+    pub fn is_mask(&self) -> bool {
+        match self {
+            GroupType::Normal       => false,
+            GroupType::Added        => false,
+            GroupType::Subtracted   => false,
+            GroupType::Masked       => true,
+            GroupType::InvertedMask => true
+        }
+    }
 }
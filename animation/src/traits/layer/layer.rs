@@ -2,6 +2,7 @@ use super::vector::*;
 use super::super::edit::*;
 use super::super::frame::*;
 use super::super::cache::*;
+use super::super::layer_blend_mode::*;
 
 use std::u32;
 use std::sync::*;
@@ -27,6 +28,36 @@ pub trait Layer : Send+Sync {
     ///
     fn alpha(&self) -> f64;
 
+    ///
+    /// True if this layer is hidden
+    ///
+    fn is_hidden(&self) -> bool;
+
+    ///
+    /// True if this layer is locked against editing
+    ///
+    fn is_locked(&self) -> bool;
+
+    ///
+    /// How this layer is combined with the layers behind it
+    ///
+    fn blend_mode(&self) -> LayerBlendMode;
+
+    ///
+    /// True if this layer is a folder that can contain other layers
+    ///
+    fn is_folder(&self) -> bool;
+
+    ///
+    /// True if this layer is a folder that is collapsed in the timeline
+    ///
+    fn is_collapsed(&self) -> bool;
+
+    ///
+    /// The ID of the folder layer containing this layer, if there is one
+    ///
+    fn parent_folder(&self) -> Option<u64>;
+
     ///
     /// The types of edit that are supported by this layer
     ///
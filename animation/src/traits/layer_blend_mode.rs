@@ -0,0 +1,46 @@
+use flo_canvas::*;
+
+///
+/// How a layer is combined with the layers behind it
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LayerBlendMode {
+    /// The layer is drawn over the layers behind it
+    Normal,
+
+    /// The colours of the layer are multiplied with the colours behind it (darkening the result)
+    Multiply,
+
+    /// The inverse colours of the layer are multiplied with the inverse colours behind it (lightening the result)
+    Screen,
+
+    /// Multiplies the dark areas and screens the light areas of the layers behind this one
+    Overlay,
+
+    /// The colours of the layer are added to the colours behind it
+    Add
+}
+
+impl Default for LayerBlendMode {
+    fn default() -> LayerBlendMode {
+        LayerBlendMode::Normal
+    }
+}
+
+impl LayerBlendMode {
+    ///
+    /// The blend mode to use when drawing a layer with this blend mode on a canvas
+    ///
+    /// The canvas has no overlay or additive blend modes, so this returns `None` for these: layers using them need to be
+    /// rendered offscreen and blended with the layers behind them instead.
+    ///
+    pub fn canvas_blend_mode(&self) -> Option<BlendMode> {
+        match self {
+            LayerBlendMode::Normal      => Some(BlendMode::SourceOver),
+            LayerBlendMode::Multiply    => Some(BlendMode::Multiply),
+            LayerBlendMode::Screen      => Some(BlendMode::Screen),
+            LayerBlendMode::Overlay     => None,
+            LayerBlendMode::Add         => None,
+        }
+    }
+}
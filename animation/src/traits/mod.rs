@@ -16,6 +16,7 @@ mod motion;
 mod cache;
 mod combine_result;
 mod group_type;
+mod layer_blend_mode;
 mod fill_option;
 
 pub use self::edit::*;
@@ -36,4 +37,5 @@ pub use self::motion::*;
 pub use self::cache::*;
 pub use self::combine_result::*;
 pub use self::group_type::*;
+pub use self::layer_blend_mode::*;
 pub use self::fill_option::*;
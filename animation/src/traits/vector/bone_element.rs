@@ -0,0 +1,302 @@
+use super::vector::*;
+use super::properties::*;
+use super::control_point::*;
+use super::vector_element::*;
+use super::path_conversion_options::*;
+use super::super::path::*;
+use super::super::edit::*;
+
+use flo_canvas::*;
+use flo_curves::*;
+
+use std::sync::*;
+use std::time::{Duration};
+
+pub const BONE_OUTLINE:         Color = Color::Rgba(0.8, 0.5, 0.1, 0.8);
+pub const BONE_OUTLINE_DARK:    Color = Color::Rgba(0.4, 0.25, 0.05, 0.5);
+
+///
+/// Represents a bone in a skeleton
+///
+/// Bones are not rendered in the final animation: instead, the elements that are bound to them are deformed as the
+/// bones are rotated. Bones rotate around their start point, relative to their parent bone.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct BoneElement {
+    /// The ID of this bone
+    id: ElementId,
+
+    /// The parent bone, if there is one
+    parent: Option<ElementId>,
+
+    /// The start point of this bone in its rest pose (this is the joint it rotates around)
+    start: (f64, f64),
+
+    /// The end point of this bone in its rest pose
+    end: (f64, f64),
+
+    /// The elements bound to this bone, and the weight of each binding
+    bindings: Arc<Vec<(ElementId, f64)>>,
+
+    /// The rotation in degrees of this bone relative to its parent, at various times relative to the start of the keyframe
+    rotations: Arc<Vec<(Duration, f64)>>
+}
+
+impl BoneElement {
+    ///
+    /// Creates a new bone with no bindings or rotations
+    ///
+    pub fn new(id: ElementId, parent: Option<ElementId>, start: (f64, f64), end: (f64, f64)) -> BoneElement {
+        BoneElement {
+            id:         id,
+            parent:     parent,
+            start:      start,
+            end:        end,
+            bindings:   Arc::new(vec![]),
+            rotations:  Arc::new(vec![])
+        }
+    }
+
+    ///
+    /// Creates a bone with a full set of bindings and rotations
+    ///
+    pub fn with_bindings_and_rotations(id: ElementId, parent: Option<ElementId>, start: (f64, f64), end: (f64, f64), bindings: Vec<(ElementId, f64)>, rotations: Vec<(Duration, f64)>) -> BoneElement {
+        BoneElement {
+            id:         id,
+            parent:     parent,
+            start:      start,
+            end:        end,
+            bindings:   Arc::new(bindings),
+            rotations:  Arc::new(rotations)
+        }
+    }
+
+    ///
+    /// The parent of this bone
+    ///
+    pub fn parent(&self) -> Option<ElementId> {
+        self.parent
+    }
+
+    ///
+    /// The start point of this bone in its rest pose
+    ///
+    pub fn start(&self) -> (f64, f64) {
+        self.start
+    }
+
+    ///
+    /// The end point of this bone in its rest pose
+    ///
+    pub fn end(&self) -> (f64, f64) {
+        self.end
+    }
+
+    ///
+    /// The elements that are bound to this bone, along with the weight of the binding
+    ///
+    pub fn bindings(&self) -> Arc<Vec<(ElementId, f64)>> {
+        Arc::clone(&self.bindings)
+    }
+
+    ///
+    /// The rotations of this bone (in degrees, relative to the parent bone), in time order
+    ///
+    pub fn rotations(&self) -> Arc<Vec<(Duration, f64)>> {
+        Arc::clone(&self.rotations)
+    }
+
+    ///
+    /// Returns a copy of this bone with a new parent
+    ///
+    pub fn with_parent(&self, parent: Option<ElementId>) -> BoneElement {
+        BoneElement {
+            parent: parent,
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this bone with a new rest position
+    ///
+    pub fn with_position(&self, start: (f64, f64), end: (f64, f64)) -> BoneElement {
+        BoneElement {
+            start:  start,
+            end:    end,
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this bone with an element bound to it (replacing any existing binding for that element)
+    ///
+    pub fn with_binding(&self, element_id: ElementId, weight: f64) -> BoneElement {
+        let mut bindings = (*self.bindings).clone();
+
+        match bindings.iter().position(|(bound_id, _)| *bound_id == element_id) {
+            Some(existing_idx)  => { bindings[existing_idx] = (element_id, weight); }
+            None                => { bindings.push((element_id, weight)); }
+        }
+
+        BoneElement {
+            bindings: Arc::new(bindings),
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this bone with an element unbound from it
+    ///
+    pub fn without_binding(&self, element_id: ElementId) -> BoneElement {
+        let bindings = self.bindings.iter()
+            .filter(|(bound_id, _)| *bound_id != element_id)
+            .cloned()
+            .collect();
+
+        BoneElement {
+            bindings: Arc::new(bindings),
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this bone with a rotation set at a particular time
+    ///
+    pub fn with_rotation(&self, when: Duration, degrees: f64) -> BoneElement {
+        let mut rotations = (*self.rotations).clone();
+
+        match rotations.binary_search_by(|(rotation_time, _)| rotation_time.cmp(&when)) {
+            Ok(existing_idx)    => { rotations[existing_idx] = (when, degrees); }
+            Err(insert_idx)     => { rotations.insert(insert_idx, (when, degrees)); }
+        }
+
+        BoneElement {
+            rotations: Arc::new(rotations),
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Returns a copy of this bone with the rotation at a particular time removed
+    ///
+    pub fn without_rotation(&self, when: Duration) -> BoneElement {
+        let rotations = self.rotations.iter()
+            .filter(|(rotation_time, _)| *rotation_time != when)
+            .cloned()
+            .collect();
+
+        BoneElement {
+            rotations: Arc::new(rotations),
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// The rotation that's set at exactly the specified time, if there is one
+    ///
+    pub fn rotation_at(&self, when: Duration) -> Option<f64> {
+        self.rotations.iter()
+            .filter(|(rotation_time, _)| *rotation_time == when)
+            .map(|(_, degrees)| *degrees)
+            .nth(0)
+    }
+}
+
+impl VectorElement for BoneElement {
+    ///
+    /// The ID of this element
+    ///
+    fn id(&self) -> ElementId {
+        self.id
+    }
+
+    ///
+    /// Modifies this element to have a new ID
+    ///
+    fn set_id(&mut self, new_id: ElementId) {
+        self.id = new_id
+    }
+
+    ///
+    /// Retrieves the paths for this element, if there are any
+    ///
+    fn to_path(&self, _properties: &VectorProperties, _options: PathConversion) -> Option<Vec<Path>> {
+        // Not a path element
+        None
+    }
+
+    ///
+    /// Renders this vector element
+    ///
+    fn render_static(&self, _gc: &mut dyn GraphicsContext, _properties: &VectorProperties, _when: Duration) {
+        // Bones are not visible in the animation
+    }
+
+    ///
+    /// Bones are selected by clicking close to the line between their start and end points
+    ///
+    fn is_selected_with_point(&self, properties: &VectorProperties, x: f64, y: f64) -> Option<i32> {
+        let start       = properties.transform_point(&Coord2(self.start.0, self.start.1));
+        let end         = properties.transform_point(&Coord2(self.end.0, self.end.1));
+        let point       = Coord2(x, y);
+
+        let along       = end - start;
+        let length_sq   = along.dot(&along);
+        let t           = if length_sq > 0.0 { ((point - start).dot(&along) / length_sq).max(0.0).min(1.0) } else { 0.0 };
+        let distance    = point.distance_to(&(start + along * t));
+
+        if distance < 4.0 {
+            Some(150)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Renders the bone as a line with a circle around the joint it rotates around
+    ///
+    fn render_overlay(&self, gc: &mut dyn GraphicsContext, properties: &VectorProperties, _when: Duration) {
+        let Coord2(x1, y1) = properties.transform_point(&Coord2(self.start.0, self.start.1));
+        let Coord2(x2, y2) = properties.transform_point(&Coord2(self.end.0, self.end.1));
+
+        gc.new_path();
+        gc.circle(x1 as _, y1 as _, 4.0);
+        gc.move_to(x1 as _, y1 as _);
+        gc.line_to(x2 as _, y2 as _);
+
+        gc.line_width_pixels(3.0);
+        gc.stroke_color(BONE_OUTLINE_DARK);
+        gc.stroke();
+
+        gc.line_width_pixels(1.0);
+        gc.stroke_color(BONE_OUTLINE);
+        gc.stroke();
+    }
+
+    ///
+    /// Fetches the control points for this element
+    ///
+    fn control_points(&self, properties: &VectorProperties) -> Vec<ControlPoint> {
+        let Coord2(x1, y1) = properties.transform_point(&Coord2(self.start.0, self.start.1));
+        let Coord2(x2, y2) = properties.transform_point(&Coord2(self.end.0, self.end.1));
+
+        vec![ControlPoint::BezierPoint(x1, y1), ControlPoint::BezierPoint(x2, y2)]
+    }
+
+    ///
+    /// Creates a new vector element from this one with the control points updated to the specified set of new values
+    ///
+    /// The vector here specifies the updated position for each control point in control_points
+    ///
+    fn with_adjusted_control_points(&self, new_positions: Vec<(f32, f32)>, properties: &VectorProperties) -> Vector {
+        let inverse_properties  = properties.with_inverse_transformation().expect("Invertable transformation");
+        let mut positions       = new_positions.into_iter()
+            .map(|(x, y)| inverse_properties.transform_point(&Coord2(x as _, y as _)))
+            .map(|Coord2(x, y)| (x, y));
+
+        let start               = positions.next().unwrap_or(self.start);
+        let end                 = positions.next().unwrap_or(self.end);
+
+        Vector::Bone(self.with_position(start, end))
+    }
+}
@@ -54,7 +54,7 @@ impl GroupElement {
     ///
     /// Sets a hint path for this element
     ///
-    /// For certain group types that generate an output path with a single property (eg, GroupType::Added), this path will be
+    /// For group types that perform path arithmetic (eg, GroupType::Added or GroupType::Masked), this path will be
     /// used instead of recomputing the path arithmetic operation represented by this group
     ///
     pub fn set_hint_path(&mut self, hint_path: Arc<Vec<Path>>) {
@@ -72,12 +72,19 @@ impl GroupElement {
     /// Renders the contents of this group in 'normal' mode
     ///
     fn render_normal(&self, gc: &mut dyn GraphicsContext, properties: &VectorProperties, when: Duration) {
+        self.render_elements(gc, &self.grouped_elements, properties, when);
+    }
+
+    ///
+    /// Renders a set of elements from this group, using their attachments to determine their properties
+    ///
+    fn render_elements(&self, gc: &mut dyn GraphicsContext, elements: &[Vector], properties: &VectorProperties, when: Duration) {
         // Properties update internally to the group
         let default_properties      = Arc::new(properties.clone());
         let mut properties          = Arc::clone(&default_properties);
         let mut active_attachments  = vec![];
 
-        for elem in self.grouped_elements.iter() {
+        for elem in elements.iter() {
             // Retrieve the attachments for the element
             let element_attachments     = (properties.retrieve_attachments)(elem.id());
 
@@ -101,37 +108,111 @@ impl GroupElement {
     }
 
     ///
-    /// Returns the added path for this element
+    /// Retrieves the paths for a set of elements, divided into subpaths ready for performing path arithmetic
+    ///
+    fn subpaths_for_elements<'a, Elements: IntoIterator<Item=&'a Vector>>(elements: Elements, properties: &VectorProperties) -> Vec<Vec<Path>> {
+        elements.into_iter()
+            .flat_map(|elem| elem.to_path(properties, PathConversion::RemoveInteriorPoints))
+            .map(|paths| paths.into_iter().flat_map(|path| path.to_subpaths()).collect::<Vec<_>>())
+            .filter(|subpaths| subpaths.len() > 0)
+            .collect()
+    }
+
+    ///
+    /// Adds together a set of subpaths, returning the result
     ///
-    fn added_path(&self, properties: &VectorProperties) -> Vec<Path> {
+    fn add_subpaths(subpaths: &Vec<Vec<Path>>) -> Vec<Path> {
+        match subpaths.len() {
+            0 => vec![],
+            1 => subpaths[0].clone(),
+            _ => path_add_chain::<Path>(subpaths, 0.01)
+        }
+    }
+
+    ///
+    /// Returns the path generated by performing the path arithmetic operation for this group
+    ///
+    /// For `Normal` groups, this is just the paths of all the elements
+    ///
+    fn combined_path(&self, properties: &VectorProperties) -> Vec<Path> {
         if let Some(hint_path) = self.hint_path.as_ref() {
             // If a hint path has been set we can use this as the short-circuit for this path
-            (**hint_path).clone()
-        } else {
-            // Get the paths for this rendering
-            let paths = self.grouped_elements.iter()
-                .flat_map(|elem| elem.to_path(properties, PathConversion::RemoveInteriorPoints))
-                .flat_map(|paths| paths.into_iter().map(|path| path.to_subpaths()))
-                .collect::<Vec<_>>();
-
-            // Render if there are more than one path
-            if paths.len() > 0 {
+            return (**hint_path).clone();
+        }
+
+        // Nothing to do if there are no elements in this group
+        if self.grouped_elements.len() == 0 {
+            return vec![];
+        }
+
+        // The first element is the one that the rest of the elements in the group are combined with
+        let first_element   = &self.grouped_elements[0];
+        let other_elements  = self.grouped_elements.iter().skip(1);
+
+        let paths: Vec<Path> = match self.group_type {
+            GroupType::Normal       => {
+                self.grouped_elements.iter()
+                    .flat_map(|elem| elem.to_path(properties, PathConversion::RemoveInteriorPoints))
+                    .flatten()
+                    .collect()
+            }
+
+            GroupType::Added        => {
                 // Add the paths into a single path
-                let paths = path_add_chain::<Path>(&paths, 0.01);
-                vec![Path::from_paths(&paths)]
-            } else {
-                vec![]
+                let paths = Self::subpaths_for_elements(self.grouped_elements.iter(), properties);
+                Self::add_subpaths(&paths)
+            }
+
+            GroupType::Subtracted   => {
+                // Everything after the first element is cut out of the first element
+                let first_path  = Self::add_subpaths(&Self::subpaths_for_elements(vec![first_element], properties));
+                let other_path  = Self::add_subpaths(&Self::subpaths_for_elements(other_elements, properties));
+
+                if first_path.len() == 0 || other_path.len() == 0 {
+                    first_path
+                } else {
+                    path_sub(&first_path, &other_path, 0.01)
+                }
+            }
+
+            GroupType::Masked       => {
+                // Only the parts of the first element that are inside the other elements are kept
+                let first_path  = Self::add_subpaths(&Self::subpaths_for_elements(vec![first_element], properties));
+                let mask_path   = Self::add_subpaths(&Self::subpaths_for_elements(other_elements, properties));
+
+                if first_path.len() == 0 || mask_path.len() == 0 {
+                    vec![]
+                } else {
+                    path_intersect(&first_path, &mask_path, 0.01)
+                }
+            }
+
+            GroupType::InvertedMask => {
+                // The first element is cut out of the other elements
+                let mask_path   = Self::add_subpaths(&Self::subpaths_for_elements(vec![first_element], properties));
+                let other_path  = Self::add_subpaths(&Self::subpaths_for_elements(other_elements, properties));
+
+                if mask_path.len() == 0 || other_path.len() == 0 {
+                    other_path
+                } else {
+                    path_sub(&other_path, &mask_path, 0.01)
+                }
             }
+        };
+
+        // Combined paths are merged into a single path
+        if paths.len() > 0 && self.group_type != GroupType::Normal {
+            vec![Path::from_paths(&paths)]
+        } else {
+            paths
         }
     }
 
     ///
-    /// Renders the contents of this group in 'added' mode
+    /// Applies the transformations from a set of properties to a set of paths
     ///
-    fn render_added(&self, gc: &mut dyn GraphicsContext, properties: &VectorProperties) {
-        let paths = self.added_path(properties);
-
-        let paths = if properties.transformations.len() > 0 {
+    fn transform_paths(paths: Vec<Path>, properties: &VectorProperties) -> Vec<Path> {
+        if properties.transformations.len() > 0 {
             paths.into_iter()
                 .map(|mut path| {
                     for transform in properties.transformations.iter() {
@@ -142,13 +223,58 @@ impl GroupElement {
                 .collect()
         } else {
             paths
-        };
+        }
+    }
+
+    ///
+    /// Renders the contents of this group in a mode that generates a single path (eg, 'added' or 'subtracted' mode)
+    ///
+    fn render_combined(&self, gc: &mut dyn GraphicsContext, properties: &VectorProperties) {
+        let paths = self.combined_path(properties);
+        let paths = Self::transform_paths(paths, properties);
 
         gc.draw_list(properties.brush.prepare_to_render(&properties.brush_properties));
         paths.into_iter()
             .for_each(|path| gc.draw_list(properties.brush.render_path(&properties.brush_properties, &path)));
     }
 
+    ///
+    /// Renders the contents of this group in one of the masked modes
+    ///
+    /// The visible elements are rendered as for a normal group, but are clipped to the combined path for this group
+    ///
+    fn render_masked(&self, gc: &mut dyn GraphicsContext, properties: &VectorProperties, when: Duration) {
+        // Nothing to render if the group is empty
+        if self.grouped_elements.len() == 0 {
+            return;
+        }
+
+        // The clip path is the visible region of the group
+        let clip_path = self.combined_path(properties);
+        let clip_path = Self::transform_paths(clip_path, properties);
+
+        if clip_path.len() == 0 {
+            return;
+        }
+
+        // For masked groups, the first element is visible, and for inverted masks, the remaining elements are visible
+        let visible_elements = match self.group_type {
+            GroupType::InvertedMask => &self.grouped_elements[1..],
+            _                       => &self.grouped_elements[0..1]
+        };
+
+        // Clip to the combined path
+        gc.push_state();
+        gc.new_path();
+        clip_path.iter().for_each(|path| path.to_drawing().for_each(|draw| gc.draw(draw)));
+        gc.clip();
+
+        // Render the visible elements
+        self.render_elements(gc, visible_elements, properties, when);
+
+        gc.pop_state();
+    }
+
     ///
     /// The number of elements in this group
     ///
@@ -190,10 +316,13 @@ impl VectorElement for GroupElement {
     /// Retrieves the paths for this element, if there are any
     ///
     fn to_path(&self, properties: &VectorProperties, options: PathConversion) -> Option<Vec<Path>> {
-        // With the added path type we can assume that the interior points are already removed so there's no need to apply the options
+        // With the path arithmetic group types we can assume that the interior points are already removed so there's no need to apply the options
         let path = match self.group_type {
-            GroupType::Normal   => Some(self.grouped_elements.iter().flat_map(|elem| elem.to_path(properties, options)).flatten().collect()),
-            GroupType::Added    => Some(self.added_path(properties))
+            GroupType::Normal       => Some(self.grouped_elements.iter().flat_map(|elem| elem.to_path(properties, options)).flatten().collect()),
+            GroupType::Added        |
+            GroupType::Subtracted   |
+            GroupType::Masked       |
+            GroupType::InvertedMask => Some(self.combined_path(properties))
         };
 
         // Apply any transformations in the properties
@@ -221,8 +350,11 @@ impl VectorElement for GroupElement {
     ///
     fn render_static(&self, gc: &mut dyn GraphicsContext, properties: &VectorProperties, when: Duration) {
         match self.group_type {
-            GroupType::Normal   => self.render_normal(gc, properties, when),
-            GroupType::Added    => self.render_added(gc, properties)
+            GroupType::Normal       => self.render_normal(gc, properties, when),
+            GroupType::Added        => self.render_combined(gc, properties),
+            GroupType::Subtracted   => self.render_combined(gc, properties),
+            GroupType::Masked       => self.render_masked(gc, properties, when),
+            GroupType::InvertedMask => self.render_masked(gc, properties, when)
         }
     }
 
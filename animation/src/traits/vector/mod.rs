@@ -5,6 +5,8 @@ mod vector_type;
 mod path_element;
 mod control_point;
 mod error_element;
+mod bone_element;
+mod tween_element;
 mod brush_element;
 mod group_element;
 mod shape_element;
@@ -23,6 +25,8 @@ pub use self::vector_type::*;
 pub use self::path_element::*;
 pub use self::control_point::*;
 pub use self::error_element::*;
+pub use self::bone_element::*;
+pub use self::tween_element::*;
 pub use self::brush_element::*;
 pub use self::group_element::*;
 pub use self::shape_element::*;
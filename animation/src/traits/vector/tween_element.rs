@@ -0,0 +1,99 @@
+use super::vector::*;
+use super::properties::*;
+use super::control_point::*;
+use super::vector_element::*;
+use super::path_conversion_options::*;
+use super::super::edit::*;
+use super::super::path::*;
+
+use flo_canvas::*;
+
+use std::sync::*;
+use std::time::Duration;
+
+///
+/// The tween element is attached to an element to morph it into the shape of an element in the following keyframe
+///
+/// Hints are pairs of points: a point on the element this is attached to, and the point on the target element that
+/// it should move to.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct TweenElement {
+    id:     ElementId,
+    target: ElementId,
+    hints:  Arc<Vec<((f64, f64), (f64, f64))>>
+}
+
+impl TweenElement {
+    ///
+    /// Creates a new tween element that morphs into the specified target element
+    ///
+    pub fn new(id: ElementId, target: ElementId, hints: Vec<((f64, f64), (f64, f64))>) -> TweenElement {
+        TweenElement {
+            id:     id,
+            target: target,
+            hints:  Arc::new(hints)
+        }
+    }
+
+    ///
+    /// The element in the following keyframe that this tween changes into
+    ///
+    pub fn target(&self) -> ElementId {
+        self.target
+    }
+
+    ///
+    /// The pairs of points on the original and target shapes that should be matched with each other
+    ///
+    pub fn hints(&self) -> Arc<Vec<((f64, f64), (f64, f64))>> {
+        Arc::clone(&self.hints)
+    }
+}
+
+impl VectorElement for TweenElement {
+    ///
+    /// The ID of this element
+    ///
+    fn id(&self) -> ElementId {
+        self.id
+    }
+
+    ///
+    /// Modifies this element to have a new ID
+    ///
+    fn set_id(&mut self, new_id: ElementId) {
+        self.id = new_id
+    }
+
+    ///
+    /// Retrieves the paths for this element, if there are any
+    ///
+    fn to_path(&self, _properties: &VectorProperties, _options: PathConversion) -> Option<Vec<Path>> {
+        // Not a path element
+        None
+    }
+
+    ///
+    /// Renders this vector element
+    ///
+    fn render_static(&self, _gc: &mut dyn GraphicsContext, _properties: &VectorProperties, _when: Duration) {
+        // Tweens are rendered by the keyframe, which knows about the target element
+    }
+
+    ///
+    /// Fetches the control points for this element
+    ///
+    fn control_points(&self, _properties: &VectorProperties) -> Vec<ControlPoint> {
+        vec![]
+    }
+
+    ///
+    /// Creates a new vector element from this one with the control points updated to the specified set of new values
+    ///
+    /// The vector here specifies the updated position for each control point in control_points
+    ///
+    fn with_adjusted_control_points(&self, _new_positions: Vec<(f32, f32)>, _properties: &VectorProperties) -> Vector {
+        Vector::Tween(self.clone())
+    }
+}
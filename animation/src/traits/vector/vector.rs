@@ -1,4 +1,5 @@
 use super::path_element::*;
+use super::bone_element::*;
 use super::brush_element::*;
 use super::shape_element::*;
 use super::group_element::*;
@@ -55,6 +56,12 @@ pub enum Vector {
     /// An element representing an animation region for the keyframe
     AnimationRegion(AnimationElement),
 
+    /// A bone in a skeleton, which deforms the elements that are bound to it
+    Bone(BoneElement),
+
+    /// Attached to an element to morph it into the shape of an element in the following keyframe
+    Tween(TweenElement),
+
     /// Element exists but could not be loaded from the file
     Error
 }
@@ -203,6 +210,8 @@ impl DerefMut for Vector {
             Group(elem)                     => elem,
             Transformation(elem)            => elem,
             AnimationRegion(elem)           => elem,
+            Bone(elem)                      => elem,
+            Tween(elem)                     => elem,
             Error                           => panic!("Cannot edit an error element")
         }
     }
@@ -227,6 +236,8 @@ impl Deref for Vector {
             Motion(elem)                    => elem,
             Group(elem)                     => elem,
             AnimationRegion(elem)           => elem,
+            Bone(elem)                      => elem,
+            Tween(elem)                     => elem,
             Transformation(transform)       => transform,
             Error                           => &*ERROR_ELEMENT
         }
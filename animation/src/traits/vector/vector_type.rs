@@ -32,6 +32,12 @@ pub enum VectorType {
     /// Represents a region of the canvas that has an animation effect applied to it
     AnimationRegion,
 
+    /// A bone in a skeleton
+    Bone,
+
+    /// Morphs an element into the shape of an element in the following keyframe
+    Tween,
+
     /// Element that exists but could not be loaded
     Error
 }
@@ -51,6 +57,8 @@ impl From<&Vector> for VectorType {
             Group(_)                        => VectorType::Group,
             Transformation(_)               => VectorType::Transformation,
             AnimationRegion(_)              => VectorType::AnimationRegion,
+            Bone(_)                         => VectorType::Bone,
+            Tween(_)                        => VectorType::Tween,
             Error                           => VectorType::Error
         }
     }
@@ -438,6 +438,80 @@ fn double_redo() {
     });
 }
 
+#[test]
+fn redo_copy_then_edit_copy() {
+    executor::block_on(async {
+        use AnimationEdit::*;
+        use LayerEdit::*;
+
+        // Create the animation
+        let in_memory_store = InMemoryStorage::new();
+        let animation       = create_animation_editor(move |commands| in_memory_store.get_responses(commands).boxed());
+        let animation       = UndoableAnimation::new(animation);
+
+        // Setup a layer with a single element
+        animation.edit().publish(Arc::new(vec![
+            AddNewLayer(0),
+            Layer(0, LayerEdit::AddKeyFrame(Duration::from_millis(0))),
+            Layer(0, LayerEdit::AddKeyFrame(Duration::from_millis(20000))),
+            Layer(0, Path(Duration::from_millis(0), PathEdit::SelectBrush(ElementId::Assigned(100), BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw))),
+            Layer(0, Path(Duration::from_millis(0), PathEdit::BrushProperties(ElementId::Assigned(101), BrushProperties::new()))),
+            Layer(0, Path(Duration::from_millis(0), PathEdit::CreatePath(ElementId::Assigned(0), circle_path((100.0, 100.0), 50.0)))),
+            Undo(UndoEdit::FinishAction),
+        ])).await;
+
+        // Copy the element to the second keyframe
+        animation.edit().publish(Arc::new(vec![
+            Element(vec![ElementId::Assigned(0)], ElementEdit::CopyTo { layer: 0, when: Duration::from_millis(20000), new_ids: vec![] }),
+            Undo(UndoEdit::FinishAction),
+        ])).await;
+        animation.edit().when_empty().await;
+
+        let frame       = animation.get_layer_with_id(0).unwrap().get_frame_at_time(Duration::from_millis(20000));
+        let copy_ids    = frame.vector_elements().unwrap().map(|elem| elem.id()).collect::<Vec<_>>();
+
+        assert!(copy_ids.len() == 1);
+        assert!(copy_ids[0] != ElementId::Assigned(0));
+
+        // Undo the copy
+        let timeout     = Delay::new(Duration::from_secs(10));
+        let undo_result = match select(animation.undo().boxed(), timeout).await {
+            Either::Right(_)        => { assert!(false, "Timed out"); unimplemented!() }
+            Either::Left(result)    => result.0,
+        };
+        assert!(undo_result.is_ok());
+
+        let frame       = animation.get_layer_with_id(0).unwrap().get_frame_at_time(Duration::from_millis(20000));
+        assert!(frame.vector_elements().unwrap().count() == 0);
+
+        // Redo the copy: the copy should get the same ID as before
+        let timeout     = Delay::new(Duration::from_secs(10));
+        let redo_result = match select(animation.redo().boxed(), timeout).await {
+            Either::Right(_)        => { assert!(false, "Timed out"); unimplemented!() }
+            Either::Left(result)    => result.0,
+        };
+        assert!(redo_result.is_ok());
+
+        let frame       = animation.get_layer_with_id(0).unwrap().get_frame_at_time(Duration::from_millis(20000));
+        let redo_ids    = frame.vector_elements().unwrap().map(|elem| elem.id()).collect::<Vec<_>>();
+
+        assert!(redo_ids == copy_ids);
+
+        // Edits to the copy should affect the copy and not the original
+        animation.edit().publish(Arc::new(vec![
+            Element(copy_ids.clone(), ElementEdit::Delete),
+            Undo(UndoEdit::FinishAction),
+        ])).await;
+        animation.edit().when_empty().await;
+
+        let copy_frame      = animation.get_layer_with_id(0).unwrap().get_frame_at_time(Duration::from_millis(20000));
+        let original_frame  = animation.get_layer_with_id(0).unwrap().get_frame_at_time(Duration::from_millis(0));
+
+        assert!(copy_frame.vector_elements().unwrap().count() == 0);
+        assert!(original_frame.vector_elements().unwrap().map(|elem| elem.id()).collect::<Vec<_>>() == vec![ElementId::Assigned(0)]);
+    });
+}
+
 #[test]
 fn follow_undo_log_size() {
     executor::block_on(async {
@@ -640,3 +714,176 @@ fn double_undo_with_errant_finish_action() {
         assert!(elements.len() == 0);
     });
 }
+
+#[test]
+fn jump_to_undone_branch() {
+    executor::block_on(async {
+        use AnimationEdit::*;
+
+        // Create the animation
+        let in_memory_store = InMemoryStorage::new();
+        let animation       = create_animation_editor(move |commands| in_memory_store.get_responses(commands).boxed());
+        let animation       = UndoableAnimation::new(animation);
+
+        // Add two layers, then undo the second one and add a different layer in its place
+        animation.edit().publish(Arc::new(vec![AddNewLayer(0), Undo(UndoEdit::FinishAction)])).await;
+        animation.edit().publish(Arc::new(vec![AddNewLayer(1), Undo(UndoEdit::FinishAction)])).await;
+        animation.edit().when_empty().await;
+
+        assert!(animation.undo().await.is_ok());
+
+        animation.edit().publish(Arc::new(vec![AddNewLayer(2), Undo(UndoEdit::FinishAction)])).await;
+        animation.edit().when_empty().await;
+        assert!(animation.get_layer_ids() == vec![0, 2]);
+
+        // The branch with layer 1 should still be in the history, so we can jump back to it
+        let timeout     = Delay::new(Duration::from_secs(10));
+        let jump_result = match select(animation.jump_to(Some(1)).boxed(), timeout).await {
+            Either::Right(_)        => { assert!(false, "Timed out"); unimplemented!() }
+            Either::Left(result)    => result.0,
+        };
+        println!("{:?}", jump_result);
+        assert!(jump_result.is_ok());
+
+        assert!(animation.get_layer_ids() == vec![0, 1]);
+
+        let history = animation.history();
+        assert!(history.nodes.len() == 3);
+        assert!(history.nodes[2].parent == Some(0));
+        assert!(history.position == Some(1));
+    });
+}
+
+#[test]
+fn undo_history_is_stored_with_animation() {
+    executor::block_on(async {
+        use AnimationEdit::*;
+
+        // Create the animation
+        let in_memory_store = Arc::new(InMemoryStorage::new());
+        let store           = Arc::clone(&in_memory_store);
+        let animation       = create_animation_editor(move |commands| store.get_responses(commands).boxed());
+        let animation       = UndoableAnimation::new(animation);
+
+        animation.edit().publish(Arc::new(vec![AddNewLayer(0), Undo(UndoEdit::FinishAction)])).await;
+        animation.edit().publish(Arc::new(vec![AddNewLayer(1), Undo(UndoEdit::FinishAction)])).await;
+        animation.edit().publish(Arc::new(vec![Undo(UndoEdit::Checkpoint("Second layer".to_string()))])).await;
+        animation.edit().when_empty().await;
+
+        // Open the same storage again (the history is written in the background, so wait for it to arrive)
+        let store           = Arc::clone(&in_memory_store);
+        let reopened        = create_animation_editor(move |commands| store.get_responses(commands).boxed());
+        let mut history     = UndoHistory::empty();
+
+        for _ in 0..100 {
+            history = reopened.read_undo_history();
+            if history.nodes.len() == 2 && history.nodes[1].checkpoint.is_some() { break; }
+
+            Delay::new(Duration::from_millis(10)).await;
+        }
+
+        assert!(history.nodes.len() == 2);
+        assert!(history.nodes[1].parent == Some(0));
+        assert!(history.nodes[1].checkpoint == Some("Second layer".to_string()));
+        assert!(history.nodes[1].edit_log_range == (2..4));
+        assert!(history.nodes[1].undone_edits == None);
+        assert!(history.nodes[1].reverse_edits.len() > 0);
+        assert!(history.position == Some(1));
+
+        // The actions should still be undoable when the history is loaded (using the reverse edits stored with the history)
+        let reopened        = UndoableAnimation::new(reopened);
+        assert!(reopened.history().nodes.len() == 2);
+
+        let timeout         = Delay::new(Duration::from_secs(10));
+        let undo_result     = match select(reopened.undo().boxed(), timeout).await {
+            Either::Right(_)        => { assert!(false, "Timed out"); unimplemented!() }
+            Either::Left(result)    => result.0,
+        };
+        println!("{:?}", undo_result);
+        assert!(undo_result.is_ok());
+
+        assert!(reopened.get_layer_ids() == vec![0]);
+    });
+}
+
+#[test]
+fn redo_undone_action_from_stored_history() {
+    executor::block_on(async {
+        use AnimationEdit::*;
+
+        // Create the animation, then add two layers and undo the second one
+        let in_memory_store = Arc::new(InMemoryStorage::new());
+        let store           = Arc::clone(&in_memory_store);
+        let animation       = create_animation_editor(move |commands| store.get_responses(commands).boxed());
+        let animation       = UndoableAnimation::new(animation);
+
+        animation.edit().publish(Arc::new(vec![AddNewLayer(0), Undo(UndoEdit::FinishAction)])).await;
+        animation.edit().publish(Arc::new(vec![AddNewLayer(1), Undo(UndoEdit::FinishAction)])).await;
+        animation.edit().when_empty().await;
+
+        assert!(animation.undo().await.is_ok());
+
+        // Open the same storage again (the history is written in the background, so wait for it to arrive)
+        let store           = Arc::clone(&in_memory_store);
+        let reopened        = create_animation_editor(move |commands| store.get_responses(commands).boxed());
+        let mut history     = UndoHistory::empty();
+
+        for _ in 0..100 {
+            history = reopened.read_undo_history();
+            if history.position == Some(0) && history.nodes.len() == 2 && history.nodes[1].undone_edits.is_some() { break; }
+
+            Delay::new(Duration::from_millis(10)).await;
+        }
+
+        // The undone action isn't in the edit log any more, so its edits are stored with the history
+        assert!(history.nodes[1].undone_edits == Some(Arc::new(vec![AddNewLayer(1), Undo(UndoEdit::FinishAction)])));
+
+        // Redo the action, then undo it again
+        let reopened        = UndoableAnimation::new(reopened);
+        assert!(reopened.get_layer_ids() == vec![0]);
+
+        let timeout         = Delay::new(Duration::from_secs(10));
+        let redo_result     = match select(reopened.redo().boxed(), timeout).await {
+            Either::Right(_)        => { assert!(false, "Timed out"); unimplemented!() }
+            Either::Left(result)    => result.0,
+        };
+        assert!(redo_result.is_ok());
+        assert!(reopened.get_layer_ids() == vec![0, 1]);
+
+        assert!(reopened.undo().await.is_ok());
+        assert!(reopened.get_layer_ids() == vec![0]);
+    });
+}
+
+#[test]
+fn jump_to_checkpoint_edit() {
+    executor::block_on(async {
+        use AnimationEdit::*;
+
+        // Create the animation
+        let in_memory_store = InMemoryStorage::new();
+        let animation       = create_animation_editor(move |commands| in_memory_store.get_responses(commands).boxed());
+        let animation       = UndoableAnimation::new(animation);
+
+        // Name the point after the first layer is added, then add two more layers
+        animation.edit().publish(Arc::new(vec![AddNewLayer(0), Undo(UndoEdit::FinishAction)])).await;
+        animation.edit().publish(Arc::new(vec![Undo(UndoEdit::Checkpoint("One layer".to_string()))])).await;
+        animation.edit().publish(Arc::new(vec![AddNewLayer(1), Undo(UndoEdit::FinishAction)])).await;
+        animation.edit().publish(Arc::new(vec![AddNewLayer(2), Undo(UndoEdit::FinishAction)])).await;
+        animation.edit().when_empty().await;
+
+        // Jumping to the checkpoint happens once the edit has retired
+        animation.edit().publish(Arc::new(vec![Undo(UndoEdit::JumpTo("One layer".to_string()))])).await;
+
+        for _ in 0..100 {
+            if animation.get_layer_ids() == vec![0] { break; }
+            Delay::new(Duration::from_millis(10)).await;
+        }
+
+        assert!(animation.get_layer_ids() == vec![0]);
+        assert!(animation.history().position == Some(0));
+
+        // Checkpoints that don't exist can't be jumped to
+        assert!(animation.jump_to_checkpoint("Three layers").await == Err(UndoFailureReason::NoSuchHistoryNode));
+    });
+}
@@ -27,6 +27,21 @@ pub struct LayerData {
     /// The alpha value of the layer
     pub alpha: f64,
 
+    /// Whether or not the layer is hidden
+    pub hidden: bool,
+
+    /// Whether or not the layer is locked
+    pub locked: bool,
+
+    /// The blend mode for the layer
+    pub blend_mode: LayerBlendMode,
+
+    /// Whether or not the layer is a folder
+    pub is_folder: bool,
+
+    /// The folder containing the layer
+    pub parent_folder: Option<u64>,
+
     /// Data at each of the keyframes for this layer
     pub keyframes: HashMap<Duration, FrameData>
 }
@@ -49,9 +64,14 @@ pub async fn read_layer(animation: &impl Animation, layer_id: u64) -> Option<Lay
     }
 
     Some(LayerData {
-        keyframes:  keyframe_data,
-        name:       layer_name,
-        alpha:      layer_alpha
+        keyframes:      keyframe_data,
+        name:           layer_name,
+        alpha:          layer_alpha,
+        hidden:         layer.is_hidden(),
+        locked:         layer.is_locked(),
+        blend_mode:     layer.blend_mode(),
+        is_folder:      layer.is_folder(),
+        parent_folder:  layer.parent_folder(),
     })
 }
 
@@ -336,6 +356,161 @@ fn set_alpha() {
     });
 }
 
+#[test]
+fn set_hidden() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        test_layer_edit_undo(
+            vec![
+                AddNewLayer(0),
+                Layer(0, AddKeyFrame(Duration::from_millis(0))),
+
+                Layer(0, Path(Duration::from_millis(0), PathEdit::SelectBrush(ElementId::Assigned(100), BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw))),
+                Layer(0, Path(Duration::from_millis(0), PathEdit::BrushProperties(ElementId::Assigned(101), BrushProperties::new()))),
+
+                Layer(0, Path(Duration::from_millis(0), PathEdit::CreatePath(ElementId::Assigned(0), circle_path((100.0, 100.0), 50.0)))),
+            ],
+            vec![
+                Layer(0, SetHidden(true)),
+            ]
+        ).await;
+    });
+}
+
+#[test]
+fn set_locked_and_blend_mode() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        test_layer_edit_undo(
+            vec![
+                AddNewLayer(0),
+                Layer(0, AddKeyFrame(Duration::from_millis(0))),
+
+                Layer(0, Path(Duration::from_millis(0), PathEdit::SelectBrush(ElementId::Assigned(100), BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw))),
+                Layer(0, Path(Duration::from_millis(0), PathEdit::BrushProperties(ElementId::Assigned(101), BrushProperties::new()))),
+
+                Layer(0, Path(Duration::from_millis(0), PathEdit::CreatePath(ElementId::Assigned(0), circle_path((100.0, 100.0), 50.0)))),
+            ],
+            vec![
+                Layer(0, SetLocked(true)),
+                Layer(0, SetBlendMode(LayerBlendMode::Screen)),
+            ]
+        ).await;
+    });
+}
+
+#[test]
+fn put_layer_in_folder() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        test_layer_edit_undo(
+            vec![
+                AddNewLayer(0),
+                Layer(0, AddKeyFrame(Duration::from_millis(0))),
+                AddNewLayer(1),
+                Layer(1, SetIsFolder(true)),
+            ],
+            vec![
+                Layer(0, SetParentFolder(Some(1))),
+            ]
+        ).await;
+    });
+}
+
+#[test]
+fn remove_folder_layer() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        test_layer_edit_undo(
+            vec![
+                AddNewLayer(0),
+                Layer(0, AddKeyFrame(Duration::from_millis(0))),
+                AddNewLayer(1),
+                Layer(1, SetIsFolder(true)),
+                Layer(1, SetCollapsed(true)),
+                Layer(1, SetHidden(true)),
+                Layer(0, SetParentFolder(Some(1))),
+            ],
+            vec![
+                RemoveLayer(1),
+            ]
+        ).await;
+    });
+}
+
+#[test]
+fn order_folder_with_contents() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        test_layer_edit_undo(
+            vec![
+                AddNewLayer(0),
+                Layer(0, AddKeyFrame(Duration::from_millis(0))),
+                AddNewLayer(1),
+                Layer(1, AddKeyFrame(Duration::from_millis(0))),
+                AddNewLayer(2),
+                Layer(2, AddKeyFrame(Duration::from_millis(0))),
+
+                Layer(1, SetIsFolder(true)),
+                Layer(0, SetParentFolder(Some(1))),
+                Layer(2, SetOrdering(0)),
+            ],
+            vec![
+                Layer(1, SetOrdering(2)),
+            ]
+        ).await;
+    });
+}
+
+#[test]
+fn folder_moves_with_contents() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        let in_memory_store = InMemoryStorage::new();
+        let animation       = create_animation_editor(move |commands| in_memory_store.get_responses(commands).boxed());
+
+        // Layers 0 and 1 are in the folder 2, which is behind layer 3
+        animation.edit().publish(Arc::new(vec![
+            AddNewLayer(0),
+            AddNewLayer(1),
+            AddNewLayer(2),
+            AddNewLayer(3),
+
+            Layer(2, SetIsFolder(true)),
+            Layer(0, SetParentFolder(Some(2))),
+            Layer(1, SetParentFolder(Some(2))),
+        ])).await;
+        animation.edit().when_empty().await;
+
+        // Moving the folder behind the layer 0 should do nothing (as it's inside the folder)
+        animation.edit().publish(Arc::new(vec![Layer(2, SetOrdering(0))])).await;
+        animation.edit().when_empty().await;
+        assert!(animation.get_layer_ids() == vec![0, 1, 2, 3]);
+
+        // Moving layer 3 to the back should leave the folder in front of it, with its contents
+        animation.edit().publish(Arc::new(vec![Layer(3, SetOrdering(0))])).await;
+        animation.edit().when_empty().await;
+        assert!(animation.get_layer_ids() == vec![3, 0, 1, 2]);
+
+        // Moving the folder to the back should move its contents along with it
+        animation.edit().publish(Arc::new(vec![Layer(2, SetOrdering(3))])).await;
+        animation.edit().when_empty().await;
+        assert!(animation.get_layer_ids() == vec![0, 1, 2, 3]);
+    });
+}
+
 #[test]
 fn remove_layer_with_multiple_keyframes() {
     executor::block_on(async {
@@ -504,6 +679,227 @@ fn remove_two_key_frames() {
     });
 }
 
+///
+/// Creates a layer with three keyframes with drawings on them, at 0ms, 1000ms and 3000ms (plus an empty keyframe at 2000ms)
+///
+fn three_drawn_key_frames() -> Vec<AnimationEdit> {
+    use self::AnimationEdit::*;
+    use self::LayerEdit::*;
+
+    vec![
+        AddNewLayer(0),
+        Layer(0, AddKeyFrame(Duration::from_millis(0))),
+        Layer(0, AddKeyFrame(Duration::from_millis(1000))),
+        Layer(0, AddKeyFrame(Duration::from_millis(2000))),
+        Layer(0, AddKeyFrame(Duration::from_millis(3000))),
+
+        Layer(0, Path(Duration::from_millis(0), PathEdit::SelectBrush(ElementId::Assigned(100), BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw))),
+        Layer(0, Path(Duration::from_millis(0), PathEdit::BrushProperties(ElementId::Assigned(101), BrushProperties::new()))),
+
+        Layer(0, Path(Duration::from_millis(0), PathEdit::CreatePath(ElementId::Assigned(0), circle_path((100.0, 100.0), 50.0)))),
+        Layer(0, Path(Duration::from_millis(0), PathEdit::CreatePath(ElementId::Assigned(1), circle_path((100.0, 150.0), 50.0)))),
+
+        Layer(0, Path(Duration::from_millis(1000), PathEdit::SelectBrush(ElementId::Assigned(102), BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw))),
+        Layer(0, Path(Duration::from_millis(1000), PathEdit::BrushProperties(ElementId::Assigned(103), BrushProperties::new()))),
+
+        Layer(0, Path(Duration::from_millis(1000), PathEdit::CreatePath(ElementId::Assigned(2), circle_path((100.0, 100.0), 50.0)))),
+        Layer(0, Path(Duration::from_millis(1000), PathEdit::CreatePath(ElementId::Assigned(3), circle_path((100.0, 150.0), 50.0)))),
+        Element(vec![ElementId::Assigned(2), ElementId::Assigned(3)], ElementEdit::Group(ElementId::Assigned(4), GroupType::Normal)),
+
+        Layer(0, Path(Duration::from_millis(3000), PathEdit::SelectBrush(ElementId::Assigned(104), BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw))),
+        Layer(0, Path(Duration::from_millis(3000), PathEdit::BrushProperties(ElementId::Assigned(105), BrushProperties::new()))),
+
+        Layer(0, Path(Duration::from_millis(3000), PathEdit::CreatePath(ElementId::Assigned(5), circle_path((100.0, 200.0), 50.0)))),
+    ]
+}
+
+#[test]
+fn move_key_frame() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        test_layer_edit_undo(
+            three_drawn_key_frames(),
+            vec![
+                Layer(0, MoveKeyFrame(Duration::from_millis(1000), Duration::from_millis(1500))),
+            ]
+        ).await;
+    });
+}
+
+#[test]
+fn move_key_frame_onto_existing_key_frame() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        test_layer_edit_undo(
+            three_drawn_key_frames(),
+            vec![
+                Layer(0, MoveKeyFrame(Duration::from_millis(1000), Duration::from_millis(3000))),
+            ]
+        ).await;
+    });
+}
+
+#[test]
+fn duplicate_key_frame() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        test_layer_edit_undo(
+            three_drawn_key_frames(),
+            vec![
+                Layer(0, DuplicateKeyFrame(Duration::from_millis(1000), Duration::from_millis(4000), (200..205).map(|id| ElementId::Assigned(id)).collect())),
+            ]
+        ).await;
+    });
+}
+
+#[test]
+fn duplicate_key_frame_onto_existing_key_frame() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        test_layer_edit_undo(
+            three_drawn_key_frames(),
+            vec![
+                Layer(0, DuplicateKeyFrame(Duration::from_millis(0), Duration::from_millis(3000), (200..204).map(|id| ElementId::Assigned(id)).collect())),
+            ]
+        ).await;
+    });
+}
+
+#[test]
+fn redo_duplicate_key_frame_with_same_ids() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        let in_memory_store = InMemoryStorage::new();
+        let animation       = create_animation_editor(move |commands| in_memory_store.get_responses(commands).boxed());
+
+        animation.edit().publish(Arc::new(three_drawn_key_frames())).await;
+        animation.edit().when_empty().await;
+
+        // Duplicate a keyframe without specifying the IDs for the copies
+        let mut retired_edits   = animation.retired_edits();
+        animation.edit().publish(Arc::new(vec![Layer(0, DuplicateKeyFrame(Duration::from_millis(1000), Duration::from_millis(4000), vec![]))])).await;
+        animation.edit().when_empty().await;
+
+        let retired_edit        = retired_edits.next().await.unwrap();
+        let committed           = retired_edit.committed_edits();
+        let reverse             = retired_edit.reverse_edits();
+
+        // The IDs that were assigned to the copies should be in the edit that was committed
+        let new_ids = match &committed[0] {
+            Layer(0, DuplicateKeyFrame(_, _, new_ids))  => new_ids.clone(),
+            _                                           => { assert!(false, "Unexpected edit"); vec![] }
+        };
+        assert!(!new_ids.is_empty());
+        assert!(new_ids.iter().all(|new_id| new_id.is_assigned()));
+
+        let duplicated          = read_all_layers(&animation).await;
+
+        // Undo, then redo by performing the committed edit again: the copies should get the same IDs
+        animation.edit().publish(Arc::clone(&reverse)).await;
+        animation.edit().when_empty().await;
+        animation.edit().publish(Arc::clone(&committed)).await;
+        animation.edit().when_empty().await;
+
+        let redone              = read_all_layers(&animation).await;
+        assert!(duplicated == redone);
+
+        // Edits to the copies should affect the same elements as they did the first time
+        let copied_frame        = animation.get_layer_with_id(0).unwrap().get_frame_at_time(Duration::from_millis(4000));
+        let copied_ids          = copied_frame.vector_elements().unwrap().map(|elem| elem.id()).collect::<Vec<_>>();
+        assert!(copied_ids.len() == 1);
+        assert!(new_ids.contains(&copied_ids[0]));
+
+        animation.edit().publish(Arc::new(vec![Element(copied_ids.clone(), ElementEdit::Delete)])).await;
+        animation.edit().when_empty().await;
+
+        let copied_frame        = animation.get_layer_with_id(0).unwrap().get_frame_at_time(Duration::from_millis(4000));
+        let original_frame      = animation.get_layer_with_id(0).unwrap().get_frame_at_time(Duration::from_millis(1000));
+        assert!(copied_frame.vector_elements().unwrap().count() == 0);
+        assert!(original_frame.vector_elements().unwrap().count() == 1);
+    });
+}
+
+#[test]
+fn shift_key_frames_forward() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        test_layer_edit_undo(
+            three_drawn_key_frames(),
+            vec![
+                Layer(0, ShiftKeyFrames(Duration::from_millis(1000)..Duration::from_micros(i64::MAX as u64), 1_000_000)),
+            ]
+        ).await;
+    });
+}
+
+#[test]
+fn shift_key_frames_backward() {
+    executor::block_on(async {
+        use self::AnimationEdit::*;
+        use self::LayerEdit::*;
+
+        test_layer_edit_undo(
+            three_drawn_key_frames(),
+            vec![
+                Layer(0, ShiftKeyFrames(Duration::from_millis(2000)..Duration::from_micros(i64::MAX as u64), -500_000)),
+            ]
+        ).await;
+    });
+}
+
+///
+/// Performs a shift that should be rejected, and checks that the layers are unchanged
+///
+async fn test_rejected_shift(range: std::ops::Range<Duration>, offset: i64) {
+    use self::AnimationEdit::*;
+    use self::LayerEdit::*;
+
+    let in_memory_store = InMemoryStorage::new();
+    let animation       = create_animation_editor(move |commands| in_memory_store.get_responses(commands).boxed());
+
+    animation.edit().publish(Arc::new(three_drawn_key_frames())).await;
+    animation.edit().when_empty().await;
+
+    let initial_layers  = read_all_layers(&animation).await;
+
+    animation.edit().publish(Arc::new(vec![Layer(0, ShiftKeyFrames(range, offset))])).await;
+    animation.edit().when_empty().await;
+
+    let shifted_layers  = read_all_layers(&animation).await;
+    assert!(initial_layers == shifted_layers);
+}
+
+#[test]
+fn shift_key_frames_onto_key_frame_outside_range_is_rejected() {
+    executor::block_on(async {
+        // Moving the keyframe at 2s back by 1s would replace the keyframe at 1s
+        test_rejected_shift(Duration::from_millis(2000)..Duration::from_micros(i64::MAX as u64), -1_000_000).await;
+
+        // Moving the keyframe at 1s forward by 2s would replace the keyframe at 3s (which isn't moving)
+        test_rejected_shift(Duration::from_millis(1000)..Duration::from_millis(1500), 2_000_000).await;
+    });
+}
+
+#[test]
+fn shift_key_frames_before_start_is_rejected() {
+    executor::block_on(async {
+        // The keyframe at 0s can't move to -1s, so none of the keyframes are moved
+        test_rejected_shift(Duration::from_millis(0)..Duration::from_micros(i64::MAX as u64), -1_000_000).await;
+    });
+}
+
 #[test]
 fn remove_layer_with_nested_group() {
     executor::block_on(async {
@@ -28,4 +28,206 @@ fn does_not_commit_empty_action() {
 
     // The last 'FinishAction' should not create a new entry
     assert!(log.undo_depth() == 1);
-}
\ No newline at end of file
+}
+
+///
+/// Retires a single action (ending with a FinishAction) to an undo log
+///
+fn retire_action(log: &mut UndoLog, edit: AnimationEdit) {
+    log.retire(RetiredEdit::new(Arc::new(vec![edit, AnimationEdit::Undo(UndoEdit::FinishAction)]), Arc::new(vec![])));
+}
+
+#[test]
+fn undo_then_edit_keeps_old_branch() {
+    let mut log = UndoLog::new();
+
+    retire_action(&mut log, AnimationEdit::AddNewLayer(0));
+    retire_action(&mut log, AnimationEdit::AddNewLayer(1));
+
+    // Undo the second action and perform a different one
+    assert!(log.undo().is_some());
+    retire_action(&mut log, AnimationEdit::AddNewLayer(2));
+
+    // New action can't be redone, but the old one is still in the history
+    assert!(log.undo_depth() == 2);
+    assert!(log.redo_depth() == 0);
+
+    let history = log.history();
+    assert!(history.nodes.len() == 3);
+    assert!(history.nodes[1].parent == Some(0));
+    assert!(history.nodes[2].parent == Some(0));
+    assert!(history.position == Some(2));
+}
+
+#[test]
+fn redo_follows_last_branch() {
+    let mut log = UndoLog::new();
+
+    retire_action(&mut log, AnimationEdit::AddNewLayer(0));
+    retire_action(&mut log, AnimationEdit::AddNewLayer(1));
+    log.undo();
+    retire_action(&mut log, AnimationEdit::AddNewLayer(2));
+    log.undo();
+
+    assert!(log.redo_depth() == 1);
+    assert!(log.redo() == Some(Arc::new(vec![AnimationEdit::AddNewLayer(2), AnimationEdit::Undo(UndoEdit::FinishAction)])));
+
+    log.undo();
+    assert!(log.redo_to(1) == Some(Arc::new(vec![AnimationEdit::AddNewLayer(1), AnimationEdit::Undo(UndoEdit::FinishAction)])));
+    assert!(log.history().position == Some(1));
+}
+
+#[test]
+fn checkpoint_names_last_action() {
+    let mut log = UndoLog::new();
+
+    log.retire(RetiredEdit::new(Arc::new(vec![AnimationEdit::AddNewLayer(0)]), Arc::new(vec![])));
+    log.retire(RetiredEdit::new(Arc::new(vec![AnimationEdit::Undo(UndoEdit::Checkpoint("Before lip sync".to_string()))]), Arc::new(vec![])));
+
+    let history = log.history();
+    assert!(history.nodes.len() == 1);
+    assert!(history.nodes[0].checkpoint == Some("Before lip sync".to_string()));
+    assert!(history.position == Some(0));
+}
+
+#[test]
+fn route_to_other_branch() {
+    let mut log = UndoLog::new();
+
+    retire_action(&mut log, AnimationEdit::AddNewLayer(0));
+    retire_action(&mut log, AnimationEdit::AddNewLayer(1));
+    retire_action(&mut log, AnimationEdit::AddNewLayer(2));
+    log.undo();
+    log.undo();
+    retire_action(&mut log, AnimationEdit::AddNewLayer(3));
+
+    // Node 3 is a sibling of node 1, so we need to undo once and redo twice to get to node 2
+    assert!(log.route_to(Some(2)) == Some((1, vec![1, 2])));
+    assert!(log.route_to(None) == Some((2, vec![])));
+    assert!(log.route_to(Some(3)) == Some((0, vec![])));
+    assert!(log.route_to(Some(42)) == None);
+}
+
+#[test]
+fn history_changes_are_only_taken_once() {
+    let mut log = UndoLog::new();
+
+    retire_action(&mut log, AnimationEdit::AddNewLayer(0));
+
+    let changes = log.take_history_changes().unwrap();
+    assert!(changes.nodes.len() == 1);
+    assert!(changes.position == Some(0));
+    assert!(log.take_history_changes().is_none());
+
+    // Undoing an action stores its edits with the history, as they're removed from the edit log
+    log.undo();
+    let changes = log.take_history_changes().unwrap();
+    assert!(changes.nodes.len() == 1);
+    assert!(changes.nodes[0].undone_edits == Some(Arc::new(vec![AnimationEdit::AddNewLayer(0), AnimationEdit::Undo(UndoEdit::FinishAction)])));
+    assert!(changes.position == None);
+}
+
+#[test]
+fn nodes_store_edit_log_ranges() {
+    let mut log = UndoLog::new_at(3);
+
+    retire_action(&mut log, AnimationEdit::AddNewLayer(0));
+    retire_action(&mut log, AnimationEdit::AddNewLayer(1));
+    log.undo();
+    retire_action(&mut log, AnimationEdit::AddNewLayer(2));
+
+    // The new branch replaces the undone edits in the edit log, and only the undone action has its edits in the history
+    let history = log.history();
+    assert!(history.nodes[0].edit_log_range == (3..5));
+    assert!(history.nodes[1].edit_log_range == (5..7));
+    assert!(history.nodes[2].edit_log_range == (5..7));
+    assert!(history.nodes[0].undone_edits == None);
+    assert!(history.nodes[1].undone_edits.is_some());
+    assert!(history.nodes[2].undone_edits == None);
+}
+
+#[test]
+fn checkpoint_node_finds_most_recent() {
+    let mut log = UndoLog::new();
+
+    retire_action(&mut log, AnimationEdit::AddNewLayer(0));
+    log.retire(RetiredEdit::new(Arc::new(vec![AnimationEdit::Undo(UndoEdit::Checkpoint("Rough".to_string()))]), Arc::new(vec![])));
+    retire_action(&mut log, AnimationEdit::AddNewLayer(1));
+    retire_action(&mut log, AnimationEdit::AddNewLayer(2));
+    log.retire(RetiredEdit::new(Arc::new(vec![AnimationEdit::Undo(UndoEdit::Checkpoint("Rough".to_string()))]), Arc::new(vec![])));
+
+    assert!(log.checkpoint_node("Rough") == Some(2));
+    assert!(log.checkpoint_node("Final") == None);
+}
+
+#[test]
+fn load_from_history() {
+    let mut log = UndoLog::new();
+
+    retire_action(&mut log, AnimationEdit::AddNewLayer(0));
+    retire_action(&mut log, AnimationEdit::AddNewLayer(1));
+    log.undo();
+
+    let mut loaded = UndoLog::from_history(log.history(), 2);
+    assert!(loaded.undo_depth() == 1);
+    assert!(loaded.redo_depth() == 1);
+
+    // The applied action needs its edits to be read from the edit log before it can be undone
+    assert!(loaded.position_without_edits() == Some((0, 0..2)));
+
+    retire_action(&mut loaded, AnimationEdit::AddNewLayer(2));
+    assert!(loaded.history().position == Some(2));
+    assert!(loaded.history().nodes[2].edit_log_range == (2..4));
+}
+
+#[test]
+fn undo_from_loaded_history_uses_stored_reverse_edits() {
+    let mut log = UndoLog::new();
+
+    log.retire(RetiredEdit::new(Arc::new(vec![AnimationEdit::AddNewLayer(0), AnimationEdit::Undo(UndoEdit::FinishAction)]), Arc::new(vec![AnimationEdit::RemoveLayer(0)])));
+    assert!(log.history().nodes[0].reverse_edits == Arc::new(vec![AnimationEdit::RemoveLayer(0)]));
+
+    // The loaded action can't be undone until its committed edits have been read back from the edit log
+    let mut loaded = UndoLog::from_history(log.history(), 2);
+    assert!(loaded.undo() == None);
+    assert!(loaded.history().nodes[0].reverse_edits == Arc::new(vec![AnimationEdit::RemoveLayer(0)]));
+
+    loaded.set_committed_edits(0, Arc::new(vec![AnimationEdit::AddNewLayer(0), AnimationEdit::Undo(UndoEdit::FinishAction)]));
+    assert!(loaded.position_without_edits() == None);
+    assert!(loaded.undo() == Some(UndoEdit::PerformUndo {
+        original_actions:   Arc::new(vec![AnimationEdit::AddNewLayer(0), AnimationEdit::Undo(UndoEdit::FinishAction)]),
+        undo_actions:       Arc::new(vec![AnimationEdit::RemoveLayer(0)])
+    }));
+}
+
+#[test]
+fn redo_from_loaded_history() {
+    let mut log = UndoLog::new();
+
+    retire_action(&mut log, AnimationEdit::AddNewLayer(0));
+    retire_action(&mut log, AnimationEdit::AddNewLayer(1));
+    log.undo();
+
+    // The undone action is stored with its edits, so it can be redone
+    let mut loaded = UndoLog::from_history(log.history(), 2);
+    assert!(loaded.redo() == Some(Arc::new(vec![AnimationEdit::AddNewLayer(1), AnimationEdit::Undo(UndoEdit::FinishAction)])));
+    assert!(loaded.history().nodes[1].undone_edits == None);
+}
+
+#[test]
+fn discard_history_that_does_not_match_edit_log() {
+    let mut log = UndoLog::new();
+
+    retire_action(&mut log, AnimationEdit::AddNewLayer(0));
+    retire_action(&mut log, AnimationEdit::AddNewLayer(1));
+
+    // The edit log has been changed without updating the history
+    let loaded = UndoLog::from_history(log.history(), 5);
+    assert!(loaded.undo_depth() == 0);
+    assert!(loaded.history().nodes.len() == 0);
+
+    // New actions start at the end of the edit log
+    let mut loaded = loaded;
+    retire_action(&mut loaded, AnimationEdit::AddNewLayer(2));
+    assert!(loaded.history().nodes[0].edit_log_range == (5..7));
+}
@@ -2,19 +2,62 @@ use super::undo_step::*;
 use crate::traits::*;
 
 use std::sync::*;
+use std::ops::{Range};
+use std::collections::{HashMap, HashSet};
+
+///
+/// A completed action in the undo log
+///
+struct UndoNode {
+    /// The node that this action was performed after
+    parent: Option<u64>,
+
+    /// The name of the checkpoint at this node, if there is one
+    checkpoint: Option<String>,
+
+    /// Where the edits for this action are in the edit log while it's applied
+    edit_log_range: Range<usize>,
+
+    /// The edits making up this action (None for actions loaded from storage whose edits haven't been read back from the edit log yet)
+    step: Option<UndoStep>,
+
+    /// The edits that reverse this action, for actions loaded from storage that are waiting for their edits to be read back
+    reverse_edits: Option<Arc<Vec<AnimationEdit>>>,
+}
 
 ///
 /// A log of undo elements
 ///
+/// The log is stored as a tree: undoing some actions and then performing a new one starts a new branch rather than
+/// discarding the actions that were undone.
+///
 pub struct UndoLog {
     /// Set to true if we're currently performing an undo operation (retired edits won't be recorded)
     undoing: bool,
 
-    /// The list of undo steps, with the latest at the end
-    undo: Vec<UndoStep>,
+    /// The actions that have been completed, indexed by ID
+    nodes: HashMap<u64, UndoNode>,
+
+    /// The ID to assign to the next completed action
+    next_id: u64,
 
-    /// Steps that have been undone and which can be re-done
-    redo: Vec<UndoStep>,
+    /// The node representing the current state of the animation (None if no actions are applied)
+    position: Option<u64>,
+
+    /// The action that is currently being performed
+    current: UndoStep,
+
+    /// The child of each node that will be followed when performing a redo
+    redo_child: HashMap<Option<u64>, u64>,
+
+    /// The nodes that have changed since the last time the history changes were taken
+    changed_nodes: HashSet<u64>,
+
+    /// True if the position has changed since the last time the history changes were taken
+    position_changed: bool,
+
+    /// The index in the edit log where the first action in the history starts
+    edit_log_start: usize,
 }
 
 impl UndoLog {
@@ -22,30 +65,126 @@ impl UndoLog {
     /// Creates a new empty undo log
     ///
     pub fn new() -> UndoLog {
+        Self::new_at(0)
+    }
+
+    ///
+    /// Creates a new empty undo log for an animation whose edit log already contains the specified number of edits
+    ///
+    pub fn new_at(edit_log_start: usize) -> UndoLog {
         UndoLog {
-            undoing:    false,
-            undo:       vec![],
-            redo:       vec![],
+            undoing:            false,
+            nodes:              HashMap::new(),
+            next_id:            0,
+            position:           None,
+            current:            UndoStep::new(),
+            redo_child:         HashMap::new(),
+            changed_nodes:      HashSet::new(),
+            position_changed:   false,
+            edit_log_start:     edit_log_start,
         }
     }
 
+    ///
+    /// Creates an undo log from a history that was read from storage, for an edit log containing the specified number of edits
+    ///
+    /// Applied actions only store their reverse edits and where their committed edits are in the edit log: they can't be
+    /// undone until `set_committed_edits()` has supplied the edits from the edit log. If the history doesn't match the edit
+    /// log (eg, because the animation was edited without recording the history), it's discarded and a new history is started.
+    ///
+    pub fn from_history(history: UndoHistory, edit_log_length: usize) -> UndoLog {
+        let mut log = UndoLog::new_at(edit_log_length);
+
+        for node in history.nodes {
+            // Undone actions are stored with their edits, so they can be redone
+            let (step, reverse_edits) = match node.undone_edits {
+                Some(edits) => {
+                    let mut step = UndoStep::new();
+                    step.push_edit(RetiredEdit::new(edits, node.reverse_edits));
+                    (Some(step), None)
+                }
+
+                None        => (None, Some(node.reverse_edits))
+            };
+
+            if node.parent.is_none() {
+                log.edit_log_start = node.edit_log_range.start;
+            }
+
+            log.next_id = log.next_id.max(node.id+1);
+            log.nodes.insert(node.id, UndoNode {
+                parent:         node.parent,
+                checkpoint:     node.checkpoint,
+                edit_log_range: node.edit_log_range,
+                step:           step,
+                reverse_edits:  reverse_edits,
+            });
+        }
+
+        let position    = history.position.filter(|position| log.nodes.contains_key(position));
+        log.position    = position;
+
+        // The applied actions must finish at the end of the edit log
+        if log.edit_log_end() != edit_log_length {
+            return UndoLog::new_at(edit_log_length);
+        }
+
+        log
+    }
+
+    ///
+    /// The index in the edit log just after the edits for the action at the current position
+    ///
+    fn edit_log_end(&self) -> usize {
+        self.position
+            .and_then(|node_id| self.nodes.get(&node_id))
+            .map(|node| node.edit_log_range.end)
+            .unwrap_or(self.edit_log_start)
+    }
+
     ///
     /// The number of entries in the undo log
     ///
     pub fn undo_depth(&self) -> usize {
-        if self.undo.last().map(|last| last.is_empty()).unwrap_or(false) {
-            // If the last edit is empty, it doesn't count
-            self.undo.len()-1
-        } else {
-            self.undo.len()
+        let mut depth   = 0;
+        let mut node    = self.position;
+
+        while let Some(node_id) = node {
+            depth   += 1;
+            node    = self.nodes.get(&node_id).and_then(|node| node.parent);
+        }
+
+        // The action that's currently being performed can also be undone
+        if !self.current.is_empty() {
+            depth += 1;
         }
+
+        depth
     }
 
     ///
     /// The number of entries in the redo log
     ///
     pub fn redo_depth(&self) -> usize {
-        self.redo.len()
+        // Any action in progress destroys the redo log
+        if !self.current.is_empty() { return 0; }
+
+        let mut depth   = 0;
+        let mut node    = self.position;
+
+        while let Some(child) = self.preferred_child(node) {
+            depth   += 1;
+            node    = Some(child);
+        }
+
+        depth
+    }
+
+    ///
+    /// The node in the history that matches the current state of the animation
+    ///
+    pub fn position(&self) -> Option<u64> {
+        self.position
     }
 
     ///
@@ -62,26 +201,92 @@ impl UndoLog {
         self.undoing = false;
     }
 
+    ///
+    /// The child of a node that will be followed by a redo action
+    ///
+    fn preferred_child(&self, node: Option<u64>) -> Option<u64> {
+        // Use the child that was last moved through if there is one
+        if let Some(child) = self.redo_child.get(&node) {
+            if self.nodes.get(child).map(|child| child.parent == node).unwrap_or(false) {
+                return Some(*child);
+            }
+        }
+
+        // Otherwise, use the most recently created child
+        self.nodes.iter()
+            .filter(|(_, child)| child.parent == node)
+            .map(|(child_id, _)| *child_id)
+            .max()
+    }
+
+    ///
+    /// Moves the position to a new node
+    ///
+    fn set_position(&mut self, position: Option<u64>) {
+        self.position           = position;
+        self.position_changed   = true;
+    }
+
+    ///
+    /// Adds the current action to the tree as a child of the current position
+    ///
+    fn finish_current_action(&mut self) {
+        if self.current.is_empty() { return; }
+
+        let node_id     = self.next_id;
+        let step        = std::mem::replace(&mut self.current, UndoStep::new());
+        let start       = self.edit_log_end();
+        let num_edits   = step.committed_edits().iter().filter(|edit| edit.is_serialized()).count();
+        self.next_id += 1;
+
+        self.nodes.insert(node_id, UndoNode {
+            parent:         self.position,
+            checkpoint:     None,
+            edit_log_range: start..(start+num_edits),
+            step:           Some(step),
+            reverse_edits:  None,
+        });
+        self.redo_child.insert(self.position, node_id);
+        self.changed_nodes.insert(node_id);
+        self.set_position(Some(node_id));
+    }
+
     ///
     /// Retires an edit to this undo log
     ///
     pub fn retire(&mut self, edit: RetiredEdit) {
         // When in the undoing state, retiring edits has no effect (the edits are assumed to be generated by the undo action)
         if self.undoing { return; }
-        
+
         let committed_edits = edit.committed_edits();
 
         // A 'prepare to undo' action on its own is always ignored (it's used for synchronising things up and will cause an editing mismatch)
         if committed_edits.len() == 1 {
-            match committed_edits[0] {
+            match &committed_edits[0] {
                 AnimationEdit::Undo(UndoEdit::PrepareToUndo(_)) => { return; }
+                AnimationEdit::Undo(UndoEdit::JumpTo(_))        => { return; }
+                AnimationEdit::Undo(UndoEdit::Checkpoint(name)) => {
+                    // Checkpoints name the most recent action
+                    self.finish_current_action();
+
+                    if let Some(node_id) = self.position {
+                        self.nodes.get_mut(&node_id).unwrap().checkpoint = Some(name.clone());
+                        self.changed_nodes.insert(node_id);
+                    }
+                    return;
+                }
                 AnimationEdit::Undo(UndoEdit::FinishAction)     => {
                     // Don't push a 'finish action' undo edit onto an otherwise empty undo step
-                    if self.undo.last().map(|last_step| last_step.is_empty()).unwrap_or(true) {
+                    if self.current.is_empty() {
                         // Apply to the previous undo step
-                        if self.undo.len() > 1 {
-                            let previous_step_idx = self.undo.len()-2;
-                            self.undo[previous_step_idx].push_edit(edit);
+                        if let Some(node_id) = self.position {
+                            let node = self.nodes.get_mut(&node_id).unwrap();
+
+                            node.edit_log_range.end += 1;
+                            if let Some(step) = &mut node.step {
+                                step.push_edit(edit);
+                            }
+                            self.changed_nodes.insert(node_id);
                         }
                         return;
                     }
@@ -90,14 +295,6 @@ impl UndoLog {
             }
         }
 
-        // Create the initial undo step if needed
-        if self.undo.is_empty() {
-            self.undo.push(UndoStep::new());
-        }
-
-        // Any redo actions are destroyed when a new action is created
-        self.redo.drain(..);
-
         // Determine if the edit finishes an action group
         let finishes_action_group = committed_edits.iter().any(|edit| match edit {
             AnimationEdit::Undo(UndoEdit::FinishAction) => true,
@@ -105,46 +302,218 @@ impl UndoLog {
         });
 
         // Add the edit to the current undo step
-        self.undo.last_mut().unwrap().push_edit(edit);
+        self.current.push_edit(edit);
 
-        // Start a new action group if this edit finished one (and there were any actions performed in the last step)
-        if finishes_action_group && self.undo.last().map(|last_step| !last_step.is_empty()).unwrap_or(true) {
-            self.undo.push(UndoStep::new());
+        // Add the action to the history if this edit finished it
+        if finishes_action_group {
+            self.finish_current_action();
         }
     }
 
     ///
-    /// Pops the action on top of the undo stack and moves it to the redo stack. Returns the edit to perform the action.
+    /// Moves the position back to the parent of the most recent action. Returns the edit to perform the action.
     ///
-    /// Returns 'None' if the stack is empty.
+    /// Returns 'None' if there are no actions to undo, or if the edits for the action have not been supplied yet.
     ///
     pub fn undo(&mut self) -> Option<UndoEdit> {
-        // Pop up to two actions (in case the first one is empty)
-        let most_recent_action = self.undo.pop()?;
-        let most_recent_action = if most_recent_action.is_empty() { self.undo.pop()? } else { most_recent_action };
+        // Any action in progress is finished so it can be undone
+        self.finish_current_action();
 
-        let undo_edit = most_recent_action.undo_edit();
+        let node_id     = self.position?;
+        let node        = self.nodes.get(&node_id)?;
+        let undo_edit   = node.step.as_ref()?.undo_edit();
+        let parent      = node.parent;
 
-        // Add as a redo action
-        self.redo.push(most_recent_action);
+        // Redoing from the parent will return to this node
+        self.redo_child.insert(parent, node_id);
+        self.set_position(parent);
 
-        // Add a new action group to the undo list so if there are any future actions, they won't extend an existing one
-        self.undo.push(UndoStep::new());
+        // The edits for the node are removed from the edit log, so they need to be stored with the history
+        self.changed_nodes.insert(node_id);
 
         Some(undo_edit)
     }
 
     ///
-    /// Pops the action on top of the redo stack and moves it to the undo stack. Returns the edit to perform the action.
+    /// Moves the position forward to the most recently visited child of the current node. Returns the edit to perform the action.
     ///
     pub fn redo(&mut self) -> Option<Arc<Vec<AnimationEdit>>> {
-        // Pop the next redo action and 
-        let next_redo = self.redo.pop()?;
-        let redo_edit = next_redo.redo_edit();
+        if !self.current.is_empty() { return None; }
+
+        let child = self.preferred_child(self.position)?;
+        self.redo_to(child)
+    }
+
+    ///
+    /// Moves the position forward to a particular child of the current node. Returns the edit to perform the action.
+    ///
+    pub fn redo_to(&mut self, child: u64) -> Option<Arc<Vec<AnimationEdit>>> {
+        if !self.current.is_empty() { return None; }
+
+        let node = self.nodes.get(&child)?;
+        if node.parent != self.position { return None; }
 
-        // Add as an undo action
-        self.undo.push(next_redo);
+        let redo_edit = node.step.as_ref()?.redo_edit();
+
+        self.redo_child.insert(self.position, child);
+        self.set_position(Some(child));
+
+        // The edits for the node are back in the edit log
+        self.changed_nodes.insert(child);
 
         Some(redo_edit)
     }
+
+    ///
+    /// Returns the path from a node back to the start of the history
+    ///
+    fn path_to_start(&self, node: Option<u64>) -> Vec<Option<u64>> {
+        let mut path = vec![node];
+        let mut node = node;
+
+        while let Some(node_id) = node {
+            node = self.nodes.get(&node_id).and_then(|node| node.parent);
+            path.push(node);
+        }
+
+        path
+    }
+
+    ///
+    /// If the action at the current position was loaded from storage and its edits haven't been read back from the edit log
+    /// yet, returns its ID and where its edits are in the edit log
+    ///
+    pub fn position_without_edits(&self) -> Option<(u64, Range<usize>)> {
+        let node_id = self.position?;
+        let node    = self.nodes.get(&node_id)?;
+
+        if node.step.is_none() {
+            Some((node_id, node.edit_log_range.clone()))
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Supplies the committed edits for an action loaded from storage after they've been read back from the edit log
+    ///
+    /// The action can be undone after this, using the reverse edits that were stored with the history.
+    ///
+    pub fn set_committed_edits(&mut self, node_id: u64, committed_edits: Arc<Vec<AnimationEdit>>) {
+        if let Some(node) = self.nodes.get_mut(&node_id) {
+            if node.step.is_some() { return; }
+
+            if let Some(reverse_edits) = node.reverse_edits.take() {
+                let mut step = UndoStep::new();
+                step.push_edit(RetiredEdit::new(committed_edits, reverse_edits));
+                node.step = Some(step);
+            }
+        }
+    }
+
+    ///
+    /// Supplies the edits for an action (eg, after redoing it)
+    ///
+    pub fn set_step(&mut self, node_id: u64, step: UndoStep) {
+        if let Some(node) = self.nodes.get_mut(&node_id) {
+            node.step           = Some(step);
+            node.reverse_edits  = None;
+        }
+    }
+
+    ///
+    /// Finds the most recent node in the history with the specified checkpoint name
+    ///
+    pub fn checkpoint_node(&self, checkpoint: &str) -> Option<u64> {
+        self.nodes.iter()
+            .filter(|(_, node)| node.checkpoint.as_ref().map(|name| name == checkpoint).unwrap_or(false))
+            .map(|(node_id, _)| *node_id)
+            .max()
+    }
+
+    ///
+    /// Finds the route from the current position to a target node in the history
+    ///
+    /// The result is the number of undo actions that need to be performed followed by the nodes that need to be redone
+    /// (in order) to reach the target. Returns None if the target is not in the history.
+    ///
+    pub fn route_to(&mut self, target: Option<u64>) -> Option<(usize, Vec<u64>)> {
+        if let Some(target) = target {
+            if !self.nodes.contains_key(&target) { return None; }
+        }
+
+        // Any action in progress is finished so it can be undone
+        self.finish_current_action();
+
+        // The route goes via the closest node that's shared between the paths to the start
+        let from_position   = self.path_to_start(self.position);
+        let from_target     = self.path_to_start(target);
+        let (target_idx, undo_count) = from_target.iter()
+            .enumerate()
+            .filter_map(|(target_idx, node)| from_position.iter().position(|position_node| position_node == node).map(|undo_count| (target_idx, undo_count)))
+            .next()?;
+
+        let redo_nodes      = from_target[0..target_idx].iter()
+            .rev()
+            .map(|node| node.unwrap())
+            .collect();
+
+        Some((undo_count, redo_nodes))
+    }
+
+    ///
+    /// Retrieves the entire undo history
+    ///
+    pub fn history(&self) -> UndoHistory {
+        let mut node_ids = self.nodes.keys().cloned().collect::<Vec<_>>();
+        node_ids.sort();
+
+        UndoHistory {
+            nodes:      node_ids.into_iter().map(|node_id| self.history_node(node_id)).collect(),
+            position:   self.position
+        }
+    }
+
+    ///
+    /// Retrieves the changes to the undo history since the last time this was called, or None if there are no changes
+    ///
+    pub fn take_history_changes(&mut self) -> Option<UndoHistory> {
+        if self.changed_nodes.is_empty() && !self.position_changed {
+            return None;
+        }
+
+        let mut node_ids = self.changed_nodes.drain().collect::<Vec<_>>();
+        node_ids.sort();
+        self.position_changed = false;
+
+        Some(UndoHistory {
+            nodes:      node_ids.into_iter().map(|node_id| self.history_node(node_id)).collect(),
+            position:   self.position
+        })
+    }
+
+    ///
+    /// Creates the history node for a node in this log
+    ///
+    fn history_node(&self, node_id: u64) -> UndoHistoryNode {
+        let node            = &self.nodes[&node_id];
+
+        // Applied actions can be read from the edit log, so only the undone actions store their edits
+        let is_applied      = self.path_to_start(self.position).contains(&Some(node_id));
+        let undone_edits    = if is_applied { None } else { node.step.as_ref().map(|step| step.committed_edits()) };
+
+        // The reverse edits are always stored, so that applied actions can be undone without replaying the edit log
+        let reverse_edits   = node.step.as_ref().map(|step| step.reverse_edits())
+            .or_else(|| node.reverse_edits.clone())
+            .unwrap_or_else(|| Arc::new(vec![]));
+
+        UndoHistoryNode {
+            id:             node_id,
+            parent:         node.parent,
+            checkpoint:     node.checkpoint.clone(),
+            edit_log_range: node.edit_log_range.clone(),
+            reverse_edits:  reverse_edits,
+            undone_edits:   undone_edits,
+        }
+    }
 }
@@ -37,14 +37,26 @@ impl UndoStep {
         self.edits.push(edit);
     }
 
+    ///
+    /// The edits that were committed to the animation by this step
+    ///
+    pub fn committed_edits(&self) -> Arc<Vec<AnimationEdit>> {
+        Arc::new(self.edits.iter().flat_map(|edit| edit.committed_edits().iter().cloned().collect::<Vec<_>>()).collect())
+    }
+
+    ///
+    /// The edits that will reverse this step (in the order that they were generated)
+    ///
+    pub fn reverse_edits(&self) -> Arc<Vec<AnimationEdit>> {
+        Arc::new(self.edits.iter().flat_map(|edit| edit.reverse_edits().iter().cloned().collect::<Vec<_>>()).collect())
+    }
+
     ///
     /// Creates the undo edit for this step
     ///
     pub fn undo_edit(&self) -> UndoEdit {
-        let original_actions    = self.edits.iter().flat_map(|edit| edit.committed_edits().iter().cloned().collect::<Vec<_>>()).collect();
-        let undo_actions        = self.edits.iter().flat_map(|edit| edit.reverse_edits().iter().cloned().collect::<Vec<_>>()).collect();
-        let original_actions    = Arc::new(original_actions);
-        let undo_actions        = Arc::new(undo_actions);
+        let original_actions    = self.committed_edits();
+        let undo_actions        = self.reverse_edits();
 
         UndoEdit::PerformUndo { original_actions, undo_actions }
     }
@@ -53,9 +65,6 @@ impl UndoStep {
     /// Creates the redo edit for this step
     ///
     pub fn redo_edit(&self) -> Arc<Vec<AnimationEdit>> {
-        let original_actions    = self.edits.iter().flat_map(|edit| edit.committed_edits().iter().cloned().collect::<Vec<_>>()).collect();
-        let original_actions    = Arc::new(original_actions);
-
-        original_actions
+        self.committed_edits()
     }
 }
@@ -1,5 +1,6 @@
 use super::undo_log::*;
 use super::undo_log_size::*;
+use super::undo_step::*;
 use super::edit_log_reader::*;
 use super::undoable_animation_core::*;
 use crate::traits::*;
@@ -32,14 +33,25 @@ impl<Anim: 'static+Unpin+EditableAnimation> UndoableAnimation<Anim> {
     /// Adds undo support to an existing animation
     ///
     pub fn new(animation: Anim) -> UndoableAnimation<Anim> {
+        // The undo log starts with the history that was stored with the animation
+        let undo_log            = UndoLog::from_history(animation.read_undo_history(), animation.get_num_edits());
+        let log_size_publisher  = ExpiringPublisher::new(1);
+
         // Box up the animation and create the edit stream
         let animation           = Arc::new(Desync::new(animation));
-        let undo_log            = UndoLog::new();
-        let log_size_publisher  = ExpiringPublisher::new(1);
+
+        // Changes to the history are written after any edits that are already waiting for the animation
+        let weak_animation      = Arc::downgrade(&animation);
+        let history_writer      = Box::new(move |changes: UndoHistory| {
+            if let Some(animation) = weak_animation.upgrade() {
+                animation.desync(move |animation| animation.write_undo_history(changes));
+            }
+        });
 
         let core                = UndoableAnimationCore {
             undo_log,
-            log_size_publisher
+            log_size_publisher,
+            history_writer
         };
         let core                = Arc::new(Desync::new(core));
 
@@ -56,11 +68,26 @@ impl<Anim: 'static+Unpin+EditableAnimation> UndoableAnimation<Anim> {
     /// When the underlying animation retires its edits, send them to the undo log
     ///
     fn pipe_retired_edits_to_undo_log(animation: &Arc<Desync<Anim>>, core: &Arc<Desync<UndoableAnimationCore>>) {
-        let retired_edits = animation.sync(|anim| anim.retired_edits());
+        let retired_edits   = animation.sync(|anim| anim.retired_edits());
+        let weak_animation  = Arc::downgrade(animation);
+        let weak_core       = Arc::downgrade(core);
 
         pipe_in(Arc::clone(core), retired_edits, move |core, retired_edits| {
+            // A 'jump to' edit moves the animation to a checkpoint in the undo history
+            let committed = retired_edits.committed_edits();
+            if committed.len() == 1 {
+                if let AnimationEdit::Undo(UndoEdit::JumpTo(checkpoint)) = &committed[0] {
+                    if let (Some(animation), Some(undo_core)) = (weak_animation.upgrade(), weak_core.upgrade()) {
+                        // The jump is scheduled after any edits that are already waiting for the animation
+                        let checkpoint = checkpoint.clone();
+                        animation.future_desync(move |animation| Self::jump_to_checkpoint_node(animation, undo_core, checkpoint).boxed()).detach();
+                    }
+                }
+            }
+
             async move {
                 core.undo_log.retire(retired_edits);
+                core.persist_history();
                 core.update_undo_log_size().await;
             }.boxed()
         });
@@ -91,6 +118,178 @@ impl<Anim: 'static+Unpin+EditableAnimation> UndoableAnimation<Anim> {
         }
     }
 
+    ///
+    /// Waits for all of the edits that have been sent to the animation to retire
+    ///
+    fn prepare_to_undo<'a>(animation: &'a Anim, retired_edits: &'a mut BoxStream<'static, RetiredEdit>) -> impl 'a+Future<Output=()> {
+        async move {
+            // Use 'prepare to undo' to ensure that all the edits have retired
+            let id                  = Uuid::new_v4().to_simple().to_string();
+            let prepare_undo        = Arc::new(vec![AnimationEdit::Undo(UndoEdit::PrepareToUndo(id))]);
+            animation.edit().publish(Arc::clone(&prepare_undo)).await;
+
+            // Process edits from retired_edits until the 'prepare' event is relayed back to us
+            // (as we're blocking the animation, no other actions will interfere with the undo action)
+            while let Some(edit) = retired_edits.next().await {
+                if edit.committed_edits() == prepare_undo {
+                    break;
+                }
+            }
+        }
+    }
+
+    ///
+    /// If the action at the current position was loaded from storage, reads its edits back from the edit log so that it can be undone
+    ///
+    /// Only the edits for this action are read: its reverse edits are stored with the undo history.
+    ///
+    fn read_position_edits<'a>(animation: &'a Anim, core: &'a Arc<Desync<UndoableAnimationCore>>) -> impl 'a+Future<Output=()> {
+        async move {
+            let missing = core.future_sync(|core| async move { core.undo_log.position_without_edits() }.boxed()).await.unwrap();
+            let (node_id, range) = if let Some(missing) = missing { missing } else { return; };
+
+            let num_edits   = range.len();
+            let edits       = animation.read_edit_log(range).collect::<Vec<_>>().await;
+            if edits.len() != num_edits { return; }
+
+            core.future_sync(move |core| async move {
+                core.undo_log.set_committed_edits(node_id, Arc::new(edits));
+            }.boxed()).await.unwrap();
+        }
+    }
+
+    ///
+    /// Undoes the action at the current position in the undo log
+    ///
+    fn perform_undo_step<'a>(animation: &'a Anim, core: &'a Arc<Desync<UndoableAnimationCore>>, retired_edits: &'a mut BoxStream<'static, RetiredEdit>) -> impl 'a+Future<Output=Result<(), UndoFailureReason>> {
+        async move {
+            // Actions loaded from storage only know where their edits are in the edit log
+            Self::read_position_edits(animation, core).await;
+
+            // Fetch the undo action that we're about to perform
+            let undo_edit = core.future_sync(|core| async move {
+                core.undo_log.start_undoing();
+                core.undo_log.undo()
+            }.boxed()).await.unwrap();
+
+            let undo_edit = if let Some(undo_edit) = undo_edit { 
+                undo_edit 
+            } else { 
+                core.future_sync(|core| async move { core.finish_undoing().await; }.boxed()).await.unwrap();
+                return Err(UndoFailureReason::NothingToUndo); 
+            };
+
+            // Carry out the undo action on the animation
+            animation.edit().publish(Arc::new(vec![AnimationEdit::Undo(undo_edit)])).await;
+
+            // A failure will produce a single retired edit, and a success will produce two, so read up to two edits
+            let undo_result = Self::read_undo_completion(&mut *retired_edits).await;
+            let undo_result = if let Some(undo_result) = undo_result { Some(undo_result) } else { Self::read_undo_completion(&mut *retired_edits).await };
+
+            // The undo is complete at this point
+            // Note: we're relying on the edit to have been queued in sequence here so that the 'finish_undoing' happens after the
+            // undo log pipe has received this edit
+            core.future_sync(|core| async move { core.finish_undoing().await; }.boxed()).await.unwrap();
+
+            match undo_result {
+                Some(Ok(()))        => Ok(()),
+                Some(Err(failure))  => Err(failure),
+                None                => Err(UndoFailureReason::BadEditingSequence),
+            }
+        }
+    }
+
+    ///
+    /// Redoes a child of the node at the current position in the undo log (or the most recently visited child if no node is specified)
+    ///
+    fn perform_redo_step<'a>(animation: &'a Anim, core: &'a Arc<Desync<UndoableAnimationCore>>, retired_edits: &'a mut BoxStream<'static, RetiredEdit>, child: Option<u64>) -> impl 'a+Future<Output=Result<(), UndoFailureReason>> {
+        async move {
+            // Fetch the redo action that we're about to perform
+            let redo_edit = core.future_sync(move |core| async move {
+                core.undo_log.start_undoing();
+
+                match child {
+                    Some(child) => core.undo_log.redo_to(child),
+                    None        => core.undo_log.redo()
+                }
+            }.boxed()).await.unwrap();
+
+            let redo_edit = if let Some(redo_edit) = redo_edit { 
+                redo_edit 
+            } else { 
+                core.future_sync(|core| async move { core.finish_undoing().await; }.boxed()).await.unwrap();
+                return Err(UndoFailureReason::NothingToRedo); 
+            };
+
+            // Carry out the redo action on the animation
+            animation.edit().publish(redo_edit).await;
+
+            // Wait for the redo edit to retire
+            // Note: we're relying on the edit to have been queued in sequence here so that the 'finish_undoing' happens after the
+            // undo log pipe has received this edit
+            let redone = retired_edits.next().await;
+
+            // The redo is complete at this point (the retired edit has the reverse edits for the action, which won't be known if it was loaded from storage)
+            core.future_sync(move |core| async move {
+                if let (Some(node_id), Some(redone)) = (core.undo_log.position(), redone) {
+                    let mut step = UndoStep::new();
+                    step.push_edit(redone);
+                    core.undo_log.set_step(node_id, step);
+                }
+
+                core.finish_undoing().await;
+            }.boxed()).await.unwrap();
+
+            Ok(())
+        }
+    }
+
+    ///
+    /// Moves the animation to a node in the undo history by undoing back to the closest shared node and redoing from there
+    ///
+    fn jump_to_node<'a>(animation: &'a Anim, core: Arc<Desync<UndoableAnimationCore>>, target: Option<u64>) -> impl 'a+Future<Output=Result<(), UndoFailureReason>> {
+        async move {
+            // We'll monitor the retired edits from the animation
+            let mut retired_edits   = animation.retired_edits();
+            Self::prepare_to_undo(animation, &mut retired_edits).await;
+
+            // Work out which actions need to be undone and redone
+            let route = core.future_sync(move |core| async move {
+                let route = core.undo_log.route_to(target);
+                core.persist_history();
+                route
+            }.boxed()).await.unwrap();
+            let (undo_count, redo_nodes) = route.ok_or(UndoFailureReason::NoSuchHistoryNode)?;
+
+            // Perform the actions
+            for _ in 0..undo_count {
+                Self::perform_undo_step(animation, &core, &mut retired_edits).await?;
+            }
+
+            for node in redo_nodes {
+                Self::perform_redo_step(animation, &core, &mut retired_edits, Some(node)).await?;
+            }
+
+            Ok(())
+        }
+    }
+
+    ///
+    /// Moves the animation to the most recent node in the undo history with the specified checkpoint name
+    ///
+    fn jump_to_checkpoint_node<'a>(animation: &'a Anim, core: Arc<Desync<UndoableAnimationCore>>, checkpoint: String) -> impl 'a+Future<Output=Result<(), UndoFailureReason>> {
+        async move {
+            // Wait for any checkpoints that are being sent to the animation to reach the undo log
+            let mut retired_edits   = animation.retired_edits();
+            Self::prepare_to_undo(animation, &mut retired_edits).await;
+
+            let target = core.future_sync(move |core| async move { core.undo_log.checkpoint_node(&checkpoint) }.boxed()).await.unwrap();
+            let target = target.ok_or(UndoFailureReason::NoSuchHistoryNode)?;
+
+            Self::jump_to_node(animation, core, Some(target)).await
+        }
+    }
+
     ///
     /// Undoes the last action performed on this animation
     ///
@@ -104,54 +303,8 @@ impl<Anim: 'static+Unpin+EditableAnimation> UndoableAnimation<Anim> {
                     // We'll monitor the retired edits from the animation
                     let mut retired_edits   = animation.retired_edits();
 
-                    // Use 'prepare to undo' to ensure that all the edits have retired
-                    let id                  = Uuid::new_v4().to_simple().to_string();
-                    let prepare_undo        = Arc::new(vec![AnimationEdit::Undo(UndoEdit::PrepareToUndo(id))]);
-                    animation.edit().publish(Arc::clone(&prepare_undo)).await;
-
-                    // Process edits from retired_edits until the 'prepare' event is relayed back to us
-                    while let Some(edit) = retired_edits.next().await {
-                        if edit.committed_edits() == prepare_undo {
-                            break;
-                        }
-                    }
-
-                    // Fetch the undo action that we're about to perform
-                    let undo_edit = core.future_sync(|core| async move {
-                        core.undo_log.start_undoing();
-                        core.undo_log.undo()
-                    }.boxed()).await.unwrap();
-
-                    let undo_edit = if let Some(undo_edit) = undo_edit { 
-                        undo_edit 
-                    } else { 
-                        core.future_sync(|core| async move { 
-                            core.undo_log.finish_undoing(); 
-                            core.update_undo_log_size().await;
-                        }.boxed()).await.unwrap();
-                        return Err(UndoFailureReason::NothingToUndo); 
-                    };
-
-                    // Carry out the undo action on the animation
-                    animation.edit().publish(Arc::new(vec![AnimationEdit::Undo(undo_edit)])).await;
-
-                    // A failure will produce a single retired edit, and a success will produce two, so read up to two edits
-                    let undo_result = Self::read_undo_completion(&mut retired_edits).await;
-                    let undo_result = if let Some(undo_result) = undo_result { Some(undo_result) } else { Self::read_undo_completion(&mut retired_edits).await };
-
-                    // The undo is complete at this point
-                    // Note: we're relying on the edit to have been queued in sequence here so that the 'finish_undoing' happens after the
-                    // undo log pipe has received this edit
-                    core.future_sync(|core| async move { 
-                        core.undo_log.finish_undoing(); 
-                        core.update_undo_log_size().await;
-                    }.boxed()).await.unwrap();
-
-                    match undo_result {
-                        Some(Ok(()))        => Ok(()),
-                        Some(Err(failure))  => Err(failure),
-                        None                => Err(UndoFailureReason::BadEditingSequence),
-                    }
+                    Self::prepare_to_undo(animation, &mut retired_edits).await;
+                    Self::perform_undo_step(animation, &core, &mut retired_edits).await
                 }.boxed()
             }).await.unwrap()
         }
@@ -162,7 +315,7 @@ impl<Anim: 'static+Unpin+EditableAnimation> UndoableAnimation<Anim> {
     ///
     pub fn redo<'a>(&'a self) -> impl 'a + Future<Output=Result<(), UndoFailureReason>> {
         async move {
-            // Scheduling on the animation desync will prevent any further edits from occurring while we're performing the undo
+            // Scheduling on the animation desync will prevent any further edits from occurring while we're performing the redo
             let core = self.core.clone();
 
             self.animation.future_desync(move |animation| {
@@ -170,55 +323,45 @@ impl<Anim: 'static+Unpin+EditableAnimation> UndoableAnimation<Anim> {
                     // We'll monitor the retired edits from the animation
                     let mut retired_edits   = animation.retired_edits();
 
-                    // Use 'prepare to undo' to ensure that all the edits have retired
-                    let id                  = Uuid::new_v4().to_simple().to_string();
-                    let prepare_undo        = Arc::new(vec![AnimationEdit::Undo(UndoEdit::PrepareToUndo(id))]);
-                    animation.edit().publish(Arc::clone(&prepare_undo)).await;
-
-                    // Process edits from retired_edits until the 'prepare' event is relayed back to us 
-                    // (as we're blocking the animation, no other actions will interfere with this redo action)
-                    while let Some(edit) = retired_edits.next().await {
-                        if edit.committed_edits() == prepare_undo {
-                            break;
-                        }
-                    }
-
-                    // Fetch the redo action that we're about to perform
-                    let redo_edit = core.future_sync(|core| async move {
-                        core.undo_log.start_undoing();
-                        core.undo_log.redo()
-                    }.boxed()).await.unwrap();
-
-                    let redo_edit = if let Some(redo_edit) = redo_edit { 
-                        redo_edit 
-                    } else { 
-                        core.future_sync(|core| async move { 
-                            core.undo_log.finish_undoing(); 
-                            core.update_undo_log_size().await;
-                        }.boxed()).await.unwrap();
-                        return Err(UndoFailureReason::NothingToRedo); 
-                    };
-
-                    // Carry out the redo action on the animation
-                    animation.edit().publish(redo_edit).await;
-
-                    // Wait for the redo edit to retire
-                    // Note: we're relying on the edit to have been queued in sequence here so that the 'finish_undoing' happens after the
-                    // undo log pipe has received this edit
-                    retired_edits.next().await;
-
-                    // The redo is complete at this point
-                    core.future_sync(|core| async move { 
-                        core.undo_log.finish_undoing(); 
-                        core.update_undo_log_size().await;
-                    }.boxed()).await.unwrap();
-
-                    Ok(())
+                    Self::prepare_to_undo(animation, &mut retired_edits).await;
+                    Self::perform_redo_step(animation, &core, &mut retired_edits, None).await
                 }.boxed()
             }).await.unwrap()
         }
     }
 
+    ///
+    /// Moves the animation to a node in the undo history (or to the start of the history if the node is `None`)
+    pub fn jump_to<'a>(&'a self, node: Option<u64>) -> impl 'a + Future<Output=Result<(), UndoFailureReason>> {
+        async move {
+            let core = self.core.clone();
+
+            self.animation.future_desync(move |animation| Self::jump_to_node(animation, core, node).boxed()).await.unwrap()
+        }
+    }
+
+    ///
+    /// Moves the animation to the most recent node in the undo history with the specified checkpoint name
+    ///
+    /// This is the same as sending `UndoEdit::JumpTo(checkpoint)` to the animation, except the result can be waited for
+    ///
+    pub fn jump_to_checkpoint<'a>(&'a self, checkpoint: &str) -> impl 'a + Future<Output=Result<(), UndoFailureReason>> {
+        let checkpoint = checkpoint.to_string();
+
+        async move {
+            let core = self.core.clone();
+
+            self.animation.future_desync(move |animation| Self::jump_to_checkpoint_node(animation, core, checkpoint).boxed()).await.unwrap()
+        }
+    }
+
+    ///
+    /// Retrieves the undo history for this animation
+    ///
+    pub fn history(&self) -> UndoHistory {
+        self.core.sync(|core| core.undo_log.history())
+    }
+
     ///
     /// Retrieves a stream that tracks the size of the undo log (this is an expiring stream, so backpressure will cause
     /// updates to be discarded: ie, reads will always return the lastest value)
@@ -319,6 +462,13 @@ impl<Anim: 'static+Unpin+EditableAnimation> EditableAnimation for UndoableAnimat
         self.animation.sync(|anim| anim.retired_edits())
     }
 
+    ///
+    /// Reads the undo history that has been stored for this animation
+    ///
+    fn read_undo_history(&self) -> UndoHistory {
+        self.history()
+    }
+
     ///
     /// Flushes any caches this might have (forces reload from data storage)
     ///
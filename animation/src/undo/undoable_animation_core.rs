@@ -1,5 +1,6 @@
 use super::undo_log::*;
 use super::undo_log_size::*;
+use crate::traits::*;
 
 use flo_stream::*;
 
@@ -10,8 +11,11 @@ pub struct UndoableAnimationCore {
     /// The undo log stores the list of undo and redo actions
     pub (super) undo_log: UndoLog,
 
-    /// Publisher that can be used to track changes to the number of entries in the undo log 
+    /// Publisher that can be used to track changes to the number of entries in the undo log
     pub (super) log_size_publisher: ExpiringPublisher<UndoLogSize>,
+
+    /// Sends changes to the undo history to the animation so they can be stored
+    pub (super) history_writer: Box<dyn Send+Fn(UndoHistory)>,
 }
 
 
@@ -30,4 +34,24 @@ impl UndoableAnimationCore {
 
         self.log_size_publisher.publish(log_size).await;
     }
-}
\ No newline at end of file
+
+    ///
+    /// Sends any changes to the undo history to the animation so that they're stored alongside the edit log
+    ///
+    /// The changes are sent in the background, so this never waits for the animation
+    ///
+    pub fn persist_history(&mut self) {
+        if let Some(changes) = self.undo_log.take_history_changes() {
+            (self.history_writer)(changes);
+        }
+    }
+
+    ///
+    /// Finishes an undo or redo step, storing the new history and updating the log size
+    ///
+    pub async fn finish_undoing(&mut self) {
+        self.undo_log.finish_undoing();
+        self.persist_history();
+        self.update_undo_log_size().await;
+    }
+}
@@ -55,14 +55,22 @@ impl EffectDescription {
             Sequence(sequence)          => { if sequence.len() > 0 { sequence[0].base_animation_type() } else { BaseAnimationType::BuildOverTime } }
             
             // Embedded effects like repeats or time curves preserve the base animation type of their underlying animation
-            Repeat(_length, effect)     => { effect.base_animation_type() },
-            TimeCurve(_curve, effect)   => { effect.base_animation_type() },
+            Repeat(_length, effect)         => { effect.base_animation_type() },
+            TimeCurve(_curve, effect)       => { effect.base_animation_type() },
+            EasedTimeCurve(_, _, effect)    => { effect.base_animation_type() },
 
             // Other built-in effects mean there's no 'base' type, ie we're using the build over time effect
             Other(_, _)                 |
             Move(_, _)                  |
+            FollowPath(_, _, _)         |
             FittedTransform(_, _)       |
-            StopMotionTransform(_, _)   => BaseAnimationType::BuildOverTime
+            StopMotionTransform(_, _)   |
+            RotateAround(_, _, _, _)    |
+            ScaleAround(_, _, _, _)     |
+            Fade(_, _, _)               |
+            Tint(_, _, _, _)            |
+            Skeleton(_, _)              |
+            ShapeTween(_, _)            => BaseAnimationType::BuildOverTime
         }
     }
 
@@ -101,12 +109,22 @@ impl EffectDescription {
             // Embedded effects recurse
             Repeat(length, effect)      => { Repeat(*length, Box::new(effect.update_effect_animation_type(new_base_type))) },
             TimeCurve(curve, effect)    => { TimeCurve(curve.clone(), Box::new(effect.update_effect_animation_type(new_base_type))) },
+            EasedTimeCurve(easing, length, effect) => {
+                EasedTimeCurve(*easing, *length, Box::new(effect.update_effect_animation_type(new_base_type)))
+            },
 
             // Other effects are unaffected
             Other(_, _)                 |
             Move(_, _)                  |
+            FollowPath(_, _, _)         |
             FittedTransform(_, _)       |
-            StopMotionTransform(_, _)   => self.clone()
+            StopMotionTransform(_, _)   |
+            RotateAround(_, _, _, _)    |
+            ScaleAround(_, _, _, _)     |
+            Fade(_, _, _)               |
+            Tint(_, _, _, _)            |
+            Skeleton(_, _)              |
+            ShapeTween(_, _)            => self.clone()
         }
     }
 }
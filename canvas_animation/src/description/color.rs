@@ -0,0 +1,29 @@
+use flo_canvas::*;
+
+use serde::{Serialize, Deserialize};
+
+///
+/// A colour with red, green and blue components in the range 0-1
+///
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RgbColor(pub f64, pub f64, pub f64);
+
+impl Default for RgbColor {
+    fn default() -> RgbColor {
+        RgbColor(1.0, 1.0, 1.0)
+    }
+}
+
+impl From<Color> for RgbColor {
+    fn from(color: Color) -> RgbColor {
+        let (r, g, b, _) = color.to_rgba_components();
+        RgbColor(r as _, g as _, b as _)
+    }
+}
+
+impl Into<Color> for RgbColor {
+    fn into(self) -> Color {
+        let RgbColor(r, g, b) = self;
+        Color::Rgba(r as _, g as _, b as _, 1.0)
+    }
+}
@@ -164,10 +164,21 @@ impl Into<Box<dyn AnimationEffect>> for &EffectDescription {
             FrameByFrameAddToInitial                    => Box::new(FrameByFrameEffect::AddToInitial),
             Repeat(time, effect)                        => Box::new(RepeatEffect::<Box<dyn AnimationEffect>>::repeat_effect((&**effect).into(), *time)),
             TimeCurve(curve_points, effect)             => Box::new(TimeCurveEffect::<Box<dyn AnimationEffect>>::with_control_points((&**effect).into(), curve_points.clone())),
+            EasedTimeCurve(easing, length, effect)      => Box::new(TimeCurveEffect::<Box<dyn AnimationEffect>>::with_control_points((&**effect).into(), easing.time_curve(*length))),
 
             Move(time, BezierPath(start_point, coords)) => Box::new(LinearMotionEffect::from_points(*time, start_point.into(), coords.iter().map(|BezierPoint(cp1, cp2, ep)| (cp1.into(), cp2.into(), ep.into())).collect())),
+            FollowPath(time, BezierPath(start_point, coords), orientation) => {
+                Box::new(FollowPathEffect::from_points(*time, start_point.into(), coords.iter().map(|BezierPoint(cp1, cp2, ep)| (cp1.into(), cp2.into(), ep.into())).collect(), *orientation))
+            }
             FittedTransform(anchor, points)             => optional_transform(FittedTransformEffect::by_fitting_transformation(*anchor, points.clone())),
             StopMotionTransform(anchor, points)         => Box::new(StopMotionTransformEffect::with_points(*anchor, points.clone())),
+
+            RotateAround(origin, time, start, end)      => Box::new(RotateEffect::rotate(*origin, *time, *start, *end)),
+            ScaleAround(origin, time, start, end)       => Box::new(ScaleEffect::scale(*origin, *time, *start, *end)),
+            Fade(time, start, end)                      => Box::new(FadeEffect::fade(*time, *start, *end)),
+            Tint(time, color, start, end)               => Box::new(TintEffect::tint(*time, *color, *start, *end)),
+            Skeleton(skeleton, bindings)                => Box::new(SkeletonDeformEffect::deform(skeleton.clone(), bindings.clone())),
+            ShapeTween(time, tween)                     => Box::new(ShapeTweenEffect::tween(*time, tween)),
         }
     }
 }
@@ -0,0 +1,370 @@
+use super::effect_description::*;
+
+use serde::{Serialize, Deserialize};
+
+use std::time::{Duration};
+
+///
+/// The overshoot used for the 'back' easing curves
+///
+const BACK_OVERSHOOT: f64 = 1.70158;
+
+///
+/// How close a time curve has to be to a preset (as a proportion of the length of the curve) to be decompiled to that preset
+///
+const PRESET_TOLERANCE: f64 = 1e-6;
+
+///
+/// The number of samples used when fitting a time curve to an easing function
+///
+const FIT_SAMPLES: usize = 1000;
+
+///
+/// A named easing curve that can be applied to an animation effect
+///
+/// Easing curves are compiled down to the control points used by `EffectDescription::TimeCurve` (see `time_curve()`), and
+/// time curves that match a preset can be turned back into an easing curve with `from_time_curve()`.
+///
+/// Time curves always match the actual time at the end of each section, so curves that don't have this property
+/// (elastic and bounce, which oscillate around the end time) are approximated by fitting a curve between the points
+/// where they do match. The 'steps' curve holds each step and then moves rapidly to the next one instead of jumping.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Easing {
+    /// Time passes at a constant rate
+    Linear,
+
+    /// Starts slowly and speeds up (quadratic)
+    EaseIn,
+
+    /// Starts quickly and slows down (quadratic)
+    EaseOut,
+
+    /// Starts slowly, speeds up and then slows down again (quadratic)
+    EaseInOut,
+
+    /// Starts slowly and speeds up (cubic)
+    CubicIn,
+
+    /// Starts quickly and slows down (cubic)
+    CubicOut,
+
+    /// Starts slowly, speeds up and then slows down again (cubic)
+    CubicInOut,
+
+    /// Moves backwards slightly before starting (animation times can't be before the start, so this holds at the start instead)
+    BackIn,
+
+    /// Overshoots the end before settling
+    BackOut,
+
+    /// Moves backwards slightly before starting and overshoots the end before settling (the part before the start is held at the start)
+    BackInOut,
+
+    /// Overshoots the end and springs back
+    ElasticOut,
+
+    /// Bounces against the end
+    BounceOut,
+
+    /// Moves in the specified number of steps
+    Steps(usize),
+
+    /// A custom bezier curve, with the two control points specified as a proportion of the length of the curve
+    CustomBezier(f64, f64)
+}
+
+impl Easing {
+    ///
+    /// Returns a description of this easing curve that can be displayed to the user
+    ///
+    pub fn description(&self) -> String {
+        use self::Easing::*;
+
+        match self {
+            Linear              => "Linear".to_string(),
+            EaseIn              => "Ease in".to_string(),
+            EaseOut             => "Ease out".to_string(),
+            EaseInOut           => "Ease in and out".to_string(),
+            CubicIn             => "Cubic ease in".to_string(),
+            CubicOut            => "Cubic ease out".to_string(),
+            CubicInOut          => "Cubic ease in and out".to_string(),
+            BackIn              => "Back in".to_string(),
+            BackOut             => "Back out".to_string(),
+            BackInOut           => "Back in and out".to_string(),
+            ElasticOut          => "Elastic".to_string(),
+            BounceOut           => "Bounce".to_string(),
+            Steps(num_steps)    => format!("{} steps", num_steps),
+            CustomBezier(_, _)  => "Custom curve".to_string()
+        }
+    }
+
+    ///
+    /// Returns the easing curves that can be offered to the user (excluding the custom bezier curve)
+    ///
+    pub fn presets() -> Vec<Easing> {
+        use self::Easing::*;
+
+        vec![
+            Linear,
+            EaseIn, EaseOut, EaseInOut,
+            CubicIn, CubicOut, CubicInOut,
+            BackIn, BackOut, BackInOut,
+            ElasticOut, BounceOut,
+            Steps(4)
+        ]
+    }
+
+    ///
+    /// Returns the time curve for this easing curve, with the length of the curve scaled to 1.0
+    ///
+    fn normalized_time_curve(&self) -> Vec<(f64, f64, f64)> {
+        use self::Easing::*;
+
+        match self {
+            Linear                  => vec![(1.0/3.0, 2.0/3.0, 1.0)],
+            EaseIn                  => vec![(0.0, 1.0/3.0, 1.0)],
+            EaseOut                 => vec![(2.0/3.0, 1.0, 1.0)],
+            EaseInOut               => vec![(0.0, 1.0/6.0, 0.5), (5.0/6.0, 1.0, 1.0)],
+            CubicIn                 => vec![(0.0, 0.0, 1.0)],
+            CubicOut                => vec![(1.0, 1.0, 1.0)],
+            CubicInOut              => vec![(0.0, 0.0, 0.5), (1.0, 1.0, 1.0)],
+            BackIn                  => vec![(0.0, -BACK_OVERSHOOT/3.0, 1.0)],
+            BackOut                 => vec![(1.0 + BACK_OVERSHOOT/3.0, 1.0, 1.0)],
+            BackInOut               => vec![(0.0, -BACK_OVERSHOOT/6.0, 0.5), (1.0 + BACK_OVERSHOOT/6.0, 1.0, 1.0)],
+            ElasticOut              => fit_time_curve(elastic_out),
+            BounceOut               => fit_time_curve(bounce_out),
+
+            Steps(num_steps)        => {
+                let num_steps = (*num_steps).max(1);
+
+                (0..num_steps)
+                    .map(|step| {
+                        let start   = (step as f64) / (num_steps as f64);
+                        let end     = ((step+1) as f64) / (num_steps as f64);
+
+                        (start, start, end)
+                    })
+                    .collect()
+            }
+
+            CustomBezier(cp1, cp2)  => vec![(*cp1, *cp2, 1.0)]
+        }
+    }
+
+    ///
+    /// Compiles this easing curve to the control points for a time curve (as used by `EffectDescription::TimeCurve`)
+    ///
+    pub fn time_curve(&self, length: Duration) -> Vec<(f64, f64, f64)> {
+        let length = (length.as_nanos() as f64) / 1_000_000.0;
+
+        self.normalized_time_curve()
+            .into_iter()
+            .map(|(cp1, cp2, end)| (cp1 * length, cp2 * length, end * length))
+            .collect()
+    }
+
+    ///
+    /// Finds the easing curve and length that a set of time curve control points were generated from
+    ///
+    /// Time curves with a single section that don't match one of the presets are returned as custom bezier curves. Returns
+    /// `None` if the time curve doesn't match any easing curve.
+    ///
+    pub fn from_time_curve(curve_points: &Vec<(f64, f64, f64)>) -> Option<(Easing, Duration)> {
+        // The length of the curve is the last end point
+        let length = curve_points.last()?.2;
+        if length <= 0.0 { return None; }
+
+        let normalized  = curve_points.iter().map(|(cp1, cp2, end)| (cp1/length, cp2/length, end/length)).collect::<Vec<_>>();
+        let length      = Duration::from_nanos((length * 1_000_000.0).round() as u64);
+
+        // Try the presets (the steps preset can have any number of steps, so try the one that matches the number of sections)
+        let presets     = Easing::presets().into_iter()
+            .filter(|preset| !matches!(preset, Easing::Steps(_)))
+            .chain(Some(Easing::Steps(normalized.len())));
+
+        for preset in presets {
+            let preset_curve = preset.normalized_time_curve();
+
+            if preset_curve.len() == normalized.len() && preset_curve.iter().zip(normalized.iter()).all(|(a, b)| points_match(*a, *b)) {
+                return Some((preset, length));
+            }
+        }
+
+        // Curves with a single section can be represented as custom curves
+        if normalized.len() == 1 && (normalized[0].2 - 1.0).abs() <= PRESET_TOLERANCE {
+            Some((Easing::CustomBezier(normalized[0].0, normalized[0].1), length))
+        } else {
+            None
+        }
+    }
+}
+
+impl EffectDescription {
+    ///
+    /// If this is an eased time curve, converts it to the equivalent time curve
+    ///
+    /// Other effect descriptions are returned unchanged.
+    ///
+    pub fn compile_easing(&self) -> EffectDescription {
+        match self {
+            EffectDescription::EasedTimeCurve(easing, length, effect)   => EffectDescription::TimeCurve(easing.time_curve(*length), effect.clone()),
+            _                                                           => self.clone()
+        }
+    }
+
+    ///
+    /// If this is a time curve that matches an easing curve, converts it to an eased time curve so the easing curve
+    /// can be displayed to the user
+    ///
+    /// Other effect descriptions are returned unchanged.
+    ///
+    pub fn decompile_easing(&self) -> EffectDescription {
+        match self {
+            EffectDescription::TimeCurve(curve_points, effect)  => {
+                if let Some((easing, length)) = Easing::from_time_curve(curve_points) {
+                    EffectDescription::EasedTimeCurve(easing, length, effect.clone())
+                } else {
+                    self.clone()
+                }
+            }
+
+            _                                                   => self.clone()
+        }
+    }
+}
+
+///
+/// True if two sets of time curve control points are the same
+///
+#[inline]
+fn points_match(a: (f64, f64, f64), b: (f64, f64, f64)) -> bool {
+    (a.0 - b.0).abs() <= PRESET_TOLERANCE && (a.1 - b.1).abs() <= PRESET_TOLERANCE && (a.2 - b.2).abs() <= PRESET_TOLERANCE
+}
+
+///
+/// The 'elastic out' easing function
+///
+fn elastic_out(t: f64) -> f64 {
+    if t <= 0.0 {
+        0.0
+    } else if t >= 1.0 {
+        1.0
+    } else {
+        let period = (2.0 * std::f64::consts::PI) / 3.0;
+        2.0f64.powf(-10.0 * t) * ((t * 10.0 - 0.75) * period).sin() + 1.0
+    }
+}
+
+///
+/// The 'bounce out' easing function
+///
+fn bounce_out(t: f64) -> f64 {
+    let n1 = 7.5625;
+    let d1 = 2.75;
+
+    if t < 1.0/d1 {
+        n1 * t * t
+    } else if t < 2.0/d1 {
+        let t = t - 1.5/d1;
+        n1 * t * t + 0.75
+    } else if t < 2.5/d1 {
+        let t = t - 2.25/d1;
+        n1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625/d1;
+        n1 * t * t + 0.984375
+    }
+}
+
+///
+/// Fits a time curve to an easing function over the range 0-1
+///
+/// The curve is divided into sections at the points where the easing function crosses the line `f(t) = t` (where the
+/// time curve must pass through), and each section is fitted using least squares.
+///
+fn fit_time_curve(easing_fn: impl Fn(f64) -> f64) -> Vec<(f64, f64, f64)> {
+    // Find the places where the easing function crosses the 'linear' line
+    let mut section_ends    = vec![];
+    let mut last_difference = easing_fn(1.0 / (FIT_SAMPLES as f64)) - 1.0 / (FIT_SAMPLES as f64);
+
+    for sample in 2..FIT_SAMPLES {
+        let t           = (sample as f64) / (FIT_SAMPLES as f64);
+        let difference  = easing_fn(t) - t;
+
+        if difference.signum() != last_difference.signum() {
+            // Estimate where the crossing is by linear interpolation
+            let last_t      = ((sample-1) as f64) / (FIT_SAMPLES as f64);
+            let ratio       = last_difference / (last_difference - difference);
+            let crossing    = last_t + ratio * (t - last_t);
+
+            // Very short sections can't be fitted very well, so skip crossings that are close together
+            if crossing - section_ends.last().copied().unwrap_or(0.0) > 0.01 && crossing < 0.99 {
+                section_ends.push(crossing);
+            }
+        }
+
+        last_difference = difference;
+    }
+
+    section_ends.push(1.0);
+
+    // Fit each section
+    let mut start   = 0.0;
+    let mut result  = vec![];
+
+    for end in section_ends {
+        let (cp1, cp2) = fit_section(&easing_fn, start, end);
+        result.push((cp1, cp2, end));
+
+        start = end;
+    }
+
+    result
+}
+
+///
+/// Finds the control points that best fit an easing function between two points (the start and end points of the
+/// time curve section are fixed at the start and end times)
+///
+fn fit_section(easing_fn: &impl Fn(f64) -> f64, start: f64, end: f64) -> (f64, f64) {
+    // Solve the least-squares equations for the two control points
+    let mut b1_b1   = 0.0;
+    let mut b1_b2   = 0.0;
+    let mut b2_b2   = 0.0;
+    let mut b1_r    = 0.0;
+    let mut b2_r    = 0.0;
+
+    let num_samples = 100;
+    for sample in 1..num_samples {
+        let t   = (sample as f64) / (num_samples as f64);
+        let mt  = 1.0 - t;
+
+        // Bernstein polynomials
+        let b0  = mt * mt * mt;
+        let b1  = 3.0 * mt * mt * t;
+        let b2  = 3.0 * mt * t * t;
+        let b3  = t * t * t;
+
+        // Part of the easing function that needs to be matched by the control points
+        let target      = easing_fn(start + t * (end - start));
+        let remainder   = target - b0 * start - b3 * end;
+
+        b1_b1   += b1 * b1;
+        b1_b2   += b1 * b2;
+        b2_b2   += b2 * b2;
+        b1_r    += b1 * remainder;
+        b2_r    += b2 * remainder;
+    }
+
+    let determinant = b1_b1 * b2_b2 - b1_b2 * b1_b2;
+    if determinant.abs() < 1e-12 {
+        // Fall back to a linear section
+        return (start + (end - start) / 3.0, start + (end - start) * 2.0 / 3.0);
+    }
+
+    let cp1 = (b1_r * b2_b2 - b2_r * b1_b2) / determinant;
+    let cp2 = (b2_r * b1_b1 - b1_r * b1_b2) / determinant;
+
+    (cp1, cp2)
+}
@@ -1,5 +1,9 @@
 use super::time::*;
+use super::color::*;
 use super::space::*;
+use super::easing::*;
+use super::skeleton::*;
+use super::shape_tween::*;
 
 use std::time::*;
 
@@ -23,6 +27,9 @@ pub enum EffectDescription {
     /// Applies a time curve to another animation effect
     TimeCurve(Vec<(f64, f64, f64)>, Box<EffectDescription>),
 
+    /// Applies a named easing curve with the specified length to another animation effect (this is compiled to a time curve)
+    EasedTimeCurve(Easing, Duration, Box<EffectDescription>),
+
     /// Animate frame-by-frame by replacing the entire region
     FrameByFrameReplaceWhole,
 
@@ -32,6 +39,9 @@ pub enum EffectDescription {
     /// A simple move through a bezier path at constant speed
     Move(Duration, BezierPath),
 
+    /// Moves through a bezier path, rotating to face along the path
+    FollowPath(Duration, BezierPath, PathOrientation),
+
     /// Transform through a set of points, interpolating at other times using a fitted curve
     ///
     /// Contents are an anchor point and positions for the animation
@@ -40,7 +50,66 @@ pub enum EffectDescription {
     /// Transform through a set of points with no interpolation
     ///
     /// Contents are an anchor point and positions for the animation
-    StopMotionTransform(Point2D, Vec<TimeTransformPoint>)
+    StopMotionTransform(Point2D, Vec<TimeTransformPoint>),
+
+    /// Rotates around an origin point over a period of time
+    ///
+    /// Contents are the origin, the length of the effect, and the start and end angles
+    RotateAround(Point2D, Duration, RotateDegrees, RotateDegrees),
+
+    /// Scales around an origin point over a period of time (the horizontal and vertical scale factors can be different)
+    ///
+    /// Contents are the origin, the length of the effect, and the start and end scale factors
+    ScaleAround(Point2D, Duration, Scale, Scale),
+
+    /// Changes the opacity over a period of time
+    ///
+    /// Contents are the length of the effect and the start and end opacity (0-1)
+    Fade(Duration, f64, f64),
+
+    /// Blends the colours towards a tint colour over a period of time
+    ///
+    /// Contents are the length of the effect, the tint colour and the start and end amount of tint (0-1)
+    Tint(Duration, RgbColor, f64, f64),
+
+    /// Deforms the region by moving it with the bones of a skeleton
+    ///
+    /// Contents are the skeleton and the bones that the region is bound to, along with the weight of each binding
+    Skeleton(SkeletonDescription, Vec<(usize, f64)>),
+
+    /// Morphs the paths in the region into new shapes over a period of time
+    ///
+    /// Contents are the length of the effect and the shapes that the paths change into
+    ShapeTween(Duration, ShapeTweenDescription)
+}
+
+///
+/// Describes how a region is rotated as it follows a path
+///
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PathOrientation {
+    /// True if the region should be rotated to face along the path (if false, the region is only rotated by the offset angle)
+    pub follow_tangent: bool,
+
+    /// The angle added to the direction of the path (regions are assumed to be drawn facing to the right, so this can be used for regions facing in a different direction)
+    pub offset_angle: RotateDegrees,
+
+    /// If set, the rotation to follow the path is limited to this many degrees either side of horizontal
+    pub bank_limit: Option<RotateDegrees>,
+
+    /// True if the path should be followed at a constant speed, false if each section of the path should take the same amount of time
+    pub constant_speed: bool
+}
+
+impl Default for PathOrientation {
+    fn default() -> PathOrientation {
+        PathOrientation {
+            follow_tangent: true,
+            offset_angle:   RotateDegrees(0.0),
+            bank_limit:     None,
+            constant_speed: true
+        }
+    }
 }
 
 impl EffectDescription {
@@ -0,0 +1,145 @@
+use super::space::*;
+
+use flo_curves::{Coordinate};
+use serde::{Serialize, Deserialize};
+
+///
+/// The algorithms that can be used to move a chain of bones so that its end reaches a target point
+///
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum IkSolver {
+    /// Analytic solver for a chain of exactly two bones (eg, an arm or a leg). The middle joint keeps bending in the same direction
+    /// as it does in the current pose. Chains with a different number of bones are solved using FABRIK instead.
+    TwoBone,
+
+    /// 'Forward and backward reaching inverse kinematics' solver for chains of any length, stopping after the specified number
+    /// of iterations or when the end of the chain is within the specified distance of the target
+    Fabrik { max_iterations: usize, tolerance: f64 }
+}
+
+impl Default for IkSolver {
+    fn default() -> IkSolver {
+        IkSolver::Fabrik { max_iterations: 32, tolerance: 0.01 }
+    }
+}
+
+impl IkSolver {
+    ///
+    /// Given the positions of the joints in a chain of bones (the start of each bone followed by the end of the last bone), returns
+    /// the new positions of the joints after moving the end of the chain towards the target. The first joint is never moved, and the
+    /// lengths of the bones are preserved.
+    ///
+    pub fn solve(&self, joints: &Vec<Point2D>, target: Point2D) -> Vec<Point2D> {
+        match self {
+            IkSolver::TwoBone if joints.len() == 3  => {
+                let (middle, end) = solve_two_bone_ik(joints[0], joints[1], joints[2], target);
+                vec![joints[0], middle, end]
+            }
+
+            IkSolver::TwoBone                       => IkSolver::default().solve(joints, target),
+
+            IkSolver::Fabrik { max_iterations, tolerance } => solve_fabrik(joints, target, *max_iterations, *tolerance)
+        }
+    }
+}
+
+///
+/// Returns the unit vector pointing from one point towards another (or a vector pointing right if the points are the same)
+///
+fn direction(from: Point2D, to: Point2D) -> Point2D {
+    let offset      = to - from;
+    let magnitude   = offset.magnitude();
+
+    if magnitude > 0.0 {
+        offset * (1.0/magnitude)
+    } else {
+        Point2D(1.0, 0.0)
+    }
+}
+
+///
+/// Solves the inverse kinematics for a chain of two bones, returning the new position of the middle joint and the end of the chain
+///
+/// The root joint stays where it is and the lengths of the bones are preserved. If the target is out of reach, the chain is stretched
+/// out towards it. The middle joint bends to the same side as it is on in the current pose.
+///
+pub fn solve_two_bone_ik(root: Point2D, middle: Point2D, end: Point2D, target: Point2D) -> (Point2D, Point2D) {
+    let upper_length    = root.distance_to(&middle);
+    let lower_length    = middle.distance_to(&end);
+
+    // Clamp the target distance to the range that the chain can reach
+    let min_distance    = (upper_length - lower_length).abs();
+    let max_distance    = upper_length + lower_length;
+    let distance        = root.distance_to(&target).max(min_distance).min(max_distance);
+
+    if upper_length <= 0.0 || distance <= 0.0 {
+        // Degenerate chain: just point the lower bone at the target
+        let new_end = middle + direction(middle, target) * lower_length;
+        return (middle, new_end);
+    }
+
+    // The angle between the upper bone and the line from the root to the target comes from the law of cosines
+    let cos_angle       = (upper_length*upper_length + distance*distance - lower_length*lower_length) / (2.0 * upper_length * distance);
+    let joint_angle     = cos_angle.max(-1.0).min(1.0).acos();
+
+    // Bend in the same direction as the current pose
+    let Point2D(dx, dy) = direction(root, target);
+    let base_angle      = dy.atan2(dx);
+    let current_side    = (middle - root).0 * (end - root).1 - (middle - root).1 * (end - root).0;
+    let upper_angle     = if current_side >= 0.0 { base_angle - joint_angle } else { base_angle + joint_angle };
+
+    let new_middle      = root + Point2D(upper_angle.cos(), upper_angle.sin()) * upper_length;
+    let reached_target  = root + Point2D(dx, dy) * distance;
+    let new_end         = new_middle + direction(new_middle, reached_target) * lower_length;
+
+    (new_middle, new_end)
+}
+
+///
+/// Solves the inverse kinematics for a chain of bones using the FABRIK algorithm, returning the new positions of the joints
+///
+/// The first joint stays where it is and the lengths of the bones are preserved. If the target is out of reach, the chain is
+/// stretched out towards it.
+///
+pub fn solve_fabrik(joints: &Vec<Point2D>, target: Point2D, max_iterations: usize, tolerance: f64) -> Vec<Point2D> {
+    if joints.len() < 2 {
+        return joints.clone();
+    }
+
+    let mut joints      = joints.clone();
+    let root            = joints[0];
+    let lengths         = joints.iter().zip(joints.iter().skip(1)).map(|(start, end)| start.distance_to(end)).collect::<Vec<_>>();
+    let total_length    = lengths.iter().sum::<f64>();
+    let last_joint      = joints.len()-1;
+
+    if root.distance_to(&target) >= total_length {
+        // Target is out of reach: straighten the chain to point at the target
+        let towards_target = direction(root, target);
+
+        for bone_idx in 0..lengths.len() {
+            joints[bone_idx+1] = joints[bone_idx] + towards_target * lengths[bone_idx];
+        }
+
+        return joints;
+    }
+
+    for _iteration in 0..max_iterations {
+        if joints[last_joint].distance_to(&target) <= tolerance {
+            break;
+        }
+
+        // Backward pass: move the end to the target and work back towards the root
+        joints[last_joint] = target;
+        for bone_idx in (0..lengths.len()).rev() {
+            joints[bone_idx] = joints[bone_idx+1] + direction(joints[bone_idx+1], joints[bone_idx]) * lengths[bone_idx];
+        }
+
+        // Forward pass: move the root back to where it started and work towards the end
+        joints[0] = root;
+        for bone_idx in 0..lengths.len() {
+            joints[bone_idx+1] = joints[bone_idx] + direction(joints[bone_idx], joints[bone_idx+1]) * lengths[bone_idx];
+        }
+    }
+
+    joints
+}
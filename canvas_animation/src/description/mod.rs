@@ -1,15 +1,25 @@
 mod time;
+mod color;
 mod space;
+mod easing;
 mod convert;
+mod skeleton;
+mod shape_tween;
 mod sub_effect;
 mod base_animation;
+mod inverse_kinematics;
 mod effect_description;
 mod region_description;
 
 pub use self::time::*;
+pub use self::color::*;
 pub use self::space::*;
+pub use self::easing::*;
 pub use self::convert::*;
+pub use self::skeleton::*;
+pub use self::shape_tween::*;
 pub use self::sub_effect::*;
 pub use self::base_animation::*;
+pub use self::inverse_kinematics::*;
 pub use self::effect_description::*;
 pub use self::region_description::*;
@@ -0,0 +1,56 @@
+use super::space::*;
+use super::color::*;
+use crate::path::*;
+
+use flo_canvas::{Color};
+use serde::{Serialize, Deserialize};
+
+///
+/// Describes the shape that a path in a region turns into at the end of a shape tween
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TweenPathDescription {
+    /// The subpaths that make up the final shape
+    pub path: Vec<BezierPath>,
+
+    /// The final colour and opacity of the path (or None to keep the original colour)
+    pub color: Option<(RgbColor, f64)>,
+
+    /// The final width of the path, if it's drawn as a stroke (or None to keep the original width)
+    pub width: Option<f64>
+}
+
+///
+/// Describes how the paths in a region morph into a new shape over time
+///
+/// The first path in the region turns into the first target, the second path into the second target and so on. Hints are pairs of
+/// points on the original and final shapes that should end up matched to each other.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShapeTweenDescription {
+    /// The shapes that the paths in the region morph into
+    pub targets: Vec<TweenPathDescription>,
+
+    /// Points on the original shape and the point on the final shape that they should move to
+    pub hints: Vec<(Point2D, Point2D)>
+}
+
+impl From<&AnimationPath> for TweenPathDescription {
+    fn from(path: &AnimationPath) -> TweenPathDescription {
+        use self::AnimationPathAttribute::*;
+
+        let (color, width) = match path.attributes {
+            Stroke(_, width, color, _, _)       |
+            StrokePixels(_, width, color, _, _) => (Some(color), Some(width as f64)),
+            Fill(_, color, _)                   => (Some(color), None),
+            FillTexture(_, _, _, _, _, _)       |
+            FillGradient(_, _, _, _, _, _)      => (None, None)
+        };
+
+        TweenPathDescription {
+            path:   path.path.iter().map(|subpath| BezierPath::from(subpath)).collect(),
+            color:  color.map(|color: Color| (RgbColor::from(color), color.to_rgba_components().3 as f64)),
+            width:  width
+        }
+    }
+}
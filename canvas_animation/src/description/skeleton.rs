@@ -0,0 +1,253 @@
+use super::space::*;
+
+use flo_canvas::{Transform2D};
+use flo_curves::{Coordinate};
+use serde::{Serialize, Deserialize};
+
+use std::time::{Duration};
+
+///
+/// Describes a single bone in a skeleton
+///
+/// Bones rotate around their start point. The rotation of a bone is relative to its parent, so rotating a bone will also
+/// move all of its children.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BoneDescription {
+    /// The index of the parent of this bone in the skeleton (or None if this is a root bone)
+    pub parent: Option<usize>,
+
+    /// The position of the start of the bone in the rest pose (the joint that the bone rotates around)
+    pub start: Point2D,
+
+    /// The position of the end of the bone in the rest pose
+    pub end: Point2D,
+
+    /// The rotation of this bone relative to its parent at various times, in time order
+    pub rotations: Vec<(Duration, RotateDegrees)>
+}
+
+///
+/// Describes a skeleton as a set of bones
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SkeletonDescription(pub Vec<BoneDescription>);
+
+impl BoneDescription {
+    ///
+    /// Creates a bone with no rotations
+    ///
+    pub fn new(parent: Option<usize>, start: Point2D, end: Point2D) -> BoneDescription {
+        BoneDescription {
+            parent:     parent,
+            start:      start,
+            end:        end,
+            rotations:  vec![]
+        }
+    }
+
+    ///
+    /// The angle of this bone in the rest pose, in radians
+    ///
+    pub fn rest_angle(&self) -> f64 {
+        let Point2D(x1, y1) = self.start;
+        let Point2D(x2, y2) = self.end;
+
+        (y2-y1).atan2(x2-x1)
+    }
+
+    ///
+    /// The length of this bone
+    ///
+    pub fn length(&self) -> f64 {
+        self.start.distance_to(&self.end)
+    }
+
+    ///
+    /// Returns the rotation of this bone relative to its parent at the specified time
+    ///
+    /// Rotations are interpolated linearly between the times where they're set, and stay fixed before the first and after the last time
+    ///
+    pub fn rotation_at_time(&self, time: Duration) -> RotateDegrees {
+        let after = self.rotations.iter().position(|(when, _)| *when > time);
+
+        match after {
+            None if self.rotations.len() == 0   => RotateDegrees(0.0),
+            None                                => self.rotations[self.rotations.len()-1].1,
+            Some(0)                             => self.rotations[0].1,
+
+            Some(after)                         => {
+                let (start_time, RotateDegrees(start_angle))    = self.rotations[after-1];
+                let (end_time, RotateDegrees(end_angle))        = self.rotations[after];
+
+                let start_time  = start_time.as_nanos() as f64;
+                let end_time    = end_time.as_nanos() as f64;
+                let time        = time.as_nanos() as f64;
+                let ratio       = (time - start_time) / (end_time - start_time);
+
+                RotateDegrees(start_angle + (end_angle - start_angle) * ratio)
+            }
+        }
+    }
+
+    ///
+    /// Sets the rotation of this bone at a particular time, replacing any rotation that's already set for that time
+    ///
+    pub fn set_rotation(&mut self, time: Duration, rotation: RotateDegrees) {
+        match self.rotations.binary_search_by(|(when, _)| when.cmp(&time)) {
+            Ok(existing_idx)    => { self.rotations[existing_idx] = (time, rotation); }
+            Err(insert_idx)     => { self.rotations.insert(insert_idx, (time, rotation)); }
+        }
+    }
+}
+
+impl SkeletonDescription {
+    ///
+    /// The time of the last rotation in this skeleton (after this time, the skeleton will stay in the same pose)
+    ///
+    pub fn duration(&self) -> Duration {
+        self.0.iter()
+            .flat_map(|bone| bone.rotations.last())
+            .map(|(when, _)| *when)
+            .max()
+            .unwrap_or(Duration::from_millis(0))
+    }
+
+    ///
+    /// Returns the indexes of the bones from the root of the skeleton to the specified bone (inclusive)
+    ///
+    pub fn chain_to_root(&self, bone: usize) -> Vec<usize> {
+        let mut chain   = vec![];
+        let mut current = Some(bone);
+
+        while let Some(bone_idx) = current {
+            // Stop if the bone doesn't exist or if the parents form a loop
+            if bone_idx >= self.0.len() || chain.contains(&bone_idx) { break; }
+
+            chain.push(bone_idx);
+            current = self.0[bone_idx].parent;
+        }
+
+        chain.reverse();
+        chain
+    }
+
+    ///
+    /// The total rotation applied to a bone at a particular time (ie, the sum of its rotation and the rotations of all of its parents), in degrees
+    ///
+    pub fn total_rotation_at_time(&self, bone: usize, time: Duration) -> RotateDegrees {
+        RotateDegrees(self.chain_to_root(bone).into_iter()
+            .map(|bone_idx| self.0[bone_idx].rotation_at_time(time).0)
+            .sum())
+    }
+
+    ///
+    /// Returns the transformation that moves the points attached to each bone from the rest pose to the pose at the specified time
+    ///
+    pub fn bone_transforms(&self, time: Duration) -> Vec<Transform2D> {
+        self.0.iter().enumerate()
+            .map(|(bone_idx, _)| {
+                // Each bone rotates around its start point, after the transformations of the parent bones have been applied
+                self.chain_to_root(bone_idx).into_iter()
+                    .map(|bone_idx| {
+                        let bone    = &self.0[bone_idx];
+                        let angle   = bone.rotation_at_time(time);
+
+                        let rotate: Transform2D = TransformWithAnchor(bone.start, TransformPoint(Point2D::default(), Scale::default(), angle.into())).into();
+                        rotate
+                    })
+                    .fold(Transform2D::identity(), |parent_transform, bone_transform| parent_transform * bone_transform)
+            })
+            .collect()
+    }
+
+    ///
+    /// Returns the positions of the joints for a chain of bones at a particular time
+    ///
+    /// The chain should be a list of bones where each bone is the parent of the next, as returned by `chain_to_root`. The result has
+    /// one more point than there are bones in the chain: the start point of each bone followed by the end point of the last bone.
+    ///
+    pub fn joint_positions(&self, chain: &Vec<usize>, time: Duration) -> Vec<Point2D> {
+        let transforms = self.bone_transforms(time);
+
+        chain.iter()
+            .map(|bone_idx| self.0[*bone_idx].start.transform(&transforms[*bone_idx]))
+            .chain(chain.last().map(|bone_idx| self.0[*bone_idx].end.transform(&transforms[*bone_idx])))
+            .collect()
+    }
+
+    ///
+    /// Given a chain of bones and the new positions of its joints (as generated by one of the IK solvers), returns the
+    /// rotation relative to its parent that each bone in the chain needs at the specified time to reach those positions
+    ///
+    pub fn rotations_for_joints(&self, chain: &Vec<usize>, joints: &Vec<Point2D>, time: Duration) -> Vec<(usize, RotateDegrees)> {
+        // The rotation inherited by the first bone in the chain is not changed
+        let mut parent_rotation = chain.first()
+            .and_then(|first_bone| self.0[*first_bone].parent)
+            .map(|parent_bone| self.total_rotation_at_time(parent_bone, time).0)
+            .unwrap_or(0.0);
+
+        chain.iter().enumerate()
+            .filter(|(joint_idx, _)| joint_idx+1 < joints.len())
+            .map(|(joint_idx, bone_idx)| {
+                let Point2D(x1, y1) = joints[joint_idx];
+                let Point2D(x2, y2) = joints[joint_idx+1];
+
+                // The total rotation is the difference between the new angle and the rest angle
+                let new_angle       = (y2-y1).atan2(x2-x1);
+                let total_rotation  = RotateRadians(new_angle - self.0[*bone_idx].rest_angle());
+                let total_rotation  = Into::<RotateDegrees>::into(total_rotation).0;
+
+                // Work out the rotation relative to the parent, and normalize to the range -180 to 180
+                let mut rotation    = total_rotation - parent_rotation;
+                while rotation > 180.0  { rotation -= 360.0; }
+                while rotation < -180.0 { rotation += 360.0; }
+
+                parent_rotation     += rotation;
+
+                (*bone_idx, RotateDegrees(rotation))
+            })
+            .collect()
+    }
+
+    ///
+    /// Works out how much each bone affects a point, given the weights that an element is bound to those bones with
+    ///
+    /// The weight for each bone is the binding weight, scaled by the inverse square of the distance from the point to the bone in
+    /// its rest pose, so points near a joint will bend smoothly between the bones on either side. The weights are normalized to add up to 1.
+    ///
+    pub fn weights_for_point(&self, point: &Point2D, bindings: &Vec<(usize, f64)>) -> Vec<(usize, f64)> {
+        let weights = bindings.iter()
+            .filter(|(bone_idx, _)| *bone_idx < self.0.len())
+            .map(|(bone_idx, binding_weight)| {
+                let distance = distance_to_bone(point, &self.0[*bone_idx]);
+                (*bone_idx, binding_weight / (1.0 + distance*distance))
+            })
+            .collect::<Vec<_>>();
+
+        let total_weight: f64 = weights.iter().map(|(_, weight)| weight).sum();
+
+        if total_weight > 0.0 {
+            weights.into_iter().map(|(bone_idx, weight)| (bone_idx, weight / total_weight)).collect()
+        } else {
+            vec![]
+        }
+    }
+}
+
+///
+/// The distance from a point to the line segment that makes up a bone in its rest pose
+///
+fn distance_to_bone(point: &Point2D, bone: &BoneDescription) -> f64 {
+    let along       = bone.end - bone.start;
+    let length_sq   = along.dot(&along);
+
+    if length_sq <= 0.0 {
+        point.distance_to(&bone.start)
+    } else {
+        let t       = ((*point - bone.start).dot(&along) / length_sq).max(0.0).min(1.0);
+        let nearest = bone.start + along * t;
+
+        point.distance_to(&nearest)
+    }
+}
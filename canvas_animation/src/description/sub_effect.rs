@@ -1,4 +1,8 @@
+use super::color::*;
 use super::space::*;
+use super::easing::*;
+use super::skeleton::*;
+use super::shape_tween::*;
 use super::effect_description::*;
 
 use smallvec::*;
@@ -28,8 +32,29 @@ pub enum SubEffectType {
     /// Follows a curve at a constant speed
     LinearPosition,
 
+    /// Follows a curve, rotating to face along it
+    FollowPath,
+
     /// A stop-motion or fitted transformation effect
-    TransformPosition
+    TransformPosition,
+
+    /// Rotates around an origin point
+    Rotation,
+
+    /// Scales around an origin point
+    Scaling,
+
+    /// Fades in or out
+    Opacity,
+
+    /// Blends towards a tint colour
+    Tint,
+
+    /// Deforms using the bones of a skeleton
+    Skeleton,
+
+    /// Morphs into a new shape
+    ShapeTween
 }
 
 ///
@@ -59,7 +84,14 @@ impl SubEffectType {
             Repeat              => "Repeat",
             TimeCurve           => "Time curve",
             LinearPosition      => "Move at constant speed",
+            FollowPath          => "Follow path",
             TransformPosition   => "Transform position",
+            Rotation            => "Rotate",
+            Scaling             => "Scale",
+            Opacity             => "Fade",
+            Tint                => "Tint",
+            Skeleton            => "Skeleton",
+            ShapeTween          => "Shape tween",
         }
     }
 
@@ -72,9 +104,16 @@ impl SubEffectType {
         match self {
             Other               => EffectDescription::Other("".to_string(), json::Value::Null),
             Repeat              => EffectDescription::Repeat(Duration::from_millis(1000), EffectDescription::Sequence(vec![]).boxed()),
-            TimeCurve           => EffectDescription::TimeCurve(vec![], EffectDescription::Sequence(vec![]).boxed()),
+            TimeCurve           => EffectDescription::EasedTimeCurve(Easing::EaseInOut, Duration::from_millis(1000), EffectDescription::Sequence(vec![]).boxed()),
             LinearPosition      => EffectDescription::Move(Duration::from_millis(1000), BezierPath(Point2D(0.0, 0.0), vec![])),
-            TransformPosition   => EffectDescription::FittedTransform(Point2D(0.0, 0.0), vec![])
+            FollowPath          => EffectDescription::FollowPath(Duration::from_millis(1000), BezierPath(Point2D(0.0, 0.0), vec![]), PathOrientation::default()),
+            TransformPosition   => EffectDescription::FittedTransform(Point2D(0.0, 0.0), vec![]),
+            Rotation            => EffectDescription::RotateAround(Point2D(0.0, 0.0), Duration::from_millis(1000), RotateDegrees(0.0), RotateDegrees(360.0)),
+            Scaling             => EffectDescription::ScaleAround(Point2D(0.0, 0.0), Duration::from_millis(1000), Scale(1.0, 1.0), Scale(2.0, 2.0)),
+            Opacity             => EffectDescription::Fade(Duration::from_millis(1000), 1.0, 0.0),
+            Tint                => EffectDescription::Tint(Duration::from_millis(1000), RgbColor(1.0, 0.0, 0.0), 0.0, 1.0),
+            Skeleton            => EffectDescription::Skeleton(SkeletonDescription(vec![]), vec![]),
+            ShapeTween          => EffectDescription::ShapeTween(Duration::from_millis(1000), ShapeTweenDescription { targets: vec![], hints: vec![] })
         }
     }
 }
@@ -139,8 +178,15 @@ impl EffectDescription {
 
             Other(_name, _json)                     => { sub_effects.push(SubEffectDescription::new(SubEffectType::Other, address, self)); }
             Move(_length, _path)                    => { sub_effects.push(SubEffectDescription::new(SubEffectType::LinearPosition, address, self)); }
+            FollowPath(_length, _path, _)           => { sub_effects.push(SubEffectDescription::new(SubEffectType::FollowPath, address, self)); }
             FittedTransform(_origin, _points)       => { sub_effects.push(SubEffectDescription::new(SubEffectType::TransformPosition, address, self)); }
             StopMotionTransform(_origin, _points)   => { sub_effects.push(SubEffectDescription::new(SubEffectType::TransformPosition, address, self)); }
+            RotateAround(_, _, _, _)                => { sub_effects.push(SubEffectDescription::new(SubEffectType::Rotation, address, self)); }
+            ScaleAround(_, _, _, _)                 => { sub_effects.push(SubEffectDescription::new(SubEffectType::Scaling, address, self)); }
+            Fade(_, _, _)                           => { sub_effects.push(SubEffectDescription::new(SubEffectType::Opacity, address, self)); }
+            Tint(_, _, _, _)                        => { sub_effects.push(SubEffectDescription::new(SubEffectType::Tint, address, self)); }
+            Skeleton(_skeleton, _bindings)          => { sub_effects.push(SubEffectDescription::new(SubEffectType::Skeleton, address, self)); }
+            ShapeTween(_length, _tween)             => { sub_effects.push(SubEffectDescription::new(SubEffectType::ShapeTween, address, self)); }
 
             Repeat(_length, effect)                 => {
                 // We assume 'repeat' and 'time curve' apply to the effect as a whole and not a partial sub-effect at the moment: an improvement might be to support representing a tree of effects here
@@ -150,7 +196,8 @@ impl EffectDescription {
                 sub_effects.push(SubEffectDescription::new(SubEffectType::Repeat, address, self));
                 effect.build_sub_effects(new_address, sub_effects);
             }
-            TimeCurve(_curve, effect)               => {
+            TimeCurve(_, effect)                    |
+            EasedTimeCurve(_, _, effect)            => {
                 // We assume 'repeat' and 'time curve' apply to the effect as a whole and not a partial sub-effect at the moment: an improvement might be to support representing a tree of effects here
                 let mut new_address = address.clone();
                 new_address.push(0);
@@ -187,15 +234,23 @@ impl EffectDescription {
                 FrameByFrameReplaceWhole    |
                 FrameByFrameAddToInitial    |
                 Move(_, _)                  |
+                FollowPath(_, _, _)         |
                 FittedTransform(_, _)       |
-                StopMotionTransform(_, _)   => None,
+                StopMotionTransform(_, _)   |
+                RotateAround(_, _, _, _)    |
+                ScaleAround(_, _, _, _)     |
+                Fade(_, _, _)               |
+                Tint(_, _, _, _)            |
+                Skeleton(_, _)              |
+                ShapeTween(_, _)            => None,
 
                 Sequence(seq)               => {
                     let new_address = address.iter().skip(1).cloned().collect();
                     seq[address[0]].find_sub_effect(&new_address)
                 }
-                Repeat(_, subeffect)        |
-                TimeCurve(_, subeffect)     => {
+                Repeat(_, subeffect)            |
+                TimeCurve(_, subeffect)         |
+                EasedTimeCurve(_, _, subeffect) => {
                     let new_address = address.iter().skip(1).cloned().collect();
                     subeffect.find_sub_effect(&new_address)
                 }
@@ -216,12 +271,20 @@ impl EffectDescription {
             FrameByFrameReplaceWhole    |
             FrameByFrameAddToInitial    |
             Move(_, _)                  |
+            FollowPath(_, _, _)         |
             FittedTransform(_, _)       |
             StopMotionTransform(_, _)   |
+            RotateAround(_, _, _, _)    |
+            ScaleAround(_, _, _, _)     |
+            Fade(_, _, _)               |
+            Tint(_, _, _, _)            |
+            Skeleton(_, _)              |
+            ShapeTween(_, _)            |
             Sequence(_)                 => EffectDescription::Sequence(vec![]),
 
-            Repeat(_, subeffect)        |
-            TimeCurve(_, subeffect)     => (**subeffect).clone()
+            Repeat(_, subeffect)            |
+            TimeCurve(_, subeffect)         |
+            EasedTimeCurve(_, _, subeffect) => (**subeffect).clone()
         }
     }
 
@@ -249,16 +312,24 @@ impl EffectDescription {
         use self::EffectDescription::*;
 
         let new_effect = match new_effect {
-            Other(_, _)                 |
-            FrameByFrameReplaceWhole    |
-            FrameByFrameAddToInitial    |
-            Move(_, _)                  |
-            FittedTransform(_, _)       |
-            StopMotionTransform(_, _)   |
-            Sequence(_)                 => new_effect,
-
-            Repeat(len, _)              => Repeat(len, replaced_effect.recursive_effect().boxed()),
-            TimeCurve(curve, _)         => TimeCurve(curve, replaced_effect.recursive_effect().boxed()),
+            Other(_, _)                       |
+            FrameByFrameReplaceWhole          |
+            FrameByFrameAddToInitial          |
+            Move(_, _)                        |
+            FollowPath(_, _, _)               |
+            FittedTransform(_, _)             |
+            StopMotionTransform(_, _)         |
+            RotateAround(_, _, _, _)          |
+            ScaleAround(_, _, _, _)           |
+            Fade(_, _, _)                     |
+            Tint(_, _, _, _)                  |
+            Skeleton(_, _)                    |
+            ShapeTween(_, _)                  |
+            Sequence(_)                       => new_effect,
+
+            Repeat(len, _)                    => Repeat(len, replaced_effect.recursive_effect().boxed()),
+            TimeCurve(curve, _)               => TimeCurve(curve, replaced_effect.recursive_effect().boxed()),
+            EasedTimeCurve(easing, length, _) => EasedTimeCurve(easing, length, replaced_effect.recursive_effect().boxed()),
         };
 
         // Replace the effect
@@ -279,23 +350,35 @@ impl EffectDescription {
 
         match (new_effect, self) {
             // Nested effects have special behaviour
-            (Repeat(_, _), Repeat(_, _))            => self.clone(),
-            (TimeCurve(_, _), TimeCurve(_, _))      => self.clone(),
-            (_, Repeat(duration, nested_effect))    => Repeat(*duration, nested_effect.add_new_effect(new_effect_type).boxed()),
-            (Repeat(duration, _), _)                => Repeat(duration, self.clone().boxed()),      // Note ordering: Repeat will nest over timecurve so it has priority
-            (_, TimeCurve(curve, nested_effect))    => TimeCurve(curve.clone(), nested_effect.add_new_effect(new_effect_type).boxed()),
-            (TimeCurve(curve, _), _)                => TimeCurve(curve, self.clone().boxed()),
+            (Repeat(_, _), Repeat(_, _))                       => self.clone(),
+            (TimeCurve(_, _), TimeCurve(_, _))                 |
+            (TimeCurve(_, _), EasedTimeCurve(_, _, _))         |
+            (EasedTimeCurve(_, _, _), TimeCurve(_, _))         |
+            (EasedTimeCurve(_, _, _), EasedTimeCurve(_, _, _)) => self.clone(),
+            (_, Repeat(duration, nested_effect))               => Repeat(*duration, nested_effect.add_new_effect(new_effect_type).boxed()),
+            (Repeat(duration, _), _)                           => Repeat(duration, self.clone().boxed()),      // Note ordering: Repeat will nest over timecurve so it has priority
+            (_, TimeCurve(curve, nested_effect))               => TimeCurve(curve.clone(), nested_effect.add_new_effect(new_effect_type).boxed()),
+            (_, EasedTimeCurve(easing, length, nested))        => EasedTimeCurve(*easing, *length, nested.add_new_effect(new_effect_type).boxed()),
+            (TimeCurve(curve, _), _)                           => TimeCurve(curve, self.clone().boxed()),
+            (EasedTimeCurve(easing, length, _), _)             => EasedTimeCurve(easing, length, self.clone().boxed()),
 
             // Sequences are extended
-            (new_effect, Sequence(items))           => Sequence(items.iter().cloned().chain(iter::once(new_effect)).collect()),
+            (new_effect, Sequence(items))                      => Sequence(items.iter().cloned().chain(iter::once(new_effect)).collect()),
 
             // Standard effects get turned into a sequence
-            (new_effect, Other(_, _))               |
-            (new_effect, FrameByFrameReplaceWhole)  |
-            (new_effect, FrameByFrameAddToInitial)  |
-            (new_effect, Move(_, _))                |
-            (new_effect, FittedTransform(_, _))     |
-            (new_effect, StopMotionTransform(_, _)) => Sequence(vec![self.clone(), new_effect])
+            (new_effect, Other(_, _))                          |
+            (new_effect, FrameByFrameReplaceWhole)             |
+            (new_effect, FrameByFrameAddToInitial)             |
+            (new_effect, Move(_, _))                           |
+            (new_effect, FollowPath(_, _, _))                  |
+            (new_effect, FittedTransform(_, _))                |
+            (new_effect, StopMotionTransform(_, _))            |
+            (new_effect, RotateAround(_, _, _, _))             |
+            (new_effect, ScaleAround(_, _, _, _))              |
+            (new_effect, Fade(_, _, _))                        |
+            (new_effect, Tint(_, _, _, _))                     |
+            (new_effect, Skeleton(_, _))                       |
+            (new_effect, ShapeTween(_, _))                     => Sequence(vec![self.clone(), new_effect])
         }
     }
 }
@@ -0,0 +1,82 @@
+use crate::region::*;
+
+use std::sync::*;
+use std::time::{Duration};
+
+///
+/// Effect that changes the opacity of a region over time
+///
+/// Texture and gradient fills are not affected by this effect.
+///
+#[derive(Clone)]
+pub struct FadeEffect {
+    /// The time taken to change from the start opacity to the end opacity, in milliseconds
+    duration: f64,
+
+    /// The opacity at the start of the effect (0-1)
+    start_opacity: f64,
+
+    /// The opacity at the end of the effect (0-1)
+    end_opacity: f64
+}
+
+impl FadeEffect {
+    ///
+    /// Creates a fade effect that changes from the start opacity to the end opacity over the specified duration
+    ///
+    pub fn fade(duration: Duration, start_opacity: f64, end_opacity: f64) -> FadeEffect {
+        FadeEffect {
+            duration:       (duration.as_nanos() as f64) / 1_000_000.0,
+            start_opacity:  start_opacity,
+            end_opacity:    end_opacity
+        }
+    }
+
+    ///
+    /// Returns the opacity at a particular time
+    ///
+    pub fn opacity_at_time(&self, time: Duration) -> f64 {
+        let time    = (time.as_nanos() as f64) / 1_000_000.0;
+        let ratio   = if self.duration <= 0.0 { 1.0 } else { (time / self.duration).min(1.0).max(0.0) };
+
+        (self.start_opacity + (self.end_opacity - self.start_opacity) * ratio).min(1.0).max(0.0)
+    }
+}
+
+impl AnimationEffect for FadeEffect {
+    ///
+    /// Returns the duration of this effect (or None if this effect will animate forever)
+    ///
+    /// If the effect is passed a time that's after where the 'duration' has completed it should always generate the same result
+    ///
+    fn duration(&self) -> Option<f64> {
+        Some(self.duration)
+    }
+
+    ///
+    /// Given the contents of the regions for this effect, calculates the path that should be rendered
+    ///
+    fn animate(&self, region_contents: Arc<AnimationRegionContent>, time: Duration) -> Arc<AnimationRegionContent> {
+        // Multiply the alpha component of each path's colour by the opacity
+        let opacity = self.opacity_at_time(time) as f32;
+        let paths   = region_contents.paths()
+            .map(|path| path.with_attributes(path.attributes.map_color(|color| {
+                let (_, _, _, alpha) = color.to_rgba_components();
+                color.with_alpha(alpha * opacity)
+            })));
+
+        Arc::new(AnimationRegionContent::from_paths(paths))
+    }
+
+    ///
+    /// Given an input region that will remain fixed throughout the time period, returns a function that
+    /// will animate it. This can be used to speed up operations when some pre-processing is required for
+    /// the region contents, but is not always available as the region itself might be changing over time
+    /// (eg, if many effects are combined)
+    ///
+    fn animate_cached(&self, region_contents: Arc<AnimationRegionContent>) -> Box<dyn Send+Fn(Duration) -> Arc<AnimationRegionContent>> {
+        let cached_effect = self.clone();
+
+        Box::new(move |time| cached_effect.animate(Arc::clone(&region_contents), time))
+    }
+}
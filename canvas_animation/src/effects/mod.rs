@@ -1,16 +1,30 @@
+mod fade;
+mod tint;
+mod scale;
+mod rotate;
 mod repeat;
 mod sequence;
 mod time_curve;
 mod motion_linear;
+mod motion_follow_path;
+mod shape_tween;
+mod skeleton_deform;
 mod effect_region;
 mod frame_by_frame;
 mod transform_fitted;
 mod transform_stop_motion;
 
+pub use self::fade::*;
+pub use self::tint::*;
+pub use self::scale::*;
+pub use self::rotate::*;
 pub use self::repeat::*;
 pub use self::sequence::*;
 pub use self::time_curve::*;
 pub use self::motion_linear::*;
+pub use self::motion_follow_path::*;
+pub use self::shape_tween::*;
+pub use self::skeleton_deform::*;
 pub use self::effect_region::*;
 pub use self::frame_by_frame::*;
 pub use self::transform_fitted::*;
@@ -0,0 +1,159 @@
+use super::motion_linear::*;
+use crate::region::*;
+use crate::description::*;
+
+use flo_curves::*;
+use flo_curves::bezier::*;
+use flo_canvas::{Transform2D};
+
+use std::f64;
+use std::sync::*;
+use std::time::{Duration};
+
+///
+/// Effect that moves a region along a bezier path, rotating it to face along the path
+///
+/// The region is rotated around the start point of the path, which should be the point in the region that's placed on the path.
+///
+#[derive(Clone)]
+pub struct FollowPathEffect {
+    /// The motion along the path
+    motion: LinearMotionEffect,
+
+    /// The time taken to move through the path, in milliseconds
+    duration: f64,
+
+    /// How the region is rotated as it moves along the path
+    orientation: PathOrientation
+}
+
+///
+/// Returns the direction of a curve at a particular t value
+///
+fn tangent_at_pos(curve: &Curve<Coord2>, t: f64) -> Coord2 {
+    let start           = curve.start_point();
+    let (cp1, cp2)      = curve.control_points();
+    let end             = curve.end_point();
+
+    let (d1, d2, d3)    = derivative4(start, cp1, cp2, end);
+    let tangent         = de_casteljau3(t, d1, d2, d3);
+
+    if tangent.magnitude() > 1e-9 {
+        tangent
+    } else {
+        // Curves where the control points are on top of the end points have no tangent at the ends, so use the direction from the start to the end
+        end - start
+    }
+}
+
+///
+/// Changes an angle in degrees so it's in the range -180 to 180
+///
+fn normalize_degrees(angle: f64) -> f64 {
+    let angle = angle % 360.0;
+
+    if angle > 180.0 {
+        angle - 360.0
+    } else if angle <= -180.0 {
+        angle + 360.0
+    } else {
+        angle
+    }
+}
+
+impl FollowPathEffect {
+    ///
+    /// Creates an effect that follows the specified path, with the specified orientation
+    ///
+    pub fn from_points(duration: Duration, start_point: Coord2, path: Vec<(Coord2, Coord2, Coord2)>, orientation: PathOrientation) -> FollowPathEffect {
+        FollowPathEffect {
+            motion:         LinearMotionEffect::from_points(duration, start_point, path),
+            duration:       (duration.as_nanos() as f64) / 1_000_000.0,
+            orientation:    orientation
+        }
+    }
+
+    ///
+    /// Returns the offset from the start point and the rotation of the region at the specified time
+    ///
+    pub fn position_at_time(&self, time: f64, tolerance: f64) -> (Coord2, RotateDegrees) {
+        // Find where we are along the path
+        let section = if self.orientation.constant_speed {
+            self.motion.section_at_time(time, tolerance)
+        } else {
+            self.motion.section_at_time_by_segment(time)
+        };
+
+        let (segment, t) = if let Some(section) = section { section } else { return (Coord2(0.0, 0.0), self.orientation.offset_angle); };
+
+        // The offset is the position along the path
+        let offset = segment.point_at_pos(t) - self.motion.start_point();
+
+        // The rotation is the angle of the tangent, limited by the banking limit
+        let RotateDegrees(offset_angle) = self.orientation.offset_angle;
+
+        let angle = if self.orientation.follow_tangent {
+            let tangent     = tangent_at_pos(&segment, t);
+            let angle       = normalize_degrees(tangent.y().atan2(tangent.x()) * 180.0 / f64::consts::PI);
+
+            let angle       = if let Some(RotateDegrees(bank_limit)) = self.orientation.bank_limit {
+                let bank_limit = bank_limit.abs();
+                angle.min(bank_limit).max(-bank_limit)
+            } else {
+                angle
+            };
+
+            angle + offset_angle
+        } else {
+            offset_angle
+        };
+
+        (offset, RotateDegrees(angle))
+    }
+
+    ///
+    /// Returns the transformation to apply to the region at the specified time
+    ///
+    pub fn transform_at_time(&self, time: Duration) -> Transform2D {
+        let time                = (time.as_nanos() as f64) / 1_000_000.0;
+        let (offset, rotation)  = self.position_at_time(time, 0.01);
+        let start_point         = self.motion.start_point();
+
+        TransformWithAnchor(start_point.into(), TransformPoint(offset.into(), Scale::default(), rotation.into())).into()
+    }
+}
+
+impl AnimationEffect for FollowPathEffect {
+    ///
+    /// Returns the duration of this effect (or None if this effect will animate forever)
+    ///
+    /// If the effect is passed a time that's after where the 'duration' has completed it should always generate the same result
+    ///
+    fn duration(&self) -> Option<f64> {
+        Some(self.duration)
+    }
+
+    ///
+    /// Given the contents of the regions for this effect, calculates the path that should be rendered
+    ///
+    fn animate(&self, region_contents: Arc<AnimationRegionContent>, time: Duration) -> Arc<AnimationRegionContent> {
+        // Move and rotate all of the paths in the region
+        let transform   = self.transform_at_time(time);
+        let paths       = region_contents.paths()
+            .map(|path| path.transform_by(&transform));
+
+        Arc::new(AnimationRegionContent::from_paths(paths))
+    }
+
+    ///
+    /// Given an input region that will remain fixed throughout the time period, returns a function that
+    /// will animate it. This can be used to speed up operations when some pre-processing is required for
+    /// the region contents, but is not always available as the region itself might be changing over time
+    /// (eg, if many effects are combined)
+    ///
+    fn animate_cached(&self, region_contents: Arc<AnimationRegionContent>) -> Box<dyn Send+Fn(Duration) -> Arc<AnimationRegionContent>> {
+        let cached_effect = self.clone();
+
+        Box::new(move |time| cached_effect.animate(Arc::clone(&region_contents), time))
+    }
+}
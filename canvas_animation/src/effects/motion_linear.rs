@@ -72,9 +72,19 @@ impl LinearMotionEffect {
     }
 
     ///
-    /// Returns the offset from the start point at the specified time
+    /// Returns the point where the path starts
     ///
-    pub fn offset_at_time(&self, time: f64, tolerance: f64) -> Coord2 {
+    pub fn start_point(&self) -> Coord2 {
+        self.start_point
+    }
+
+    ///
+    /// Returns the curve section and the t value along it for the point on the path at the specified time
+    ///
+    /// The path is followed at a constant speed. Times beyond the end of the path return the end of the last section.
+    /// Returns `None` if the path is empty.
+    ///
+    pub fn section_at_time(&self, time: f64, tolerance: f64) -> Option<(Curve<Coord2>, f64)> {
         // Work out the distance down the path that we want for this time
         let distance                = time / self.duration * self.total_length;
 
@@ -82,6 +92,7 @@ impl LinearMotionEffect {
         let mut start_point         = self.start_point;
         let mut segment_iter        = self.path.iter();
         let mut segment_distance    = distance;
+        let mut last_segment        = None;
         let segment;
         let segment_length;
 
@@ -95,15 +106,18 @@ impl LinearMotionEffect {
                 }
 
                 // Move to the start of the next egment
+                last_segment        = Some(Curve::from_points(start_point, (point.cp1, point.cp2), point.end_point));
                 start_point         = point.end_point;
                 segment_distance    -= point.segment_length;
             } else {
                 // No point matches this distance (the end of the path is where everything appears after this pont)
-                return start_point;
+                return last_segment.map(|segment| (segment, 1.0));
             }
         }
 
         // Estimate a t location for this distance
+        if segment_length <= 0.0 { return Some((segment, 0.0)); }
+
         let mut min_t       = 0.0;
         let mut t           = segment_distance / segment_length;
 
@@ -131,11 +145,42 @@ impl LinearMotionEffect {
             }
         }
 
-        // Offset is the point on the curve at t minus the start point
-        let curve_point = segment.point_at_pos(closest_t);
-        let offset      = curve_point - self.start_point;
+        Some((segment, closest_t))
+    }
+
+    ///
+    /// Returns the curve section and the t value along it for the point on the path at the specified time, where each section
+    /// of the path takes the same amount of time to travel along (so the speed varies along the path)
+    ///
+    /// Returns `None` if the path is empty.
+    ///
+    pub fn section_at_time_by_segment(&self, time: f64) -> Option<(Curve<Coord2>, f64)> {
+        if self.path.len() == 0 { return None; }
+
+        // Work out which segment the time is in
+        let ratio       = if self.duration <= 0.0 { 1.0 } else { (time / self.duration).min(1.0).max(0.0) };
+        let position    = ratio * (self.path.len() as f64);
+        let index       = (position.floor() as usize).min(self.path.len()-1);
+        let t           = position - (index as f64);
+
+        // Generate the curve for that segment
+        let start_point = if index == 0 { self.start_point } else { self.path[index-1].end_point };
+        let point       = &self.path[index];
+
+        Some((Curve::from_points(start_point, (point.cp1, point.cp2), point.end_point), t))
+    }
 
-        offset
+    ///
+    /// Returns the offset from the start point at the specified time
+    ///
+    pub fn offset_at_time(&self, time: f64, tolerance: f64) -> Coord2 {
+        // Offset is the point on the curve at t minus the start point
+        if let Some((segment, t)) = self.section_at_time(time, tolerance) {
+            let curve_point = segment.point_at_pos(t);
+            curve_point - self.start_point
+        } else {
+            Coord2(0.0, 0.0)
+        }
     }
 }
 
@@ -0,0 +1,96 @@
+use crate::region::*;
+use crate::description::*;
+
+use flo_canvas::{Transform2D};
+
+use std::sync::*;
+use std::time::{Duration};
+
+///
+/// Effect that rotates a region around an origin point over time
+///
+#[derive(Clone)]
+pub struct RotateEffect {
+    /// The point that the region is rotated around
+    origin: Point2D,
+
+    /// The time taken to rotate from the start angle to the end angle, in milliseconds
+    duration: f64,
+
+    /// The angle at the start of the effect
+    start_angle: RotateDegrees,
+
+    /// The angle at the end of the effect
+    end_angle: RotateDegrees
+}
+
+impl RotateEffect {
+    ///
+    /// Creates a rotation effect that rotates from the start angle to the end angle over the specified duration
+    ///
+    pub fn rotate(origin: Point2D, duration: Duration, start_angle: RotateDegrees, end_angle: RotateDegrees) -> RotateEffect {
+        RotateEffect {
+            origin:         origin,
+            duration:       (duration.as_nanos() as f64) / 1_000_000.0,
+            start_angle:    start_angle,
+            end_angle:      end_angle
+        }
+    }
+
+    ///
+    /// Returns the angle of the rotation at a particular time
+    ///
+    pub fn angle_at_time(&self, time: Duration) -> RotateDegrees {
+        let time    = (time.as_nanos() as f64) / 1_000_000.0;
+        let ratio   = if self.duration <= 0.0 { 1.0 } else { (time / self.duration).min(1.0).max(0.0) };
+
+        let RotateDegrees(start)    = self.start_angle;
+        let RotateDegrees(end)      = self.end_angle;
+
+        RotateDegrees(start + (end - start) * ratio)
+    }
+
+    ///
+    /// Returns the transform to apply at a particular time
+    ///
+    pub fn transform_at_time(&self, time: Duration) -> Transform2D {
+        let angle = self.angle_at_time(time);
+
+        TransformWithAnchor(self.origin, TransformPoint(Point2D::default(), Scale::default(), angle.into())).into()
+    }
+}
+
+impl AnimationEffect for RotateEffect {
+    ///
+    /// Returns the duration of this effect (or None if this effect will animate forever)
+    ///
+    /// If the effect is passed a time that's after where the 'duration' has completed it should always generate the same result
+    ///
+    fn duration(&self) -> Option<f64> {
+        Some(self.duration)
+    }
+
+    ///
+    /// Given the contents of the regions for this effect, calculates the path that should be rendered
+    ///
+    fn animate(&self, region_contents: Arc<AnimationRegionContent>, time: Duration) -> Arc<AnimationRegionContent> {
+        // Rotate all of the paths in the region
+        let transform   = self.transform_at_time(time);
+        let paths       = region_contents.paths()
+            .map(|path| path.transform_by(&transform));
+
+        Arc::new(AnimationRegionContent::from_paths(paths))
+    }
+
+    ///
+    /// Given an input region that will remain fixed throughout the time period, returns a function that
+    /// will animate it. This can be used to speed up operations when some pre-processing is required for
+    /// the region contents, but is not always available as the region itself might be changing over time
+    /// (eg, if many effects are combined)
+    ///
+    fn animate_cached(&self, region_contents: Arc<AnimationRegionContent>) -> Box<dyn Send+Fn(Duration) -> Arc<AnimationRegionContent>> {
+        let cached_effect = self.clone();
+
+        Box::new(move |time| cached_effect.animate(Arc::clone(&region_contents), time))
+    }
+}
@@ -0,0 +1,98 @@
+use crate::region::*;
+use crate::description::*;
+
+use flo_canvas::{Transform2D};
+
+use std::sync::*;
+use std::time::{Duration};
+
+///
+/// Effect that scales a region around an origin point over time
+///
+/// The horizontal and vertical scale factors can be different
+///
+#[derive(Clone)]
+pub struct ScaleEffect {
+    /// The point that the region is scaled around
+    origin: Point2D,
+
+    /// The time taken to scale from the start size to the end size, in milliseconds
+    duration: f64,
+
+    /// The scale factor at the start of the effect
+    start_scale: Scale,
+
+    /// The scale factor at the end of the effect
+    end_scale: Scale
+}
+
+impl ScaleEffect {
+    ///
+    /// Creates a scale effect that changes from the start scale factor to the end scale factor over the specified duration
+    ///
+    pub fn scale(origin: Point2D, duration: Duration, start_scale: Scale, end_scale: Scale) -> ScaleEffect {
+        ScaleEffect {
+            origin:         origin,
+            duration:       (duration.as_nanos() as f64) / 1_000_000.0,
+            start_scale:    start_scale,
+            end_scale:      end_scale
+        }
+    }
+
+    ///
+    /// Returns the scale factor at a particular time
+    ///
+    pub fn scale_at_time(&self, time: Duration) -> Scale {
+        let time    = (time.as_nanos() as f64) / 1_000_000.0;
+        let ratio   = if self.duration <= 0.0 { 1.0 } else { (time / self.duration).min(1.0).max(0.0) };
+
+        let Scale(start_x, start_y) = self.start_scale;
+        let Scale(end_x, end_y)     = self.end_scale;
+
+        Scale(start_x + (end_x - start_x) * ratio, start_y + (end_y - start_y) * ratio)
+    }
+
+    ///
+    /// Returns the transform to apply at a particular time
+    ///
+    pub fn transform_at_time(&self, time: Duration) -> Transform2D {
+        let scale = self.scale_at_time(time);
+
+        TransformWithAnchor(self.origin, TransformPoint(Point2D::default(), scale, RotateRadians::default())).into()
+    }
+}
+
+impl AnimationEffect for ScaleEffect {
+    ///
+    /// Returns the duration of this effect (or None if this effect will animate forever)
+    ///
+    /// If the effect is passed a time that's after where the 'duration' has completed it should always generate the same result
+    ///
+    fn duration(&self) -> Option<f64> {
+        Some(self.duration)
+    }
+
+    ///
+    /// Given the contents of the regions for this effect, calculates the path that should be rendered
+    ///
+    fn animate(&self, region_contents: Arc<AnimationRegionContent>, time: Duration) -> Arc<AnimationRegionContent> {
+        // Scale all of the paths in the region
+        let transform   = self.transform_at_time(time);
+        let paths       = region_contents.paths()
+            .map(|path| path.transform_by(&transform));
+
+        Arc::new(AnimationRegionContent::from_paths(paths))
+    }
+
+    ///
+    /// Given an input region that will remain fixed throughout the time period, returns a function that
+    /// will animate it. This can be used to speed up operations when some pre-processing is required for
+    /// the region contents, but is not always available as the region itself might be changing over time
+    /// (eg, if many effects are combined)
+    ///
+    fn animate_cached(&self, region_contents: Arc<AnimationRegionContent>) -> Box<dyn Send+Fn(Duration) -> Arc<AnimationRegionContent>> {
+        let cached_effect = self.clone();
+
+        Box::new(move |time| cached_effect.animate(Arc::clone(&region_contents), time))
+    }
+}
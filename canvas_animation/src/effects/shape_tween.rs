@@ -0,0 +1,425 @@
+use crate::path::*;
+use crate::region::*;
+use crate::description::*;
+
+use flo_canvas::{Color};
+use flo_curves::*;
+use flo_curves::bezier::path::*;
+
+use std::sync::*;
+use std::time::{Duration};
+
+/// How much more a hint point counts towards the cost of a correspondence than a point on the path
+const HINT_WEIGHT: f64 = 1000.0;
+
+/// Distance below which the start and end of a path are considered to be the same point
+const CLOSED_PATH_TOLERANCE: f64 = 0.01;
+
+///
+/// A path in a region along with the subpaths that have been matched up with the target shape
+///
+#[derive(Clone)]
+struct MatchedPath {
+    /// The original path
+    source: AnimationPath,
+
+    /// Pairs of subpaths with the same number of points (original shape, final shape)
+    subpaths: Vec<(SimpleBezierPath, SimpleBezierPath)>,
+
+    /// The final colour of the path, if it changes
+    color: Option<Color>,
+
+    /// The final width of the path, if it changes
+    width: Option<f32>
+}
+
+///
+/// Effect that morphs the paths in a region into a new shape over time
+///
+/// Paths are resampled so that the original and final shapes have the same number of curves, then the points are matched up
+/// so that they move the shortest total distance (taking any hint points into account). The colour and width of each path
+/// are also blended towards the final values. Paths with no target shape are left as they are.
+///
+#[derive(Clone)]
+pub struct ShapeTweenEffect {
+    /// The time taken for the shape to change, in milliseconds
+    duration: f64,
+
+    /// The shapes that the paths in the region turn into
+    targets: Arc<Vec<TweenPathDescription>>,
+
+    /// Pairs of points on the original and final shapes that should be matched with each other
+    hints: Arc<Vec<(Coord2, Coord2)>>
+}
+
+///
+/// Linearly interpolates between two points
+///
+#[inline]
+fn lerp(from: Coord2, to: Coord2, ratio: f64) -> Coord2 {
+    from + (to - from) * ratio
+}
+
+///
+/// Estimates the length of a bezier curve using its control polygon
+///
+fn curve_length(start: Coord2, (cp1, cp2, end): (Coord2, Coord2, Coord2)) -> f64 {
+    let polygon = start.distance_to(&cp1) + cp1.distance_to(&cp2) + cp2.distance_to(&end);
+    let chord   = start.distance_to(&end);
+
+    (polygon + chord) / 2.0
+}
+
+///
+/// Splits a bezier curve in half
+///
+fn split_curve(start: Coord2, (cp1, cp2, end): (Coord2, Coord2, Coord2)) -> ((Coord2, Coord2, Coord2), (Coord2, Coord2, Coord2)) {
+    let p01     = lerp(start, cp1, 0.5);
+    let p12     = lerp(cp1, cp2, 0.5);
+    let p23     = lerp(cp2, end, 0.5);
+    let p012    = lerp(p01, p12, 0.5);
+    let p123    = lerp(p12, p23, 0.5);
+    let mid     = lerp(p012, p123, 0.5);
+
+    ((p01, p012, mid), (p123, p23, end))
+}
+
+///
+/// The points at the start and end of each curve in a path
+///
+fn path_points((start, curves): &SimpleBezierPath) -> Vec<Coord2> {
+    let mut points = vec![*start];
+    points.extend(curves.iter().map(|(_, _, end)| *end));
+
+    points
+}
+
+///
+/// True if the start and end of a path are at the same point
+///
+fn is_closed(path: &SimpleBezierPath) -> bool {
+    let (start, curves) = path;
+
+    match curves.last() {
+        Some((_, _, end))   => start.distance_to(end) < CLOSED_PATH_TOLERANCE,
+        None                => false
+    }
+}
+
+///
+/// The average position of the points in a path
+///
+fn centroid(path: &SimpleBezierPath) -> Coord2 {
+    let points = path_points(path);
+    let total  = points.iter().fold(Coord2(0.0, 0.0), |total, point| total + *point);
+
+    total * (1.0 / points.len() as f64)
+}
+
+///
+/// A path with all of its points in the same place, with the specified number of curves
+///
+fn collapsed_path(point: Coord2, num_curves: usize) -> SimpleBezierPath {
+    (point, (0..num_curves).map(|_| (point, point, point)).collect())
+}
+
+///
+/// Splits the longest curves in a path until it has at least the specified number of curves
+///
+/// The shape of the path is unchanged: only the number of curves that are used to describe it is altered.
+///
+pub fn resample_path(path: &SimpleBezierPath, num_curves: usize) -> SimpleBezierPath {
+    let (start, curves) = path;
+
+    if curves.len() == 0 {
+        return collapsed_path(*start, num_curves);
+    }
+
+    let mut curves = curves.clone();
+
+    while curves.len() < num_curves {
+        // Find the longest curve
+        let (longest_idx, _) = curves.iter().enumerate()
+            .map(|(idx, curve)| {
+                let curve_start = if idx == 0 { *start } else { curves[idx-1].2 };
+                (idx, curve_length(curve_start, *curve))
+            })
+            .fold((0, -1.0), |(longest_idx, longest_length), (idx, length)| if length > longest_length { (idx, length) } else { (longest_idx, longest_length) });
+
+        // Split it in two
+        let curve_start     = if longest_idx == 0 { *start } else { curves[longest_idx-1].2 };
+        let (first, second) = split_curve(curve_start, curves[longest_idx]);
+
+        curves[longest_idx] = first;
+        curves.insert(longest_idx+1, second);
+    }
+
+    (*start, curves)
+}
+
+///
+/// Returns a path that follows the same curves in the opposite direction
+///
+fn reverse_path(path: &SimpleBezierPath) -> SimpleBezierPath {
+    let points = path_points(path);
+    let curves = path.1.iter().enumerate().rev()
+        .map(|(idx, (cp1, cp2, _))| (*cp2, *cp1, points[idx]))
+        .collect();
+
+    (points[points.len()-1], curves)
+}
+
+///
+/// Returns a closed path that starts at a different point
+///
+fn rotate_path(path: &SimpleBezierPath, offset: usize) -> SimpleBezierPath {
+    let points = path_points(path);
+    let curves = path.1[offset..].iter().chain(path.1[..offset].iter()).cloned().collect();
+
+    (points[offset], curves)
+}
+
+///
+/// Resamples two paths so that they have the same number of curves, then matches up their points so that they're moved as little as
+/// possible when interpolating from one to the other (hint points are pairs of positions on the two paths that should be matched)
+///
+/// The result is the two paths with the same number of curves: the first path keeps its original starting point, and the second may
+/// be reversed or (if it's closed) start from a different point.
+///
+pub fn match_paths(from: &SimpleBezierPath, to: &SimpleBezierPath, hints: &Vec<(Coord2, Coord2)>) -> (SimpleBezierPath, SimpleBezierPath) {
+    // Use the same number of curves for both paths
+    let num_curves  = from.1.len().max(to.1.len());
+    let from        = resample_path(from, num_curves);
+    let to          = resample_path(to, num_curves);
+
+    if num_curves == 0 {
+        return (from, to);
+    }
+
+    // Find the points in the 'from' path that are closest to the hint points
+    let from_points = path_points(&from);
+    let hints       = hints.iter()
+        .map(|(hint_from, hint_to)| {
+            let (nearest_idx, _) = from_points.iter().enumerate()
+                .map(|(idx, point)| (idx, point.distance_to(hint_from)))
+                .fold((0, f64::MAX), |(nearest_idx, nearest_distance), (idx, distance)| if distance < nearest_distance { (idx, distance) } else { (nearest_idx, nearest_distance) });
+
+            (nearest_idx, *hint_to)
+        })
+        .collect::<Vec<_>>();
+
+    // Closed paths can start at any point, open paths can only be reversed
+    let offsets     = if is_closed(&from) && is_closed(&to) { num_curves } else { 1 };
+    let reversed_to = reverse_path(&to);
+
+    let candidates  = (0..offsets)
+        .flat_map(|offset| vec![rotate_path(&to, offset), rotate_path(&reversed_to, offset)]);
+
+    // The best candidate is the one where the points move the least
+    let cost        = |candidate: &SimpleBezierPath| {
+        let to_points   = path_points(candidate);
+        let movement    = from_points.iter().zip(to_points.iter())
+            .map(|(from_point, to_point)| { let distance = from_point.distance_to(to_point); distance * distance })
+            .sum::<f64>();
+        let hint_error  = hints.iter()
+            .map(|(from_idx, hint_to)| { let distance = to_points[*from_idx].distance_to(hint_to); distance * distance })
+            .sum::<f64>();
+
+        movement + hint_error * HINT_WEIGHT
+    };
+
+    let best_to     = candidates
+        .map(|candidate| { let candidate_cost = cost(&candidate); (candidate, candidate_cost) })
+        .fold(None, |best: Option<(SimpleBezierPath, f64)>, (candidate, candidate_cost)| {
+            match best {
+                Some((best, best_cost)) if best_cost <= candidate_cost  => Some((best, best_cost)),
+                _                                                       => Some((candidate, candidate_cost))
+            }
+        })
+        .map(|(best, _)| best)
+        .unwrap_or_else(|| to.clone());
+
+    (from, best_to)
+}
+
+///
+/// Interpolates between two paths with the same number of curves
+///
+pub fn interpolate_paths(from: &SimpleBezierPath, to: &SimpleBezierPath, ratio: f64) -> SimpleBezierPath {
+    let start   = lerp(from.0, to.0, ratio);
+    let curves  = from.1.iter().zip(to.1.iter())
+        .map(|((from_cp1, from_cp2, from_end), (to_cp1, to_cp2, to_end))| {
+            (lerp(*from_cp1, *to_cp1, ratio), lerp(*from_cp2, *to_cp2, ratio), lerp(*from_end, *to_end, ratio))
+        })
+        .collect();
+
+    (start, curves)
+}
+
+impl ShapeTweenEffect {
+    ///
+    /// Creates a shape tween effect that changes the paths in a region into the target shapes over the specified duration
+    ///
+    pub fn tween(duration: Duration, description: &ShapeTweenDescription) -> ShapeTweenEffect {
+        ShapeTweenEffect {
+            duration:   (duration.as_nanos() as f64) / 1_000_000.0,
+            targets:    Arc::new(description.targets.clone()),
+            hints:      Arc::new(description.hints.iter().map(|(from, to)| (from.into(), to.into())).collect())
+        }
+    }
+
+    ///
+    /// Returns how far through the tween the effect is at a particular time (0-1)
+    ///
+    pub fn ratio_at_time(&self, time: Duration) -> f64 {
+        let time = (time.as_nanos() as f64) / 1_000_000.0;
+
+        if self.duration <= 0.0 { 1.0 } else { (time / self.duration).min(1.0).max(0.0) }
+    }
+
+    ///
+    /// Matches up the paths in a region with the target shapes
+    ///
+    fn match_region(&self, region_contents: &AnimationRegionContent) -> Vec<MatchedPath> {
+        let paths = region_contents.paths().cloned().collect::<Vec<_>>();
+
+        // Each hint is used for the subpath whose start point is closest to it
+        let hint_subpath = |hint_from: &Coord2| {
+            paths.iter().enumerate()
+                .flat_map(|(path_idx, path)| path.path.iter().enumerate().map(move |(subpath_idx, subpath)| ((path_idx, subpath_idx), subpath)))
+                .flat_map(|(subpath_idx, subpath)| path_points(subpath).into_iter().map(move |point| (subpath_idx, point.distance_to(hint_from))))
+                .fold(None, |nearest: Option<((usize, usize), f64)>, (subpath_idx, distance)| {
+                    match nearest {
+                        Some((_, nearest_distance)) if nearest_distance <= distance => nearest,
+                        _                                                           => Some((subpath_idx, distance))
+                    }
+                })
+                .map(|(subpath_idx, _)| subpath_idx)
+        };
+        let hints = self.hints.iter()
+            .map(|hint| (hint_subpath(&hint.0), *hint))
+            .collect::<Vec<_>>();
+
+        paths.iter().enumerate()
+            .map(|(path_idx, path)| {
+                let target = match self.targets.get(path_idx) {
+                    Some(target)    => target,
+                    None            => { return MatchedPath { source: path.clone(), subpaths: vec![], color: None, width: None }; }
+                };
+
+                let target_paths    = target.path.iter().map(|subpath| subpath.into()).collect::<Vec<SimpleBezierPath>>();
+                let num_subpaths    = path.path.len().max(target_paths.len());
+
+                // Subpaths that don't have a match shrink to or grow from their centre point
+                let subpaths        = (0..num_subpaths)
+                    .map(|subpath_idx| {
+                        match (path.path.get(subpath_idx), target_paths.get(subpath_idx)) {
+                            (Some(from), Some(to))  => {
+                                let subpath_hints = hints.iter()
+                                    .filter(|(hint_subpath, _)| *hint_subpath == Some((path_idx, subpath_idx)))
+                                    .map(|(_, hint)| *hint)
+                                    .collect();
+
+                                match_paths(from, to, &subpath_hints)
+                            }
+
+                            (Some(from), None)      => (from.clone(), collapsed_path(centroid(from), from.1.len())),
+                            (None, Some(to))        => (collapsed_path(centroid(to), to.1.len()), to.clone()),
+                            (None, None)            => unreachable!()
+                        }
+                    })
+                    .collect();
+
+                let color = target.color.map(|(RgbColor(r, g, b), alpha)| Color::Rgba(r as _, g as _, b as _, alpha as _));
+                let width = target.width.map(|width| width as f32);
+
+                MatchedPath { source: path.clone(), subpaths: subpaths, color: color, width: width }
+            })
+            .collect()
+    }
+
+    ///
+    /// Generates the region contents for a set of matched paths at a particular point in the tween
+    ///
+    fn tween_paths(matched: &Vec<MatchedPath>, ratio: f64) -> Arc<AnimationRegionContent> {
+        use self::AnimationPathAttribute::*;
+
+        let paths = matched.iter()
+            .map(|matched_path| {
+                if matched_path.subpaths.len() == 0 {
+                    // Path has no target
+                    return matched_path.source.clone();
+                }
+
+                // Interpolate the shape
+                let new_path    = matched_path.subpaths.iter()
+                    .map(|(from, to)| interpolate_paths(from, to, ratio))
+                    .collect::<Vec<_>>();
+
+                // Interpolate the colour and width
+                let blend_color = |color: Color| {
+                    match matched_path.color {
+                        Some(target_color)  => {
+                            let (r1, g1, b1, a1) = color.to_rgba_components();
+                            let (r2, g2, b2, a2) = target_color.to_rgba_components();
+                            let ratio            = ratio as f32;
+
+                            Color::Rgba(r1 + (r2-r1)*ratio, g1 + (g2-g1)*ratio, b1 + (b2-b1)*ratio, a1 + (a2-a1)*ratio)
+                        }
+
+                        None                => color
+                    }
+                };
+                let blend_width = |width: f32| {
+                    match matched_path.width {
+                        Some(target_width)  => width + (target_width - width) * (ratio as f32),
+                        None                => width
+                    }
+                };
+
+                let attributes  = match matched_path.source.attributes {
+                    Stroke(blend_mode, width, color, join, cap)         => Stroke(blend_mode, blend_width(width), blend_color(color), join, cap),
+                    StrokePixels(blend_mode, width, color, join, cap)   => StrokePixels(blend_mode, blend_width(width), blend_color(color), join, cap),
+                    other                                               => other.map_color(blend_color)
+                };
+
+                matched_path.source.with_path(Arc::new(new_path)).with_attributes(attributes)
+            });
+
+        Arc::new(AnimationRegionContent::from_paths(paths))
+    }
+}
+
+impl AnimationEffect for ShapeTweenEffect {
+    ///
+    /// Returns the duration of this effect (or None if this effect will animate forever)
+    ///
+    /// If the effect is passed a time that's after where the 'duration' has completed it should always generate the same result
+    ///
+    fn duration(&self) -> Option<f64> {
+        Some(self.duration)
+    }
+
+    ///
+    /// Given the contents of the regions for this effect, calculates the path that should be rendered
+    ///
+    fn animate(&self, region_contents: Arc<AnimationRegionContent>, time: Duration) -> Arc<AnimationRegionContent> {
+        let matched = self.match_region(&*region_contents);
+
+        Self::tween_paths(&matched, self.ratio_at_time(time))
+    }
+
+    ///
+    /// Given an input region that will remain fixed throughout the time period, returns a function that
+    /// will animate it. This can be used to speed up operations when some pre-processing is required for
+    /// the region contents, but is not always available as the region itself might be changing over time
+    /// (eg, if many effects are combined)
+    ///
+    fn animate_cached(&self, region_contents: Arc<AnimationRegionContent>) -> Box<dyn Send+Fn(Duration) -> Arc<AnimationRegionContent>> {
+        // Matching the paths is the expensive part, and only needs to be done once
+        let matched = self.match_region(&*region_contents);
+        let effect  = self.clone();
+
+        Box::new(move |time| Self::tween_paths(&matched, effect.ratio_at_time(time)))
+    }
+}
@@ -0,0 +1,136 @@
+use crate::path::*;
+use crate::region::*;
+use crate::description::*;
+
+use flo_canvas::{Transform2D};
+use flo_curves::*;
+use flo_curves::bezier::path::*;
+
+use std::sync::*;
+use std::time::{Duration};
+
+///
+/// The weights of each bone for the points in a path
+///
+type PathWeights = Vec<(Vec<(usize, f64)>, Vec<[Vec<(usize, f64)>; 3]>)>;
+
+///
+/// Effect that deforms the contents of a region by moving them along with the bones of a skeleton
+///
+/// Every point in the region is moved by a blend of the transformations of the bones it's bound to, weighted by how close the point is
+/// to each bone. Texture and gradient fills are not deformed by this effect.
+///
+#[derive(Clone)]
+pub struct SkeletonDeformEffect {
+    /// The skeleton that deforms the region
+    skeleton: Arc<SkeletonDescription>,
+
+    /// The bones that the region is bound to, and the weight of each binding
+    bindings: Arc<Vec<(usize, f64)>>
+}
+
+impl SkeletonDeformEffect {
+    ///
+    /// Creates a new effect that deforms a region using a skeleton
+    ///
+    pub fn deform(skeleton: SkeletonDescription, bindings: Vec<(usize, f64)>) -> SkeletonDeformEffect {
+        SkeletonDeformEffect {
+            skeleton:   Arc::new(skeleton),
+            bindings:   Arc::new(bindings)
+        }
+    }
+
+    ///
+    /// Calculates the weight of each bone for every point in a path
+    ///
+    fn path_weights(&self, path: &AnimationPath) -> PathWeights {
+        let weights = |point: &Coord2| self.skeleton.weights_for_point(&Point2D::from(point), &self.bindings);
+
+        path.path.iter()
+            .map(|(start_point, points)| {
+                (weights(start_point), points.iter().map(|(cp1, cp2, p)| [weights(cp1), weights(cp2), weights(p)]).collect())
+            })
+            .collect()
+    }
+
+    ///
+    /// Moves a point using the bone transformations and the weights of each bone
+    ///
+    fn deform_point(point: &Coord2, weights: &Vec<(usize, f64)>, transforms: &Vec<Transform2D>) -> Coord2 {
+        if weights.len() == 0 {
+            // Points not bound to any bones are left where they are
+            *point
+        } else {
+            weights.iter()
+                .map(|(bone_idx, weight)| {
+                    let (x, y) = transforms[*bone_idx].transform_point(point.x() as _, point.y() as _);
+                    Coord2(x as f64 * weight, y as f64 * weight)
+                })
+                .fold(Coord2(0.0, 0.0), |total, weighted| total + weighted)
+        }
+    }
+
+    ///
+    /// Deforms a path using a precalculated set of weights for each of its points
+    ///
+    fn deform_path(path: &AnimationPath, weights: &PathWeights, transforms: &Vec<Transform2D>) -> AnimationPath {
+        let new_path = path.path.iter().zip(weights.iter())
+            .map(|((start_point, points), (start_weights, point_weights))| {
+                let start_point = Self::deform_point(start_point, start_weights, transforms);
+                let points      = points.iter().zip(point_weights.iter())
+                    .map(|((cp1, cp2, p), [w1, w2, w3])| {
+                        (Self::deform_point(cp1, w1, transforms), Self::deform_point(cp2, w2, transforms), Self::deform_point(p, w3, transforms))
+                    })
+                    .collect();
+
+                (start_point, points)
+            })
+            .collect::<Vec<SimpleBezierPath>>();
+
+        path.with_path(Arc::new(new_path))
+    }
+}
+
+impl AnimationEffect for SkeletonDeformEffect {
+    ///
+    /// Returns the duration of this effect (or None if this effect will animate forever)
+    ///
+    /// If the effect is passed a time that's after where the 'duration' has completed it should always generate the same result
+    ///
+    fn duration(&self) -> Option<f64> {
+        Some((self.skeleton.duration().as_nanos() as f64) / 1_000_000.0)
+    }
+
+    ///
+    /// Given the contents of the regions for this effect, calculates the path that should be rendered
+    ///
+    fn animate(&self, region_contents: Arc<AnimationRegionContent>, time: Duration) -> Arc<AnimationRegionContent> {
+        let transforms  = self.skeleton.bone_transforms(time);
+        let paths       = region_contents.paths()
+            .map(|path| Self::deform_path(path, &self.path_weights(path), &transforms));
+
+        Arc::new(AnimationRegionContent::from_paths(paths))
+    }
+
+    ///
+    /// Given an input region that will remain fixed throughout the time period, returns a function that
+    /// will animate it. This can be used to speed up operations when some pre-processing is required for
+    /// the region contents, but is not always available as the region itself might be changing over time
+    /// (eg, if many effects are combined)
+    ///
+    fn animate_cached(&self, region_contents: Arc<AnimationRegionContent>) -> Box<dyn Send+Fn(Duration) -> Arc<AnimationRegionContent>> {
+        // The weights only depend on the rest pose, so they can be calculated once
+        let weights     = region_contents.paths()
+            .map(|path| self.path_weights(path))
+            .collect::<Vec<_>>();
+        let skeleton    = Arc::clone(&self.skeleton);
+
+        Box::new(move |time| {
+            let transforms  = skeleton.bone_transforms(time);
+            let paths       = region_contents.paths().zip(weights.iter())
+                .map(|(path, weights)| Self::deform_path(path, weights, &transforms));
+
+            Arc::new(AnimationRegionContent::from_paths(paths))
+        })
+    }
+}
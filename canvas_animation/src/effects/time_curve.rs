@@ -38,6 +38,9 @@ impl<TEffect: AnimationEffect> TimeCurveEffect<TEffect> {
     ///
     /// Works out where the specified time lies on the curve
     ///
+    /// Control points can move before the start of the animation (eg, for the 'back in' easing curves), but animation times can't
+    /// be negative, so the returned time is clamped to 0 in this case.
+    ///
     pub fn time_for_time(curve_points: &Vec<(f64, f64, f64)>, time: Duration) -> Duration {
         // Convert time to milliseconds
         let time            = (time.as_nanos() as f64) / 1_000_000.0;
@@ -75,7 +78,7 @@ impl<TEffect: AnimationEffect> TimeCurveEffect<TEffect> {
         // Time can be calculated using the bezier algorithm
         let milliseconds    = de_casteljau4(t, start_point, cp1, cp2, end_point);
 
-        Duration::from_nanos((milliseconds * 1_000_000.0) as u64)
+        Duration::from_nanos((milliseconds.max(0.0) * 1_000_000.0) as u64)
     }
 }
 
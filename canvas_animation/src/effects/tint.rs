@@ -0,0 +1,97 @@
+use crate::region::*;
+use crate::description::*;
+
+use flo_canvas::{Color};
+
+use std::sync::*;
+use std::time::{Duration};
+
+///
+/// Effect that blends the colours of a region towards a tint colour over time
+///
+/// The alpha component of the original colours is preserved. Texture and gradient fills are not affected by this effect.
+///
+#[derive(Clone)]
+pub struct TintEffect {
+    /// The time taken to change from the start amount to the end amount, in milliseconds
+    duration: f64,
+
+    /// The colour to tint the region with
+    tint_color: RgbColor,
+
+    /// The amount of tint at the start of the effect (0 = original colour, 1 = tint colour)
+    start_amount: f64,
+
+    /// The amount of tint at the end of the effect
+    end_amount: f64
+}
+
+impl TintEffect {
+    ///
+    /// Creates a tint effect that changes from the start amount of tint to the end amount over the specified duration
+    ///
+    pub fn tint(duration: Duration, tint_color: RgbColor, start_amount: f64, end_amount: f64) -> TintEffect {
+        TintEffect {
+            duration:       (duration.as_nanos() as f64) / 1_000_000.0,
+            tint_color:     tint_color,
+            start_amount:   start_amount,
+            end_amount:     end_amount
+        }
+    }
+
+    ///
+    /// Returns the amount of tint at a particular time
+    ///
+    pub fn amount_at_time(&self, time: Duration) -> f64 {
+        let time    = (time.as_nanos() as f64) / 1_000_000.0;
+        let ratio   = if self.duration <= 0.0 { 1.0 } else { (time / self.duration).min(1.0).max(0.0) };
+
+        (self.start_amount + (self.end_amount - self.start_amount) * ratio).min(1.0).max(0.0)
+    }
+
+    ///
+    /// Applies the tint to a colour
+    ///
+    pub fn tint_color(&self, color: Color, amount: f64) -> Color {
+        let (r, g, b, a)            = color.to_rgba_components();
+        let RgbColor(tr, tg, tb)    = self.tint_color;
+        let amount                  = amount as f32;
+
+        Color::Rgba(r + (tr as f32 - r) * amount, g + (tg as f32 - g) * amount, b + (tb as f32 - b) * amount, a)
+    }
+}
+
+impl AnimationEffect for TintEffect {
+    ///
+    /// Returns the duration of this effect (or None if this effect will animate forever)
+    ///
+    /// If the effect is passed a time that's after where the 'duration' has completed it should always generate the same result
+    ///
+    fn duration(&self) -> Option<f64> {
+        Some(self.duration)
+    }
+
+    ///
+    /// Given the contents of the regions for this effect, calculates the path that should be rendered
+    ///
+    fn animate(&self, region_contents: Arc<AnimationRegionContent>, time: Duration) -> Arc<AnimationRegionContent> {
+        // Blend the colour of each path towards the tint colour
+        let amount  = self.amount_at_time(time);
+        let paths   = region_contents.paths()
+            .map(|path| path.with_attributes(path.attributes.map_color(|color| self.tint_color(color, amount))));
+
+        Arc::new(AnimationRegionContent::from_paths(paths))
+    }
+
+    ///
+    /// Given an input region that will remain fixed throughout the time period, returns a function that
+    /// will animate it. This can be used to speed up operations when some pre-processing is required for
+    /// the region contents, but is not always available as the region itself might be changing over time
+    /// (eg, if many effects are combined)
+    ///
+    fn animate_cached(&self, region_contents: Arc<AnimationRegionContent>) -> Box<dyn Send+Fn(Duration) -> Arc<AnimationRegionContent>> {
+        let cached_effect = self.clone();
+
+        Box::new(move |time| cached_effect.animate(Arc::clone(&region_contents), time))
+    }
+}
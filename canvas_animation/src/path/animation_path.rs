@@ -246,4 +246,15 @@ impl AnimationPath {
             path:               new_path
         }
     }
+
+    ///
+    /// Creates a path with identical operations but a new set of attributes
+    ///
+    pub fn with_attributes(&self, new_attributes: AnimationPathAttribute) -> AnimationPath {
+        AnimationPath {
+            appearance_time:    self.appearance_time,
+            attributes:         new_attributes,
+            path:               Arc::clone(&self.path)
+        }
+    }
 }
@@ -20,3 +20,23 @@ pub enum AnimationPathAttribute {
     /// Path is filled with the specified gradient
     FillGradient(BlendMode, GradientId, (f32, f32), (f32, f32), Option<Transform2D>, WindingRule)
 }
+
+impl AnimationPathAttribute {
+    ///
+    /// Returns a copy of these attributes with the colour replaced using a mapping function
+    ///
+    /// Texture and gradient fills have no single colour, so they are returned unchanged.
+    ///
+    pub fn map_color(&self, map_color: impl Fn(Color) -> Color) -> AnimationPathAttribute {
+        use self::AnimationPathAttribute::*;
+
+        match self {
+            Stroke(blend_mode, width, color, join, cap)         => Stroke(*blend_mode, *width, map_color(*color), *join, *cap),
+            StrokePixels(blend_mode, width, color, join, cap)   => StrokePixels(*blend_mode, *width, map_color(*color), *join, *cap),
+            Fill(blend_mode, color, winding_rule)               => Fill(*blend_mode, map_color(*color), *winding_rule),
+
+            FillTexture(_, _, _, _, _, _)                       |
+            FillGradient(_, _, _, _, _, _)                      => *self
+        }
+    }
+}
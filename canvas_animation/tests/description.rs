@@ -42,3 +42,44 @@ fn deserialize_fitted_transform_description() {
 
     assert!(transform_description == description_again);
 }
+
+#[test]
+fn rotate_scale_fade_tint_round_trip() {
+    let description         = EffectDescription::Sequence(vec![
+        EffectDescription::RotateAround(Point2D(10.0, 20.0), Duration::from_millis(1000), RotateDegrees(0.0), RotateDegrees(90.0)),
+        EffectDescription::ScaleAround(Point2D(10.0, 20.0), Duration::from_millis(2000), Scale(1.0, 1.0), Scale(2.0, 0.5)),
+        EffectDescription::Fade(Duration::from_millis(500), 0.0, 1.0),
+        EffectDescription::Tint(Duration::from_millis(750), RgbColor(0.25, 0.5, 1.0), 0.0, 0.8),
+    ]);
+    let as_json             = json::to_string(&description).unwrap();
+    let description_again   = json::from_str(&as_json).unwrap();
+
+    assert!(description == description_again);
+}
+
+#[test]
+fn skeleton_round_trip() {
+    let mut bone            = BoneDescription::new(None, Point2D(0.0, 0.0), Point2D(100.0, 0.0));
+    bone.set_rotation(Duration::from_millis(500), RotateDegrees(45.0));
+
+    let description         = EffectDescription::Skeleton(SkeletonDescription(vec![bone, BoneDescription::new(Some(0), Point2D(100.0, 0.0), Point2D(150.0, 20.0))]), vec![(0, 0.25), (1, 0.75)]);
+    let as_json             = json::to_string(&description).unwrap();
+    let description_again   = json::from_str(&as_json).unwrap();
+
+    assert!(description == description_again);
+}
+
+#[test]
+fn shape_tween_round_trip() {
+    let target              = TweenPathDescription {
+        path:   vec![BezierPath(Point2D(0.0, 0.0), vec![BezierPoint(Point2D(10.0, 0.0), Point2D(20.0, 10.0), Point2D(30.0, 30.0))])],
+        color:  Some((RgbColor(1.0, 0.5, 0.0), 0.75)),
+        width:  None
+    };
+    let tween               = ShapeTweenDescription { targets: vec![target], hints: vec![(Point2D(0.0, 0.0), Point2D(30.0, 30.0))] };
+    let description         = EffectDescription::ShapeTween(Duration::from_millis(1500), tween);
+    let as_json             = json::to_string(&description).unwrap();
+    let description_again   = json::from_str(&as_json).unwrap();
+
+    assert!(description == description_again);
+}
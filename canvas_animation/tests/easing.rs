@@ -0,0 +1,144 @@
+use flo_canvas_animation::effects::*;
+use flo_canvas_animation::description::*;
+
+use serde_json as json;
+
+use std::time::{Duration};
+
+fn time_at(curve_points: &Vec<(f64, f64, f64)>, millis: u64) -> f64 {
+    let time = TimeCurveEffect::<Box<dyn flo_canvas_animation::AnimationEffect>>::time_for_time(curve_points, Duration::from_millis(millis));
+    (time.as_nanos() as f64) / 1_000_000.0
+}
+
+#[test]
+fn presets_decompile_to_themselves() {
+    for preset in Easing::presets() {
+        let curve               = preset.time_curve(Duration::from_millis(2000));
+        let (easing, length)    = Easing::from_time_curve(&curve).unwrap();
+
+        assert!(easing == preset, "{:?} decompiled as {:?}", preset, easing);
+        assert!(length == Duration::from_millis(2000));
+    }
+}
+
+#[test]
+fn steps_decompile_with_number_of_steps() {
+    let curve               = Easing::Steps(7).time_curve(Duration::from_millis(700));
+    let (easing, length)    = Easing::from_time_curve(&curve).unwrap();
+
+    assert!(curve.len() == 7);
+    assert!(easing == Easing::Steps(7));
+    assert!(length == Duration::from_millis(700));
+}
+
+#[test]
+fn custom_bezier_decompiles() {
+    let curve               = vec![(100.0, 900.0, 1000.0)];
+    let (easing, length)    = Easing::from_time_curve(&curve).unwrap();
+
+    assert!(easing == Easing::CustomBezier(0.1, 0.9));
+    assert!(length == Duration::from_millis(1000));
+}
+
+#[test]
+fn unknown_curve_does_not_decompile() {
+    assert!(Easing::from_time_curve(&vec![]).is_none());
+    assert!(Easing::from_time_curve(&vec![(100.0, 200.0, 300.0), (300.0, 900.0, 1000.0)]).is_none());
+}
+
+#[test]
+fn ease_in_is_quadratic() {
+    let curve = Easing::EaseIn.time_curve(Duration::from_millis(1000));
+
+    for millis in 0..=10 {
+        let millis      = millis * 100;
+        let expected    = (millis as f64) / 1000.0;
+        let expected    = expected * expected * 1000.0;
+
+        assert!((time_at(&curve, millis) - expected).abs() < 0.1, "{} {}", time_at(&curve, millis), expected);
+    }
+}
+
+#[test]
+fn ease_in_out_is_symmetrical() {
+    let curve = Easing::EaseInOut.time_curve(Duration::from_millis(1000));
+
+    assert!((time_at(&curve, 500) - 500.0).abs() < 0.1);
+    assert!((time_at(&curve, 250) + time_at(&curve, 750) - 1000.0).abs() < 0.1);
+    assert!(time_at(&curve, 250) < 250.0);
+}
+
+#[test]
+fn back_out_overshoots() {
+    let curve = Easing::BackOut.time_curve(Duration::from_millis(1000));
+
+    assert!(time_at(&curve, 750) > 1000.0);
+    assert!((time_at(&curve, 1000) - 1000.0).abs() < 0.1);
+}
+
+#[test]
+fn back_in_clamps_to_start() {
+    let curve = Easing::BackIn.time_curve(Duration::from_millis(1000));
+
+    // The curve itself moves before the start, which can't be represented as an animation time
+    assert!(curve[0].1 < 0.0);
+
+    assert!(time_at(&curve, 0) == 0.0);
+    assert!(time_at(&curve, 100) == 0.0);
+    assert!(time_at(&curve, 900) > 0.0);
+    assert!((time_at(&curve, 1000) - 1000.0).abs() < 0.1);
+}
+
+#[test]
+fn back_in_out_overshoots() {
+    let curve = Easing::BackInOut.time_curve(Duration::from_millis(1000));
+
+    assert!(time_at(&curve, 50) == 0.0);
+    assert!((time_at(&curve, 500) - 500.0).abs() < 0.1);
+    assert!(time_at(&curve, 900) > 1000.0);
+    assert!((time_at(&curve, 1000) - 1000.0).abs() < 0.1);
+}
+
+#[test]
+fn bounce_and_elastic_end_at_the_end() {
+    for easing in vec![Easing::BounceOut, Easing::ElasticOut] {
+        let curve = easing.time_curve(Duration::from_millis(1000));
+
+        assert!((curve.last().unwrap().2 - 1000.0).abs() < 0.001);
+        assert!(time_at(&curve, 500) > 500.0);
+        assert!((time_at(&curve, 1000) - 1000.0).abs() < 0.1);
+    }
+}
+
+#[test]
+fn compile_and_decompile_description() {
+    let eased       = EffectDescription::EasedTimeCurve(Easing::CubicInOut, Duration::from_millis(3000), EffectDescription::Sequence(vec![]).boxed());
+    let compiled    = eased.compile_easing();
+
+    if let EffectDescription::TimeCurve(curve, _) = &compiled {
+        assert!(curve == &Easing::CubicInOut.time_curve(Duration::from_millis(3000)));
+    } else {
+        assert!(false, "Not compiled to a time curve");
+    }
+
+    assert!(compiled.decompile_easing() == eased);
+}
+
+#[test]
+fn eased_description_round_trip() {
+    let eased               = EffectDescription::EasedTimeCurve(Easing::Steps(3), Duration::from_millis(1500), EffectDescription::Sequence(vec![]).boxed());
+    let as_json             = json::to_string(&eased).unwrap();
+    let description_again   = json::from_str(&as_json).unwrap();
+
+    assert!(eased == description_again);
+}
+
+#[test]
+fn eased_time_curve_is_time_curve_sub_effect() {
+    let effect = EffectDescription::Sequence(vec![]).add_new_effect(SubEffectType::TimeCurve);
+
+    assert!(effect.sub_effects()[0].effect_type() == SubEffectType::TimeCurve);
+
+    let replaced = effect.replace_sub_effect(&effect.sub_effects()[0], EffectDescription::EasedTimeCurve(Easing::BackIn, Duration::from_millis(500), EffectDescription::Sequence(vec![]).boxed()));
+    assert!(replaced.sub_effects()[0].effect_description() == &EffectDescription::EasedTimeCurve(Easing::BackIn, Duration::from_millis(500), EffectDescription::Sequence(vec![]).boxed()));
+}
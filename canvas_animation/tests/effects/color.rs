@@ -0,0 +1,57 @@
+use flo_canvas::*;
+use flo_canvas_animation::*;
+use flo_canvas_animation::effects::*;
+use flo_canvas_animation::description::{RgbColor};
+
+use std::sync::*;
+use std::time::{Duration};
+
+fn square_content(color: Color) -> Arc<AnimationRegionContent> {
+    let square  = vec![PathOp::Move(0.0, 0.0), PathOp::Line(100.0, 0.0), PathOp::Line(100.0, 100.0), PathOp::Line(0.0, 100.0), PathOp::ClosePath];
+    let path    = AnimationPath::from_path_ops(square.iter(), Duration::from_millis(0), AnimationPathAttribute::Fill(BlendMode::SourceOver, color, WindingRule::NonZero));
+
+    Arc::new(AnimationRegionContent::from_paths(vec![path]))
+}
+
+fn fill_color(content: &Arc<AnimationRegionContent>) -> (f32, f32, f32, f32) {
+    match content.paths().nth(0).unwrap().attributes {
+        AnimationPathAttribute::Fill(_, color, _)   => color.to_rgba_components(),
+        _                                           => panic!("Not a fill")
+    }
+}
+
+#[test]
+pub fn fade_out() {
+    let effect  = FadeEffect::fade(Duration::from_millis(1000), 1.0, 0.0);
+    let content = square_content(Color::Rgba(1.0, 0.0, 0.0, 0.5));
+
+    let (_, _, _, start)    = fill_color(&effect.animate(Arc::clone(&content), Duration::from_millis(0)));
+    let (_, _, _, middle)   = fill_color(&effect.animate(Arc::clone(&content), Duration::from_millis(500)));
+    let (_, _, _, end)      = fill_color(&effect.animate(Arc::clone(&content), Duration::from_millis(1000)));
+
+    assert!((start - 0.5).abs() < 0.001);
+    assert!((middle - 0.25).abs() < 0.001);
+    assert!(end.abs() < 0.001);
+}
+
+#[test]
+pub fn fade_duration() {
+    let effect = FadeEffect::fade(Duration::from_millis(1500), 0.0, 1.0);
+
+    assert!(effect.duration() == Some(1500.0));
+    assert!(effect.opacity_at_time(Duration::from_millis(3000)) == 1.0);
+}
+
+#[test]
+pub fn tint_to_red() {
+    let effect  = TintEffect::tint(Duration::from_millis(1000), RgbColor(1.0, 0.0, 0.0), 0.0, 1.0);
+    let content = square_content(Color::Rgba(0.0, 0.0, 1.0, 0.75));
+
+    let (r, g, b, a)        = fill_color(&effect.animate(Arc::clone(&content), Duration::from_millis(500)));
+    assert!((r - 0.5).abs() < 0.001 && g.abs() < 0.001 && (b - 0.5).abs() < 0.001);
+    assert!((a - 0.75).abs() < 0.001);
+
+    let (r, g, b, a)        = fill_color(&effect.animate(Arc::clone(&content), Duration::from_millis(1000)));
+    assert!((r - 1.0).abs() < 0.001 && g.abs() < 0.001 && b.abs() < 0.001);
+    assert!((a - 0.75).abs() < 0.001);
+}
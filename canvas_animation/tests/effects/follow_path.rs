@@ -0,0 +1,77 @@
+use flo_curves::*;
+use flo_canvas_animation::effects::*;
+use flo_canvas_animation::description::{PathOrientation, RotateDegrees};
+
+use std::time::{Duration};
+
+fn straight_line(start: Coord2, end: Coord2) -> (Coord2, Coord2, Coord2) {
+    let diff = end - start;
+    (start + diff * (1.0/3.0), start + diff * (2.0/3.0), end)
+}
+
+#[test]
+pub fn faces_along_path() {
+    // Path goes right and then up
+    let effect = FollowPathEffect::from_points(Duration::from_millis(2000), Coord2(0.0, 0.0), vec![
+        straight_line(Coord2(0.0, 0.0), Coord2(100.0, 0.0)),
+        straight_line(Coord2(100.0, 0.0), Coord2(100.0, 100.0))
+    ], PathOrientation::default());
+
+    let (offset, RotateDegrees(angle)) = effect.position_at_time(500.0, 0.01);
+    assert!(offset.distance_to(&Coord2(50.0, 0.0)) < 0.1, "{:?}", offset);
+    assert!(angle.abs() < 0.01, "{:?}", angle);
+
+    let (offset, RotateDegrees(angle)) = effect.position_at_time(1500.0, 0.01);
+    assert!(offset.distance_to(&Coord2(100.0, 50.0)) < 0.1, "{:?}", offset);
+    assert!((angle - 90.0).abs() < 0.01, "{:?}", angle);
+}
+
+#[test]
+pub fn offset_angle() {
+    let orientation = PathOrientation { offset_angle: RotateDegrees(-90.0), ..PathOrientation::default() };
+    let effect      = FollowPathEffect::from_points(Duration::from_millis(1000), Coord2(0.0, 0.0), vec![
+        straight_line(Coord2(0.0, 0.0), Coord2(0.0, 100.0))
+    ], orientation);
+
+    let (_, RotateDegrees(angle)) = effect.position_at_time(500.0, 0.01);
+    assert!(angle.abs() < 0.01, "{:?}", angle);
+}
+
+#[test]
+pub fn bank_limit() {
+    let orientation = PathOrientation { bank_limit: Some(RotateDegrees(30.0)), ..PathOrientation::default() };
+    let effect      = FollowPathEffect::from_points(Duration::from_millis(1000), Coord2(0.0, 0.0), vec![
+        straight_line(Coord2(0.0, 0.0), Coord2(0.0, 100.0))
+    ], orientation);
+
+    let (_, RotateDegrees(angle)) = effect.position_at_time(500.0, 0.01);
+    assert!((angle - 30.0).abs() < 0.01, "{:?}", angle);
+}
+
+#[test]
+pub fn segment_timing() {
+    // Short segment followed by a long segment: with constant speed, the halfway point is on the long segment, otherwise it's at the join
+    let path            = vec![
+        straight_line(Coord2(0.0, 0.0), Coord2(10.0, 0.0)),
+        straight_line(Coord2(10.0, 0.0), Coord2(10.0, 100.0))
+    ];
+    let constant_speed  = FollowPathEffect::from_points(Duration::from_millis(1000), Coord2(0.0, 0.0), path.clone(), PathOrientation::default());
+    let by_segment      = FollowPathEffect::from_points(Duration::from_millis(1000), Coord2(0.0, 0.0), path, PathOrientation { constant_speed: false, ..PathOrientation::default() });
+
+    let (offset, _)     = constant_speed.position_at_time(500.0, 0.01);
+    assert!(offset.distance_to(&Coord2(10.0, 45.0)) < 0.1, "{:?}", offset);
+
+    let (offset, _)     = by_segment.position_at_time(500.0, 0.01);
+    assert!(offset.distance_to(&Coord2(10.0, 0.0)) < 0.1, "{:?}", offset);
+}
+
+#[test]
+pub fn stays_at_end() {
+    let effect = FollowPathEffect::from_points(Duration::from_millis(1000), Coord2(0.0, 0.0), vec![
+        straight_line(Coord2(0.0, 0.0), Coord2(100.0, 0.0))
+    ], PathOrientation::default());
+
+    let (offset, RotateDegrees(angle)) = effect.position_at_time(2000.0, 0.01);
+    assert!(offset.distance_to(&Coord2(100.0, 0.0)) < 0.1, "{:?}", offset);
+    assert!(angle.abs() < 0.01);
+}
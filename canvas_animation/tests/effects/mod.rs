@@ -1,3 +1,8 @@
 mod motion;
 mod time_curve;
 mod region;
+mod transform;
+mod color;
+mod follow_path;
+mod skeleton;
+mod shape_tween;
@@ -0,0 +1,129 @@
+use flo_canvas::*;
+use flo_curves::*;
+use flo_curves::bezier::path::*;
+use flo_canvas_animation::*;
+use flo_canvas_animation::effects::*;
+use flo_canvas_animation::description::*;
+
+use std::sync::*;
+use std::time::{Duration};
+
+///
+/// A closed path made of straight lines through the specified points
+///
+fn polygon(points: Vec<(f64, f64)>) -> SimpleBezierPath {
+    let start   = Coord2(points[0].0, points[0].1);
+    let mut pos = start;
+    let curves  = points.iter().skip(1).chain(points.iter().take(1))
+        .map(|(x, y)| {
+            let end     = Coord2(*x, *y);
+            let curve   = (pos + (end - pos) * (1.0/3.0), pos + (end - pos) * (2.0/3.0), end);
+            pos         = end;
+
+            curve
+        })
+        .collect();
+
+    (start, curves)
+}
+
+fn end_points(path: &SimpleBezierPath) -> Vec<Coord2> {
+    path.1.iter().map(|(_, _, end)| *end).collect()
+}
+
+#[test]
+fn resample_line() {
+    let line        = (Coord2(0.0, 0.0), vec![(Coord2(10.0, 0.0), Coord2(20.0, 0.0), Coord2(30.0, 0.0))]);
+    let resampled   = resample_path(&line, 3);
+
+    assert!(resampled.1.len() == 3);
+    assert!(resampled.0 == Coord2(0.0, 0.0));
+    assert!(resampled.1[2].2 == Coord2(30.0, 0.0));
+
+    // All the points should still be on the line
+    for (cp1, cp2, end) in resampled.1.iter() {
+        assert!(cp1.y().abs() < 0.001 && cp2.y().abs() < 0.001 && end.y().abs() < 0.001);
+    }
+}
+
+#[test]
+fn match_square_with_different_start_point() {
+    let square          = polygon(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+    let rotated_square  = polygon(vec![(10.0, 10.0), (0.0, 10.0), (0.0, 0.0), (10.0, 0.0)]);
+
+    let (from, to)      = match_paths(&square, &rotated_square, &vec![]);
+
+    // The target should be changed to start at the same point, so nothing moves
+    assert!(to.0.distance_to(&from.0) < 0.001, "{:?}", to);
+    for (from_point, to_point) in end_points(&from).into_iter().zip(end_points(&to)) {
+        assert!(from_point.distance_to(&to_point) < 0.001, "{:?} {:?}", from, to);
+    }
+}
+
+#[test]
+fn match_square_with_reversed_direction() {
+    let square          = polygon(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+    let reversed_square = polygon(vec![(0.0, 0.0), (0.0, 10.0), (10.0, 10.0), (10.0, 0.0)]);
+
+    let (from, to)      = match_paths(&square, &reversed_square, &vec![]);
+
+    for (from_point, to_point) in end_points(&from).into_iter().zip(end_points(&to)) {
+        assert!(from_point.distance_to(&to_point) < 0.001, "{:?} {:?}", from, to);
+    }
+}
+
+#[test]
+fn hint_changes_correspondence() {
+    let square          = polygon(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+
+    // Ask for the top-left corner to move to the bottom-right corner
+    let (from, to)      = match_paths(&square, &square, &vec![(Coord2(0.0, 0.0), Coord2(10.0, 10.0))]);
+
+    assert!(from.0 == Coord2(0.0, 0.0));
+    assert!(to.0.distance_to(&Coord2(10.0, 10.0)) < 0.001, "{:?}", to);
+}
+
+#[test]
+fn match_paths_with_different_curve_counts() {
+    let triangle        = polygon(vec![(0.0, 0.0), (10.0, 0.0), (5.0, 10.0)]);
+    let square          = polygon(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+
+    let (from, to)      = match_paths(&triangle, &square, &vec![]);
+
+    assert!(from.1.len() == 4);
+    assert!(to.1.len() == 4);
+}
+
+#[test]
+fn tween_square_into_larger_square() {
+    let small_square    = polygon(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+    let large_square    = polygon(vec![(0.0, 0.0), (20.0, 0.0), (20.0, 20.0), (0.0, 20.0)]);
+
+    let no_ops: Vec<PathOp> = vec![];
+    let path            = AnimationPath::from_path_ops(no_ops.iter(), Duration::from_millis(0), AnimationPathAttribute::Fill(BlendMode::SourceOver, Color::Rgba(0.0, 0.0, 0.0, 1.0), WindingRule::NonZero));
+    let path            = path.with_path(Arc::new(vec![small_square]));
+    let content         = Arc::new(AnimationRegionContent::from_paths(vec![path]));
+
+    let target          = TweenPathDescription { path: vec![(&large_square).into()], color: Some((RgbColor(1.0, 1.0, 1.0), 1.0)), width: None };
+    let tween           = ShapeTweenDescription { targets: vec![target], hints: vec![] };
+    let effect          = ShapeTweenEffect::tween(Duration::from_millis(1000), &tween);
+
+    assert!(effect.duration() == Some(1000.0));
+
+    // Halfway through, the square should be 15 units across and grey
+    let halfway         = effect.animate(Arc::clone(&content), Duration::from_millis(500));
+    let halfway_path    = halfway.paths().nth(0).unwrap();
+
+    assert!(end_points(&halfway_path.path[0]).iter().any(|point| point.distance_to(&Coord2(15.0, 15.0)) < 0.001), "{:?}", halfway_path.path);
+    assert!(match halfway_path.attributes {
+        AnimationPathAttribute::Fill(_, color, _)   => (color.to_rgba_components().0 - 0.5).abs() < 0.001,
+        _                                           => false
+    });
+
+    // The cached version should reach the final shape at the end
+    let cached          = effect.animate_cached(Arc::clone(&content));
+    let end             = cached(Duration::from_millis(2000));
+    let end_path        = end.paths().nth(0).unwrap();
+
+    assert!(end_points(&end_path.path[0]).iter().any(|point| point.distance_to(&Coord2(20.0, 20.0)) < 0.001), "{:?}", end_path.path);
+}
@@ -0,0 +1,132 @@
+use flo_canvas::*;
+use flo_curves::*;
+use flo_canvas_animation::*;
+use flo_canvas_animation::effects::*;
+use flo_canvas_animation::description::{Point2D, RotateDegrees, BoneDescription, SkeletonDescription, IkSolver, solve_two_bone_ik, solve_fabrik};
+
+use std::sync::*;
+use std::time::{Duration};
+
+///
+/// An arm made of two bones, pointing to the right
+///
+fn arm() -> SkeletonDescription {
+    SkeletonDescription(vec![
+        BoneDescription::new(None, Point2D(0.0, 0.0), Point2D(100.0, 0.0)),
+        BoneDescription::new(Some(0), Point2D(100.0, 0.0), Point2D(200.0, 0.0))
+    ])
+}
+
+fn close_to(a: Point2D, b: Point2D) -> bool {
+    a.distance_to(&b) < 0.1
+}
+
+#[test]
+pub fn child_bone_moves_with_parent() {
+    let mut skeleton = arm();
+    skeleton.0[0].set_rotation(Duration::from_millis(1000), RotateDegrees(90.0));
+
+    let joints = skeleton.joint_positions(&vec![0, 1], Duration::from_millis(1000));
+    assert!(close_to(joints[1], Point2D(0.0, 100.0)), "{:?}", joints);
+    assert!(close_to(joints[2], Point2D(0.0, 200.0)), "{:?}", joints);
+
+    // Halfway through, the bone is rotated by 45 degrees
+    let joints = skeleton.joint_positions(&vec![0, 1], Duration::from_millis(500));
+    assert!(close_to(joints[2], Point2D(200.0 * 0.5f64.sqrt(), 200.0 * 0.5f64.sqrt())), "{:?}", joints);
+}
+
+#[test]
+pub fn chain_to_root() {
+    assert!(arm().chain_to_root(1) == vec![0, 1]);
+    assert!(arm().chain_to_root(0) == vec![0]);
+}
+
+#[test]
+pub fn two_bone_ik_reaches_target() {
+    let (middle, end) = solve_two_bone_ik(Point2D(0.0, 0.0), Point2D(100.0, 0.0), Point2D(200.0, 0.0), Point2D(100.0, 100.0));
+
+    assert!(close_to(end, Point2D(100.0, 100.0)), "{:?}", end);
+    assert!((middle.distance_to(&Point2D(0.0, 0.0)) - 100.0).abs() < 0.1);
+    assert!((middle.distance_to(&end) - 100.0).abs() < 0.1);
+}
+
+#[test]
+pub fn two_bone_ik_keeps_bend_direction() {
+    // Elbow bent upwards, then downwards
+    let (middle_up, _)      = solve_two_bone_ik(Point2D(0.0, 0.0), Point2D(50.0, 50.0), Point2D(100.0, 0.0), Point2D(120.0, 0.0));
+    let (middle_down, _)    = solve_two_bone_ik(Point2D(0.0, 0.0), Point2D(50.0, -50.0), Point2D(100.0, 0.0), Point2D(120.0, 0.0));
+
+    assert!(middle_up.y() > 0.0, "{:?}", middle_up);
+    assert!(middle_down.y() < 0.0, "{:?}", middle_down);
+}
+
+#[test]
+pub fn two_bone_ik_stretches_towards_unreachable_target() {
+    let (middle, end) = solve_two_bone_ik(Point2D(0.0, 0.0), Point2D(100.0, 0.0), Point2D(100.0, 100.0), Point2D(0.0, 500.0));
+
+    assert!(close_to(middle, Point2D(0.0, 100.0)), "{:?}", middle);
+    assert!(close_to(end, Point2D(0.0, 200.0)), "{:?}", end);
+}
+
+#[test]
+pub fn fabrik_reaches_target() {
+    let joints      = vec![Point2D(0.0, 0.0), Point2D(50.0, 0.0), Point2D(100.0, 0.0), Point2D(150.0, 0.0)];
+    let new_joints  = solve_fabrik(&joints, Point2D(50.0, 80.0), 64, 0.01);
+
+    assert!(new_joints[0] == Point2D(0.0, 0.0));
+    assert!(new_joints[3].distance_to(&Point2D(50.0, 80.0)) < 0.1, "{:?}", new_joints);
+
+    for idx in 0..3 {
+        assert!((new_joints[idx].distance_to(&new_joints[idx+1]) - 50.0).abs() < 0.001);
+    }
+}
+
+#[test]
+pub fn fabrik_stretches_towards_unreachable_target() {
+    let joints      = vec![Point2D(0.0, 0.0), Point2D(50.0, 0.0), Point2D(100.0, 0.0)];
+    let new_joints  = solve_fabrik(&joints, Point2D(0.0, -300.0), 64, 0.01);
+
+    assert!(close_to(new_joints[1], Point2D(0.0, -50.0)), "{:?}", new_joints);
+    assert!(close_to(new_joints[2], Point2D(0.0, -100.0)), "{:?}", new_joints);
+}
+
+#[test]
+pub fn rotations_for_solved_joints() {
+    let mut skeleton    = arm();
+    let chain           = skeleton.chain_to_root(1);
+    let joints          = skeleton.joint_positions(&chain, Duration::from_millis(0));
+    let target          = Point2D(100.0, 100.0);
+    let new_joints      = IkSolver::TwoBone.solve(&joints, target);
+
+    // Apply the rotations to the skeleton: the end of the arm should now be at the target
+    for (bone_idx, rotation) in skeleton.rotations_for_joints(&chain, &new_joints, Duration::from_millis(0)) {
+        skeleton.0[bone_idx].set_rotation(Duration::from_millis(0), rotation);
+    }
+
+    let posed_joints    = skeleton.joint_positions(&chain, Duration::from_millis(0));
+    assert!(close_to(posed_joints[2], target), "{:?}", posed_joints);
+}
+
+#[test]
+pub fn deform_region_with_skeleton() {
+    // A square around the end of the arm that's entirely bound to the second bone
+    let mut skeleton    = arm();
+    skeleton.0[1].set_rotation(Duration::from_millis(1000), RotateDegrees(90.0));
+
+    let square          = vec![PathOp::Move(190.0, -10.0), PathOp::Line(210.0, -10.0), PathOp::Line(210.0, 10.0), PathOp::Line(190.0, 10.0), PathOp::ClosePath];
+    let path            = AnimationPath::from_path_ops(square.iter(), Duration::from_millis(0), AnimationPathAttribute::Fill(BlendMode::SourceOver, Color::Rgba(0.0, 0.0, 0.0, 1.0), WindingRule::NonZero));
+    let content         = Arc::new(AnimationRegionContent::from_paths(vec![path]));
+
+    let effect          = SkeletonDeformEffect::deform(skeleton, vec![(1, 1.0)]);
+    let deformed        = effect.animate(Arc::clone(&content), Duration::from_millis(1000));
+    let (start, _)      = &deformed.paths().nth(0).unwrap().path[0];
+
+    // Rotating the second bone by 90 degrees around (100, 0) moves (190, -10) to (110, 90)
+    assert!(start.distance_to(&Coord2(110.0, 90.0)) < 0.1, "{:?}", start);
+    assert!(effect.duration() == Some(1000.0));
+
+    // The cached version should generate the same result
+    let cached          = effect.animate_cached(Arc::clone(&content));
+    let (start, _)      = &cached(Duration::from_millis(1000)).paths().nth(0).unwrap().path[0];
+    assert!(start.distance_to(&Coord2(110.0, 90.0)) < 0.1, "{:?}", start);
+}
@@ -0,0 +1,64 @@
+use flo_curves::*;
+use flo_canvas::*;
+use flo_canvas_animation::*;
+use flo_canvas_animation::effects::*;
+use flo_canvas_animation::description::{Point2D, Scale, RotateDegrees};
+
+use std::sync::*;
+use std::time::{Duration};
+
+fn square_content() -> Arc<AnimationRegionContent> {
+    let square  = vec![PathOp::Move(0.0, 0.0), PathOp::Line(100.0, 0.0), PathOp::Line(100.0, 100.0), PathOp::Line(0.0, 100.0), PathOp::ClosePath];
+    let path    = AnimationPath::from_path_ops(square.iter(), Duration::from_millis(0), AnimationPathAttribute::Fill(BlendMode::SourceOver, Color::Rgba(0.0, 0.0, 0.0, 1.0), WindingRule::NonZero));
+
+    Arc::new(AnimationRegionContent::from_paths(vec![path]))
+}
+
+fn first_point(content: &Arc<AnimationRegionContent>) -> Coord2 {
+    content.paths().nth(0).unwrap().path[0].0
+}
+
+#[test]
+pub fn rotate_halfway() {
+    let effect  = RotateEffect::rotate(Point2D(50.0, 50.0), Duration::from_millis(1000), RotateDegrees(0.0), RotateDegrees(180.0));
+
+    assert!(effect.angle_at_time(Duration::from_millis(500)) == RotateDegrees(90.0));
+    assert!(effect.angle_at_time(Duration::from_millis(2000)) == RotateDegrees(180.0));
+}
+
+#[test]
+pub fn rotate_around_origin() {
+    let effect  = RotateEffect::rotate(Point2D(50.0, 50.0), Duration::from_millis(1000), RotateDegrees(0.0), RotateDegrees(180.0));
+    let content = square_content();
+
+    let start   = first_point(&effect.animate(Arc::clone(&content), Duration::from_millis(0)));
+    let end     = first_point(&effect.animate(Arc::clone(&content), Duration::from_millis(1000)));
+
+    assert!(start.distance_to(&Coord2(0.0, 0.0)) < 0.01);
+    assert!(end.distance_to(&Coord2(100.0, 100.0)) < 0.01, "{:?}", end);
+}
+
+#[test]
+pub fn scale_non_uniform() {
+    let effect  = ScaleEffect::scale(Point2D(0.0, 0.0), Duration::from_millis(1000), Scale(1.0, 1.0), Scale(2.0, 3.0));
+    let content = square_content();
+
+    assert!(effect.scale_at_time(Duration::from_millis(500)) == Scale(1.5, 2.0));
+
+    let scaled  = effect.animate(Arc::clone(&content), Duration::from_millis(1000));
+    let corner  = scaled.paths().nth(0).unwrap().path[0].1[1].2;
+
+    assert!(corner.distance_to(&Coord2(200.0, 300.0)) < 0.01, "{:?}", corner);
+}
+
+#[test]
+pub fn cached_rotation_matches_uncached() {
+    let effect  = RotateEffect::rotate(Point2D(20.0, 30.0), Duration::from_millis(1000), RotateDegrees(10.0), RotateDegrees(80.0));
+    let content = square_content();
+    let cached  = effect.animate_cached(Arc::clone(&content));
+
+    for time in 0..10 {
+        let time = Duration::from_millis(time * 100);
+        assert!(first_point(&cached(time)).distance_to(&first_point(&effect.animate(Arc::clone(&content), time))) < 0.001);
+    }
+}
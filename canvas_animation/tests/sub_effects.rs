@@ -225,3 +225,52 @@ fn add_timecurve_then_repeat() {
     assert!(new_effect.sub_effects()[1].effect_type() == SubEffectType::TimeCurve);
     assert!(new_effect.sub_effects()[2].effect_type() == SubEffectType::LinearPosition);
 }
+
+#[test]
+fn add_rotate_scale_fade_and_tint() {
+    let effect = EffectDescription::Sequence(vec![]);
+
+    let new_effect = effect.add_new_effect(SubEffectType::Rotation);
+    let new_effect = new_effect.add_new_effect(SubEffectType::Scaling);
+    let new_effect = new_effect.add_new_effect(SubEffectType::Opacity);
+    let new_effect = new_effect.add_new_effect(SubEffectType::Tint);
+
+    assert!(new_effect.sub_effects().len() == 4);
+    assert!(new_effect.sub_effects()[0].effect_type() == SubEffectType::Rotation);
+    assert!(new_effect.sub_effects()[1].effect_type() == SubEffectType::Scaling);
+    assert!(new_effect.sub_effects()[2].effect_type() == SubEffectType::Opacity);
+    assert!(new_effect.sub_effects()[3].effect_type() == SubEffectType::Tint);
+}
+
+#[test]
+fn fade_inside_repeat() {
+    let effect = EffectDescription::Repeat(Duration::from_millis(1000), EffectDescription::Fade(Duration::from_millis(500), 1.0, 0.0).boxed());
+
+    assert!(effect.sub_effects().len() == 2);
+    assert!(effect.sub_effects()[0].effect_type() == SubEffectType::Repeat);
+    assert!(effect.sub_effects()[1].effect_type() == SubEffectType::Opacity);
+}
+
+#[test]
+fn follow_path_effect() {
+    let effect = EffectDescription::FollowPath(Duration::from_millis(10000), BezierPath(Point2D(20.0, 30.0), vec![BezierPoint(Point2D(20.0, 100.0), Point2D(200.0, 200.0), Point2D(300.0, 400.0))]), PathOrientation::default());
+
+    assert!(effect.sub_effects().len() == 1);
+    assert!(effect.sub_effects()[0].effect_type() == SubEffectType::FollowPath);
+}
+
+#[test]
+fn skeleton_effect() {
+    let effect = EffectDescription::Sequence(vec![]).add_new_effect(SubEffectType::Skeleton);
+
+    assert!(effect.sub_effects().len() == 1);
+    assert!(effect.sub_effects()[0].effect_type() == SubEffectType::Skeleton);
+}
+
+#[test]
+fn shape_tween_effect() {
+    let effect = EffectDescription::Sequence(vec![]).add_new_effect(SubEffectType::ShapeTween);
+
+    assert!(effect.sub_effects().len() == 1);
+    assert!(effect.sub_effects()[0].effect_type() == SubEffectType::ShapeTween);
+}
@@ -0,0 +1,157 @@
+//!
+//! Golden-image harness for the visual regression tests
+//!
+//! Scenes are rendered on the CPU using `flo_canvas_raster` and compared against the reference images stored in
+//! `tests/golden`. A missing reference image is a failure: run the tests with the `FLO_UPDATE_GOLDEN` environment
+//! variable set to write the rendered images as the new references. When a comparison fails, the rendered image and
+//! an image highlighting the differences are written to the test output directory.
+//!
+
+use flo_canvas::*;
+use flo_canvas_raster::*;
+
+use std::env;
+use std::fs;
+use std::path::{PathBuf};
+
+///
+/// How much an image is allowed to differ from its reference before a test fails
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Tolerance {
+    /// The maximum difference in any colour channel (0-255) before a pixel is counted as being different
+    pub max_channel_difference: u8,
+
+    /// The proportion of pixels (0-1) that can be different before the images are considered to not match
+    pub max_different_pixels: f64
+}
+
+impl Default for Tolerance {
+    fn default() -> Tolerance {
+        Tolerance {
+            max_channel_difference: 8,
+            max_different_pixels:   0.002
+        }
+    }
+}
+
+///
+/// The directory containing the reference images
+///
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+///
+/// The directory where the images from failed tests are written
+///
+fn output_dir() -> PathBuf {
+    option_env!("CARGO_TARGET_TMPDIR")
+        .map(|dir| PathBuf::from(dir))
+        .unwrap_or_else(|| env::temp_dir())
+        .join("flo_canvas_animation_visual")
+}
+
+///
+/// Renders a drawing to a raster canvas of the specified size
+///
+pub fn render_drawing(drawing: Vec<Draw>, width: usize, height: usize) -> RasterCanvas {
+    let mut canvas = RasterCanvas::new(width, height);
+    canvas.draw(drawing);
+
+    canvas
+}
+
+///
+/// Generates an image showing where two images differ: matching pixels are shown as a faded version of the
+/// reference image, and differing pixels are shown in red
+///
+fn diff_image(expected: &[u8], actual: &[u8], tolerance: Tolerance) -> Vec<u8> {
+    expected.chunks(4).zip(actual.chunks(4))
+        .flat_map(|(expected, actual)| {
+            let difference = expected.iter().zip(actual.iter())
+                .map(|(a, b)| (*a as i32 - *b as i32).abs())
+                .max()
+                .unwrap_or(0);
+
+            if difference > tolerance.max_channel_difference as i32 {
+                vec![255, 0, 0, 255]
+            } else {
+                let grey = ((expected[0] as u32 + expected[1] as u32 + expected[2] as u32) / 3) as u8;
+                let grey = 192 + grey / 4;
+                vec![grey, grey, grey, 255]
+            }
+        })
+        .collect()
+}
+
+///
+/// Checks that a rendered canvas matches the reference image with the specified name
+///
+pub fn assert_matches_golden(name: &str, canvas: &RasterCanvas, tolerance: Tolerance) {
+    let actual          = canvas.to_rgba();
+    let golden_path     = golden_dir().join(format!("{}.png", name));
+
+    // Write a new reference image if requested
+    if env::var("FLO_UPDATE_GOLDEN").is_ok() {
+        fs::create_dir_all(golden_dir()).unwrap();
+        fs::write(&golden_path, canvas.to_png().unwrap()).unwrap();
+
+        return;
+    }
+
+    assert!(golden_path.exists(), "There is no reference image for '{}' (expected at {}). Run the tests with FLO_UPDATE_GOLDEN set to create it.", name, golden_path.display());
+
+    // Load the reference image
+    let golden_png                              = fs::read(&golden_path).unwrap();
+    let (golden_width, golden_height, expected) = rgba_for_png_data(&golden_png).unwrap();
+
+    // Count the pixels that are different
+    let different_pixels = if golden_width != canvas.width() || golden_height != canvas.height() {
+        None
+    } else {
+        Some(expected.chunks(4).zip(actual.chunks(4))
+            .filter(|(expected, actual)| expected.iter().zip(actual.iter()).any(|(a, b)| (*a as i32 - *b as i32).abs() > tolerance.max_channel_difference as i32))
+            .count())
+    };
+
+    let num_pixels  = (canvas.width() * canvas.height()).max(1);
+    let matches     = different_pixels
+        .map(|different_pixels| (different_pixels as f64) / (num_pixels as f64) <= tolerance.max_different_pixels)
+        .unwrap_or(false);
+
+    if !matches {
+        // Write out the actual image and the differences so the failure can be investigated
+        let output_dir  = output_dir();
+        let actual_path = output_dir.join(format!("{}.actual.png", name));
+        let diff_path   = output_dir.join(format!("{}.diff.png", name));
+
+        fs::create_dir_all(&output_dir).unwrap();
+        fs::write(&actual_path, canvas.to_png().unwrap()).unwrap();
+
+        if different_pixels.is_some() {
+            let diff = diff_image(&expected, &actual, tolerance);
+            fs::write(&diff_path, png_data_for_rgba(&diff, canvas.width(), canvas.height()).unwrap()).unwrap();
+        }
+
+        match different_pixels {
+            Some(different_pixels)  => assert!(false, "'{}' does not match the reference image ({} of {} pixels are different). Rendered image: {}, differences: {}", name, different_pixels, num_pixels, actual_path.display(), diff_path.display()),
+            None                    => assert!(false, "'{}' has a different size to the reference image ({}x{} vs {}x{}). Rendered image: {}", name, canvas.width(), canvas.height(), golden_width, golden_height, actual_path.display())
+        }
+    }
+}
+
+#[test]
+fn identical_images_match() {
+    let mut drawing = vec![];
+    drawing.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0));
+    drawing.new_path();
+    drawing.circle(0.0, 0.0, 0.5);
+    drawing.fill();
+
+    let canvas  = render_drawing(drawing.clone(), 32, 32);
+    let same    = render_drawing(drawing, 32, 32);
+    let diff    = diff_image(&canvas.to_rgba(), &same.to_rgba(), Tolerance::default());
+
+    assert!(diff.chunks(4).all(|pixel| pixel[0] != 255 || pixel[1] != 0));
+}
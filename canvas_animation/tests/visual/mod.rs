@@ -0,0 +1,4 @@
+mod harness;
+mod shatter;
+mod shatter_transform;
+mod motion_effect;
@@ -0,0 +1,110 @@
+//!
+//! Regression tests ported from the `motion_effect` demo
+//!
+//! The demo lets the user drag out a motion path; here a fixed path is used instead
+//!
+
+use super::harness::*;
+
+use flo_canvas::*;
+use flo_curves::*;
+use flo_canvas_animation::effects::*;
+
+use std::time::{Duration};
+
+///
+/// The start point of the motion path
+///
+const START_POINT: Coord2 = Coord2(200.0, 200.0);
+
+///
+/// The curves making up the motion path, as (control point 1, control point 2, end point)
+///
+fn motion_path() -> Vec<(Coord2, Coord2, Coord2)> {
+    vec![
+        (Coord2(200.0, 500.0), Coord2(400.0, 800.0), Coord2(500.0, 700.0)),
+        (Coord2(600.0, 600.0), Coord2(600.0, 300.0), Coord2(800.0, 300.0))
+    ]
+}
+
+///
+/// Renders a frame of the motion effect demo at a particular time (in milliseconds)
+///
+fn render_motion_frame(time: f64) -> Vec<Draw> {
+    let motion_path     = motion_path();
+    let motion_effect   = LinearMotionEffect::from_points(Duration::from_secs(10), START_POINT, motion_path.clone());
+    let mut drawing     = vec![];
+
+    drawing.clear_canvas(Color::Rgba(0.7, 0.8, 0.5, 1.0));
+    drawing.canvas_height(1000.0);
+    drawing.center_region(0.0, 0.0, 1000.0, 1000.0);
+
+    // Draw the motion path
+    drawing.layer(LayerId(1));
+
+    drawing.line_width(4.0);
+    drawing.stroke_color(Color::Rgba(0.0, 0.0, 0.0, 1.0));
+
+    drawing.new_path();
+    drawing.move_to(START_POINT.x() as _, START_POINT.y() as _);
+    for (cp1, cp2, end_point) in motion_path.iter() {
+        drawing.bezier_curve_to(end_point.x() as _, end_point.y() as _, cp1.x() as _, cp1.y() as _, cp2.x() as _, cp2.y() as _);
+    }
+    drawing.stroke();
+
+    drawing.line_width(1.0);
+    drawing.stroke_color(Color::Rgba(0.0, 0.0, 0.0, 1.0));
+    drawing.fill_color(Color::Rgba(1.0, 1.0, 1.0, 0.9));
+
+    for point in Some(START_POINT).into_iter().chain(motion_path.iter().map(|(_, _, end_point)| *end_point)) {
+        drawing.new_path();
+        drawing.circle(point.x() as _, point.y() as _, 6.0);
+        drawing.fill();
+        drawing.stroke();
+    }
+
+    // Draw the circle at its position along the path
+    let animation_time  = time % 10_000.0;
+    let offset          = motion_effect.offset_at_time(animation_time, 0.01);
+    let pos             = START_POINT + offset;
+
+    drawing.layer(LayerId(2));
+    drawing.new_path();
+    drawing.circle(pos.x() as _, pos.y() as _, 75.0);
+
+    drawing.fill_color(Color::Rgba(0.9, 0.9, 1.0, 0.6));
+    drawing.line_width(6.0);
+    drawing.stroke_color(Color::Rgba(0.1, 0.1, 0.1, 1.0));
+    drawing.fill();
+    drawing.stroke();
+
+    drawing.line_width(1.0);
+    drawing.stroke_color(Color::Rgba(0.5, 0.1, 0.1, 1.0));
+
+    drawing.new_path();
+    drawing.move_to((pos.x() - 40.0) as _, pos.y() as _);
+    drawing.line_to((pos.x() + 40.0) as _, pos.y() as _);
+    drawing.move_to(pos.x() as _, (pos.y() - 40.0) as _);
+    drawing.line_to(pos.x() as _, (pos.y() + 40.0) as _);
+    drawing.stroke();
+
+    drawing
+}
+
+#[test]
+fn motion_effect_start() {
+    let canvas = render_drawing(render_motion_frame(0.0), 256, 256);
+    assert_matches_golden("motion_effect_0s", &canvas, Tolerance::default());
+}
+
+#[test]
+fn motion_effect_midpoint() {
+    let canvas = render_drawing(render_motion_frame(5_000.0), 256, 256);
+    assert_matches_golden("motion_effect_5s", &canvas, Tolerance::default());
+}
+
+#[test]
+fn motion_effect_near_end() {
+    let canvas = render_drawing(render_motion_frame(9_000.0), 256, 256);
+    assert_matches_golden("motion_effect_9s", &canvas, Tolerance::default());
+}
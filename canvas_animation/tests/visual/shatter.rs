@@ -0,0 +1,126 @@
+//!
+//! Regression tests ported from the `shatter` demo
+//!
+
+use super::harness::*;
+
+use flo_canvas::*;
+use flo_curves::bezier::path::*;
+use flo_canvas_animation::*;
+use flo_canvas_animation::effects::*;
+
+use futures::executor;
+
+use std::f64;
+use std::time::{Duration};
+
+///
+/// Creates the animation layer for the shatter demo: a circle split into 16 slices, each moving outwards and back again
+///
+fn shatter_layer() -> AnimationLayer {
+    // Fill a canvas layer with a circle and some regions
+    let mut circle_drawing = vec![];
+
+    circle_drawing.new_path();
+    circle_drawing.circle(500.0, 500.0, 116.0);
+    circle_drawing.fill_color(Color::Rgba(0.1, 0.1, 0.1, 1.0));
+    circle_drawing.fill();
+    circle_drawing.new_path();
+    circle_drawing.circle(500.0, 500.0, 100.0);
+    circle_drawing.fill_color(Color::Rgba(0.91, 1.0, 0.99, 1.0));
+    circle_drawing.fill();
+
+    // Create an animation layer for our circle
+    let mut animation_layer = AnimationLayer::new();
+    animation_layer.draw(circle_drawing);
+
+    // Create a bunch of regions to 'shatter' the circle
+    for slice_idx in 0..16 {
+        // Angle in radians of this slice
+        let middle_angle            = f64::consts::PI*2.0 / 16.0 * (slice_idx as f64);
+        let start_angle             = middle_angle - (f64::consts::PI*2.0 / 32.0);
+        let end_angle               = middle_angle + (f64::consts::PI*2.0 / 32.0);
+
+        // Create a triangle slice
+        let (center_x, center_y)    = (500.0, 500.0);
+        let (x1, y1)                = (center_x + (f64::sin(start_angle) * 300.0),  center_y + (f64::cos(start_angle) * 300.0));
+        let (x2, y2)                = (center_x + (f64::sin(end_angle) * 300.0),    center_y + (f64::cos(end_angle) * 300.0));
+        let (x3, y3)                = (center_x + (f64::sin(start_angle) * 16.0),  center_y + (f64::cos(start_angle) * 16.0));
+        let (x4, y4)                = (center_x + (f64::sin(end_angle) * 16.0),    center_y + (f64::cos(end_angle) * 16.0));
+
+        let fragment                = BezierPathBuilder::<SimpleBezierPath>::start(Coord2(x3, y3))
+            .line_to(Coord2(x1, y1))
+            .line_to(Coord2(x2, y2))
+            .line_to(Coord2(x4, y4))
+            .line_to(Coord2(x3, y3))
+            .build();
+
+        // Create an animation effect
+        let (dx, dy)                = (f64::sin(middle_angle) * 300.0, f64::cos(middle_angle) * 300.0);
+        let motion_effect           = LinearMotionEffect::from_points(Duration::from_secs(20), 
+            Coord2(center_x, center_y), 
+            vec![
+                (Coord2(center_x + dx * 0.33, center_y + dy * 0.33), Coord2(center_x + dx * 0.66, center_y + dy * 0.66), Coord2(center_x + dx, center_y + dy)),
+                (Coord2(center_x + dx * 0.66, center_y + dy * 0.66), Coord2(center_x + dx * 0.33, center_y + dy * 0.33), Coord2(center_x, center_y))
+            ]);
+
+        // Apply a time curve
+        let motion_effect           = TimeCurveEffect::with_control_points(motion_effect, vec![(0.0, 10000.0, 10000.0), (10000.0, 19000.0, 20000.0)]);
+        let motion_effect           = RepeatEffect::repeat_effect(motion_effect, Duration::from_secs(20));
+
+        // Apply it to a region of the layer
+        let motion_effect           = motion_effect.with_region(vec![fragment]);
+        animation_layer.add_region(motion_effect);
+    }
+
+    animation_layer
+}
+
+///
+/// Renders a frame of the shatter demo at a particular time
+///
+fn render_shatter_frame(animation_layer: &mut AnimationLayer, time: Duration) -> Vec<Draw> {
+    let mut drawing = vec![];
+
+    drawing.clear_canvas(Color::Rgba(0.7, 0.8, 0.5, 1.0));
+    drawing.canvas_height(1000.0);
+    drawing.center_region(0.0, 0.0, 1000.0, 1000.0);
+
+    drawing.layer(LayerId(2));
+    drawing.extend(executor::block_on(animation_layer.render_at_time(time)));
+
+    drawing
+}
+
+#[test]
+fn shatter_start() {
+    let mut layer   = shatter_layer();
+    let canvas      = render_drawing(render_shatter_frame(&mut layer, Duration::from_secs(0)), 256, 256);
+
+    assert_matches_golden("shatter_0s", &canvas, Tolerance::default());
+}
+
+#[test]
+fn shatter_moving_out() {
+    let mut layer   = shatter_layer();
+    let canvas      = render_drawing(render_shatter_frame(&mut layer, Duration::from_secs(5)), 256, 256);
+
+    assert_matches_golden("shatter_5s", &canvas, Tolerance::default());
+}
+
+#[test]
+fn shatter_furthest_point() {
+    let mut layer   = shatter_layer();
+    let canvas      = render_drawing(render_shatter_frame(&mut layer, Duration::from_secs(10)), 256, 256);
+
+    assert_matches_golden("shatter_10s", &canvas, Tolerance::default());
+}
+
+#[test]
+fn shatter_repeats() {
+    // The effect repeats every 20 seconds, so the frame at 25s should be the same as the frame at 5s
+    let mut layer   = shatter_layer();
+    let canvas      = render_drawing(render_shatter_frame(&mut layer, Duration::from_secs(25)), 256, 256);
+
+    assert_matches_golden("shatter_5s", &canvas, Tolerance::default());
+}
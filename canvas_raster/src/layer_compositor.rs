@@ -0,0 +1,208 @@
+use crate::raster_canvas::*;
+
+use flo_canvas::*;
+
+///
+/// How a layer is combined with the pixels behind it when it's composited
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RasterBlendMode {
+    /// The layer is drawn over the pixels behind it
+    Normal,
+
+    /// The colours of the layer are multiplied with the colours behind it
+    Multiply,
+
+    /// The inverse colours of the layer are multiplied with the inverse colours behind it
+    Screen,
+
+    /// Multiplies the dark parts of the pixels behind the layer and screens the light parts
+    Overlay,
+
+    /// The colours of the layer are added to the colours behind it
+    Add
+}
+
+///
+/// A layer to be rendered offscreen and composited with the layers behind it
+///
+#[derive(Clone, Debug)]
+pub struct RasterLayer {
+    /// The drawing instructions for this layer (these are rendered on a transparent canvas)
+    pub drawing: Vec<Draw>,
+
+    /// The opacity of this layer
+    pub alpha: f64,
+
+    /// How this layer is blended with the layers behind it
+    pub blend_mode: RasterBlendMode
+}
+
+///
+/// Renders a set of layers, each on their own offscreen canvas, and composites them over a background colour
+///
+/// This supports blend modes that are not available when drawing layers directly on a canvas (`Overlay` and `Add`).
+/// The result is a set of 8-bit RGBA values, in rows from top to bottom.
+///
+pub fn composite_layers<LayerIter: IntoIterator<Item=RasterLayer>>(width: usize, height: usize, background: Color, layers: LayerIter) -> Vec<u8> {
+    // Fill the result with the background colour
+    let (r, g, b, a)    = background.to_rgba_components();
+    let background      = [to_byte(r as f64), to_byte(g as f64), to_byte(b as f64), to_byte(a as f64)];
+    let mut rgba        = background.iter().cloned().cycle().take(width*height*4).collect::<Vec<_>>();
+
+    // Render each layer offscreen and blend it with the result so far
+    for layer in layers {
+        let mut canvas = RasterCanvas::new(width, height);
+        canvas.draw(layer.drawing);
+
+        blend_rgba(&mut rgba, &canvas.to_rgba(), layer.alpha, layer.blend_mode);
+    }
+
+    rgba
+}
+
+///
+/// Blends a set of RGBA pixels over another set of RGBA pixels of the same size
+///
+/// Pixels are non-premultiplied 8-bit RGBA values. The blend modes are applied to the colour channels using the usual
+/// separable blend formulae (as in the W3C compositing specification), and the result is composited using source-over.
+///
+pub fn blend_rgba(target: &mut [u8], source: &[u8], alpha: f64, blend_mode: RasterBlendMode) {
+    for (target, source) in target.chunks_exact_mut(4).zip(source.chunks_exact(4)) {
+        let source_alpha    = from_byte(source[3]) * alpha;
+        let target_alpha    = from_byte(target[3]);
+        let result_alpha    = source_alpha + target_alpha * (1.0 - source_alpha);
+
+        if result_alpha <= 0.0 {
+            target.iter_mut().for_each(|component| *component = 0);
+            continue;
+        }
+
+        for component in 0..3 {
+            let source_col  = from_byte(source[component]);
+            let target_col  = from_byte(target[component]);
+
+            // Blend the colours where the layer overlaps the pixels behind it, then composite using source-over
+            let blended     = (1.0 - target_alpha) * source_col + target_alpha * blend_component(blend_mode, target_col, source_col);
+            let result      = source_alpha * blended + target_alpha * target_col * (1.0 - source_alpha);
+
+            target[component] = to_byte(result / result_alpha);
+        }
+
+        target[3] = to_byte(result_alpha);
+    }
+}
+
+///
+/// Applies a blend mode to a single colour component
+///
+#[inline]
+fn blend_component(blend_mode: RasterBlendMode, backdrop: f64, source: f64) -> f64 {
+    match blend_mode {
+        RasterBlendMode::Normal     => source,
+        RasterBlendMode::Multiply   => backdrop * source,
+        RasterBlendMode::Screen     => backdrop + source - backdrop * source,
+        RasterBlendMode::Add        => (backdrop + source).min(1.0),
+        RasterBlendMode::Overlay    => {
+            if backdrop <= 0.5 {
+                2.0 * backdrop * source
+            } else {
+                1.0 - 2.0 * (1.0 - backdrop) * (1.0 - source)
+            }
+        }
+    }
+}
+
+#[inline]
+fn from_byte(byte: u8) -> f64 {
+    (byte as f64) / 255.0
+}
+
+#[inline]
+fn to_byte(value: f64) -> u8 {
+    (value.max(0.0).min(1.0) * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn normal_replaces_opaque_pixels() {
+        let mut target = vec![10, 20, 30, 255];
+        blend_rgba(&mut target, &[200, 100, 50, 255], 1.0, RasterBlendMode::Normal);
+
+        assert!(target == vec![200, 100, 50, 255]);
+    }
+
+    #[test]
+    fn layer_alpha_mixes_with_pixels_behind() {
+        let mut target = vec![0, 0, 0, 255];
+        blend_rgba(&mut target, &[255, 255, 255, 255], 0.5, RasterBlendMode::Normal);
+
+        assert!(target == vec![128, 128, 128, 255]);
+    }
+
+    #[test]
+    fn transparent_pixels_leave_target_unchanged() {
+        let mut target = vec![10, 20, 30, 255];
+        blend_rgba(&mut target, &[200, 100, 50, 0], 1.0, RasterBlendMode::Add);
+
+        assert!(target == vec![10, 20, 30, 255]);
+    }
+
+    #[test]
+    fn add_saturates() {
+        let mut target = vec![100, 200, 0, 255];
+        blend_rgba(&mut target, &[100, 100, 50, 255], 1.0, RasterBlendMode::Add);
+
+        assert!(target == vec![200, 255, 50, 255]);
+    }
+
+    #[test]
+    fn overlay_darkens_dark_pixels_and_lightens_light_pixels() {
+        // 25% grey overlaid with 25% grey is darker, 75% grey overlaid with 75% grey is lighter
+        let mut target = vec![64, 191, 0, 255];
+        blend_rgba(&mut target, &[64, 191, 128, 255], 1.0, RasterBlendMode::Overlay);
+
+        assert!(target[0] < 64);
+        assert!(target[1] > 191);
+        assert!(target[2] == 0);
+        assert!(target[3] == 255);
+    }
+
+    #[test]
+    fn overlay_uses_backdrop_to_pick_multiply_or_screen() {
+        // Overlay is hard light with the layers swapped: a light layer over a dark backdrop is still multiplied
+        let mut target = vec![51, 255, 0, 255];
+        blend_rgba(&mut target, &[255, 0, 0, 255], 1.0, RasterBlendMode::Overlay);
+
+        assert!(target == vec![102, 255, 0, 255]);
+    }
+
+    ///
+    /// Creates a layer drawing that fills a 2x2 canvas with a colour
+    ///
+    fn fill_layer(color: Color) -> Vec<Draw> {
+        let mut drawing: Vec<Draw> = vec![];
+        drawing.canvas_height(2.0);
+        drawing.center_region(0.0, 0.0, 2.0, 2.0);
+        drawing.fill_color(color);
+        drawing.new_path();
+        drawing.rect(0.0, 0.0, 2.0, 2.0);
+        drawing.fill();
+
+        drawing
+    }
+
+    #[test]
+    fn composite_layers_on_background() {
+        let rgba = composite_layers(2, 2, Color::Rgba(0.0, 0.0, 0.0, 1.0), vec![
+            RasterLayer { drawing: fill_layer(Color::Rgba(1.0, 0.0, 0.0, 1.0)), alpha: 1.0, blend_mode: RasterBlendMode::Normal },
+            RasterLayer { drawing: fill_layer(Color::Rgba(0.0, 1.0, 0.0, 1.0)), alpha: 1.0, blend_mode: RasterBlendMode::Add }
+        ]);
+
+        assert!(rgba.len() == 2*2*4);
+        assert!(rgba[0..4] == [255, 255, 0, 255]);
+    }
+}
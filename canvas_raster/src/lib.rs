@@ -0,0 +1,16 @@
+//!
+//! # flo_canvas_raster
+//!
+//! Renders `flo_canvas` drawing instructions to PNG images on the CPU using `flo_render_software`. This doesn't need
+//! a GPU or a windowing system, so it can be used to render animations on headless machines (eg, render farms or CI
+//! servers) and to generate the images used by regression tests.
+//!
+#![warn(bare_trait_objects)]
+
+mod raster_canvas;
+mod png_output;
+mod layer_compositor;
+
+pub use self::raster_canvas::*;
+pub use self::png_output::*;
+pub use self::layer_compositor::*;
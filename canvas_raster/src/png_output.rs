@@ -0,0 +1,69 @@
+use crate::raster_canvas::*;
+
+use ::png;
+
+use std::io;
+
+///
+/// Encodes a set of RGBA pixels as PNG data
+///
+pub fn png_data_for_rgba(rgba: &[u8], width: usize, height: usize) -> Result<Vec<u8>, io::Error> {
+    let mut png_data: Vec<u8> = vec![];
+
+    {
+        // Create an encoder that will write to the buffer
+        let mut png_encoder = png::Encoder::new(&mut png_data, width as u32, height as u32);
+        png_encoder.set_color(png::ColorType::RGBA);
+        png_encoder.set_depth(png::BitDepth::Eight);
+
+        // Write the header and the image data
+        let mut png_writer = png_encoder.write_header().map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        png_writer.write_image_data(rgba).map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+    }
+
+    Ok(png_data)
+}
+
+impl RasterCanvas {
+    ///
+    /// Encodes the contents of this canvas as a PNG file
+    ///
+    pub fn to_png(&self) -> Result<Vec<u8>, io::Error> {
+        png_data_for_rgba(&self.to_rgba(), self.width(), self.height())
+    }
+}
+
+///
+/// Decodes PNG data into a set of RGBA pixels, returning the width, height and pixel data
+///
+/// Only 8-bit RGBA images (like the ones generated by `png_data_for_rgba`) are supported
+///
+pub fn rgba_for_png_data(png_data: &[u8]) -> Result<(usize, usize, Vec<u8>), io::Error> {
+    let decoder             = png::Decoder::new(png_data);
+    let (info, mut reader)  = decoder.read_info().map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    if info.color_type != png::ColorType::RGBA || info.bit_depth != png::BitDepth::Eight {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Only 8-bit RGBA PNG files are supported"));
+    }
+
+    let mut rgba = vec![0; info.buffer_size()];
+    reader.next_frame(&mut rgba).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    Ok((info.width as usize, info.height as usize, rgba))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn png_round_trip() {
+        let rgba                        = (0..(4*3*4)).map(|val| (val * 5) as u8).collect::<Vec<_>>();
+        let png                         = png_data_for_rgba(&rgba, 4, 3).unwrap();
+        let (width, height, decoded)    = rgba_for_png_data(&png).unwrap();
+
+        assert!(width == 4);
+        assert!(height == 3);
+        assert!(decoded == rgba);
+    }
+}
@@ -0,0 +1,186 @@
+use flo_canvas::*;
+use flo_render_software::canvas::*;
+use flo_render_software::render::*;
+
+///
+/// The gamma value to use when converting the rendered pixels to 8-bit values
+///
+const GAMMA: f64 = 2.2;
+
+///
+/// A canvas that renders drawing instructions to a bitmap using the CPU
+///
+/// Rendering is performed by `flo_render_software`, so this supports the same instructions as the other canvas renderers
+/// (including clipping, gradients, textures and text). Coordinates follow the same conventions: the default coordinate
+/// scheme runs from -1 to 1 in both directions, and `CanvasHeight` and `CenterRegion` can be used to set up a scheme with
+/// a fixed aspect ratio and a y axis that points upwards.
+///
+pub struct RasterCanvas {
+    /// The width of the bitmap in pixels
+    width: usize,
+
+    /// The height of the bitmap in pixels
+    height: usize,
+
+    /// The instructions that have been drawn on this canvas
+    drawing: Vec<Draw>,
+}
+
+impl RasterCanvas {
+    ///
+    /// Creates a new raster canvas with the specified size in pixels
+    ///
+    pub fn new(width: usize, height: usize) -> RasterCanvas {
+        RasterCanvas {
+            width:      width,
+            height:     height,
+            drawing:    vec![],
+        }
+    }
+
+    ///
+    /// The width of this canvas in pixels
+    ///
+    pub fn width(&self) -> usize { self.width }
+
+    ///
+    /// The height of this canvas in pixels
+    ///
+    pub fn height(&self) -> usize { self.height }
+
+    ///
+    /// Adds a set of drawing instructions to this canvas
+    ///
+    pub fn draw<DrawIter: IntoIterator<Item=Draw>>(&mut self, drawing: DrawIter) {
+        self.drawing.extend(drawing);
+    }
+
+    ///
+    /// Returns the contents of this canvas as 8-bit RGBA values, in rows from top to bottom
+    ///
+    pub fn to_rgba(&self) -> Vec<u8> {
+        let mut rgba = vec![0u8; self.width*self.height*4];
+
+        {
+            let mut frame = RgbaFrame::from_bytes(self.width, self.height, GAMMA, &mut rgba).expect("RGBA buffer matches the size of the canvas");
+            render_drawing(&mut frame, self.drawing.iter().cloned());
+        }
+
+        rgba
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    ///
+    /// Reads the RGBA value of a pixel
+    ///
+    fn pixel(rgba: &Vec<u8>, width: usize, x: usize, y: usize) -> [u8; 4] {
+        let pos = (y*width + x) * 4;
+        [rgba[pos], rgba[pos+1], rgba[pos+2], rgba[pos+3]]
+    }
+
+    #[test]
+    fn fill_rectangle() {
+        let mut canvas = RasterCanvas::new(100, 100);
+
+        canvas.draw(vec![
+            Draw::ClearCanvas(Color::Rgba(1.0, 1.0, 1.0, 1.0)),
+            Draw::CanvasHeight(100.0),
+            Draw::CenterRegion((0.0, 0.0), (100.0, 100.0)),
+            Draw::FillColor(Color::Rgba(1.0, 0.0, 0.0, 1.0)),
+            Draw::Path(PathOp::NewPath),
+            Draw::Path(PathOp::Move(10.0, 10.0)),
+            Draw::Path(PathOp::Line(50.0, 10.0)),
+            Draw::Path(PathOp::Line(50.0, 30.0)),
+            Draw::Path(PathOp::Line(10.0, 30.0)),
+            Draw::Path(PathOp::ClosePath),
+            Draw::Fill
+        ]);
+
+        let rgba = canvas.to_rgba();
+
+        // y axis points upwards, so the rectangle is near the bottom of the image
+        assert!(pixel(&rgba, 100, 20, 80) == [255, 0, 0, 255]);
+        assert!(pixel(&rgba, 100, 20, 20) == [255, 255, 255, 255]);
+        assert!(pixel(&rgba, 100, 60, 80) == [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn stroke_line() {
+        let mut canvas = RasterCanvas::new(100, 100);
+
+        canvas.draw(vec![
+            Draw::CanvasHeight(100.0),
+            Draw::CenterRegion((0.0, 0.0), (100.0, 100.0)),
+            Draw::StrokeColor(Color::Rgba(0.0, 0.0, 1.0, 1.0)),
+            Draw::LineWidth(10.0),
+            Draw::Path(PathOp::NewPath),
+            Draw::Path(PathOp::Move(10.0, 50.0)),
+            Draw::Path(PathOp::Line(90.0, 50.0)),
+            Draw::Stroke
+        ]);
+
+        let rgba = canvas.to_rgba();
+
+        assert!(pixel(&rgba, 100, 50, 48) == [0, 0, 255, 255]);
+        assert!(pixel(&rgba, 100, 50, 52) == [0, 0, 255, 255]);
+        assert!(pixel(&rgba, 100, 50, 40)[3] == 0);
+        assert!(pixel(&rgba, 100, 5, 50)[3] == 0);
+    }
+
+    #[test]
+    fn erase_from_layer() {
+        let mut canvas = RasterCanvas::new(10, 10);
+
+        canvas.draw(vec![
+            Draw::FillColor(Color::Rgba(0.0, 1.0, 0.0, 1.0)),
+            Draw::Path(PathOp::NewPath),
+            Draw::Path(PathOp::Move(-1.0, -1.0)),
+            Draw::Path(PathOp::Line(1.0, -1.0)),
+            Draw::Path(PathOp::Line(1.0, 1.0)),
+            Draw::Path(PathOp::Line(-1.0, 1.0)),
+            Draw::Path(PathOp::ClosePath),
+            Draw::Fill,
+
+            Draw::BlendMode(BlendMode::DestinationOut),
+            Draw::Path(PathOp::NewPath),
+            Draw::Path(PathOp::Move(-1.0, -1.0)),
+            Draw::Path(PathOp::Line(0.0, -1.0)),
+            Draw::Path(PathOp::Line(0.0, 1.0)),
+            Draw::Path(PathOp::Line(-1.0, 1.0)),
+            Draw::Path(PathOp::ClosePath),
+            Draw::Fill
+        ]);
+
+        let rgba = canvas.to_rgba();
+
+        assert!(pixel(&rgba, 10, 2, 5)[3] == 0);
+        assert!(pixel(&rgba, 10, 7, 5) == [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn layers_are_composited_in_order() {
+        let mut canvas  = RasterCanvas::new(10, 10);
+        let square      = |color| vec![
+            Draw::FillColor(color),
+            Draw::Path(PathOp::NewPath),
+            Draw::Path(PathOp::Move(-1.0, -1.0)),
+            Draw::Path(PathOp::Line(1.0, -1.0)),
+            Draw::Path(PathOp::Line(1.0, 1.0)),
+            Draw::Path(PathOp::Line(-1.0, 1.0)),
+            Draw::Path(PathOp::ClosePath),
+            Draw::Fill
+        ];
+
+        canvas.draw(vec![Draw::Layer(LayerId(2))]);
+        canvas.draw(square(Color::Rgba(0.0, 0.0, 1.0, 1.0)));
+        canvas.draw(vec![Draw::Layer(LayerId(1))]);
+        canvas.draw(square(Color::Rgba(1.0, 0.0, 0.0, 1.0)));
+
+        // Layer 2 is on top, even though it was drawn first
+        assert!(pixel(&canvas.to_rgba(), 10, 5, 5) == [0, 0, 255, 255]);
+    }
+}
@@ -3,6 +3,9 @@ use super::storage_descriptor::*;
 
 use flo_animation::*;
 
+use std::ops::{Range};
+use std::time::{Duration};
+
 ///
 /// Command that can be issued to a FlowBetween instance
 ///
@@ -63,5 +66,11 @@ pub enum FloCommand {
     ListElements,
 
     /// Writes out debugging SVG files for raycasting a particular element
-    RayCastToSvg(ElementId)
+    RayCastToSvg(ElementId),
+
+    /// Converts an SVG document (supplied as a string) into edits in the edit buffer that add it to the specified layer and frame of the output animation
+    ImportSvg(String, u64, usize),
+
+    /// Renders the frames of the input animation in the specified time range to PNG files with the specified prefix, at the specified size (or the animation size if no size is specified)
+    RenderFrames(Range<Duration>, Option<(usize, usize)>, String)
 }
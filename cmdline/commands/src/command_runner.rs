@@ -70,6 +70,8 @@ fn run_command<'a>(command: FloCommand, output: &'a mut Publisher<FloCommandOutp
             FloCommand::SelectFrame(layer, when)        => { select_frame(output, state, layer, when).await; }
             FloCommand::ListElements                    => { list_elements(output, state).await; }
             FloCommand::RayCastToSvg(element_id)        => { raycast_to_svg(output, state, element_id).await?; }
+            FloCommand::ImportSvg(ref svg, layer, when) => { import_svg(output, state, svg.clone(), layer, when).await?; }
+            FloCommand::RenderFrames(ref range, size, ref prefix) => { render_frames(output, state, range.clone(), size, prefix.clone()).await?; }
         }
 
         // Finish the command
@@ -21,7 +21,13 @@ pub enum CommandError {
     NoFrameSelected,
 
     /// The element ID was not found
-    ElementNotFound(ElementId)
+    ElementNotFound(ElementId),
+
+    /// A file could not be imported
+    CouldNotImport(String),
+
+    /// A frame could not be rendered
+    CouldNotRender(String)
 }
 
 impl Display for CommandError {
@@ -33,7 +39,9 @@ impl Display for CommandError {
             CouldNotCreateAnimation(name)   => write!(fmt, "Coult not create animation '{}'", name),
             CannotParseEdit(line, edit)     => write!(fmt, "{}: cannot parse edit '{}'", line, edit),
             NoFrameSelected                 => write!(fmt, "A frame must be selected for this operation"),
-            ElementNotFound(id)             => write!(fmt, "Element {} was not found", id.id().map(|id| id.to_string()).unwrap_or("<unassigned>".to_string()))
+            ElementNotFound(id)             => write!(fmt, "Element {} was not found", id.id().map(|id| id.to_string()).unwrap_or("<unassigned>".to_string())),
+            CouldNotImport(reason)          => write!(fmt, "Could not import file: {}", reason),
+            CouldNotRender(reason)          => write!(fmt, "Could not render frame: {}", reason)
         }
     }
 }
@@ -19,6 +19,9 @@ pub enum FloCommandOutput {
     /// Generates output for saving
     Output(String),
 
+    /// Generates binary output for saving (eg, image data)
+    OutputBytes(Vec<u8>),
+
     /// Display an error message to the user
     Error(String),
 
@@ -0,0 +1,62 @@
+use crate::state::*;
+use crate::error::*;
+use crate::output::*;
+
+use flo_stream::*;
+use flo_animation::*;
+use flo_animation::import::*;
+
+use futures::prelude::*;
+
+use std::time::{Duration};
+
+///
+/// Converts an SVG document into edits that add its contents to the specified layer and frame of the output animation
+///
+/// The layer and keyframe are created if they don't already exist. The resulting edits are added to the edit buffer.
+///
+pub fn import_svg<'a>(output: &'a mut Publisher<FloCommandOutput>, state: &'a mut CommandState, svg: String, layer_id: u64, frame_number: usize) -> impl 'a+Future<Output=Result<(), CommandError>>+Send {
+    async move {
+        use FloCommandOutput::*;
+
+        output.publish(StartTask("Import SVG".to_string())).await;
+
+        let output_animation    = state.output_animation();
+        let when                = output_animation.frame_length() * (frame_number as u32);
+        let (_, height)         = output_animation.size();
+        let mut edits           = state.edit_buffer().clone();
+
+        // Create the layer and the keyframe if they don't exist yet
+        match output_animation.get_layer_with_id(layer_id) {
+            None        => {
+                edits.push(AnimationEdit::AddNewLayer(layer_id));
+                edits.push(AnimationEdit::Layer(layer_id, LayerEdit::AddKeyFrame(when)));
+            }
+
+            Some(layer) => {
+                // Edits are applied to the keyframe at or before the specified time
+                let (previous, _next) = layer.previous_and_next_key_frame(when + Duration::from_nanos(1));
+                if previous.is_none() {
+                    edits.push(AnimationEdit::Layer(layer_id, LayerEdit::AddKeyFrame(when)));
+                }
+            }
+        }
+
+        // Convert the SVG document to edits
+        let import_edits        = svg_to_edits(&svg, layer_id, when, height, || output_animation.assign_element_id());
+        let import_edits        = match import_edits {
+            Ok(import_edits)                        => import_edits,
+            Err(ImportError::CouldNotParse(reason)) => { return Err(CommandError::CouldNotImport(reason)); }
+        };
+
+        output.publish(Message(format!("Imported SVG into frame {}:{} ({} edits)", layer_id, frame_number, import_edits.len()))).await;
+        edits.extend(import_edits);
+
+        // Update the edit buffer
+        *state = state.set_edit_buffer(edits);
+
+        output.publish(FinishTask).await;
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
 mod raycast_to_svg;
+mod import_svg;
 
 pub use self::raycast_to_svg::*;
+pub use self::import_svg::*;
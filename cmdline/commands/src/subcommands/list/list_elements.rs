@@ -28,12 +28,17 @@ fn describe_vector(vec: &Vector) -> String {
         Motion(_motion)                 => { format!("Motion description") }
         Transformation(_transform)      => { format!("Transformation description") }
         AnimationRegion(_region)        => { format!("Animation region") }
+        Bone(bone)                      => { format!("Bone, {} bound elements", bone.bindings().len()) }
+        Tween(tween)                    => { format!("Tween to {:?}", tween.target()) }
         Error                           => { format!("Error :-(") }
 
         Group(group)                    => { 
             let group_type  = match group.group_type() {
-                GroupType::Normal => "Group",
-                GroupType::Added  => "Boolean addition"
+                GroupType::Normal       => "Group",
+                GroupType::Added        => "Boolean addition",
+                GroupType::Subtracted   => "Boolean subtraction",
+                GroupType::Masked       => "Mask",
+                GroupType::InvertedMask => "Inverted mask"
             };
             let elements    = group.elements().map(|elem| describe_vector(elem)).join(", ");
 
@@ -4,6 +4,7 @@ mod elements;
 mod read_from;
 mod dump_catalog;
 mod select_frame;
+mod render_frames;
 mod write_to_catalog;
 mod set_catalog_folder;
 
@@ -13,5 +14,6 @@ pub (super) use self::elements::*;
 pub (super) use self::read_from::*;
 pub (super) use self::dump_catalog::*;
 pub (super) use self::select_frame::*;
+pub (super) use self::render_frames::*;
 pub (super) use self::write_to_catalog::*;
 pub (super) use self::set_catalog_folder::*;
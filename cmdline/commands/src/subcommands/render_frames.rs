@@ -0,0 +1,113 @@
+use crate::state::*;
+use crate::error::*;
+use crate::output::*;
+
+use flo_stream::*;
+use flo_canvas::*;
+use flo_animation::*;
+use flo_canvas_raster::*;
+
+use futures::prelude::*;
+
+use std::ops::{Range};
+use std::time::{Duration};
+
+///
+/// Converts a layer blend mode to the blend mode used when compositing the layer on the CPU
+///
+fn raster_blend_mode(blend_mode: LayerBlendMode) -> RasterBlendMode {
+    match blend_mode {
+        LayerBlendMode::Normal      => RasterBlendMode::Normal,
+        LayerBlendMode::Multiply    => RasterBlendMode::Multiply,
+        LayerBlendMode::Screen      => RasterBlendMode::Screen,
+        LayerBlendMode::Overlay     => RasterBlendMode::Overlay,
+        LayerBlendMode::Add         => RasterBlendMode::Add,
+    }
+}
+
+///
+/// Generates the layers to composite for a frame of an animation
+///
+/// The layers are returned in the order returned by `get_layer_ids()`. Hidden layers (and layers in hidden folders)
+/// are skipped, in the same way as they are when exporting.
+///
+fn frame_layers(animation: &dyn Animation, when: Duration) -> Vec<RasterLayer> {
+    let (width, height) = animation.size();
+    let mut layers      = vec![];
+
+    for layer_id in animation.get_layer_ids() {
+        let layer = match animation.get_layer_with_id(layer_id) {
+            Some(layer) => layer,
+            None        => { continue; }
+        };
+
+        if !is_layer_visible(animation, &*layer) {
+            continue;
+        }
+
+        // The animation should fill the canvas
+        let mut drawing: Vec<Draw> = vec![];
+        drawing.canvas_height(height as f32);
+        drawing.center_region(0.0, 0.0, width as f32, height as f32);
+
+        // Render the keyframe's animation layer at the appropriate time
+        let frame               = layer.get_frame_at_time(when);
+        let (time, anim_layer)  = frame.to_animation_layer();
+
+        anim_layer.sync(|anim_layer| anim_layer.render_sync(time, &mut drawing));
+
+        layers.push(RasterLayer {
+            drawing:    drawing,
+            alpha:      layer.alpha(),
+            blend_mode: raster_blend_mode(layer.blend_mode())
+        });
+    }
+
+    layers
+}
+
+///
+/// Renders the frames of the input animation in a particular time range to PNG files, using the CPU
+///
+/// Frames are rendered at the specified size (or the size of the animation if no size is specified) and are written
+/// to files named `<file_prefix>00000.png`, `<file_prefix>00001.png`, etc, where the number is the frame number.
+///
+pub fn render_frames<'a>(output: &'a mut Publisher<FloCommandOutput>, state: &'a mut CommandState, time_range: Range<Duration>, size: Option<(usize, usize)>, file_prefix: String) -> impl 'a+Future<Output=Result<(), CommandError>>+Send {
+    async move {
+        use FloCommandOutput::*;
+
+        let animation           = state.input_animation();
+        let (width, height)     = animation.size();
+        let (width, height)     = size.unwrap_or((width.round() as usize, height.round() as usize));
+
+        // Work out which frames to render (rendering stops at the end of the animation)
+        let end_time            = time_range.end.min(animation.duration());
+        let frame_length        = animation.frame_length().as_nanos().max(1);
+        let first_frame         = (time_range.start.as_nanos() + frame_length - 1) / frame_length;
+        let end_frame           = (end_time.as_nanos() + frame_length - 1) / frame_length;
+        let (first_frame, end_frame) = (first_frame as usize, (end_frame as usize).max(first_frame as usize));
+
+        output.publish(StartTask("Render frames".to_string())).await;
+
+        for frame_num in first_frame..end_frame {
+            // Render this frame
+            let when        = animation.frame_length() * (frame_num as u32);
+            let layers      = frame_layers(&*animation, when);
+            let rgba        = composite_layers(width, height, Color::Rgba(1.0, 1.0, 1.0, 1.0), layers);
+
+            let png         = png_data_for_rgba(&rgba, width, height).map_err(|err| CommandError::CouldNotRender(err.to_string()))?;
+
+            // Write to a file
+            let filename    = format!("{}{:05}.png", file_prefix, frame_num);
+            output.publish(Message(format!("  Writing {}", filename))).await;
+            output.publish(BeginOutput(filename)).await;
+            output.publish(OutputBytes(png)).await;
+
+            output.publish(TaskProgress((frame_num + 1 - first_frame) as f64, (end_frame - first_frame) as f64)).await;
+        }
+
+        output.publish(FinishTask).await;
+
+        Ok(())
+    }
+}
@@ -47,6 +47,10 @@ where InputStream: Stream<Item=FloCommandOutput>+Send+Unpin {
                         pos                 += num_written;
                     }
                 }
+
+                OutputBytes(bytes)              => {
+                    output_stream.write_all(&bytes).await.unwrap();
+                }
             }
         }
     }
@@ -11,6 +11,7 @@ mod console;
 use self::console::*;
 
 use std::str::{FromStr};
+use std::time::{Duration};
 
 #[tokio::main]
 async fn main() {
@@ -80,6 +81,30 @@ async fn main() {
                 .help("The element ID in the selected frame to raycast")
                 .required(true)
                 .index(1)))
+        .subcommand(SubCommand::with_name("render-frames")
+            .about("Renders the frames of the input animation to PNG files using the CPU (no GPU is required)")
+            .arg(Arg::with_name("OUTPUT")
+                .help("The prefix for the PNG files to generate (the frame number and '.png' are appended to this)")
+                .required(false)
+                .index(1))
+            .arg(Arg::with_name("start")
+                .long("start")
+                .takes_value(true)
+                .help("The time in seconds of the first frame to render (default 0)"))
+            .arg(Arg::with_name("end")
+                .long("end")
+                .takes_value(true)
+                .help("The time in seconds where rendering should stop (default is the end of the animation)"))
+            .arg(Arg::with_name("size")
+                .long("size")
+                .takes_value(true)
+                .help("The size of the images to generate, as <width>x<height> (eg: --size 1920x1080; default is the size of the animation)")))
+        .subcommand(SubCommand::with_name("import-svg")
+            .about("Imports an SVG file into the output animation, at the layer and frame specified by the --frame parameter (or 0:0 if none is specified)")
+            .arg(Arg::with_name("INPUT")
+                .help("The SVG file to import")
+                .required(true)
+                .index(1)))
         .get_matches();
 
     tokio::spawn(async move {
@@ -208,6 +233,72 @@ async fn main() {
             // Add a raycast command
             input.push(FloCommand::RayCastToSvg(element_id));
         }
+
+        // Render frames command
+        if let Some(render_frames) = params.subcommand_matches("render-frames") {
+            // Parse the time range
+            let start   = render_frames.value_of("start").map(|start| f64::from_str(start).ok().map(|start| Duration::from_secs_f64(start)));
+            let end     = render_frames.value_of("end").map(|end| f64::from_str(end).ok().map(|end| Duration::from_secs_f64(end)));
+
+            let start   = match start {
+                None                => Duration::from_millis(0),
+                Some(Some(start))   => start,
+                Some(None)          => {
+                    stderr().write(format!("'{}' is not a valid start time\n\n", render_frames.value_of("start").unwrap_or("-")).as_bytes()).await.unwrap();
+                    return;
+                }
+            };
+            let end     = match end {
+                None                => Duration::from_secs(u32::MAX as u64),
+                Some(Some(end))     => end,
+                Some(None)          => {
+                    stderr().write(format!("'{}' is not a valid end time\n\n", render_frames.value_of("end").unwrap_or("-")).as_bytes()).await.unwrap();
+                    return;
+                }
+            };
+
+            // Parse the size
+            let size    = render_frames.value_of("size").map(|size| {
+                let sep_pos = size.find('x')?;
+                Some((usize::from_str(&size[0..sep_pos]).ok()?, usize::from_str(&size[sep_pos+1..size.len()]).ok()?))
+            });
+            let size    = match size {
+                None                => None,
+                Some(Some(size))    => Some(size),
+                Some(None)          => {
+                    stderr().write(format!("'{}' is not a valid size. The size must be of the format <width>x<height> (eg: 1920x1080)\n\n", render_frames.value_of("size").unwrap_or("-")).as_bytes()).await.unwrap();
+                    return;
+                }
+            };
+
+            let prefix  = render_frames.value_of("OUTPUT").unwrap_or("frame").to_string();
+
+            input.push(FloCommand::RenderFrames(start..end, size, prefix));
+        }
+
+        // Import SVG command
+        if let Some(import_svg) = params.subcommand_matches("import-svg") {
+            // Read the SVG file
+            let input_file  = import_svg.value_of("INPUT").unwrap();
+            let svg         = match fs::read_to_string(input_file).await {
+                Ok(svg)     => svg,
+                Err(err)    => {
+                    stderr().write(format!("Could not read '{}': {}\n\n", input_file, err).as_bytes()).await.unwrap();
+                    return;
+                }
+            };
+
+            // The layer and frame are the same as the ones selected by the --frame parameter (which has been validated above)
+            let (layer_num, frame_num) = params.value_of("frame")
+                .and_then(|frame| {
+                    let sep_pos = frame.find(':')?;
+                    Some((u64::from_str(&frame[0..sep_pos]).ok()?, usize::from_str(&frame[sep_pos+1..frame.len()]).ok()?))
+                })
+                .unwrap_or((0, 0));
+
+            input.push(FloCommand::ImportSvg(svg, layer_num, frame_num));
+            input.push(FloCommand::WriteAllEdits);
+        }
         
         // Prepare as a stream as input to the command line
         let input       = stream::iter(input);
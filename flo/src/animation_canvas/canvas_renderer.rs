@@ -6,6 +6,7 @@ use flo_ui::*;
 use flo_canvas::*;
 use flo_binding::*;
 use flo_animation::*;
+use flo_canvas_raster::*;
 
 use std::sync::*;
 use std::collections::{HashMap};
@@ -28,6 +29,18 @@ struct FrameLayer {
     /// The alpha used for rendering this layer last time through
     render_alpha:       f64,
 
+    /// True if this layer is hidden (or is in a hidden folder)
+    hidden:             BindRef<bool>,
+
+    /// True if the layer was hidden when it was last rendered
+    render_hidden:      bool,
+
+    /// How this layer is blended with the layers behind it
+    blend_mode:         BindRef<LayerBlendMode>,
+
+    /// The blend mode used for rendering this layer last time through
+    render_blend_mode:  LayerBlendMode,
+
     /// The brush that was last used for this layer
     active_brush:       Option<(BrushDefinition, BrushDrawingStyle)>,
 
@@ -60,7 +73,10 @@ pub struct CanvasRenderer {
     overlay_layers: HashMap<OverlayLayerId, OverlayLayer>,
 
     /// The layer that we're currently 'annotating'
-    annotated_layer: Option<u64>
+    annotated_layer: Option<u64>,
+
+    /// The size of the animation, as of the last time the frame layers were drawn
+    size: (f64, f64)
 }
 
 impl OverlayLayer {
@@ -84,7 +100,8 @@ impl CanvasRenderer {
         CanvasRenderer {
             frame_layers:       HashMap::new(),
             overlay_layers:     HashMap::new(),
-            annotated_layer:    None
+            annotated_layer:    None,
+            size:               (0.0, 0.0)
         }
     }
 
@@ -201,7 +218,7 @@ impl CanvasRenderer {
     ///
     /// Loads a particular frame from a layer into this renderer
     ///
-    pub fn load_frame(&mut self, model: &FrameLayerModel, layer_model: &LayerModel) {
+    pub fn load_frame(&mut self, model: &FrameLayerModel, layer_model: &LayerModel, hidden: BindRef<bool>) {
         // Load the frame data (we don't necessarily form a binding here)
         let frame = model.frame.get();
 
@@ -213,6 +230,8 @@ impl CanvasRenderer {
             if let Some(existing_layer) = existing_layer {
                 // Update the model of the existing layer
                 existing_layer.alpha                = BindRef::from(&layer_model.alpha);
+                existing_layer.hidden               = hidden;
+                existing_layer.blend_mode           = BindRef::from(&layer_model.blend_mode);
                 existing_layer.active_brush         = None;
                 existing_layer.active_properties    = None;
                 existing_layer.layer_frame          = frame;
@@ -230,6 +249,10 @@ impl CanvasRenderer {
                     layer_frame:        layer_frame,
                     alpha:              BindRef::from(&layer_model.alpha),
                     render_alpha:       1.0,
+                    hidden:             hidden,
+                    render_hidden:      false,
+                    blend_mode:         BindRef::from(&layer_model.blend_mode),
+                    render_blend_mode:  LayerBlendMode::Normal,
                     active_brush:       None,
                     active_properties:  None,
                 });
@@ -284,6 +307,8 @@ impl CanvasRenderer {
     /// Draws the current set of frame layers to the specified canvas
     ///
     pub fn draw_frame_layers(&mut self, canvas: &BindingCanvas, size: (f64, f64)) {
+        self.size = size;
+
         // Clear the canvas and redraw the background
         self.clear_canvas(canvas, size);
         canvas.draw(|gc| self.draw_background(gc, size));
@@ -292,14 +317,13 @@ impl CanvasRenderer {
         canvas.draw(move |gc| {
             // Draw the layers
             for layer in self.frame_layers.values_mut() {
-                let alpha           = layer.alpha.get();
-                layer.render_alpha  = alpha;
-
                 gc.layer(layer.layer_id);
                 gc.clear_layer();
-                gc.layer_alpha(layer.layer_id, alpha);
-                layer.layer_frame.render_to(gc);
+                layer.render(gc);
             }
+
+            // Draw any layers that need to be blended offscreen
+            self.draw_offscreen_blended_layers(gc);
         });
     }
 
@@ -312,21 +336,82 @@ impl CanvasRenderer {
         let layer       = if let Some(layer) = layer { layer } else { return; };
 
         canvas.draw(|gc| {
-            let alpha           = layer.alpha.get();
-            layer.render_alpha  = alpha;
-
             gc.push_state();
 
             gc.layer(layer.layer_id);
             gc.clear_layer();
-            gc.layer_alpha(layer.layer_id, alpha);
-
-            layer.layer_frame.render_to(gc);
+            layer.render(gc);
 
             gc.layer(LayerId(0));
 
             gc.pop_state();
-        })
+        });
+
+        // The layers blended offscreen might include this layer
+        canvas.draw(|gc| self.draw_offscreen_blended_layers(gc));
+    }
+
+    ///
+    /// Redraws the layers that use blend modes that the canvas doesn't support
+    ///
+    /// These layers are composited with the layers behind them on the CPU, and the result is drawn as a texture covering the
+    /// animation (replacing the layers behind it). As this needs the layers behind to be rendered too, this needs to be called
+    /// again whenever any of the layers behind a layer blended this way is changed.
+    ///
+    fn draw_offscreen_blended_layers(&self, gc: &mut dyn GraphicsContext) {
+        // Order the layers from back to front
+        let mut layers = self.frame_layers.values().collect::<Vec<_>>();
+        layers.sort_by_key(|layer| { let LayerId(layer_id) = layer.layer_id; layer_id });
+
+        // Only the layers up to the topmost layer that's blended offscreen need to be rendered
+        let num_layers = layers.iter()
+            .rposition(|layer| !layer.render_hidden && layer.render_blend_mode.canvas_blend_mode().is_none())
+            .map(|pos| pos+1)
+            .unwrap_or(0);
+        if num_layers == 0 { return; }
+
+        // Composite the layers at the animation's size, on the same white background as the canvas
+        let (width, height)             = self.size;
+        let (pixel_width, pixel_height) = (width.round().max(1.0) as usize, height.round().max(1.0) as usize);
+        let mut rgba                    = composite_layers(pixel_width, pixel_height, Color::Rgba(1.0, 1.0, 1.0, 1.0), vec![]);
+
+        gc.push_state();
+
+        for layer in layers.into_iter().take(num_layers) {
+            if layer.render_hidden { continue; }
+
+            // Render the layer offscreen and blend it with the layers behind
+            let mut drawing: Vec<Draw> = vec![];
+            drawing.canvas_height(height as f32);
+            drawing.center_region(0.0, 0.0, width as f32, height as f32);
+            layer.layer_frame.render_to(&mut drawing);
+
+            let mut layer_canvas = RasterCanvas::new(pixel_width, pixel_height);
+            layer_canvas.draw(drawing);
+            blend_rgba(&mut rgba, &layer_canvas.to_rgba(), layer.render_alpha, raster_blend_mode(layer.render_blend_mode));
+
+            // Layers that the canvas can't blend are replaced by the result so far
+            if layer.render_blend_mode.canvas_blend_mode().is_none() {
+                let LayerId(canvas_layer_id)    = layer.layer_id;
+                let texture_id                  = TextureId(canvas_layer_id);
+
+                gc.layer(layer.layer_id);
+                gc.clear_layer();
+                gc.layer_alpha(layer.layer_id, 1.0);
+                gc.layer_blend(layer.layer_id, BlendMode::SourceOver);
+
+                gc.create_texture(texture_id, pixel_width as _, pixel_height as _, TextureFormat::Rgba);
+                gc.set_texture_bytes(texture_id, 0, 0, pixel_width as _, pixel_height as _, Arc::new(rgba.clone()));
+
+                gc.new_path();
+                gc.rect(0.0, 0.0, width as f32, height as f32);
+                gc.fill_texture(texture_id, 0.0, 0.0, width as f32, height as f32);
+                gc.fill();
+            }
+        }
+
+        gc.layer(LayerId(0));
+        gc.pop_state();
     }
 
     ///
@@ -510,16 +595,110 @@ impl CanvasRenderer {
     }
 
     ///
-    /// Updates the layer alphas to the latest versions, if they're different from what's set
+    /// Updates the layer alphas, blend modes and visibility to the latest versions, if they're different from what's set
     ///
-    pub fn update_layer_alphas(&mut self, canvas: &BindingCanvas) {
+    pub fn update_layer_appearance(&mut self, canvas: &BindingCanvas) {
+        let mut changed = false;
+
         for layer in self.frame_layers.values_mut() {
-            let new_alpha = layer.alpha.get();
+            let new_alpha       = layer.alpha.get();
+            let new_hidden      = layer.hidden.get();
+            let new_blend_mode  = layer.blend_mode.get();
+
+            if new_hidden != layer.render_hidden {
+                // Showing or hiding a layer means it needs to be redrawn
+                changed = true;
+                canvas.draw(|gc| {
+                    gc.push_state();
+
+                    gc.layer(layer.layer_id);
+                    gc.clear_layer();
+                    layer.render(gc);
 
-            if new_alpha != layer.render_alpha {
-                layer.render_alpha = new_alpha;
-                canvas.draw(|gc| gc.layer_alpha(layer.layer_id, new_alpha));
+                    gc.layer(LayerId(0));
+
+                    gc.pop_state();
+                });
+            } else {
+                // Layers that are blended offscreen have the alpha applied when they're composited
+                let offscreen = layer.render_blend_mode.canvas_blend_mode().is_none();
+
+                if new_alpha != layer.render_alpha {
+                    changed             = true;
+                    layer.render_alpha  = new_alpha;
+
+                    if !offscreen {
+                        canvas.draw(|gc| gc.layer_alpha(layer.layer_id, new_alpha));
+                    }
+                }
+
+                if new_blend_mode != layer.render_blend_mode {
+                    changed = true;
+
+                    match (offscreen, new_blend_mode.canvas_blend_mode()) {
+                        (false, Some(canvas_blend_mode)) => {
+                            layer.render_blend_mode = new_blend_mode;
+                            canvas.draw(|gc| gc.layer_blend(layer.layer_id, canvas_blend_mode));
+                        }
+
+                        _ => {
+                            // Switching to or from a blend mode that's applied offscreen means the layer needs to be redrawn
+                            canvas.draw(|gc| {
+                                gc.push_state();
+
+                                gc.layer(layer.layer_id);
+                                gc.clear_layer();
+                                layer.render(gc);
+
+                                gc.layer(LayerId(0));
+
+                                gc.pop_state();
+                            });
+                        }
+                    }
+                }
             }
         }
+
+        // Any changes might affect the layers that are blended offscreen
+        if changed {
+            canvas.draw(|gc| self.draw_offscreen_blended_layers(gc));
+        }
+    }
+}
+
+impl FrameLayer {
+    ///
+    /// Renders this layer to the currently selected canvas layer (which should be cleared beforehand)
+    ///
+    fn render(&mut self, gc: &mut dyn GraphicsContext) {
+        let alpha               = self.alpha.get();
+        let hidden              = self.hidden.get();
+        let blend_mode          = self.blend_mode.get();
+
+        self.render_alpha       = alpha;
+        self.render_hidden      = hidden;
+        self.render_blend_mode  = blend_mode;
+
+        gc.layer_alpha(self.layer_id, alpha);
+        gc.layer_blend(self.layer_id, blend_mode.canvas_blend_mode().unwrap_or(BlendMode::SourceOver));
+
+        // Hidden layers are left cleared, and layers that the canvas can't blend are drawn by `draw_offscreen_blended_layers()`
+        if !hidden && blend_mode.canvas_blend_mode().is_some() {
+            self.layer_frame.render_to(gc);
+        }
+    }
+}
+
+///
+/// Converts a layer blend mode to the blend mode used when compositing the layer on the CPU
+///
+fn raster_blend_mode(blend_mode: LayerBlendMode) -> RasterBlendMode {
+    match blend_mode {
+        LayerBlendMode::Normal      => RasterBlendMode::Normal,
+        LayerBlendMode::Multiply    => RasterBlendMode::Multiply,
+        LayerBlendMode::Screen      => RasterBlendMode::Screen,
+        LayerBlendMode::Overlay     => RasterBlendMode::Overlay,
+        LayerBlendMode::Add         => RasterBlendMode::Add,
     }
 }
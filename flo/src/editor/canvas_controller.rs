@@ -343,7 +343,7 @@ impl<Anim: 'static+Animation+EditableAnimation> CanvasCore<Anim> {
             let timeline_layer = timeline_layers.iter().filter(|layer| layer.id == layer_frame.layer_id).nth(0);
             let timeline_layer = if let Some(timeline_layer) = timeline_layer { timeline_layer } else { continue; };
 
-            self.renderer.load_frame(&layer_frame, timeline_layer);
+            self.renderer.load_frame(&layer_frame, timeline_layer, layer_hidden_binding(self.model.timeline().layers.clone(), layer_frame.layer_id));
         }
     }
 
@@ -421,15 +421,15 @@ impl<Anim: 'static+Animation+EditableAnimation> CanvasCore<Anim> {
                 let timeline_layer  = timeline_layers.iter().filter(|layer| layer.id == layer_id).nth(0);
                 let timeline_layer  = if let Some(timeline_layer) = timeline_layer { timeline_layer } else { continue; };
 
-                self.renderer.load_frame(&invalid_layer, timeline_layer);
+                self.renderer.load_frame(&invalid_layer, timeline_layer, layer_hidden_binding(self.model.timeline().layers.clone(), layer_id));
                 self.renderer.redraw_layer(layer_id, &*canvas);
             }
 
-            // Update any out of date layer alphas
-            self.renderer.update_layer_alphas(&*canvas);
+            // Update any out of date layer alphas and blend modes
+            self.renderer.update_layer_appearance(&*canvas);
         } else {
-            // Just update the alphas            
-            self.renderer.update_layer_alphas(&*canvas);
+            // Just update the alphas and blend modes
+            self.renderer.update_layer_appearance(&*canvas);
         }
     }
 
@@ -488,6 +488,7 @@ impl<Anim: 'static+Animation+EditableAnimation> CanvasCore<Anim> {
         for edit in edits.iter() {
             match edit {
                 AnimationEdit::Motion(_element, _motion)        => { /* Motions are deprecated */ }
+                AnimationEdit::Bone(_element, _bone)            => { self.model.timeline().invalidate_canvas(); }
                 AnimationEdit::SetSize(_w, _h)                  => { self.model.timeline().invalidate_canvas(); }
                 AnimationEdit::SetFrameLength(_length)          => { self.model.timeline().invalidate_canvas(); }
                 AnimationEdit::SetLength(_length)               => { }
@@ -505,9 +506,18 @@ impl<Anim: 'static+Animation+EditableAnimation> CanvasCore<Anim> {
                         LayerEdit::Cut { path: _, when: _, inside_group: _ }    => { true }
                         LayerEdit::AddKeyFrame(_)                               => { true }
                         LayerEdit::RemoveKeyFrame(_)                            => { true },
+                        LayerEdit::MoveKeyFrame(_, _)                           => { true },
+                        LayerEdit::DuplicateKeyFrame(_, _, _)                   => { true },
+                        LayerEdit::ShiftKeyFrames(_, _)                         => { true },
                         LayerEdit::SetName(_)                                   => { false },
                         LayerEdit::SetOrdering(_)                               => { self.model.timeline().invalidate_canvas(); false /* ... but whole canvas update */ },
                         LayerEdit::SetAlpha(_)                                  => { true },
+                        LayerEdit::SetBlendMode(_)                              => { true },
+                        LayerEdit::SetLocked(_)                                 => { false },
+                        LayerEdit::SetCollapsed(_)                              => { false },
+                        LayerEdit::SetIsFolder(_)                               |
+                        LayerEdit::SetParentFolder(_)                           |
+                        LayerEdit::SetHidden(_)                                 => { self.model.timeline().invalidate_canvas(); false /* Folders can hide the layers they contain, so update the whole canvas */ },
                     };
 
                     // Force the layer to update if necessary
@@ -535,6 +545,8 @@ impl<Anim: 'static+Animation+EditableAnimation> CanvasCore<Anim> {
                         ElementEdit::SetAnimationBaseType(_)        => true,
                         ElementEdit::AddAnimationEffect(_)          => true,
                         ElementEdit::ReplaceAnimationEffect(_, _)   => true,
+                        ElementEdit::CopyTo { .. }                  => true,
+                        ElementEdit::MoveTo { .. }                  => true,
                     };
 
                     // Update all of the layers if needed
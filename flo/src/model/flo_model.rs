@@ -145,6 +145,7 @@ impl<Anim: EditableAnimation+Animation+'static> FloModel<Anim> {
                 RemoveLayer(_)                                      |
                 Element(_, _)                                       |
                 Motion(_, _)                                        |
+                Bone(_, _)                                          |
                 Layer(_, Path(_, _))                                |
                 Layer(_, CreateElement(_, _, _))                    |
                 Layer(_, CreateElementUnattachedToFrame(_, _, _))   |
@@ -156,8 +157,11 @@ impl<Anim: EditableAnimation+Animation+'static> FloModel<Anim> {
                     advance_edit_counter = true;
                 },
 
-                Layer(_, AddKeyFrame(_))    |
-                Layer(_, RemoveKeyFrame(_)) => {
+                Layer(_, AddKeyFrame(_))                |
+                Layer(_, RemoveKeyFrame(_))             |
+                Layer(_, MoveKeyFrame(_, _))            |
+                Layer(_, DuplicateKeyFrame(_, _, _))    |
+                Layer(_, ShiftKeyFrames(_, _))          => {
                     advance_edit_counter = true;
                 },
 
@@ -181,6 +185,48 @@ impl<Anim: EditableAnimation+Animation+'static> FloModel<Anim> {
                     advance_edit_counter = true;
                 },
 
+                Layer(layer_id, SetHidden(hidden)) => {
+                    timeline.layers.get()
+                        .into_iter()
+                        .for_each(|layer| if &layer.id == layer_id { layer.hidden.set(*hidden); });
+
+                    advance_edit_counter = true;
+                },
+
+                Layer(layer_id, SetLocked(locked)) => {
+                    timeline.layers.get()
+                        .into_iter()
+                        .for_each(|layer| if &layer.id == layer_id { layer.locked.set(*locked); });
+                },
+
+                Layer(layer_id, SetBlendMode(blend_mode)) => {
+                    timeline.layers.get()
+                        .into_iter()
+                        .for_each(|layer| if &layer.id == layer_id { layer.blend_mode.set(*blend_mode); });
+
+                    advance_edit_counter = true;
+                },
+
+                Layer(layer_id, SetIsFolder(is_folder)) => {
+                    timeline.layers.get()
+                        .into_iter()
+                        .for_each(|layer| if &layer.id == layer_id { layer.is_folder.set(*is_folder); });
+                },
+
+                Layer(layer_id, SetCollapsed(collapsed)) => {
+                    timeline.layers.get()
+                        .into_iter()
+                        .for_each(|layer| if &layer.id == layer_id { layer.collapsed.set(*collapsed); });
+                },
+
+                Layer(layer_id, SetParentFolder(parent_folder)) => {
+                    timeline.layers.get()
+                        .into_iter()
+                        .for_each(|layer| if &layer.id == layer_id { layer.parent_folder.set(*parent_folder); });
+
+                    advance_edit_counter = true;
+                },
+
                 Layer(layer_id, SetOrdering(at_index)) => {
                     unimplemented!("Cannot update model with layer ordering yet")
                 },
@@ -285,6 +331,27 @@ impl<Anim: 'static+EditableAnimation> FloModel<UndoableAnimation<Anim>> {
         self.animation.redo().await
     }
 
+    ///
+    /// Moves the animation to a node in the undo history (or to the start of the history if the node is `None`)
+    ///
+    pub async fn jump_to(&self, node: Option<u64>) -> Result<(), UndoFailureReason> {
+        self.animation.jump_to(node).await
+    }
+
+    ///
+    /// Moves the animation to the most recent node in the undo history with the specified checkpoint name
+    ///
+    pub async fn jump_to_checkpoint(&self, checkpoint: &str) -> Result<(), UndoFailureReason> {
+        self.animation.jump_to_checkpoint(checkpoint).await
+    }
+
+    ///
+    /// Retrieves the undo history for this animation
+    ///
+    pub fn undo_history(&self) -> UndoHistory {
+        self.animation.history()
+    }
+
     ///
     /// Retrieves a stream that tracks the size of the undo log (this is an expiring stream, so backpressure will cause
     /// updates to be discarded: ie, reads will always return the lastest value)
@@ -389,6 +456,13 @@ impl<Anim: 'static+Animation+EditableAnimation> EditableAnimation for FloModel<A
         self.animation.retired_edits()
     }
 
+    ///
+    /// Reads the undo history that has been stored for this animation
+    ///
+    fn read_undo_history(&self) -> UndoHistory {
+        self.animation.read_undo_history()
+    }
+
     ///
     /// Assigns a new unique ID for creating a new motion
     ///
@@ -196,7 +196,10 @@ impl FrameModel {
     fn is_key_frame_update(layer_id: u64, edit: &AnimationEdit) -> bool {
         match edit {
             AnimationEdit::Layer(edit_layer_id, LayerEdit::AddKeyFrame(_)) |
-            AnimationEdit::Layer(edit_layer_id, LayerEdit::RemoveKeyFrame(_)) => edit_layer_id == &layer_id,
+            AnimationEdit::Layer(edit_layer_id, LayerEdit::RemoveKeyFrame(_)) |
+            AnimationEdit::Layer(edit_layer_id, LayerEdit::MoveKeyFrame(_, _)) |
+            AnimationEdit::Layer(edit_layer_id, LayerEdit::DuplicateKeyFrame(_, _, _)) |
+            AnimationEdit::Layer(edit_layer_id, LayerEdit::ShiftKeyFrames(_, _)) => edit_layer_id == &layer_id,
             _ => false
         }
     }
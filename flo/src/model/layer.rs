@@ -14,6 +14,24 @@ pub struct LayerModel {
 
     /// The alpha value for this layer
     pub alpha: Binding<f64>,
+
+    /// True if this layer is hidden
+    pub hidden: Binding<bool>,
+
+    /// True if this layer is locked against editing
+    pub locked: Binding<bool>,
+
+    /// How this layer is combined with the layers behind it
+    pub blend_mode: Binding<LayerBlendMode>,
+
+    /// True if this layer is a folder that can contain other layers
+    pub is_folder: Binding<bool>,
+
+    /// True if this layer is a folder that is collapsed in the timeline
+    pub collapsed: Binding<bool>,
+
+    /// The folder layer that contains this layer
+    pub parent_folder: Binding<Option<u64>>,
 }
 
 impl PartialEq for LayerModel {
@@ -25,9 +43,71 @@ impl PartialEq for LayerModel {
 impl LayerModel {
     pub fn new<'a>(layer: &'a dyn Layer) -> LayerModel {
         LayerModel {
-            id:     layer.id(),
-            name:   bind(layer.name().unwrap_or_else(|| format!("Layer {}", layer.id()))),
-            alpha:  bind(layer.alpha()),
+            id:             layer.id(),
+            name:           bind(layer.name().unwrap_or_else(|| format!("Layer {}", layer.id()))),
+            alpha:          bind(layer.alpha()),
+            hidden:         bind(layer.is_hidden()),
+            locked:         bind(layer.is_locked()),
+            blend_mode:     bind(layer.blend_mode()),
+            is_folder:      bind(layer.is_folder()),
+            collapsed:      bind(layer.is_collapsed()),
+            parent_folder:  bind(layer.parent_folder()),
         }
     }
 }
+
+///
+/// True if a test is true for a layer or any of the folders that contain it
+///
+fn layer_or_folder_matches(layers: &[LayerModel], layer_id: u64, test: impl Fn(&LayerModel) -> bool) -> bool {
+    let mut next_layer_id   = Some(layer_id);
+    let mut depth           = 0;
+
+    // The depth check guards against folders that contain themselves
+    while let (Some(layer_id), true) = (next_layer_id, depth <= layers.len()) {
+        let layer = if let Some(layer) = layers.iter().find(|layer| layer.id == layer_id) { layer } else { break; };
+
+        if test(layer) {
+            return true;
+        }
+
+        next_layer_id   = layer.parent_folder.get();
+        depth           += 1;
+    }
+
+    false
+}
+
+///
+/// True if the layer with the specified ID is hidden, or is inside a hidden folder
+///
+pub fn layer_is_hidden(layers: &[LayerModel], layer_id: u64) -> bool {
+    layer_or_folder_matches(layers, layer_id, |layer| layer.hidden.get())
+}
+
+///
+/// True if the layer with the specified ID can be edited by tools (it's not hidden or locked, and is not in a hidden or locked folder)
+///
+pub fn layer_is_editable(layers: &[LayerModel], layer_id: u64) -> bool {
+    !layer_or_folder_matches(layers, layer_id, |layer| layer.hidden.get() || layer.locked.get())
+}
+
+///
+/// True if the layer with the specified ID is inside a folder that's collapsed in the timeline
+///
+pub fn layer_is_in_collapsed_folder(layers: &[LayerModel], layer_id: u64) -> bool {
+    let parent_folder = layers.iter().find(|layer| layer.id == layer_id).and_then(|layer| layer.parent_folder.get());
+
+    if let Some(parent_folder) = parent_folder {
+        layer_or_folder_matches(layers, parent_folder, |folder| folder.collapsed.get())
+    } else {
+        false
+    }
+}
+
+///
+/// Creates a binding that's true when a layer is hidden, or is inside a hidden folder
+///
+pub fn layer_hidden_binding(layers: BindRef<Vec<LayerModel>>, layer_id: u64) -> BindRef<bool> {
+    BindRef::from(computed(move || layer_is_hidden(&layers.get(), layer_id)))
+}
@@ -113,6 +113,10 @@ fn default_effects() -> Vec<AvailableEffect> {
     vec![
         AvailableEffect::new("Repeat",      smallvec![ElementEdit::AddAnimationEffect(SubEffectType::Repeat)]),
         AvailableEffect::new("Time Curve",  smallvec![ElementEdit::AddAnimationEffect(SubEffectType::TimeCurve)]),
+        AvailableEffect::new("Rotate",      smallvec![ElementEdit::AddAnimationEffect(SubEffectType::Rotation)]),
+        AvailableEffect::new("Scale",       smallvec![ElementEdit::AddAnimationEffect(SubEffectType::Scaling)]),
+        AvailableEffect::new("Fade",        smallvec![ElementEdit::AddAnimationEffect(SubEffectType::Opacity)]),
+        AvailableEffect::new("Tint",        smallvec![ElementEdit::AddAnimationEffect(SubEffectType::Tint)]),
     ]
 }
 
@@ -54,7 +54,14 @@ pub fn animation_selection_panels<Anim: 'static+EditableAnimation>(model: &Arc<F
                     SubEffectType::Repeat               => { panels.push(anim_repeat_panel.clone()); }
                     SubEffectType::TimeCurve            => { }
                     SubEffectType::LinearPosition       => { }
+                    SubEffectType::FollowPath           => { }
                     SubEffectType::TransformPosition    => { }
+                    SubEffectType::Rotation             => { }
+                    SubEffectType::Scaling              => { }
+                    SubEffectType::Opacity              => { }
+                    SubEffectType::Tint                 => { }
+                    SubEffectType::Skeleton             => { }
+                    SubEffectType::ShapeTween           => { }
                 }
             }
         }
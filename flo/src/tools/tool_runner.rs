@@ -34,7 +34,7 @@ pub struct ToolRunner<Anim: Animation> {
     model_actions: Option<BoxStream<'static, ToolAction<GenericToolData>>>
 }
 
-impl<Anim: Animation> ToolRunner<Anim> {
+impl<Anim: Animation+'static> ToolRunner<Anim> {
     ///
     /// Creates a new tool runner
     ///
@@ -157,8 +157,48 @@ impl<Anim: Animation> ToolRunner<Anim> {
             self.set_tool_data(new_tool_data);
         }
 
-        // The 'after processing' vec forms the result
-        Box::new(after_processing_data.into_iter())
+        // The 'after processing' vec forms the result (tools can't edit hidden or locked layers)
+        let actions = self.remove_locked_layer_actions(after_processing_data);
+        Box::new(actions.into_iter())
+    }
+
+    ///
+    /// Removes any actions that would edit a layer that's hidden or locked
+    ///
+    /// Element edits and brush previews apply to the selected layer, so these are removed if the selected layer can't be edited
+    ///
+    fn remove_locked_layer_actions(&self, actions: Vec<ToolAction<GenericToolData>>) -> Vec<ToolAction<GenericToolData>> {
+        let layers          = self.view_model.timeline().layers.get();
+        let selected_layer  = self.view_model.timeline().selected_layer.get();
+        let selected_locked = selected_layer.map(|layer_id| !layer_is_editable(&layers, layer_id)).unwrap_or(false);
+
+        let can_edit        = |edit: &AnimationEdit| {
+            match edit {
+                AnimationEdit::Layer(layer_id, _)   => layer_is_editable(&layers, *layer_id),
+                AnimationEdit::Element(_, _)        => !selected_locked,
+                _                                   => true
+            }
+        };
+
+        actions.into_iter()
+            .flat_map(|action| {
+                match action {
+                    ToolAction::Edit(edit)                                  => if can_edit(&edit) { Some(ToolAction::Edit(edit)) } else { None },
+                    ToolAction::EditAnimation(edits)                        => {
+                        if edits.iter().all(|edit| can_edit(edit)) {
+                            Some(ToolAction::EditAnimation(edits))
+                        } else {
+                            Some(ToolAction::EditAnimation(Arc::new(edits.iter().filter(|edit| can_edit(edit)).cloned().collect())))
+                        }
+                    }
+
+                    ToolAction::CreateKeyFrameForDrawing if selected_locked => None,
+                    ToolAction::BrushPreview(_) if selected_locked          => None,
+
+                    other                                                   => Some(other)
+                }
+            })
+            .collect()
     }
 }
 
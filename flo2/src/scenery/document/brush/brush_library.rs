@@ -0,0 +1,164 @@
+use super::brush_pack::*;
+use crate::scenery::document::canvas::*;
+
+use flo_scene::*;
+use flo_scene::commands::*;
+use flo_scene::programs::*;
+
+use futures::prelude::*;
+use ::serde::*;
+
+use std::fs;
+use std::path::*;
+
+///
+/// Requests for the brush library program
+///
+/// The brush library is a set of brushes that is kept separately from any document (for example, a personal or studio-wide set of
+/// brushes). It's filled in by loading brush packs, and brushes can be imported from it into the document or exported to it from the
+/// document.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BrushLibrary {
+    /// Adds the brushes from a brush pack (in the format written by `BrushPack::to_bytes()`) to the library
+    AddPack(Vec<u8>),
+
+    /// Reads a brush pack file and adds its brushes to the library
+    LoadPackFile(PathBuf),
+
+    /// Writes the brushes with the specified names to a brush pack file
+    SavePackFile(PathBuf, Vec<String>),
+
+    /// Sends the brushes with the specified names as a brush pack to a target as a `BrushPackData` message
+    GetPack(StreamTarget, Vec<String>),
+
+    /// Sends the entries in the library to a target as a `BrushLibraryEntries` message
+    List(StreamTarget),
+
+    /// Creates new brushes in the document from the library brushes with the specified names, then sends the new brush IDs to a target as an `ImportedBrushes` message
+    ImportToDocument(StreamTarget, Vec<String>),
+
+    /// Adds brushes from the document to the library (brushes with no core brush settings are ignored)
+    ExportFromDocument(Vec<CanvasBrushId>),
+}
+
+///
+/// The entries in the brush library, sent in response to `BrushLibrary::List`
+///
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BrushLibraryEntries(pub Vec<BrushPackEntry>);
+
+///
+/// The names and IDs of the brushes created in the document by `BrushLibrary::ImportToDocument`
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportedBrushes(pub Vec<(String, CanvasBrushId)>);
+
+///
+/// A brush pack, sent in response to `BrushLibrary::GetPack`
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BrushPackData(pub Vec<u8>);
+
+impl SceneMessage for BrushLibrary {
+    fn default_target() -> StreamTarget {
+        SubProgramId::called("flowbetween::brush_library").into()
+    }
+
+    fn initialise(init_context: &impl SceneInitialisationContext) {
+        init_context.add_subprogram(SubProgramId::called("flowbetween::brush_library"), brush_library_program, 20);
+        init_context.connect_programs((), SubProgramId::called("flowbetween::brush_library"), StreamId::with_message_type::<BrushLibrary>()).unwrap();
+    }
+}
+
+impl SceneMessage for BrushLibraryEntries {
+
+}
+
+impl SceneMessage for ImportedBrushes {
+
+}
+
+impl SceneMessage for BrushPackData {
+
+}
+
+///
+/// Runs the brush library program
+///
+pub async fn brush_library_program(input: InputStream<BrushLibrary>, context: SceneContext) {
+    let mut library = BrushPack::default();
+
+    let mut input = input;
+    while let Some(msg) = input.next().await {
+        match msg {
+            BrushLibrary::AddPack(bytes) => {
+                if let Ok(pack) = BrushPack::from_bytes(&bytes) {
+                    pack.brushes.into_iter().for_each(|entry| library.add_brush(entry));
+                }
+            }
+
+            BrushLibrary::LoadPackFile(path) => {
+                let pack = fs::read(&path).ok().and_then(|bytes| BrushPack::from_bytes(&bytes).ok());
+
+                if let Some(pack) = pack {
+                    pack.brushes.into_iter().for_each(|entry| library.add_brush(entry));
+                }
+            }
+
+            BrushLibrary::SavePackFile(path, names) => {
+                fs::write(&path, library.with_names(&names).to_bytes()).ok();
+            }
+
+            BrushLibrary::GetPack(target, names) => {
+                if let Ok(mut target) = context.send(target) {
+                    target.send(BrushPackData(library.with_names(&names).to_bytes())).await.ok();
+                }
+            }
+
+            BrushLibrary::List(target) => {
+                if let Ok(mut target) = context.send(target) {
+                    target.send(BrushLibraryEntries(library.brushes.clone())).await.ok();
+                }
+            }
+
+            BrushLibrary::ImportToDocument(target, names) => {
+                let entries         = library.with_names(&names).brushes;
+                let mut imported    = vec![];
+
+                if !entries.is_empty() {
+                    // The import is undone as a single action (the action markers are sent on the same stream as the edits so they stay in order)
+                    let Ok(mut vector_editor)   = context.send::<VectorCanvas>(()) else { continue; };
+
+                    vector_editor.send(VectorCanvas::StartUndoAction("Import brushes".to_string())).await.ok();
+                    for entry in entries {
+                        let brush_id = CanvasBrushId::new();
+
+                        vector_editor.send(VectorCanvas::AddBrush(brush_id)).await.ok();
+                        vector_editor.send(VectorCanvas::SetProperty(CanvasPropertyTarget::Brush(brush_id), entry.to_brush_properties())).await.ok();
+
+                        imported.push((entry.name, brush_id));
+                    }
+                    vector_editor.send(VectorCanvas::FinishUndoAction).await.ok();
+                }
+
+                if let Ok(mut target) = context.send(target) {
+                    target.send(ImportedBrushes(imported)).await.ok();
+                }
+            }
+
+            BrushLibrary::ExportFromDocument(brush_ids) => {
+                let Ok(brushes) = context.spawn_query(ReadCommand::default(), VectorQuery::Brushes(().into(), brush_ids), ()) else { continue; };
+                let brushes     = brushes.collect::<Vec<_>>().await;
+
+                for brush in brushes {
+                    if let VectorResponse::Brush(_brush_id, properties) = brush {
+                        if let Some(entry) = BrushPackEntry::from_brush_properties(&properties) {
+                            library.add_brush(entry);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
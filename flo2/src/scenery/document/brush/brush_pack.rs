@@ -0,0 +1,194 @@
+use super::core_brush_settings::*;
+use super::legacy_brush_settings::*;
+use crate::scenery::document::canvas::*;
+
+use flo_draw::*;
+use flo_draw::canvas::*;
+
+use serde::*;
+
+use std::sync::*;
+
+/// The bytes that a brush pack file starts with
+const BRUSH_PACK_MAGIC: &[u8] = b"FLOBRUSH";
+
+/// The version of the brush pack format written by this version of FlowBetween
+///
+/// Version 1 stored the brush settings directly. Version 2 stores them along with their own version number (see
+/// `CoreBrushSettings::to_bytes()`), so new brush settings don't need a new version of the pack format.
+const BRUSH_PACK_VERSION: u32 = 2;
+
+/// The size of the preview thumbnails stored in brush packs
+const BRUSH_PREVIEW_SIZE: f64 = 64.0;
+
+///
+/// A brush stored in a brush pack
+///
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrushPackEntry {
+    /// The name of this brush
+    pub name: String,
+
+    /// The settings that describe how the brush generates shapes
+    #[serde(serialize_with = "serialize_brush_settings", deserialize_with = "deserialize_brush_settings")]
+    pub settings: CoreBrushSettings,
+
+    /// A thumbnail drawing that previews the brush, centered around 0,0
+    pub preview: Arc<Vec<Draw>>,
+}
+
+///
+/// A brush pack is a shareable collection of brushes, which can be imported into any document
+///
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct BrushPack {
+    /// The brushes in this pack
+    pub brushes: Vec<BrushPackEntry>,
+}
+
+///
+/// A brush stored in a version 1 brush pack (the settings are stored directly, in whichever format the writer was using)
+///
+#[derive(Deserialize)]
+struct BrushPackEntryV1<Settings> {
+    name:       String,
+    settings:   Settings,
+    preview:    Arc<Vec<Draw>>,
+}
+
+///
+/// Errors that can occur while reading a brush pack
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum BrushPackError {
+    /// The data does not start with the brush pack header
+    NotABrushPack,
+
+    /// The brush pack was written by a newer version of FlowBetween
+    UnsupportedVersion(u32),
+
+    /// The brush pack data could not be decoded
+    InvalidData,
+}
+
+impl BrushPackEntry {
+    ///
+    /// Creates a brush pack entry, generating the preview thumbnail from the settings
+    ///
+    pub fn new(name: impl Into<String>, settings: CoreBrushSettings) -> Self {
+        let preview_state   = PointerState { location_in_window: (0.0, 0.0), location_in_canvas: Some((0.0, 0.0)), buttons: vec![], pressure: None, tilt: None, rotation: None, flow_rate: None };
+        let preview         = settings.preview(preview_state, BRUSH_PREVIEW_SIZE);
+
+        BrushPackEntry {
+            name:       name.into(),
+            settings:   settings,
+            preview:    Arc::new(preview),
+        }
+    }
+
+    ///
+    /// Reads a brush pack entry from the properties of a brush in a document (returning None if the brush has no core brush settings)
+    ///
+    pub fn from_brush_properties(properties: &Vec<(CanvasPropertyId, CanvasProperty)>) -> Option<Self> {
+        let settings    = CoreBrushSettings::from_properties(properties.iter())?;
+        let name        = Name::from_properties(properties.iter()).map(|Name(name)| name).unwrap_or_else(|| "Brush".to_string());
+
+        Some(Self::new(name, settings))
+    }
+
+    ///
+    /// Returns the properties to set on a document brush to use this entry
+    ///
+    pub fn to_brush_properties(&self) -> Vec<(CanvasPropertyId, CanvasProperty)> {
+        let mut properties = Name(self.name.clone()).to_properties();
+        properties.extend(self.settings.to_properties());
+
+        properties
+    }
+}
+
+impl BrushPack {
+    ///
+    /// Encodes this brush pack in the brush pack file format
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = BRUSH_PACK_MAGIC.to_vec();
+        bytes.extend(BRUSH_PACK_VERSION.to_le_bytes());
+        bytes.extend(postcard::to_allocvec(self).unwrap_or_default());
+
+        bytes
+    }
+
+    ///
+    /// Decodes a brush pack from the brush pack file format
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BrushPackError> {
+        let Some(bytes) = bytes.strip_prefix(BRUSH_PACK_MAGIC) else { return Err(BrushPackError::NotABrushPack); };
+        if bytes.len() < 4 { return Err(BrushPackError::InvalidData); }
+
+        let (version, bytes) = bytes.split_at(4);
+        let version          = u32::from_le_bytes([version[0], version[1], version[2], version[3]]);
+
+        if version > BRUSH_PACK_VERSION { return Err(BrushPackError::UnsupportedVersion(version)); }
+
+        if version <= 1 {
+            Self::from_v1_bytes(bytes).ok_or(BrushPackError::InvalidData)
+        } else {
+            postcard::from_bytes(bytes).map_err(|_| BrushPackError::InvalidData)
+        }
+    }
+
+    ///
+    /// Decodes the contents of a version 1 brush pack
+    ///
+    /// Version 1 packs were written with the brush settings with and without the stabiliser, so this tries both.
+    ///
+    fn from_v1_bytes(bytes: &[u8]) -> Option<Self> {
+        fn to_pack<Settings: Into<CoreBrushSettings>>(brushes: Vec<BrushPackEntryV1<Settings>>) -> BrushPack {
+            BrushPack {
+                brushes: brushes.into_iter()
+                    .map(|entry| BrushPackEntry { name: entry.name, settings: entry.settings.into(), preview: entry.preview })
+                    .collect()
+            }
+        }
+
+        postcard_exact::<Vec<BrushPackEntryV1<CoreBrushSettings>>>(bytes).map(to_pack)
+            .or_else(|| postcard_exact::<Vec<BrushPackEntryV1<CoreBrushSettingsV2>>>(bytes).map(to_pack))
+    }
+
+    ///
+    /// Adds a brush to this pack, replacing any existing brush with the same name
+    ///
+    pub fn add_brush(&mut self, entry: BrushPackEntry) {
+        if let Some(existing) = self.brushes.iter_mut().find(|existing| existing.name == entry.name) {
+            *existing = entry;
+        } else {
+            self.brushes.push(entry);
+        }
+    }
+
+    ///
+    /// Returns a new pack containing the brushes with the specified names
+    ///
+    pub fn with_names(&self, names: &[String]) -> BrushPack {
+        BrushPack {
+            brushes: self.brushes.iter().filter(|entry| names.contains(&entry.name)).cloned().collect()
+        }
+    }
+}
+
+///
+/// Stores the settings for a brush pack entry along with their version
+///
+fn serialize_brush_settings<S: Serializer>(settings: &CoreBrushSettings, serializer: S) -> Result<S::Ok, S::Error> {
+    settings.to_bytes().serialize(serializer)
+}
+
+///
+/// Reads the settings for a brush pack entry, converting them from older versions if needed
+///
+fn deserialize_brush_settings<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CoreBrushSettings, D::Error> {
+    let bytes = Vec::<u8>::deserialize(deserializer)?;
+
+    CoreBrushSettings::from_bytes(&bytes).ok_or_else(|| de::Error::custom("unsupported brush settings"))
+}
@@ -22,11 +22,20 @@ pub struct BrushPoint {
     /// If the device has a 'flow rate' adjustment (emulating an airbrush, for example) this is the value of that (from 0.0 to 1.0).
     pub flow_rate: f64,
 
+    /// The distance the pointer moved between the previous input event and this one (a measure of the speed of the stroke)
+    pub speed: f64,
+
     /// The radius of the 'daub' that should be generated by this brush point (centered on the position)
     pub daub_radius: Option<f64>,
 
     /// The transform that should be applied to the daub shape (after scaling to the radius but before transforming to the position value)
     pub daub_transform: Option<canvas::Transform2D>,
+
+    /// The opacity of the daub generated by this brush point, from 0.0 to 1.0
+    pub opacity: Option<f64>,
+
+    /// Offsets to apply to the hue (in degrees), saturation and lightness of the colour of the daub generated by this brush point
+    pub color_jitter: Option<(f64, f64, f64)>,
 }
 
 impl BrushPoint {
@@ -51,8 +60,11 @@ impl From<flo_draw::PointerState> for BrushPoint {
             tilt:           value.tilt.unwrap_or((0.0, 0.0)),
             rotation:       value.rotation.unwrap_or(0.0),
             flow_rate:      value.flow_rate.unwrap_or(1.0),
+            speed:          0.0,
             daub_radius:    None,
-            daub_transform: None
+            daub_transform: None,
+            opacity:        None,
+            color_jitter:   None,
         }
     }
 }
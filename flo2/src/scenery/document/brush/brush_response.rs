@@ -75,8 +75,8 @@ pub fn create_shape_stream<'a>(points: impl 'static + Send + Stream<Item=BrushPo
     for response in responses {
         // TODO: the shape generator should combine the shapes generated by the other generators to create a vec of all the generated shapes
         match response {
-            BrushResponse::Points(point_fn)             => { points_stream      = Box::new(move |stream| (points_stream)(point_fn(stream))); },
-            BrushResponse::Shapes(shape_fn)             => { shape_stream       = Box::new(move |stream| (shape_stream)(shape_fn(stream))); },
+            BrushResponse::Points(point_fn)             => { points_stream      = Box::new(move |stream| point_fn((points_stream)(stream))); },
+            BrushResponse::Shapes(shape_fn)             => { shape_stream       = Box::new(move |stream| shape_fn((shape_stream)(stream))); },
             BrushResponse::ShapeGenerator(generator_fn) => { generator_stream   = Box::new(move |stream| generator_fn(stream).map(|shape| vec![shape]).boxed()); },
         }
     }
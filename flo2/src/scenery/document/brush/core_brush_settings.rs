@@ -1,4 +1,5 @@
 use super::brush_response::*;
+use super::legacy_brush_settings::*;
 use super::shape_streams::*;
 use super::smoothing_streams::*;
 use crate::scenery::document::canvas::*;
@@ -6,8 +7,6 @@ use crate::scenery::ui::*;
 
 use flo_curves::bezier::*;
 use flo_curves::bezier::path::*;
-use flo_curves::bezier::rasterize::*;
-use flo_curves::bezier::vectorize::*;
 use flo_draw::*;
 use flo_draw::canvas::*;
 
@@ -16,6 +15,19 @@ use serde::*;
 
 use std::sync::*;
 
+/// Property used to store the core brush settings for a brush in a document
+pub static PROP_CORE_BRUSH_SETTINGS: LazyCanvasPropertyId = LazyCanvasPropertyId::new("flowbetween::core_brush_settings");
+
+/// The bytes that encoded core brush settings start with (settings written before the version was stored have no header)
+const CORE_BRUSH_SETTINGS_MAGIC: &[u8] = b"FLOBSET";
+
+/// The version of the core brush settings format written by this version of FlowBetween
+///
+/// The settings are encoded with postcard, which isn't self-describing, so this needs to be increased whenever a field is
+/// added to `CoreBrushSettings`. The previous version of the struct should be kept in `legacy_brush_settings` along with a
+/// conversion to the new version.
+pub const CORE_BRUSH_SETTINGS_VERSION: u32 = 3;
+
 ///
 /// The core brush settings describe how a brush stroke is turned into a shape
 ///
@@ -31,6 +43,15 @@ pub struct CoreBrushSettings {
 
     /// What the stroke speed will vary (or the empty vec if the speed has no effect)
     pub speed_vary: Vec<BrushVary>,
+
+    /// What the tilt of the pen will vary (or the empty vec if the tilt has no effect)
+    pub tilt_vary: Vec<BrushVary>,
+
+    /// How the daubs that make up the brush stroke are rotated
+    pub rotation: BrushDaubRotation,
+
+    /// How the input points are stabilised before the brush stroke is generated
+    pub stabiliser: BrushStabiliser,
 }
 
 impl CoreBrushSettings {
@@ -70,6 +91,9 @@ impl CoreBrushSettings {
             builder:        BrushShapeBuilder::Daubs(brush_daub_settings), 
             pressure_vary:  vec![BrushVary::Radius { min: 0.0, max: 1.0, profile: vec![ResponseCurve::linear()] }], 
             speed_vary:     vec![],
+            tilt_vary:      vec![],
+            rotation:       BrushDaubRotation::Fixed,
+            stabiliser:     BrushStabiliser::None,
         }
     }
 
@@ -81,6 +105,41 @@ impl CoreBrushSettings {
             builder:        BrushShapeBuilder::LineWidth, 
             pressure_vary:  vec![BrushVary::Radius { min: 0.0, max: 1.0, profile: vec![ResponseCurve::linear()] }], 
             speed_vary:     vec![],
+            tilt_vary:      vec![],
+            rotation:       BrushDaubRotation::Fixed,
+            stabiliser:     BrushStabiliser::None,
+        }
+    }
+
+    ///
+    /// Encodes these settings, along with the version of the format they're stored in
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = CORE_BRUSH_SETTINGS_MAGIC.to_vec();
+        bytes.extend(CORE_BRUSH_SETTINGS_VERSION.to_le_bytes());
+        bytes.extend(postcard::to_allocvec(self).unwrap_or_default());
+
+        bytes
+    }
+
+    ///
+    /// Decodes settings written by `to_bytes()`, converting settings written by older versions of FlowBetween
+    ///
+    /// Returns None if the settings can't be decoded, or were written by a newer version.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        // Settings written before the version was stored start with the builder instead of the header
+        let Some(bytes) = bytes.strip_prefix(CORE_BRUSH_SETTINGS_MAGIC) else { return decode_unversioned_brush_settings(bytes); };
+        if bytes.len() < 4 { return None; }
+
+        let (version, bytes) = bytes.split_at(4);
+        let version          = u32::from_le_bytes([version[0], version[1], version[2], version[3]]);
+
+        match version {
+            1 => postcard_exact::<CoreBrushSettingsV1>(bytes).map(|settings| settings.into()),
+            2 => postcard_exact::<CoreBrushSettingsV2>(bytes).map(|settings| settings.into()),
+            3 => postcard_exact::<CoreBrushSettings>(bytes),
+            _ => None,
         }
     }
 
@@ -96,13 +155,35 @@ impl CoreBrushSettings {
             BrushShapeBuilder::LineWidth    => { BrushResponse::ShapeGenerator(Arc::new(|points| width_brush_stream(points, 0.25).boxed())) }
         };
 
+        // The 'speed' step measures how fast the pointer is moving before the points are filled in
+        let speed_step = BrushResponse::Points(Arc::new(|points| brush_speed_stream(points).boxed()));
+
+        // The 'stabiliser' step smooths out the input points
+        let stabiliser_step = match self.stabiliser {
+            BrushStabiliser::None                           => None,
+            BrushStabiliser::MovingAverage { window }       => Some(BrushResponse::Points(Arc::new(move |points| brush_moving_average(window, points).boxed()))),
+            BrushStabiliser::PulledString { radius }        => Some(BrushResponse::Points(Arc::new(move |points| brush_pulled_string(radius, points).boxed()))),
+            BrushStabiliser::CurveFit { tolerance }         => Some(BrushResponse::Points(Arc::new(move |points| brush_curve_fit(tolerance, points).boxed()))),
+        };
+
         // The 'distance' step, used to convert the input points into values suitable for the generation algorithm
         let distance_step = match &self.builder {
             BrushShapeBuilder::Daubs(daubs) => { let distance = daubs.distance; Some(BrushResponse::Points(Arc::new(move |points| brush_fill_in_points(distance, points).boxed()))) },
             BrushShapeBuilder::LineWidth    => None,
         };
 
-        distance_step.into_iter()
+        // The 'vary' step sets the radius, opacity, colour, rotation and scatter of the filled-in points
+        let base_radius = match &self.builder {
+            BrushShapeBuilder::Daubs(daubs) => { daubs.radius() },
+            BrushShapeBuilder::LineWidth    => { 1.0 },
+        };
+        let vary_settings   = Arc::new(self.clone());
+        let vary_step       = BrushResponse::Points(Arc::new(move |points| brush_vary_stream(vary_settings.clone(), base_radius, points).boxed()));
+
+        iter::once(speed_step)
+            .chain(stabiliser_step)
+            .chain(distance_step)
+            .chain(iter::once(vary_step))
             .chain(iter::once(create_shape))
             .collect()
     }
@@ -159,6 +240,33 @@ impl CoreBrushSettings {
     }
 }
 
+impl ToCanvasProperties for CoreBrushSettings {
+    fn to_properties(&self) -> Vec<(CanvasPropertyId, CanvasProperty)> {
+        vec![
+            (*PROP_CORE_BRUSH_SETTINGS, CanvasProperty::ByteList(self.to_bytes())),
+        ]
+    }
+}
+
+impl FromCanvasProperties for CoreBrushSettings {
+    fn used_properties() -> Vec<CanvasPropertyId> {
+        vec![*PROP_CORE_BRUSH_SETTINGS]
+    }
+
+    fn from_properties<'a>(properties: impl Iterator<Item=&'a (CanvasPropertyId, CanvasProperty)>) -> Option<Self> {
+        let mut settings = None;
+
+        for (prop_id, prop_val) in properties {
+            if *prop_id == *PROP_CORE_BRUSH_SETTINGS { settings = Some(prop_val); }
+        }
+
+        match settings? {
+            CanvasProperty::ByteList(bytes) => Self::from_bytes(bytes),
+            _                               => None,
+        }
+    }
+}
+
 impl Default for CoreBrushSettings {
     fn default() -> Self {
         use flo_curves::arc::*;
@@ -202,16 +310,22 @@ pub struct BrushDaubSettings {
 }
 
 impl BrushDaubSettings {
+    ///
+    /// The radius of the daub shape when it's drawn at its original size
+    ///
+    pub fn radius(&self) -> f64 {
+        (self.bounds.1.x - self.bounds.0.x).max(self.bounds.1.y - self.bounds.0.y) / 2.0
+    }
+
     ///
     /// Creates the 'create brush shape' program for these daub settings
     ///
     pub fn create_shape_response(&self) -> BrushResponse {
-        let daub_size       = ContourSize((self.bounds.1.x - self.bounds.0.x).ceil() as usize, (self.bounds.1.y - self.bounds.0.y).ceil() as usize);
-        let daub_contour    = Arc::new(PathContour::from_path(self.shape.clone(), daub_size));
+        let daub_contours   = Arc::new(DaubContours::new(self.shape.clone(), self.bounds));
         let max_error       = self.fit;
 
         BrushResponse::ShapeGenerator(Arc::new(move |points| {
-            daub_brush_stream(daub_contour.clone(), points, max_error).boxed()
+            daub_brush_stream(daub_contours.clone(), points, max_error).boxed()
         }))
     }
 }
@@ -226,6 +340,83 @@ pub enum BrushVary {
 
     /// Change the distance between daubs based on this parameter
     Distance { min: f64, max: f64, profile: Vec<ResponseCurve>, },
+
+    /// Change the opacity of the brush stroke based on this parameter (opacities from multiple inputs are multiplied together)
+    Opacity { min: f64, max: f64, profile: Vec<ResponseCurve>, },
+
+    /// Randomly change the hue of the daubs by up to this many degrees
+    HueJitter { min: f64, max: f64, profile: Vec<ResponseCurve>, },
+
+    /// Randomly change the saturation of the daubs by up to this amount (saturation ranges from 0.0 to 1.0)
+    SaturationJitter { min: f64, max: f64, profile: Vec<ResponseCurve>, },
+
+    /// Randomly change the lightness of the daubs by up to this amount (lightness ranges from 0.0 to 1.0)
+    LightnessJitter { min: f64, max: f64, profile: Vec<ResponseCurve>, },
+
+    /// Rotate the daubs by this many degrees (added to the rotation set by `BrushDaubRotation`)
+    Rotation { min: f64, max: f64, profile: Vec<ResponseCurve>, },
+
+    /// Randomly move the daubs away from the brush stroke by up to this distance
+    Scatter { min: f64, max: f64, profile: Vec<ResponseCurve>, },
+}
+
+///
+/// How the daubs in a brush stroke are rotated
+///
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum BrushDaubRotation {
+    /// Daubs are always drawn at their original angle
+    #[default]
+    Fixed,
+
+    /// Daubs are rotated to follow the direction of the brush stroke
+    FollowStroke,
+
+    /// Daubs are rotated to face the direction the pen is tilted in
+    FollowTilt,
+}
+
+///
+/// How the input points for a brush stroke are stabilised
+///
+/// Stabilisers all finish the stroke at the point where the pen was lifted
+///
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum BrushStabiliser {
+    /// Points are used as they are received from the input device
+    #[default]
+    None,
+
+    /// Each point is averaged with the points before it
+    MovingAverage { window: usize },
+
+    /// The brush is pulled along behind the pen on a string of the specified length ('lazy mouse')
+    PulledString { radius: f64 },
+
+    /// Bezier curves are fitted to the points with the specified tolerance
+    CurveFit { tolerance: f64 },
+}
+
+impl BrushVary {
+    ///
+    /// Returns the value that this variation has for a particular input value (from 0.0 to 1.0)
+    ///
+    pub fn value(&self, input: f64) -> f64 {
+        use BrushVary::*;
+
+        let (min, max, profile) = match self {
+            Radius { min, max, profile }            |
+            Distance { min, max, profile }          |
+            Opacity { min, max, profile }           |
+            HueJitter { min, max, profile }         |
+            SaturationJitter { min, max, profile }  |
+            LightnessJitter { min, max, profile }   |
+            Rotation { min, max, profile }          |
+            Scatter { min, max, profile }           => (*min, *max, profile),
+        };
+
+        min + (max-min) * ResponseCurve::evaluate_profile(profile, input)
+    }
 }
 
 ///
@@ -262,4 +453,108 @@ impl ResponseCurve {
     pub fn linear() -> Self {
         ResponseCurve([0.0, 1.0/3.0, 2.0/3.0, 1.0])
     }
-}
\ No newline at end of file
+
+    ///
+    /// Evaluates a response profile for an input value from 0.0 to 1.0
+    ///
+    /// Each curve in the profile covers an equal part of the input range. An empty profile is treated as linear.
+    ///
+    pub fn evaluate_profile(profile: &[ResponseCurve], input: f64) -> f64 {
+        if profile.is_empty() { return input.clamp(0.0, 1.0); }
+
+        // Find the curve that covers this input, and the position within that curve
+        let input       = input.clamp(0.0, 1.0) * (profile.len() as f64);
+        let curve_idx   = (input.floor() as usize).min(profile.len()-1);
+        let t           = input - (curve_idx as f64);
+
+        profile[curve_idx].point_at_pos(t)
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn linear_profile() {
+        let profile = vec![ResponseCurve::linear()];
+
+        assert!((ResponseCurve::evaluate_profile(&profile, 0.0) - 0.0).abs() < 0.001);
+        assert!((ResponseCurve::evaluate_profile(&profile, 0.25) - 0.25).abs() < 0.001);
+        assert!((ResponseCurve::evaluate_profile(&profile, 1.0) - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn multiple_curve_profile() {
+        // Rises to 1.0 over the first half, then falls back to 0.0
+        let profile = vec![ResponseCurve([0.0, 1.0/3.0, 2.0/3.0, 1.0]), ResponseCurve([1.0, 2.0/3.0, 1.0/3.0, 0.0])];
+
+        assert!((ResponseCurve::evaluate_profile(&profile, 0.25) - 0.5).abs() < 0.001);
+        assert!((ResponseCurve::evaluate_profile(&profile, 0.5) - 1.0).abs() < 0.001);
+        assert!((ResponseCurve::evaluate_profile(&profile, 0.75) - 0.5).abs() < 0.001);
+        assert!((ResponseCurve::evaluate_profile(&profile, 1.0) - 0.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn vary_value_uses_range() {
+        let vary = BrushVary::Opacity { min: 0.25, max: 0.75, profile: vec![ResponseCurve::linear()] };
+
+        assert!((vary.value(0.0) - 0.25).abs() < 0.001);
+        assert!((vary.value(0.5) - 0.5).abs() < 0.001);
+        assert!((vary.value(2.0) - 0.75).abs() < 0.001);
+    }
+
+    #[test]
+    fn stabiliser_is_saved_with_brush() {
+        let mut settings    = CoreBrushSettings::default();
+        settings.stabiliser = BrushStabiliser::PulledString { radius: 12.0 };
+
+        let properties      = settings.to_properties();
+        let loaded          = CoreBrushSettings::from_properties(properties.iter()).unwrap();
+
+        assert!(loaded.stabiliser == BrushStabiliser::PulledString { radius: 12.0 });
+        assert!(loaded == settings);
+    }
+
+    #[test]
+    fn load_v1_settings_stored_without_version() {
+        // Brushes created before the tilt, rotation and stabiliser settings were added have no version property
+        let original    = CoreBrushSettings::default();
+        let v1          = CoreBrushSettingsV1 { builder: original.builder.clone(), pressure_vary: original.pressure_vary.clone(), speed_vary: original.speed_vary.clone() };
+        let properties  = vec![(*PROP_CORE_BRUSH_SETTINGS, CanvasProperty::ByteList(postcard::to_allocvec(&v1).unwrap()))];
+        let loaded      = CoreBrushSettings::from_properties(properties.iter()).unwrap();
+
+        assert!(loaded == original);
+    }
+
+    #[test]
+    fn load_v2_settings_stored_without_version() {
+        let mut original    = CoreBrushSettings::default();
+        original.rotation   = BrushDaubRotation::FollowTilt;
+        original.tilt_vary  = vec![BrushVary::Opacity { min: 0.5, max: 1.0, profile: vec![ResponseCurve::linear()] }];
+
+        let v2              = CoreBrushSettingsV2 { builder: original.builder.clone(), pressure_vary: original.pressure_vary.clone(), speed_vary: original.speed_vary.clone(), tilt_vary: original.tilt_vary.clone(), rotation: original.rotation };
+        let properties      = vec![(*PROP_CORE_BRUSH_SETTINGS, CanvasProperty::ByteList(postcard::to_allocvec(&v2).unwrap()))];
+        let loaded          = CoreBrushSettings::from_properties(properties.iter()).unwrap();
+
+        assert!(loaded == original);
+    }
+
+    #[test]
+    fn load_v3_settings_stored_without_version() {
+        let mut original    = CoreBrushSettings::default();
+        original.stabiliser = BrushStabiliser::MovingAverage { window: 4 };
+
+        let properties      = vec![(*PROP_CORE_BRUSH_SETTINGS, CanvasProperty::ByteList(postcard::to_allocvec(&original).unwrap()))];
+        let loaded          = CoreBrushSettings::from_properties(properties.iter()).unwrap();
+
+        assert!(loaded == original);
+    }
+
+    #[test]
+    fn reject_settings_from_newer_version() {
+        let mut bytes = CoreBrushSettings::default().to_bytes();
+        bytes[CORE_BRUSH_SETTINGS_MAGIC.len()] = 99;
+
+        assert!(CoreBrushSettings::from_bytes(&bytes).is_none());
+    }
+}
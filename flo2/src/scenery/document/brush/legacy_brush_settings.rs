@@ -0,0 +1,81 @@
+use super::core_brush_settings::*;
+
+use serde::*;
+
+///
+/// The core brush settings as they were stored before any of the optional settings were added (version 1)
+///
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct CoreBrushSettingsV1 {
+    pub builder:        BrushShapeBuilder,
+    pub pressure_vary:  Vec<BrushVary>,
+    pub speed_vary:     Vec<BrushVary>,
+}
+
+///
+/// The core brush settings with the tilt variations and daub rotation, but no stabiliser (version 2)
+///
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct CoreBrushSettingsV2 {
+    pub builder:        BrushShapeBuilder,
+    pub pressure_vary:  Vec<BrushVary>,
+    pub speed_vary:     Vec<BrushVary>,
+    pub tilt_vary:      Vec<BrushVary>,
+    pub rotation:       BrushDaubRotation,
+}
+
+impl From<CoreBrushSettingsV1> for CoreBrushSettingsV2 {
+    fn from(settings: CoreBrushSettingsV1) -> Self {
+        CoreBrushSettingsV2 {
+            builder:        settings.builder,
+            pressure_vary:  settings.pressure_vary,
+            speed_vary:     settings.speed_vary,
+            tilt_vary:      vec![],
+            rotation:       BrushDaubRotation::Fixed,
+        }
+    }
+}
+
+impl From<CoreBrushSettingsV2> for CoreBrushSettings {
+    fn from(settings: CoreBrushSettingsV2) -> Self {
+        CoreBrushSettings {
+            builder:        settings.builder,
+            pressure_vary:  settings.pressure_vary,
+            speed_vary:     settings.speed_vary,
+            tilt_vary:      settings.tilt_vary,
+            rotation:       settings.rotation,
+            stabiliser:     BrushStabiliser::None,
+        }
+    }
+}
+
+impl From<CoreBrushSettingsV1> for CoreBrushSettings {
+    fn from(settings: CoreBrushSettingsV1) -> Self {
+        CoreBrushSettingsV2::from(settings).into()
+    }
+}
+
+///
+/// Decodes postcard data, failing if any of the data is left over
+///
+/// Postcard isn't self-describing, so this is how data written without a version can be matched against the versions
+/// of the settings it might have been written with: each version has a different number of fields.
+///
+pub (crate) fn postcard_exact<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Option<T> {
+    match postcard::take_from_bytes(bytes) {
+        Ok((value, []))     => Some(value),
+        _                   => None,
+    }
+}
+
+///
+/// Decodes core brush settings that were stored without a version number
+///
+/// These could have been written by any of the versions of FlowBetween before the version started to be stored
+/// alongside the settings, so this tries each format, newest first.
+///
+pub fn decode_unversioned_brush_settings(bytes: &[u8]) -> Option<CoreBrushSettings> {
+    postcard_exact::<CoreBrushSettings>(bytes)
+        .or_else(|| postcard_exact::<CoreBrushSettingsV2>(bytes).map(|settings| settings.into()))
+        .or_else(|| postcard_exact::<CoreBrushSettingsV1>(bytes).map(|settings| settings.into()))
+}
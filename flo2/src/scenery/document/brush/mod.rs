@@ -1,15 +1,24 @@
 mod basic_brush_streams;
+mod brush_library;
+mod brush_pack;
 mod brush_point;
 mod brush_request;
 mod brush_response;
 mod shape_streams;
 mod smoothing_streams;
 mod core_brush_settings;
+mod legacy_brush_settings;
 
 pub use basic_brush_streams::*;
+pub use brush_library::*;
+pub use brush_pack::*;
 pub use brush_point::*;
 pub use brush_request::*;
 pub use brush_response::*;
 pub use shape_streams::*;
 pub use smoothing_streams::*;
-pub use core_brush_settings::*;
\ No newline at end of file
+pub use core_brush_settings::*;
+pub use legacy_brush_settings::*;
+
+#[cfg(test)]
+mod test_brush_library;
@@ -1,67 +1,398 @@
 use super::brush_point::*;
+use super::core_brush_settings::*;
 use crate::scenery::document::canvas::*;
 
 use flo_curves::*;
 use flo_curves::bezier::*;
+use flo_curves::bezier::rasterize::*;
 use flo_curves::bezier::vectorize::*;
+use flo_draw::canvas::*;
 
 use futures::prelude::*;
 
+use std::collections::*;
+use std::f64::consts::{PI};
 use std::sync::*;
 
+/// The speed (distance moved between input events) that is treated as the maximum value for `speed_vary`
+const MAX_BRUSH_SPEED: f64 = 40.0;
+
+/// Number of different angles that daubs can be rotated to
+const DAUB_ROTATION_STEPS: usize = 64;
+
+/// Number of different opacity values that daubs are grouped into
+const OPACITY_STEPS: f64 = 32.0;
+
+/// Size of the hue steps (in degrees) that daubs are grouped into
+const HUE_STEP: f64 = 2.0;
+
+/// Number of different saturation and lightness offsets that daubs are grouped into
+const SATURATION_LIGHTNESS_STEPS: f64 = 50.0;
+
+/// Number of consecutive daubs that share the same colour jitter (each variation is a separate shape, so jittering every daub would make too many)
+const JITTER_SEGMENT_LENGTH: u64 = 8;
+
+///
+/// The contours for a daub shape, rotated to different angles
+///
+pub struct DaubContours {
+    /// The daub shape, with its bounds starting at 0,0
+    shape: Vec<WorkingSubpath>,
+
+    /// The width and height of the daub shape
+    size: (f64, f64),
+
+    /// The contours that have been generated for each angle so far
+    contours: Mutex<HashMap<usize, Arc<PathContour>>>,
+}
+
+impl DaubContours {
+    ///
+    /// Creates the contours for a daub shape
+    ///
+    pub fn new(shape: Vec<WorkingSubpath>, bounds: (WorkingPoint, WorkingPoint)) -> Self {
+        DaubContours {
+            shape:      shape,
+            size:       (bounds.1.x - bounds.0.x, bounds.1.y - bounds.0.y),
+            contours:   Mutex::new(HashMap::new()),
+        }
+    }
+
+    ///
+    /// The size of the largest side of the daub when it is not rotated
+    ///
+    #[inline]
+    pub fn max_size(&self) -> f64 {
+        self.size.0.max(self.size.1)
+    }
+
+    ///
+    /// Retrieves the contour for the daub rotated by the specified angle (in radians)
+    ///
+    /// The daub is rotated around its center. Rotated daubs have a larger contour size than the original so that the whole shape fits.
+    ///
+    pub fn contour_for_angle(&self, angle: f64) -> Arc<PathContour> {
+        // Angles are rounded to the nearest step
+        let step = (angle.rem_euclid(2.0*PI) / (2.0*PI) * (DAUB_ROTATION_STEPS as f64)).round() as usize % DAUB_ROTATION_STEPS;
+
+        let mut contours = self.contours.lock().unwrap();
+        contours.entry(step)
+            .or_insert_with(|| {
+                if step == 0 {
+                    // The unrotated daub is used as-is
+                    Arc::new(PathContour::from_path(self.shape.clone(), ContourSize(self.size.0.ceil() as usize, self.size.1.ceil() as usize)))
+                } else {
+                    // Rotate around the center, and move the center to the middle of a square large enough to fit the daub at any angle
+                    let angle       = (step as f64) / (DAUB_ROTATION_STEPS as f64) * 2.0 * PI;
+                    let (sin, cos)  = angle.sin_cos();
+                    let diagonal    = (self.size.0*self.size.0 + self.size.1*self.size.1).sqrt().ceil();
+                    let center      = (self.size.0/2.0, self.size.1/2.0);
+
+                    let rotated = self.shape.iter()
+                        .map(|subpath| subpath.map_points::<WorkingSubpath>(|point| {
+                            let (x, y) = (point.x - center.0, point.y - center.1);
+
+                            WorkingPoint { x: x*cos - y*sin + diagonal/2.0, y: x*sin + y*cos + diagonal/2.0 }
+                        }))
+                        .collect::<Vec<_>>();
+
+                    Arc::new(PathContour::from_path(rotated, ContourSize(diagonal as usize, diagonal as usize)))
+                }
+            })
+            .clone()
+    }
+}
+
+///
+/// Generates a pseudo-random value between -1.0 and 1.0 for a brush point
+///
+/// Brush strokes are regenerated from their points, so the same point index and channel always generate the same value.
+///
+#[inline]
+fn brush_jitter(point_idx: u64, channel: u64) -> f64 {
+    // splitmix64
+    let mut z = point_idx.wrapping_mul(0x9e37_79b9_7f4a_7c15).wrapping_add(channel.wrapping_mul(0xbf58_476d_1ce4_e5b9));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z = z ^ (z >> 31);
+
+    ((z >> 11) as f64) / ((1u64 << 53) as f64) * 2.0 - 1.0
+}
+
+///
+/// Sets the `speed` of each brush point to the distance moved from the previous point
+///
+/// This should be applied to the points received from the input device (before they are filled in), as these arrive at a roughly constant rate
+///
+pub fn brush_speed_stream(input_stream: impl 'static + Send + Stream<Item=BrushPoint>) -> impl 'static + Send + Stream<Item=BrushPoint> {
+    let mut last_point: Option<BrushPoint> = None;
+
+    input_stream.map(move |point| {
+        let mut point = point;
+
+        point.speed = last_point.map(|last_point| last_point.distance_to(&point)).unwrap_or(0.0);
+        last_point  = Some(point);
+
+        point
+    })
+}
+
+///
+/// Applies the `pressure_vary`, `speed_vary` and `tilt_vary` settings from a brush to a stream of brush points
+///
+/// This sets the radius, opacity and colour jitter of each point, and also rotates and scatters the daubs. `BrushVary::Distance` is
+/// not applied here, as the distance between the points has already been decided by the time this runs.
+///
+pub fn brush_vary_stream(settings: Arc<CoreBrushSettings>, base_radius: f64, input_stream: impl 'static + Send + Stream<Item=BrushPoint>) -> impl 'static + Send + Stream<Item=BrushPoint> {
+    let mut last_position   = None;
+    let mut point_idx       = 0u64;
+
+    input_stream.map(move |point| {
+        let mut point = point;
+
+        // The inputs are all normalised to the range 0.0-1.0
+        let pressure    = point.pressure.clamp(0.0, 1.0);
+        let speed       = (point.speed / MAX_BRUSH_SPEED).clamp(0.0, 1.0);
+        let tilt        = ((point.tilt.0*point.tilt.0 + point.tilt.1*point.tilt.1).sqrt() / 90.0).clamp(0.0, 1.0);
+
+        let variations  = settings.pressure_vary.iter().map(|vary| (vary, pressure))
+            .chain(settings.speed_vary.iter().map(|vary| (vary, speed)))
+            .chain(settings.tilt_vary.iter().map(|vary| (vary, tilt)));
+
+        // Colour jitter is applied to segments of the stroke rather than to individual daubs
+        let segment_idx = point_idx / JITTER_SEGMENT_LENGTH;
+
+        // Radius and opacity are multiplied together, the others are added together
+        let mut radius      = base_radius;
+        let mut opacity     = None;
+        let mut jitter      = None;
+        let mut rotation    = match settings.rotation {
+            BrushDaubRotation::Fixed        => 0.0,
+            BrushDaubRotation::FollowStroke => last_position.map(|(x, y)| (point.position.1 - y).atan2(point.position.0 - x)).unwrap_or(0.0),
+            BrushDaubRotation::FollowTilt   => if point.tilt != (0.0, 0.0) { point.tilt.1.atan2(point.tilt.0) } else { 0.0 },
+        };
+        let mut scatter     = 0.0;
+
+        for (vary, input) in variations {
+            let value = vary.value(input);
+
+            match vary {
+                BrushVary::Radius { .. }            => { radius *= value; }
+                BrushVary::Distance { .. }          => { }
+                BrushVary::Opacity { .. }           => { opacity = Some(opacity.unwrap_or(1.0) * value); }
+                BrushVary::HueJitter { .. }         => { let (h, s, l) = jitter.unwrap_or((0.0, 0.0, 0.0)); jitter = Some((h + value*brush_jitter(segment_idx, 0), s, l)); }
+                BrushVary::SaturationJitter { .. }  => { let (h, s, l) = jitter.unwrap_or((0.0, 0.0, 0.0)); jitter = Some((h, s + value*brush_jitter(segment_idx, 1), l)); }
+                BrushVary::LightnessJitter { .. }   => { let (h, s, l) = jitter.unwrap_or((0.0, 0.0, 0.0)); jitter = Some((h, s, l + value*brush_jitter(segment_idx, 2))); }
+                BrushVary::Rotation { .. }          => { rotation += value.to_radians(); }
+                BrushVary::Scatter { .. }           => { scatter += value; }
+            }
+        }
+
+        last_position = Some(point.position);
+
+        // Scatter the daub in a random direction
+        if scatter != 0.0 {
+            let angle       = brush_jitter(point_idx, 3) * PI;
+            let distance    = scatter * (brush_jitter(point_idx, 4) + 1.0) / 2.0;
+
+            point.position.0 += angle.cos() * distance;
+            point.position.1 += angle.sin() * distance;
+        }
+
+        point.daub_radius   = Some(radius.max(0.0));
+        point.opacity       = opacity.map(|opacity| opacity.clamp(0.0, 1.0));
+        point.color_jitter  = jitter;
+
+        if rotation != 0.0 {
+            point.daub_transform = Some(Transform2D::rotate(rotation as _));
+        }
+
+        point_idx += 1;
+
+        point
+    })
+}
+
+///
+/// Returns the rotation in radians described by a daub transform
+///
+#[inline]
+fn daub_rotation(transform: &Option<Transform2D>) -> f64 {
+    match transform {
+        Some(Transform2D(matrix))   => (matrix[1][0] as f64).atan2(matrix[0][0] as f64),
+        None                        => 0.0,
+    }
+}
+
+///
+/// Returns the key used to group daubs with similar opacity and colour together (as they have to be drawn as separate shapes)
+///
+#[inline]
+fn daub_variation_key(point: &BrushPoint) -> (i64, i64, i64, i64) {
+    let opacity             = point.opacity.unwrap_or(1.0);
+    let (hue, sat, light)   = point.color_jitter.unwrap_or((0.0, 0.0, 0.0));
+
+    ((opacity * OPACITY_STEPS).round() as i64, (hue / HUE_STEP).round() as i64, (sat * SATURATION_LIGHTNESS_STEPS).round() as i64, (light * SATURATION_LIGHTNESS_STEPS).round() as i64)
+}
+
+///
+/// Converts a daub variation key back to a colour variation
+///
+#[inline]
+fn color_variation_for_key((opacity, hue, sat, light): (i64, i64, i64, i64)) -> ColorVariation {
+    ColorVariation {
+        opacity:    (opacity as f64) / OPACITY_STEPS,
+        hue:        (hue as f64) * HUE_STEP,
+        saturation: (sat as f64) / SATURATION_LIGHTNESS_STEPS,
+        lightness:  (light as f64) / SATURATION_LIGHTNESS_STEPS,
+    }
+}
+
 ///
 /// Brush stream that builds up a shape by drawing the supplied 'daub' shape
 ///
 /// This returns new shapes for every batch of brush points that it receives (typically generated by `ready_chunks()` from a stream of points from
 /// the UI). To generate a single shape, just send a single batch of points.
 ///
-pub fn daub_brush_stream<'a>(daub: Arc<impl 'a + Send + Sync + ColumnSampledContour>, points: impl 'a + Send + Stream<Item=Vec<BrushPoint>>, max_error: f64) -> impl 'a + Send + Stream<Item=ShapeWithProperties> {
+/// Daubs are rotated by the `daub_transform` of each point. If any of the points have an opacity or a colour jitter, the result is a group, with
+/// a path for each different variation that has a `ColorVariation` property.
+///
+pub fn daub_brush_stream<'a>(daub: Arc<DaubContours>, points: impl 'a + Send + Stream<Item=Vec<BrushPoint>>, max_error: f64) -> impl 'a + Send + Stream<Item=ShapeWithProperties> {
     // Used for scaling the daub contour
-    let default_size = daub.contour_size();
-    let default_size = (default_size.0 as f64, default_size.1 as f64);
-    let radius_ratio = 2.0 / (default_size.0.max(default_size.1));
+    let radius_ratio = 2.0 / daub.max_size();
 
-    // Start with an empty range contour
-    let brush_contour = ColumnRangeContour::default();
+    // Start with no contours: there's one contour for each variation, along with the shape traced from it
+    let brush_contours  = BTreeMap::<_, ColumnRangeContour>::new();
+    let traced_shapes   = BTreeMap::<_, (Arc<ShapeWithProperties>, Arc<Vec<Draw>>)>::new();
 
     // Prepare the points for reading
     let points = Box::pin(points);
 
     // Stream the points and generate the shapes that result from each set
-    stream::unfold((brush_contour, daub, points), move |(mut brush_contour, daub, mut points)| async move {
+    stream::unfold((brush_contours, traced_shapes, daub, points), move |(mut brush_contours, mut traced_shapes, daub, mut points)| async move {
         // Fetch the next batch of points
         let Some(next_points) = points.next().await else { return None };
 
         // Add to our contour to build the brush stroke
+        let mut changed_keys = BTreeSet::new();
+
         for point in next_points {
-            // Scale the daub
-            let radius  = point.daub_radius.unwrap_or(1.0);
-            let scale   = radius_ratio * radius;
-            let daub    = ScaledContour::from_contour(&*daub, scale, (0.0, 0.0));
+            // Rotate and scale the daub
+            let contour         = daub.contour_for_angle(daub_rotation(&point.daub_transform));
+            let default_size    = contour.contour_size();
+            let default_size    = (default_size.0 as f64, default_size.1 as f64);
+            let radius          = point.daub_radius.unwrap_or(1.0);
+            let scale           = radius_ratio * radius;
+            let scaled_daub     = ScaledContour::from_contour(&*contour, scale, (0.0, 0.0));
 
             // Center around the x, y position
             let x = point.position.0 - default_size.0*scale*0.5;
             let y = point.position.1 - default_size.1*scale*0.5;
 
-            // Add to the brush contour
-            brush_contour.add_contour(&daub, (x, y));
+            // Add to the brush contour for this variation
+            let key = daub_variation_key(&point);
+            brush_contours.entry(key)
+                .or_insert_with(|| ColumnRangeContour::default())
+                .add_contour(&scaled_daub, (x, y));
+            changed_keys.insert(key);
+        }
+
+        // Only the variations that had daubs added need to be traced again
+        for key in changed_keys {
+            let path = trace_paths_from_intercepts::<WorkingSubpath>(&brush_contours[&key], max_error);
+            traced_shapes.insert(key, daub_variation_shape(key, &path));
         }
 
         // Generate a shape for the current set of daubs
-        let path    = trace_paths_from_intercepts::<WorkingSubpath>(&brush_contour, max_error);
-        let shape   = CanvasShape::Path(WorkingSubpath::to_canvas_path(&path));
-        let shape   = ShapeWithProperties {
-            shape:      shape,
-            shape_type: ShapeType::default(),
-            shape_time: FrameTime::from_nanos(0),
-            properties: Arc::new(vec![]),
-            group:      vec![],
+        let no_variation_key    = daub_variation_key(&BrushPoint::default());
+
+        let shape = if traced_shapes.len() <= 1 && traced_shapes.keys().next().map(|key| *key == no_variation_key).unwrap_or(true) {
+            // A single path with no variation
+            let path = traced_shapes.values().next()
+                .map(|(shape, _)| shape.shape.clone())
+                .unwrap_or_else(|| CanvasShape::Path(WorkingSubpath::to_canvas_path(&[])));
+
+            ShapeWithProperties {
+                shape:      path,
+                shape_type: ShapeType::default(),
+                shape_time: FrameTime::from_nanos(0),
+                properties: Arc::new(vec![]),
+                group:      vec![],
+            }
+        } else {
+            // A group of paths, each with its own variation (these are drawn using the fill colour of the group until a colour is set with shape_with_fill_color())
+            ShapeWithProperties {
+                shape:      CanvasShape::Group,
+                shape_type: ShapeType::default(),
+                shape_time: FrameTime::from_nanos(0),
+                properties: Arc::new(vec![]),
+                group:      traced_shapes.values().cloned().collect(),
+            }
         };
 
-        Some((shape, (brush_contour, daub, points)))
+        Some((shape, (brush_contours, traced_shapes, daub, points)))
     })
 }
 
+///
+/// Creates the shape and drawing for the daubs with a particular variation in a brush stroke
+///
+fn daub_variation_shape(key: (i64, i64, i64, i64), path: &[WorkingSubpath]) -> (Arc<ShapeWithProperties>, Arc<Vec<Draw>>) {
+    let mut drawing = vec![];
+    drawing.new_path();
+    path.iter().for_each(|subpath| drawing.bezier_path(subpath));
+    drawing.fill();
+
+    let shape = ShapeWithProperties {
+        shape:      CanvasShape::Path(WorkingSubpath::to_canvas_path(path)),
+        shape_type: ShapeType::default(),
+        shape_time: FrameTime::from_nanos(0),
+        properties: Arc::new(color_variation_for_key(key).to_properties()),
+        group:      vec![],
+    };
+
+    (Arc::new(shape), Arc::new(drawing))
+}
+
+///
+/// Sets the fill colour of a shape generated by a brush stream
+///
+/// For shapes with a group generated by `daub_brush_stream()`, this also sets the colour of the shapes in the group, applying their colour variation.
+///
+pub fn shape_with_fill_color(shape: ShapeWithProperties, color: Color) -> ShapeWithProperties {
+    // Replaces the fill colour in a set of properties
+    let with_fill = |properties: Arc<Vec<(CanvasPropertyId, CanvasProperty)>>| {
+        let mut properties = Arc::unwrap_or_clone(properties);
+
+        properties.retain(|(prop, _val)| !FlatFill::used_properties().contains(prop));
+        properties.extend(FlatFill(color).to_properties());
+
+        Arc::new(properties)
+    };
+
+    let mut shape = shape;
+
+    shape.properties    = with_fill(shape.properties);
+    shape.group         = shape.group.into_iter()
+        .map(|(grouped_shape, _old_drawing)| {
+            let mut grouped_shape = Arc::unwrap_or_clone(grouped_shape);
+            grouped_shape.properties = with_fill(grouped_shape.properties);
+
+            // Redraw using the varied colour
+            let fill        = FlatFill(color).with_variation(ColorVariation::from_properties(grouped_shape.properties.iter()));
+            let mut drawing = vec![];
+
+            drawing.new_path();
+            grouped_shape.shape.to_path().iter().for_each(|subpath| drawing.bezier_path(subpath));
+            fill.draw(&mut drawing);
+
+            (Arc::new(grouped_shape), Arc::new(drawing))
+        })
+        .collect();
+
+    shape
+}
+
 ///
 /// Creates a brush stream that generates shapes by fitting the curve and applying an offset according to the radius stored in the points
 ///
@@ -129,3 +460,91 @@ pub fn width_brush_stream<'a>(points: impl 'a + Send + Stream<Item=Vec<BrushPoin
         }
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    ///
+    /// Runs a set of points through the vary stream for some brush settings
+    ///
+    fn vary_points(settings: CoreBrushSettings, points: Vec<BrushPoint>) -> Vec<BrushPoint> {
+        futures::executor::block_on(async {
+            brush_vary_stream(Arc::new(settings), 10.0, stream::iter(points)).collect::<Vec<_>>().await
+        })
+    }
+
+    fn line_of_points(pressure: f64) -> Vec<BrushPoint> {
+        (0..10).map(|idx| BrushPoint { position: (idx as f64 * 5.0, 0.0), pressure: pressure, ..Default::default() }).collect()
+    }
+
+    #[test]
+    fn pressure_varies_radius_and_opacity() {
+        let mut settings = CoreBrushSettings::line_width_brush();
+        settings.pressure_vary.push(BrushVary::Opacity { min: 0.2, max: 1.0, profile: vec![ResponseCurve::linear()] });
+
+        let points = vary_points(settings, line_of_points(0.5));
+
+        for point in points {
+            assert!((point.daub_radius.unwrap() - 5.0).abs() < 0.001, "{:?}", point);
+            assert!((point.opacity.unwrap() - 0.6).abs() < 0.001, "{:?}", point);
+            assert!(point.color_jitter.is_none(), "{:?}", point);
+        }
+    }
+
+    #[test]
+    fn jitter_is_repeatable_and_within_range() {
+        let mut settings = CoreBrushSettings::line_width_brush();
+        settings.pressure_vary.push(BrushVary::HueJitter { min: 0.0, max: 20.0, profile: vec![ResponseCurve::linear()] });
+
+        let first   = vary_points(settings.clone(), line_of_points(1.0));
+        let second  = vary_points(settings, line_of_points(1.0));
+
+        for (a, b) in first.iter().zip(second.iter()) {
+            let (hue, _, _) = a.color_jitter.unwrap();
+
+            assert!(hue.abs() <= 20.0, "{:?}", a);
+            assert!(a.color_jitter == b.color_jitter, "{:?} {:?}", a, b);
+        }
+
+        // The hue should actually vary
+        assert!(first.iter().any(|point| point.color_jitter != first[0].color_jitter));
+    }
+
+    #[test]
+    fn follow_stroke_rotates_daubs() {
+        let mut settings    = CoreBrushSettings::line_width_brush();
+        settings.rotation   = BrushDaubRotation::FollowStroke;
+
+        // Points going straight up should be rotated by 90 degrees
+        let points = (0..4).map(|idx| BrushPoint { position: (0.0, idx as f64 * 5.0), pressure: 1.0, ..Default::default() }).collect();
+        let points = vary_points(settings, points);
+
+        let rotation = daub_rotation(&points[2].daub_transform);
+        assert!((rotation - PI/2.0).abs() < 0.001, "{}", rotation);
+    }
+
+    #[test]
+    fn daubs_with_opacity_generate_a_group() {
+        use flo_curves::arc::*;
+
+        let circle      = Circle::new(WorkingPoint { x: 10.0, y: 10.0 }, 10.0).to_path::<WorkingSubpath>();
+        let daubs       = Arc::new(DaubContours::new(vec![circle], (WorkingPoint { x: 0.0, y: 0.0 }, WorkingPoint { x: 20.0, y: 20.0 })));
+
+        let points      = (0..10).map(|idx| BrushPoint { position: (idx as f64 * 20.0, 0.0), daub_radius: Some(10.0), opacity: Some(if idx < 5 { 1.0 } else { 0.5 }), ..Default::default() }).collect::<Vec<_>>();
+        let shapes      = futures::executor::block_on(daub_brush_stream(daubs, stream::iter(vec![points]), 1.0).collect::<Vec<_>>());
+
+        assert!(shapes.len() == 1);
+        assert!(matches!(shapes[0].shape, CanvasShape::Group), "{:?}", shapes[0].shape);
+        assert!(shapes[0].group.len() == 2, "{:?}", shapes[0].group.len());
+
+        // Setting the colour applies the variation
+        let shape       = shape_with_fill_color(shapes[0].clone(), Color::Rgba(1.0, 0.0, 0.0, 1.0));
+        let opacities   = shape.group.iter()
+            .map(|(shape, _)| ColorVariation::from_properties(shape.properties.iter()).unwrap().opacity)
+            .collect::<Vec<_>>();
+
+        assert!(opacities.contains(&0.5) && opacities.contains(&1.0), "{:?}", opacities);
+        assert!(shape.group.iter().all(|(_, drawing)| drawing.iter().any(|draw| matches!(draw, Draw::FillColor(_)))));
+    }
+}
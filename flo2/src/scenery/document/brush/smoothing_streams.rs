@@ -75,12 +75,13 @@ fn walk_between_brush_points(p1: &BrushPoint, p2: &BrushPoint, p3: &BrushPoint,
     // Interpolate the coordinates
     let coords_curve = interpolate_coords(Coord2(p1.position.0, p1.position.1), Coord2(p2.position.0, p2.position.1), Coord2(p3.position.0, p3.position.1), Coord2(p4.position.0, p4.position.1), tension);
 
-    // Also the pressure, tilt, rotation and flow rate. Optional fields are left as None by this routine
+    // Also the pressure, tilt, rotation, flow rate and speed. Optional fields are left as None by this routine
     let pressure    = interpolate_points(p1.pressure, p2.pressure, p3.pressure, p4.pressure, tension);
     let rotation    = interpolate_points(p1.rotation, p2.rotation, p3.rotation, p4.rotation, tension);
     let flow_rate   = interpolate_points(p1.flow_rate, p2.flow_rate, p3.flow_rate, p4.flow_rate, tension);
     let tilt_x      = interpolate_points(p1.tilt.0, p2.tilt.0, p3.tilt.0, p4.tilt.0, tension);
     let tilt_y      = interpolate_points(p1.tilt.1, p2.tilt.1, p3.tilt.1, p4.tilt.1, tension);
+    let speed       = interpolate_points(p1.speed, p2.speed, p3.speed, p4.speed, tension);
 
     // Create the initial walk
     let walk = walk_curve_evenly(&coords_curve, step_distance, 0.01);
@@ -103,6 +104,7 @@ fn walk_between_brush_points(p1: &BrushPoint, p2: &BrushPoint, p3: &BrushPoint,
             rotation:   rotation(t),
             flow_rate:  flow_rate(t),
             tilt:       (tilt_x(t), tilt_y(t)),
+            speed:      speed(t),
 
             ..Default::default()
         }
@@ -195,10 +197,191 @@ pub fn brush_fill_in_points(distance: f64, input_stream: impl 'static + Send + S
     })
 }
 
+///
+/// Smooths a stream of brush points by averaging the position and pressure of each point with the points before it
+///
+/// When the input stream finishes, the window is emptied out so the stroke catches up with and finishes at the final input point.
+///
+pub fn brush_moving_average(window: usize, input_stream: impl 'static + Send + Stream<Item=BrushPoint>) -> impl 'static + Send + Stream<Item=BrushPoint> {
+    use std::collections::{VecDeque};
+
+    generator_stream(move |yield_fn| async move {
+        use std::pin::{pin};
+
+        let window          = window.max(1);
+        let mut input       = pin!(input_stream);
+        let mut points      = VecDeque::new();
+
+        // Averages the points in the window, taking the other values from the most recent point
+        let average = |points: &VecDeque<BrushPoint>| {
+            let count       = points.len() as f64;
+            let mut result  = *points.back().unwrap();
+
+            result.position = (points.iter().map(|point| point.position.0).sum::<f64>() / count, points.iter().map(|point| point.position.1).sum::<f64>() / count);
+            result.pressure = points.iter().map(|point| point.pressure).sum::<f64>() / count;
+
+            result
+        };
+
+        while let Some(point) = input.next().await {
+            points.push_back(point);
+            if points.len() > window { points.pop_front(); }
+
+            yield_fn(average(&points)).await;
+        }
+
+        // Catch up with the final point
+        while points.len() > 1 {
+            points.pop_front();
+            yield_fn(average(&points)).await;
+        }
+    })
+}
+
+///
+/// Stabilises a stream of brush points using the 'pulled string' (or 'lazy mouse') method
+///
+/// The stroke is drawn as though the pen is being pulled along on a string of the specified length: the brush only moves once the input
+/// point is further than the radius away, and then only far enough to stay within the radius. When the input stream finishes, the
+/// final input point is generated so the stroke finishes at the pen-up position.
+///
+pub fn brush_pulled_string(radius: f64, input_stream: impl 'static + Send + Stream<Item=BrushPoint>) -> impl 'static + Send + Stream<Item=BrushPoint> {
+    generator_stream(move |yield_fn| async move {
+        use std::pin::{pin};
+
+        let mut input = pin!(input_stream);
+
+        // The first point is where the brush starts
+        let Some(first_point) = input.next().await else { return; };
+        yield_fn(first_point).await;
+
+        let mut brush_pos   = first_point.position;
+        let mut last_point  = first_point;
+        let mut moved       = false;
+
+        while let Some(point) = input.next().await {
+            last_point = point;
+
+            let dx          = point.position.0 - brush_pos.0;
+            let dy          = point.position.1 - brush_pos.1;
+            let distance    = (dx*dx + dy*dy).sqrt();
+
+            if distance > radius {
+                // Pull the brush towards the input point until it's on the end of the string
+                let ratio   = (distance - radius) / distance;
+                brush_pos   = (brush_pos.0 + dx*ratio, brush_pos.1 + dy*ratio);
+                moved       = true;
+
+                yield_fn(BrushPoint { position: brush_pos, ..point }).await;
+            } else {
+                moved = false;
+            }
+        }
+
+        // Catch up with the final point
+        if moved || last_point.position != brush_pos {
+            yield_fn(last_point).await;
+        }
+    })
+}
+
+///
+/// Stabilises a stream of brush points by fitting bezier curves to them with the specified tolerance
+///
+/// Points are buffered until the fitted curve is split into more than one section, at which point the earlier sections are generated as
+/// brush points sampled along the curve. When the input stream finishes the remaining points are fitted, so the stroke finishes at the
+/// pen-up position.
+///
+pub fn brush_curve_fit(tolerance: f64, input_stream: impl 'static + Send + Stream<Item=BrushPoint>) -> impl 'static + Send + Stream<Item=BrushPoint> {
+    generator_stream(move |yield_fn| async move {
+        use std::pin::{pin};
+
+        let mut input   = pin!(input_stream);
+        let mut points  = Vec::<BrushPoint>::new();
+
+        // Samples a curve once for each of the input points that it replaces (t=0 is skipped as it's the end of the previous curve)
+        let sample_curve = |curve: &Curve<Coord3>, points: &[BrushPoint]| {
+            let num_points = points.len().max(1);
+
+            (1..=num_points).map(|idx| {
+                let t               = (idx as f64) / (num_points as f64);
+                let Coord3(x, y, p) = curve.point_at_pos(t);
+
+                BrushPoint { position: (x, y), pressure: p.clamp(0.0, 1.0), ..points[(idx-1).min(points.len()-1)] }
+            }).collect::<Vec<_>>()
+        };
+
+        let to_coords = |points: &[BrushPoint]| points.iter().map(|point| Coord3(point.position.0, point.position.1, point.pressure)).collect::<Vec<_>>();
+
+        // The first point is generated as-is
+        let Some(first_point) = input.next().await else { return; };
+        yield_fn(first_point).await;
+        points.push(first_point);
+
+        while let Some(point) = input.next().await {
+            points.push(point);
+            if points.len() < 4 { continue; }
+
+            // Fit the buffered points, and generate the curves that won't change any more
+            let Some(curves) = Curve::<Coord3>::fit_from_points(&to_coords(&points), tolerance) else { continue; };
+            if curves.len() <= 1 { continue; }
+
+            for curve in curves.iter().take(curves.len()-1) {
+                // Find the point nearest to the end of this curve
+                let Coord3(end_x, end_y, _) = curve.end_point();
+                let end_idx                 = points.iter().enumerate()
+                    .min_by(|(_, a), (_, b)| {
+                        let dist_a = (a.position.0-end_x).powi(2) + (a.position.1-end_y).powi(2);
+                        let dist_b = (b.position.0-end_x).powi(2) + (b.position.1-end_y).powi(2);
+
+                        dist_a.total_cmp(&dist_b)
+                    })
+                    .map(|(idx, _)| idx)
+                    .unwrap_or(0)
+                    .max(1);
+
+                for sample in sample_curve(curve, &points[1..=end_idx]) {
+                    yield_fn(sample).await;
+                }
+
+                // The end of this curve becomes the start of the next one
+                let mut end_point       = points[end_idx];
+                end_point.position      = (end_x, end_y);
+                points.drain(0..end_idx);
+                points[0]               = end_point;
+
+                if points.len() < 2 { break; }
+            }
+        }
+
+        // Catch up with the final point by fitting whatever is left
+        if points.len() > 1 {
+            let curves = Curve::<Coord3>::fit_from_points(&to_coords(&points), tolerance).unwrap_or_default();
+
+            if curves.is_empty() {
+                yield_fn(*points.last().unwrap()).await;
+            } else {
+                // Share the points out between the curves
+                let points_per_curve = ((points.len()-1) / curves.len()).max(1);
+
+                for (idx, curve) in curves.iter().enumerate() {
+                    let start   = (1 + idx*points_per_curve).min(points.len()-1);
+                    let end     = if idx == curves.len()-1 { points.len() } else { (start + points_per_curve).min(points.len()) };
+
+                    for sample in sample_curve(curve, &points[start..end.max(start+1)]) {
+                        yield_fn(sample).await;
+                    }
+                }
+            }
+        }
+    })
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use itertools::*;
+    use futures::stream::{BoxStream};
 
     #[test]
     fn interpolate_1d_start_and_end() {
@@ -252,4 +435,62 @@ mod test {
             assert!((p1.distance_to(p2)-3.0).abs() < 0.1, "{:?}", points.iter().map(|p| p.position).collect::<Vec<_>>());
         }
     }
+
+    fn run_stabiliser(points: Vec<(f64, f64)>, stabiliser: impl FnOnce(BoxStream<'static, BrushPoint>) -> BoxStream<'static, BrushPoint>) -> Vec<BrushPoint> {
+        futures::executor::block_on(async {
+            let stream = stream::iter(points.into_iter().map(|(x, y)| BrushPoint { position: (x, y), pressure: 1.0, ..Default::default() }));
+            stabiliser(stream.boxed()).collect::<Vec<_>>().await
+        })
+    }
+
+    fn wobbly_line() -> Vec<(f64, f64)> {
+        (0..50).map(|idx| (idx as f64 * 4.0, if idx % 2 == 0 { 3.0 } else { -3.0 })).collect()
+    }
+
+    #[test]
+    fn moving_average_smooths_and_catches_up() {
+        let points = run_stabiliser(wobbly_line(), |stream| brush_moving_average(4, stream).boxed());
+
+        // The wobble is averaged out in the middle of the stroke
+        assert!(points[10..40].iter().all(|point| point.position.1.abs() < 1.0), "{:?}", points.iter().map(|p| p.position).collect::<Vec<_>>());
+
+        // Finishes at the final point
+        assert!(points.last().unwrap().position == (196.0, -3.0), "{:?}", points.last().unwrap().position);
+    }
+
+    #[test]
+    fn pulled_string_stays_behind_the_pen_and_catches_up() {
+        let input   = (0..20).map(|idx| (idx as f64 * 5.0, 0.0)).collect::<Vec<_>>();
+        let points  = run_stabiliser(input, |stream| brush_pulled_string(20.0, stream).boxed());
+
+        // The brush doesn't move until the pen is 20 units away, and then trails by 20 units
+        assert!(points[0].position == (0.0, 0.0));
+        assert!(points[1].position.0 > 0.0 && (points[1].position.0 - 5.0).abs() < 0.001, "{:?}", points.iter().map(|p| p.position).collect::<Vec<_>>());
+        assert!((points[points.len()-2].position.0 - 75.0).abs() < 0.001, "{:?}", points.iter().map(|p| p.position).collect::<Vec<_>>());
+
+        // Finishes at the final point
+        assert!(points.last().unwrap().position == (95.0, 0.0), "{:?}", points.last().unwrap().position);
+    }
+
+    #[test]
+    fn pulled_string_ignores_small_movements() {
+        let points = run_stabiliser(vec![(0.0, 0.0), (1.0, 1.0), (-1.0, 2.0), (0.0, 1.0)], |stream| brush_pulled_string(10.0, stream).boxed());
+
+        // Just the start point and the catch-up point
+        assert!(points.len() == 2, "{:?}", points.iter().map(|p| p.position).collect::<Vec<_>>());
+        assert!(points.last().unwrap().position == (0.0, 1.0));
+    }
+
+    #[test]
+    fn curve_fit_smooths_and_catches_up() {
+        let points = run_stabiliser(wobbly_line(), |stream| brush_curve_fit(8.0, stream).boxed());
+
+        assert!(points.len() > 2);
+        assert!(points[0].position == (0.0, 3.0));
+        assert!(points.iter().all(|point| point.position.1.abs() <= 8.0), "{:?}", points.iter().map(|p| p.position).collect::<Vec<_>>());
+
+        // Finishes at the final point
+        let (x, y) = points.last().unwrap().position;
+        assert!((x - 196.0).abs() < 0.001 && (y + 3.0).abs() < 0.001, "{:?}", (x, y));
+    }
 }
@@ -0,0 +1,138 @@
+use super::*;
+use crate::scenery::document::canvas::*;
+
+use flo_scene::*;
+use flo_scene::programs::*;
+use flo_scene::commands::*;
+
+use futures::prelude::*;
+use ::serde::*;
+
+fn test_pack() -> BrushPack {
+    BrushPack {
+        brushes: vec![
+            BrushPackEntry::new("Ink", CoreBrushSettings::default()),
+            BrushPackEntry::new("Marker", CoreBrushSettings::line_width_brush()),
+        ]
+    }
+}
+
+#[test]
+fn brush_pack_round_trip() {
+    let pack    = test_pack();
+    let bytes   = pack.to_bytes();
+
+    assert!(bytes.starts_with(b"FLOBRUSH"));
+    assert!(BrushPack::from_bytes(&bytes).unwrap() == pack);
+}
+
+#[test]
+fn reject_data_that_is_not_a_brush_pack() {
+    assert!(BrushPack::from_bytes(b"not a brush pack").err() == Some(BrushPackError::NotABrushPack));
+    assert!(BrushPack::from_bytes(b"FLOBRUSH\x01\x00\x00\x00\xff\xff").err() == Some(BrushPackError::InvalidData));
+}
+
+#[test]
+fn reject_brush_pack_from_newer_version() {
+    let mut bytes = test_pack().to_bytes();
+    bytes[8] = 99;
+
+    assert!(BrushPack::from_bytes(&bytes).err() == Some(BrushPackError::UnsupportedVersion(99)));
+}
+
+#[test]
+fn read_version_1_brush_pack() {
+    // Version 1 packs stored the settings directly, and brush settings didn't have a stabiliser when they were introduced
+    let ink         = BrushPackEntry::new("Ink", CoreBrushSettings::default());
+    let settings    = CoreBrushSettingsV2 { builder: ink.settings.builder.clone(), pressure_vary: ink.settings.pressure_vary.clone(), speed_vary: ink.settings.speed_vary.clone(), tilt_vary: vec![], rotation: BrushDaubRotation::FollowStroke };
+
+    let mut bytes   = b"FLOBRUSH".to_vec();
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend(postcard::to_allocvec(&vec![("Ink".to_string(), settings, ink.preview.clone())]).unwrap());
+
+    let pack        = BrushPack::from_bytes(&bytes).unwrap();
+
+    assert!(pack.brushes.len() == 1);
+    assert!(pack.brushes[0].name == "Ink");
+    assert!(pack.brushes[0].settings.rotation == BrushDaubRotation::FollowStroke);
+    assert!(pack.brushes[0].settings.stabiliser == BrushStabiliser::None);
+    assert!(pack.brushes[0].settings.builder == ink.settings.builder);
+    assert!(pack.brushes[0].preview == ink.preview);
+}
+
+#[test]
+fn read_version_1_brush_pack_with_stabiliser() {
+    let mut settings    = CoreBrushSettings::line_width_brush();
+    settings.stabiliser = BrushStabiliser::CurveFit { tolerance: 2.0 };
+    let marker          = BrushPackEntry::new("Marker", settings.clone());
+
+    let mut bytes       = b"FLOBRUSH".to_vec();
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend(postcard::to_allocvec(&vec![("Marker".to_string(), settings, marker.preview.clone())]).unwrap());
+
+    assert!(BrushPack::from_bytes(&bytes).unwrap() == BrushPack { brushes: vec![marker] });
+}
+
+#[test]
+fn entries_have_preview() {
+    let pack = test_pack();
+
+    assert!(pack.brushes.iter().all(|entry| !entry.preview.is_empty()));
+}
+
+#[test]
+fn brush_properties_round_trip() {
+    let entry       = BrushPackEntry::new("Marker", CoreBrushSettings::line_width_brush());
+    let properties  = entry.to_brush_properties();
+
+    assert!(BrushPackEntry::from_brush_properties(&properties).unwrap() == entry);
+}
+
+#[test]
+fn export_and_import_brushes() {
+    let scene = Scene::default();
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct TestResponse(Vec<String>, usize);
+
+    impl SceneMessage for TestResponse { }
+
+    let test_program    = SubProgramId::new();
+    let library_program = SubProgramId::new();
+
+    let brush           = CanvasBrushId::new();
+    let plain_brush     = CanvasBrushId::new();
+
+    // Export a brush from the document to the library, then import it again
+    scene.add_subprogram(library_program, move |input: InputStream<ImportedBrushes>, context| async move {
+        let _sqlite         = context.send::<SqliteCanvasRequest>(()).unwrap();
+        let mut canvas      = context.send(()).unwrap();
+        let mut library     = context.send(()).unwrap();
+
+        let entry = BrushPackEntry::new("Marker", CoreBrushSettings::line_width_brush());
+
+        canvas.send(VectorCanvas::AddBrush(brush)).await.unwrap();
+        canvas.send(VectorCanvas::SetProperty(CanvasPropertyTarget::Brush(brush), entry.to_brush_properties())).await.unwrap();
+        canvas.send(VectorCanvas::AddBrush(plain_brush)).await.unwrap();
+
+        library.send(BrushLibrary::ExportFromDocument(vec![brush, plain_brush])).await.unwrap();
+        library.send(BrushLibrary::ImportToDocument(library_program.into(), vec!["Marker".to_string()])).await.unwrap();
+
+        let mut input                       = input;
+        let ImportedBrushes(imported)       = input.next().await.unwrap();
+        let imported_ids                    = imported.iter().map(|(_, brush_id)| *brush_id).collect::<Vec<_>>();
+
+        // The imported brush should have a new ID and the same settings as the original
+        let brushes = context.spawn_query(ReadCommand::default(), VectorQuery::Brushes(().into(), imported_ids), ()).unwrap();
+        let brushes = brushes.collect::<Vec<_>>().await;
+        let num_brushes = brushes.iter()
+            .filter(|response| matches!(response, VectorResponse::Brush(brush_id, properties) if *brush_id != brush && BrushPackEntry::from_brush_properties(properties).as_ref() == Some(&entry)))
+            .count();
+
+        context.send_message(TestResponse(imported.into_iter().map(|(name, _)| name).collect(), num_brushes)).await.unwrap();
+    }, 1);
+
+    TestBuilder::new()
+        .expect_message_matching(TestResponse(vec!["Marker".to_string()], 1), "Brush should be exported to the library and imported into the document again")
+        .run_in_scene(&scene, test_program);
+}
@@ -11,6 +11,7 @@ pub static PROP_STROKE_COLOR: LazyCanvasPropertyId      = LazyCanvasPropertyId::
 pub static PROP_STROKE_LINECAP: LazyCanvasPropertyId    = LazyCanvasPropertyId::new("flowbetween::stroke_linecap");
 pub static PROP_STROKE_LINEJOIN: LazyCanvasPropertyId   = LazyCanvasPropertyId::new("flowbetween::stroke_linejoin");
 pub static PROP_STROKE_WIDTH: LazyCanvasPropertyId      = LazyCanvasPropertyId::new("flowbetween::stroke_width");
+pub static PROP_COLOR_VARIATION: LazyCanvasPropertyId   = LazyCanvasPropertyId::new("flowbetween::color_variation");
 
 ///
 /// Property applied to a shape that should have a flat fill
@@ -30,6 +31,24 @@ pub struct StrokeWidth(pub f64);
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Stroke(pub StrokeWidth, pub LineCap, pub LineJoin, pub Color);
 
+///
+/// Variation applied to the fill colour of a shape (used by brushes that vary the colour and opacity along a stroke)
+///
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ColorVariation {
+    /// The opacity to multiply the alpha value of the colour by
+    pub opacity: f64,
+
+    /// The amount to rotate the hue by, in degrees
+    pub hue: f64,
+
+    /// The amount to add to the saturation (which ranges from 0.0 to 1.0)
+    pub saturation: f64,
+
+    /// The amount to add to the lightness (which ranges from 0.0 to 1.0)
+    pub lightness: f64,
+}
+
 ///
 /// Returns the type of a color property (a color has a type and a value property)
 ///
@@ -207,7 +226,63 @@ impl FromCanvasProperties for Stroke {
     }
 }
 
+impl ToCanvasProperties for ColorVariation {
+    fn to_properties(&self) -> Vec<(CanvasPropertyId, CanvasProperty)> {
+        vec![
+            (*PROP_COLOR_VARIATION, CanvasProperty::FloatList(vec![self.opacity as _, self.hue as _, self.saturation as _, self.lightness as _])),
+        ]
+    }
+}
+
+impl FromCanvasProperties for ColorVariation {
+    fn used_properties() -> Vec<CanvasPropertyId> {
+        vec![*PROP_COLOR_VARIATION]
+    }
+
+    fn from_properties<'a>(properties: impl Iterator<Item=&'a (CanvasPropertyId, CanvasProperty)>) -> Option<Self> {
+        let mut variation = None;
+
+        for (prop_id, prop_val) in properties {
+            if *prop_id == *PROP_COLOR_VARIATION { variation = Some(prop_val); }
+        }
+
+        match variation? {
+            CanvasProperty::FloatList(vals) => { let (opacity, hue, saturation, lightness) = four_floats(vals)?; Some(ColorVariation { opacity, hue, saturation, lightness }) }
+            _                               => None,
+        }
+    }
+}
+
+impl ColorVariation {
+    ///
+    /// Applies this variation to a colour
+    ///
+    pub fn apply(&self, color: &Color) -> Color {
+        let (h, s, l, a) = color.to_hsluv_components();
+
+        // Hsluv colours have saturation and lightness values between 0 and 100
+        let h = (h + self.hue as f32).rem_euclid(360.0);
+        let s = (s + (self.saturation as f32)*100.0).clamp(0.0, 100.0);
+        let l = (l + (self.lightness as f32)*100.0).clamp(0.0, 100.0);
+        let a = (a * self.opacity as f32).clamp(0.0, 1.0);
+
+        Color::Hsluv(h, s, l, a)
+    }
+}
+
 impl FlatFill {
+    ///
+    /// Returns this fill with a colour variation applied to it
+    ///
+    #[inline]
+    pub fn with_variation(&self, variation: Option<ColorVariation>) -> FlatFill {
+        if let Some(variation) = variation {
+            FlatFill(variation.apply(&self.0))
+        } else {
+            *self
+        }
+    }
+
     ///
     /// Renders the current shape using this fill
     ///
@@ -268,6 +343,24 @@ mod test {
         assert!(Some(original_stroke) == stroke_from_props, "{:?} != {:?}", Some(original_stroke), stroke_from_props);
     }
 
+    #[test]
+    pub fn round_trip_color_variation() {
+        let original_variation      = ColorVariation { opacity: 0.5, hue: 30.0, saturation: -0.25, lightness: 0.125 };
+        let properties              = original_variation.to_properties();
+        let variation_from_props    = ColorVariation::from_properties(properties.iter());
+
+        assert!(Some(original_variation) == variation_from_props, "{:?} != {:?}", Some(original_variation), variation_from_props);
+    }
+
+    #[test]
+    pub fn color_variation_multiplies_opacity() {
+        let variation           = ColorVariation { opacity: 0.5, hue: 0.0, saturation: 0.0, lightness: 0.0 };
+        let (r, g, b, a)        = variation.apply(&Color::Rgba(1.0, 0.0, 0.0, 0.8)).to_rgba_components();
+
+        assert!((a - 0.4).abs() < 0.001, "{}", a);
+        assert!((r - 1.0).abs() < 0.01 && g.abs() < 0.01 && b.abs() < 0.01, "{:?}", (r, g, b));
+    }
+
     #[test]
     pub fn stroke_from_properties() {
         let original_stroke     = Stroke(StrokeWidth(42.0), LineCap::Round, LineJoin::Bevel, Color::Rgba(0.4, 0.3, 0.2, 0.1));
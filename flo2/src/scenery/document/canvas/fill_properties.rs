@@ -0,0 +1,302 @@
+use super::point::*;
+use super::property::*;
+
+use flo_draw::canvas::*;
+
+use ::serde::*;
+
+use std::collections::{HashMap, VecDeque};
+use std::collections::hash_map::{DefaultHasher};
+use std::hash::{Hash, Hasher};
+use std::sync::*;
+
+pub static PROP_FILL_GRADIENT: LazyCanvasPropertyId    = LazyCanvasPropertyId::new("flowbetween::fill_gradient");
+pub static PROP_FILL_PATTERN: LazyCanvasPropertyId     = LazyCanvasPropertyId::new("flowbetween::fill_pattern");
+
+/// Largest size in pixels of a side of the texture used to draw a radial gradient
+const MAX_RADIAL_GRADIENT_TEXTURE_SIZE: f32 = 4096.0;
+
+/// Number of radial gradient textures that are kept by `RadialGradientTextures`
+const RADIAL_GRADIENT_CACHE_SIZE: usize = 64;
+
+///
+/// A colour stop in a gradient
+///
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GradientStop {
+    /// Position of this stop along the gradient, from 0.0 (the start) to 1.0 (the end)
+    pub position: f32,
+
+    /// The colour at this stop
+    pub color: Color,
+}
+
+///
+/// Property applied to a shape that should be filled with a gradient
+///
+/// Gradients are stored as a single blob property, so any number of stops can be used
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GradientFill {
+    /// A gradient that changes colour along the line from `start` to `end`
+    Linear { start: CanvasPoint, end: CanvasPoint, stops: Vec<GradientStop> },
+
+    /// A gradient that changes colour from the center point out to the radius
+    Radial { center: CanvasPoint, radius: f32, stops: Vec<GradientStop> },
+}
+
+///
+/// Property applied to a shape that should be filled with a repeating image
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternFill {
+    /// Width of the pattern image in pixels
+    pub width: u32,
+
+    /// Height of the pattern image in pixels
+    pub height: u32,
+
+    /// The pattern image, as 8-bit RGBA values (`width * height * 4` bytes)
+    pub pixels: Vec<u8>,
+
+    /// Where the top-left corner of the pattern is placed on the canvas
+    pub origin: CanvasPoint,
+
+    /// The size of a pixel of the pattern in canvas units
+    pub scale: f32,
+}
+
+///
+/// Cache of the pixels generated for the textures used to draw radial gradients
+///
+/// Radial gradients are generated on the CPU, so this stops them from being regenerated every time a shape is rendered.
+///
+#[derive(Default)]
+pub struct RadialGradientTextures {
+    /// The pixels for each texture, indexed by texture ID
+    pixels: HashMap<u64, Arc<Vec<u8>>>,
+
+    /// The textures in the cache, oldest first
+    order: VecDeque<u64>,
+}
+
+///
+/// Encodes a value as a blob property
+///
+fn blob_property(value: &impl Serialize) -> CanvasProperty {
+    CanvasProperty::ByteList(postcard::to_allocvec(value).unwrap_or_default())
+}
+
+///
+/// Decodes a value from a blob property
+///
+fn from_blob_property<T: for<'de> Deserialize<'de>>(property: &CanvasProperty) -> Option<T> {
+    match property {
+        CanvasProperty::ByteList(bytes) => postcard::from_bytes(bytes).ok(),
+        _                               => None,
+    }
+}
+
+///
+/// Generates the ID for the gradient or texture used by a fill from its definition
+///
+/// Fills with different definitions get different IDs so they can be drawn on the same layer, and a fill that's drawn again
+/// just redefines its own gradient or texture.
+///
+fn fill_id(definition: &impl Serialize) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    postcard::to_allocvec(definition).unwrap_or_default().hash(&mut hasher);
+
+    hasher.finish()
+}
+
+///
+/// Finds the colour at a position in a list of gradient stops
+///
+pub fn gradient_color_at(stops: &[GradientStop], position: f32) -> Color {
+    let Some(first) = stops.first() else { return Color::Rgba(0.0, 0.0, 0.0, 0.0); };
+    let last        = stops.last().unwrap();
+
+    if position <= first.position { return first.color; }
+    if position >= last.position { return last.color; }
+
+    // Interpolate between the stops either side of the position
+    for pair in stops.windows(2) {
+        let (before, after) = (&pair[0], &pair[1]);
+
+        if position >= before.position && position <= after.position {
+            let range   = after.position - before.position;
+            let ratio   = if range > 0.0 { (position - before.position) / range } else { 0.0 };
+
+            let (r1, g1, b1, a1) = before.color.to_rgba_components();
+            let (r2, g2, b2, a2) = after.color.to_rgba_components();
+
+            return Color::Rgba(r1 + (r2-r1)*ratio, g1 + (g2-g1)*ratio, b1 + (b2-b1)*ratio, a1 + (a2-a1)*ratio);
+        }
+    }
+
+    last.color
+}
+
+impl ToCanvasProperties for GradientFill {
+    fn to_properties(&self) -> Vec<(CanvasPropertyId, CanvasProperty)> {
+        vec![
+            (*PROP_FILL_GRADIENT, blob_property(self)),
+        ]
+    }
+}
+
+impl FromCanvasProperties for GradientFill {
+    fn used_properties() -> Vec<CanvasPropertyId> {
+        vec![*PROP_FILL_GRADIENT]
+    }
+
+    fn from_properties<'a>(properties: impl Iterator<Item=&'a (CanvasPropertyId, CanvasProperty)>) -> Option<Self> {
+        let mut gradient = None;
+
+        for (prop_id, prop_val) in properties {
+            if *prop_id == *PROP_FILL_GRADIENT { gradient = Some(prop_val); }
+        }
+
+        from_blob_property(gradient?)
+    }
+}
+
+impl ToCanvasProperties for PatternFill {
+    fn to_properties(&self) -> Vec<(CanvasPropertyId, CanvasProperty)> {
+        vec![
+            (*PROP_FILL_PATTERN, blob_property(self)),
+        ]
+    }
+}
+
+impl FromCanvasProperties for PatternFill {
+    fn used_properties() -> Vec<CanvasPropertyId> {
+        vec![*PROP_FILL_PATTERN]
+    }
+
+    fn from_properties<'a>(properties: impl Iterator<Item=&'a (CanvasPropertyId, CanvasProperty)>) -> Option<Self> {
+        let mut pattern = None;
+
+        for (prop_id, prop_val) in properties {
+            if *prop_id == *PROP_FILL_PATTERN { pattern = Some(prop_val); }
+        }
+
+        let pattern: PatternFill = from_blob_property(pattern?)?;
+
+        // Patterns with the wrong number of pixels can't be rendered
+        if pattern.pixels.len() == (pattern.width as usize) * (pattern.height as usize) * 4 && pattern.width > 0 && pattern.height > 0 {
+            Some(pattern)
+        } else {
+            None
+        }
+    }
+}
+
+impl RadialGradientTextures {
+    ///
+    /// Retrieves the pixels for a texture, generating them if they're not in the cache
+    ///
+    fn pixels(&mut self, texture_id: u64, generate: impl FnOnce() -> Vec<u8>) -> Arc<Vec<u8>> {
+        if let Some(pixels) = self.pixels.get(&texture_id) {
+            return Arc::clone(pixels);
+        }
+
+        // Make room for the new texture by discarding the oldest one
+        if self.order.len() >= RADIAL_GRADIENT_CACHE_SIZE {
+            if let Some(oldest) = self.order.pop_front() {
+                self.pixels.remove(&oldest);
+            }
+        }
+
+        let pixels = Arc::new(generate());
+        self.pixels.insert(texture_id, Arc::clone(&pixels));
+        self.order.push_back(texture_id);
+
+        pixels
+    }
+}
+
+impl GradientFill {
+    ///
+    /// Renders the current shape using this gradient
+    ///
+    /// `bounds` is the bounding box of the current path (min, max). Radial gradients are drawn as a texture covering the bounds, as
+    /// the canvas only has linear gradients. The texture is generated at `pixel_scale` pixels per canvas unit, and is stored in
+    /// `textures` so it can be re-used the next time the shape is drawn at the same scale.
+    ///
+    pub fn draw(&self, bounds: (CanvasPoint, CanvasPoint), pixel_scale: f32, textures: &mut RadialGradientTextures, drawing: &mut impl GraphicsContext) {
+        match self {
+            GradientFill::Linear { start, end, stops } => {
+                let Some(first) = stops.first() else { return; };
+                let gradient_id = GradientId(fill_id(self));
+
+                drawing.create_gradient(gradient_id, first.color);
+                stops.iter()
+                    .for_each(|stop| drawing.gradient_stop(gradient_id, stop.position, stop.color));
+
+                drawing.fill_gradient(gradient_id, start.x, start.y, end.x, end.y);
+                drawing.fill();
+            }
+
+            GradientFill::Radial { center, radius, stops } => {
+                if stops.is_empty() { return; }
+
+                let (min, max)  = bounds;
+                let size        = (max.x - min.x).max(max.y - min.y);
+                if size <= 0.0 { return; }
+
+                // The texture covers the bounds at the render scale (limited so very large or zoomed in shapes don't generate huge textures)
+                let pixel_scale = pixel_scale.max(0.0).min(MAX_RADIAL_GRADIENT_TEXTURE_SIZE / size);
+                let width       = ((max.x - min.x) * pixel_scale).ceil().max(1.0) as u32;
+                let height      = ((max.y - min.y) * pixel_scale).ceil().max(1.0) as u32;
+                let texture_id  = fill_id(&(self, min, max, width, height));
+
+                let pixels = textures.pixels(texture_id, || {
+                    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+
+                    // Rows go from the top of the bounds downwards (canvas coordinates have y going upwards)
+                    for row in 0..height {
+                        let y = max.y - ((row as f32) + 0.5) / (height as f32) * (max.y - min.y);
+
+                        for col in 0..width {
+                            let x           = min.x + ((col as f32) + 0.5) / (width as f32) * (max.x - min.x);
+                            let distance    = ((x - center.x).powi(2) + (y - center.y).powi(2)).sqrt();
+                            let position    = if *radius > 0.0 { distance / radius } else { 1.0 };
+
+                            let (r, g, b, a) = gradient_color_at(stops, position).to_rgba_components();
+                            pixels.extend([r, g, b, a].iter().map(|component| (component.clamp(0.0, 1.0) * 255.0).round() as u8));
+                        }
+                    }
+
+                    pixels
+                });
+
+                let texture_id = TextureId(texture_id);
+
+                drawing.create_texture(texture_id, width, height, TextureFormat::Rgba);
+                drawing.set_texture_bytes(texture_id, 0, 0, width, height, pixels);
+                drawing.fill_texture(texture_id, min.x, min.y, max.x, max.y);
+                drawing.fill();
+            }
+        }
+    }
+}
+
+impl PatternFill {
+    ///
+    /// Renders the current shape using this pattern
+    ///
+    pub fn draw(&self, drawing: &mut impl GraphicsContext) {
+        let width       = self.width as f32 * self.scale;
+        let height      = self.height as f32 * self.scale;
+        let texture_id  = TextureId(fill_id(self));
+
+        drawing.create_texture(texture_id, self.width, self.height, TextureFormat::Rgba);
+        drawing.set_texture_bytes(texture_id, 0, 0, self.width, self.height, Arc::new(self.pixels.clone()));
+
+        // Canvas coordinates have y going upwards, so the pattern extends down from its origin
+        drawing.fill_texture(texture_id, self.origin.x, self.origin.y - height, self.origin.x + width, self.origin.y);
+        drawing.fill();
+    }
+}
@@ -0,0 +1,182 @@
+///
+/// Serialization tests for the gradient and pattern fill properties
+///
+
+use crate::scenery::document::canvas::*;
+
+use flo_draw::canvas::*;
+
+use std::sync::*;
+
+/// Helper to create a test CanvasPoint
+fn test_point(x: f32, y: f32) -> CanvasPoint {
+    CanvasPoint { x, y }
+}
+
+/// Helper to create a list of gradient stops going from red to green to blue
+fn test_stops() -> Vec<GradientStop> {
+    vec![
+        GradientStop { position: 0.0, color: Color::Rgba(1.0, 0.0, 0.0, 1.0) },
+        GradientStop { position: 0.5, color: Color::Rgba(0.0, 1.0, 0.0, 1.0) },
+        GradientStop { position: 1.0, color: Color::Rgba(0.0, 0.0, 1.0, 0.5) },
+    ]
+}
+
+// ============================================
+// Round-trip tests
+// ============================================
+
+#[test]
+fn linear_gradient_round_trip() {
+    let gradient    = GradientFill::Linear { start: test_point(0.0, 0.0), end: test_point(100.0, 50.0), stops: test_stops() };
+    let properties  = gradient.to_properties();
+
+    assert_eq!(properties.len(), 1);
+    assert!(matches!(properties[0].1, CanvasProperty::ByteList(_)));
+    assert_eq!(GradientFill::from_properties(properties.iter()), Some(gradient));
+}
+
+#[test]
+fn radial_gradient_round_trip() {
+    let gradient    = GradientFill::Radial { center: test_point(-20.0, 30.0), radius: 45.0, stops: test_stops() };
+    let properties  = gradient.to_properties();
+
+    assert_eq!(GradientFill::from_properties(properties.iter()), Some(gradient));
+}
+
+#[test]
+fn pattern_round_trip() {
+    let pattern = PatternFill {
+        width:  2,
+        height: 2,
+        pixels: vec![255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 255, 255, 255, 0],
+        origin: test_point(10.0, 20.0),
+        scale:  4.0,
+    };
+    let properties = pattern.to_properties();
+
+    assert_eq!(properties.len(), 1);
+    assert_eq!(PatternFill::from_properties(properties.iter()), Some(pattern));
+}
+
+#[test]
+fn fills_round_trip_alongside_flat_fill() {
+    let gradient        = GradientFill::Linear { start: test_point(0.0, 0.0), end: test_point(0.0, 10.0), stops: test_stops() };
+    let flat            = FlatFill(Color::Rgba(0.1, 0.2, 0.3, 1.0));
+    let mut properties  = flat.to_properties();
+    properties.extend(gradient.to_properties());
+
+    assert_eq!(FlatFill::from_properties(properties.iter()), Some(flat));
+    assert_eq!(GradientFill::from_properties(properties.iter()), Some(gradient));
+    assert_eq!(PatternFill::from_properties(properties.iter()), None);
+}
+
+// ============================================
+// Invalid data tests
+// ============================================
+
+#[test]
+fn pattern_with_wrong_pixel_count_is_rejected() {
+    let pattern = PatternFill {
+        width:  4,
+        height: 4,
+        pixels: vec![0; 12],
+        origin: test_point(0.0, 0.0),
+        scale:  1.0,
+    };
+    let properties = pattern.to_properties();
+
+    assert_eq!(PatternFill::from_properties(properties.iter()), None);
+}
+
+#[test]
+fn gradient_from_corrupt_blob_is_rejected() {
+    let properties = vec![(CanvasPropertyId::new("flowbetween::fill_gradient"), CanvasProperty::ByteList(vec![42, 255, 255, 255]))];
+
+    assert_eq!(GradientFill::from_properties(properties.iter()), None);
+}
+
+// ============================================
+// Gradient colour tests
+// ============================================
+
+#[test]
+fn gradient_color_interpolates_between_stops() {
+    let stops = test_stops();
+
+    let (r, g, b, _a) = gradient_color_at(&stops, 0.25).to_rgba_components();
+    assert!((r - 0.5).abs() < 0.001 && (g - 0.5).abs() < 0.001 && b.abs() < 0.001, "{:?}", (r, g, b));
+
+    let (_r, _g, _b, a) = gradient_color_at(&stops, 0.75).to_rgba_components();
+    assert!((a - 0.75).abs() < 0.001, "{:?}", a);
+}
+
+#[test]
+fn gradient_color_clamps_outside_stops() {
+    let stops = test_stops();
+
+    assert_eq!(gradient_color_at(&stops, -1.0), stops[0].color);
+    assert_eq!(gradient_color_at(&stops, 2.0), stops[2].color);
+}
+
+// ============================================
+// Drawing tests
+// ============================================
+
+#[test]
+fn radial_gradient_is_one_fill() {
+    let gradient    = GradientFill::Radial { center: test_point(50.0, 50.0), radius: 50.0, stops: test_stops() };
+    let mut drawing = vec![];
+    gradient.draw((test_point(0.0, 0.0), test_point(100.0, 100.0)), 1.0, &mut RadialGradientTextures::default(), &mut drawing);
+
+    assert_eq!(drawing.iter().filter(|draw| matches!(draw, Draw::Fill)).count(), 1, "{:?}", drawing);
+}
+
+/// Helper to draw a radial gradient, returning the size of its texture and its pixels
+fn radial_texture(gradient: &GradientFill, pixel_scale: f32, textures: &mut RadialGradientTextures) -> (TextureSize, Arc<Vec<u8>>) {
+    let mut drawing = vec![];
+    gradient.draw((test_point(0.0, 0.0), test_point(100.0, 50.0)), pixel_scale, textures, &mut drawing);
+
+    let size    = drawing.iter().filter_map(|draw| match draw { Draw::Texture(_, TextureOp::Create(size, _)) => Some(*size), _ => None }).next().unwrap();
+    let pixels  = drawing.iter().filter_map(|draw| match draw { Draw::Texture(_, TextureOp::SetBytes(_, _, pixels)) => Some(Arc::clone(pixels)), _ => None }).next().unwrap();
+
+    (size, pixels)
+}
+
+#[test]
+fn radial_gradient_texture_follows_render_scale() {
+    let gradient        = GradientFill::Radial { center: test_point(50.0, 25.0), radius: 50.0, stops: test_stops() };
+    let mut textures    = RadialGradientTextures::default();
+
+    assert_eq!(radial_texture(&gradient, 1.0, &mut textures).0, TextureSize(100, 50));
+    assert_eq!(radial_texture(&gradient, 4.0, &mut textures).0, TextureSize(400, 200));
+}
+
+#[test]
+fn radial_gradient_texture_is_cached() {
+    let gradient        = GradientFill::Radial { center: test_point(50.0, 25.0), radius: 50.0, stops: test_stops() };
+    let mut textures    = RadialGradientTextures::default();
+
+    let (_, first)      = radial_texture(&gradient, 2.0, &mut textures);
+    let (_, second)     = radial_texture(&gradient, 2.0, &mut textures);
+
+    assert!(Arc::ptr_eq(&first, &second));
+}
+
+#[test]
+fn different_gradients_use_different_ids() {
+    let gradient_1  = GradientFill::Linear { start: test_point(0.0, 0.0), end: test_point(100.0, 50.0), stops: test_stops() };
+    let gradient_2  = GradientFill::Linear { start: test_point(0.0, 0.0), end: test_point(0.0, 50.0), stops: test_stops() };
+    let bounds      = (test_point(0.0, 0.0), test_point(100.0, 100.0));
+
+    let gradient_id = |gradient: &GradientFill| {
+        let mut drawing = vec![];
+        gradient.draw(bounds, 1.0, &mut RadialGradientTextures::default(), &mut drawing);
+
+        drawing.iter().filter_map(|draw| match draw { Draw::Gradient(gradient_id, _) => Some(*gradient_id), _ => None }).next()
+    };
+
+    assert!(gradient_id(&gradient_1).is_some());
+    assert_ne!(gradient_id(&gradient_1), gradient_id(&gradient_2));
+    assert_eq!(gradient_id(&gradient_1), gradient_id(&gradient_1.clone()));
+}
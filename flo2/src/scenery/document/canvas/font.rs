@@ -0,0 +1,31 @@
+use flo_draw::canvas::*;
+
+use std::collections::*;
+use std::sync::*;
+
+/// The name of the font that is always available for text shapes
+pub const DEFAULT_CANVAS_FONT: &str = "flowbetween::lato";
+
+/// Maps font names to the font faces that text shapes are rendered with
+static CANVAS_FONTS: LazyLock<Mutex<HashMap<String, Arc<CanvasFontFace>>>> = LazyLock::new(|| {
+    let mut fonts = HashMap::new();
+    fonts.insert(DEFAULT_CANVAS_FONT.to_string(), CanvasFontFace::from_slice(include_bytes!("../../../../../static_files/fonts/lato/Lato-Regular.ttf")));
+
+    Mutex::new(fonts)
+});
+
+///
+/// Makes a font available to text shapes using the specified name
+///
+/// Text shapes refer to their font by name, so the same font needs to be registered with the same name before a document that uses it is rendered
+///
+pub fn register_canvas_font(name: &str, font: Arc<CanvasFontFace>) {
+    CANVAS_FONTS.lock().unwrap().insert(name.to_string(), font);
+}
+
+///
+/// Retrieves the font face that has been registered with a particular name
+///
+pub fn canvas_font(name: &str) -> Option<Arc<CanvasFontFace>> {
+    CANVAS_FONTS.lock().unwrap().get(name).cloned()
+}
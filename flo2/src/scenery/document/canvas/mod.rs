@@ -1,8 +1,10 @@
 mod basic_properties;
+mod fill_properties;
 mod name_property;
 mod brush;
 mod document_properties;
 mod error;
+mod font;
 mod frame_time;
 mod shape;
 mod layer;
@@ -12,14 +14,17 @@ mod queries;
 mod render;
 mod shape_type;
 mod sqlite;
+mod undo;
 mod vector_editor;
 mod vector_editor_sugar;
 
 pub use basic_properties::*;
+pub use fill_properties::*;
 pub use name_property::*;
 pub use brush::*;
 pub use document_properties::*;
 pub use error::*;
+pub use font::*;
 pub use frame_time::*;
 pub use shape::*;
 pub use layer::*;
@@ -29,5 +34,9 @@ pub use queries::*;
 pub use render::*;
 pub use shape_type::*;
 pub use sqlite::*;
+pub use undo::*;
 pub use vector_editor::*;
 pub use vector_editor_sugar::*;
+
+#[cfg(test)]
+mod fill_serialization_tests;
@@ -30,6 +30,12 @@ pub enum VectorQuery {
 
     /// Queries the properties of the specified set of brushes
     Brushes(StreamTarget, Vec<CanvasBrushId>),
+
+    /// Queries the full definitions of a set of shapes, including the brushes attached to them and the shapes in their groups
+    ///
+    /// Unlike `Shapes`, the properties returned are just the ones set on the shape itself, and each shape is followed by a
+    /// `ShapeBrushes` response if it has any brushes, then by any child shapes between `StartGroup` and `EndGroup`.
+    ShapeDefinitions(StreamTarget, Vec<CanvasShapeId>),
 }
 
 ///
@@ -56,6 +62,9 @@ pub enum VectorResponse {
     /// shape itself, along with any attached brushes
     Shape(CanvasShapeId, CanvasShape, FrameTime, ShapeType, Vec<(CanvasPropertyId, CanvasProperty)>),
 
+    /// The brushes attached to the previous shape, in the order they were attached
+    ShapeBrushes(Vec<CanvasBrushId>),
+
     /// The following shapes are parented to the previous shape
     StartGroup,
 
@@ -79,11 +88,12 @@ impl QueryRequest for VectorQuery {
         use VectorQuery::*;
 
         match self {
-            WholeDocument(_target, when)    => WholeDocument(new_target, when),
-            DocumentOutline(_target)        => DocumentOutline(new_target),
-            Layers(_target, layers, when)   => Layers(new_target, layers, when),
-            Shapes(_target, shape_id)       => Shapes(new_target, shape_id),
-            Brushes(_target, brush_id)      => Brushes(new_target, brush_id),
+            WholeDocument(_target, when)        => WholeDocument(new_target, when),
+            DocumentOutline(_target)            => DocumentOutline(new_target),
+            Layers(_target, layers, when)       => Layers(new_target, layers, when),
+            Shapes(_target, shape_id)           => Shapes(new_target, shape_id),
+            Brushes(_target, brush_id)          => Brushes(new_target, brush_id),
+            ShapeDefinitions(_target, shape_id) => ShapeDefinitions(new_target, shape_id),
         }
     }
 }
@@ -137,3 +147,13 @@ pub fn query_vector_brushes(brushes: impl IntoIterator<Item=CanvasBrushId>) -> i
 
     context.spawn_query(ReadCommand::default(), VectorQuery::Brushes(().into(), brushes.into_iter().collect()), ()).unwrap()
 }
+
+///
+/// Queries the full definitions of a set of shapes, along with their brushes and the shapes grouped with them
+///
+#[inline]
+pub fn query_vector_shape_definitions(shapes: impl IntoIterator<Item=CanvasShapeId>) -> impl Stream<Item=VectorResponse> {
+    let context = scene_context().unwrap();
+
+    context.spawn_query(ReadCommand::default(), VectorQuery::ShapeDefinitions(().into(), shapes.into_iter().collect()), ()).unwrap()
+}
@@ -81,6 +81,40 @@ impl SceneMessage for CanvasRender {
     }
 }
 
+///
+/// The number of window units per canvas unit for a layer transform
+///
+pub fn layer_transform_pixel_scale(layer_transform: &Transform2D) -> f64 {
+    layer_transform.transform_coord(UiPoint(0.0, 0.0)).distance_to(&layer_transform.transform_coord(UiPoint(0.0, 1.0)))
+}
+
+///
+/// The render scale (pixels per canvas unit) to use when rendering layers with a particular transform and window scale
+///
+/// This is rounded up to a power of two, so the layers only need to be re-rendered when the zoom level changes significantly
+///
+fn render_scale(layer_transform: &Transform2D, window_scale: f64) -> f64 {
+    let pixel_scale = layer_transform_pixel_scale(layer_transform) * window_scale;
+
+    if pixel_scale > 0.0 && pixel_scale.is_finite() {
+        2.0_f64.powf(pixel_scale.log2().ceil())
+    } else {
+        1.0
+    }
+}
+
+///
+/// Finds the textures that are created by a set of drawing instructions
+///
+fn textures_created(drawing: &[Draw]) -> HashSet<TextureId> {
+    drawing.iter()
+        .filter_map(|draw| match draw {
+            Draw::Texture(texture_id, TextureOp::Create(..))    => Some(*texture_id),
+            _                                                   => None,
+        })
+        .collect()
+}
+
 ///
 /// Calculates the layer transform for the canvas
 ///
@@ -125,12 +159,16 @@ pub async fn canvas_render_program(input: InputStream<CanvasRender>, context: Sc
     let mut doc_w           = 0.0;
     let mut doc_h           = 0.0;
     let mut scale           = 1.0;
+    let mut pixel_scale     = 1.0;
 
     // List of layers that have been rendered and not invalidated
     let mut valid_layers        = HashSet::<CanvasLayerId>::new();
     let mut layer_map           = HashMap::new();
     let mut last_layer_order    = vec![];
 
+    // The textures created by the drawing for each layer (freed once no layer uses them)
+    let mut layer_textures      = HashMap::<CanvasLayerId, HashSet<TextureId>>::new();
+
     // Connect to our dependencies
     let mut idle_request    = context.send::<IdleRequest>(()).unwrap();
     let mut drawing_request = context.send::<DrawingRequest>(()).unwrap();
@@ -239,19 +277,43 @@ pub async fn canvas_render_program(input: InputStream<CanvasRender>, context: Sc
                     // Render the layers to get their drawing instructions
                     let mut layer_instructions = layer_rendering
                         .into_iter()
-                        .map(|(layer_id, layer_data)| render_layer(layer_data, frame_time, &context).map(move |drawing| (layer_id, drawing)))
+                        .map(|(layer_id, layer_data)| render_layer(layer_data, frame_time, pixel_scale, &context).map(move |drawing| (layer_id, drawing)))
                         .collect::<FuturesUnordered<_>>();
 
                     // Read the rendered layers and add to our drawing instructions
+                    let mut replaced_textures = HashSet::new();
+
                     while let Some((canvas_layer_id, layer_drawing)) = layer_instructions.next().await {
                         let layer_id = layer_map.get(&canvas_layer_id).copied().unwrap();
 
+                        // Clearing the layer doesn't free its textures, so remember the ones that the old drawing used
+                        if let Some(old_textures) = layer_textures.insert(canvas_layer_id, textures_created(&layer_drawing)) {
+                            replaced_textures.extend(old_textures);
+                        }
+
                         drawing.layer(layer_id);
                         drawing.clear_layer();
                         drawing.set_layer_transform(layer_transform);
                         drawing.extend(layer_drawing);
                     }
 
+                    // Layers that have been removed from the document don't need their textures any more
+                    layer_textures.retain(|layer_id, textures| {
+                        if layers.contains_key(layer_id) {
+                            true
+                        } else {
+                            replaced_textures.extend(textures.drain());
+                            false
+                        }
+                    });
+
+                    // Free any textures that aren't used by any of the layers any more
+                    for texture_id in replaced_textures {
+                        if !layer_textures.values().any(|textures| textures.contains(&texture_id)) {
+                            drawing.free_texture(texture_id);
+                        }
+                    }
+
                     // Update the document size if necessary
                     if let Some(doc_size) = DocumentSize::from_properties(properties.iter()) {
                         if doc_size.width != doc_w || doc_size.height != doc_h {
@@ -349,7 +411,8 @@ pub async fn canvas_render_program(input: InputStream<CanvasRender>, context: Sc
 
             CanvasRender::SetTransform(new_transform) => {
                 // Update the transform. We use layer transforms to move the rendering around
-                transform = new_transform;
+                transform           = new_transform;
+                update_transform    = true;
 
                 if !idle_requested && idle_request.send(IdleRequest::WhenIdle(our_program_id)).await.is_ok() {
                     idle_requested = true;
@@ -393,8 +456,19 @@ pub async fn canvas_render_program(input: InputStream<CanvasRender>, context: Sc
             }
 
             // Figure out the scale of a pixel after the transform is applied
-            let transform_scale = layer_transform.transform_coord(UiPoint(0.0, 0.0)).distance_to(&layer_transform.transform_coord(UiPoint(0.0, 1.0)));
-            let transform_scale = 1.0 / transform_scale;
+            let transform_scale = 1.0 / layer_transform_pixel_scale(&layer_transform);
+
+            // Layers are rendered at a scale that matches the zoom level (for things like gradient textures), so they need to be redrawn if it changes
+            let new_pixel_scale = render_scale(&layer_transform, scale);
+            if new_pixel_scale != pixel_scale {
+                pixel_scale = new_pixel_scale;
+                valid_layers.clear();
+                need_redraw = true;
+
+                if !idle_requested && idle_request.send(IdleRequest::WhenIdle(our_program_id)).await.is_ok() {
+                    idle_requested = true;
+                }
+            }
 
             // Redraw the document frame on layer 0
             drawing.layer(LayerId(0));
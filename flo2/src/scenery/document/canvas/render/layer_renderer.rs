@@ -11,7 +11,9 @@ use std::sync::*;
 ///
 /// Renders the shapes on a layer when described as a set of vector responses
 ///
-pub async fn render_layer(layer: impl Send + IntoIterator<Item=VectorResponse>, frame_time: FrameTime, context: &SceneContext) -> Vec<Draw> {
+/// `pixel_scale` is the number of pixels per canvas unit that the layer will be drawn at.
+///
+pub async fn render_layer(layer: impl Send + IntoIterator<Item=VectorResponse>, frame_time: FrameTime, pixel_scale: f64, context: &SceneContext) -> Vec<Draw> {
     struct RenderItem {
         /// Shape to render
         shape:          Arc<ShapeWithProperties>,
@@ -118,7 +120,7 @@ pub async fn render_layer(layer: impl Send + IntoIterator<Item=VectorResponse>,
         }
 
         // Render the shapes in this pass
-        let drawings = render_shapes(render_pass_idxs.iter().map(|idx| Arc::clone(&render[*idx].shape)), frame_time, context).await;
+        let drawings = render_shapes(render_pass_idxs.iter().map(|idx| Arc::clone(&render[*idx].shape)), frame_time, pixel_scale, context).await;
 
         // Put the drawings back into the render items
         for (idx, drawing) in render_pass_idxs.into_iter().zip(drawings) {
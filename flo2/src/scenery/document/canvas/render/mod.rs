@@ -3,12 +3,14 @@ mod layer_renderer;
 mod shape_type_renderer;
 mod shape_renderer;
 mod standard_shape_type_renderer;
+mod text_shape_type_renderer;
 
 pub use canvas_render_program::*;
 pub use layer_renderer::*;
 pub use shape_renderer::*;
 pub use shape_type_renderer::*;
 pub use standard_shape_type_renderer::*;
+pub use text_shape_type_renderer::*;
 
 #[cfg(test)]
 mod test_shape_renderer;
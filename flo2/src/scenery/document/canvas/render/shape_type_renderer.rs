@@ -38,7 +38,9 @@ pub struct ShapeWithProperties {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum RenderShapesRequest {
     /// Queries the rendering instructions for each shape in the list. The query should return one response per shape
-    RenderRequest(Vec<Arc<ShapeWithProperties>>, FrameTime, StreamTarget),
+    ///
+    /// The `f64` is the render scale: the number of pixels per canvas unit that the shapes will be drawn at
+    RenderRequest(Vec<Arc<ShapeWithProperties>>, FrameTime, f64, StreamTarget),
 }
 
 ///
@@ -58,7 +60,7 @@ impl QueryRequest for RenderShapesRequest {
 
     fn with_new_target(self, new_target: StreamTarget) -> Self {
         match self {
-            Self::RenderRequest(shapes, frame_time, _old_target) => Self::RenderRequest(shapes, frame_time, new_target)
+            Self::RenderRequest(shapes, frame_time, pixel_scale, _old_target) => Self::RenderRequest(shapes, frame_time, pixel_scale, new_target)
         }
     }
 }
@@ -69,13 +71,16 @@ impl SceneMessage for RenderShapesResponse {
 ///
 /// Runs a shape renderer program that uses the supplied function to generate the drawing instructions for a shape
 ///
-pub async fn shape_renderer_program(input: InputStream<RenderShapesRequest>, context: SceneContext, shape_renderer: impl 'static + Send + Sync + Fn(&ShapeWithProperties, FrameTime, &mut Vec<Draw>)) {
+/// The function is called with the shape, the frame time, the render scale (pixels per canvas unit) and the drawing to add the
+/// instructions to.
+///
+pub async fn shape_renderer_program(input: InputStream<RenderShapesRequest>, context: SceneContext, shape_renderer: impl 'static + Send + Sync + Fn(&ShapeWithProperties, FrameTime, f64, &mut Vec<Draw>)) {
     let mut input       = input;
     let shape_renderer  = Arc::new(shape_renderer);
 
     while let Some(request) = input.next().await {
         match request {
-            RenderShapesRequest::RenderRequest(shapes, frame_time, response_target) => {
+            RenderShapesRequest::RenderRequest(shapes, frame_time, pixel_scale, response_target) => {
                 // Send to the target (we'll just ignore errors by not doing any work)
                 let Ok(mut target) = context.send(response_target) else { continue; };
 
@@ -83,7 +88,7 @@ pub async fn shape_renderer_program(input: InputStream<RenderShapesRequest>, con
                 let shape_renderer = Arc::clone(&shape_renderer);
                 target.send(QueryResponse::with_iterator(shapes.into_iter().map(move |shape| {
                     let mut drawing = vec![];
-                    (shape_renderer)(&*shape, frame_time, &mut drawing);
+                    (shape_renderer)(&*shape, frame_time, pixel_scale, &mut drawing);
 
                     RenderShapesResponse::ShapeRendering(Arc::new(drawing))
                 }))).await.ok();
@@ -1,28 +1,43 @@
 use super::shape_type_renderer::*;
 use super::super::basic_properties::*;
+use super::super::fill_properties::*;
+use super::super::point::*;
 use super::super::property::*;
+use super::super::shape::*;
 
 use flo_draw::canvas::*;
 use flo_scene::*;
 
+use std::sync::*;
+
 ///
 /// Runs the standard shape renderer program (for `ShapeType::default().render_program_id()`)
 ///
-/// This renders shapes using the properties defined in basic_properties. Groups are just rendered in a 'straight through' fashion
+/// This renders shapes using the properties defined in basic_properties and fill_properties. Groups are just rendered in a 'straight through' fashion
 ///
 pub async fn standard_shape_type_renderer_program(input: InputStream<RenderShapesRequest>, context: SceneContext) {
-    shape_renderer_program(input, context, |shape, _frame_time, drawing| {
+    // Radial gradients are generated as textures, which are kept around so that they're not regenerated every time a shape is redrawn
+    let radial_gradients = Mutex::new(RadialGradientTextures::default());
+
+    shape_renderer_program(input, context, move |shape, _frame_time, pixel_scale, drawing| {
         // Generate the path
+        let path = shape.shape.to_path();
+
         drawing.new_path();
-        shape.shape.to_path()
-            .into_iter()
-            .for_each(|path| drawing.bezier_path(&path));
+        path.iter().for_each(|path| drawing.bezier_path(path));
 
         // Set up the properties to render it
-        let fill    = FlatFill::from_properties(shape.properties.iter());
-        let stroke  = Stroke::from_properties(shape.properties.iter());
+        let fill        = FlatFill::from_properties(shape.properties.iter()).map(|fill| fill.with_variation(ColorVariation::from_properties(shape.properties.iter())));
+        let gradient    = GradientFill::from_properties(shape.properties.iter());
+        let pattern     = PatternFill::from_properties(shape.properties.iter());
+        let stroke      = Stroke::from_properties(shape.properties.iter());
 
-        if let Some(fill) = fill {
+        // Only one fill is drawn: patterns take priority over gradients, which take priority over flat fills
+        if let Some(pattern) = pattern {
+            pattern.draw(drawing);
+        } else if let Some(gradient) = gradient {
+            gradient.draw(path_bounds(&path), pixel_scale as f32, &mut radial_gradients.lock().unwrap(), drawing);
+        } else if let Some(fill) = fill {
             fill.draw(drawing);
         }
 
@@ -30,8 +45,50 @@ pub async fn standard_shape_type_renderer_program(input: InputStream<RenderShape
             stroke.draw(drawing);
         }
 
+        // Text has no path (its glyphs can only be converted asynchronously), so it's drawn with its font in the fill colour
+        if let CanvasShape::Text(text) = &shape.shape {
+            let fill = FlatFill::from_properties(shape.properties.iter()).unwrap_or(FlatFill(Color::Rgba(0.0, 0.0, 0.0, 1.0)));
+
+            drawing.fill_color(fill.0);
+            text.draw(FontId(0), drawing);
+        }
+
         // Any grouped shapes are rendered after
         shape.group.iter()
             .for_each(|(_shape, shape_drawing)| drawing.extend(shape_drawing.iter().cloned()));
     }).await;
 }
+
+///
+/// Finds the bounding box of a path (the control points are included, so this may be larger than the path itself)
+///
+fn path_bounds(path: &[WorkingSubpath]) -> (CanvasPoint, CanvasPoint) {
+    let points = path.iter()
+        .flat_map(|subpath| {
+            let actions = subpath.actions.iter().flat_map(|action| match action {
+                WorkingPathAction::Line(end)                    => vec![*end],
+                WorkingPathAction::QuadraticCurve { end, cp }   => vec![*cp, *end],
+                WorkingPathAction::CubicCurve { end, cp1, cp2 } => vec![*cp1, *cp2, *end],
+                WorkingPathAction::Close                        => vec![],
+            });
+
+            Some(subpath.start_point).into_iter().chain(actions)
+        });
+
+    let mut min = CanvasPoint { x: f32::MAX, y: f32::MAX };
+    let mut max = CanvasPoint { x: f32::MIN, y: f32::MIN };
+
+    for point in points {
+        min.x = min.x.min(point.x as f32);
+        min.y = min.y.min(point.y as f32);
+        max.x = max.x.max(point.x as f32);
+        max.y = max.y.max(point.y as f32);
+    }
+
+    if min.x > max.x {
+        // Empty path
+        (CanvasPoint { x: 0.0, y: 0.0 }, CanvasPoint { x: 0.0, y: 0.0 })
+    } else {
+        (min, max)
+    }
+}
@@ -0,0 +1,28 @@
+use super::shape_type_renderer::*;
+use super::super::basic_properties::*;
+use super::super::property::*;
+use super::super::shape::*;
+
+use flo_draw::canvas::*;
+use flo_scene::*;
+
+///
+/// Runs the renderer program for text shapes (for `SHAPE_TYPE_TEXT.render_program_id()`)
+///
+/// Text is rendered using the font it refers to, filled using the fill colour from the basic properties (or black if there's no fill). Other
+/// shapes are rendered as if they had no text.
+///
+pub async fn text_shape_type_renderer_program(input: InputStream<RenderShapesRequest>, context: SceneContext) {
+    shape_renderer_program(input, context, |shape, _frame_time, _pixel_scale, drawing| {
+        if let CanvasShape::Text(text) = &shape.shape {
+            let fill = FlatFill::from_properties(shape.properties.iter()).unwrap_or(FlatFill(Color::Rgba(0.0, 0.0, 0.0, 1.0)));
+
+            drawing.fill_color(fill.0);
+            text.draw(FontId(0), drawing);
+        }
+
+        // Any grouped shapes are rendered after
+        shape.group.iter()
+            .for_each(|(_shape, shape_drawing)| drawing.extend(shape_drawing.iter().cloned()));
+    }).await;
+}
@@ -3,6 +3,7 @@ mod path;
 mod polygon;
 mod rectangle;
 mod shape;
+mod text;
 mod working_path;
 mod working_point;
 
@@ -11,6 +12,7 @@ pub use path::*;
 pub use polygon::*;
 pub use rectangle::*;
 pub use shape::*;
+pub use text::*;
 pub use working_path::*;
 pub use working_point::*;
 
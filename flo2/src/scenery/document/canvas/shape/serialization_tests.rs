@@ -5,6 +5,8 @@
 use crate::scenery::document::canvas::point::*;
 use crate::scenery::document::canvas::shape::*;
 
+use futures::executor;
+
 /// Helper to create a test CanvasPoint
 fn test_point(x: f32, y: f32) -> CanvasPoint {
     CanvasPoint { x, y }
@@ -398,3 +400,60 @@ fn polygon_with_many_sides_round_trip() {
 
     assert_eq!(polygon, deserialized);
 }
+
+#[test]
+fn text_round_trip() {
+    let text = CanvasText {
+        min:        test_point(0.0, 0.0),
+        max:        test_point(200.0, 100.0),
+        text:       "Hello\nworld".to_string(),
+        font:       "flowbetween::lato".to_string(),
+        size:       24.0,
+        alignment:  CanvasTextAlignment::Center,
+    };
+
+    let serialized = postcard::to_allocvec(&text).expect("Failed to serialize");
+    let deserialized: CanvasText =
+        postcard::from_bytes(&serialized).expect("Failed to deserialize");
+
+    assert_eq!(text, deserialized);
+}
+
+#[test]
+fn text_wraps_to_box_width() {
+    let text = CanvasText {
+        min:        test_point(0.0, 0.0),
+        max:        test_point(60.0, 100.0),
+        text:       "one two three four five six".to_string(),
+        font:       crate::scenery::document::canvas::DEFAULT_CANVAS_FONT.to_string(),
+        size:       20.0,
+        alignment:  CanvasTextAlignment::Left,
+    };
+
+    let font    = crate::scenery::document::canvas::canvas_font(&text.font).unwrap();
+    let lines   = text.lines(&font);
+
+    assert!(lines.len() > 1, "{:?}", lines);
+    assert_eq!(lines.join(" "), text.text);
+}
+
+#[test]
+fn text_converts_to_glyph_paths() {
+    let text = CanvasText {
+        min:        test_point(0.0, 0.0),
+        max:        test_point(200.0, 100.0),
+        text:       "Hi".to_string(),
+        font:       crate::scenery::document::canvas::DEFAULT_CANVAS_FONT.to_string(),
+        size:       20.0,
+        alignment:  CanvasTextAlignment::Left,
+    };
+
+    let paths = executor::block_on(text.to_path());
+    assert!(!paths.is_empty());
+
+    // Glyphs are placed inside the box
+    for path in paths.iter() {
+        assert!(path.start_point.x >= -1.0 && path.start_point.x <= 200.0, "{:?}", path.start_point);
+        assert!(path.start_point.y >= -10.0 && path.start_point.y <= 100.0, "{:?}", path.start_point);
+    }
+}
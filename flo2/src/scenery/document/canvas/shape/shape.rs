@@ -6,6 +6,7 @@ use super::ellipse::*;
 use super::path::*;
 use super::polygon::*;
 use super::rectangle::*;
+use super::text::*;
 use super::working_path::*;
 
 use ::serde::*;
@@ -39,6 +40,9 @@ pub enum CanvasShape {
 
     /// Polygon filling a rectangle, with the specified number of points
     Polygon(CanvasPolygon),
+
+    /// Text wrapped to fit a rectangle
+    Text(CanvasText),
 }
 
 ///
@@ -90,6 +94,8 @@ impl CanvasShape {
     ///
     /// Converts this shape to a path (empty vec if ther's no path here)
     ///
+    /// Text shapes have no path here, as converting glyphs to paths is asynchronous: use `to_path_with_text()` to get their outlines.
+    ///
     pub fn to_path(&self) -> Vec<WorkingSubpath> {
         match self {
             CanvasShape::Path(canvas_path)           => WorkingSubpath::from_canvas_path(canvas_path),
@@ -97,6 +103,27 @@ impl CanvasShape {
             CanvasShape::Rectangle(canvas_rectangle) => vec![WorkingSubpath::rectangle(canvas_rectangle)],
             CanvasShape::Ellipse(canvas_ellipse)     => vec![WorkingSubpath::ellipse(canvas_ellipse)],
             CanvasShape::Polygon(canvas_polygon)     => vec![WorkingSubpath::polygon(canvas_polygon)],
+            CanvasShape::Text(_canvas_text)          => vec![],
+        }
+    }
+
+    ///
+    /// Converts this shape to a path, including the outlines of the glyphs for text shapes
+    ///
+    pub async fn to_path_with_text(&self) -> Vec<WorkingSubpath> {
+        match self {
+            CanvasShape::Text(canvas_text)  => canvas_text.to_path().await,
+            _                               => self.to_path(),
+        }
+    }
+
+    ///
+    /// If this is a text shape, converts it to a path shape with the outlines of its glyphs (so the lettering can be edited by hand)
+    ///
+    pub async fn convert_text_to_path(&self) -> Option<CanvasShape> {
+        match self {
+            CanvasShape::Text(canvas_text)  => Some(CanvasShape::Path(WorkingSubpath::to_canvas_path(&canvas_text.to_path().await))),
+            _                               => None,
         }
     }
 }
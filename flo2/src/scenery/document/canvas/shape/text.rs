@@ -0,0 +1,157 @@
+use super::super::font::*;
+use super::super::point::*;
+use super::working_path::*;
+use super::working_point::*;
+
+use flo_draw::canvas::*;
+use futures::prelude::*;
+use ::serde::*;
+
+use std::sync::*;
+
+/// Spacing between the baselines of lines of text, as a multiple of the font size
+const LINE_SPACING: f32 = 1.2;
+
+///
+/// How the lines in a text shape are aligned within its box
+///
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CanvasTextAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+///
+/// Serialized form of a text shape in the canvas
+///
+/// Text is laid out from the top of the box (`max.y`) downwards, and is wrapped at word boundaries to fit the width of the box.
+///
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CanvasTextV1 {
+    pub min:        CanvasPoint,
+    pub max:        CanvasPoint,
+    pub text:       String,
+    pub font:       String,
+    pub size:       f32,
+    pub alignment:  CanvasTextAlignment,
+}
+
+pub type CanvasText = CanvasTextV1;
+
+/// Shape type to indicate a shape encoded in V1 canvas text format
+pub const CANVAS_TEXT_V1_TYPE: i64 = 5;
+
+impl CanvasTextV1 {
+    ///
+    /// Splits the text into lines that fit the width of the box when rendered in the specified font
+    ///
+    pub fn lines(&self, font: &Arc<CanvasFontFace>) -> Vec<String> {
+        let max_width   = (self.max.x - self.min.x).abs() as f64;
+        let mut lines   = vec![];
+
+        for paragraph in self.text.split('\n') {
+            let mut line = String::new();
+
+            for word in paragraph.split(' ') {
+                // Try adding the word to the end of the current line
+                let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+
+                let mut layout = CanvasFontLineLayout::new(font, self.size);
+                layout.add_text(&candidate);
+                let width = layout.measure().pos.0;
+
+                if width > max_width && !line.is_empty() {
+                    // Start a new line if the word won't fit (words that are wider than the box are left on a line by themselves)
+                    lines.push(line);
+                    line = word.to_string();
+                } else {
+                    line = candidate;
+                }
+            }
+
+            lines.push(line);
+        }
+
+        lines
+    }
+
+    ///
+    /// Generates the drawing instructions to render this text using the current fill colour
+    ///
+    /// Nothing is drawn if the font has not been registered with `register_canvas_font()`
+    ///
+    pub fn draw(&self, font_id: FontId, drawing: &mut impl GraphicsContext) {
+        let Some(font) = canvas_font(&self.font) else { return; };
+
+        drawing.define_font_data(font_id, Arc::clone(&font));
+        drawing.set_font_size(font_id, self.size);
+
+        let (x, alignment) = match self.alignment {
+            CanvasTextAlignment::Left   => (self.min.x.min(self.max.x), TextAlignment::Left),
+            CanvasTextAlignment::Center => ((self.min.x + self.max.x) / 2.0, TextAlignment::Center),
+            CanvasTextAlignment::Right  => (self.min.x.max(self.max.x), TextAlignment::Right),
+        };
+
+        // The first baseline is one line below the top of the box
+        let mut baseline = self.min.y.max(self.max.y) - self.size;
+
+        for line in self.lines(&font) {
+            drawing.begin_line_layout(x, baseline, alignment);
+            drawing.layout_text(font_id, line);
+            drawing.draw_text_layout();
+
+            baseline -= self.size * LINE_SPACING;
+        }
+    }
+
+    ///
+    /// Converts the glyphs in this text shape to a set of paths
+    ///
+    pub async fn to_path(&self) -> Vec<WorkingSubpath> {
+        // Draw the text, then convert the glyphs to paths
+        let mut drawing = vec![];
+        self.draw(FontId(0), &mut drawing);
+
+        let glyph_paths = drawing_with_text_as_paths(drawing_with_laid_out_text(stream::iter(drawing))).collect::<Vec<_>>().await;
+
+        // Read the path instructions into subpaths
+        let mut subpaths    = vec![];
+        let mut current     = None;
+
+        for draw in glyph_paths {
+            match draw {
+                Draw::Path(PathOp::Move(x, y)) => {
+                    if let Some(subpath) = current.take() { subpaths.push(subpath); }
+                    current = Some(WorkingSubpath { start_point: WorkingPoint { x: x as _, y: y as _ }, actions: vec![] });
+                }
+
+                Draw::Path(PathOp::Line(x, y)) => {
+                    if let Some(subpath) = current.as_mut() { subpath.actions.push(WorkingPathAction::Line(WorkingPoint { x: x as _, y: y as _ })); }
+                }
+
+                Draw::Path(PathOp::BezierCurve(((cp1x, cp1y), (cp2x, cp2y)), (x, y))) => {
+                    if let Some(subpath) = current.as_mut() {
+                        subpath.actions.push(WorkingPathAction::CubicCurve {
+                            end: WorkingPoint { x: x as _, y: y as _ },
+                            cp1: WorkingPoint { x: cp1x as _, y: cp1y as _ },
+                            cp2: WorkingPoint { x: cp2x as _, y: cp2y as _ },
+                        });
+                    }
+                }
+
+                Draw::Path(PathOp::ClosePath) => {
+                    if let Some(subpath) = current.as_mut() { subpath.actions.push(WorkingPathAction::Close); }
+                }
+
+                _ => { }
+            }
+        }
+
+        if let Some(subpath) = current.take() { subpaths.push(subpath); }
+
+        subpaths.into_iter()
+            .filter(|subpath| !subpath.actions.is_empty())
+            .collect()
+    }
+}
@@ -12,6 +12,9 @@ static SHAPE_TYPE_NAMES: LazyLock<Mutex<Vec<&'static str>>> = LazyLock::new(|| M
 /// Hashmap mapping shape type names to IDs
 static SHAPE_TYPE_FOR_NAME: LazyLock<Mutex<HashMap<&'static str, usize>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
 
+/// Shape type for text shapes that are rendered using their font (rather than as paths)
+pub static SHAPE_TYPE_TEXT: LazyShapeType = LazyShapeType::new("flowbetween::text");
+
 ///
 /// Represents the type of a shape
 ///
@@ -14,6 +14,9 @@ use std::result::{Result};
 /// Definition for the canvas sqlite storage
 pub (super) static SCHEMA: &'static str = include_str!("canvas.sql");
 
+/// Definition for the undo history tables (created when opening documents that don't have them yet)
+pub (super) static UNDO_SCHEMA: &'static str = include_str!("canvas_undo.sql");
+
 ///
 /// Storage for the sqlite canvas
 ///
@@ -41,6 +44,12 @@ pub struct SqliteCanvas {
 
     /// The next shape ID to use (None if we haven't retrieved this from the database yet)
     pub (super) next_shape_id: Option<i64>,
+
+    /// The undo action that edits are currently being added to (None if the next edit should start a new action)
+    pub (super) undo_action: Option<i64>,
+
+    /// The description to give to the next undo action that's created
+    pub (super) undo_description: Option<String>,
 }
 
 impl SqliteCanvas {
@@ -50,6 +59,9 @@ impl SqliteCanvas {
     pub fn with_connection(sqlite: Connection) -> Result<Self, CanvasError> {
         sqlite.execute_batch("PRAGMA foreign_keys = ON")?;
 
+        // Documents created before the undo history was added won't have its tables yet
+        sqlite.execute_batch(UNDO_SCHEMA)?;
+
         Ok(Self {
             sqlite:                 sqlite,
             property_id_cache:      HashMap::new(),
@@ -59,6 +71,8 @@ impl SqliteCanvas {
             shape_id_cache:         IdCache::new(200),
             layer_id_cache:         HashMap::new(),
             next_shape_id:          None,
+            undo_action:            None,
+            undo_description:       None,
         })
     }
 
@@ -84,4 +98,17 @@ impl SqliteCanvas {
 
         Ok(canvas)
     }
+
+    ///
+    /// Discards the cached IDs, so that they're read from the database again (eg, after a transaction has been rolled back)
+    ///
+    pub (super) fn clear_caches(&mut self) {
+        self.property_id_cache.clear();
+        self.property_for_id_cache.clear();
+        self.shapetype_id_cache.clear();
+        self.shapetype_for_id_cache.clear();
+        self.shape_id_cache.retain(|_, _| false);
+        self.layer_id_cache.clear();
+        self.next_shape_id = None;
+    }
 }
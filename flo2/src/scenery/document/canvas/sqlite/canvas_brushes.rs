@@ -28,7 +28,7 @@ impl SqliteCanvas {
             .collect::<Result<Vec<_>, _>>()?;
 
         // Write the properties themselves
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         // Run commands to set each type of property value
         let mut blob_properties_cmd = transaction.prepare_cached("REPLACE INTO BrushBlobProperties (BrushId, PropertyId, BlobValue) VALUES (?, ?, ?)")?;
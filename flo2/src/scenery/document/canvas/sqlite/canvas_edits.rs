@@ -0,0 +1,81 @@
+use super::canvas::*;
+use super::super::error::*;
+use super::super::vector_editor::*;
+
+use std::result::{Result};
+
+impl SqliteCanvas {
+    ///
+    /// Applies a single edit to this canvas, without recording it in the undo history
+    ///
+    /// Subscriptions and undo actions are managed by the canvas program, so `VectorCanvas::Subscribe`, `VectorCanvas::StartUndoAction`
+    /// and `VectorCanvas::FinishUndoAction` have no effect here.
+    ///
+    pub fn apply_edit(&mut self, edit: VectorCanvas) -> Result<(), CanvasError> {
+        use VectorCanvas::*;
+
+        match edit {
+            AddLayer { new_layer_id, before_layer, }    => self.add_layer(new_layer_id, before_layer),
+            RemoveLayer(layer_id)                       => self.remove_layer(layer_id),
+            AddFrame { frame_layer, when, length }      => self.add_frame(frame_layer, when, length),
+            RemoveFrame { frame_layer, when }           => self.remove_frame(frame_layer, when),
+            ReorderLayer { layer_id, before_layer, }    => self.reorder_layer(layer_id, before_layer),
+            AddShape(shape_id, shape_type, shape_defn)  => self.add_shape(shape_id, shape_type, shape_defn),
+            RemoveShape(shape_id)                       => self.remove_shape(shape_id),
+            SetShapeDefinition(shape_id, shape_defn)    => self.set_shape_definition(shape_id, shape_defn),
+            SetShapeTime(shape_id, when)                => self.set_shape_time(shape_id, when),
+            AddBrush(brush_id)                          => self.add_brush(brush_id),
+            RemoveBrush(brush_id)                       => self.remove_brush(brush_id),
+            ReorderShape { shape_id, before_shape, }    => self.reorder_shape(shape_id, before_shape),
+            SetShapeParent(shape_id, parent)            => self.set_shape_parent(shape_id, parent),
+            SetProperty(property_target, properties)    => self.set_properties(property_target, properties),
+            AddShapeBrushes(shape_id, brush_ids)        => self.add_shape_brushes(shape_id, brush_ids),
+            RemoveProperty(property_target, properties) => self.delete_properties(property_target, properties),
+            RemoveShapeBrushes(shape_id, brush_ids)     => self.remove_shape_brushes(shape_id, brush_ids),
+            Subscribe(_)                                => Ok(()),
+            StartUndoAction(_)                          => Ok(()),
+            FinishUndoAction                            => Ok(()),
+        }
+    }
+
+    ///
+    /// Performs a set of edits on this canvas, adding them to the current action in the undo history
+    ///
+    /// Edits that fail are skipped and are not added to the undo history.
+    ///
+    pub fn perform_edits(&mut self, edits: Vec<VectorCanvas>) -> Result<(), CanvasError> {
+        self.perform_edits_with(edits, |_canvas, _edit| { })
+    }
+
+    ///
+    /// Performs a set of edits on this canvas as for `perform_edits()`, calling `before_edit` with the state of the canvas
+    /// just before each edit is applied
+    ///
+    pub fn perform_edits_with(&mut self, edits: Vec<VectorCanvas>, mut before_edit: impl FnMut(&SqliteCanvas, &VectorCanvas)) -> Result<(), CanvasError> {
+        let mut redo_edits = vec![];
+        let mut undo_edits = vec![];
+
+        for edit in edits {
+            before_edit(self, &edit);
+
+            // The reverse of the edit is read from the canvas before it's changed
+            let reverse_edits = self.reverse_edits(&edit);
+
+            if self.apply_edit(edit.clone()).is_ok() {
+                if let Ok(reverse_edits) = reverse_edits {
+                    redo_edits.push(edit);
+                    undo_edits.push(reverse_edits);
+                }
+            }
+        }
+
+        if redo_edits.is_empty() {
+            return Ok(());
+        }
+
+        // The edits are undone in the opposite order to how they were performed
+        let undo_edits = undo_edits.into_iter().rev().flatten().collect::<Vec<_>>();
+
+        self.record_undo(&redo_edits, &undo_edits)
+    }
+}
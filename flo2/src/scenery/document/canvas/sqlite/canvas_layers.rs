@@ -43,14 +43,14 @@ impl SqliteCanvas {
     /// Queries the database for the index of the specified layer
     ///
     #[inline]
-    pub (super) fn order_for_layer_in_transaction(transaction: &Transaction<'_>, layer_id: CanvasLayerId) -> Result<i64, CanvasError> {
+    pub (super) fn order_for_layer_in_transaction(transaction: &Connection, layer_id: CanvasLayerId) -> Result<i64, CanvasError> {
         Ok(transaction.query_one::<i64, _, _>("SELECT OrderIdx FROM Layers WHERE LayerGuid = ?", [layer_id.to_string()], |row| row.get(0))?)
     }
 
     ///
     /// Inserts a block of shapes into ShapeLayers at the specified position, shifting existing entries to make room.
     ///
-    pub (super) fn insert_shapes_on_layer(transaction: &Transaction<'_>, layer_id: i64, at_order: i64, shape_ids: &[i64], time: i64) -> Result<(), CanvasError> {
+    pub (super) fn insert_shapes_on_layer(transaction: &Connection, layer_id: i64, at_order: i64, shape_ids: &[i64], time: i64) -> Result<(), CanvasError> {
         let block_size = shape_ids.len() as i64;
 
         // Make room for the block
@@ -68,7 +68,7 @@ impl SqliteCanvas {
     ///
     /// Removes a contiguous block of entries from ShapeLayers and compacts the ordering.
     ///
-    pub (super) fn remove_shapes_from_layer(transaction: &Transaction<'_>, layer_id: i64, from_order: i64, block_size: i64) -> Result<(), CanvasError> {
+    pub (super) fn remove_shapes_from_layer(transaction: &Connection, layer_id: i64, from_order: i64, block_size: i64) -> Result<(), CanvasError> {
         // Delete the block
         transaction.execute("DELETE FROM ShapeLayers WHERE LayerId = ? AND OrderIdx >= ? AND OrderIdx < ?", params![layer_id, from_order, from_order + block_size])?;
 
@@ -90,7 +90,7 @@ impl SqliteCanvas {
             .collect::<Result<Vec<_>, _>>()?;
 
         // Write the properties themselves
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         // Run commands to set each type of property value
         let mut blob_properties_cmd = transaction.prepare_cached("REPLACE INTO LayerBlobProperties (LayerId, PropertyId, BlobValue) VALUES (?, ?, ?)")?;
@@ -107,7 +107,7 @@ impl SqliteCanvas {
     /// Adds a new layer to the canvas
     ///
     pub fn add_layer(&mut self, new_layer_id: CanvasLayerId, before_layer: Option<CanvasLayerId>) -> Result<(), CanvasError> {
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         let new_layer_order = if let Some(before_layer) = before_layer {
             // Add between the existing layers
@@ -135,7 +135,7 @@ impl SqliteCanvas {
     /// Removes an existing layer
     ///
     pub fn remove_layer(&mut self, old_layer_id: CanvasLayerId) -> Result<(), CanvasError> {
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         let old_layer_order = Self::order_for_layer_in_transaction(&transaction, old_layer_id)?;
         transaction.execute("DELETE FROM Layers WHERE OrderIdx = ?", params![old_layer_order])?;
@@ -178,7 +178,7 @@ impl SqliteCanvas {
     /// Changes the ordering of a layer
     ///
     pub fn reorder_layer(&mut self, layer_id: CanvasLayerId, before_layer: Option<CanvasLayerId>) -> Result<(), CanvasError> {
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         // Work out the layer indexes where we want to add the new layer and the 
         let original_layer_order  = Self::order_for_layer_in_transaction(&transaction, layer_id)?;
@@ -1,7 +1,9 @@
 use super::canvas::*;
+use super::super::layer::*;
 use super::super::property::*;
 use super::super::queries::*;
 use super::super::shape::*;
+use super::super::undo::*;
 use super::super::vector_editor::*;
 
 use flo_scene::*;
@@ -11,6 +13,7 @@ use futures::prelude::*;
 use ::serde::*;
 
 use std::collections::{HashSet};
+use std::mem;
 
 ///
 /// Messages for the sqlite canvas program
@@ -19,6 +22,7 @@ use std::collections::{HashSet};
 pub enum SqliteCanvasRequest {
     Edit(Vec<VectorCanvas>),
     Query(VectorQuery),
+    Undo(VectorUndo),
 }
 
 ///
@@ -32,69 +36,43 @@ pub async fn sqlite_canvas_program(input: InputStream<SqliteCanvasRequest>, cont
     while let Some(msg) = input.next().await {
         use SqliteCanvasRequest::*;
 
+        // Track which layers and shapes are affected by each request
+        let mut changed_layers = HashSet::new();
+        let mut changed_shapes = HashSet::new();
+
         match msg {
             Edit(edits) => {
-                use VectorCanvas::*;
-
-                // Track which layers and shapes are affected by this batch of edits
-                let mut changed_layers = HashSet::new();
-                let mut changed_shapes = HashSet::new();
+                let mut undoable_edits = vec![];
 
                 for edit in edits {
                     match edit {
-                        AddLayer { new_layer_id, before_layer, }            => { changed_layers.insert(new_layer_id); canvas.add_layer(new_layer_id, before_layer).ok(); }
-                        RemoveLayer(layer_id)                               => { changed_layers.insert(layer_id); canvas.remove_layer(layer_id).ok(); }
-                        AddFrame { frame_layer, when, length }              => { changed_layers.insert(frame_layer); canvas.add_frame(frame_layer, when, length).ok(); }
-                        RemoveFrame { frame_layer, when }                   => { changed_layers.insert(frame_layer); canvas.remove_frame(frame_layer, when).ok(); }
-                        ReorderLayer { layer_id, before_layer, }            => { changed_layers.insert(layer_id); canvas.reorder_layer(layer_id, before_layer).ok(); }
-                        AddShape(shape_id, shape_type, shape_defn)          => { changed_shapes.insert(shape_id); canvas.add_shape(shape_id, shape_type, shape_defn).ok(); }
-                        RemoveShape(shape_id)                               => { changed_shapes.insert(shape_id); canvas.remove_shape(shape_id).ok(); }
-                        SetShapeDefinition(shape_id, shape_defn)            => { changed_shapes.insert(shape_id); canvas.set_shape_definition(shape_id, shape_defn).ok(); }
-                        SetShapeTime(shape_id, when)                        => { changed_shapes.insert(shape_id); canvas.set_shape_time(shape_id, when).ok(); }
-                        AddBrush(brush_id)                                  => { canvas.add_brush(brush_id).ok(); }
-                        RemoveBrush(brush_id)                               => { canvas.remove_brush(brush_id).ok(); }
-                        ReorderShape { shape_id, before_shape, }            => { changed_shapes.insert(shape_id); canvas.reorder_shape(shape_id, before_shape).ok(); }
-                        AddShapeBrushes(shape_id, brush_ids)                => { changed_shapes.insert(shape_id); canvas.add_shape_brushes(shape_id, brush_ids).ok(); }
-                        RemoveShapeBrushes(shape_id, brush_ids)             => { changed_shapes.insert(shape_id); canvas.remove_shape_brushes(shape_id, brush_ids).ok(); }
-                        Subscribe(edit_target)                              => { if let Ok(edit_target) = context.send(edit_target) { subscribers.add_target(edit_target); } }
-
-                        SetShapeParent(shape_id, parent) => {
-                            changed_shapes.insert(shape_id);
-                            match &parent {
-                                CanvasShapeParent::Layer(layer_id, _)  => { changed_layers.insert(*layer_id); }
-                                CanvasShapeParent::Shape(parent_id) => { changed_shapes.insert(*parent_id); }
-                                CanvasShapeParent::None             => { }
-                            }
-                            canvas.set_shape_parent(shape_id, parent).ok();
-                        }
+                        VectorCanvas::Subscribe(edit_target) => { if let Ok(edit_target) = context.send(edit_target) { subscribers.add_target(edit_target); } }
 
-                        SetProperty(property_target, properties) => {
-                            match &property_target {
-                                CanvasPropertyTarget::Layer(layer_id)    => { changed_layers.insert(*layer_id); }
-                                CanvasPropertyTarget::Shape(shape_id)    => { changed_shapes.insert(*shape_id); }
-                                CanvasPropertyTarget::Brush(brush_id)    => { changed_shapes.extend(canvas.shapes_with_brush(*brush_id).unwrap_or(vec![])); }
-                                _                                        => { }
-                            }
-                            canvas.set_properties(property_target, properties).ok();
+                        VectorCanvas::StartUndoAction(description) => {
+                            // The edits before the marker belong to the previous action
+                            canvas.perform_edits_with(mem::take(&mut undoable_edits), |canvas, edit| track_changes(canvas, edit, &mut changed_layers, &mut changed_shapes)).ok();
+                            canvas.start_undo_action(description);
                         }
 
-                        RemoveProperty(property_target, property_list) => {
-                            match &property_target {
-                                CanvasPropertyTarget::Layer(layer_id)    => { changed_layers.insert(*layer_id); }
-                                CanvasPropertyTarget::Shape(shape_id)    => { changed_shapes.insert(*shape_id); }
-                                CanvasPropertyTarget::Brush(brush_id)    => { changed_shapes.extend(canvas.shapes_with_brush(*brush_id).unwrap_or(vec![])); }
-                                _                                        => { }
-                            }
-                            canvas.delete_properties(property_target, property_list).ok();
+                        VectorCanvas::FinishUndoAction => {
+                            canvas.perform_edits_with(mem::take(&mut undoable_edits), |canvas, edit| track_changes(canvas, edit, &mut changed_layers, &mut changed_shapes)).ok();
+                            canvas.finish_undo_action();
                         }
+
+                        edit => { undoable_edits.push(edit); }
                     }
                 }
 
-                if !changed_layers.is_empty() {
-                    subscribers.send(VectorCanvasUpdate::LayerChanged(changed_layers.into_iter().collect())).await;
-                }
-                if !changed_shapes.is_empty() {
-                    subscribers.send(VectorCanvasUpdate::ShapeChanged(changed_shapes.into_iter().collect())).await;
+                // Changes are tracked as each edit is applied, so they see the effects of the earlier edits in the batch
+                canvas.perform_edits_with(undoable_edits, |canvas, edit| track_changes(canvas, edit, &mut changed_layers, &mut changed_shapes)).ok();
+            }
+
+            Undo(undo) => {
+                match undo {
+                    VectorUndo::StartAction(description)    => { canvas.start_undo_action(description); }
+                    VectorUndo::FinishAction                => { canvas.finish_undo_action(); }
+                    VectorUndo::Undo                        => { canvas.undo_with(|canvas, edit| track_changes(canvas, edit, &mut changed_layers, &mut changed_shapes)).ok(); }
+                    VectorUndo::Redo                        => { canvas.redo_with(|canvas, edit| track_changes(canvas, edit, &mut changed_layers, &mut changed_shapes)).ok(); }
                 }
             }
 
@@ -103,14 +81,67 @@ pub async fn sqlite_canvas_program(input: InputStream<SqliteCanvasRequest>, cont
                 use VectorQuery::*;
 
                 match query {
-                    WholeDocument(target, when)         => { canvas.send_vec_query_response(target, &context, |canvas, response| canvas.query_document_whole(response, when)).await.ok(); },
-                    DocumentOutline(target)             => { canvas.send_vec_query_response(target, &context, |canvas, response| canvas.query_document_outline(response)).await.ok(); },
-                    Layers(target, layer_list, when)    => { canvas.send_vec_query_response(target, &context, move |canvas, response| canvas.query_layers_with_shapes(layer_list, response, when)).await.ok(); },
-                    Shapes(target, shape_list)          => { canvas.send_vec_query_response(target, &context, move |canvas, response| canvas.query_shapes(shape_list, response)).await.ok(); },
-                    Brushes(target, brush_list)         => { canvas.send_vec_query_response(target, &context, move |canvas, response| canvas.query_brushes(brush_list, response)).await.ok(); },
+                    WholeDocument(target, when)           => { canvas.send_vec_query_response(target, &context, |canvas, response| canvas.query_document_whole(response, when)).await.ok(); },
+                    DocumentOutline(target)               => { canvas.send_vec_query_response(target, &context, |canvas, response| canvas.query_document_outline(response)).await.ok(); },
+                    Layers(target, layer_list, when)      => { canvas.send_vec_query_response(target, &context, move |canvas, response| canvas.query_layers_with_shapes(layer_list, response, when)).await.ok(); },
+                    Shapes(target, shape_list)            => { canvas.send_vec_query_response(target, &context, move |canvas, response| canvas.query_shapes(shape_list, response)).await.ok(); },
+                    Brushes(target, brush_list)           => { canvas.send_vec_query_response(target, &context, move |canvas, response| canvas.query_brushes(brush_list, response)).await.ok(); },
+                    ShapeDefinitions(target, shape_list)  => { canvas.send_vec_query_response(target, &context, move |canvas, response| canvas.query_shape_definitions(shape_list, response)).await.ok(); },
                 }
             }
         }
+
+        if !changed_layers.is_empty() {
+            subscribers.send(VectorCanvasUpdate::LayerChanged(changed_layers.into_iter().collect())).await;
+        }
+        if !changed_shapes.is_empty() {
+            subscribers.send(VectorCanvasUpdate::ShapeChanged(changed_shapes.into_iter().collect())).await;
+        }
+    }
+}
+
+///
+/// Adds the layers and shapes that are affected by an edit to the sets of changed items
+///
+fn track_changes(canvas: &SqliteCanvas, edit: &VectorCanvas, changed_layers: &mut HashSet<CanvasLayerId>, changed_shapes: &mut HashSet<CanvasShapeId>) {
+    use VectorCanvas::*;
+
+    match edit {
+        AddLayer { new_layer_id, .. }       => { changed_layers.insert(*new_layer_id); }
+        RemoveLayer(layer_id)               => { changed_layers.insert(*layer_id); }
+        AddFrame { frame_layer, .. }        => { changed_layers.insert(*frame_layer); }
+        RemoveFrame { frame_layer, .. }     => { changed_layers.insert(*frame_layer); }
+        ReorderLayer { layer_id, .. }       => { changed_layers.insert(*layer_id); }
+        AddShape(shape_id, _, _)            => { changed_shapes.insert(*shape_id); }
+        RemoveShape(shape_id)               => { changed_shapes.insert(*shape_id); }
+        SetShapeDefinition(shape_id, _)     => { changed_shapes.insert(*shape_id); }
+        SetShapeTime(shape_id, _)           => { changed_shapes.insert(*shape_id); }
+        ReorderShape { shape_id, .. }       => { changed_shapes.insert(*shape_id); }
+        AddShapeBrushes(shape_id, _)        => { changed_shapes.insert(*shape_id); }
+        RemoveShapeBrushes(shape_id, _)     => { changed_shapes.insert(*shape_id); }
+        AddBrush(_)                         => { }
+        RemoveBrush(_)                      => { }
+        Subscribe(_)                        => { }
+        StartUndoAction(_)                  => { }
+        FinishUndoAction                    => { }
+
+        SetShapeParent(shape_id, parent) => {
+            changed_shapes.insert(*shape_id);
+            match parent {
+                CanvasShapeParent::Layer(layer_id, _)   => { changed_layers.insert(*layer_id); }
+                CanvasShapeParent::Shape(parent_id)     => { changed_shapes.insert(*parent_id); }
+                CanvasShapeParent::None                 => { }
+            }
+        }
+
+        SetProperty(property_target, _) | RemoveProperty(property_target, _) => {
+            match property_target {
+                CanvasPropertyTarget::Layer(layer_id)    => { changed_layers.insert(*layer_id); }
+                CanvasPropertyTarget::Shape(shape_id)    => { changed_shapes.insert(*shape_id); }
+                CanvasPropertyTarget::Brush(brush_id)    => { changed_shapes.extend(canvas.shapes_with_brush(*brush_id).unwrap_or(vec![])); }
+                _                                        => { }
+            }
+        }
     }
 }
 
@@ -133,8 +164,10 @@ impl SceneMessage for SqliteCanvasRequest {
 
         init_context.connect_programs(StreamSource::Filtered(FilterHandle::for_filter(|msgs| msgs.ready_chunks(100).map(|msgs| SqliteCanvasRequest::Edit(msgs)))), (), StreamId::with_message_type::<VectorCanvas>()).unwrap();
         init_context.connect_programs(StreamSource::Filtered(FilterHandle::for_filter(|msgs| msgs.map(|msg| SqliteCanvasRequest::Query(msg)))), (), StreamId::with_message_type::<VectorQuery>()).unwrap();
+        init_context.connect_programs(StreamSource::Filtered(FilterHandle::for_filter(|msgs| msgs.map(|msg| SqliteCanvasRequest::Undo(msg)))), (), StreamId::with_message_type::<VectorUndo>()).unwrap();
 
         init_context.connect_programs((), StreamTarget::Filtered(FilterHandle::for_filter(|msgs| msgs.ready_chunks(100).map(|msgs| SqliteCanvasRequest::Edit(msgs))), SubProgramId::called("flowbetween::sqlite_canvas")), StreamId::with_message_type::<VectorCanvas>()).unwrap();
         init_context.connect_programs((), StreamTarget::Filtered(FilterHandle::for_filter(|msgs| msgs.map(|msg| SqliteCanvasRequest::Query(msg))), SubProgramId::called("flowbetween::sqlite_canvas")), StreamId::with_message_type::<VectorQuery>()).unwrap();
+        init_context.connect_programs((), StreamTarget::Filtered(FilterHandle::for_filter(|msgs| msgs.map(|msg| SqliteCanvasRequest::Undo(msg))), SubProgramId::called("flowbetween::sqlite_canvas")), StreamId::with_message_type::<VectorUndo>()).unwrap();
     }
 }
@@ -127,7 +127,7 @@ impl SqliteCanvas {
     #[inline]
     fn delete_sql_properties(&mut self, command_template: &str, properties: impl Iterator<Item=i64>, other_params: Vec<&dyn ToSql>) -> Result<(), CanvasError> {
         // Delete all or nothing
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         // Prepare the three commands to delete the three different types of property
         let delete_blobs        = command_template.replace("{}", "Blob");
@@ -180,7 +180,7 @@ impl SqliteCanvas {
             .collect::<Result<Vec<_>, _>>()?;
 
         // Write the properties themselves
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         // Run commands to set each type of property value
         let mut blob_properties_cmd = transaction.prepare_cached("REPLACE INTO DocumentBlobProperties (PropertyId, BlobValue) VALUES (?, ?)")?;
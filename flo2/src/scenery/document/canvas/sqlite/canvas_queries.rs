@@ -214,6 +214,55 @@ impl SqliteCanvas {
         Ok(())
     }
 
+    ///
+    /// Queries the full definitions of a list of shapes: the properties set on the shapes themselves, their brushes and any shapes grouped under them
+    ///
+    pub fn query_shape_definitions(&mut self, query_shapes: impl IntoIterator<Item=CanvasShapeId>, shape_response: &mut Vec<VectorResponse>) -> Result<(), CanvasError> {
+        for shape_id in query_shapes {
+            self.query_shape_definition(shape_id, shape_response)?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Generates the definition of a single shape, recursing into its group
+    ///
+    fn query_shape_definition(&mut self, shape_id: CanvasShapeId, shape_response: &mut Vec<VectorResponse>) -> Result<(), CanvasError> {
+        // The shape itself, with only the properties that are set directly on it
+        let properties   = self.properties_for_target(CanvasPropertyTarget::Shape(shape_id))?;
+        let shape_type   = self.shapetype_for_shape(shape_id)?;
+        let canvas_shape = self.shape_for_shape_id(shape_id)?;
+        let frame_time   = FrameTime::from_nanos(self.time_for_shape(shape_id)? as _);
+
+        shape_response.push(VectorResponse::Shape(shape_id, canvas_shape, frame_time, shape_type, properties));
+
+        // The brushes that are attached to this shape
+        let brushes = self.brushes_for_shape(shape_id)?;
+        if !brushes.is_empty() {
+            shape_response.push(VectorResponse::ShapeBrushes(brushes));
+        }
+
+        // The shapes in the group for this shape
+        let shape_idx           = self.index_for_shape(shape_id)?;
+        let mut select_children = self.sqlite.prepare_cached("SELECT s.ShapeGuid FROM ShapeGroups g INNER JOIN Shapes s ON s.ShapeId = g.ShapeId WHERE g.ParentShapeId = ? ORDER BY g.OrderIdx ASC")?;
+        let children            = select_children.query_map(params![shape_idx], |row| Ok(CanvasShapeId::from_string(&row.get::<_, String>(0)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(select_children);
+
+        if !children.is_empty() {
+            shape_response.push(VectorResponse::StartGroup);
+
+            for child_id in children {
+                self.query_shape_definition(child_id, shape_response)?;
+            }
+
+            shape_response.push(VectorResponse::EndGroup);
+        }
+
+        Ok(())
+    }
+
     ///
     /// Queries the shapes and their properties on a particular layer
     ///
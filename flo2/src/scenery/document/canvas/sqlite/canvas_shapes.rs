@@ -105,7 +105,7 @@ impl SqliteCanvas {
     /// Collects all descendents of a shape in depth-first pre-order (does not include the shape itself).
     ///
     #[inline]
-    fn all_descendents_for_shape(transaction: &Transaction<'_>, shape_idx: i64) -> Result<Vec<i64>, CanvasError> {
+    pub (super) fn all_descendents_for_shape(transaction: &Connection, shape_idx: i64) -> Result<Vec<i64>, CanvasError> {
         let mut result = Vec::new();
         Self::collect_shape_dependents(transaction, shape_idx, &mut result)?;
         Ok(result)
@@ -128,7 +128,7 @@ impl SqliteCanvas {
     ///
     /// Recurses through the descendents of a shape
     ///
-    fn collect_shape_dependents(transaction: &Transaction<'_>, parent_idx: i64, result: &mut Vec<i64>) -> Result<(), CanvasError> {
+    fn collect_shape_dependents(transaction: &Connection, parent_idx: i64, result: &mut Vec<i64>) -> Result<(), CanvasError> {
         // Using a recursive Rust function because SQL CTEs don't guarantee depth-first ordering.
         let mut stmt = transaction.prepare_cached("SELECT ShapeId FROM ShapeGroups WHERE ParentShapeId = ? ORDER BY OrderIdx ASC")?;
         let children: Vec<i64> = stmt.query_map(params![parent_idx], |row| row.get(0))?
@@ -156,7 +156,7 @@ impl SqliteCanvas {
             .collect::<Result<Vec<_>, _>>()?;
 
         // Write the properties themselves
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         // Run commands to set each type of property value
         let mut blob_properties_cmd = transaction.prepare_cached("REPLACE INTO ShapeBlobProperties (ShapeId, PropertyId, BlobValue) VALUES (?, ?, ?)")?;
@@ -193,6 +193,7 @@ impl SqliteCanvas {
             CanvasShape::Rectangle(rect)    => Ok((CANVAS_RECTANGLE_V1_TYPE, postcard::to_allocvec(rect)?)),
             CanvasShape::Ellipse(ellipse)   => Ok((CANVAS_ELLIPSE_V1_TYPE, postcard::to_allocvec(ellipse)?)),
             CanvasShape::Polygon(polygon)   => Ok((CANVAS_POLYGON_V1_TYPE, postcard::to_allocvec(polygon)?)),
+            CanvasShape::Text(text)         => Ok((CANVAS_TEXT_V1_TYPE, postcard::to_allocvec(text)?)),
         }
     }
 
@@ -206,6 +207,7 @@ impl SqliteCanvas {
             CANVAS_RECTANGLE_V1_TYPE => Ok(CanvasShape::Rectangle(postcard::from_bytes(shape_data).map_err(|e| CanvasError::SerializationError(e.to_string()))?)),
             CANVAS_ELLIPSE_V1_TYPE   => Ok(CanvasShape::Ellipse(postcard::from_bytes(shape_data).map_err(|e| CanvasError::SerializationError(e.to_string()))?)),
             CANVAS_POLYGON_V1_TYPE   => Ok(CanvasShape::Polygon(postcard::from_bytes(shape_data).map_err(|e| CanvasError::SerializationError(e.to_string()))?)),
+            CANVAS_TEXT_V1_TYPE      => Ok(CanvasShape::Text(postcard::from_bytes(shape_data).map_err(|e| CanvasError::SerializationError(e.to_string()))?)),
             _                        => Err(CanvasError::UnexpectedStorageError(format!("Unknown shape data type: {}", shape_data_type))),
         }
     }
@@ -247,7 +249,7 @@ impl SqliteCanvas {
     pub fn remove_shape(&mut self, shape_id: CanvasShapeId) -> Result<(), CanvasError> {
         let shape_idx = self.index_for_shape(shape_id)?;
 
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         // Query parent info for order compaction before the cascading delete
         let layer_info = transaction.query_one("SELECT LayerId, OrderIdx FROM ShapeLayers WHERE ShapeId = ?", params![shape_idx], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))).ok();
@@ -318,7 +320,7 @@ impl SqliteCanvas {
         let shape_idx           = self.index_for_shape(shape_id)?;
         let before_shape_idx    = before_shape.map(|bs| self.index_for_shape(bs)).transpose()?;
 
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         // Check if shape is in a group first
         if let Some((parent_id, original_order)) = transaction.query_one("SELECT ParentShapeId, OrderIdx FROM ShapeGroups WHERE ShapeId = ?", params![shape_idx], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))).optional()? {
@@ -421,7 +423,7 @@ impl SqliteCanvas {
         }
 
         // Perform the update itself in a transaction
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         // Collect descendents for block operations (the shape and its descendents move together)
         let descendents = Self::all_descendents_for_shape(&transaction, shape_idx)?;
@@ -491,7 +493,7 @@ impl SqliteCanvas {
             .map(|brush_id| self.index_for_brush(*brush_id))
             .collect::<Result<Vec<_>, _>>()?;
 
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         let mut next_order: i64 = transaction.query_one("SELECT COALESCE(MAX(OrderIdx), -1) + 1 FROM ShapeBrushes WHERE ShapeId = ?", params![shape_idx], |row| row.get(0))?;
 
@@ -514,7 +516,7 @@ impl SqliteCanvas {
             .map(|brush_id| self.index_for_brush(*brush_id))
             .collect::<Result<Vec<_>, _>>()?;
 
-        let transaction = self.sqlite.transaction()?;
+        let transaction = self.sqlite.savepoint()?;
 
         for brush_idx in brush_indices {
             if let Some(order_idx) = transaction.query_one::<i64, _, _>("SELECT OrderIdx FROM ShapeBrushes WHERE ShapeId = ? AND BrushId = ?", params![shape_idx, brush_idx], |row| row.get(0)).optional()? {
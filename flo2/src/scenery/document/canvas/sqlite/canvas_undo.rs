@@ -0,0 +1,503 @@
+use super::canvas::*;
+use super::super::brush::*;
+use super::super::error::*;
+use super::super::frame_time::*;
+use super::super::layer::*;
+use super::super::property::*;
+use super::super::shape::*;
+use super::super::vector_editor::*;
+
+use rusqlite::*;
+
+use std::collections::{HashMap};
+use std::result::{Result};
+use std::time::{Duration};
+
+impl SqliteCanvas {
+    ///
+    /// Reads the properties that are set directly on a property target
+    ///
+    pub (super) fn properties_for_target(&mut self, target: CanvasPropertyTarget) -> Result<Vec<(CanvasPropertyId, CanvasProperty)>, CanvasError> {
+        let properties = match target {
+            CanvasPropertyTarget::Document          => { self.read_properties("SELECT BlobValue, PropertyId FROM DocumentBlobProperties", params![])? },
+            CanvasPropertyTarget::Layer(layer_id)   => { let layer_idx = self.index_for_layer(layer_id)?; self.read_properties("SELECT BlobValue, PropertyId FROM LayerBlobProperties WHERE LayerId = ?", params![layer_idx])? },
+            CanvasPropertyTarget::Brush(brush_id)   => { let brush_idx = self.index_for_brush(brush_id)?; self.read_properties("SELECT BlobValue, PropertyId FROM BrushBlobProperties WHERE BrushId = ?", params![brush_idx])? },
+            CanvasPropertyTarget::Shape(shape_id)   => { let shape_idx = self.index_for_shape(shape_id)?; self.read_properties("SELECT BlobValue, PropertyId FROM ShapeBlobProperties WHERE ShapeId = ?", params![shape_idx])? },
+        };
+
+        properties.into_iter()
+            .map(|(property_idx, value)| Ok((self.property_for_index(property_idx)?, value)))
+            .collect()
+    }
+
+    ///
+    /// Runs a query that returns property values in column 0 and property indexes in column 1
+    ///
+    fn read_properties(&self, query: &str, params: &[&dyn ToSql]) -> Result<Vec<(i64, CanvasProperty)>, CanvasError> {
+        let mut query   = self.sqlite.prepare_cached(query)?;
+        let properties  = query.query_map(params, |row| Ok((row.get::<_, i64>(1)?, Self::decode_property(row))))?
+            .flatten()
+            .flat_map(|(property_idx, value)| Some((property_idx, value?)))
+            .collect();
+
+        Ok(properties)
+    }
+
+    ///
+    /// Retrieves the shape ID for a shape index
+    ///
+    fn shape_for_index(&self, shape_idx: i64) -> Result<CanvasShapeId, CanvasError> {
+        let shape_guid = self.sqlite.query_one::<String, _, _>("SELECT ShapeGuid FROM Shapes WHERE ShapeId = ?", params![shape_idx], |row| row.get(0))?;
+
+        Ok(CanvasShapeId::from_string(&shape_guid))
+    }
+
+    ///
+    /// Returns the layer that the specified layer is ordered before (None if it's the topmost layer)
+    ///
+    fn layer_after(&self, layer_id: CanvasLayerId) -> Result<Option<CanvasLayerId>, CanvasError> {
+        let layer_guid = self.sqlite.query_one::<String, _, _>("SELECT LayerGuid FROM Layers WHERE OrderIdx = (SELECT OrderIdx FROM Layers WHERE LayerGuid = ?) + 1", params![layer_id.to_string()], |row| row.get(0)).optional()?;
+
+        Ok(layer_guid.map(|layer_guid| CanvasLayerId::from_string(&layer_guid)))
+    }
+
+    ///
+    /// Returns true if a layer has a frame at the specified time
+    ///
+    fn layer_has_frame(&mut self, layer_id: CanvasLayerId, when: FrameTime) -> Result<bool, CanvasError> {
+        let layer_idx   = self.index_for_layer(layer_id)?;
+        let num_frames  = self.sqlite.query_one::<i64, _, _>("SELECT COUNT(*) FROM LayerFrames WHERE LayerId = ? AND Time = ?", params![layer_idx, when.as_nanos()], |row| row.get(0))?;
+
+        Ok(num_frames > 0)
+    }
+
+    ///
+    /// Returns the parent of a shape, along with the shape that follows it in that parent (if there is one)
+    ///
+    fn shape_parent(&mut self, shape_id: CanvasShapeId) -> Result<(CanvasShapeParent, Option<CanvasShapeId>), CanvasError> {
+        let shape_idx = self.index_for_shape(shape_id)?;
+
+        // Shapes in groups
+        if let Some((parent_idx, order_idx)) = self.sqlite.query_one("SELECT ParentShapeId, OrderIdx FROM ShapeGroups WHERE ShapeId = ?", params![shape_idx], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))).optional()? {
+            let parent_id   = self.shape_for_index(parent_idx)?;
+            let next_shape  = self.sqlite.query_one::<String, _, _>("
+                SELECT      s.ShapeGuid
+                FROM        ShapeGroups g
+                INNER JOIN  Shapes      s ON s.ShapeId = g.ShapeId
+                WHERE       g.ParentShapeId = ? AND g.OrderIdx > ?
+                ORDER BY    g.OrderIdx ASC
+                LIMIT 1", params![parent_idx, order_idx], |row| row.get(0)).optional()?;
+
+            return Ok((CanvasShapeParent::Shape(parent_id), next_shape.map(|shape_guid| CanvasShapeId::from_string(&shape_guid))));
+        }
+
+        // Shapes directly on layers (the next shape is the next one on the layer that's not in a group)
+        if let Some((layer_guid, layer_idx, order_idx, time)) = self.sqlite.query_one("
+                SELECT      l.LayerGuid, sl.LayerId, sl.OrderIdx, sl.Time
+                FROM        ShapeLayers sl
+                INNER JOIN  Layers      l ON l.LayerId = sl.LayerId
+                WHERE       sl.ShapeId = ?", params![shape_idx], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?, row.get::<_, i64>(3)?))).optional()? {
+            let next_shape = self.sqlite.query_one::<String, _, _>("
+                SELECT      s.ShapeGuid
+                FROM        ShapeLayers sl
+                INNER JOIN  Shapes      s ON s.ShapeId = sl.ShapeId
+                WHERE       sl.LayerId = ? AND sl.OrderIdx > ? AND NOT EXISTS (SELECT 1 FROM ShapeGroups g WHERE g.ShapeId = sl.ShapeId)
+                ORDER BY    sl.OrderIdx ASC
+                LIMIT 1", params![layer_idx, order_idx], |row| row.get(0)).optional()?;
+
+            return Ok((CanvasShapeParent::Layer(CanvasLayerId::from_string(&layer_guid), FrameTime::from_nanos(time as _)), next_shape.map(|shape_guid| CanvasShapeId::from_string(&shape_guid))));
+        }
+
+        Ok((CanvasShapeParent::None, None))
+    }
+
+    ///
+    /// Returns the descendents of a shape in depth-first order, along with the group that each one is a part of
+    ///
+    fn descendents_with_parents(&mut self, shape_id: CanvasShapeId) -> Result<Vec<(CanvasShapeId, CanvasShapeId)>, CanvasError> {
+        let shape_idx = self.index_for_shape(shape_id)?;
+
+        // Reading the descendents needs a transaction, but it's not committed as nothing is changed
+        let transaction = self.sqlite.savepoint()?;
+        let descendents = Self::all_descendents_for_shape(&transaction, shape_idx)?;
+        drop(transaction);
+
+        descendents.into_iter()
+            .map(|descendent_idx| {
+                let parent_idx = self.sqlite.query_one::<i64, _, _>("SELECT ParentShapeId FROM ShapeGroups WHERE ShapeId = ?", params![descendent_idx], |row| row.get(0))?;
+
+                Ok((self.shape_for_index(descendent_idx)?, self.shape_for_index(parent_idx)?))
+            })
+            .collect()
+    }
+
+    ///
+    /// Returns the brushes attached to a shape, in order
+    ///
+    pub (super) fn brushes_for_shape(&mut self, shape_id: CanvasShapeId) -> Result<Vec<CanvasBrushId>, CanvasError> {
+        let shape_idx   = self.index_for_shape(shape_id)?;
+        let mut query   = self.sqlite.prepare_cached("SELECT b.BrushGuid FROM ShapeBrushes sb JOIN Brushes b ON sb.BrushId = b.BrushId WHERE sb.ShapeId = ? ORDER BY sb.OrderIdx ASC")?;
+        let brushes     = query.query_map(params![shape_idx], |row| Ok(CanvasBrushId::from_string(&row.get::<_, String>(0)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(brushes)
+    }
+
+    ///
+    /// Creates the edits that will restore the specified properties to their current values
+    ///
+    fn restore_properties(&mut self, target: CanvasPropertyTarget, property_ids: impl IntoIterator<Item=CanvasPropertyId>) -> Result<Vec<VectorCanvas>, CanvasError> {
+        let current_values = self.properties_for_target(target)?.into_iter().collect::<HashMap<_, _>>();
+
+        // Properties that are set are restored to their values, and properties that are not set are removed
+        let mut set_properties      = vec![];
+        let mut removed_properties  = vec![];
+
+        for property_id in property_ids {
+            if let Some(value) = current_values.get(&property_id) {
+                set_properties.push((property_id, value.clone()));
+            } else {
+                removed_properties.push(property_id);
+            }
+        }
+
+        let mut edits = vec![];
+        if !set_properties.is_empty()       { edits.push(VectorCanvas::SetProperty(target, set_properties)); }
+        if !removed_properties.is_empty()   { edits.push(VectorCanvas::RemoveProperty(target, removed_properties)); }
+
+        Ok(edits)
+    }
+
+    ///
+    /// Creates the edits that will put a shape back in its current position in its parent
+    ///
+    fn restore_shape_parent(&mut self, shape_id: CanvasShapeId) -> Result<Vec<VectorCanvas>, CanvasError> {
+        let (parent, next_shape)    = self.shape_parent(shape_id)?;
+        let mut edits               = vec![VectorCanvas::SetShapeParent(shape_id, parent.clone())];
+
+        if let Some(next_shape) = next_shape {
+            edits.push(VectorCanvas::ReorderShape { shape_id: shape_id, before_shape: Some(next_shape) });
+
+            // Reordering a shape on a layer moves it to the time of the shape it's placed before
+            if let CanvasShapeParent::Layer(_, when) = parent {
+                if self.time_for_shape(next_shape)? != when.as_nanos() {
+                    edits.push(VectorCanvas::SetShapeTime(shape_id, when));
+
+                    for (descendent_id, _) in self.descendents_with_parents(shape_id)? {
+                        edits.push(VectorCanvas::SetShapeTime(descendent_id, FrameTime::from_nanos(self.time_for_shape(descendent_id)? as _)));
+                    }
+                }
+            }
+        }
+
+        Ok(edits)
+    }
+
+    ///
+    /// Creates the edits that will restore a layer and its contents after it has been removed
+    ///
+    fn restore_layer(&mut self, layer_id: CanvasLayerId) -> Result<Vec<VectorCanvas>, CanvasError> {
+        let layer_idx = self.index_for_layer(layer_id)?;
+
+        let mut edits = vec![VectorCanvas::AddLayer { new_layer_id: layer_id, before_layer: self.layer_after(layer_id)? }];
+
+        // Layer properties
+        let properties = self.properties_for_target(CanvasPropertyTarget::Layer(layer_id))?;
+        if !properties.is_empty() {
+            edits.push(VectorCanvas::SetProperty(CanvasPropertyTarget::Layer(layer_id), properties));
+        }
+
+        // Frames (the length of a frame is not stored in the canvas)
+        let mut frames_query    = self.sqlite.prepare_cached("SELECT Time FROM LayerFrames WHERE LayerId = ? ORDER BY Time ASC")?;
+        let frames              = frames_query.query_map(params![layer_idx], |row| row.get::<_, i64>(0))?.collect::<Result<Vec<_>, _>>()?;
+        drop(frames_query);
+
+        edits.extend(frames.into_iter().map(|when| VectorCanvas::AddFrame { frame_layer: layer_id, when: FrameTime::from_nanos(when as _), length: Duration::ZERO }));
+
+        // The shapes that are directly on the layer (shapes in groups move with their parent)
+        let mut shapes_query    = self.sqlite.prepare_cached("
+            SELECT      s.ShapeGuid, sl.Time
+            FROM        ShapeLayers sl
+            INNER JOIN  Shapes      s ON s.ShapeId = sl.ShapeId
+            WHERE       sl.LayerId = ? AND NOT EXISTS (SELECT 1 FROM ShapeGroups g WHERE g.ShapeId = sl.ShapeId)
+            ORDER BY    sl.OrderIdx ASC")?;
+        let shapes              = shapes_query.query_map(params![layer_idx], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?.collect::<Result<Vec<_>, _>>()?;
+        drop(shapes_query);
+
+        edits.extend(shapes.into_iter().map(|(shape_guid, when)| VectorCanvas::SetShapeParent(CanvasShapeId::from_string(&shape_guid), CanvasShapeParent::Layer(layer_id, FrameTime::from_nanos(when as _)))));
+
+        Ok(edits)
+    }
+
+    ///
+    /// Creates the edits that will restore a shape and the shapes grouped with it after it has been removed
+    ///
+    fn restore_shape(&mut self, shape_id: CanvasShapeId) -> Result<Vec<VectorCanvas>, CanvasError> {
+        let descendents = self.descendents_with_parents(shape_id)?;
+        let mut edits   = vec![];
+
+        // Recreate the shape and its descendents, along with their properties and brushes
+        for restore_id in Some(shape_id).into_iter().chain(descendents.iter().map(|(descendent_id, _)| *descendent_id)) {
+            edits.push(VectorCanvas::AddShape(restore_id, self.shapetype_for_shape(restore_id)?, self.shape_for_shape_id(restore_id)?));
+
+            let properties = self.properties_for_target(CanvasPropertyTarget::Shape(restore_id))?;
+            if !properties.is_empty() {
+                edits.push(VectorCanvas::SetProperty(CanvasPropertyTarget::Shape(restore_id), properties));
+            }
+
+            let brushes = self.brushes_for_shape(restore_id)?;
+            if !brushes.is_empty() {
+                edits.push(VectorCanvas::AddShapeBrushes(restore_id, brushes));
+            }
+        }
+
+        // Put the shape back where it was, then regroup the descendents (in depth-first order so they keep their ordering)
+        edits.extend(self.restore_shape_parent(shape_id)?);
+        edits.extend(descendents.into_iter().map(|(descendent_id, parent_id)| VectorCanvas::SetShapeParent(descendent_id, CanvasShapeParent::Shape(parent_id))));
+
+        Ok(edits)
+    }
+
+    ///
+    /// Creates the edits that will restore a brush and where it's used after it has been removed
+    ///
+    fn restore_brush(&mut self, brush_id: CanvasBrushId) -> Result<Vec<VectorCanvas>, CanvasError> {
+        let mut edits = vec![VectorCanvas::AddBrush(brush_id)];
+
+        let properties = self.properties_for_target(CanvasPropertyTarget::Brush(brush_id))?;
+        if !properties.is_empty() {
+            edits.push(VectorCanvas::SetProperty(CanvasPropertyTarget::Brush(brush_id), properties));
+        }
+
+        // Replace the brushes for every shape that uses this one, so the brush ordering is preserved
+        let mut shapes = self.shapes_with_brush(brush_id)?;
+        shapes.sort();
+        shapes.dedup();
+
+        for shape_id in shapes {
+            edits.extend(self.restore_shape_brushes(shape_id)?);
+        }
+
+        Ok(edits)
+    }
+
+    ///
+    /// Creates the edits that will restore the brushes attached to a shape
+    ///
+    fn restore_shape_brushes(&mut self, shape_id: CanvasShapeId) -> Result<Vec<VectorCanvas>, CanvasError> {
+        let brushes = self.brushes_for_shape(shape_id)?;
+
+        Ok(vec![
+            VectorCanvas::RemoveShapeBrushes(shape_id, brushes.clone()),
+            VectorCanvas::AddShapeBrushes(shape_id, brushes),
+        ])
+    }
+
+    ///
+    /// Returns the edits that will reverse the specified edit, based on the current state of the canvas
+    ///
+    /// This has to be called before the edit is applied.
+    ///
+    pub fn reverse_edits(&mut self, edit: &VectorCanvas) -> Result<Vec<VectorCanvas>, CanvasError> {
+        use VectorCanvas::*;
+
+        match edit {
+            AddLayer { new_layer_id, .. }           => Ok(vec![RemoveLayer(*new_layer_id)]),
+            RemoveLayer(layer_id)                   => self.restore_layer(*layer_id),
+            ReorderLayer { layer_id, .. }           => Ok(vec![ReorderLayer { layer_id: *layer_id, before_layer: self.layer_after(*layer_id)? }]),
+            SetShapeDefinition(shape_id, _)         => Ok(vec![SetShapeDefinition(*shape_id, self.shape_for_shape_id(*shape_id)?)]),
+            SetShapeTime(shape_id, _)               => Ok(vec![SetShapeTime(*shape_id, FrameTime::from_nanos(self.time_for_shape(*shape_id)? as _))]),
+            RemoveShape(shape_id)                   => self.restore_shape(*shape_id),
+            ReorderShape { shape_id, .. }           => self.restore_shape_parent(*shape_id),
+            SetShapeParent(shape_id, _)             => self.restore_shape_parent(*shape_id),
+            AddBrush(brush_id)                      => Ok(vec![RemoveBrush(*brush_id)]),
+            RemoveBrush(brush_id)                   => self.restore_brush(*brush_id),
+            SetProperty(target, properties)         => self.restore_properties(*target, properties.iter().map(|(property_id, _)| *property_id)),
+            RemoveProperty(target, property_ids)    => self.restore_properties(*target, property_ids.iter().copied()),
+            AddShapeBrushes(shape_id, brush_ids)    => Ok(vec![RemoveShapeBrushes(*shape_id, brush_ids.clone())]),
+            RemoveShapeBrushes(shape_id, _)         => self.restore_shape_brushes(*shape_id),
+            Subscribe(_)                            => Ok(vec![]),
+            StartUndoAction(_)                      => Ok(vec![]),
+            FinishUndoAction                        => Ok(vec![]),
+
+            AddFrame { frame_layer, when, .. } => {
+                if self.layer_has_frame(*frame_layer, *when)? {
+                    Ok(vec![])
+                } else {
+                    Ok(vec![RemoveFrame { frame_layer: *frame_layer, when: *when }])
+                }
+            }
+
+            RemoveFrame { frame_layer, when } => {
+                if self.layer_has_frame(*frame_layer, *when)? {
+                    Ok(vec![AddFrame { frame_layer: *frame_layer, when: *when, length: Duration::ZERO }])
+                } else {
+                    Ok(vec![])
+                }
+            }
+
+            AddShape(shape_id, _, _) => {
+                if self.index_for_shape(*shape_id).optional()?.is_some() {
+                    // Adding an existing shape replaces its definition
+                    Ok(vec![AddShape(*shape_id, self.shapetype_for_shape(*shape_id)?, self.shape_for_shape_id(*shape_id)?)])
+                } else {
+                    Ok(vec![RemoveShape(*shape_id)])
+                }
+            }
+        }
+    }
+
+    ///
+    /// Finishes the current undo action and starts a new one with the specified description
+    ///
+    pub fn start_undo_action(&mut self, description: String) {
+        self.finish_undo_action();
+        self.undo_description = Some(description);
+    }
+
+    ///
+    /// Finishes the current undo action, so that further edits are undone separately
+    ///
+    pub fn finish_undo_action(&mut self) {
+        self.undo_action        = None;
+        self.undo_description   = None;
+    }
+
+    ///
+    /// Adds a set of edits and the edits that reverse them to the current undo action
+    ///
+    pub (super) fn record_undo(&mut self, redo_edits: &Vec<VectorCanvas>, undo_edits: &Vec<VectorCanvas>) -> Result<(), CanvasError> {
+        let redo_edits  = postcard::to_allocvec(redo_edits)?;
+        let undo_edits  = postcard::to_allocvec(undo_edits)?;
+
+        let transaction = self.sqlite.savepoint()?;
+
+        let action_id = if let Some(action_id) = self.undo_action {
+            action_id
+        } else {
+            // Starting a new action means that the actions that have been undone can no longer be redone
+            transaction.execute("DELETE FROM UndoActions WHERE Undone = 1", [])?;
+
+            let description = self.undo_description.clone().unwrap_or_default();
+            transaction.query_one::<i64, _, _>("INSERT INTO UndoActions (Description, Undone) VALUES (?, 0) RETURNING ActionId", params![description], |row| row.get(0))?
+        };
+
+        let edit_idx = transaction.query_one::<i64, _, _>("SELECT COALESCE(MAX(EditIdx), -1) + 1 FROM UndoEdits WHERE ActionId = ?", params![action_id], |row| row.get(0))?;
+        transaction.execute("INSERT INTO UndoEdits (ActionId, EditIdx, RedoEdits, UndoEdits) VALUES (?, ?, ?, ?)", params![action_id, edit_idx, redo_edits, undo_edits])?;
+
+        transaction.commit()?;
+
+        self.undo_action = Some(action_id);
+
+        Ok(())
+    }
+
+    ///
+    /// Reads the edits for an undo action, using a query that returns the serialized edit lists in column 0
+    ///
+    fn read_undo_edits(&self, query: &str, action_id: i64) -> Result<Vec<VectorCanvas>, CanvasError> {
+        let mut query   = self.sqlite.prepare_cached(query)?;
+        let edit_lists  = query.query_map(params![action_id], |row| row.get::<_, Vec<u8>>(0))?.collect::<Result<Vec<_>, _>>()?;
+
+        let mut edits = vec![];
+        for edit_list in edit_lists {
+            edits.extend(postcard::from_bytes::<Vec<VectorCanvas>>(&edit_list)?);
+        }
+
+        Ok(edits)
+    }
+
+    ///
+    /// Applies the edits for an undo or redo action and marks the action as undone or redone
+    ///
+    /// This happens in a single transaction: if any of the edits fail, the canvas and the undo history are left as they were.
+    ///
+    fn apply_undo_edits(&mut self, action_id: i64, edits: &[VectorCanvas], undone: bool, before_edit: &mut impl FnMut(&SqliteCanvas, &VectorCanvas)) -> Result<(), CanvasError> {
+        self.sqlite.execute_batch("SAVEPOINT ApplyUndoAction")?;
+
+        let mut apply_edits = || -> Result<(), CanvasError> {
+            for edit in edits.iter() {
+                before_edit(self, edit);
+                self.apply_edit(edit.clone())?;
+            }
+
+            self.sqlite.execute("UPDATE UndoActions SET Undone = ? WHERE ActionId = ?", params![undone, action_id])?;
+
+            Ok(())
+        };
+
+        match apply_edits() {
+            Ok(()) => {
+                self.sqlite.execute_batch("RELEASE ApplyUndoAction")?;
+                Ok(())
+            }
+
+            Err(err) => {
+                // The edits that were applied are rolled back, so anything they cached is out of date
+                self.sqlite.execute_batch("ROLLBACK TO ApplyUndoAction; RELEASE ApplyUndoAction").ok();
+                self.clear_caches();
+
+                Err(err)
+            }
+        }
+    }
+
+    ///
+    /// Reverses the most recent action in the undo history, returning the edits that were applied to the canvas
+    ///
+    pub fn undo(&mut self) -> Result<Vec<VectorCanvas>, CanvasError> {
+        self.undo_with(|_canvas, _edit| { })
+    }
+
+    ///
+    /// Reverses the most recent action in the undo history as for `undo()`, calling `before_edit` with the state of the canvas
+    /// just before each edit is applied
+    ///
+    pub fn undo_with(&mut self, mut before_edit: impl FnMut(&SqliteCanvas, &VectorCanvas)) -> Result<Vec<VectorCanvas>, CanvasError> {
+        self.finish_undo_action();
+
+        let Some(action_id) = self.sqlite.query_one::<Option<i64>, _, _>("SELECT MAX(ActionId) FROM UndoActions WHERE Undone = 0", [], |row| row.get(0))? else { return Ok(vec![]); };
+
+        // The undo edits for each set of edits are already reversed, so the sets are just applied in reverse order
+        let edits = self.read_undo_edits("SELECT UndoEdits FROM UndoEdits WHERE ActionId = ? ORDER BY EditIdx DESC", action_id)?;
+        self.apply_undo_edits(action_id, &edits, true, &mut before_edit)?;
+
+        Ok(edits)
+    }
+
+    ///
+    /// Re-applies the most recently undone action, returning the edits that were applied to the canvas
+    ///
+    pub fn redo(&mut self) -> Result<Vec<VectorCanvas>, CanvasError> {
+        self.redo_with(|_canvas, _edit| { })
+    }
+
+    ///
+    /// Re-applies the most recently undone action as for `redo()`, calling `before_edit` with the state of the canvas just
+    /// before each edit is applied
+    ///
+    pub fn redo_with(&mut self, mut before_edit: impl FnMut(&SqliteCanvas, &VectorCanvas)) -> Result<Vec<VectorCanvas>, CanvasError> {
+        self.finish_undo_action();
+
+        let Some(action_id) = self.sqlite.query_one::<Option<i64>, _, _>("SELECT MIN(ActionId) FROM UndoActions WHERE Undone = 1", [], |row| row.get(0))? else { return Ok(vec![]); };
+
+        let edits = self.read_undo_edits("SELECT RedoEdits FROM UndoEdits WHERE ActionId = ? ORDER BY EditIdx ASC", action_id)?;
+        self.apply_undo_edits(action_id, &edits, false, &mut before_edit)?;
+
+        Ok(edits)
+    }
+
+    ///
+    /// Returns the descriptions of the actions that can be undone and redone, with the next action to undo or redo first
+    ///
+    pub fn undo_history(&self) -> Result<(Vec<String>, Vec<String>), CanvasError> {
+        let mut undo_query  = self.sqlite.prepare_cached("SELECT Description FROM UndoActions WHERE Undone = 0 ORDER BY ActionId DESC")?;
+        let undo            = undo_query.query_map([], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>()?;
+        drop(undo_query);
+
+        let mut redo_query  = self.sqlite.prepare_cached("SELECT Description FROM UndoActions WHERE Undone = 1 ORDER BY ActionId ASC")?;
+        let redo            = redo_query.query_map([], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok((undo, redo))
+    }
+}
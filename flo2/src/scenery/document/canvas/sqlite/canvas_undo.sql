@@ -0,0 +1,30 @@
+/**
+ * Undo history for the canvas
+ *
+ * These tables were added after the main schema, so they're created on open if they're missing from an existing document
+ **/
+
+/**
+ * The actions in the undo history for this document
+ *
+ * Actions that have been undone have Undone set to 1 and can be redone. They are removed when a new action is added.
+ **/
+CREATE TABLE IF NOT EXISTS UndoActions (
+    ActionId    INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    Description TEXT    NOT NULL,
+    Undone      INTEGER NOT NULL
+);
+
+/**
+ * The edits that make up each undo action (postcard serialized `Vec<VectorCanvas>` values)
+ *
+ * RedoEdits are the edits as they were originally performed, and UndoEdits are the edits that reverse them
+ **/
+CREATE TABLE IF NOT EXISTS UndoEdits (
+    ActionId    INTEGER NOT NULL REFERENCES UndoActions(ActionId) ON DELETE CASCADE,
+    EditIdx     INTEGER NOT NULL,
+    RedoEdits   BLOB    NOT NULL,
+    UndoEdits   BLOB    NOT NULL,
+
+    PRIMARY KEY (ActionId, EditIdx)
+);
@@ -1,10 +1,12 @@
 mod canvas;
 mod canvas_brushes;
+mod canvas_edits;
 mod canvas_layers;
 mod canvas_properties;
 mod canvas_queries;
 mod canvas_shapes;
 mod canvas_program;
+mod canvas_undo;
 mod error;
 mod id_cache;
 
@@ -16,3 +18,6 @@ mod test_canvas;
 
 #[cfg(test)]
 mod test_canvas_program;
+
+#[cfg(test)]
+mod test_canvas_undo;
@@ -1248,3 +1248,40 @@ fn ellipse_visual_properties_read_back_2() {
         ]),
     ], "Response was {:?}", response);
 }
+
+#[test]
+fn query_shape_definitions_with_group_and_brushes() {
+    let mut canvas  = SqliteCanvas::new_in_memory().unwrap();
+    let layer_1     = CanvasLayerId::new();
+    let group       = CanvasShapeId::new();
+    let child_1     = CanvasShapeId::new();
+    let child_2     = CanvasShapeId::new();
+    let brush_1     = CanvasBrushId::new();
+
+    // A group containing two shapes, with a brush attached to the second one
+    canvas.add_layer(layer_1, None).unwrap();
+    canvas.add_brush(brush_1).unwrap();
+    canvas.set_properties(CanvasPropertyTarget::Brush(brush_1), vec![(CanvasPropertyId::new("test::brush"), CanvasProperty::Int(1))]).unwrap();
+
+    canvas.add_shape(group, test_shape_type(), CanvasShape::Group).unwrap();
+    canvas.add_shape(child_1, test_shape_type(), test_rect()).unwrap();
+    canvas.add_shape(child_2, test_shape_type(), test_ellipse()).unwrap();
+    canvas.set_shape_parent(group, CanvasShapeParent::Layer(layer_1, FrameTime::ZERO)).unwrap();
+    canvas.set_shape_parent(child_1, CanvasShapeParent::Shape(group)).unwrap();
+    canvas.set_shape_parent(child_2, CanvasShapeParent::Shape(group)).unwrap();
+    canvas.add_shape_brushes(child_2, vec![brush_1]).unwrap();
+    canvas.set_properties(CanvasPropertyTarget::Shape(child_2), vec![(CanvasPropertyId::new("test::shape"), CanvasProperty::Int(2))]).unwrap();
+
+    // The definition should include the group's children, and the brush properties shouldn't be merged into the shape
+    let mut response = vec![];
+    canvas.query_shape_definitions(vec![group], &mut response).unwrap();
+
+    assert!(response == vec![
+        VectorResponse::Shape(group, CanvasShape::Group, FrameTime::ZERO, test_shape_type(), vec![]),
+        VectorResponse::StartGroup,
+        VectorResponse::Shape(child_1, test_rect(), FrameTime::ZERO, test_shape_type(), vec![]),
+        VectorResponse::Shape(child_2, test_ellipse(), FrameTime::ZERO, test_shape_type(), vec![(CanvasPropertyId::new("test::shape"), CanvasProperty::Int(2))]),
+        VectorResponse::ShapeBrushes(vec![brush_1]),
+        VectorResponse::EndGroup,
+    ], "Response was {:?}", response);
+}
@@ -673,3 +673,110 @@ fn ellipse_visual_properties_read_back_via_layers_query() {
         .expect_message_matching(TestResponse(expected), "Ellipse visual properties")
         .run_in_scene(&scene, test_program);
 }
+
+#[test]
+fn undo_action_with_program() {
+    let scene = Scene::default();
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct TestResponse(Vec<VectorResponse>);
+
+    impl SceneMessage for TestResponse { }
+
+    let test_program    = SubProgramId::new();
+    let query_program   = SubProgramId::new();
+
+    let layer_1         = CanvasLayerId::new();
+    let layer_2         = CanvasLayerId::new();
+
+    // Add two layers as separate actions, then undo the second one
+    scene.add_subprogram(query_program, move |_input: InputStream<()>, context| async move {
+        let _sqlite     = context.send::<SqliteCanvasRequest>(()).unwrap();
+        let mut canvas  = context.send(()).unwrap();
+        let mut undo    = context.send(()).unwrap();
+
+        undo.send(VectorUndo::StartAction("Add layer 1".to_string())).await.unwrap();
+        canvas.send(VectorCanvas::AddLayer { new_layer_id: layer_1, before_layer: None }).await.unwrap();
+        undo.send(VectorUndo::StartAction("Add layer 2".to_string())).await.unwrap();
+        canvas.send(VectorCanvas::AddLayer { new_layer_id: layer_2, before_layer: None }).await.unwrap();
+        undo.send(VectorUndo::FinishAction).await.unwrap();
+
+        undo.send(VectorUndo::Undo).await.unwrap();
+
+        // Query the document outline
+        let outline = context.spawn_query(ReadCommand::default(), VectorQuery::DocumentOutline(().into()), ()).unwrap();
+        let outline = outline.collect::<Vec<_>>().await;
+
+        context.send_message(TestResponse(outline)).await.unwrap();
+    }, 1);
+
+    let expected_rest = vec![
+        VectorResponse::Layer(layer_1, vec![]),
+        VectorResponse::LayerOrder(vec![layer_1]),
+    ];
+
+    TestBuilder::new()
+        .expect_message(move |response: TestResponse| {
+            let outline = response.0;
+
+            if outline[1..] != expected_rest[..] {
+                return Err(format!("Expected only layer 1 ({:?}), got {:?}", layer_1, &outline[1..]));
+            }
+            Ok(())
+        })
+        .run_in_scene(&scene, test_program);
+}
+
+#[test]
+fn undo_action_markers_on_edit_stream() {
+    let scene = Scene::default();
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct TestResponse(Vec<VectorResponse>);
+
+    impl SceneMessage for TestResponse { }
+
+    let test_program    = SubProgramId::new();
+    let query_program   = SubProgramId::new();
+
+    let layer_1         = CanvasLayerId::new();
+    let layer_2         = CanvasLayerId::new();
+
+    // Add two layers as separate actions, marking the actions in the same stream as the edits, then undo the second one
+    scene.add_subprogram(query_program, move |_input: InputStream<()>, context| async move {
+        let _sqlite     = context.send::<SqliteCanvasRequest>(()).unwrap();
+        let mut canvas  = context.send(()).unwrap();
+        let mut undo    = context.send(()).unwrap();
+
+        canvas.send(VectorCanvas::StartUndoAction("Add layer 1".to_string())).await.unwrap();
+        canvas.send(VectorCanvas::AddLayer { new_layer_id: layer_1, before_layer: None }).await.unwrap();
+        canvas.send(VectorCanvas::StartUndoAction("Add layer 2".to_string())).await.unwrap();
+        canvas.send(VectorCanvas::AddLayer { new_layer_id: layer_2, before_layer: None }).await.unwrap();
+        canvas.send(VectorCanvas::FinishUndoAction).await.unwrap();
+        context.wait_for_idle(100).await;
+
+        undo.send(VectorUndo::Undo).await.unwrap();
+
+        // Query the document outline
+        let outline = context.spawn_query(ReadCommand::default(), VectorQuery::DocumentOutline(().into()), ()).unwrap();
+        let outline = outline.collect::<Vec<_>>().await;
+
+        context.send_message(TestResponse(outline)).await.unwrap();
+    }, 1);
+
+    let expected_rest = vec![
+        VectorResponse::Layer(layer_1, vec![]),
+        VectorResponse::LayerOrder(vec![layer_1]),
+    ];
+
+    TestBuilder::new()
+        .expect_message(move |response: TestResponse| {
+            let outline = response.0;
+
+            if outline[1..] != expected_rest[..] {
+                return Err(format!("Expected only layer 1 ({:?}), got {:?}", layer_1, &outline[1..]));
+            }
+            Ok(())
+        })
+        .run_in_scene(&scene, test_program);
+}
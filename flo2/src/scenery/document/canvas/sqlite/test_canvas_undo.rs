@@ -0,0 +1,352 @@
+use super::*;
+use super::super::point::*;
+
+use super::super::brush::*;
+use super::super::frame_time::*;
+use super::super::layer::*;
+use super::super::property::*;
+use super::super::queries::*;
+use super::super::shape::*;
+use super::super::shape_type::*;
+use super::super::vector_editor::*;
+use rusqlite::*;
+
+use std::time::{Duration};
+
+fn test_shape_type() -> ShapeType {
+    ShapeType::new("flowbetween::test")
+}
+
+fn test_rect() -> CanvasShape {
+    CanvasShape::Rectangle(CanvasRectangle { min: CanvasPoint { x: 0.0, y: 0.0 }, max: CanvasPoint { x: 10.0, y: 10.0 } })
+}
+
+fn test_ellipse() -> CanvasShape {
+    CanvasShape::Ellipse(CanvasEllipse { min: CanvasPoint { x: 0.0, y: 0.0 }, max: CanvasPoint { x: 5.0, y: 5.0 }, direction: CanvasPoint { x: 1.0, y: 0.0 } })
+}
+
+/// Helper: reads everything in the document at time 0
+fn whole_document(canvas: &mut SqliteCanvas) -> Vec<VectorResponse> {
+    let mut document = vec![];
+    canvas.query_document_whole(&mut document, FrameTime::ZERO).unwrap();
+    document
+}
+
+/// Helper: returns the ShapeGuids for shapes in a group, ordered by OrderIdx
+fn shapes_in_group(canvas: &SqliteCanvas, parent_shape_id: CanvasShapeId) -> Vec<String> {
+    let parent_idx  = canvas.sqlite.query_one::<i64, _, _>("SELECT ShapeId FROM Shapes WHERE ShapeGuid = ?", [parent_shape_id.to_string()], |row| row.get(0)).unwrap();
+    let mut stmt    = canvas.sqlite.prepare("SELECT s.ShapeGuid FROM ShapeGroups sg JOIN Shapes s ON sg.ShapeId = s.ShapeId WHERE sg.ParentShapeId = ? ORDER BY sg.OrderIdx ASC").unwrap();
+    let rows        = stmt.query_map(params![parent_idx], |row| row.get::<_, String>(0)).unwrap();
+    rows.map(|r| r.unwrap()).collect()
+}
+
+/// Helper: creates a canvas with a layer containing a rectangle and a group containing an ellipse and another rectangle
+fn canvas_with_shapes() -> (SqliteCanvas, CanvasLayerId, CanvasShapeId, CanvasShapeId, CanvasShapeId, CanvasShapeId) {
+    let mut canvas  = SqliteCanvas::new_in_memory().unwrap();
+    let layer       = CanvasLayerId::new();
+    let rect        = CanvasShapeId::new();
+    let group       = CanvasShapeId::new();
+    let ellipse     = CanvasShapeId::new();
+    let group_rect  = CanvasShapeId::new();
+
+    canvas.perform_edits(vec![
+        VectorCanvas::AddLayer { new_layer_id: layer, before_layer: None },
+        VectorCanvas::AddShape(rect, test_shape_type(), test_rect()),
+        VectorCanvas::SetShapeParent(rect, CanvasShapeParent::Layer(layer, FrameTime::ZERO)),
+        VectorCanvas::AddShape(group, test_shape_type(), CanvasShape::Group),
+        VectorCanvas::SetShapeParent(group, CanvasShapeParent::Layer(layer, FrameTime::ZERO)),
+        VectorCanvas::AddShape(ellipse, test_shape_type(), test_ellipse()),
+        VectorCanvas::SetShapeParent(ellipse, CanvasShapeParent::Shape(group)),
+        VectorCanvas::AddShape(group_rect, test_shape_type(), test_rect()),
+        VectorCanvas::SetShapeParent(group_rect, CanvasShapeParent::Shape(group)),
+        VectorCanvas::SetProperty(CanvasPropertyTarget::Shape(ellipse), vec![(CanvasPropertyId::new("test::property"), CanvasProperty::Int(42))]),
+    ]).unwrap();
+    canvas.finish_undo_action();
+
+    (canvas, layer, rect, group, ellipse, group_rect)
+}
+
+#[test]
+fn undo_add_layer() {
+    let mut canvas  = SqliteCanvas::new_in_memory().unwrap();
+    let layer       = CanvasLayerId::new();
+    let empty       = whole_document(&mut canvas);
+
+    canvas.perform_edits(vec![VectorCanvas::AddLayer { new_layer_id: layer, before_layer: None }]).unwrap();
+    let with_layer  = whole_document(&mut canvas);
+    assert!(with_layer != empty);
+
+    canvas.undo().unwrap();
+    assert!(whole_document(&mut canvas) == empty, "{:?}", whole_document(&mut canvas));
+
+    canvas.redo().unwrap();
+    assert!(whole_document(&mut canvas) == with_layer, "{:?}", whole_document(&mut canvas));
+}
+
+#[test]
+fn undo_set_property_restores_previous_value() {
+    let mut canvas  = SqliteCanvas::new_in_memory().unwrap();
+    let layer       = CanvasLayerId::new();
+    let property    = CanvasPropertyId::new("test::property");
+
+    canvas.perform_edits(vec![
+        VectorCanvas::AddLayer { new_layer_id: layer, before_layer: None },
+        VectorCanvas::SetProperty(CanvasPropertyTarget::Layer(layer), vec![(property, CanvasProperty::Int(1))]),
+    ]).unwrap();
+    canvas.finish_undo_action();
+
+    let before = whole_document(&mut canvas);
+
+    // Overwrite the existing property and add a new one
+    canvas.perform_edits(vec![VectorCanvas::SetProperty(CanvasPropertyTarget::Layer(layer), vec![(property, CanvasProperty::Int(2)), (CanvasPropertyId::new("test::other"), CanvasProperty::Float(3.0))])]).unwrap();
+    assert!(whole_document(&mut canvas) != before);
+
+    canvas.undo().unwrap();
+    assert!(whole_document(&mut canvas) == before, "{:?}", whole_document(&mut canvas));
+}
+
+#[test]
+fn undo_remove_property() {
+    let (mut canvas, _, _, _, ellipse, _) = canvas_with_shapes();
+    let before = whole_document(&mut canvas);
+
+    canvas.perform_edits(vec![VectorCanvas::RemoveProperty(CanvasPropertyTarget::Shape(ellipse), vec![CanvasPropertyId::new("test::property")])]).unwrap();
+    assert!(whole_document(&mut canvas) != before);
+
+    canvas.undo().unwrap();
+    assert!(whole_document(&mut canvas) == before, "{:?}", whole_document(&mut canvas));
+}
+
+#[test]
+fn undo_remove_group_restores_children() {
+    let (mut canvas, _, _, group, ellipse, group_rect) = canvas_with_shapes();
+    let before = whole_document(&mut canvas);
+
+    canvas.perform_edits(vec![VectorCanvas::RemoveShape(group)]).unwrap();
+    assert!(whole_document(&mut canvas) != before);
+
+    canvas.undo().unwrap();
+    assert!(whole_document(&mut canvas) == before, "{:?}", whole_document(&mut canvas));
+    assert!(shapes_in_group(&canvas, group) == vec![ellipse.to_string(), group_rect.to_string()]);
+}
+
+#[test]
+fn undo_remove_layer_restores_shapes() {
+    let (mut canvas, layer, _, _, _, _) = canvas_with_shapes();
+    let before = whole_document(&mut canvas);
+
+    canvas.perform_edits(vec![VectorCanvas::RemoveLayer(layer)]).unwrap();
+    assert!(whole_document(&mut canvas) != before);
+
+    canvas.undo().unwrap();
+    assert!(whole_document(&mut canvas) == before, "{:?}", whole_document(&mut canvas));
+}
+
+#[test]
+fn undo_reorder_shape_in_group() {
+    let (mut canvas, _, _, group, ellipse, group_rect) = canvas_with_shapes();
+    let before = whole_document(&mut canvas);
+
+    canvas.perform_edits(vec![VectorCanvas::ReorderShape { shape_id: group_rect, before_shape: Some(ellipse) }]).unwrap();
+    assert!(shapes_in_group(&canvas, group) == vec![group_rect.to_string(), ellipse.to_string()]);
+
+    canvas.undo().unwrap();
+    assert!(shapes_in_group(&canvas, group) == vec![ellipse.to_string(), group_rect.to_string()]);
+    assert!(whole_document(&mut canvas) == before, "{:?}", whole_document(&mut canvas));
+}
+
+#[test]
+fn undo_move_shape_out_of_group() {
+    let (mut canvas, layer, _, _, ellipse, _) = canvas_with_shapes();
+    let before = whole_document(&mut canvas);
+
+    canvas.perform_edits(vec![VectorCanvas::SetShapeParent(ellipse, CanvasShapeParent::Layer(layer, FrameTime::ZERO))]).unwrap();
+    assert!(whole_document(&mut canvas) != before);
+
+    canvas.undo().unwrap();
+    assert!(whole_document(&mut canvas) == before, "{:?}", whole_document(&mut canvas));
+}
+
+#[test]
+fn undo_set_shape_definition() {
+    let (mut canvas, _, rect, _, _, _) = canvas_with_shapes();
+    let before = whole_document(&mut canvas);
+
+    canvas.perform_edits(vec![VectorCanvas::SetShapeDefinition(rect, test_ellipse())]).unwrap();
+    assert!(whole_document(&mut canvas) != before);
+
+    canvas.undo().unwrap();
+    assert!(whole_document(&mut canvas) == before, "{:?}", whole_document(&mut canvas));
+}
+
+#[test]
+fn undo_remove_brush_restores_brush_order() {
+    let (mut canvas, _, rect, _, _, _) = canvas_with_shapes();
+    let brush_1 = CanvasBrushId::new();
+    let brush_2 = CanvasBrushId::new();
+
+    canvas.perform_edits(vec![
+        VectorCanvas::AddBrush(brush_1),
+        VectorCanvas::AddBrush(brush_2),
+        VectorCanvas::SetProperty(CanvasPropertyTarget::Brush(brush_1), vec![(CanvasPropertyId::new("test::brush"), CanvasProperty::Int(1))]),
+        VectorCanvas::SetProperty(CanvasPropertyTarget::Brush(brush_2), vec![(CanvasPropertyId::new("test::brush"), CanvasProperty::Int(2))]),
+        VectorCanvas::AddShapeBrushes(rect, vec![brush_1, brush_2]),
+    ]).unwrap();
+    canvas.finish_undo_action();
+
+    let before = whole_document(&mut canvas);
+
+    canvas.perform_edits(vec![VectorCanvas::RemoveBrush(brush_2)]).unwrap();
+    assert!(whole_document(&mut canvas) != before);
+
+    canvas.undo().unwrap();
+    assert!(whole_document(&mut canvas) == before, "{:?}", whole_document(&mut canvas));
+}
+
+#[test]
+fn undo_add_and_remove_frame() {
+    let (mut canvas, layer, _, _, _, _) = canvas_with_shapes();
+    let when = FrameTime::from_nanos(1_000_000);
+
+    canvas.perform_edits(vec![VectorCanvas::AddFrame { frame_layer: layer, when: when, length: Duration::from_millis(100) }]).unwrap();
+    canvas.finish_undo_action();
+    assert!(canvas.layer_frame_time(layer, when).unwrap() == when);
+
+    canvas.perform_edits(vec![VectorCanvas::RemoveFrame { frame_layer: layer, when: when }]).unwrap();
+    assert!(canvas.layer_frame_time(layer, when).unwrap() == FrameTime::ZERO);
+
+    canvas.undo().unwrap();
+    assert!(canvas.layer_frame_time(layer, when).unwrap() == when);
+
+    canvas.undo().unwrap();
+    assert!(canvas.layer_frame_time(layer, when).unwrap() == FrameTime::ZERO);
+}
+
+#[test]
+fn edits_are_grouped_into_actions() {
+    let mut canvas  = SqliteCanvas::new_in_memory().unwrap();
+    let layer_1     = CanvasLayerId::new();
+    let layer_2     = CanvasLayerId::new();
+    let layer_3     = CanvasLayerId::new();
+    let empty       = whole_document(&mut canvas);
+
+    canvas.start_undo_action("Add two layers".to_string());
+    canvas.perform_edits(vec![VectorCanvas::AddLayer { new_layer_id: layer_1, before_layer: None }]).unwrap();
+    canvas.perform_edits(vec![VectorCanvas::AddLayer { new_layer_id: layer_2, before_layer: None }]).unwrap();
+    canvas.finish_undo_action();
+    let two_layers  = whole_document(&mut canvas);
+
+    canvas.start_undo_action("Add one layer".to_string());
+    canvas.perform_edits(vec![VectorCanvas::AddLayer { new_layer_id: layer_3, before_layer: Some(layer_1) }]).unwrap();
+    canvas.finish_undo_action();
+
+    assert!(canvas.undo_history().unwrap() == (vec!["Add one layer".to_string(), "Add two layers".to_string()], vec![]));
+
+    canvas.undo().unwrap();
+    assert!(whole_document(&mut canvas) == two_layers, "{:?}", whole_document(&mut canvas));
+    assert!(canvas.undo_history().unwrap() == (vec!["Add two layers".to_string()], vec!["Add one layer".to_string()]));
+
+    canvas.undo().unwrap();
+    assert!(whole_document(&mut canvas) == empty, "{:?}", whole_document(&mut canvas));
+
+    // Nothing left to undo
+    assert!(canvas.undo().unwrap().is_empty());
+}
+
+#[test]
+fn failed_undo_leaves_canvas_and_history_unchanged() {
+    let mut canvas  = SqliteCanvas::new_in_memory().unwrap();
+    let layer_1     = CanvasLayerId::new();
+    let layer_2     = CanvasLayerId::new();
+
+    canvas.start_undo_action("Add two layers".to_string());
+    canvas.perform_edits(vec![VectorCanvas::AddLayer { new_layer_id: layer_1, before_layer: None }]).unwrap();
+    canvas.perform_edits(vec![VectorCanvas::AddLayer { new_layer_id: layer_2, before_layer: None }]).unwrap();
+    canvas.finish_undo_action();
+
+    // Remove the first layer behind the undo history's back, so the last edit in the undo action fails
+    canvas.sqlite.execute("DELETE FROM Layers WHERE LayerGuid = ?", [layer_1.to_string()]).unwrap();
+    let before_undo = whole_document(&mut canvas);
+
+    // Removing the second layer succeeds, but should be rolled back when removing the first layer fails
+    assert!(canvas.undo().is_err());
+    assert!(whole_document(&mut canvas) == before_undo, "{:?}", whole_document(&mut canvas));
+    assert!(canvas.undo_history().unwrap() == (vec!["Add two layers".to_string()], vec![]));
+}
+
+#[test]
+fn new_edit_clears_redo() {
+    let mut canvas  = SqliteCanvas::new_in_memory().unwrap();
+    let layer_1     = CanvasLayerId::new();
+    let layer_2     = CanvasLayerId::new();
+
+    canvas.perform_edits(vec![VectorCanvas::AddLayer { new_layer_id: layer_1, before_layer: None }]).unwrap();
+    canvas.undo().unwrap();
+    assert!(canvas.undo_history().unwrap().1.len() == 1);
+
+    canvas.perform_edits(vec![VectorCanvas::AddLayer { new_layer_id: layer_2, before_layer: None }]).unwrap();
+    assert!(canvas.undo_history().unwrap() == (vec!["".to_string()], vec![]));
+    assert!(canvas.redo().unwrap().is_empty());
+}
+
+#[test]
+fn undo_history_survives_reload() {
+    let path    = std::env::temp_dir().join(format!("flowbetween-undo-test-{}.flo", CanvasLayerId::new()));
+    let layer   = CanvasLayerId::new();
+    let empty;
+
+    {
+        let mut canvas = SqliteCanvas::with_connection(Connection::open(&path).unwrap()).unwrap();
+        canvas.initialise().unwrap();
+        empty = whole_document(&mut canvas);
+
+        canvas.start_undo_action("Add layer".to_string());
+        canvas.perform_edits(vec![
+            VectorCanvas::AddLayer { new_layer_id: layer, before_layer: None },
+            VectorCanvas::SetProperty(CanvasPropertyTarget::Layer(layer), vec![(CanvasPropertyId::new("test::property"), CanvasProperty::Int(42))]),
+        ]).unwrap();
+        canvas.finish_undo_action();
+    }
+
+    {
+        let mut canvas = SqliteCanvas::with_connection(Connection::open(&path).unwrap()).unwrap();
+
+        assert!(canvas.undo_history().unwrap() == (vec!["Add layer".to_string()], vec![]));
+
+        canvas.undo().unwrap();
+        assert!(whole_document(&mut canvas) == empty, "{:?}", whole_document(&mut canvas));
+    }
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn undo_tables_are_added_to_existing_documents() {
+    let path    = std::env::temp_dir().join(format!("flowbetween-undo-upgrade-test-{}.flo", CanvasLayerId::new()));
+    let layer   = CanvasLayerId::new();
+    let empty;
+
+    {
+        // Create a document without the undo tables, as it would have been before the undo history was added
+        let mut canvas = SqliteCanvas::with_connection(Connection::open(&path).unwrap()).unwrap();
+        canvas.initialise().unwrap();
+        empty = whole_document(&mut canvas);
+
+        canvas.sqlite.execute_batch("DROP TABLE UndoEdits; DROP TABLE UndoActions;").unwrap();
+    }
+
+    {
+        let mut canvas = SqliteCanvas::with_connection(Connection::open(&path).unwrap()).unwrap();
+
+        canvas.start_undo_action("Add layer".to_string());
+        canvas.perform_edits(vec![VectorCanvas::AddLayer { new_layer_id: layer, before_layer: None }]).unwrap();
+        canvas.finish_undo_action();
+
+        assert!(canvas.undo_history().unwrap() == (vec!["Add layer".to_string()], vec![]));
+
+        canvas.undo().unwrap();
+        assert!(whole_document(&mut canvas) == empty, "{:?}", whole_document(&mut canvas));
+    }
+
+    std::fs::remove_file(&path).ok();
+}
@@ -0,0 +1,32 @@
+use flo_scene::*;
+
+use ::serde::*;
+
+///
+/// Requests that manage the undo history of a vector canvas
+///
+/// Edits sent to the canvas as `VectorCanvas` messages are added to the current undo action until it is finished
+/// with `FinishAction` (or a new action is started). Undoing reverses a whole action at once. The history is stored
+/// alongside the document, so it's still available after the document is reloaded.
+///
+/// These requests aren't ordered with the `VectorCanvas` stream, so a program that sends a group of edits that should be
+/// undone together should mark the action with `VectorCanvas::StartUndoAction` and `VectorCanvas::FinishUndoAction` instead.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum VectorUndo {
+    /// Finishes any existing action and starts a new one, with a description that can be displayed to the user
+    StartAction(String),
+
+    /// Finishes the current action: any edits after this will be undone separately
+    FinishAction,
+
+    /// Reverses the most recent action
+    Undo,
+
+    /// Re-applies the most recently undone action
+    Redo,
+}
+
+impl SceneMessage for VectorUndo {
+
+}
@@ -80,6 +80,14 @@ pub enum VectorCanvas {
 
     /// Subscribe for any updates to this canvas (eg, to implement a rendering program)
     Subscribe(StreamTarget),
+
+    /// Finishes any existing undo action and starts a new one with a description (as for `VectorUndo::StartAction`)
+    ///
+    /// This is sent in order with the other edits, so it's guaranteed to apply to the edits that follow it on the same stream
+    StartUndoAction(String),
+
+    /// Finishes the current undo action (as for `VectorUndo::FinishAction`), after the edits sent before it on the same stream
+    FinishUndoAction,
 }
 
 ///
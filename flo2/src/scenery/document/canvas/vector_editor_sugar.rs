@@ -4,6 +4,8 @@ use super::layer::*;
 use super::shape::*;
 use super::shape_type::*;
 use super::property::*;
+use super::queries::*;
+use super::undo::*;
 use super::vector_editor::*;
 
 use flo_scene::*;
@@ -173,6 +175,28 @@ pub async fn vector_detach_brushes_to_shape(shape_id: CanvasShapeId, brushes: im
     vector_editor.send(VectorCanvas::RemoveShapeBrushes(shape_id, brushes.into_iter().collect())).await.unwrap();
 }
 
+///
+/// Replaces a text shape in the canvas with a path shape containing the outlines of its glyphs, so the lettering can be edited by hand
+///
+/// The shape keeps its ID, properties and place in the canvas. Shapes that aren't text are left unchanged.
+///
+pub async fn vector_convert_text_to_path(shape_id: CanvasShapeId) {
+    // Fetch the context
+    let context             = scene_context().expect("Must be called from a flo_scene subprogram");
+    let mut vector_editor   = context.send(()).unwrap();
+
+    // Read the current definition of the shape
+    let mut shapes = query_vector_shapes([shape_id]);
+    while let Some(response) = shapes.next().await {
+        if let VectorResponse::Shape(_, shape, _, _, _) = response {
+            // Replacing the shape definition keeps its parent and properties
+            if let Some(path) = shape.convert_text_to_path().await {
+                vector_editor.send(VectorCanvas::AddShape(shape_id, ShapeType::default(), path)).await.unwrap();
+            }
+        }
+    }
+}
+
 ///
 /// Adds a brush to the canvas in the current scene
 ///
@@ -224,3 +248,49 @@ pub async fn vector_remove_frame(layer_id: CanvasLayerId, when: impl Into<FrameT
 
     vector_editor.send(VectorCanvas::RemoveFrame { frame_layer: layer_id, when: when.into() }).await.unwrap();
 }
+
+///
+/// Starts a new undo action for the canvas in the current scene. Edits until the action is finished are undone together
+///
+pub async fn vector_start_undo_action(description: impl Into<String>) {
+    // Fetch the context
+    let context             = scene_context().expect("Must be called from a flo_scene subprogram");
+    let mut vector_editor   = context.send(()).unwrap();
+
+    // Sent along with the edits so that it's ordered with them
+    vector_editor.send(VectorCanvas::StartUndoAction(description.into())).await.unwrap();
+}
+
+///
+/// Finishes the current undo action for the canvas in the current scene
+///
+pub async fn vector_finish_undo_action() {
+    // Fetch the context
+    let context             = scene_context().expect("Must be called from a flo_scene subprogram");
+    let mut vector_editor   = context.send(()).unwrap();
+
+    // Sent along with the edits so that it's ordered with them
+    vector_editor.send(VectorCanvas::FinishUndoAction).await.unwrap();
+}
+
+///
+/// Undoes the most recent action on the canvas in the current scene
+///
+pub async fn vector_undo() {
+    // Fetch the context
+    let context         = scene_context().expect("Must be called from a flo_scene subprogram");
+    let mut vector_undo = context.send(()).unwrap();
+
+    vector_undo.send(VectorUndo::Undo).await.unwrap();
+}
+
+///
+/// Redoes the most recently undone action on the canvas in the current scene
+///
+pub async fn vector_redo() {
+    // Fetch the context
+    let context         = scene_context().expect("Must be called from a flo_scene subprogram");
+    let mut vector_undo = context.send(()).unwrap();
+
+    vector_undo.send(VectorUndo::Redo).await.unwrap();
+}
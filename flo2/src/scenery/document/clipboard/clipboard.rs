@@ -0,0 +1,148 @@
+use super::clipboard_contents::*;
+use crate::scenery::document::canvas::*;
+
+use flo_scene::*;
+use flo_scene::commands::*;
+use flo_scene::programs::*;
+
+use futures::prelude::*;
+use ::serde::*;
+
+///
+/// The formats that the clipboard can be read or written in
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ClipboardFormat {
+    /// `ClipboardContents` in postcard format (compact, for copying between FlowBetween documents)
+    Postcard,
+
+    /// `ClipboardContents` in JSON format
+    Json,
+
+    /// An SVG document (for copying to and from other tools)
+    Svg,
+}
+
+///
+/// Requests for the clipboard program
+///
+/// The clipboard holds a self-contained copy of a set of shapes along with their groups, properties and brushes, so it can be
+/// pasted back into the document it was copied from or (via `GetData` and `SetData`) into a different document.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Clipboard {
+    /// Copies the specified shapes (along with the shapes in their groups and their brushes) from the document, replacing the clipboard contents
+    Copy(Vec<CanvasShapeId>),
+
+    /// Pastes the clipboard contents into the document as new shapes on the specified layer and frame
+    Paste(CanvasLayerId, FrameTime),
+
+    /// Replaces the clipboard contents with data in the specified format
+    SetData(ClipboardFormat, Vec<u8>),
+
+    /// Sends the clipboard contents in the specified format to a target as a `ClipboardData` message
+    GetData(StreamTarget, ClipboardFormat),
+}
+
+///
+/// The contents of the clipboard in a particular format, sent in response to `Clipboard::GetData`
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClipboardData(pub ClipboardFormat, pub Vec<u8>);
+
+impl SceneMessage for Clipboard {
+    fn default_target() -> StreamTarget {
+        SubProgramId::called("flowbetween::clipboard").into()
+    }
+
+    fn initialise(init_context: &impl SceneInitialisationContext) {
+        init_context.add_subprogram(SubProgramId::called("flowbetween::clipboard"), clipboard_program, 20);
+        init_context.connect_programs((), SubProgramId::called("flowbetween::clipboard"), StreamId::with_message_type::<Clipboard>()).unwrap();
+    }
+}
+
+impl SceneMessage for ClipboardData {
+
+}
+
+impl ClipboardContents {
+    ///
+    /// Serializes these contents in a particular clipboard format
+    ///
+    pub fn to_format(&self, format: ClipboardFormat) -> Vec<u8> {
+        match format {
+            ClipboardFormat::Postcard   => self.to_postcard().unwrap_or_default(),
+            ClipboardFormat::Json       => self.to_json().unwrap_or_default().into_bytes(),
+            ClipboardFormat::Svg        => self.to_svg().into_bytes(),
+        }
+    }
+
+    ///
+    /// Reads clipboard contents from data in a particular clipboard format, returning None if the data is not valid
+    ///
+    pub fn from_format(format: ClipboardFormat, data: &[u8]) -> Option<Self> {
+        match format {
+            ClipboardFormat::Postcard   => Self::from_postcard(data).ok(),
+            ClipboardFormat::Json       => Self::from_json(str::from_utf8(data).ok()?).ok(),
+            ClipboardFormat::Svg        => Self::from_svg(str::from_utf8(data).ok()?),
+        }
+    }
+}
+
+///
+/// Runs the clipboard program, which copies shapes from and pastes shapes into the vector canvas in the current scene
+///
+pub async fn clipboard_program(input: InputStream<Clipboard>, context: SceneContext) {
+    let mut contents = ClipboardContents::default();
+
+    let mut input = input;
+    while let Some(msg) = input.next().await {
+        match msg {
+            Clipboard::Copy(shape_ids) => {
+                // Read the shapes, then the brushes they use
+                let mut responses   = context.spawn_query(ReadCommand::default(), VectorQuery::ShapeDefinitions(().into(), shape_ids), ()).unwrap().collect::<Vec<_>>().await;
+                let brush_ids       = ClipboardContents::from_responses(responses.clone()).used_brushes();
+
+                if !brush_ids.is_empty() {
+                    responses.extend(context.spawn_query(ReadCommand::default(), VectorQuery::Brushes(().into(), brush_ids), ()).unwrap().collect::<Vec<_>>().await);
+                }
+
+                contents = ClipboardContents::from_responses(responses);
+            }
+
+            Clipboard::Paste(layer_id, when) => {
+                if contents.is_empty() { continue; }
+
+                let (_new_shapes, edits) = contents.paste_edits(layer_id, when);
+
+                // The paste is undone as a single action (the action markers are sent on the same stream as the edits so they stay in order)
+                let Ok(mut vector_editor)   = context.send::<VectorCanvas>(()) else { continue; };
+
+                vector_editor.send(VectorCanvas::StartUndoAction("Paste".to_string())).await.ok();
+                for edit in edits {
+                    vector_editor.send(edit).await.ok();
+                }
+                vector_editor.send(VectorCanvas::FinishUndoAction).await.ok();
+            }
+
+            Clipboard::SetData(format, data) => {
+                if let Some(new_contents) = ClipboardContents::from_format(format, &data) {
+                    contents = new_contents;
+                }
+            }
+
+            Clipboard::GetData(target, format) => {
+                if let Ok(mut target) = context.send(target) {
+                    // SVG can only contain paths, so text is converted to the outlines of its glyphs first
+                    let data = if format == ClipboardFormat::Svg {
+                        contents.with_text_as_paths().await.to_format(format)
+                    } else {
+                        contents.to_format(format)
+                    };
+
+                    target.send(ClipboardData(format, data)).await.ok();
+                }
+            }
+        }
+    }
+}
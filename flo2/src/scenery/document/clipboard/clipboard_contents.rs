@@ -0,0 +1,237 @@
+use crate::scenery::document::canvas::*;
+
+use ::serde::*;
+
+use std::collections::*;
+
+///
+/// A shape stored on the clipboard, along with the shapes that are grouped with it
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClipboardShape {
+    /// The ID of the shape in the document it was copied from
+    pub shape_id: CanvasShapeId,
+
+    /// The definition of the shape
+    pub shape: CanvasShape,
+
+    /// The type of this shape
+    pub shape_type: ShapeType,
+
+    /// The properties set directly on this shape
+    pub properties: Vec<(CanvasPropertyId, CanvasProperty)>,
+
+    /// The brushes attached to this shape (these are the IDs from the original document, which are found in the brushes for the clipboard)
+    pub brushes: Vec<CanvasBrushId>,
+
+    /// The shapes grouped with this one, in bottom-to-top order
+    pub children: Vec<ClipboardShape>,
+}
+
+///
+/// A brush stored on the clipboard
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClipboardBrush {
+    /// The ID of the brush in the document it was copied from
+    pub brush_id: CanvasBrushId,
+
+    /// The properties set on this brush
+    pub properties: Vec<(CanvasPropertyId, CanvasProperty)>,
+}
+
+///
+/// A self-contained set of shapes that can be copied from one document and pasted into another
+///
+/// Property IDs and shape types are serialized by name, so they are mapped to the matching IDs in the process that
+/// deserializes the contents. Shape and brush IDs are replaced with new IDs whenever the contents are pasted.
+///
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ClipboardContents {
+    /// The shapes on the clipboard, in bottom-to-top order
+    pub shapes: Vec<ClipboardShape>,
+
+    /// The brushes used by the shapes on the clipboard
+    pub brushes: Vec<ClipboardBrush>,
+}
+
+impl ClipboardContents {
+    ///
+    /// Creates the clipboard contents from the response to a `VectorQuery::ShapeDefinitions` query followed by a `VectorQuery::Brushes` query
+    ///
+    pub fn from_responses(responses: impl IntoIterator<Item=VectorResponse>) -> Self {
+        // Shapes are added to the top of the stack, which grows when a group is started
+        let mut shape_stack = vec![vec![]];
+        let mut brushes     = vec![];
+
+        for response in responses {
+            match response {
+                VectorResponse::Shape(shape_id, shape, _when, shape_type, properties) => {
+                    shape_stack.last_mut().unwrap().push(ClipboardShape {
+                        shape_id:   shape_id,
+                        shape:      shape,
+                        shape_type: shape_type,
+                        properties: properties,
+                        brushes:    vec![],
+                        children:   vec![],
+                    });
+                }
+
+                VectorResponse::ShapeBrushes(shape_brushes) => {
+                    if let Some(shape) = shape_stack.last_mut().unwrap().last_mut() {
+                        shape.brushes = shape_brushes;
+                    }
+                }
+
+                VectorResponse::StartGroup => {
+                    shape_stack.push(vec![]);
+                }
+
+                VectorResponse::EndGroup => {
+                    if shape_stack.len() > 1 {
+                        let children = shape_stack.pop().unwrap();
+
+                        if let Some(parent) = shape_stack.last_mut().unwrap().last_mut() {
+                            parent.children = children;
+                        }
+                    }
+                }
+
+                VectorResponse::Brush(brush_id, properties) => {
+                    brushes.push(ClipboardBrush { brush_id: brush_id, properties: properties });
+                }
+
+                _ => { }
+            }
+        }
+
+        // Close any groups that weren't finished
+        while shape_stack.len() > 1 {
+            let children = shape_stack.pop().unwrap();
+
+            if let Some(parent) = shape_stack.last_mut().unwrap().last_mut() {
+                parent.children = children;
+            }
+        }
+
+        ClipboardContents {
+            shapes:     shape_stack.pop().unwrap(),
+            brushes:    brushes,
+        }
+    }
+
+    ///
+    /// Returns the IDs of all of the brushes used by the shapes on the clipboard (including the shapes in groups)
+    ///
+    pub fn used_brushes(&self) -> Vec<CanvasBrushId> {
+        let mut brushes     = vec![];
+        let mut seen        = HashSet::new();
+        let mut to_visit    = self.shapes.iter().collect::<Vec<_>>();
+
+        while let Some(shape) = to_visit.pop() {
+            for brush_id in shape.brushes.iter() {
+                if seen.insert(*brush_id) {
+                    brushes.push(*brush_id);
+                }
+            }
+
+            to_visit.extend(shape.children.iter());
+        }
+
+        brushes
+    }
+
+    ///
+    /// True if there are no shapes on the clipboard
+    ///
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    ///
+    /// Serializes these contents in postcard format
+    ///
+    pub fn to_postcard(&self) -> Result<Vec<u8>, postcard::Error> {
+        postcard::to_allocvec(self)
+    }
+
+    ///
+    /// Reads clipboard contents serialized by `to_postcard()`
+    ///
+    pub fn from_postcard(bytes: &[u8]) -> Result<Self, postcard::Error> {
+        postcard::from_bytes(bytes)
+    }
+
+    ///
+    /// Serializes these contents as JSON
+    ///
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    ///
+    /// Reads clipboard contents serialized by `to_json()`
+    ///
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    ///
+    /// Creates the edits that will paste these contents into a document as new shapes on the specified layer and frame
+    ///
+    /// Every shape and brush is given a new ID, so the same contents can be pasted many times into the same document.
+    /// Returns the IDs of the new shapes at the top level of the paste, along with the edits.
+    ///
+    pub fn paste_edits(&self, layer_id: CanvasLayerId, when: FrameTime) -> (Vec<CanvasShapeId>, Vec<VectorCanvas>) {
+        let mut edits = vec![];
+
+        // Create new copies of the brushes
+        let mut new_brush_ids = HashMap::new();
+
+        for brush in self.brushes.iter() {
+            let new_brush_id = CanvasBrushId::new();
+            new_brush_ids.insert(brush.brush_id, new_brush_id);
+
+            edits.push(VectorCanvas::AddBrush(new_brush_id));
+            if !brush.properties.is_empty() {
+                edits.push(VectorCanvas::SetProperty(CanvasPropertyTarget::Brush(new_brush_id), brush.properties.clone()));
+            }
+        }
+
+        // Add the shapes to the layer
+        let new_shape_ids = self.shapes.iter()
+            .map(|shape| Self::paste_shape_edits(shape, CanvasShapeParent::Layer(layer_id, when), &new_brush_ids, &mut edits))
+            .collect();
+
+        (new_shape_ids, edits)
+    }
+
+    ///
+    /// Adds the edits to paste a shape and its group to a list of edits, returning the new ID of the shape
+    ///
+    fn paste_shape_edits(shape: &ClipboardShape, parent: CanvasShapeParent, new_brush_ids: &HashMap<CanvasBrushId, CanvasBrushId>, edits: &mut Vec<VectorCanvas>) -> CanvasShapeId {
+        let new_shape_id = CanvasShapeId::new();
+
+        edits.push(VectorCanvas::AddShape(new_shape_id, shape.shape_type, shape.shape.clone()));
+        if !shape.properties.is_empty() {
+            edits.push(VectorCanvas::SetProperty(CanvasPropertyTarget::Shape(new_shape_id), shape.properties.clone()));
+        }
+        edits.push(VectorCanvas::SetShapeParent(new_shape_id, parent));
+
+        // Brushes that weren't copied along with the shape are left out
+        let brushes = shape.brushes.iter()
+            .flat_map(|brush_id| new_brush_ids.get(brush_id).copied())
+            .collect::<Vec<_>>();
+        if !brushes.is_empty() {
+            edits.push(VectorCanvas::AddShapeBrushes(new_shape_id, brushes));
+        }
+
+        // Add the children to the group for the new shape
+        for child in shape.children.iter() {
+            Self::paste_shape_edits(child, CanvasShapeParent::Shape(new_shape_id), new_brush_ids, edits);
+        }
+
+        new_shape_id
+    }
+}
@@ -0,0 +1,272 @@
+use super::clipboard_contents::*;
+use crate::scenery::document::canvas::*;
+
+use flo_draw::canvas::*;
+use flo_svg::*;
+
+use std::collections::*;
+use std::fmt::{Write};
+
+impl ClipboardContents {
+    ///
+    /// Converts the shapes on the clipboard to an SVG document, so they can be pasted into other tools
+    ///
+    /// Shapes are written as paths, using the fill and stroke from their properties and brushes. Groups are written as `<g>` elements.
+    /// Text shapes have no path until they're converted with `with_text_as_paths()`.
+    ///
+    pub fn to_svg(&self) -> String {
+        let brushes = self.brushes.iter()
+            .map(|brush| (brush.brush_id, &brush.properties))
+            .collect::<HashMap<_, _>>();
+
+        // Write out the shapes and measure the bounds at the same time
+        let mut body    = String::new();
+        let mut bounds  = None;
+
+        for shape in self.shapes.iter() {
+            Self::write_svg_shape(shape, &brushes, &mut body, &mut bounds, 1);
+        }
+
+        // Wrap the shapes in an SVG element
+        let mut svg = String::new();
+
+        if let Some(((min_x, min_y), (max_x, max_y))) = bounds {
+            writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">", min_x, min_y, max_x-min_x, max_y-min_y).ok();
+        } else {
+            writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\">").ok();
+        }
+
+        svg.push_str(&body);
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+
+    ///
+    /// Returns these contents with any text shapes replaced by the outlines of their glyphs (so they can be written as SVG)
+    ///
+    pub async fn with_text_as_paths(&self) -> ClipboardContents {
+        // Convert the text shapes in the same depth-first order that they're replaced in
+        let mut texts = vec![];
+        Self::find_text_shapes(&self.shapes, &mut texts);
+
+        let mut paths = vec![];
+        for text in texts {
+            paths.push(text.convert_text_to_path().await);
+        }
+
+        let mut contents    = self.clone();
+        let mut paths       = paths.into_iter();
+        Self::replace_text_shapes(&mut contents.shapes, &mut paths);
+
+        contents
+    }
+
+    ///
+    /// Finds the text shapes in a set of clipboard shapes (depth-first)
+    ///
+    fn find_text_shapes(shapes: &Vec<ClipboardShape>, texts: &mut Vec<CanvasShape>) {
+        for shape in shapes.iter() {
+            if let CanvasShape::Text(_) = &shape.shape {
+                texts.push(shape.shape.clone());
+            }
+
+            Self::find_text_shapes(&shape.children, texts);
+        }
+    }
+
+    ///
+    /// Replaces the text shapes in a set of clipboard shapes with paths generated for the shapes found by `find_text_shapes()`
+    ///
+    fn replace_text_shapes(shapes: &mut Vec<ClipboardShape>, paths: &mut impl Iterator<Item=Option<CanvasShape>>) {
+        for shape in shapes.iter_mut() {
+            if let CanvasShape::Text(_) = &shape.shape {
+                if let Some(Some(path)) = paths.next() {
+                    shape.shape = path;
+                }
+            }
+
+            Self::replace_text_shapes(&mut shape.children, paths);
+        }
+    }
+
+    ///
+    /// Writes out a shape (and the shapes in its group) as SVG
+    ///
+    fn write_svg_shape(shape: &ClipboardShape, brushes: &HashMap<CanvasBrushId, &Vec<(CanvasPropertyId, CanvasProperty)>>, svg: &mut String, bounds: &mut Option<((f64, f64), (f64, f64))>, indent: usize) {
+        let indent_str = "  ".repeat(indent);
+
+        if !shape.children.is_empty() {
+            writeln!(svg, "{}<g>", indent_str).ok();
+        }
+
+        // Shape properties take priority over brush properties, and later brushes take priority over earlier ones
+        let mut properties = shape.properties.clone();
+        for brush_id in shape.brushes.iter().rev() {
+            if let Some(brush_properties) = brushes.get(brush_id) {
+                for (property_id, value) in brush_properties.iter() {
+                    if !properties.iter().any(|(existing_id, _)| existing_id == property_id) {
+                        properties.push((*property_id, value.clone()));
+                    }
+                }
+            }
+        }
+
+        // Write the path for this shape
+        let subpaths = shape.shape.to_path();
+        if !subpaths.is_empty() {
+            let mut path_data = String::new();
+
+            for subpath in subpaths.iter() {
+                let mut add_point = |x: f64, y: f64| {
+                    let ((min_x, min_y), (max_x, max_y)) = bounds.unwrap_or(((x, y), (x, y)));
+                    *bounds = Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y))));
+                };
+
+                write!(path_data, "M{} {} ", subpath.start_point.x, subpath.start_point.y).ok();
+                add_point(subpath.start_point.x, subpath.start_point.y);
+
+                for action in subpath.actions.iter() {
+                    match action {
+                        WorkingPathAction::Line(end)                    => { write!(path_data, "L{} {} ", end.x, end.y).ok(); add_point(end.x, end.y); }
+                        WorkingPathAction::QuadraticCurve { end, cp }   => { write!(path_data, "Q{} {} {} {} ", cp.x, cp.y, end.x, end.y).ok(); add_point(cp.x, cp.y); add_point(end.x, end.y); }
+                        WorkingPathAction::CubicCurve { end, cp1, cp2 } => { write!(path_data, "C{} {} {} {} {} {} ", cp1.x, cp1.y, cp2.x, cp2.y, end.x, end.y).ok(); add_point(cp1.x, cp1.y); add_point(cp2.x, cp2.y); add_point(end.x, end.y); }
+                        WorkingPathAction::Close                        => { path_data.push_str("Z "); }
+                    }
+                }
+            }
+
+            // Shapes with no fill or stroke are written with no fill so they don't turn black in other tools
+            let fill    = FlatFill::from_properties(properties.iter());
+            let stroke  = Stroke::from_properties(properties.iter());
+
+            write!(svg, "{}<path d=\"{}\"", indent_str, path_data.trim_end()).ok();
+
+            if let Some(FlatFill(color)) = fill {
+                let (color, opacity) = Self::svg_color(&color);
+                write!(svg, " fill=\"{}\"", color).ok();
+                if opacity < 1.0 { write!(svg, " fill-opacity=\"{}\"", opacity).ok(); }
+            } else {
+                write!(svg, " fill=\"none\"").ok();
+            }
+
+            if let Some(Stroke(StrokeWidth(width), line_cap, line_join, color)) = stroke {
+                let (color, opacity) = Self::svg_color(&color);
+                let line_cap    = match line_cap { LineCap::Butt => "butt", LineCap::Round => "round", LineCap::Square => "square" };
+                let line_join   = match line_join { LineJoin::Miter => "miter", LineJoin::Round => "round", LineJoin::Bevel => "bevel" };
+
+                write!(svg, " stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"{}\" stroke-linejoin=\"{}\"", color, width, line_cap, line_join).ok();
+                if opacity < 1.0 { write!(svg, " stroke-opacity=\"{}\"", opacity).ok(); }
+            }
+
+            writeln!(svg, "/>").ok();
+        }
+
+        // Write the group
+        for child in shape.children.iter() {
+            Self::write_svg_shape(child, brushes, svg, bounds, indent+1);
+        }
+
+        if !shape.children.is_empty() {
+            writeln!(svg, "{}</g>", indent_str).ok();
+        }
+    }
+
+    ///
+    /// Returns the SVG colour and opacity for a colour
+    ///
+    fn svg_color(color: &Color) -> (String, f32) {
+        let (r, g, b, a)    = color.to_rgba_components();
+        let to_byte         = |component: f32| (component.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+        (format!("#{:02x}{:02x}{:02x}", to_byte(r), to_byte(g), to_byte(b)), a)
+    }
+
+    ///
+    /// Creates clipboard contents from an SVG document (eg, art copied from another tool)
+    ///
+    /// Every filled or stroked path in the SVG becomes a path shape with the default shape type. Returns None if the SVG can't be parsed.
+    ///
+    pub fn from_svg(svg: &str) -> Option<Self> {
+        // Render the SVG to find the paths it contains
+        let mut drawing = vec![];
+        parse_svg(svg, &mut drawing).ok()?;
+
+        let mut shapes          = vec![];
+        let mut state_stack     = vec![];
+        let mut transform       = Transform2D::identity();
+        let mut fill_color      = Color::Rgba(0.0, 0.0, 0.0, 1.0);
+        let mut stroke_color    = Color::Rgba(0.0, 0.0, 0.0, 1.0);
+        let mut line_width      = 1.0;
+        let mut line_cap        = LineCap::Butt;
+        let mut line_join       = LineJoin::Miter;
+
+        // The path being built, and the index of the shape it was added as (if it's been filled or stroked already)
+        let mut start_point     = None;
+        let mut actions         = vec![];
+        let mut path_shape_idx  = None;
+
+        for draw in drawing {
+            match draw {
+                Draw::PushState                     => { state_stack.push((transform, fill_color, stroke_color, line_width, line_cap, line_join)); }
+                Draw::PopState                      => { if let Some(state) = state_stack.pop() { (transform, fill_color, stroke_color, line_width, line_cap, line_join) = state; } }
+                Draw::IdentityTransform             => { transform = Transform2D::identity(); }
+                Draw::MultiplyTransform(multiply)   => { transform = transform * multiply; }
+                Draw::FillColor(color)              => { fill_color = color; }
+                Draw::StrokeColor(color)            => { stroke_color = color; }
+                Draw::LineWidth(width)              => { line_width = width; }
+                Draw::LineCap(cap)                  => { line_cap = cap; }
+                Draw::LineJoin(join)                => { line_join = join; }
+
+                Draw::Path(path_op) => {
+                    let point = |x: f32, y: f32| { let (x, y) = transform.transform_point(x, y); CanvasPoint { x: x, y: y } };
+
+                    match path_op {
+                        PathOp::NewPath                                           => { start_point = None; actions = vec![]; }
+                        PathOp::Move(x, y)                                        => { if start_point.is_none() { start_point = Some(point(x, y)); } else { actions.push(CanvasPathV1Action::Move(point(x, y))); } }
+                        PathOp::Line(x, y)                                        => { actions.push(CanvasPathV1Action::Line(point(x, y))); }
+                        PathOp::BezierCurve(((cp1x, cp1y), (cp2x, cp2y)), (x, y)) => { actions.push(CanvasPathV1Action::CubicCurve { end: point(x, y), cp1: point(cp1x, cp1y), cp2: point(cp2x, cp2y) }); }
+                        PathOp::ClosePath                                         => { actions.push(CanvasPathV1Action::Close); }
+                    }
+
+                    // Changing the path after it's been drawn starts a new shape
+                    path_shape_idx = None;
+                }
+
+                Draw::Fill | Draw::Stroke => {
+                    let Some(start_point) = start_point else { continue; };
+
+                    // Add a shape for the current path if it hasn't been drawn before
+                    let shape_idx = *path_shape_idx.get_or_insert_with(|| {
+                        shapes.push(ClipboardShape {
+                            shape_id:   CanvasShapeId::new(),
+                            shape:      CanvasShape::Path(CanvasPath { start_point: start_point, actions: actions.clone() }),
+                            shape_type: ShapeType::default(),
+                            properties: vec![],
+                            brushes:    vec![],
+                            children:   vec![],
+                        });
+
+                        shapes.len() - 1
+                    });
+
+                    // Set the fill or stroke properties for the shape
+                    let properties = if let Draw::Fill = draw {
+                        FlatFill(fill_color).to_properties()
+                    } else {
+                        Stroke(StrokeWidth(line_width as _), line_cap, line_join, stroke_color).to_properties()
+                    };
+
+                    shapes[shape_idx].properties.extend(properties);
+                }
+
+                _ => { }
+            }
+        }
+
+        Some(ClipboardContents {
+            shapes:     shapes,
+            brushes:    vec![],
+        })
+    }
+}
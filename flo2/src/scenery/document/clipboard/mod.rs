@@ -0,0 +1,9 @@
+mod clipboard;
+mod clipboard_contents;
+mod clipboard_svg;
+
+pub use clipboard::*;
+pub use clipboard_contents::*;
+
+#[cfg(test)]
+mod test_clipboard;
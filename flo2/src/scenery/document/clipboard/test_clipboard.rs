@@ -0,0 +1,210 @@
+use super::*;
+use crate::scenery::document::canvas::*;
+
+use flo_scene::*;
+use flo_scene::programs::*;
+use flo_scene::commands::*;
+use flo_draw::canvas::*;
+
+use futures::prelude::*;
+use ::serde::*;
+
+fn test_rect() -> CanvasShape {
+    CanvasShape::Rectangle(CanvasRectangle { min: CanvasPoint { x: 0.0, y: 0.0 }, max: CanvasPoint { x: 10.0, y: 10.0 } })
+}
+
+fn test_ellipse() -> CanvasShape {
+    CanvasShape::Ellipse(CanvasEllipse { min: CanvasPoint { x: 0.0, y: 0.0 }, max: CanvasPoint { x: 5.0, y: 5.0 }, direction: CanvasPoint { x: 1.0, y: 0.0 } })
+}
+
+///
+/// Clipboard contents with a group containing a red rectangle and an ellipse that uses a brush
+///
+fn test_contents() -> ClipboardContents {
+    let brush_id = CanvasBrushId::new();
+
+    ClipboardContents {
+        shapes: vec![
+            ClipboardShape {
+                shape_id:   CanvasShapeId::new(),
+                shape:      CanvasShape::Group,
+                shape_type: ShapeType::default(),
+                properties: vec![],
+                brushes:    vec![],
+                children:   vec![
+                    ClipboardShape {
+                        shape_id:   CanvasShapeId::new(),
+                        shape:      test_rect(),
+                        shape_type: ShapeType::default(),
+                        properties: FlatFill(Color::Rgba(1.0, 0.0, 0.0, 1.0)).to_properties(),
+                        brushes:    vec![],
+                        children:   vec![],
+                    },
+                    ClipboardShape {
+                        shape_id:   CanvasShapeId::new(),
+                        shape:      test_ellipse(),
+                        shape_type: ShapeType::default(),
+                        properties: vec![],
+                        brushes:    vec![brush_id],
+                        children:   vec![],
+                    },
+                ],
+            },
+        ],
+        brushes: vec![
+            ClipboardBrush { brush_id: brush_id, properties: FlatFill(Color::Rgba(0.0, 0.0, 1.0, 1.0)).to_properties() },
+        ],
+    }
+}
+
+#[test]
+fn paste_remaps_shape_and_brush_ids() {
+    let contents                = test_contents();
+    let layer_id                = CanvasLayerId::new();
+    let (new_shapes, edits)     = contents.paste_edits(layer_id, FrameTime::ZERO);
+
+    let old_group_id            = contents.shapes[0].shape_id;
+    let old_brush_id            = contents.brushes[0].brush_id;
+
+    // One new group is pasted at the top level
+    assert!(new_shapes.len() == 1, "{:?}", new_shapes);
+    assert!(new_shapes[0] != old_group_id);
+
+    // None of the edits should refer to the original IDs
+    for edit in edits.iter() {
+        match edit {
+            VectorCanvas::AddShape(shape_id, _, _)          => { assert!(*shape_id != old_group_id); }
+            VectorCanvas::AddBrush(brush_id)                => { assert!(*brush_id != old_brush_id); }
+            VectorCanvas::AddShapeBrushes(_, brush_ids)     => { assert!(!brush_ids.contains(&old_brush_id)); }
+            _                                               => { }
+        }
+    }
+
+    // The group is added to the layer and the children are added to the new group
+    assert!(edits.contains(&VectorCanvas::SetShapeParent(new_shapes[0], CanvasShapeParent::Layer(layer_id, FrameTime::ZERO))));
+    assert!(edits.iter().filter(|edit| matches!(edit, VectorCanvas::SetShapeParent(_, CanvasShapeParent::Shape(parent_id)) if *parent_id == new_shapes[0])).count() == 2);
+
+    // Pasting twice generates different IDs
+    let (second_shapes, _) = contents.paste_edits(layer_id, FrameTime::ZERO);
+    assert!(second_shapes[0] != new_shapes[0]);
+}
+
+#[test]
+fn postcard_round_trip() {
+    let contents    = test_contents();
+    let bytes       = contents.to_postcard().unwrap();
+
+    assert!(ClipboardContents::from_postcard(&bytes).unwrap() == contents);
+}
+
+#[test]
+fn json_round_trip() {
+    let contents    = test_contents();
+    let json        = contents.to_json().unwrap();
+
+    // Property IDs are written by name
+    assert!(json.contains("flowbetween::fill_color"), "{}", json);
+    assert!(ClipboardContents::from_json(&json).unwrap() == contents);
+}
+
+#[test]
+fn svg_export_uses_shape_and_brush_colors() {
+    let svg = test_contents().to_svg();
+
+    assert!(svg.starts_with("<svg"), "{}", svg);
+    assert!(svg.contains("<g>"), "{}", svg);
+    assert!(svg.matches("<path").count() == 2, "{}", svg);
+    assert!(svg.contains("fill=\"#ff0000\""), "{}", svg);
+    assert!(svg.contains("fill=\"#0000ff\""), "{}", svg);
+}
+
+#[test]
+fn svg_import_creates_filled_paths() {
+    let svg         = "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 20 20\"><path d=\"M0 0 L10 0 L10 10 Z\" fill=\"#ff0000\"/></svg>";
+    let contents    = ClipboardContents::from_svg(svg).unwrap();
+
+    assert!(contents.shapes.len() == 1, "{:?}", contents);
+    assert!(matches!(contents.shapes[0].shape, CanvasShape::Path(_)), "{:?}", contents);
+
+    let fill = FlatFill::from_properties(contents.shapes[0].properties.iter()).unwrap();
+    let (r, g, b, _a) = fill.0.to_rgba_components();
+    assert!((r-1.0).abs() < 0.01 && g.abs() < 0.01 && b.abs() < 0.01, "{:?}", fill);
+}
+
+#[test]
+fn copy_and_paste_group_with_brush() {
+    let scene = Scene::default();
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct TestResponse(Vec<VectorResponse>);
+
+    impl SceneMessage for TestResponse { }
+
+    let test_program    = SubProgramId::new();
+    let copy_program    = SubProgramId::new();
+
+    let layer_1         = CanvasLayerId::new();
+    let group           = CanvasShapeId::new();
+    let child           = CanvasShapeId::new();
+    let brush           = CanvasBrushId::new();
+
+    // Create a group with a child that uses a brush, then copy and paste it
+    scene.add_subprogram(copy_program, move |input: InputStream<ClipboardData>, context| async move {
+        let _sqlite         = context.send::<SqliteCanvasRequest>(()).unwrap();
+        let mut canvas      = context.send(()).unwrap();
+        let mut clipboard   = context.send(()).unwrap();
+
+        canvas.send(VectorCanvas::AddLayer { new_layer_id: layer_1, before_layer: None }).await.unwrap();
+        canvas.send(VectorCanvas::AddBrush(brush)).await.unwrap();
+        canvas.send(VectorCanvas::SetProperty(CanvasPropertyTarget::Brush(brush), vec![(CanvasPropertyId::new("test::brush"), CanvasProperty::Int(42))])).await.unwrap();
+        canvas.send(VectorCanvas::AddShape(group, ShapeType::default(), CanvasShape::Group)).await.unwrap();
+        canvas.send(VectorCanvas::SetShapeParent(group, CanvasShapeParent::Layer(layer_1, FrameTime::ZERO))).await.unwrap();
+        canvas.send(VectorCanvas::AddShape(child, ShapeType::default(), test_rect())).await.unwrap();
+        canvas.send(VectorCanvas::SetShapeParent(child, CanvasShapeParent::Shape(group))).await.unwrap();
+        canvas.send(VectorCanvas::AddShapeBrushes(child, vec![brush])).await.unwrap();
+
+        clipboard.send(Clipboard::Copy(vec![group])).await.unwrap();
+        clipboard.send(Clipboard::Paste(layer_1, FrameTime::ZERO)).await.unwrap();
+
+        // Wait for the clipboard to finish pasting by requesting its contents
+        let mut input = input;
+        clipboard.send(Clipboard::GetData(copy_program.into(), ClipboardFormat::Postcard)).await.unwrap();
+        input.next().await;
+
+        // Read back the layer, which should have the original group and a copy
+        let layer = context.spawn_query(ReadCommand::default(), VectorQuery::Layers(().into(), vec![layer_1], FrameTime::ZERO), ()).unwrap();
+        let layer = layer.collect::<Vec<_>>().await;
+
+        context.send_message(TestResponse(layer)).await.unwrap();
+    }, 1);
+
+    TestBuilder::new()
+        .expect_message(move |response: TestResponse| {
+            let layer = response.0;
+
+            // Layer, group, start group, child, end group (twice)
+            let groups = layer.iter().filter(|response| matches!(response, VectorResponse::Shape(_, CanvasShape::Group, _, _, _))).collect::<Vec<_>>();
+            let rects  = layer.iter().filter(|response| matches!(response, VectorResponse::Shape(_, CanvasShape::Rectangle(_), _, _, _))).collect::<Vec<_>>();
+
+            if groups.len() != 2 || rects.len() != 2 {
+                return Err(format!("Expected two groups and two rectangles, got {:?}", layer));
+            }
+
+            // The pasted shapes have new IDs
+            if !groups.iter().any(|response| !matches!(response, VectorResponse::Shape(shape_id, _, _, _, _) if *shape_id == group)) {
+                return Err(format!("Pasted group has the same ID as the original: {:?}", layer));
+            }
+
+            // Both rectangles get the brush property (the pasted one from the copy of the brush)
+            for rect in rects {
+                let VectorResponse::Shape(_, _, _, _, properties) = rect else { unreachable!() };
+
+                if !properties.contains(&(CanvasPropertyId::new("test::brush"), CanvasProperty::Int(42))) {
+                    return Err(format!("Brush property missing from {:?}", rect));
+                }
+            }
+
+            Ok(())
+        })
+        .run_in_scene(&scene, test_program);
+}
@@ -0,0 +1,144 @@
+use super::canvas::*;
+use super::tools::*;
+use super::user_config::*;
+use crate::scenery::ui::*;
+
+use flo_draw::*;
+use flo_scene::*;
+use flo_scene::commands::*;
+use flo_scene::programs::*;
+
+use futures::prelude::*;
+
+use std::path::*;
+
+/// Command that undoes the last action
+pub const COMMAND_UNDO: &str            = "edit::undo";
+
+/// Command that redoes the last undone action
+pub const COMMAND_REDO: &str            = "edit::redo";
+
+/// Command that moves to the next frame
+pub const COMMAND_NEXT_FRAME: &str      = "frame::next";
+
+/// Command that moves to the previous frame
+pub const COMMAND_PREVIOUS_FRAME: &str  = "frame::previous";
+
+/// Command that moves to the first frame
+pub const COMMAND_FIRST_FRAME: &str     = "frame::first";
+
+///
+/// The tools that can be selected using keyboard shortcuts, and the command names used to select them
+///
+const TOOL_COMMANDS: [(&str, ToolTypeId); 8] = [
+    ("tool::brush",             TOOL_BRUSH),
+    ("tool::eraser",            TOOL_ERASER),
+    ("tool::nonphoto_pencil",   TOOL_NONPHOTO_PENCIL),
+    ("tool::paint_bucket",      TOOL_PAINT_BUCKET),
+    ("tool::lasso",             TOOL_LASSO),
+    ("tool::ellipse",           TOOL_ELLIPSE),
+    ("tool::rectangle",         TOOL_RECTANGLE),
+    ("tool::polygon",           TOOL_POLYGON),
+];
+
+///
+/// The default keyboard shortcuts for a document
+///
+pub fn default_document_key_bindings() -> KeyBindings {
+    let mut bindings = KeyBindings::default();
+
+    let mut redo = KeyChord::cmd(Key::KeyZ);
+    redo.modifiers.shift = true;
+
+    bindings.bind(vec![KeyChord::cmd(Key::KeyZ)], COMMAND_UNDO);
+    bindings.bind(vec![redo], COMMAND_REDO);
+    bindings.bind(vec![KeyChord::cmd(Key::KeyY)], COMMAND_REDO);
+
+    bindings.bind(vec![KeyChord::key(Key::KeyFullstop)], COMMAND_NEXT_FRAME);
+    bindings.bind(vec![KeyChord::key(Key::KeyComma)], COMMAND_PREVIOUS_FRAME);
+    bindings.bind(vec![KeyChord::key(Key::KeyHome)], COMMAND_FIRST_FRAME);
+
+    bindings.bind(vec![KeyChord::key(Key::KeyB)], "tool::brush");
+    bindings.bind(vec![KeyChord::key(Key::KeyE)], "tool::eraser");
+    bindings.bind(vec![KeyChord::key(Key::KeyN)], "tool::nonphoto_pencil");
+    bindings.bind(vec![KeyChord::key(Key::KeyG)], "tool::paint_bucket");
+    bindings.bind(vec![KeyChord::key(Key::KeyL)], "tool::lasso");
+    bindings.bind(vec![KeyChord::key(Key::KeyO)], "tool::ellipse");
+    bindings.bind(vec![KeyChord::key(Key::KeyM)], "tool::rectangle");
+    bindings.bind(vec![KeyChord::key(Key::KeyP)], "tool::polygon");
+
+    bindings
+}
+
+///
+/// Returns the path of the file where the keymap for the current user is stored
+///
+pub fn user_keymap_path() -> Option<PathBuf> {
+    Some(user_config_dir()?.join("keymap.json"))
+}
+
+///
+/// Runs the program that performs the commands for the document's keyboard shortcuts
+///
+/// This sets up the default bindings, then loads the user's keymap (which can replace any of the defaults)
+///
+pub async fn document_keymap_program(input: InputStream<KeyboardCommand>, context: SceneContext) {
+    let Some(our_program_id) = context.current_program_id() else { return; };
+    let Ok(mut keymap) = context.send::<Keymap>(()) else { return; };
+
+    // Set up the commands and the default bindings
+    let commands = [COMMAND_UNDO, COMMAND_REDO, COMMAND_NEXT_FRAME, COMMAND_PREVIOUS_FRAME, COMMAND_FIRST_FRAME].into_iter()
+        .chain(TOOL_COMMANDS.iter().map(|(command, _)| *command));
+    for command in commands {
+        keymap.send(Keymap::DefineCommand(command.to_string(), our_program_id.into())).await.ok();
+    }
+
+    for binding in default_document_key_bindings().bindings {
+        keymap.send(Keymap::Bind(binding.sequence, binding.command)).await.ok();
+    }
+
+    if let Some(keymap_path) = user_keymap_path() {
+        keymap.send(Keymap::LoadKeymap(keymap_path)).await.ok();
+    }
+
+    // The frame that's being displayed
+    let mut frame: i64 = 0;
+
+    let mut input = input;
+    while let Some(KeyboardCommand(command)) = input.next().await {
+        match command.as_str() {
+            COMMAND_UNDO            => { context.send_message(VectorUndo::Undo).await.ok(); }
+            COMMAND_REDO            => { context.send_message(VectorUndo::Redo).await.ok(); }
+
+            COMMAND_NEXT_FRAME      |
+            COMMAND_PREVIOUS_FRAME  |
+            COMMAND_FIRST_FRAME     => {
+                frame = match command.as_str() {
+                    COMMAND_NEXT_FRAME      => frame + 1,
+                    COMMAND_PREVIOUS_FRAME  => (frame - 1).max(0),
+                    _                       => 0,
+                };
+
+                // Frames are spaced out by the document's time per frame
+                let Ok(outline)     = context.spawn_query(ReadCommand::default(), VectorQuery::DocumentOutline(().into()), ()) else { continue; };
+                let outline         = outline.collect::<Vec<_>>().await;
+                let time_per_frame  = outline.iter()
+                    .flat_map(|response| match response {
+                        VectorResponse::Document(properties)    => DocumentTimePerFrame::from_properties(properties.iter()),
+                        _                                       => None,
+                    })
+                    .next()
+                    .unwrap_or(DocumentTimePerFrame(1.0/12.0));
+
+                let frame_nanos = (frame as f64) * time_per_frame.0 * 1_000_000_000.0;
+                context.send_message(CanvasRender::SetFrame(FrameTime::from_nanos(frame_nanos as u64))).await.ok();
+            }
+
+            tool_command => {
+                if let Some((_, tool_type)) = TOOL_COMMANDS.iter().find(|(command, _)| *command == tool_command) {
+                    context.send_message(Tool::SelectType(*tool_type)).await.ok();
+                }
+            }
+        }
+    }
+}
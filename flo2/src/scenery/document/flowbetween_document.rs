@@ -1,3 +1,4 @@
+use super::document_keymap::*;
 use super::subprograms::*;
 use super::tools::*;
 use crate::scenery::document::canvas::*;
@@ -7,6 +8,7 @@ use flo_draw::*;
 use flo_draw::canvas::*;
 use flo_draw::canvas::scenery::*;
 use flo_scene::*;
+use flo_scene::commands::*;
 use flo_scene::programs::*;
 use flo_binding::*;
 
@@ -129,6 +131,14 @@ pub async fn flowbetween_document(document_scene: Arc<Scene>, input: InputStream
 
     document_scene.add_subprogram(SubProgramId::new(), brush_tool_program, 1);
 
+    // Keyboard shortcuts
+    document_scene.add_subprogram(subprogram_document_keymap(), document_keymap_program, 20);
+
+    // Restore the tool layout from the last session (tools that haven't started yet are restored when they're created)
+    if let Some(layout_path) = user_tool_layout_path() {
+        context.send_message(Tool::LoadLayout(layout_path)).await.ok();
+    }
+
     let test_tool       = ToolId::new();
     let test_group      = TOOL_GROUP_CANVAS;
     let test_type       = ToolTypeId::new();
@@ -146,6 +156,7 @@ pub async fn flowbetween_document(document_scene: Arc<Scene>, input: InputStream
     context.send_message(CanvasRender::SetTransform(Transform2D::scale(0.5, 0.5))).await.unwrap();
 
     document_scene.add_subprogram(ShapeType::default().render_program_id(), standard_shape_type_renderer_program, 10);
+    document_scene.add_subprogram(SHAPE_TYPE_TEXT.render_program_id(), text_shape_type_renderer_program, 10);
 
     // Add an ellipse to the canvas
     let layer_1  = vector_add_layer(&[&Name::from("Layer 1")]).await;
@@ -240,6 +251,13 @@ pub async fn flowbetween_document(document_scene: Arc<Scene>, input: InputStream
                 }
 
                 DocumentRequest::Close => {
+                    // Keep the tool layout for the next session (waiting for it to be written, as stopping the scene stops the tool state program)
+                    if let Some(layout_path) = user_tool_layout_path() {
+                        if let Ok(mut saved) = context.spawn_query(ReadCommand::default(), SaveToolLayout(layout_path, ().into()), ()) {
+                            saved.next().await;
+                        }
+                    }
+
                     // When the document is closed, we stop the whole scene
                     context.send_message(SceneControl::StopScene).await.ok();
                 }
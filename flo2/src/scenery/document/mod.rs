@@ -11,6 +11,9 @@ mod subprograms;
 mod flowbetween_document;
 mod tools;
 mod brush;
+mod clipboard;
+mod user_config;
+mod document_keymap;
 pub mod canvas;
 
 pub use document_id::*;
@@ -18,4 +21,7 @@ pub use subprograms::*;
 pub use flowbetween_document::*;
 pub use tools::*;
 pub use brush::*;
+pub use clipboard::*;
+pub use user_config::*;
+pub use document_keymap::*;
 pub use canvas::*;
@@ -24,3 +24,8 @@ pub fn subprogram_tool_dock_right() -> SubProgramId { SubProgramId::called("flow
 /// ID of the 'floating' tools program
 ///
 pub fn subprogram_floating_tools() -> SubProgramId { SubProgramId::called("flowbetween::tools::floating") }
+
+///
+/// ID of the program that handles the keyboard shortcuts for the document
+///
+pub fn subprogram_document_keymap() -> SubProgramId { SubProgramId::called("flowbetween::document::keymap") }
@@ -54,6 +54,16 @@ impl ToolData for BrushToolState {
     fn selected(&mut self, is_selected: bool) {
         self.tool_selected.set(is_selected);
     }
+
+    fn settings(&self) -> Option<Vec<u8>> {
+        Some(self.brush_settings.get().to_bytes())
+    }
+
+    fn load_settings(&mut self, settings: &[u8]) {
+        if let Some(brush_settings) = CoreBrushSettings::from_bytes(settings) {
+            self.brush_settings.set(brush_settings);
+        }
+    }
 }
 
 impl Default for BrushToolState {
@@ -230,16 +240,11 @@ async fn brush_stroke(button_down: Button, pointer_id: PointerId, input: &mut In
                 // TODO: temporary until we have an actual colour tool
                 let mut shape = shape;
                 if !shape.properties.iter().any(|(prop, _val)| prop == &*PROP_FILL_COLOR) {
-                    let mut properties = Arc::unwrap_or_clone(shape.properties);
-
-                    properties.push((*PROP_FILL_COLOR,      color_value_property(&Color::Rgba(0.0, 0.0, 0.0, 1.0))));
-                    properties.push((*PROP_FILL_COLOR_TYPE, color_type_property(&Color::Rgba(0.0, 0.0, 0.0, 1.0))));
-
-                    shape.properties = Arc::new(properties);
+                    shape = shape_with_fill_color(shape, Color::Rgba(0.0, 0.0, 0.0, 1.0));
                 }
 
                 // Generate the drawing instructions for this shape
-                let shape_drawing = render_shapes(iter::once(Arc::new(shape)), FrameTime::ZERO, context).await;
+                let shape_drawing = render_shapes(iter::once(Arc::new(shape)), FrameTime::ZERO, layer_transform_pixel_scale(&layer_transform), context).await;
 
                 // Add them to our drawing
                 drawing.extend(shape_drawing.iter().flat_map(|item| item.iter().cloned()));
@@ -0,0 +1,112 @@
+use crate::scenery::ui::*;
+use crate::scenery::document::canvas::*;
+use crate::scenery::document::user_config::*;
+
+use flo_scene::*;
+use flo_scene::commands::*;
+use flo_scene::programs::*;
+
+use futures::prelude::*;
+use ::serde::*;
+
+use std::path::*;
+
+/// Document property where the tool layout is stored
+pub static PROP_DOCUMENT_TOOL_LAYOUT: LazyCanvasPropertyId = LazyCanvasPropertyId::new("flowbetween::tool_layout");
+
+///
+/// Requests to store the tool layout in the document, or restore the layout that's stored there
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DocumentToolLayout {
+    /// Stores the current tool layout in the document
+    SaveToDocument,
+
+    /// Restores the tool layout stored in the document, if there is one
+    LoadFromDocument,
+
+    /// Stores a tool layout in the document (this is the response to the `Tool::SendLayout` request made by `SaveToDocument`)
+    StoreLayout(ToolLayout),
+}
+
+impl SceneMessage for DocumentToolLayout {
+    fn default_target() -> StreamTarget {
+        SubProgramId::called("flowbetween::document_tool_layout").into()
+    }
+
+    fn initialise(init_context: &impl SceneInitialisationContext) {
+        init_context.add_subprogram(SubProgramId::called("flowbetween::document_tool_layout"), document_tool_layout_program, 20);
+        init_context.connect_programs((), SubProgramId::called("flowbetween::document_tool_layout"), StreamId::with_message_type::<DocumentToolLayout>()).unwrap();
+        init_context.connect_programs(StreamSource::Filtered(FilterHandle::for_filter(|layouts| layouts.map(|layout| DocumentToolLayout::StoreLayout(layout)))), (), StreamId::with_message_type::<ToolLayout>()).unwrap();
+    }
+}
+
+impl ToCanvasProperties for ToolLayout {
+    fn to_properties(&self) -> Vec<(CanvasPropertyId, CanvasProperty)> {
+        vec![
+            (*PROP_DOCUMENT_TOOL_LAYOUT, CanvasProperty::ByteList(self.to_bytes())),
+        ]
+    }
+}
+
+impl FromCanvasProperties for ToolLayout {
+    fn used_properties() -> Vec<CanvasPropertyId> {
+        vec![*PROP_DOCUMENT_TOOL_LAYOUT]
+    }
+
+    fn from_properties<'a>(properties: impl Iterator<Item=&'a (CanvasPropertyId, CanvasProperty)>) -> Option<Self> {
+        let mut layout = None;
+
+        for (prop_id, prop_val) in properties {
+            if *prop_id == *PROP_DOCUMENT_TOOL_LAYOUT {
+                if let CanvasProperty::ByteList(bytes) = prop_val { layout = ToolLayout::from_bytes(bytes).ok(); }
+            }
+        }
+
+        layout
+    }
+}
+
+///
+/// Returns the path of the file where the tool layout for the current user is stored
+///
+pub fn user_tool_layout_path() -> Option<PathBuf> {
+    Some(user_config_dir()?.join("tool_layout.flotools"))
+}
+
+///
+/// Runs the program that stores tool layouts in the document
+///
+pub async fn document_tool_layout_program(input: InputStream<DocumentToolLayout>, context: SceneContext) {
+    let Some(our_program_id) = context.current_program_id() else { return; };
+
+    let mut input = input;
+    while let Some(msg) = input.next().await {
+        match msg {
+            DocumentToolLayout::SaveToDocument => {
+                // The tool state program will send the layout back to us as a `StoreLayout` request
+                context.send_message(Tool::SendLayout(our_program_id.into())).await.ok();
+            }
+
+            DocumentToolLayout::StoreLayout(layout) => {
+                context.send_message(VectorCanvas::SetProperty(CanvasPropertyTarget::Document, layout.to_properties())).await.ok();
+            }
+
+            DocumentToolLayout::LoadFromDocument => {
+                let Ok(outline) = context.spawn_query(ReadCommand::default(), VectorQuery::DocumentOutline(().into()), ()) else { continue; };
+                let outline     = outline.collect::<Vec<_>>().await;
+
+                let layout = outline.iter()
+                    .flat_map(|response| match response {
+                        VectorResponse::Document(properties)    => ToolLayout::from_properties(properties.iter()),
+                        _                                       => None,
+                    })
+                    .next();
+
+                if let Some(layout) = layout {
+                    context.send_message(Tool::RestoreLayout(layout)).await.ok();
+                }
+            }
+        }
+    }
+}
@@ -3,9 +3,11 @@ mod tool;
 mod canvas_tool_type_ids;
 mod focus_tool_program;
 mod brush_tool;
+mod document_tool_layout;
 
 pub use group_ids::*;
 pub use tool::*;
 pub use canvas_tool_type_ids::*;
 pub use focus_tool_program::*;
 pub use brush_tool::*;
+pub use document_tool_layout::*;
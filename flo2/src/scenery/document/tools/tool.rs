@@ -51,6 +51,16 @@ pub trait ToolData : Send {
     /// The tool has been selected or deselected
     ///
     fn selected(&mut self, is_selected: bool) { let _ = is_selected; }
+
+    ///
+    /// The settings for this tool that should be stored in the tool layout, or None if the tool has no settings to store
+    ///
+    fn settings(&self) -> Option<Vec<u8>> { None }
+
+    ///
+    /// Replaces the settings for this tool with some settings previously returned by `settings()`
+    ///
+    fn load_settings(&mut self, settings: &[u8]) { let _ = settings; }
 }
 
 impl<TToolData> ToolBehaviour<TToolData> 
@@ -216,6 +226,10 @@ where
             tool_target.send(Tool::SetToolLocation(tool_id, default_target, default_location)).await.ok();
             tool_target.send(Tool::SetToolName(tool_id, behaviour.name.clone())).await.ok();
 
+            if let Some(settings) = default_tool.lock().unwrap().settings() {
+                tool_target.send(Tool::SetToolSettings(tool_id, settings)).await.ok();
+            }
+
             // Start the subprograms for this tool (these will do things like set the initial icon)
             let subprograms = ToolSubPrograms::start(tool_id, &default_tool, &behaviour, &context).await;
             tool_subprograms.insert(tool_id, subprograms);
@@ -253,9 +267,17 @@ where
                 },
 
                 ToolState::Deselect(tool_id) => { 
-                    // Tell the data that the tool is unselected
+                    // Tell the data that the tool is unselected (and store the settings it was used with in the layout)
                     if let Some(data) = tool_data.get(&tool_id) {
-                        data.lock().unwrap().selected(false);
+                        let settings = {
+                            let mut data = data.lock().unwrap();
+                            data.selected(false);
+                            data.settings()
+                        };
+
+                        if let Some(settings) = settings {
+                            tool_target.send(Tool::SetToolSettings(tool_id, settings)).await.ok();
+                        }
                     }
 
                     // Tell FocusTool to stop sending canvas focus events to the tool canvas program
@@ -265,7 +287,21 @@ where
                 },
 
                 ToolState::OpenDialog(_tool_id)             => { /* TODO: run dialog program if it's not running */ },
-                ToolState::CloseDialog(_tool_id)            => { /* TODO: stop dialog program */ },
+                ToolState::CloseDialog(tool_id)             => {
+                    // TODO: stop dialog program
+
+                    // The settings will usually have been changed by the dialog
+                    let settings = tool_data.get(&tool_id).and_then(|data| data.lock().unwrap().settings());
+                    if let Some(settings) = settings {
+                        tool_target.send(Tool::SetToolSettings(tool_id, settings)).await.ok();
+                    }
+                },
+
+                ToolState::LoadSettings(tool_id, settings)  => {
+                    if let Some(data) = tool_data.get(&tool_id) {
+                        data.lock().unwrap().load_settings(&settings);
+                    }
+                },
 
                 ToolState::RemoveTool(tool_id)              => {
                     // Remove the tool data
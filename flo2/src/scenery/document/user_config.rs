@@ -0,0 +1,15 @@
+use std::env;
+use std::path::*;
+
+///
+/// Returns the directory where FlowBetween stores the configuration for the current user
+///
+/// This is `flowbetween` in the user's configuration directory (`$XDG_CONFIG_HOME`, `%APPDATA%` or `~/.config`)
+///
+pub fn user_config_dir() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_dir.join("flowbetween"))
+}
@@ -1,11 +1,13 @@
 use super::control_id::*;
 
 use flo_binding::*;
+use flo_draw::canvas::{Color};
 use ::serde::*;
 use serde::de::{Error as DeError};
 use serde::ser::{Error as SeError};
 
 use std::ops::{Range};
+use std::sync::*;
 
 ///
 /// Specifies the type of a control
@@ -20,6 +22,36 @@ pub enum ControlType {
     RadioButton(BindRef<String>),
     Separator,
     Slider(BindRef<Range<f64>>),
+
+    /// Editable single-line text field (value should be `ControlValue::Text`)
+    TextInput,
+
+    /// Numeric field with arrows/dragging to change the value within a range (value should be `ControlValue::Integer` or `ControlValue::Float`)
+    SpinBox(BindRef<Range<f64>>),
+
+    /// Button showing a colour that opens a colour picker when clicked (value should be `ControlValue::Color`)
+    ColorPicker,
+
+    /// Dropdown list of items (value should be `ControlValue::Integer` for the index of the selected item, or `ControlValue::Text` for its text)
+    Dropdown(BindRef<Vec<String>>),
+
+    /// Displays an image
+    Image(BindRef<ControlImage>),
+}
+
+///
+/// An image to display in an image control
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct ControlImage {
+    /// Width of the image in pixels
+    pub width: usize,
+
+    /// Height of the image in pixels
+    pub height: usize,
+
+    /// The pixels for the image, as 8-bit RGBA values (not premultiplied)
+    pub pixels: Arc<Vec<u8>>,
 }
 
 ///
@@ -32,6 +64,7 @@ pub enum ControlValue {
     Text(Binding<String>),
     Integer(Binding<i64>),
     Float(Binding<f64>),
+    Color(Binding<Color>),
 }
 
 ///
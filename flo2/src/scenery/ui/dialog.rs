@@ -10,6 +10,7 @@ use super::subprograms::*;
 use super::ui_path::*;
 
 use flo_scene::*;
+use flo_draw::canvas::{Color};
 
 use serde::*;
 
@@ -53,6 +54,12 @@ pub enum DialogEvent {
 
     /// Indicates that a control's numeric value has changed
     SetValueNumber(ControlId, i64),
+
+    /// Indicates that a control's floating point value has changed
+    SetValueFloat(ControlId, f64),
+
+    /// Indicates that a control's colour value has changed
+    SetValueColor(ControlId, Color),
 }
 
 impl SceneMessage for Dialog {
@@ -45,7 +45,7 @@ pub (crate) enum EguiDialogRequest {
 ///
 /// Defines dialog behavior by using egui (with rendering via flo_canvas requests)
 ///
-pub (crate) async fn dialog_egui(input: InputStream<EguiDialogRequest>, context: SceneContext, dialog_namespace: canvas::NamespaceId, dialog_layer: canvas::LayerId, bounds: (UiPoint, UiPoint)) {
+pub (crate) async fn dialog_egui(input: InputStream<EguiDialogRequest>, context: SceneContext, dialog_namespace: canvas::NamespaceId, dialog_layer: canvas::LayerId, bounds: (UiPoint, UiPoint), event_target: SubProgramId) {
     use canvas::{Draw};
 
     // Create a namespace for the dialog graphics
@@ -65,6 +65,9 @@ pub (crate) async fn dialog_egui(input: InputStream<EguiDialogRequest>, context:
     let mut drawing         = context.send::<DrawingRequest>(()).unwrap();
     let mut idle_requests   = context.send::<IdleRequest>(()).unwrap();
 
+    // Events from the controls are sent to the program that created the dialog
+    let mut dialog_events   = context.send::<DialogEvent>(event_target).ok();
+
     // Set up the dialog layer (it'll go on top of anything else in the drawing at the moment)
     drawing.send(DrawingRequest::Draw(Arc::new(vec![
         Draw::PushState,
@@ -138,6 +141,13 @@ pub (crate) async fn dialog_egui(input: InputStream<EguiDialogRequest>, context:
                 // Process the output, generating draw events
                 process_texture_output(&output, &mut drawing, dialog_namespace, dialog_layer).await;
                 process_drawing_output(&output, &mut drawing, dialog_namespace, dialog_layer).await;
+
+                // Send any events generated by the controls
+                if let Some(dialog_events) = &mut dialog_events {
+                    for event in events.into_iter().flatten() {
+                        dialog_events.send(event).await.ok();
+                    }
+                }
             },
 
             BindingsChanged => {
@@ -62,7 +62,7 @@ pub async fn dialog_egui_hub(input_stream: InputStream<Dialog>, context: SceneCo
                 let program_id = SubProgramId::new();
 
                 // Start a program to run this dialog
-                context.send_message(SceneControl::start_program(program_id, move |input, context| dialog_egui(input, context, dialog_namespace, layer_id, bounds), 20)).await.ok();
+                context.send_message(SceneControl::start_program(program_id, move |input, context| dialog_egui(input, context, dialog_namespace, layer_id, bounds, target_program_id), 20)).await.ok();
 
                 // Make a connection to the new program
                 let sink = context.send::<Dialog>(program_id).ok();
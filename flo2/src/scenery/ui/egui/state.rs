@@ -4,14 +4,19 @@ use crate::scenery::ui::dialog::*;
 use crate::scenery::ui::ui_path::*;
 
 use flo_binding::*;
+use flo_draw::canvas::{Color};
 use egui;
 
 use std::collections::{HashMap};
+use std::ops::{Range};
 
 struct ControlState {
     location:   (UiPoint, UiPoint),
     value:      ControlValue,
     visible:    bool,
+
+    /// For image controls, the image that was last loaded into egui and its texture
+    texture:    Option<(ControlImage, egui::TextureHandle)>,
 }
 
 ///
@@ -54,6 +59,7 @@ impl EguiDialogState {
                     location:   *bounds,
                     value:      initial_value.clone(),
                     visible:    true,
+                    texture:    None,
                 });
             }
 
@@ -80,7 +86,7 @@ impl EguiDialogState {
     ///
     /// Runs this control state, returning the events that should be sent
     ///
-    pub fn run(&mut self, context: &egui::Context, dialog_bounds: (UiPoint, UiPoint)) -> Vec<ControlEvent> {
+    pub fn run(&mut self, context: &egui::Context, dialog_bounds: (UiPoint, UiPoint)) -> Vec<DialogEvent> {
         // Events that are generated for this UI
         let mut events = vec![];
 
@@ -109,13 +115,18 @@ impl EguiDialogState {
                         // Render the control
                         match control_type {
                             Label(label)        => { ui.put(pos, egui::Label::new(label.get())); },
-                            Button(label)       => { if ui.button(label.get()).clicked() { events.push(ControlEvent::Pressed(*control_id)); } }
+                            Button(label)       => { if ui.button(label.get()).clicked() { events.push(DialogEvent::Activate(*control_id)); } }
                             Checkbox(label)     => { },
                             RadioButton(label)  => { },
                             ProgressBar         => { },
                             Spinner             => { },
                             Separator           => { },
                             Slider(range)       => { },
+                            TextInput           => { Self::text_input(ui, pos, *control_id, control_state, &mut events); },
+                            SpinBox(range)      => { Self::spin_box(ui, pos, *control_id, range.get(), control_state, &mut events); },
+                            ColorPicker         => { Self::color_picker(ui, pos, *control_id, control_state, &mut events); },
+                            Dropdown(items)     => { Self::dropdown(ui, pos, *control_id, items.get(), control_state, &mut events); },
+                            Image(image)        => { Self::image(ui, pos, *control_id, image.get(), control_state); },
                         }
                     }
                 }
@@ -124,4 +135,128 @@ impl EguiDialogState {
 
         events
     }
+
+    ///
+    /// Renders an editable text field
+    ///
+    fn text_input(ui: &mut egui::Ui, pos: egui::Rect, control_id: ControlId, state: &mut ControlState, events: &mut Vec<DialogEvent>) {
+        let ControlValue::Text(value) = &state.value else { return; };
+
+        let mut text = value.get();
+        if ui.put(pos, egui::TextEdit::singleline(&mut text)).changed() {
+            value.set(text.clone());
+            events.push(DialogEvent::SetValueString(control_id, text));
+        }
+    }
+
+    ///
+    /// Renders a numeric spin box
+    ///
+    fn spin_box(ui: &mut egui::Ui, pos: egui::Rect, control_id: ControlId, range: Range<f64>, state: &mut ControlState, events: &mut Vec<DialogEvent>) {
+        match &state.value {
+            ControlValue::Integer(value) => {
+                let mut number = value.get();
+                if ui.put(pos, egui::DragValue::new(&mut number).range((range.start as i64)..=(range.end as i64))).changed() {
+                    value.set(number);
+                    events.push(DialogEvent::SetValueNumber(control_id, number));
+                }
+            }
+
+            ControlValue::Float(value) => {
+                let mut number = value.get();
+                if ui.put(pos, egui::DragValue::new(&mut number).range(range.start..=range.end).speed(0.1)).changed() {
+                    value.set(number);
+                    events.push(DialogEvent::SetValueFloat(control_id, number));
+                }
+            }
+
+            _ => { }
+        }
+    }
+
+    ///
+    /// Renders a colour picker button
+    ///
+    fn color_picker(ui: &mut egui::Ui, pos: egui::Rect, control_id: ControlId, state: &mut ControlState, events: &mut Vec<DialogEvent>) {
+        let ControlValue::Color(value) = &state.value else { return; };
+
+        let (r, g, b, a)    = value.get().to_rgba_components();
+        let mut rgba        = egui::Rgba::from_rgba_unmultiplied(r, g, b, a);
+
+        let response = ui.put(pos, |ui: &mut egui::Ui| egui::color_picker::color_edit_button_rgba(ui, &mut rgba, egui::color_picker::Alpha::OnlyBlend));
+        if response.changed() {
+            let [r, g, b, a]    = rgba.to_rgba_unmultiplied();
+            let color           = Color::Rgba(r, g, b, a);
+
+            value.set(color);
+            events.push(DialogEvent::SetValueColor(control_id, color));
+        }
+    }
+
+    ///
+    /// Renders a dropdown list
+    ///
+    fn dropdown(ui: &mut egui::Ui, pos: egui::Rect, control_id: ControlId, items: Vec<String>, state: &mut ControlState, events: &mut Vec<DialogEvent>) {
+        // The value is either the index of the selected item or its text
+        let selected = match &state.value {
+            ControlValue::Integer(value)    => value.get() as usize,
+            ControlValue::Text(value)       => { let text = value.get(); items.iter().position(|item| item == &text).unwrap_or(usize::MAX) },
+            _                               => { return; }
+        };
+
+        let mut new_selected    = selected;
+        let selected_text       = items.get(selected).cloned().unwrap_or_default();
+
+        ui.put(pos, |ui: &mut egui::Ui| {
+            egui::ComboBox::from_id_salt(control_id)
+                .selected_text(selected_text)
+                .width(pos.width())
+                .show_ui(ui, |ui| {
+                    for (idx, item) in items.iter().enumerate() {
+                        ui.selectable_value(&mut new_selected, idx, item);
+                    }
+                })
+                .response
+        });
+
+        if new_selected != selected {
+            match &state.value {
+                ControlValue::Integer(value) => {
+                    value.set(new_selected as i64);
+                    events.push(DialogEvent::SetValueNumber(control_id, new_selected as i64));
+                }
+
+                ControlValue::Text(value) => {
+                    value.set(items[new_selected].clone());
+                    events.push(DialogEvent::SetValueString(control_id, items[new_selected].clone()));
+                }
+
+                _ => { }
+            }
+        }
+    }
+
+    ///
+    /// Renders an image
+    ///
+    fn image(ui: &mut egui::Ui, pos: egui::Rect, control_id: ControlId, image: ControlImage, state: &mut ControlState) {
+        if image.pixels.len() != image.width * image.height * 4 { return; }
+
+        // Load the image into a texture if it has changed
+        let needs_load = match &state.texture {
+            Some((loaded_image, _)) => loaded_image != &image,
+            None                    => true,
+        };
+
+        if needs_load {
+            let color_image = egui::ColorImage::from_rgba_unmultiplied([image.width, image.height], &image.pixels);
+            let texture     = ui.ctx().load_texture(format!("flowbetween::control_image::{:?}", control_id), color_image, egui::TextureOptions::LINEAR);
+
+            state.texture = Some((image, texture));
+        }
+
+        if let Some((_, texture)) = &state.texture {
+            ui.put(pos, egui::Image::new(egui::load::SizedTexture::new(texture.id(), pos.size())));
+        }
+    }
 }
@@ -49,6 +49,15 @@ pub enum Focus {
     /// Move keyboard focus to the preceding control
     FocusPrevious,
 
+    /// Sets the subprogram that processes keyboard shortcuts
+    ///
+    /// Keyboard events are sent to this program when no control has keyboard focus and no pointer action is in progress. Any events
+    /// that aren't used as shortcuts should be sent back with `UnhandledKeyboardEvent` so they reach the control under the pointer.
+    SetShortcutHandler(SubProgramId),
+
+    /// A keyboard event that was not used by the shortcut handler, which is sent on to the pointer target
+    UnhandledKeyboardEvent(FocusKeyboardEvent),
+
     /// Sets which subprogram receives canvas events (events that don't hit any control region)
     SetCanvas(SubProgramId),
 
@@ -123,6 +132,7 @@ pub async fn focus(input: InputStream<Focus>, context: SceneContext) {
         focused_subprogram:         None,
         focused_control:            None,
         focused_event_target:       None,
+        shortcut_handler:           None,
         tab_ordering:               HashMap::new(),
         scale:                      None,
         bounds:                     None,
@@ -151,7 +161,7 @@ pub async fn focus(input: InputStream<Focus>, context: SceneContext) {
             Event(DrawEvent::KeyUp(scancode, key))                                          => { focus.send_to_focus(FocusKeyboardEvent::KeyUp(None, scancode, key), &context).await; },
 
             // Updates from the scene in general
-            Update(SceneUpdate::Stopped(program_id))    => { focus.remove_program_claims(program_id).await; focus.remove_program_focus(program_id).await; },
+            Update(SceneUpdate::Stopped(program_id))    => { focus.remove_program_claims(program_id).await; focus.remove_program_focus(program_id).await; focus.remove_shortcut_handler(program_id); },
             Update(_)                                   => { }
 
             // Keyboard handling
@@ -160,6 +170,8 @@ pub async fn focus(input: InputStream<Focus>, context: SceneContext) {
             SetFollowingSubProgram(program_id, next_program_id)             => focus.set_following_subprogram(program_id, next_program_id).await,
             FocusNext                                                       => focus.focus_next(&context).await,
             FocusPrevious                                                   => focus.focus_previous(&context).await,
+            SetShortcutHandler(program_id)                                  => focus.shortcut_handler = Some(program_id),
+            UnhandledKeyboardEvent(event)                                   => focus.send_to_keyboard_target(event, &context).await,
 
             // Control handling
             RemoveClaim(program_id)                                     => focus.remove_program_claims(program_id).await,
@@ -250,6 +262,9 @@ struct FocusProgram {
     /// Where keyboard events should be sent
     focused_event_target: Option<OutputSink<FocusKeyboardEvent>>,
 
+    /// The subprogram that processes keyboard shortcuts
+    shortcut_handler: Option<SubProgramId>,
+
     /// The tab ordering for the controls within this program
     tab_ordering: HashMap<SubProgramId, KeyboardSubProgram>,
 
@@ -600,6 +615,15 @@ impl FocusProgram {
         self.subprogram_order.retain(|prog| prog != &program);
     }
 
+    ///
+    /// Stops sending keyboard events to a shortcut handler that has stopped
+    ///
+    fn remove_shortcut_handler(&mut self, program: SubProgramId) {
+        if self.shortcut_handler == Some(program) {
+            self.shortcut_handler = None;
+        }
+    }
+
     ///
     /// Removes the claim that matches the specified control
     ///
@@ -613,6 +637,24 @@ impl FocusProgram {
     /// Sends an event to whichever program/control is focused
     ///
     async fn send_to_focus(&mut self, event: FocusKeyboardEvent, context: &SceneContext) {
+        // Shortcuts are processed ahead of the pointer target, but focused controls (eg, text fields) and pointer actions take precedence over them
+        if self.focused_event_target.is_none() && self.button_state.num_buttons_down() == 0 {
+            if let Some(shortcut_handler) = self.shortcut_handler {
+                if let Ok(mut shortcut_handler) = context.send::<FocusKeyboardEvent>(shortcut_handler) {
+                    if shortcut_handler.send(event.clone()).await.is_ok() {
+                        return;
+                    }
+                }
+            }
+        }
+
+        self.send_to_keyboard_target(event, context).await;
+    }
+
+    ///
+    /// Sends an event to the pointer target or the focused control, bypassing the shortcut handler
+    ///
+    async fn send_to_keyboard_target(&mut self, event: FocusKeyboardEvent, context: &SceneContext) {
         let control = self.focused_control;
         let event   = event.with_target(control);
 
@@ -0,0 +1,590 @@
+//!
+//! The keymap subprogram maps keyboard shortcuts to commands.
+//!
+//! A shortcut is a sequence of one or more key chords (a key pressed along with some modifiers, such as `Ctrl+Z`). When a shortcut
+//! is typed, the keymap sends a `KeyboardCommand` message with the name of the command it's bound to, to the target that was set
+//! for that command with `Keymap::DefineCommand`. Keymaps can be loaded from a JSON file, which is how users can rebind the keys.
+//!
+//! The keymap receives keyboard events from the focus subprogram when no control has keyboard focus, so controls such as text
+//! fields take precedence over the shortcuts. Keys that aren't part of a shortcut are passed back to the focus program to be sent
+//! on as normal.
+//!
+
+use super::focus::*;
+use super::focus_events::*;
+use super::subprograms::*;
+
+use flo_scene::*;
+use flo_draw::*;
+
+use futures::prelude::*;
+use ::serde::*;
+use ::serde::de::{Error as DeError};
+
+use std::collections::{HashMap};
+use std::fmt;
+use std::fs;
+use std::path::*;
+use std::str::{FromStr};
+
+///
+/// The modifier keys that are held down as part of a key chord
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct KeyModifiers {
+    pub shift:  bool,
+    pub ctrl:   bool,
+    pub alt:    bool,
+    pub meta:   bool,
+}
+
+///
+/// A key that's pressed while holding down some modifier keys
+///
+/// Chords are written as the modifiers followed by the key, separated by '+', using the names of the `Key` enum for the key
+/// (eg `Ctrl+Shift+KeyZ`). `Cmd` can be used as a modifier to mean `Meta` on macOS and `Ctrl` elsewhere.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyChord {
+    /// The modifiers that must be held down
+    pub modifiers: KeyModifiers,
+
+    /// The key that's pressed
+    pub key: Key,
+}
+
+///
+/// A binding from a sequence of key chords to a command
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyBinding {
+    /// The chords that must be typed, in order, to run the command
+    pub sequence: Vec<KeyChord>,
+
+    /// The name of the command
+    pub command: String,
+}
+
+///
+/// A set of key bindings
+///
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct KeyBindings {
+    /// The bindings, in the order they were added
+    pub bindings: Vec<KeyBinding>,
+}
+
+///
+/// Two key bindings that can't both be used as typing the first also types (all or part of) the second
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeymapConflict {
+    /// The binding with the shorter (or same) sequence
+    pub first: KeyBinding,
+
+    /// The binding whose sequence starts with the sequence of the first binding
+    pub second: KeyBinding,
+}
+
+///
+/// The result of looking up a sequence of key chords in a set of key bindings
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeySequenceMatch {
+    /// The sequence runs the specified command
+    Command(String),
+
+    /// The sequence is the start of at least one binding
+    Partial,
+
+    /// The sequence doesn't match any binding
+    NoMatch,
+}
+
+///
+/// Errors that can occur while reading a keymap
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeymapError {
+    /// A key name was not recognised
+    UnknownKey(String),
+
+    /// A modifier name was not recognised
+    UnknownModifier(String),
+
+    /// A binding has no key chords
+    EmptySequence,
+
+    /// The keymap file could not be read or decoded
+    InvalidFile(String),
+}
+
+///
+/// Requests for the keymap subprogram
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Keymap {
+    /// A keyboard event from the focus subprogram
+    Keyboard(FocusKeyboardEvent),
+
+    /// Binds a sequence of key chords to a command, replacing any binding that has the same sequence
+    Bind(Vec<KeyChord>, String),
+
+    /// Removes the binding for a sequence of key chords
+    Unbind(Vec<KeyChord>),
+
+    /// Sets where a command is sent (as a `KeyboardCommand` message) when its shortcut is typed
+    DefineCommand(String, StreamTarget),
+
+    /// Loads a keymap file, replacing the existing bindings for any key sequence it contains
+    LoadKeymap(PathBuf),
+
+    /// Sends the conflicting bindings in the current keymap to a target as a `KeymapConflicts` message
+    SendConflicts(StreamTarget),
+}
+
+///
+/// Message sent to the target of a command when its keyboard shortcut is typed
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyboardCommand(pub String);
+
+///
+/// The conflicting bindings in the keymap, sent in response to `Keymap::SendConflicts`
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeymapConflicts(pub Vec<KeymapConflict>);
+
+impl SceneMessage for Keymap {
+    fn default_target() -> StreamTarget {
+        subprogram_keymap().into()
+    }
+
+    fn initialise(init_context: &impl SceneInitialisationContext) {
+        // Keyboard events are sent to the keymap by the focus program
+        init_context.connect_programs(StreamSource::Filtered(FilterHandle::for_filter(|keyboard_events| keyboard_events.map(|event| Keymap::Keyboard(event)))), (), StreamId::with_message_type::<FocusKeyboardEvent>()).unwrap();
+
+        init_context.add_subprogram(subprogram_keymap(), keymap_program, 20);
+        init_context.connect_programs((), subprogram_keymap(), StreamId::with_message_type::<Keymap>()).unwrap();
+    }
+}
+
+impl SceneMessage for KeyboardCommand {
+
+}
+
+impl SceneMessage for KeymapConflicts {
+
+}
+
+impl KeyModifiers {
+    ///
+    /// True if a key is a modifier key
+    ///
+    pub fn is_modifier(key: Key) -> bool {
+        matches!(key, Key::ModifierShift | Key::ModifierCtrl | Key::ModifierAlt | Key::ModifierMeta)
+    }
+
+    ///
+    /// Sets the platform's command modifier (meta on macOS, ctrl elsewhere)
+    ///
+    pub fn set_command(&mut self) {
+        if cfg!(target_os = "macos") {
+            self.meta = true;
+        } else {
+            self.ctrl = true;
+        }
+    }
+
+    ///
+    /// Updates these modifiers after a modifier key is pressed or released
+    ///
+    pub fn set_key(&mut self, key: Key, is_down: bool) {
+        match key {
+            Key::ModifierShift  => { self.shift = is_down; }
+            Key::ModifierCtrl   => { self.ctrl = is_down; }
+            Key::ModifierAlt    => { self.alt = is_down; }
+            Key::ModifierMeta   => { self.meta = is_down; }
+            _                   => { }
+        }
+    }
+}
+
+impl KeyChord {
+    ///
+    /// A key pressed with no modifiers
+    ///
+    pub fn key(key: Key) -> Self {
+        KeyChord { modifiers: KeyModifiers::default(), key: key }
+    }
+
+    ///
+    /// A key pressed while holding down ctrl
+    ///
+    pub fn ctrl(key: Key) -> Self {
+        KeyChord { modifiers: KeyModifiers { ctrl: true, ..KeyModifiers::default() }, key: key }
+    }
+
+    ///
+    /// A key pressed while holding down ctrl and shift
+    ///
+    pub fn ctrl_shift(key: Key) -> Self {
+        KeyChord { modifiers: KeyModifiers { ctrl: true, shift: true, ..KeyModifiers::default() }, key: key }
+    }
+
+    ///
+    /// A key pressed while holding down the platform's command key (meta on macOS, ctrl elsewhere)
+    ///
+    pub fn cmd(key: Key) -> Self {
+        let mut modifiers = KeyModifiers::default();
+        modifiers.set_command();
+
+        KeyChord { modifiers: modifiers, key: key }
+    }
+
+    ///
+    /// Parses a sequence of chords separated by spaces (eg `Ctrl+KeyK Ctrl+KeyC`)
+    ///
+    pub fn parse_sequence(sequence: &str) -> Result<Vec<KeyChord>, KeymapError> {
+        let chords = sequence.split_whitespace()
+            .map(|chord| chord.parse())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if chords.is_empty() {
+            Err(KeymapError::EmptySequence)
+        } else {
+            Ok(chords)
+        }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = KeymapError;
+
+    fn from_str(chord: &str) -> Result<Self, Self::Err> {
+        let mut parts       = chord.split('+').map(|part| part.trim()).collect::<Vec<_>>();
+        let key_name        = parts.pop().unwrap_or("");
+        let mut modifiers   = KeyModifiers::default();
+
+        for modifier in parts {
+            match modifier.to_lowercase().as_str() {
+                "shift"             => modifiers.shift = true,
+                "ctrl" | "control"  => modifiers.ctrl = true,
+                "alt" | "option"    => modifiers.alt = true,
+                "meta"              => modifiers.meta = true,
+                "cmd" | "command"   => modifiers.set_command(),
+                _                   => { return Err(KeymapError::UnknownModifier(modifier.to_string())); }
+            }
+        }
+
+        // Keys are named after the variants of the `Key` enum, which are the names that serde uses for them
+        let key = serde_json::from_value::<Key>(serde_json::Value::String(key_name.to_string()))
+            .map_err(|_| KeymapError::UnknownKey(key_name.to_string()))?;
+
+        if key == Key::Unknown || KeyModifiers::is_modifier(key) {
+            return Err(KeymapError::UnknownKey(key_name.to_string()));
+        }
+
+        Ok(KeyChord { modifiers, key })
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.ctrl  { write!(f, "Ctrl+")?; }
+        if self.modifiers.alt   { write!(f, "Alt+")?; }
+        if self.modifiers.shift { write!(f, "Shift+")?; }
+        if self.modifiers.meta  { write!(f, "Meta+")?; }
+
+        match serde_json::to_value(self.key) {
+            Ok(serde_json::Value::String(key_name)) => write!(f, "{}", key_name),
+            _                                       => write!(f, "{:?}", self.key),
+        }
+    }
+}
+
+impl Serialize for KeyChord {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'a> Deserialize<'a> for KeyChord {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'a>
+    {
+        let chord = String::deserialize(deserializer)?;
+        chord.parse().map_err(|err| D::Error::custom(format!("{:?}", err)))
+    }
+}
+
+impl KeyBindings {
+    ///
+    /// Binds a key sequence to a command, replacing any existing binding for the same sequence
+    ///
+    pub fn bind(&mut self, sequence: Vec<KeyChord>, command: impl Into<String>) {
+        let command = command.into();
+
+        if let Some(existing) = self.bindings.iter_mut().find(|binding| binding.sequence == sequence) {
+            existing.command = command;
+        } else {
+            self.bindings.push(KeyBinding { sequence, command });
+        }
+    }
+
+    ///
+    /// Removes the binding for a key sequence
+    ///
+    pub fn unbind(&mut self, sequence: &[KeyChord]) {
+        self.bindings.retain(|binding| binding.sequence != sequence);
+    }
+
+    ///
+    /// Adds the bindings from another set of bindings to this one, replacing any bindings with the same sequence
+    ///
+    pub fn merge(&mut self, bindings: KeyBindings) {
+        for binding in bindings.bindings {
+            self.bind(binding.sequence, binding.command);
+        }
+    }
+
+    ///
+    /// Finds the bindings that conflict with each other
+    ///
+    /// Bindings conflict if they have the same sequence, or if one sequence is the start of the other (in which case the longer
+    /// binding can never be typed)
+    ///
+    pub fn conflicts(&self) -> Vec<KeymapConflict> {
+        let mut conflicts = vec![];
+
+        for (first_idx, first) in self.bindings.iter().enumerate() {
+            for (second_idx, second) in self.bindings.iter().enumerate() {
+                if first_idx == second_idx { continue; }
+
+                // Bindings with the same sequence are only reported once
+                let is_prefix = second.sequence.starts_with(&first.sequence);
+                let is_same   = first.sequence.len() == second.sequence.len();
+
+                if is_prefix && (!is_same || first_idx < second_idx) {
+                    conflicts.push(KeymapConflict { first: first.clone(), second: second.clone() });
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    ///
+    /// Looks up the command for a sequence of chords
+    ///
+    pub fn find(&self, sequence: &[KeyChord]) -> KeySequenceMatch {
+        if let Some(binding) = self.bindings.iter().find(|binding| binding.sequence == sequence) {
+            KeySequenceMatch::Command(binding.command.clone())
+        } else if self.bindings.iter().any(|binding| binding.sequence.starts_with(sequence)) {
+            KeySequenceMatch::Partial
+        } else {
+            KeySequenceMatch::NoMatch
+        }
+    }
+
+    ///
+    /// Reads a set of key bindings from a JSON keymap
+    ///
+    pub fn from_json(json: &str) -> Result<Self, KeymapError> {
+        let bindings: KeyBindings = serde_json::from_str(json).map_err(|err| KeymapError::InvalidFile(err.to_string()))?;
+
+        if bindings.bindings.iter().any(|binding| binding.sequence.is_empty()) {
+            return Err(KeymapError::EmptySequence);
+        }
+
+        Ok(bindings)
+    }
+
+    ///
+    /// Writes these bindings as a JSON keymap
+    ///
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+///
+/// Runs the keymap subprogram
+///
+pub async fn keymap_program(input: InputStream<Keymap>, context: SceneContext) {
+    let Some(our_program_id) = context.current_program_id() else { return; };
+
+    // Ask the focus program to send us the keyboard events
+    context.send_message(Focus::SetShortcutHandler(our_program_id)).await.ok();
+
+    let mut bindings        = KeyBindings::default();
+    let mut command_targets = HashMap::new();
+
+    let mut modifiers       = KeyModifiers::default();
+    let mut sequence        = vec![];   // The chords typed so far in a sequence that partially matches a binding
+    let mut used_keys       = vec![];   // Keys that were used for shortcuts, whose key up events are not passed on
+
+    let mut input = input;
+    while let Some(request) = input.next().await {
+        use Keymap::*;
+
+        match request {
+            Keyboard(FocusKeyboardEvent::KeyDown(control_id, scancode, Some(key))) if !KeyModifiers::is_modifier(key) => {
+                sequence.push(KeyChord { modifiers, key });
+
+                // If a sequence doesn't match, it's abandoned and we check the chord on its own
+                let mut found = bindings.find(&sequence);
+                if found == KeySequenceMatch::NoMatch && sequence.len() > 1 {
+                    sequence = vec![KeyChord { modifiers, key }];
+                    found    = bindings.find(&sequence);
+                }
+
+                match found {
+                    KeySequenceMatch::Command(command) => {
+                        sequence.clear();
+                        used_keys.push(key);
+
+                        if let Some(target) = command_targets.get(&command) {
+                            if let Ok(mut target) = context.send::<KeyboardCommand>(StreamTarget::clone(target)) {
+                                target.send(KeyboardCommand(command)).await.ok();
+                            }
+                        }
+                    }
+
+                    KeySequenceMatch::Partial => {
+                        // Wait for the rest of the sequence
+                        used_keys.push(key);
+                    }
+
+                    KeySequenceMatch::NoMatch => {
+                        sequence.clear();
+                        context.send_message(Focus::UnhandledKeyboardEvent(FocusKeyboardEvent::KeyDown(control_id, scancode, Some(key)))).await.ok();
+                    }
+                }
+            }
+
+            Keyboard(FocusKeyboardEvent::KeyUp(_, _, Some(key))) if used_keys.contains(&key) => {
+                // Key up events for keys that were used as part of a shortcut are not passed on
+                used_keys.retain(|used_key| used_key != &key);
+            }
+
+            Keyboard(event) => {
+                // Track the modifiers, and pass on any other event
+                match &event {
+                    FocusKeyboardEvent::KeyDown(_, _, Some(key))    => modifiers.set_key(*key, true),
+                    FocusKeyboardEvent::KeyUp(_, _, Some(key))      => modifiers.set_key(*key, false),
+                    _                                               => { }
+                }
+
+                context.send_message(Focus::UnhandledKeyboardEvent(event)).await.ok();
+            }
+
+            Bind(sequence, command) => {
+                if !sequence.is_empty() {
+                    bindings.bind(sequence, command);
+                }
+            }
+
+            Unbind(sequence) => {
+                bindings.unbind(&sequence);
+            }
+
+            DefineCommand(command, target) => {
+                command_targets.insert(command, target);
+            }
+
+            LoadKeymap(path) => {
+                let keymap = fs::read_to_string(&path).ok().and_then(|json| KeyBindings::from_json(&json).ok());
+
+                if let Some(keymap) = keymap {
+                    bindings.merge(keymap);
+                }
+            }
+
+            SendConflicts(target) => {
+                if let Ok(mut target) = context.send(target) {
+                    target.send(KeymapConflicts(bindings.conflicts())).await.ok();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_chord() {
+        let chord = "Ctrl+Shift+KeyZ".parse::<KeyChord>().unwrap();
+
+        assert!(chord == KeyChord::ctrl_shift(Key::KeyZ));
+        assert!(chord.to_string() == "Ctrl+Shift+KeyZ");
+    }
+
+    #[test]
+    fn parse_sequence() {
+        let sequence = KeyChord::parse_sequence("Ctrl+KeyK KeyC").unwrap();
+
+        assert!(sequence == vec![KeyChord::ctrl(Key::KeyK), KeyChord::key(Key::KeyC)]);
+    }
+
+    #[test]
+    fn reject_unknown_keys() {
+        assert!("Ctrl+NotAKey".parse::<KeyChord>() == Err(KeymapError::UnknownKey("NotAKey".to_string())));
+        assert!("Hyper+KeyA".parse::<KeyChord>() == Err(KeymapError::UnknownModifier("Hyper".to_string())));
+        assert!("Ctrl+ModifierShift".parse::<KeyChord>() == Err(KeymapError::UnknownKey("ModifierShift".to_string())));
+    }
+
+    #[test]
+    fn find_sequences() {
+        let mut bindings = KeyBindings::default();
+        bindings.bind(vec![KeyChord::ctrl(Key::KeyZ)], "undo");
+        bindings.bind(vec![KeyChord::ctrl(Key::KeyK), KeyChord::key(Key::KeyC)], "comment");
+
+        assert!(bindings.find(&[KeyChord::ctrl(Key::KeyZ)]) == KeySequenceMatch::Command("undo".to_string()));
+        assert!(bindings.find(&[KeyChord::ctrl(Key::KeyK)]) == KeySequenceMatch::Partial);
+        assert!(bindings.find(&[KeyChord::ctrl(Key::KeyK), KeyChord::key(Key::KeyC)]) == KeySequenceMatch::Command("comment".to_string()));
+        assert!(bindings.find(&[KeyChord::key(Key::KeyZ)]) == KeySequenceMatch::NoMatch);
+    }
+
+    #[test]
+    fn detect_conflicts() {
+        let mut bindings = KeyBindings::default();
+        bindings.bind(vec![KeyChord::ctrl(Key::KeyK)], "kill");
+        bindings.bind(vec![KeyChord::ctrl(Key::KeyK), KeyChord::key(Key::KeyC)], "comment");
+        bindings.bind(vec![KeyChord::ctrl(Key::KeyZ)], "undo");
+
+        let conflicts = bindings.conflicts();
+
+        assert!(conflicts.len() == 1, "{:?}", conflicts);
+        assert!(conflicts[0].first.command == "kill");
+        assert!(conflicts[0].second.command == "comment");
+    }
+
+    #[test]
+    fn user_keymap_replaces_bindings() {
+        let mut bindings = KeyBindings::default();
+        bindings.bind(vec![KeyChord::ctrl(Key::KeyZ)], "undo");
+        bindings.bind(vec![KeyChord::ctrl_shift(Key::KeyZ)], "redo");
+
+        let user_keymap = KeyBindings::from_json(r#"{ "bindings": [ { "sequence": [ "Ctrl+KeyY" ], "command": "redo" }, { "sequence": [ "Ctrl+KeyZ" ], "command": "previous_frame" } ] }"#).unwrap();
+        bindings.merge(user_keymap);
+
+        assert!(bindings.find(&[KeyChord::ctrl(Key::KeyZ)]) == KeySequenceMatch::Command("previous_frame".to_string()));
+        assert!(bindings.find(&[KeyChord::ctrl(Key::KeyY)]) == KeySequenceMatch::Command("redo".to_string()));
+        assert!(bindings.find(&[KeyChord::ctrl_shift(Key::KeyZ)]) == KeySequenceMatch::Command("redo".to_string()));
+    }
+
+    #[test]
+    fn keymap_json_round_trip() {
+        let mut bindings = KeyBindings::default();
+        bindings.bind(vec![KeyChord::ctrl(Key::KeyK), KeyChord::key(Key::KeyC)], "comment");
+
+        assert!(KeyBindings::from_json(&bindings.to_json()) == Ok(bindings));
+    }
+}
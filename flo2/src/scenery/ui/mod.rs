@@ -9,6 +9,7 @@ mod control;
 mod control_id;
 mod focus;
 mod focus_events;
+mod keymap;
 mod subprograms;
 mod ui_path;
 mod dialog_id;
@@ -25,12 +26,14 @@ pub use control::*;
 pub use control_id::*;
 pub use focus::*;
 pub use focus_events::*;
+pub use keymap::*;
 pub use subprograms::*;
 pub use ui_path::*;
 pub use dialog_id::*;
 pub use dialog::*;
 pub use egui::*;
 pub use tools::tool_state::*;
+pub use tools::tool_layout::*;
 pub use tools::physics_simulation::*;
 pub use tools::physics_simulation_object::*;
 pub use tools::physics_simulation_joints::*;
@@ -19,3 +19,8 @@ pub fn subprogram_tool_state() -> SubProgramId { SubProgramId::called("flowbetwe
 /// ID of the physics layer subprogram
 ///
 pub fn subprogram_physics_layer() -> SubProgramId { SubProgramId::called("flowbetween::ui::physics_layer") }
+
+///
+/// ID of the keymap subprogram
+///
+pub fn subprogram_keymap() -> SubProgramId { SubProgramId::called("flowbetween::ui::keymap") }
@@ -315,6 +315,7 @@ pub async fn floating_tool_dock_program(input: InputStream<ToolState>, context:
             },
 
             ToolState::SetDialogLocation(_, _) => { },
+            ToolState::LoadSettings(_, _)      => { },
 
             ToolState::OpenDialog(tool_id) => {
                 let Some(tool) = tools.get(&tool_id) else { continue; };
@@ -14,6 +14,7 @@
 //!
 
 pub (crate) mod tool_state;
+pub (crate) mod tool_layout;
 pub (crate) mod tool_dock;
 pub (crate) mod floating_tool_dock;
 pub (crate) mod tool_graphics;
@@ -25,5 +26,6 @@ pub (crate) mod physics_simulation_joints;
 pub (crate) mod physics_simulation_object;
 
 pub use tool_state::*;
+pub use tool_layout::*;
 
 #[cfg(test)] mod test;
@@ -1 +1,2 @@
 mod tool_state_tests;
+mod tool_layout_tests;
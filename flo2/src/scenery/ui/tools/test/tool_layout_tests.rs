@@ -0,0 +1,105 @@
+use crate::scenery::ui::*;
+
+use flo_scene::*;
+use flo_scene::programs::*;
+
+use futures::prelude::*;
+use ::serde::*;
+
+fn test_layout() -> ToolLayout {
+    let tool_type   = ToolTypeId::new();
+    let tool_id_1   = ToolId::new();
+    let tool_id_2   = ToolId::new();
+
+    ToolLayout {
+        tools: vec![
+            ToolLayoutTool { tool_id: tool_id_1, tool_type: tool_type, duplicate_of: None, location: Some((SubProgramId::called("test::dock").into(), (1.0, 2.0))), settings: None },
+            ToolLayoutTool { tool_id: tool_id_2, tool_type: tool_type, duplicate_of: Some(tool_id_1), location: None, settings: Some(vec![1, 2, 3]) },
+        ],
+        joined:     vec![(tool_id_1, tool_id_2)],
+        selected:   vec![tool_id_2],
+    }
+}
+
+#[test]
+fn tool_layout_round_trip() {
+    let layout  = test_layout();
+    let bytes   = layout.to_bytes();
+
+    assert!(bytes.starts_with(b"FLOTOOLS"));
+    assert!(ToolLayout::from_bytes(&bytes) == Ok(layout));
+}
+
+#[test]
+fn reject_data_that_is_not_a_tool_layout() {
+    let mut newer_version = test_layout().to_bytes();
+    newer_version[8] = 99;
+
+    assert!(ToolLayout::from_bytes(b"not a tool layout") == Err(ToolLayoutError::NotAToolLayout));
+    assert!(ToolLayout::from_bytes(&newer_version) == Err(ToolLayoutError::UnsupportedVersion(99)));
+}
+
+#[test]
+fn restore_layout_when_tools_are_created() {
+    let scene = Scene::default();
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct TestResponse(usize, Option<(f64, f64)>, bool, Option<(f64, f64)>, Option<Vec<u8>>);
+
+    impl SceneMessage for TestResponse { }
+
+    let test_program        = SubProgramId::new();
+    let layout_program      = SubProgramId::new();
+    let location_program    = SubProgramId::new();
+
+    let tool_group  = ToolGroupId::new();
+    let tool_type   = ToolTypeId::new();
+    let tool_id_1   = ToolId::new();
+    let tool_id_2   = ToolId::new();
+    let tool_id_3   = ToolId::new();
+
+    // The location program ignores the tool state messages
+    scene.add_subprogram(location_program, |input: InputStream<ToolState>, _context| async move {
+        input.for_each(|_| future::ready(())).await;
+    }, 20);
+
+    // Save a layout with a duplicated tool, then restore it before the tool is created again
+    scene.add_subprogram(layout_program, move |input: InputStream<ToolLayout>, context| async move {
+        let mut input   = input;
+        let mut tools   = context.send::<Tool>(()).unwrap();
+
+        tools.send(Tool::CreateTool(tool_group, tool_type, tool_id_1)).await.unwrap();
+        tools.send(Tool::SetToolLocation(tool_id_1, location_program.into(), (1.0, 2.0))).await.unwrap();
+        tools.send(Tool::DuplicateTool(tool_id_1, tool_id_2)).await.unwrap();
+        tools.send(Tool::SetToolLocation(tool_id_2, location_program.into(), (3.0, 4.0))).await.unwrap();
+        tools.send(Tool::SetToolSettings(tool_id_2, vec![42])).await.unwrap();
+        tools.send(Tool::SendLayout(layout_program.into())).await.unwrap();
+
+        let saved_layout = input.next().await.unwrap();
+
+        tools.send(Tool::RemoveTool(tool_id_2)).await.unwrap();
+        tools.send(Tool::RemoveTool(tool_id_1)).await.unwrap();
+        tools.send(Tool::RestoreLayout(saved_layout)).await.unwrap();
+
+        // The owner creating the tool at its default location should restore the tool and its duplicate
+        tools.send(Tool::CreateTool(tool_group, tool_type, tool_id_3)).await.unwrap();
+        tools.send(Tool::SetToolLocation(tool_id_3, location_program.into(), (0.0, 0.0))).await.unwrap();
+        tools.send(Tool::SendLayout(layout_program.into())).await.unwrap();
+
+        let restored_layout = input.next().await.unwrap();
+        let restored_tool   = restored_layout.tools.iter().find(|tool| tool.tool_id == tool_id_3);
+        let duplicate_tool  = restored_layout.tools.iter().find(|tool| tool.duplicate_of == Some(tool_id_3));
+
+        context.send_message(TestResponse(
+            restored_layout.tools.len(),
+            restored_tool.and_then(|tool| tool.location.as_ref()).map(|(_, position)| *position),
+            duplicate_tool.is_some(),
+            duplicate_tool.and_then(|tool| tool.location.as_ref()).map(|(_, position)| *position),
+            duplicate_tool.and_then(|tool| tool.settings.clone()),
+        )).await.unwrap();
+    }, 20);
+
+    TestBuilder::new()
+        .expect_message_matching(TestResponse(2, Some((1.0, 2.0)), true, Some((3.0, 4.0)), Some(vec![42])), "Tool and its duplicate should be restored from the layout")
+        .run_in_scene(&scene, test_program);
+}
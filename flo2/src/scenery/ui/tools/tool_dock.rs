@@ -497,6 +497,7 @@ pub async fn tool_dock_program(input: InputStream<ToolState>, context: SceneCont
 
                 ToolState::SetName(_, _)            => { },
                 ToolState::SetDialogLocation(_, _)  => { },
+                ToolState::LoadSettings(_, _)       => { },
             }
         }
     }
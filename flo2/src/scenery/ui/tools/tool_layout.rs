@@ -0,0 +1,221 @@
+//!
+//! Tool layouts store where the tools are placed, which tools have been duplicated and joined, and the settings for each tool so that
+//! the arrangement of the tools can be restored later on (eg, when FlowBetween is next started, or when a document is re-opened).
+//!
+//! Tool IDs are generated afresh every time the tool owners start, so a layout can't be restored just by using the IDs it contains.
+//! Instead, the tools in a layout are matched against the tools created by their owners, by their type and the order they were created
+//! in. Duplicates are re-created from the tool they were originally duplicated from.
+//!
+
+use super::tool_state::*;
+
+use flo_scene::*;
+
+use ::serde::*;
+
+use std::collections::*;
+
+/// The bytes that a tool layout file starts with
+const TOOL_LAYOUT_MAGIC: &[u8] = b"FLOTOOLS";
+
+/// The version of the tool layout format written by this version of FlowBetween
+const TOOL_LAYOUT_VERSION: u32 = 1;
+
+///
+/// A tool stored in a tool layout
+///
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ToolLayoutTool {
+    /// The ID of this tool when the layout was saved (only used to identify the tool within the layout)
+    pub tool_id: ToolId,
+
+    /// The type of this tool
+    pub tool_type: ToolTypeId,
+
+    /// If this tool was created by duplicating another tool, the ID of that tool within the layout
+    pub duplicate_of: Option<ToolId>,
+
+    /// The location program for the tool and where it was placed
+    pub location: Option<(StreamTarget, (f64, f64))>,
+
+    /// The settings reported by the owner of the tool
+    pub settings: Option<Vec<u8>>,
+}
+
+///
+/// The arrangement of the tools that are managed by the tool state program
+///
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ToolLayout {
+    /// The tools, in the order they were created
+    pub tools: Vec<ToolLayoutTool>,
+
+    /// Pairs of (main tool, joined tool) created by `Tool::JoinTools`
+    pub joined: Vec<(ToolId, ToolId)>,
+
+    /// The tools that were selected
+    pub selected: Vec<ToolId>,
+}
+
+///
+/// Errors that can occur while reading a tool layout
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum ToolLayoutError {
+    /// The data does not start with the tool layout header
+    NotAToolLayout,
+
+    /// The tool layout was written by a newer version of FlowBetween
+    UnsupportedVersion(u32),
+
+    /// The tool layout data could not be decoded
+    InvalidData,
+}
+
+impl SceneMessage for ToolLayout {
+
+}
+
+impl ToolLayout {
+    ///
+    /// Encodes this layout in the tool layout file format
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = TOOL_LAYOUT_MAGIC.to_vec();
+        bytes.extend(TOOL_LAYOUT_VERSION.to_le_bytes());
+        bytes.extend(postcard::to_allocvec(self).unwrap_or_default());
+
+        bytes
+    }
+
+    ///
+    /// Decodes a layout from the tool layout file format
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ToolLayoutError> {
+        let Some(bytes) = bytes.strip_prefix(TOOL_LAYOUT_MAGIC) else { return Err(ToolLayoutError::NotAToolLayout); };
+        if bytes.len() < 4 { return Err(ToolLayoutError::InvalidData); }
+
+        let (version, bytes) = bytes.split_at(4);
+        let version          = u32::from_le_bytes([version[0], version[1], version[2], version[3]]);
+
+        if version > TOOL_LAYOUT_VERSION { return Err(ToolLayoutError::UnsupportedVersion(version)); }
+
+        postcard::from_bytes(bytes).map_err(|_| ToolLayoutError::InvalidData)
+    }
+}
+
+///
+/// Tracks the progress of restoring a layout, as the tools it refers to are created
+///
+pub (crate) struct ToolLayoutRestore {
+    /// The layout that's being restored
+    layout: ToolLayout,
+
+    /// Maps the tool IDs in the layout to the IDs of the tools that they've been restored to
+    restored_tools: HashMap<ToolId, ToolId>,
+
+    /// The joins that are waiting for both of their tools to be restored
+    pending_joins: Vec<(ToolId, ToolId)>,
+
+    /// The selections that are waiting for their tool to be restored
+    pending_selection: Vec<ToolId>,
+}
+
+impl ToolLayoutRestore {
+    ///
+    /// Starts restoring a layout
+    ///
+    pub fn new(layout: ToolLayout) -> Self {
+        let pending_joins       = layout.joined.clone();
+        let pending_selection   = layout.selected.clone();
+
+        ToolLayoutRestore {
+            layout:             layout,
+            restored_tools:     HashMap::new(),
+            pending_joins:      pending_joins,
+            pending_selection:  pending_selection,
+        }
+    }
+
+    ///
+    /// True if every tool in the layout has been restored
+    ///
+    pub fn is_finished(&self) -> bool {
+        self.restored_tools.len() >= self.layout.tools.len()
+    }
+
+    ///
+    /// Indicates that the owner of a tool type has created a new tool
+    ///
+    /// If the tool matches a tool in the layout, this returns the location it should be placed at in place of the owner's default
+    /// location, and the requests needed to restore its settings, duplicates, joins and selection.
+    ///
+    pub fn tool_created(&mut self, tool_id: ToolId, tool_type: ToolTypeId) -> (Option<(StreamTarget, (f64, f64))>, Vec<Tool>) {
+        // Match against the first tool of the same type that hasn't been restored yet (duplicates are restored from their originals)
+        let layout_tool = self.layout.tools.iter()
+            .find(|layout_tool| layout_tool.tool_type == tool_type && layout_tool.duplicate_of.is_none() && !self.restored_tools.contains_key(&layout_tool.tool_id));
+        let Some(layout_tool) = layout_tool else { return (None, vec![]); };
+
+        let layout_tool_id  = layout_tool.tool_id;
+        let location        = layout_tool.location.clone();
+
+        let mut requests = vec![];
+        self.restore_tool(layout_tool_id, tool_id, &mut requests);
+        self.restore_joins_and_selection(&mut requests);
+
+        (location, requests)
+    }
+
+    ///
+    /// Generates the requests to restore a tool that has been matched to a tool in the layout, along with its duplicates
+    ///
+    fn restore_tool(&mut self, layout_tool_id: ToolId, tool_id: ToolId, requests: &mut Vec<Tool>) {
+        self.restored_tools.insert(layout_tool_id, tool_id);
+
+        if let Some(settings) = self.layout.tools.iter().find(|layout_tool| layout_tool.tool_id == layout_tool_id).and_then(|layout_tool| layout_tool.settings.clone()) {
+            requests.push(Tool::LoadToolSettings(tool_id, settings));
+        }
+
+        // Re-create any tools that were duplicated from this one
+        let duplicates = self.layout.tools.iter()
+            .filter(|layout_tool| layout_tool.duplicate_of == Some(layout_tool_id))
+            .map(|layout_tool| (layout_tool.tool_id, layout_tool.location.clone()))
+            .collect::<Vec<_>>();
+
+        for (duplicate_layout_id, location) in duplicates {
+            let duplicate_tool_id = ToolId::new();
+
+            requests.push(Tool::DuplicateTool(tool_id, duplicate_tool_id));
+            if let Some((location_target, position)) = location {
+                requests.push(Tool::SetToolLocation(duplicate_tool_id, location_target, position));
+            }
+
+            self.restore_tool(duplicate_layout_id, duplicate_tool_id, requests);
+        }
+    }
+
+    ///
+    /// Generates the requests for any joins or selections whose tools have all been restored
+    ///
+    fn restore_joins_and_selection(&mut self, requests: &mut Vec<Tool>) {
+        let restored_tools = &self.restored_tools;
+
+        self.pending_joins.retain(|(main_tool, joined_tool)| {
+            if let (Some(main_tool), Some(joined_tool)) = (restored_tools.get(main_tool), restored_tools.get(joined_tool)) {
+                requests.push(Tool::JoinTools(*main_tool, *joined_tool));
+                false
+            } else {
+                true
+            }
+        });
+
+        self.pending_selection.retain(|selected_tool| {
+            if let Some(selected_tool) = restored_tools.get(selected_tool) {
+                requests.push(Tool::Select(*selected_tool));
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
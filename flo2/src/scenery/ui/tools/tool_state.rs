@@ -4,18 +4,24 @@
 //! in the application
 //!
 
+use super::tool_layout::*;
 use crate::scenery::ui::subprograms::*;
 
 use flo_scene::*;
+use flo_scene::programs::{QueryRequest, QueryResponse};
 use flo_draw::canvas::*;
 
 use futures::prelude::*;
+use futures::executor;
 use ::serde::*;
 use uuid::*;
 
 use std::collections::*;
+use std::fs;
 use std::iter;
+use std::path::*;
 use std::sync::*;
+use std::thread;
 
 ///
 /// Identifier used to specify a tool group within the flowbetween app. One tool can be selected
@@ -152,6 +158,9 @@ pub enum Tool {
     /// Sets a tool as a selected (unselecting any other tools in the group)
     Select(ToolId),
 
+    /// Selects the first tool of a particular type (in the order the tools were created)
+    SelectType(ToolTypeId),
+
     /// Opens the configuration dialog for a tool ID
     OpenDialog(ToolId),
 
@@ -160,6 +169,31 @@ pub enum Tool {
 
     /// Close any open dialogs
     CloseAllDialogs,
+
+    /// Sent by the owner of a tool to report its current settings, which are stored in the tool layout
+    SetToolSettings(ToolId, Vec<u8>),
+
+    /// Stores the settings for a tool and sends them to its owner as a `ToolState::LoadSettings` message
+    LoadToolSettings(ToolId, Vec<u8>),
+
+    /// Writes the current tool layout to a file, sending `ToolLayoutSaved` to the target (if there is one) once the file has been written
+    SaveLayout(PathBuf, Option<StreamTarget>),
+
+    /// Reads a tool layout from a file and restores it (see `RestoreLayout`)
+    LoadLayout(PathBuf),
+
+    /// Sends the current tool layout to a target as a `ToolLayout` message
+    SendLayout(StreamTarget),
+
+    ///
+    /// Restores a tool layout
+    ///
+    /// Tools in the layout are matched with the tools created by their owners by type, in the order they were created. Tools that
+    /// have already been created are restored immediately, and tools created later on are placed at their location from the layout
+    /// instead of the location first set by their owner. Duplicated tools are re-created from the layout, replacing any existing
+    /// duplicates.
+    ///
+    RestoreLayout(ToolLayout),
 }
 
 impl SceneMessage for Tool {
@@ -174,6 +208,35 @@ impl SceneMessage for Tool {
     }
 }
 
+///
+/// Query that writes the current tool layout to a file, responding with `ToolLayoutSaved` once it has been written
+///
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SaveToolLayout(pub PathBuf, pub StreamTarget);
+
+///
+/// Response to a `SaveToolLayout` query, sent once the layout file has been written
+///
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ToolLayoutSaved(pub PathBuf);
+
+impl SceneMessage for SaveToolLayout {
+    fn initialise(init_context: &impl SceneInitialisationContext) {
+        init_context.connect_programs(FilterHandle::for_filter(|stream: InputStream<SaveToolLayout>| stream.map(|SaveToolLayout(path, target)| Tool::SaveLayout(path, Some(target)))), subprogram_tool_state(), StreamId::with_message_type::<SaveToolLayout>()).unwrap();
+    }
+}
+
+impl QueryRequest for SaveToolLayout {
+    type ResponseData = ToolLayoutSaved;
+
+    fn with_new_target(self, new_target: StreamTarget) -> Self {
+        SaveToolLayout(self.0, new_target)
+    }
+}
+
+impl SceneMessage for ToolLayoutSaved {
+}
+
 ///
 /// Messages sent to anything that queries or subscribes to the state of the tools in FlowBetween
 ///
@@ -212,6 +275,9 @@ pub enum ToolState {
 
     /// Closes the dialog for configuring the specified tool
     CloseDialog(ToolId),
+
+    /// Replaces the settings for a tool with settings previously reported with `Tool::SetToolSettings`
+    LoadSettings(ToolId, Vec<u8>),
 }
 
 impl SceneMessage for ToolState {
@@ -255,6 +321,14 @@ pub async fn tool_state_program(input: InputStream<Tool>, context: SceneContext)
     let mut subscribers             = vec![];                           // Subscribers for general messages
     let mut open_dialogs            = HashSet::new();                   // Tools with open configuration dialogs
 
+    let mut tool_order              = vec![];                           // The tools in the order they were created
+    let mut duplicate_source        = HashMap::new();                   // The tool that each duplicated tool was created from
+    let mut tool_positions          = HashMap::new();                   // The position of each tool within its location
+    let mut tool_settings           = HashMap::new();                   // The settings reported by the owner of each tool
+    let mut layout_restore          = None::<ToolLayoutRestore>;        // The layout that's being restored as tools are created
+    let mut restored_locations      = HashMap::new();                   // Locations from the layout that replace the first location set for a tool
+    let mut pending_requests        = VecDeque::new();                  // Requests generated while restoring a layout, processed before the next input
+
     // Sends a message to all the Subscribers
     async fn send_to_subscribers(subscribers: Option<&mut Vec<Option<OutputSink<ToolState>>>>, message: ToolState) {
         if let Some(subscribers) = subscribers {
@@ -277,9 +351,17 @@ pub async fn tool_state_program(input: InputStream<Tool>, context: SceneContext)
 
     // Run the main loop
     let mut input = input;
-    while let Some(tool_request) = input.next().await {
+    loop {
         use Tool::*;
 
+        let tool_request = if let Some(pending_request) = pending_requests.pop_front() {
+            pending_request
+        } else if let Some(input_request) = input.next().await {
+            input_request
+        } else {
+            break;
+        };
+
         match tool_request {
             Subscribe(subscribe_target) => {
                 if let Ok(mut subscription_target) = context.send::<ToolState>(subscribe_target) {
@@ -320,6 +402,9 @@ pub async fn tool_state_program(input: InputStream<Tool>, context: SceneContext)
             }
 
             SetToolLocation(tool_id, location_target, position) => {
+                // The location from a restored layout replaces the first location that's set for a tool
+                let (location_target, position) = restored_locations.remove(&tool_id).unwrap_or((location_target, position));
+
                 if let Some(location) = tool_locations.get_mut(&tool_id) {
                     // If the location target has changed, remove the tool from the original
                     let mut target_changed = false;
@@ -347,6 +432,7 @@ pub async fn tool_state_program(input: InputStream<Tool>, context: SceneContext)
 
                     // Set the location of the tool
                     send_to_subscribers(Some(location), ToolState::LocateTool(tool_id, position)).await;
+                    tool_positions.insert(tool_id, position);
                 }
             }
 
@@ -378,6 +464,7 @@ pub async fn tool_state_program(input: InputStream<Tool>, context: SceneContext)
                 group_for_tool.insert(tool_id, group_id);
                 type_for_tool.insert(tool_id, type_id);
                 tools.insert(tool_id);
+                tool_order.push(tool_id);
 
                 // Set up the default properties
                 tool_locations.insert(tool_id, vec![]);
@@ -388,6 +475,16 @@ pub async fn tool_state_program(input: InputStream<Tool>, context: SceneContext)
                 // Indicate that this tool has been added to the subscribers and the type owner
                 send_to_subscribers(Some(&mut subscribers), ToolState::AddTool(tool_id)).await;
                 send_to_subscribers(tool_type_owners.get_mut(&type_id), ToolState::AddTool(tool_id)).await;
+
+                // Restore the tool if it's part of a layout that's being loaded
+                if let Some(restore) = &mut layout_restore {
+                    let (restored_location, requests) = restore.tool_created(tool_id, type_id);
+
+                    if let Some(restored_location) = restored_location { restored_locations.insert(tool_id, restored_location); }
+                    pending_requests.extend(requests);
+
+                    if restore.is_finished() { layout_restore = None; }
+                }
             }
 
             RemoveTool(tool_id) => {
@@ -398,6 +495,11 @@ pub async fn tool_state_program(input: InputStream<Tool>, context: SceneContext)
                 tool_icons.remove(&tool_id);
                 tools.remove(&tool_id);
                 tool_locations.remove(&tool_id);
+                tool_order.retain(|old_tool_id| old_tool_id != &tool_id);
+                duplicate_source.remove(&tool_id);
+                tool_positions.remove(&tool_id);
+                tool_settings.remove(&tool_id);
+                restored_locations.remove(&tool_id);
 
                 if let Some(parent_tool) = join_parent_tool.remove(&tool_id) {
                     if let Some(joined_with) = joined_tools.get_mut(&parent_tool) {
@@ -468,6 +570,12 @@ pub async fn tool_state_program(input: InputStream<Tool>, context: SceneContext)
                     group_for_tool.insert(new_tool_id, tool_group);
                     type_for_tool.insert(new_tool_id, tool_type);
                     tools.insert(new_tool_id);
+                    tool_order.push(new_tool_id);
+                    duplicate_source.insert(new_tool_id, old_tool_id);
+
+                    if let Some(settings) = tool_settings.get(&old_tool_id).cloned() {
+                        tool_settings.insert(new_tool_id, settings);
+                    }
 
                     tool_locations.insert(new_tool_id, vec![]);
                     tool_names.insert(new_tool_id, tool_name.clone());
@@ -488,6 +596,10 @@ pub async fn tool_state_program(input: InputStream<Tool>, context: SceneContext)
 
                             tool_locations.insert(new_tool_id, location);
                             tool_location_targets.insert(new_tool_id, location_target);
+
+                            if let Some(position) = tool_positions.get(&old_tool_id).copied() {
+                                tool_positions.insert(new_tool_id, position);
+                            }
                         }
                     }
 
@@ -638,6 +750,12 @@ pub async fn tool_state_program(input: InputStream<Tool>, context: SceneContext)
                 }
             }
 
+            SelectType(tool_type_id) => {
+                if let Some(tool_id) = tool_order.iter().find(|tool_id| type_for_tool.get(*tool_id) == Some(&tool_type_id)) {
+                    pending_requests.push_front(Select(*tool_id));
+                }
+            }
+
             OpenDialog(tool_id) => {
                 if tools.contains(&tool_id) {
                     if !open_dialogs.contains(&tool_id) {
@@ -673,6 +791,97 @@ pub async fn tool_state_program(input: InputStream<Tool>, context: SceneContext)
                     send_to_subscribers(type_for_tool.get_mut(&closed_dialog).and_then(|tool_type| tool_type_owners.get_mut(tool_type)), ToolState::CloseDialog(closed_dialog)).await;
                 }
             }
+
+            SetToolSettings(tool_id, settings) => {
+                if tools.contains(&tool_id) {
+                    tool_settings.insert(tool_id, settings);
+                }
+            }
+
+            LoadToolSettings(tool_id, settings) => {
+                if tools.contains(&tool_id) {
+                    tool_settings.insert(tool_id, settings.clone());
+
+                    send_to_subscribers(type_for_tool.get(&tool_id).and_then(|tool_type| tool_type_owners.get_mut(tool_type)), ToolState::LoadSettings(tool_id, settings)).await;
+                }
+            }
+
+            layout_request @ (SaveLayout(..) | SendLayout(_)) => {
+                // Generate the layout from the current state
+                let layout = ToolLayout {
+                    tools: tool_order.iter()
+                        .flat_map(|tool_id| Some(ToolLayoutTool {
+                            tool_id:        *tool_id,
+                            tool_type:      *type_for_tool.get(tool_id)?,
+                            duplicate_of:   duplicate_source.get(tool_id).copied(),
+                            location:       tool_location_targets.get(tool_id).map(|target| (target.clone(), tool_positions.get(tool_id).copied().unwrap_or((0.0, 0.0)))),
+                            settings:       tool_settings.get(tool_id).cloned(),
+                        }))
+                        .collect(),
+                    joined: joined_tools.iter()
+                        .flat_map(|(main_tool, joined_with)| joined_with.iter().map(move |joined_tool| (*main_tool, *joined_tool)))
+                        .collect(),
+                    selected: group_selection.values().copied().collect(),
+                };
+
+                match layout_request {
+                    SaveLayout(path, saved_target) => {
+                        // Write the file on its own thread so that the tool state doesn't stall while it's being written
+                        let bytes       = layout.to_bytes();
+                        let saved_sink  = saved_target.and_then(|target| context.send::<QueryResponse<ToolLayoutSaved>>(target).ok());
+
+                        thread::spawn(move || {
+                            if let Some(parent) = path.parent() { fs::create_dir_all(parent).ok(); }
+                            fs::write(&path, bytes).ok();
+
+                            if let Some(mut saved_sink) = saved_sink {
+                                executor::block_on(saved_sink.send(QueryResponse::with_data(ToolLayoutSaved(path)))).ok();
+                            }
+                        });
+                    }
+
+                    SendLayout(target) => {
+                        if let Ok(mut target) = context.send::<ToolLayout>(target) {
+                            target.send(layout).await.ok();
+                        }
+                    }
+
+                    _ => { }
+                }
+            }
+
+            LoadLayout(path) => {
+                let layout = fs::read(&path).ok().and_then(|bytes| ToolLayout::from_bytes(&bytes).ok());
+
+                if let Some(layout) = layout {
+                    pending_requests.push_front(RestoreLayout(layout));
+                }
+            }
+
+            RestoreLayout(layout) => {
+                let mut restore = ToolLayoutRestore::new(layout);
+
+                // Duplicates are re-created from the layout, so the existing ones are removed
+                let existing_duplicates = tool_order.iter()
+                    .filter(|tool_id| duplicate_source.contains_key(*tool_id))
+                    .copied()
+                    .collect::<Vec<_>>();
+                pending_requests.extend(existing_duplicates.into_iter().map(|tool_id| RemoveTool(tool_id)));
+
+                // Restore the tools that already exist
+                for tool_id in tool_order.iter().filter(|tool_id| !duplicate_source.contains_key(*tool_id)) {
+                    let Some(tool_type) = type_for_tool.get(tool_id) else { continue; };
+                    let (restored_location, requests) = restore.tool_created(*tool_id, *tool_type);
+
+                    if let Some((location_target, position)) = restored_location {
+                        pending_requests.push_back(SetToolLocation(*tool_id, location_target, position));
+                    }
+                    pending_requests.extend(requests);
+                }
+
+                // Any remaining tools are restored when their owners create them
+                layout_restore = if restore.is_finished() { None } else { Some(restore) };
+            }
         }
     }
 }
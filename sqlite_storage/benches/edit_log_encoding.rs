@@ -0,0 +1,229 @@
+use flo_animation::*;
+use flo_animation::serializer::*;
+use flo_animation::storage::*;
+use flo_sqlite_storage::*;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use futures::prelude::*;
+use futures::executor;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::*;
+use std::time::{Duration};
+
+///
+/// The number of brush strokes to draw in the benchmark session
+///
+const NUM_STROKES: usize = 500;
+
+///
+/// The number of points in each brush stroke
+///
+const POINTS_PER_STROKE: usize = 60;
+
+///
+/// Creates the edits for a drawing session, made up of the same kinds of edit as the round trip tests
+///
+fn session_edits() -> Vec<AnimationEdit> {
+    use self::LayerEdit::*;
+
+    let mut edits = vec![
+        AnimationEdit::AddNewLayer(2),
+        AnimationEdit::Layer(2, SetName("Layer 1".to_string())),
+        AnimationEdit::Layer(2, AddKeyFrame(Duration::from_millis(0))),
+        AnimationEdit::Layer(2, Paint(Duration::from_millis(0),
+            PaintEdit::SelectBrush(ElementId::Unassigned, BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw))),
+        AnimationEdit::Layer(2, Paint(Duration::from_millis(0),
+            PaintEdit::BrushProperties(ElementId::Unassigned, BrushProperties::new()))),
+        AnimationEdit::Layer(2, LayerEdit::Path(Duration::from_millis(0),
+            PathEdit::SelectBrush(ElementId::Unassigned, BrushDefinition::Ink(InkDefinition::default()), BrushDrawingStyle::Draw))),
+        AnimationEdit::Layer(2, LayerEdit::Path(Duration::from_millis(0),
+            PathEdit::BrushProperties(ElementId::Unassigned, BrushProperties::new()))),
+    ];
+
+    for stroke_num in 0..NUM_STROKES {
+        let offset = (stroke_num % 100) as f32 * 10.0;
+
+        // Mostly brush strokes, which make up the bulk of a typical edit log
+        let points = (0..POINTS_PER_STROKE).map(|point_num| {
+            let t = (point_num as f32) / (POINTS_PER_STROKE as f32);
+            RawPoint::from((100.0 + offset + t*400.0, 300.0 + (t*6.28).sin()*80.0))
+        }).collect::<Vec<_>>();
+
+        edits.push(AnimationEdit::Layer(2, Paint(Duration::from_millis(0),
+            PaintEdit::BrushStroke(ElementId::Unassigned, Arc::new(points)))));
+
+        // Some paths
+        if stroke_num % 10 == 0 {
+            edits.push(AnimationEdit::Layer(2, LayerEdit::Path(Duration::from_millis(0),
+                PathEdit::CreatePath(ElementId::Unassigned, Arc::new(vec![
+                    PathComponent::Move(PathPoint::new(10.0 + offset, 20.0)),
+                    PathComponent::Line(PathPoint::new(20.0 + offset, 30.0)),
+                    PathComponent::Bezier(PathPoint::new(40.0 + offset, 40.0), PathPoint::new(30.0 + offset, 30.0), PathPoint::new(20.0 + offset, 20.0)),
+                    PathComponent::Close
+                ])))));
+        }
+    }
+
+    edits
+}
+
+///
+/// Serializes a list of edits in the text format
+///
+fn serialize_edits(edits: &Vec<AnimationEdit>) -> Vec<String> {
+    edits.iter()
+        .map(|edit| {
+            let mut serialized = String::new();
+            edit.serialize(&mut serialized);
+            serialized
+        })
+        .collect()
+}
+
+///
+/// Returns a path in the temp directory for a benchmark file
+///
+fn temp_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("flo_edit_log_encoding_{}_{}.flo", name, std::process::id()));
+    fs::remove_file(&path).ok();
+
+    path
+}
+
+///
+/// Writes the session edits to a new animation file
+///
+fn write_session_file(path: &Path, edits: &Vec<AnimationEdit>) {
+    let storage     = SqliteAnimationStorage::new_with_file(path).unwrap();
+    let animation   = create_animation_editor(move |commands| storage.get_responses(commands).boxed());
+
+    animation.perform_edits(edits.clone());
+
+    // Reading back from the animation waits for the edits to be stored
+    animation.get_num_edits();
+    drop(animation);
+
+    // Vacuum the file so its size can be compared with the text version
+    let connection = rusqlite::Connection::open(path).unwrap();
+    connection.execute_batch("VACUUM;").unwrap();
+}
+
+///
+/// Converts the compact values in a table back to text, as they would be stored in a version 4 file
+///
+fn convert_table_to_text(connection: &rusqlite::Connection, table: &str, key: &str, column: &str) {
+    let mut read    = connection.prepare(&format!("SELECT {}, {} FROM {} WHERE typeof({}) = 'blob';", key, column, table, column)).unwrap();
+    let values      = read.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+    let mut write   = connection.prepare(&format!("UPDATE {} SET {} = ? WHERE {} = ?;", table, column, key)).unwrap();
+
+    for (key, value) in values {
+        write.execute(rusqlite::params![decode_compact_bytes(&value), key]).unwrap();
+    }
+}
+
+///
+/// Copies an animation file, converting it to the version 4 text encoding
+///
+fn write_text_file(compact_path: &Path, text_path: &Path) {
+    fs::copy(compact_path, text_path).unwrap();
+
+    let connection = rusqlite::Connection::open(text_path).unwrap();
+
+    convert_table_to_text(&connection, "EditLog", "EditId", "Edit");
+    convert_table_to_text(&connection, "Elements", "ElementId", "Element");
+
+    {
+        let mut read    = connection.prepare("SELECT LayerId, TimeMicroseconds, CacheType, Cache FROM LayerCache WHERE typeof(Cache) = 'blob';").unwrap();
+        let caches      = read.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?, row.get::<_, Vec<u8>>(3)?))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        let mut write   = connection.prepare("UPDATE LayerCache SET Cache = ? WHERE LayerId = ? AND TimeMicroseconds = ? AND CacheType = ?;").unwrap();
+
+        for (layer_id, when, cache_type, cache) in caches {
+            write.execute(rusqlite::params![decode_compact_bytes(&cache), layer_id, when, cache_type]).unwrap();
+        }
+    }
+
+    connection.execute_batch("PRAGMA user_version = 0; VACUUM;").unwrap();
+}
+
+///
+/// Opens an animation file and reads back its edit log and the elements in its first frame
+///
+fn load_file(path: &Path) -> usize {
+    let storage     = SqliteAnimationStorage::open_file(path).unwrap();
+    let animation   = create_animation_editor(move |commands| storage.get_responses(commands).boxed());
+
+    let num_edits   = animation.get_num_edits();
+    let edits       = executor::block_on(animation.read_edit_log(0..num_edits).collect::<Vec<_>>());
+
+    let frame       = animation.get_layer_with_id(2).unwrap().get_frame_at_time(Duration::from_millis(0));
+    let elements    = frame.vector_elements().map(|elements| elements.count()).unwrap_or(0);
+
+    edits.len() + elements
+}
+
+fn edit_log_encoding(c: &mut Criterion) {
+    let edits           = session_edits();
+    let serialized      = serialize_edits(&edits);
+    let compact         = serialized.iter().map(|edit| encode_compact_bytes(edit)).collect::<Vec<_>>();
+
+    // Compare the size of the edits on their own
+    let text_size       = serialized.iter().map(|edit| edit.len()).sum::<usize>();
+    let compact_size    = compact.iter().map(|edit| edit.len()).sum::<usize>();
+
+    println!("Edit log: {} edits, {} bytes as text, {} bytes compact ({:.1}%)", edits.len(), text_size, compact_size, (compact_size as f64)/(text_size as f64)*100.0);
+
+    // Compare the size of the files
+    let compact_path    = temp_file("compact");
+    let text_path       = temp_file("text");
+
+    write_session_file(&compact_path, &edits);
+    write_text_file(&compact_path, &text_path);
+
+    let compact_file    = fs::metadata(&compact_path).unwrap().len();
+    let text_file       = fs::metadata(&text_path).unwrap().len();
+
+    println!("Animation file: {} bytes as text (v4), {} bytes compact (v5) ({:.1}%)", text_file, compact_file, (compact_file as f64)/(text_file as f64)*100.0);
+
+    // Decoding the edits
+    c.bench_function("decode_text_edits", |b| b.iter(|| {
+        serialized.iter()
+            .map(|edit| AnimationEdit::deserialize(&mut edit.chars()))
+            .for_each(|edit| { black_box(edit); })
+    }));
+
+    c.bench_function("decode_compact_edits", |b| b.iter(|| {
+        compact.iter()
+            .map(|edit| decode_compact_bytes(edit))
+            .map(|edit| AnimationEdit::deserialize(&mut edit.chars()))
+            .for_each(|edit| { black_box(edit); })
+    }));
+
+    c.bench_function("encode_compact_edits", |b| b.iter(|| {
+        serialized.iter()
+            .map(|edit| encode_compact_bytes(edit))
+            .for_each(|edit| { black_box(edit); })
+    }));
+
+    // Loading the files (a version 4 file is migrated the first time it's opened, so it's copied for every iteration)
+    let mut group = c.benchmark_group("load_file");
+    group.sample_size(10);
+
+    group.bench_function("compact", |b| b.iter(|| black_box(load_file(&compact_path))));
+
+    let migrate_path = temp_file("migrate");
+    group.bench_function("text_with_migration", |b| b.iter_batched(
+        || { fs::copy(&text_path, &migrate_path).unwrap(); },
+        |_| black_box(load_file(&migrate_path)),
+        BatchSize::PerIteration));
+
+    group.finish();
+
+    fs::remove_file(&compact_path).ok();
+    fs::remove_file(&text_path).ok();
+    fs::remove_file(&migrate_path).ok();
+}
+
+criterion_group!(benches, edit_log_encoding);
+criterion_main!(benches);
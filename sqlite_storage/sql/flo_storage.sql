@@ -1,10 +1,13 @@
 /***
  **
- ** FlowBetween File format version 4
+ ** FlowBetween File format version 5
  **
  **   V4 of the file format moves the bulk of the work of data representation into the animation and its serialization
  **   format, which greatly simplifies the content of the database.
  **
+ **   V5 stores edits, elements and caches in the compact binary form of the serialization format. V4 files are
+ **   converted when they are opened (V4 files have a user_version of 0, and V5 files have a user_version of 5)
+ **
  ***/
 
 /**
@@ -20,7 +23,7 @@ CREATE TABLE AnimationProperties (
  */
 CREATE TABLE EditLog (
     EditId INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
-    Edit TEXT NOT NULL
+    Edit BLOB NOT NULL
 );
 
 /**
@@ -28,7 +31,7 @@ CREATE TABLE EditLog (
  */
 CREATE TABLE Elements (
     ElementId INTEGER NOT NULL PRIMARY KEY,
-    Element TEXT NOT NULL
+    Element BLOB NOT NULL
 ) WITHOUT ROWID;
 
 /**
@@ -70,7 +73,7 @@ CREATE TABLE LayerCache (
     LayerId INTEGER NOT NULL,
     TimeMicroseconds INTEGER NOT NULL,
     CacheType TEXT NOT NULL,
-    Cache TEXT NOT NULL,
+    Cache BLOB NOT NULL,
 
     PRIMARY KEY (LayerId, CacheType, TimeMicroseconds)
 ) WITHOUT ROWID;
@@ -0,0 +1,24 @@
+/***
+ **
+ ** FlowBetween undo history
+ **
+ **   The undo history is stored as a tree alongside the edit log, so it's added to files created before it existed
+ **   when they're opened. Nodes use the same compact binary encoding as the edit log.
+ **
+ ***/
+
+/**
+ * The nodes in the undo history
+ */
+CREATE TABLE IF NOT EXISTS UndoHistory (
+    NodeId INTEGER NOT NULL PRIMARY KEY,
+    Node BLOB NOT NULL
+) WITHOUT ROWID;
+
+/**
+ * The node in the undo history that matches the current state of the edit log (NodeId is NULL if no actions are applied)
+ */
+CREATE TABLE IF NOT EXISTS UndoHistoryPosition (
+    PositionId INTEGER NOT NULL PRIMARY KEY,
+    NodeId INTEGER
+) WITHOUT ROWID;
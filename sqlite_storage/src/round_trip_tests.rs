@@ -825,6 +825,44 @@ fn read_motion_edit_items() {
     }
 }
 
+#[test]
+fn read_bone_element() {
+    use self::LayerEdit::*;
+
+    let anim = create_animation();
+
+    anim.perform_edits(vec![
+        AnimationEdit::AddNewLayer(2),
+        AnimationEdit::Layer(2, AddKeyFrame(Duration::from_millis(442))),
+        AnimationEdit::Layer(2, CreateElement(Duration::from_millis(442), ElementId::Assigned(100),
+            Vector::Bone(BoneElement::new(ElementId::Assigned(100), None, (10.0, 20.0), (30.0, 40.0))))),
+        AnimationEdit::Bone(ElementId::Assigned(100), BoneEdit::Bind(ElementId::Assigned(50), 0.75)),
+        AnimationEdit::Bone(ElementId::Assigned(100), BoneEdit::SetRotation(Duration::from_millis(1442), 30.0)),
+    ]);
+
+    let layer   = anim.get_layer_with_id(2).unwrap();
+    let frame   = layer.get_frame_at_time(Duration::from_millis(442));
+    let bone    = frame.element_with_id(ElementId::Assigned(100));
+
+    if let Some(Vector::Bone(bone)) = bone {
+        assert!(bone.start() == (10.0, 20.0));
+        assert!(bone.end() == (30.0, 40.0));
+        assert!(*bone.bindings() == vec![(ElementId::Assigned(50), 0.75)]);
+        assert!(*bone.rotations() == vec![(Duration::from_millis(1000), 30.0)]);
+    } else {
+        assert!(false);
+    }
+
+    let edit_log        = anim.read_edit_log(3..5);
+    let edit_log        = edit_log.collect();
+    let edits: Vec<_>   = executor::block_on(edit_log);
+
+    assert!(edits == vec![
+        AnimationEdit::Bone(ElementId::Assigned(100), BoneEdit::Bind(ElementId::Assigned(50), 0.75)),
+        AnimationEdit::Bone(ElementId::Assigned(100), BoneEdit::SetRotation(Duration::from_millis(1442), 30.0)),
+    ]);
+}
+
 #[test]
 fn read_path_element() {
     use self::LayerEdit::*;
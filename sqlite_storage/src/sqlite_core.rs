@@ -1,4 +1,5 @@
 use flo_animation::storage::*;
+use flo_animation::serializer::{encode_compact_bytes, decode_compact_bytes};
 
 use rusqlite;
 
@@ -8,6 +9,12 @@ use std::iter;
 use std::time::{Duration};
 
 const BASE_DATA_DEFN: &[u8]          = include_bytes!["../sql/flo_storage.sql"];
+const UNDO_HISTORY_DEFN: &[u8]       = include_bytes!["../sql/flo_undo_history.sql"];
+
+///
+/// The version of the file format that this will create (stored as the database's user_version)
+///
+const STORAGE_VERSION: i64          = 5;
 
 ///
 /// The SQLite core stores the synchronous data for the SQLite database
@@ -51,7 +58,98 @@ impl SqliteCore {
     pub fn initialize(&mut self) -> Result<(), rusqlite::Error> {
         let defn = String::from_utf8_lossy(BASE_DATA_DEFN);
 
-        self.check_error(self.connection.execute_batch(&defn))
+        self.check_error(self.connection.execute_batch(&defn))?;
+        let set_version = self.set_storage_version(STORAGE_VERSION);
+        self.check_error(set_version)?;
+
+        self.upgrade()
+    }
+
+    ///
+    /// Adds any tables that are missing from a database created by an earlier version, and converts any data stored in an
+    /// older format
+    ///
+    pub fn upgrade(&mut self) -> Result<(), rusqlite::Error> {
+        let defn = String::from_utf8_lossy(UNDO_HISTORY_DEFN);
+        self.check_error(self.connection.execute_batch(&defn))?;
+
+        // V4 files store their edits, elements and caches as text
+        let version = self.read_storage_version();
+        if self.check_error(version)? < STORAGE_VERSION {
+            let migrate = self.migrate_to_compact_encoding();
+            self.check_error(migrate)?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Reads the version of the file format used by the database (files from before version 5 have a version of 0)
+    ///
+    fn read_storage_version(&mut self) -> Result<i64, rusqlite::Error> {
+        self.connection.query_row("PRAGMA user_version;", [], |row| row.get(0))
+    }
+
+    ///
+    /// Sets the version of the file format used by the database
+    ///
+    fn set_storage_version(&mut self, version: i64) -> Result<(), rusqlite::Error> {
+        self.connection.execute_batch(&format!("PRAGMA user_version = {};", version))
+    }
+
+    ///
+    /// Converts the edit log, elements and layer cache from text to the compact binary encoding
+    ///
+    fn migrate_to_compact_encoding(&mut self) -> Result<(), rusqlite::Error> {
+        let transaction = self.connection.transaction()?;
+
+        {
+            // Edit log
+            let mut read    = transaction.prepare("SELECT EditId, Edit FROM EditLog WHERE typeof(Edit) = 'text';")?;
+            let edits       = read.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?.collect::<Result<Vec<_>, _>>()?;
+            let mut write   = transaction.prepare("UPDATE EditLog SET Edit = ? WHERE EditId = ?;")?;
+
+            for (edit_id, edit) in edits {
+                write.execute(params![encode_compact_bytes(&edit), edit_id])?;
+            }
+
+            // Elements
+            let mut read    = transaction.prepare("SELECT ElementId, Element FROM Elements WHERE typeof(Element) = 'text';")?;
+            let elements    = read.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?.collect::<Result<Vec<_>, _>>()?;
+            let mut write   = transaction.prepare("UPDATE Elements SET Element = ? WHERE ElementId = ?;")?;
+
+            for (element_id, element) in elements {
+                write.execute(params![encode_compact_bytes(&element), element_id])?;
+            }
+
+            // Layer cache
+            let mut read    = transaction.prepare("SELECT LayerId, TimeMicroseconds, CacheType, Cache FROM LayerCache WHERE typeof(Cache) = 'text';")?;
+            let caches      = read.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?)))?.collect::<Result<Vec<_>, _>>()?;
+            let mut write   = transaction.prepare("UPDATE LayerCache SET Cache = ? WHERE LayerId = ? AND TimeMicroseconds = ? AND CacheType = ?;")?;
+
+            for (layer_id, when, cache_type, cache) in caches {
+                write.execute(params![encode_compact_bytes(&cache), layer_id, when, cache_type])?;
+            }
+        }
+
+        transaction.execute_batch(&format!("PRAGMA user_version = {};", STORAGE_VERSION))?;
+        transaction.commit()?;
+
+        // Reclaim the space that was used by the text version of the data
+        self.connection.execute_batch("VACUUM;")
+    }
+
+    ///
+    /// Reads a value written in the compact binary encoding from a row (text values from files that haven't been converted are also accepted)
+    ///
+    fn read_serialized(row: &rusqlite::Row, idx: usize) -> Result<String, rusqlite::Error> {
+        use rusqlite::types::ValueRef;
+
+        match row.get_ref(idx)? {
+            ValueRef::Blob(compact) => Ok(decode_compact_bytes(compact)),
+            ValueRef::Text(text)    => Ok(String::from_utf8_lossy(text).to_string()),
+            other                   => Err(rusqlite::Error::InvalidColumnType(idx, String::new(), other.data_type()))
+        }
     }
 
     ///
@@ -99,6 +197,7 @@ impl SqliteCore {
             ReadLayerProperties(layer_id)                       => { self.read_layer_properties(layer_id) },
             AddKeyFrame(layer_id, when)                         => { self.add_key_frame(layer_id, when) },
             DeleteKeyFrame(layer_id, when)                      => { self.delete_key_frame(layer_id, when) },
+            MoveKeyFrame(layer_id, from, to)                    => { self.move_key_frame(layer_id, from, to) },
             ReadKeyFrames(layer_id, time_range)                 => { self.read_keyframes(layer_id, time_range) },
             AttachElementToLayer(layer_id, element_id, when)    => { self.attach_element_to_layer(layer_id, element_id, when) },
             DetachElementFromLayer(element_id)                  => { self.detach_element_from_layer(element_id) },
@@ -107,6 +206,9 @@ impl SqliteCore {
             WriteLayerCache(layer_id, when, cache_type, value)  => { self.write_layer_cache(layer_id, when, cache_type, value) },
             DeleteLayerCache(layer_id, when, cache_type)        => { self.delete_layer_cache(layer_id, when, cache_type) },
             ReadLayerCache(layer_id, when, cache_type)          => { self.read_layer_cache(layer_id, when, cache_type) },
+            WriteUndoHistoryNode(node_id, node)                 => { self.write_undo_history_node(node_id, node) },
+            WriteUndoHistoryPosition(position)                  => { self.write_undo_history_position(position) },
+            ReadUndoHistory                                     => { self.read_undo_history() },
         };
 
         self.check_error(result)
@@ -160,7 +262,7 @@ impl SqliteCore {
     ///
     fn write_edit(&mut self, edit: String) -> Result<Vec<StorageResponse>, rusqlite::Error> {
         let mut write   = self.connection.prepare_cached("INSERT INTO EditLog (Edit) VALUES (?);")?;
-        write.execute([edit])?;
+        write.execute([encode_compact_bytes(&edit)])?;
 
         Ok(vec![StorageResponse::Updated])
     }
@@ -221,7 +323,7 @@ impl SqliteCore {
     ///
     fn read_edits(&mut self, range: Range<usize>) -> Result<Vec<StorageResponse>, rusqlite::Error> {
         let mut read    = self.connection.prepare_cached("SELECT EditId, Edit FROM EditLog WHERE EditId >= ? AND EditId < ? ORDER BY EditId ASC;")?;
-        let edits       = read.query_map([(range.start as i64)+1, (range.end as i64)+1], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+        let edits       = read.query_map([(range.start as i64)+1, (range.end as i64)+1], |row| Ok((row.get::<_, i64>(0)?, Self::read_serialized(row, 1)?)))?;
         let edits       = edits.map(|row| row.map(|(edit_id, edit)| StorageResponse::Edit((edit_id-1) as usize, edit)));
 
         Ok(edits.collect::<Result<_, _>>()?)
@@ -232,7 +334,7 @@ impl SqliteCore {
     ///
     fn write_element(&mut self, element_id: i64, element: String) -> Result<Vec<StorageResponse>, rusqlite::Error> {
         let mut write   = self.connection.prepare_cached("INSERT OR REPLACE INTO Elements (ElementId, Element) VALUES (?, ?);")?;
-        write.execute(params![element_id, element])?;
+        write.execute(params![element_id, encode_compact_bytes(&element)])?;
 
         Ok(vec![StorageResponse::Updated])
     }
@@ -244,7 +346,7 @@ impl SqliteCore {
         use rusqlite::Error::QueryReturnedNoRows;
 
         let mut read    = self.connection.prepare_cached("SELECT Element FROM Elements WHERE ElementId = ?;")?;
-        let element     = read.query_row([element_id], |row| row.get(0));
+        let element     = read.query_row([element_id], |row| Self::read_serialized(row, 0));
 
         match element {
             Ok(element)                 => Ok(vec![StorageResponse::Element(element_id, element)]),
@@ -364,6 +466,44 @@ impl SqliteCore {
         Ok(vec![StorageResponse::Updated])
     }
 
+    ///
+    /// Moves a keyframe and the elements attached to it to a new time
+    ///
+    fn move_key_frame(&mut self, layer_id: u64, from: Duration, to: Duration) -> Result<Vec<StorageResponse>, rusqlite::Error> {
+        let from_microseconds   = Self::time_to_int(from);
+        let to_microseconds     = Self::time_to_int(to);
+
+        // The source keyframe must exist, and the target must not already be a keyframe
+        if self.read_previous_key_frame(layer_id, from_microseconds)? != Some(from_microseconds) {
+            return Ok(vec![StorageResponse::NotFound]);
+        }
+
+        if from_microseconds == to_microseconds {
+            return Ok(vec![StorageResponse::Updated]);
+        }
+
+        if self.read_previous_key_frame(layer_id, to_microseconds)? == Some(to_microseconds) {
+            return Ok(vec![StorageResponse::NotReplacingExisting]);
+        }
+
+        let transaction = self.connection.transaction()?;
+
+        {
+            let mut update  = transaction.prepare_cached("UPDATE Keyframe SET TimeMicroseconds = ? WHERE LayerId = ? AND TimeMicroseconds = ?;")?;
+            update.execute([to_microseconds, layer_id as i64, from_microseconds])?;
+
+            let mut update  = transaction.prepare_cached("UPDATE ElementKeyframeAttachment SET TimeMicroseconds = ? WHERE LayerId = ? AND TimeMicroseconds = ?;")?;
+            update.execute([to_microseconds, layer_id as i64, from_microseconds])?;
+
+            let mut delete  = transaction.prepare_cached("DELETE FROM LayerCache WHERE LayerId = ? AND (TimeMicroseconds = ? OR TimeMicroseconds = ?);")?;
+            delete.execute([layer_id as i64, from_microseconds, to_microseconds])?;
+        }
+
+        transaction.commit()?;
+
+        Ok(vec![StorageResponse::Updated])
+    }
+
     ///
     /// Reads where the keyframe preceding or at the specified time is located
     ///
@@ -527,7 +667,7 @@ impl SqliteCore {
             INNER JOIN ElementKeyframeAttachment ON ElementKeyframeAttachment.ElementId = Elements.ElementId
             WHERE ElementKeyframeAttachment.LayerId = ? AND ElementKeyFrameAttachment.TimeMicroseconds = ?;")?;
 
-        let elements    = read.query_map([layer_id as i64, when], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        let elements    = read.query_map([layer_id as i64, when], |row| Ok((row.get(0)?, Self::read_serialized(row, 1)?)))?;
         let elements    = elements.map(|element| element.map(|(element_id, element)| StorageResponse::Element(element_id, element)));
         let elements    = iter::once(Ok(StorageResponse::KeyFrame(start_time, end_time))).chain(elements);
 
@@ -541,7 +681,7 @@ impl SqliteCore {
         let when        = Self::time_to_int(when);
 
         let mut write   = self.connection.prepare_cached("INSERT OR REPLACE INTO LayerCache (LayerId, TimeMicroseconds, CacheType, Cache) VALUES (?, ?, ?, ?);")?;
-        write.execute(params![layer_id as i64, when, cache_type, value])?;
+        write.execute(params![layer_id as i64, when, cache_type, encode_compact_bytes(&value)])?;
 
         Ok(vec![StorageResponse::Updated])
     }
@@ -567,7 +707,7 @@ impl SqliteCore {
         let when        = Self::time_to_int(when);
 
         let mut read    = self.connection.prepare_cached("SELECT Cache FROM LayerCache WHERE LayerId = ? AND TimeMicroseconds = ? AND CacheType = ?;")?;
-        let result      = read.query_row(params![layer_id as i64, when, cache_type], |row| row.get(0));
+        let result      = read.query_row(params![layer_id as i64, when, cache_type], |row| Self::read_serialized(row, 0));
 
         match result {
             Ok(cache)                   => Ok(vec![StorageResponse::LayerCache(cache)]),
@@ -575,4 +715,46 @@ impl SqliteCore {
             Err(other)                  => Err(other)
         }
     }
+
+    ///
+    /// Writes a node to the undo history
+    ///
+    fn write_undo_history_node(&mut self, node_id: u64, node: String) -> Result<Vec<StorageResponse>, rusqlite::Error> {
+        let mut write   = self.connection.prepare_cached("INSERT OR REPLACE INTO UndoHistory (NodeId, Node) VALUES (?, ?);")?;
+        write.execute(params![node_id as i64, encode_compact_bytes(&node)])?;
+
+        Ok(vec![StorageResponse::Updated])
+    }
+
+    ///
+    /// Sets the node in the undo history that matches the edit log
+    ///
+    fn write_undo_history_position(&mut self, position: Option<u64>) -> Result<Vec<StorageResponse>, rusqlite::Error> {
+        let mut write   = self.connection.prepare_cached("INSERT OR REPLACE INTO UndoHistoryPosition (PositionId, NodeId) VALUES (0, ?);")?;
+        write.execute(params![position.map(|node_id| node_id as i64)])?;
+
+        Ok(vec![StorageResponse::Updated])
+    }
+
+    ///
+    /// Reads the nodes in the undo history, followed by the position
+    ///
+    fn read_undo_history(&mut self) -> Result<Vec<StorageResponse>, rusqlite::Error> {
+        use rusqlite::Error::QueryReturnedNoRows;
+
+        let mut read_nodes      = self.connection.prepare_cached("SELECT NodeId, Node FROM UndoHistory ORDER BY NodeId ASC;")?;
+        let nodes               = read_nodes.query_map([], |row| Ok(StorageResponse::UndoHistoryNode(row.get::<_, i64>(0)? as u64, Self::read_serialized(row, 1)?)))?;
+        let mut history         = nodes.collect::<Result<Vec<_>, _>>()?;
+
+        let mut read_position   = self.connection.prepare_cached("SELECT NodeId FROM UndoHistoryPosition WHERE PositionId = 0;")?;
+        let position            = match read_position.query_row([], |row| row.get::<_, Option<i64>>(0)) {
+            Ok(position)                => position.map(|node_id| node_id as u64),
+            Err(QueryReturnedNoRows)    => None,
+            Err(other)                  => { return Err(other); }
+        };
+
+        history.push(StorageResponse::UndoHistoryPosition(position));
+
+        Ok(history)
+    }
 }
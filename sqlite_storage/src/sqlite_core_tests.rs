@@ -403,6 +403,56 @@ fn delete_nonexistent_keyframe() {
         ]);
 }
 
+#[test]
+fn move_keyframe() {
+    let mut core    = SqliteCore::new(rusqlite::Connection::open_in_memory().unwrap());
+    core.initialize().unwrap();
+
+    assert!(core.run_commands(vec![
+            StorageCommand::AddLayer(1, "Test1".to_string()),
+            StorageCommand::AddKeyFrame(1, Duration::from_millis(400)),
+            StorageCommand::AddKeyFrame(1, Duration::from_millis(500)),
+
+            StorageCommand::WriteElement(1, "Test1".to_string()),
+            StorageCommand::AttachElementToLayer(1, 1, Duration::from_millis(400)),
+        ]) == vec![StorageResponse::Updated, StorageResponse::Updated, StorageResponse::Updated, StorageResponse::Updated, StorageResponse::Updated]);
+
+    assert!(core.run_commands(vec![
+            StorageCommand::MoveKeyFrame(1, Duration::from_millis(400), Duration::from_millis(600))
+        ]) == vec![StorageResponse::Updated]);
+
+    assert!(core.run_commands(vec![StorageCommand::ReadKeyFrames(1, Duration::from_millis(0)..Duration::from_millis(700))]) ==
+        vec![
+            StorageResponse::KeyFrame(Duration::from_millis(500), Duration::from_millis(600)),
+            StorageResponse::KeyFrame(Duration::from_millis(600), Duration::from_micros(i64::MAX as u64))
+        ]);
+
+    assert!(core.run_commands(vec![StorageCommand::ReadElementAttachments(1)]) == vec![StorageResponse::ElementAttachments(1, vec![(1, Duration::from_millis(600))])]);
+
+    assert!(core.run_commands(vec![StorageCommand::ReadElementsForKeyFrame(1, Duration::from_millis(600))]) ==
+        vec![
+            StorageResponse::KeyFrame(Duration::from_millis(600), Duration::from_secs(u32::max_value() as _)),
+            StorageResponse::Element(1, "Test1".to_string()),
+        ]);
+}
+
+#[test]
+fn move_keyframe_onto_existing_keyframe() {
+    let mut core    = SqliteCore::new(rusqlite::Connection::open_in_memory().unwrap());
+    core.initialize().unwrap();
+
+    assert!(core.run_commands(vec![
+            StorageCommand::AddLayer(1, "Test1".to_string()),
+            StorageCommand::AddKeyFrame(1, Duration::from_millis(400)),
+            StorageCommand::AddKeyFrame(1, Duration::from_millis(500)),
+        ]) == vec![StorageResponse::Updated, StorageResponse::Updated, StorageResponse::Updated]);
+
+    assert!(core.run_commands(vec![
+            StorageCommand::MoveKeyFrame(1, Duration::from_millis(400), Duration::from_millis(500)),
+            StorageCommand::MoveKeyFrame(1, Duration::from_millis(450), Duration::from_millis(600)),
+        ]) == vec![StorageResponse::NotReplacingExisting, StorageResponse::NotFound]);
+}
+
 #[test]
 fn attach_element_to_keyframe() {
     let mut core    = SqliteCore::new(rusqlite::Connection::open_in_memory().unwrap());
@@ -645,3 +695,132 @@ fn delete_from_layer_cache() {
     assert!(core.run_commands(vec![StorageCommand::ReadLayerCache(1, Duration::from_millis(500), "Type".to_string())]) ==
         vec![StorageResponse::LayerCache("Cache2".to_string())]);
 }
+
+#[test]
+fn read_empty_undo_history() {
+    let mut core    = SqliteCore::new(rusqlite::Connection::open_in_memory().unwrap());
+    core.initialize().unwrap();
+
+    assert!(core.run_commands(vec![StorageCommand::ReadUndoHistory]) == vec![StorageResponse::UndoHistoryPosition(None)]);
+}
+
+#[test]
+fn write_and_read_undo_history() {
+    let mut core    = SqliteCore::new(rusqlite::Connection::open_in_memory().unwrap());
+    core.initialize().unwrap();
+
+    assert!(core.run_commands(vec![
+            StorageCommand::WriteUndoHistoryNode(1, "Node1".to_string()),
+            StorageCommand::WriteUndoHistoryNode(0, "Node0".to_string()),
+            StorageCommand::WriteUndoHistoryPosition(Some(1))
+        ]) == vec![StorageResponse::Updated, StorageResponse::Updated, StorageResponse::Updated]);
+
+    assert!(core.run_commands(vec![StorageCommand::ReadUndoHistory]) == vec![
+            StorageResponse::UndoHistoryNode(0, "Node0".to_string()),
+            StorageResponse::UndoHistoryNode(1, "Node1".to_string()),
+            StorageResponse::UndoHistoryPosition(Some(1))
+        ]);
+}
+
+#[test]
+fn replace_undo_history_node() {
+    let mut core    = SqliteCore::new(rusqlite::Connection::open_in_memory().unwrap());
+    core.initialize().unwrap();
+
+    core.run_commands(vec![
+        StorageCommand::WriteUndoHistoryNode(0, "Node0".to_string()),
+        StorageCommand::WriteUndoHistoryPosition(Some(0)),
+        StorageCommand::WriteUndoHistoryNode(0, "Checkpoint".to_string()),
+        StorageCommand::WriteUndoHistoryPosition(None)
+    ]);
+
+    assert!(core.run_commands(vec![StorageCommand::ReadUndoHistory]) == vec![
+            StorageResponse::UndoHistoryNode(0, "Checkpoint".to_string()),
+            StorageResponse::UndoHistoryPosition(None)
+        ]);
+}
+
+#[test]
+fn upgrade_adds_undo_history() {
+    let mut core    = SqliteCore::new(rusqlite::Connection::open_in_memory().unwrap());
+    core.initialize().unwrap();
+
+    // Upgrading an up-to-date database should leave it alone
+    core.run_commands(vec![StorageCommand::WriteUndoHistoryNode(0, "Node0".to_string())]);
+    assert!(core.upgrade().is_ok());
+
+    assert!(core.run_commands(vec![StorageCommand::ReadUndoHistory]) == vec![
+            StorageResponse::UndoHistoryNode(0, "Node0".to_string()),
+            StorageResponse::UndoHistoryPosition(None)
+        ]);
+}
+
+///
+/// Creates a connection containing a database in the version 4 format (where edits, elements and caches are stored as text)
+///
+fn create_v4_database() -> rusqlite::Connection {
+    let connection = rusqlite::Connection::open_in_memory().unwrap();
+
+    connection.execute_batch("
+        CREATE TABLE AnimationProperties (PropertyId INTEGER NOT NULL PRIMARY KEY, Value TEXT NOT NULL) WITHOUT ROWID;
+        CREATE TABLE EditLog (EditId INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT, Edit TEXT NOT NULL);
+        CREATE TABLE Elements (ElementId INTEGER NOT NULL PRIMARY KEY, Element TEXT NOT NULL) WITHOUT ROWID;
+        CREATE TABLE Layers (LayerId INTEGER NOT NULL PRIMARY KEY, Layer TEXT NOT NULL) WITHOUT ROWID;
+        CREATE TABLE Keyframe (LayerId INTEGER NOT NULL, TimeMicroseconds INTEGER NOT NULL, PRIMARY KEY (LayerId, TimeMicroseconds)) WITHOUT ROWID;
+        CREATE TABLE ElementKeyframeAttachment (ElementId INTEGER NOT NULL, LayerId INTEGER NOT NULL, TimeMicroseconds INTEGER NOT NULL, PRIMARY KEY (LayerId, TimeMicroseconds, ElementId)) WITHOUT ROWID;
+        CREATE TABLE LayerCache (LayerId INTEGER NOT NULL, TimeMicroseconds INTEGER NOT NULL, CacheType TEXT NOT NULL, Cache TEXT NOT NULL, PRIMARY KEY (LayerId, CacheType, TimeMicroseconds)) WITHOUT ROWID;
+
+        INSERT INTO EditLog (Edit) VALUES ('+C');
+        INSERT INTO EditLog (Edit) VALUES ('LCAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA');
+        INSERT INTO Elements (ElementId, Element) VALUES (1, 'ABCDEFGHIJKLMNOPQRSTUVWXYZ-abcdefghijklmnop');
+        INSERT INTO Layers (LayerId, Layer) VALUES (1, 'Layer');
+        INSERT INTO Keyframe (LayerId, TimeMicroseconds) VALUES (1, 0);
+        INSERT INTO ElementKeyframeAttachment (ElementId, LayerId, TimeMicroseconds) VALUES (1, 1, 0);
+        INSERT INTO LayerCache (LayerId, TimeMicroseconds, CacheType, Cache) VALUES (1, 400000, 'Type', 'Cache; with other characters');
+    ").unwrap();
+
+    connection
+}
+
+#[test]
+fn upgrade_v4_database() {
+    let mut core    = SqliteCore::new(create_v4_database());
+    core.upgrade().unwrap();
+
+    assert!(core.run_commands(vec![StorageCommand::ReadEditLogLength]) == vec![StorageResponse::NumberOfEdits(2)]);
+    assert!(core.run_commands(vec![StorageCommand::ReadEdits(0..2)]) == vec![
+            StorageResponse::Edit(0, "+C".to_string()),
+            StorageResponse::Edit(1, "LCAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA".to_string())
+        ]);
+    assert!(core.run_commands(vec![StorageCommand::ReadElement(1)]) == vec![StorageResponse::Element(1, "ABCDEFGHIJKLMNOPQRSTUVWXYZ-abcdefghijklmnop".to_string())]);
+    assert!(core.run_commands(vec![StorageCommand::ReadElementsForKeyFrame(1, Duration::from_millis(0))]) == vec![
+            StorageResponse::KeyFrame(Duration::from_millis(0), Duration::from_secs(u32::max_value() as _)),
+            StorageResponse::Element(1, "ABCDEFGHIJKLMNOPQRSTUVWXYZ-abcdefghijklmnop".to_string())
+        ]);
+    assert!(core.run_commands(vec![StorageCommand::ReadLayerCache(1, Duration::from_millis(400), "Type".to_string())]) ==
+        vec![StorageResponse::LayerCache("Cache; with other characters".to_string())]);
+
+    // The undo history tables should also be available
+    assert!(core.run_commands(vec![StorageCommand::ReadUndoHistory]) == vec![StorageResponse::UndoHistoryPosition(None)]);
+}
+
+#[test]
+fn write_after_upgrading_v4_database() {
+    let mut core    = SqliteCore::new(create_v4_database());
+    core.upgrade().unwrap();
+
+    // Upgrading twice should leave the data alone
+    core.upgrade().unwrap();
+
+    assert!(core.run_commands(vec![
+            StorageCommand::WriteEdit("LCAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAB".to_string()),
+            StorageCommand::WriteElement(2, "ABCDEFGHIJKLMNOPQRSTUVWXYZ".to_string())
+        ]) == vec![StorageResponse::Updated, StorageResponse::Updated]);
+
+    assert!(core.run_commands(vec![StorageCommand::ReadEdits(0..3)]) == vec![
+            StorageResponse::Edit(0, "+C".to_string()),
+            StorageResponse::Edit(1, "LCAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA".to_string()),
+            StorageResponse::Edit(2, "LCAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAB".to_string())
+        ]);
+    assert!(core.run_commands(vec![StorageCommand::ReadElement(2)]) == vec![StorageResponse::Element(2, "ABCDEFGHIJKLMNOPQRSTUVWXYZ".to_string())]);
+}
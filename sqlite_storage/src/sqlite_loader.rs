@@ -17,7 +17,7 @@ pub fn sqlite_animation_loader() -> impl FileAnimation {
         let storage = if opening_existing {
             // Open/restore an existing animation
             let connection  = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE).unwrap();
-            let storage     = SqliteAnimationStorage::from_connection(connection);
+            let storage     = SqliteAnimationStorage::from_connection(connection).unwrap();
 
             storage
         } else {
@@ -40,15 +40,20 @@ impl SqliteAnimationStorage {
     ///
     /// Creates a SQLite storage from an existing database connection, which should already be initialised
     ///
-    pub fn from_connection(connection: rusqlite::Connection) -> SqliteAnimationStorage {
+    /// Files created by older versions are upgraded to the current format: this returns an error if the upgrade fails.
+    ///
+    pub fn from_connection(connection: rusqlite::Connection) -> Result<SqliteAnimationStorage, rusqlite::Error> {
         // Create the core with the connection
         let core    = SqliteCore::new(connection);
         let core    = Arc::new(Desync::new(core));
 
+        // Add any tables that are missing from older files, and convert any data in an older format
+        core.sync(|core| core.upgrade())?;
+
         // Create the storage object
-        SqliteAnimationStorage {
+        Ok(SqliteAnimationStorage {
             core:   core
-        }
+        })
     }
 
     ///
@@ -56,7 +61,7 @@ impl SqliteAnimationStorage {
     ///
     pub fn open_file(path: &Path) -> Result<SqliteAnimationStorage, rusqlite::Error> {
         let connection  = rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE)?;
-        Ok(Self::from_connection(connection))
+        Self::from_connection(connection)
     }
 
     ///